@@ -0,0 +1,18 @@
+//! Feed arbitrary bytes through SigNoz response parsing, as a response
+//! body from a SigNoz version with a different schema would be.
+//!
+//! Run with: cargo +nightly fuzz run signoz_response
+
+#![no_main]
+
+use dora_studio::otlp::signoz::response::SigNozResponse;
+use dora_studio::otlp::SigNozBackend;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(resp) = serde_json::from_slice::<SigNozResponse>(data) {
+        SigNozBackend::parse_trace_results(&resp);
+        SigNozBackend::parse_log_results(&resp);
+        SigNozBackend::parse_metric_results(&resp);
+    }
+});
@@ -0,0 +1,450 @@
+use makepad_widgets::*;
+use std::cell::RefMut;
+
+use super::AlertEvent;
+use crate::datetime::format_clock;
+use crate::i18n;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    // Colors (reused from diagnostics_panel)
+    ROW_BG = #ffffff
+    ROW_ALT_BG = #f8fafc
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+
+    // Rule entry controls
+    AlertsToolbar = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        spacing: 4
+        padding: { bottom: 8 }
+
+        <View> {
+            width: Fill, height: 40
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 8
+
+            new_rule_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "New rule"
+            }
+
+            rule_input = <TextInput> {
+                width: 360, height: 28
+                empty_text: "error_rate > 5% over 5m for camera"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            add_rule_button = <Button> {
+                width: 80, height: 28
+                text: "Add rule"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        rule_status_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                wrap: Word,
+                text_style: { font_size: 11.0 }
+            }
+            text: "Metrics: error_rate, p50..p99, avg, max, count. Windows: 30s, 5m, 1h."
+        }
+    }
+
+    SectionHeader = <View> {
+        width: Fill, height: 32
+        flow: Right
+        show_bg: true
+        draw_bg: { color: #f1f5f9 }
+        padding: { left: 16, right: 16 }
+        align: { y: 0.5 }
+        spacing: 8
+    }
+
+    RuleRow = <View> {
+        width: Fill, height: 36
+        flow: Right
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+        padding: { left: 16, right: 16 }
+        align: { y: 0.5 }
+        spacing: 8
+
+        expression_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        status_label = <Label> {
+            width: 160, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        remove_button = <Button> {
+            width: 70, height: 26
+            text: "Remove"
+            draw_text: { text_style: { font_size: 10.0 } }
+        }
+    }
+
+    RuleRowAlt = <RuleRow> {
+        draw_bg: { color: (ROW_ALT_BG) }
+    }
+
+    HistoryRow = <View> {
+        width: Fill, height: 32
+        flow: Right
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+        padding: { left: 16, right: 16 }
+        align: { y: 0.5 }
+        spacing: 8
+
+        time_label = <Label> {
+            width: 100, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        state_label = <Label> {
+            width: 80, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        rule_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        value_label = <Label> {
+            width: 100, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+    }
+
+    HistoryRowAlt = <HistoryRow> {
+        draw_bg: { color: (ROW_ALT_BG) }
+    }
+
+    AlertsEmpty = <View> {
+        width: Fill, height: 60
+        align: { x: 0.5, y: 0.5 }
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+
+        empty_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 12.0 }
+            }
+        }
+    }
+
+    pub AlertsPanel = {{AlertsPanel}} {
+        width: Fill, height: Fit
+        flow: Down
+
+        <AlertsToolbar> {}
+
+        <SectionHeader> {
+            rule_header = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "RULE"
+            }
+            status_header = <Label> {
+                width: 240, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "STATUS"
+            }
+        }
+
+        rule_list = <PortalList> {
+            width: Fill, height: 160
+            flow: Down
+
+            RuleRow = <RuleRow> {}
+            RuleRowAlt = <RuleRowAlt> {}
+            AlertsEmpty = <AlertsEmpty> {}
+        }
+
+        <View> { width: Fill, height: 12 }
+
+        <SectionHeader> {
+            time_header = <Label> {
+                width: 100, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "TIME (UTC)"
+            }
+            state_header = <Label> {
+                width: 80, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "STATE"
+            }
+            history_header = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "ALERT HISTORY"
+            }
+            value_header = <Label> {
+                width: 100, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "VALUE"
+            }
+        }
+
+        history_list = <PortalList> {
+            width: Fill, height: 240
+            flow: Down
+
+            HistoryRow = <HistoryRow> {}
+            HistoryRowAlt = <HistoryRowAlt> {}
+            AlertsEmpty = <AlertsEmpty> {}
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct AlertsPanel {
+    #[deref]
+    view: View,
+    /// Rules as `(expression, status)`.
+    #[rust]
+    rules: Vec<(String, String)>,
+    /// Alert transitions, newest first.
+    #[rust]
+    history: Vec<AlertEvent>,
+}
+
+impl Widget for AlertsPanel {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rule_list_uid = self.view.portal_list(ids!(rule_list)).widget_uid();
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                if item.widget_uid() == rule_list_uid {
+                    self.draw_rules(cx, &mut list);
+                } else {
+                    self.draw_history(cx, &mut list);
+                }
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl AlertsPanel {
+    pub fn set_rules(&mut self, cx: &mut Cx, rules: Vec<(String, String)>) {
+        self.rules = rules;
+        self.view.portal_list(ids!(rule_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    pub fn set_history(&mut self, cx: &mut Cx, history: Vec<AlertEvent>) {
+        self.history = history;
+        self.view.portal_list(ids!(history_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    /// Show the text set in `live_design` in the current language
+    pub fn localize(&mut self, cx: &mut Cx) {
+        i18n::localize(
+            cx,
+            &[
+                (ids!(new_rule_label), "alerts.new_rule"),
+                (ids!(add_rule_button), "alerts.add_rule"),
+                (ids!(rule_status_label), "alerts.rule_hint"),
+                (ids!(rule_header), "alerts.column.rule"),
+                (ids!(status_header), "alerts.column.status"),
+                (ids!(time_header), "alerts.column.time"),
+                (ids!(state_header), "alerts.column.state"),
+                (ids!(history_header), "alerts.column.history"),
+                (ids!(value_header), "alerts.column.value"),
+            ],
+            |path| self.view.widget(path),
+        );
+        self.view.portal_list(ids!(rule_list)).redraw(cx);
+        self.view.portal_list(ids!(history_list)).redraw(cx);
+    }
+
+    fn draw_rules(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        if self.rules.is_empty() {
+            draw_empty(cx, list, &i18n::tr("alerts.no_rules"));
+            return;
+        }
+
+        list.set_item_range(cx, 0, self.rules.len());
+
+        while let Some(item_id) = list.next_visible_item(cx) {
+            if item_id < self.rules.len() {
+                let (expression, status) = &self.rules[item_id];
+
+                let template = if item_id % 2 == 0 {
+                    live_id!(RuleRow)
+                } else {
+                    live_id!(RuleRowAlt)
+                };
+
+                let item = list.item(cx, item_id, template);
+
+                item.label(ids!(expression_label)).set_text(cx, expression);
+                item.label(ids!(status_label)).set_text(cx, status);
+                item.button(ids!(remove_button))
+                    .set_text(cx, &i18n::tr("alerts.remove"));
+
+                item.draw_all(cx, &mut Scope::empty());
+            }
+        }
+    }
+
+    fn draw_history(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        if self.history.is_empty() {
+            draw_empty(cx, list, &i18n::tr("alerts.no_history"));
+            return;
+        }
+
+        list.set_item_range(cx, 0, self.history.len());
+
+        while let Some(item_id) = list.next_visible_item(cx) {
+            if item_id < self.history.len() {
+                let event = &self.history[item_id];
+
+                let template = if item_id % 2 == 0 {
+                    live_id!(HistoryRow)
+                } else {
+                    live_id!(HistoryRowAlt)
+                };
+
+                let item = list.item(cx, item_id, template);
+
+                item.label(ids!(time_label))
+                    .set_text(cx, &format_clock(event.timestamp_ms));
+                item.label(ids!(state_label))
+                    .set_text(cx, event.state.as_str());
+                item.label(ids!(rule_label)).set_text(cx, &event.rule);
+                item.label(ids!(value_label)).set_text(cx, &event.value);
+
+                item.draw_all(cx, &mut Scope::empty());
+            }
+        }
+    }
+}
+
+fn draw_empty(cx: &mut Cx2d, list: &mut RefMut<PortalList>, message: &str) {
+    list.set_item_range(cx, 0, 1);
+    while let Some(item_id) = list.next_visible_item(cx) {
+        if item_id == 0 {
+            let item = list.item(cx, item_id, live_id!(AlertsEmpty));
+            item.label(ids!(empty_label)).set_text(cx, message);
+            item.draw_all(cx, &mut Scope::empty());
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Ref wrapper (same pattern as DataflowTableRef)
+// ---------------------------------------------------------------------------
+
+impl AlertsPanelRef {
+    pub fn localize(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.localize(cx);
+        }
+    }
+
+    pub fn set_rules(&self, cx: &mut Cx, rules: Vec<(String, String)>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_rules(cx, rules);
+        }
+    }
+
+    pub fn set_history(&self, cx: &mut Cx, history: Vec<AlertEvent>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_history(cx, history);
+        }
+    }
+
+    /// Show a parse error or confirmation below the rule input
+    pub fn set_rule_status(&self, cx: &mut Cx, message: &str) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .label(ids!(rule_status_label))
+                .set_text(cx, message);
+        }
+    }
+
+    pub fn clear_rule_input(&self, cx: &mut Cx) {
+        if let Some(inner) = self.borrow() {
+            inner.view.text_input(ids!(rule_input)).set_text(cx, "");
+        }
+    }
+
+    /// Check if add was clicked, returns the entered rule expression if so
+    pub fn add_clicked(&self, actions: &Actions) -> Option<String> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(add_rule_button))
+            .clicked(actions)
+            .then(|| inner.view.text_input(ids!(rule_input)).text())
+    }
+
+    /// Check if a rule's remove button was clicked, returns the rule index if so
+    pub fn remove_clicked(&self, actions: &Actions) -> Option<usize> {
+        let inner = self.borrow()?;
+        let rule_list = inner.view.portal_list(ids!(rule_list));
+        rule_list
+            .items_with_actions(actions)
+            .into_iter()
+            .find(|(item_id, item)| {
+                *item_id < inner.rules.len() && item.button(ids!(remove_button)).clicked(actions)
+            })
+            .map(|(item_id, _)| item_id)
+    }
+}
@@ -0,0 +1,289 @@
+//! Rule evaluation against trace query results.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::rule::{AlertMetric, AlertRule};
+use crate::otlp::types::{Span, TimeRange, TraceQuery};
+
+const MAX_HISTORY: usize = 200;
+/// Most spans fetched for one evaluation.
+pub const ALERT_SPAN_LIMIT: u32 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertState {
+    Firing,
+    Resolved,
+}
+
+impl AlertState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertState::Firing => "FIRING",
+            AlertState::Resolved => "RESOLVED",
+        }
+    }
+}
+
+/// A rule changing between firing and resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertEvent {
+    /// Canonical rule expression.
+    pub rule: String,
+    pub state: AlertState,
+    /// Measured value, formatted in the rule's unit.
+    pub value: String,
+    pub timestamp_ms: u64,
+}
+
+/// Tracks which rules are firing and records transitions.
+#[derive(Debug, Default)]
+pub struct AlertEngine {
+    /// Last measured value per rule, `None` when there was no data.
+    last_values: HashMap<String, Option<f64>>,
+    firing: HashMap<String, bool>,
+    /// Transitions, newest first.
+    history: VecDeque<AlertEvent>,
+}
+
+impl AlertEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Evaluate `rules` against `spans` and return the rules whose state
+    /// changed. `spans` must cover each rule's whole window: a firing rule
+    /// with no spans left in its window resolves.
+    pub fn evaluate(
+        &mut self,
+        rules: &[AlertRule],
+        spans: &[Span],
+        now_ms: u64,
+    ) -> Vec<AlertEvent> {
+        let mut events = Vec::new();
+        for rule in rules {
+            let key = rule.to_string();
+            let value = measure(rule, spans, now_ms);
+            self.last_values.insert(key.clone(), value);
+
+            let breached = value.is_some_and(|v| rule.is_breached(v));
+            let was_firing = self.firing.insert(key.clone(), breached).unwrap_or(false);
+            if breached == was_firing {
+                continue;
+            }
+            let event = AlertEvent {
+                rule: key,
+                state: if breached {
+                    AlertState::Firing
+                } else {
+                    AlertState::Resolved
+                },
+                value: value.map_or_else(|| "no data".to_string(), |v| rule.format_value(v)),
+                timestamp_ms: now_ms,
+            };
+            self.history.push_front(event.clone());
+            events.push(event);
+        }
+        self.history.truncate(MAX_HISTORY);
+        events
+    }
+
+    /// Current status of a rule for display: firing, ok, or no data.
+    pub fn status(&self, rule: &AlertRule) -> String {
+        let key = rule.to_string();
+        match self.last_values.get(&key).copied().flatten() {
+            None => "NO DATA".to_string(),
+            Some(value) if self.firing.get(&key).copied().unwrap_or(false) => {
+                format!("FIRING ({})", rule.format_value(value))
+            }
+            Some(value) => format!("OK ({})", rule.format_value(value)),
+        }
+    }
+
+    /// Forget the state of a removed rule.
+    pub fn remove(&mut self, rule: &AlertRule) {
+        let key = rule.to_string();
+        self.last_values.remove(&key);
+        self.firing.remove(&key);
+    }
+
+    pub fn history(&self) -> Vec<AlertEvent> {
+        self.history.iter().cloned().collect()
+    }
+}
+
+/// A query for every span in the longest of the rules' windows ending at
+/// `now_ms`, whatever any panel shows, to pass to [`AlertEngine::evaluate`].
+pub fn window_query(rules: &[AlertRule], now_ms: u64) -> TraceQuery {
+    let window_ms = rules.iter().map(|r| r.window_ms).max().unwrap_or(0);
+    TraceQuery {
+        time_range: Some(TimeRange {
+            start_ms: now_ms.saturating_sub(window_ms),
+            end_ms: now_ms,
+        }),
+        limit: Some(ALERT_SPAN_LIMIT),
+        ..Default::default()
+    }
+}
+
+/// Compute the rule's metric over spans in its window, or `None` if there
+/// are no matching spans.
+pub fn measure(rule: &AlertRule, spans: &[Span], now_ms: u64) -> Option<f64> {
+    let start_ms = now_ms.saturating_sub(rule.window_ms);
+    let spans: Vec<&Span> = spans
+        .iter()
+        .filter(|s| s.start_time_ms >= start_ms && s.start_time_ms <= now_ms)
+        .filter(|s| {
+            rule.service
+                .as_ref()
+                .is_none_or(|svc| s.service_name == *svc)
+        })
+        .collect();
+    if spans.is_empty() {
+        return None;
+    }
+
+    let mut durations: Vec<f64> = spans.iter().map(|s| s.duration_ms as f64).collect();
+    durations.sort_by(|a, b| a.total_cmp(b));
+    let count = spans.len() as f64;
+
+    let value = match rule.metric {
+        AlertMetric::ErrorRate => {
+            let errors = spans.iter().filter(|s| s.has_error).count() as f64;
+            errors / count * 100.0
+        }
+        AlertMetric::Percentile(p) => percentile(&durations, p),
+        AlertMetric::AvgDuration => durations.iter().sum::<f64>() / count,
+        AlertMetric::MaxDuration => durations.last().copied().unwrap_or_default(),
+        AlertMetric::SpanCount => count,
+    };
+    Some(value)
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[f64], p: u8) -> f64 {
+    let rank = (p as f64 / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(service: &str, start_ms: u64, duration_ms: u64, has_error: bool) -> Span {
+        Span {
+            trace_id: "t".to_string(),
+            span_id: format!("{}-{}", service, start_ms),
+            parent_span_id: None,
+            service_name: service.to_string(),
+            operation_name: "op".to_string(),
+            start_time_ms: start_ms,
+            duration_ms,
+            status_code: if has_error { 2 } else { 0 },
+            has_error,
+            attributes: HashMap::new(),
+            events: Vec::new(),
+            links: Vec::new(),
+        }
+    }
+
+    const NOW: u64 = 1_000_000;
+
+    #[test]
+    fn test_measure_error_rate_for_service() {
+        let rule = AlertRule::parse("error_rate > 5% for camera").unwrap();
+        let spans = vec![
+            span("camera", NOW - 1_000, 10, true),
+            span("camera", NOW - 2_000, 10, false),
+            span("camera", NOW - 3_000, 10, false),
+            span("camera", NOW - 4_000, 10, false),
+            span("planner", NOW - 1_000, 10, true),
+        ];
+        assert_eq!(measure(&rule, &spans, NOW), Some(25.0));
+    }
+
+    #[test]
+    fn test_measure_ignores_spans_outside_window() {
+        let rule = AlertRule::parse("count > 0 over 1m").unwrap();
+        let spans = vec![
+            span("a", NOW - 30_000, 10, false),
+            span("a", NOW - 120_000, 10, false),
+        ];
+        assert_eq!(measure(&rule, &spans, NOW), Some(1.0));
+        assert_eq!(measure(&rule, &spans[1..], NOW), None);
+    }
+
+    #[test]
+    fn test_measure_percentiles() {
+        let spans: Vec<Span> = (1..=100).map(|i| span("a", NOW, i, false)).collect();
+        let p99 = AlertRule::parse("p99 > 0").unwrap();
+        let p50 = AlertRule::parse("p50 > 0").unwrap();
+        let max = AlertRule::parse("max > 0").unwrap();
+        let avg = AlertRule::parse("avg > 0").unwrap();
+        assert_eq!(measure(&p99, &spans, NOW), Some(99.0));
+        assert_eq!(measure(&p50, &spans, NOW), Some(50.0));
+        assert_eq!(measure(&max, &spans, NOW), Some(100.0));
+        assert_eq!(measure(&avg, &spans, NOW), Some(50.5));
+    }
+
+    #[test]
+    fn test_evaluate_reports_transitions_only() {
+        let rules = vec![AlertRule::parse("p99 > 500ms").unwrap()];
+        let mut engine = AlertEngine::new();
+
+        let slow = vec![span("a", NOW, 800, false)];
+        let fast = vec![span("a", NOW, 100, false)];
+
+        let events = engine.evaluate(&rules, &slow, NOW);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].state, AlertState::Firing);
+        assert_eq!(events[0].value, "800ms");
+        assert!(engine.evaluate(&rules, &slow, NOW).is_empty());
+        assert!(engine.status(&rules[0]).starts_with("FIRING"));
+
+        let events = engine.evaluate(&rules, &fast, NOW);
+        assert_eq!(events[0].state, AlertState::Resolved);
+        assert_eq!(engine.history().len(), 2);
+        assert_eq!(engine.history()[0].state, AlertState::Resolved);
+    }
+
+    #[test]
+    fn test_window_query() {
+        let rules = vec![
+            AlertRule::parse("count > 0 over 1m").unwrap(),
+            AlertRule::parse("p99 > 500ms over 5m").unwrap(),
+        ];
+        let query = window_query(&rules, NOW);
+        let range = query.time_range.unwrap();
+        assert_eq!((range.start_ms, range.end_ms), (NOW - 300_000, NOW));
+        assert_eq!(query.limit, Some(ALERT_SPAN_LIMIT));
+        assert_eq!(query.service_name, None);
+    }
+
+    #[test]
+    fn test_empty_window_resolves() {
+        let rules = vec![AlertRule::parse("p99 > 500ms over 1m").unwrap()];
+        let mut engine = AlertEngine::new();
+        let slow = vec![span("a", NOW - 30_000, 800, false)];
+        assert_eq!(
+            engine.evaluate(&rules, &slow, NOW)[0].state,
+            AlertState::Firing
+        );
+
+        // The slow span has left the window and nothing replaced it
+        let events = engine.evaluate(&rules, &slow, NOW + 60_000);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].state, AlertState::Resolved);
+        assert_eq!(events[0].value, "no data");
+        assert_eq!(engine.status(&rules[0]), "NO DATA");
+        assert!(engine.evaluate(&rules, &[], NOW + 60_000).is_empty());
+    }
+
+    #[test]
+    fn test_healthy_rule_does_not_resolve() {
+        let rules = vec![AlertRule::parse("error_rate > 5%").unwrap()];
+        let mut engine = AlertEngine::new();
+        let ok = vec![span("a", NOW, 1, false)];
+        assert!(engine.evaluate(&rules, &ok, NOW).is_empty());
+        assert_eq!(engine.status(&rules[0]), "OK (0%)");
+    }
+}
@@ -0,0 +1,21 @@
+//! Alerting rules evaluated against telemetry query results.
+//!
+//! Rules are kept in settings as expression strings (see [`rule`]). After each
+//! trace refresh the engine measures every rule over the spans of its window,
+//! fetched by a query of their own, and reports rules that started or stopped
+//! firing; the app raises those as
+//! toasts and desktop notifications and lists them in the alerts panel.
+
+pub mod alerts_panel;
+pub mod engine;
+pub mod rule;
+
+pub use alerts_panel::{AlertsPanel, AlertsPanelRef, AlertsPanelWidgetRefExt};
+pub use engine::{window_query, AlertEngine, AlertEvent, AlertState, ALERT_SPAN_LIMIT};
+pub use rule::{AlertMetric, AlertRule, Comparison};
+
+use makepad_widgets::*;
+
+pub fn live_design(cx: &mut Cx) {
+    alerts_panel::live_design(cx);
+}
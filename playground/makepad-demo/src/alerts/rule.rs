@@ -0,0 +1,293 @@
+//! Alert rule expressions.
+//!
+//! Rules are written as `<metric> <op> <threshold> [over <window>] [for <service>]`,
+//! for example `error_rate > 5% over 5m for camera` or `p99 > 500ms`. They are
+//! persisted in settings as expression strings and parsed on load.
+
+use std::fmt;
+
+const DEFAULT_WINDOW_MS: u64 = 5 * 60 * 1000;
+
+/// Value a rule is evaluated on, computed from spans in the rule's window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertMetric {
+    /// Percentage of spans with an error status.
+    ErrorRate,
+    /// Span duration percentile, in milliseconds.
+    Percentile(u8),
+    /// Mean span duration, in milliseconds.
+    AvgDuration,
+    /// Longest span duration, in milliseconds.
+    MaxDuration,
+    /// Number of spans.
+    SpanCount,
+}
+
+impl AlertMetric {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "error_rate" => Ok(AlertMetric::ErrorRate),
+            "avg" => Ok(AlertMetric::AvgDuration),
+            "max" => Ok(AlertMetric::MaxDuration),
+            "count" => Ok(AlertMetric::SpanCount),
+            _ => s
+                .strip_prefix('p')
+                .and_then(|p| p.parse::<u8>().ok())
+                .filter(|p| (1..=99).contains(p))
+                .map(AlertMetric::Percentile)
+                .ok_or_else(|| {
+                    format!(
+                        "Unknown metric '{}' (expected error_rate, p50..p99, avg, max or count)",
+                        s
+                    )
+                }),
+        }
+    }
+
+    fn is_duration(&self) -> bool {
+        matches!(
+            self,
+            AlertMetric::Percentile(_) | AlertMetric::AvgDuration | AlertMetric::MaxDuration
+        )
+    }
+}
+
+impl fmt::Display for AlertMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertMetric::ErrorRate => write!(f, "error_rate"),
+            AlertMetric::Percentile(p) => write!(f, "p{}", p),
+            AlertMetric::AvgDuration => write!(f, "avg"),
+            AlertMetric::MaxDuration => write!(f, "max"),
+            AlertMetric::SpanCount => write!(f, "count"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+impl Comparison {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            ">" => Ok(Comparison::Greater),
+            ">=" => Ok(Comparison::GreaterOrEqual),
+            "<" => Ok(Comparison::Less),
+            "<=" => Ok(Comparison::LessOrEqual),
+            _ => Err(format!(
+                "Unknown comparison '{}' (expected >, >=, < or <=)",
+                s
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+        }
+    }
+}
+
+/// A parsed alert rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertRule {
+    pub metric: AlertMetric,
+    pub comparison: Comparison,
+    /// Percent for `error_rate`, milliseconds for duration metrics.
+    pub threshold: f64,
+    pub window_ms: u64,
+    /// Restrict evaluation to one service; all services when `None`.
+    pub service: Option<String>,
+}
+
+impl AlertRule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let tokens: Vec<&str> = expression.split_whitespace().collect();
+        let [metric, comparison, threshold, rest @ ..] = tokens.as_slice() else {
+            return Err(
+                "Expected '<metric> <op> <threshold> [over <window>] [for <service>]'".to_string(),
+            );
+        };
+
+        let metric = AlertMetric::parse(metric)?;
+        let comparison = Comparison::parse(comparison)?;
+        let threshold = parse_threshold(metric, threshold)?;
+
+        let mut window_ms = DEFAULT_WINDOW_MS;
+        let mut service = None;
+        let mut rest = rest.iter();
+        while let Some(keyword) = rest.next() {
+            let value = rest
+                .next()
+                .ok_or_else(|| format!("Missing value after '{}'", keyword))?;
+            match *keyword {
+                "over" => window_ms = parse_window(value)?,
+                "for" => service = Some(value.to_string()),
+                _ => return Err(format!("Unexpected '{}' (expected over or for)", keyword)),
+            }
+        }
+
+        Ok(Self {
+            metric,
+            comparison,
+            threshold,
+            window_ms,
+            service,
+        })
+    }
+
+    /// Whether a measured value violates the rule.
+    pub fn is_breached(&self, value: f64) -> bool {
+        match self.comparison {
+            Comparison::Greater => value > self.threshold,
+            Comparison::GreaterOrEqual => value >= self.threshold,
+            Comparison::Less => value < self.threshold,
+            Comparison::LessOrEqual => value <= self.threshold,
+        }
+    }
+
+    /// Format a measured value in the rule's unit, e.g. `7.5%` or `612ms`.
+    pub fn format_value(&self, value: f64) -> String {
+        match self.metric {
+            AlertMetric::ErrorRate => format!("{}%", trim_number(value)),
+            AlertMetric::SpanCount => trim_number(value),
+            _ => format!("{}ms", trim_number(value)),
+        }
+    }
+}
+
+/// Canonical expression, used as the rule's identity.
+impl fmt::Display for AlertRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} over {}",
+            self.metric,
+            self.comparison.as_str(),
+            self.format_value(self.threshold),
+            format_window(self.window_ms)
+        )?;
+        if let Some(service) = &self.service {
+            write!(f, " for {}", service)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_threshold(metric: AlertMetric, s: &str) -> Result<f64, String> {
+    let invalid = || format!("Invalid threshold '{}'", s);
+    let value = if metric == AlertMetric::ErrorRate {
+        s.strip_suffix('%').unwrap_or(s).parse::<f64>()
+    } else if metric.is_duration() {
+        if let Some(ms) = s.strip_suffix("ms") {
+            ms.parse::<f64>()
+        } else if let Some(secs) = s.strip_suffix('s') {
+            secs.parse::<f64>().map(|v| v * 1000.0)
+        } else {
+            s.parse::<f64>()
+        }
+    } else {
+        s.parse::<f64>()
+    };
+    value
+        .ok()
+        .filter(|v| v.is_finite() && *v >= 0.0)
+        .ok_or_else(invalid)
+}
+
+fn parse_window(s: &str) -> Result<u64, String> {
+    let invalid = || format!("Invalid window '{}' (e.g. 30s, 5m, 1h)", s);
+    let split = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (amount, unit) = s.split_at(split);
+    let amount: u64 = amount.parse().map_err(|_| invalid())?;
+    let unit_ms = match unit {
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        _ => return Err(invalid()),
+    };
+    match amount * unit_ms {
+        0 => Err(invalid()),
+        ms => Ok(ms),
+    }
+}
+
+fn format_window(ms: u64) -> String {
+    if ms % 3_600_000 == 0 {
+        format!("{}h", ms / 3_600_000)
+    } else if ms % 60_000 == 0 {
+        format!("{}m", ms / 60_000)
+    } else {
+        format!("{}s", ms / 1_000)
+    }
+}
+
+/// Format without a trailing `.0`, keeping at most two decimals.
+fn trim_number(value: f64) -> String {
+    let s = format!("{:.2}", value);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_rate_rule() {
+        let rule = AlertRule::parse("error_rate > 5% over 5m for camera").unwrap();
+        assert_eq!(rule.metric, AlertMetric::ErrorRate);
+        assert_eq!(rule.comparison, Comparison::Greater);
+        assert_eq!(rule.threshold, 5.0);
+        assert_eq!(rule.window_ms, 5 * 60 * 1000);
+        assert_eq!(rule.service.as_deref(), Some("camera"));
+    }
+
+    #[test]
+    fn test_parse_duration_rule_with_defaults() {
+        let rule = AlertRule::parse("p99 >= 0.5s").unwrap();
+        assert_eq!(rule.metric, AlertMetric::Percentile(99));
+        assert_eq!(rule.comparison, Comparison::GreaterOrEqual);
+        assert_eq!(rule.threshold, 500.0);
+        assert_eq!(rule.window_ms, DEFAULT_WINDOW_MS);
+        assert_eq!(rule.service, None);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(AlertRule::parse("").is_err());
+        assert!(AlertRule::parse("latency > 5").is_err());
+        assert!(AlertRule::parse("p99 => 5").is_err());
+        assert!(AlertRule::parse("p99 > fast").is_err());
+        assert!(AlertRule::parse("p100 > 5").is_err());
+        assert!(AlertRule::parse("count > 5 over").is_err());
+        assert!(AlertRule::parse("count > 5 over 5d").is_err());
+        assert!(AlertRule::parse("count > 5 during 5m").is_err());
+    }
+
+    #[test]
+    fn test_display_is_canonical() {
+        let rule = AlertRule::parse("p99   >  500   for  planner").unwrap();
+        assert_eq!(rule.to_string(), "p99 > 500ms over 5m for planner");
+        assert_eq!(AlertRule::parse(&rule.to_string()).unwrap(), rule);
+
+        let rule = AlertRule::parse("error_rate > 2.5 over 90s").unwrap();
+        assert_eq!(rule.to_string(), "error_rate > 2.5% over 90s");
+    }
+
+    #[test]
+    fn test_is_breached() {
+        let rule = AlertRule::parse("count < 10").unwrap();
+        assert!(rule.is_breached(3.0));
+        assert!(!rule.is_breached(10.0));
+        let rule = AlertRule::parse("count <= 10").unwrap();
+        assert!(rule.is_breached(10.0));
+    }
+}
@@ -1,50 +1,227 @@
+use crate::i18n;
+use crate::instrument;
+use crate::logging;
 #[cfg(not(target_arch = "wasm32"))]
-use crate::tools::{execute_tool, get_dora_tools};
-#[cfg(target_arch = "wasm32")]
-use makepad_widgets::Cx;
+use crate::otlp::bridge::SignozBridge;
+use crate::text;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::tools::{execute_tool_in, get_dora_tools, ToolError, ToolGroup};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::traces::saved::{self, SavedTraceQuery};
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 
 // Native-only imports
 #[cfg(not(target_arch = "wasm32"))]
-use tokio::runtime::Runtime;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 #[cfg(not(target_arch = "wasm32"))]
-use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::sync::oneshot;
+
+/// Handle to the chat agent: its LLM settings, and a worker on the shared
+/// runtime (see [`crate::tasks::runtime`]) that answers chat, summary and
+/// model list requests and leaves the replies in shared state, which the UI
+/// polls each frame.
+///
+/// Clones share the worker, which stops once every handle is dropped.
+#[derive(Clone)]
+pub struct ChatClient {
+    #[cfg(not(target_arch = "wasm32"))]
+    sender: UnboundedSender<ApiRequest>,
+    shared: Arc<ChatShared>,
+}
 
-// Global state
-#[cfg(not(target_arch = "wasm32"))]
-static TOKIO_RUNTIME: Mutex<Option<Runtime>> = Mutex::new(None);
+/// State the worker updates and the handles read.
+#[derive(Default)]
+struct ChatShared {
+    api_key: Mutex<String>,
+    model: Mutex<String>,
+    backend: Mutex<LlmBackend>,
+    local_url: Mutex<String>,
+    /// Bumped to cancel chat requests; replies to older generations are
+    /// dropped
+    generation: AtomicU64,
+    /// Aborts the chat request in flight
+    #[cfg(not(target_arch = "wasm32"))]
+    cancel: Mutex<Option<oneshot::Sender<()>>>,
+    /// Tools run for the model, cancelled with its request
+    #[cfg(not(target_arch = "wasm32"))]
+    tools: ToolGroup,
+    // Replies waiting to be polled
+    pending_response: Mutex<Option<ChatResponse>>,
+    pending_summary: Mutex<Option<SummaryResponse>>,
+    pending_models: Mutex<Option<Result<Vec<String>, String>>>,
+    /// Full tool results, addressable by reference ID ("T1", "T2", ...)
+    tool_records: Mutex<Vec<ToolRecord>>,
+    /// Tool calls made for the chat request in flight, updated as they run
+    tool_steps: Mutex<Vec<ToolStep>>,
+    /// Why a request waits to be retried, shown in the chat
+    retry_notice: Mutex<Option<String>>,
+    /// Trace queries saved in the traces panel, runnable by name
+    #[cfg(not(target_arch = "wasm32"))]
+    saved_trace_queries: Mutex<Vec<SavedTraceQuery>>,
+    /// Where saved trace queries run
+    #[cfg(not(target_arch = "wasm32"))]
+    telemetry: Option<SignozBridge>,
+}
+
+/// Keep the newest [`MAX_TOOL_RECORDS`] of `records`.
+fn drop_oldest_records(records: &mut Vec<ToolRecord>) {
+    let excess = records.len().saturating_sub(MAX_TOOL_RECORDS);
+    records.drain(..excess);
+}
+
+/// Model used until another is picked
+pub const DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
+
+/// Local inference server used when none is set (`llama-server`'s default)
+pub const DEFAULT_LOCAL_URL: &str = "http://127.0.0.1:8080";
+
+/// Tool rounds per chat request before the agent is stopped
 #[cfg(not(target_arch = "wasm32"))]
-static REQUEST_SENDER: Mutex<Option<UnboundedSender<Vec<ChatMessage>>>> = Mutex::new(None);
-static API_KEY: Mutex<String> = Mutex::new(String::new());
+const MAX_TOOL_ITERATIONS: u32 = 10;
 
-// Pending response for polling
-static PENDING_RESPONSE: Mutex<Option<ChatResponse>> = Mutex::new(None);
+/// Characters of a tool's output shown on its card in the chat
+#[cfg(not(target_arch = "wasm32"))]
+const TOOL_OUTPUT_CHARS: usize = 1_000;
 
-/// Check if there's a pending response from the API
-pub fn take_pending_response() -> Option<ChatResponse> {
-    PENDING_RESPONSE.lock().unwrap().take()
-}
+/// Tool results kept for reference; older ones are dropped
+const MAX_TOOL_RECORDS: usize = 100;
 
-/// System prompt for the Dora assistant (native with tools)
+/// Appended to the prompt template's system prompt (native with tools; see
+/// [`crate::chat::prompts`])
 #[cfg(not(target_arch = "wasm32"))]
-const SYSTEM_PROMPT: &str = r#"You are Dora Studio Assistant. Be extremely concise and succinct.
+const TOOLS_PROMPT: &str = r#"You have tools for: dora dataflows (list/start/stop/destroy), file operations (read/write), shell commands, directory browsing, saved trace queries.
+
+Use tools proactively. Show results briefly. To run a pipeline you have written, use start_dataflow with the YAML; it builds, starts and returns the UUID.
+
+Tool results are labelled with reference IDs like [T3]. Mention the ID when you rely on a result; use get_tool_result to read a result again in full."#;
+
+/// System prompt for rolling conversation summaries
+const SUMMARY_SYSTEM_PROMPT: &str = r#"Summarize the conversation for a later continuation of it. Merge the previous summary, if any, with the new turns.
+
+Keep:
+- Dataflow names, UUIDs, paths and node IDs
+- Decisions made and questions still open
+- Tool reference IDs like [T3], verbatim, next to what they showed
 
-Rules:
-- Give short, direct answers
-- No unnecessary explanations or preambles
-- Only provide details when specifically asked
-- Use bullet points for lists
-- Skip pleasantries
+At most 200 words. Bullet points. No preamble."#;
 
-You have tools for: dora dataflows (list/start/stop/destroy), file operations (read/write), shell commands, directory browsing.
+/// Name of the built-in tool that returns a stored tool result by reference ID
+#[cfg(not(target_arch = "wasm32"))]
+const TOOL_RESULT_TOOL: &str = "get_tool_result";
 
-Use tools proactively. Show results briefly."#;
+/// Name of the built-in tool that runs a trace query saved in the traces panel
+#[cfg(not(target_arch = "wasm32"))]
+const SAVED_TRACE_QUERY_TOOL: &str = "run_saved_trace_query";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: MessageRole,
     pub content: String,
+    /// Model that wrote an assistant message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Tool calls made while writing an assistant message
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_steps: Vec<ToolStep>,
+}
+
+impl ChatMessage {
+    /// Content as the model sees it: the tool calls made for the message,
+    /// with their reference IDs, then its text.
+    pub fn context_text(&self) -> String {
+        if self.tool_steps.is_empty() {
+            return self.content.clone();
+        }
+        let mut lines: Vec<String> = self.tool_steps.iter().map(ToolStep::context_line).collect();
+        if !self.content.is_empty() {
+            lines.push(self.content.clone());
+        }
+        lines.join("\n\n")
+    }
+}
+
+/// How far a tool call has got
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolStepStatus {
+    Running,
+    Ok,
+    Error,
+    TimedOut,
+    Cancelled,
+}
+
+/// A tool call made by the agent, as shown in the chat
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolStep {
+    pub name: String,
+    /// Arguments as JSON
+    pub arguments: String,
+    pub status: ToolStepStatus,
+    /// Reference ID of the stored result, once finished
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ref_id: Option<String>,
+    /// One-line summary of the result, once finished
+    #[serde(default)]
+    pub summary: String,
+    /// Start of the output, once finished
+    #[serde(default)]
+    pub output: String,
+}
+
+impl ToolStep {
+    /// e.g. `Tool: dora_logs (dataflow: camera, lines: 200)`
+    pub fn title(&self) -> String {
+        let arguments = match serde_json::from_str::<serde_json::Value>(&self.arguments) {
+            Ok(serde_json::Value::Object(map)) => map
+                .iter()
+                .map(|(key, value)| match value {
+                    serde_json::Value::String(s) => format!("{}: {}", key, s),
+                    value => format!("{}: {}", key, value),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => self.arguments.clone(),
+        };
+        i18n::tr_args(
+            "chat.tool_title",
+            &[
+                ("name", &self.name),
+                ("arguments", &text::truncate(&arguments, 60)),
+            ],
+        )
+    }
+
+    /// e.g. `✅ T3`, or `running…`
+    pub fn status_text(&self) -> String {
+        let label = match self.status {
+            ToolStepStatus::Running => return i18n::tr("chat.tool_running"),
+            ToolStepStatus::Ok => "✅".to_string(),
+            ToolStepStatus::Error => i18n::tr("chat.tool_error"),
+            ToolStepStatus::TimedOut => i18n::tr("chat.tool_timed_out"),
+            ToolStepStatus::Cancelled => i18n::tr("chat.tool_cancelled"),
+        };
+        match &self.ref_id {
+            Some(ref_id) => format!("{} {}", label, ref_id),
+            None => label,
+        }
+    }
+
+    /// The call and its result for the model, e.g. `🔧 Executing: dora_list`
+    /// then `✅ Result [T1]: 2 dataflows`
+    pub fn context_line(&self) -> String {
+        let ref_id = self.ref_id.as_deref().unwrap_or("-");
+        let result = match self.status {
+            ToolStepStatus::Running => "Did not finish".to_string(),
+            ToolStepStatus::Ok => format!("✅ Result [{}]: {}", ref_id, self.summary),
+            ToolStepStatus::Error => format!("❌ Error [{}]: {}", ref_id, self.summary),
+            ToolStepStatus::TimedOut => format!("❌ Timed out [{}]: {}", ref_id, self.summary),
+            ToolStepStatus::Cancelled => format!("❌ Cancelled [{}]: {}", ref_id, self.summary),
+        };
+        format!("🔧 Executing: {}\n{}", self.name, result)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -54,6 +231,72 @@ pub enum MessageRole {
     Assistant,
 }
 
+/// A tool execution kept in full so it can be referenced after its turn
+/// has been summarized.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolRecord {
+    /// Reference ID shown to the user and the model, e.g. `T3`.
+    pub id: String,
+    pub name: String,
+    pub content: String,
+    pub is_error: bool,
+}
+
+/// Where chat, summary and model list requests are sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmBackend {
+    /// The Claude API, with the API key
+    #[default]
+    Anthropic,
+    /// An inference server with an OpenAI-compatible API, such as llama.cpp's
+    /// `llama-server`; needs no API key or internet access
+    Local,
+}
+
+impl LlmBackend {
+    /// In the order of the settings dropdown
+    pub const ALL: [LlmBackend; 2] = [LlmBackend::Anthropic, LlmBackend::Local];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LlmBackend::Anthropic => "Claude API",
+            LlmBackend::Local => "Local server",
+        }
+    }
+
+    /// Catalog key of the label, for the UI
+    pub fn label_key(self) -> &'static str {
+        match self {
+            LlmBackend::Anthropic => "diagnostics.llm.claude",
+            LlmBackend::Local => "diagnostics.llm.local",
+        }
+    }
+}
+
+/// Outcome of a summary request, for the messages before `covers_until`
+#[derive(Debug, Clone)]
+pub struct SummaryResponse {
+    pub covers_until: usize,
+    pub result: Result<String, String>,
+}
+
+/// Work for the native API runtime
+#[cfg(not(target_arch = "wasm32"))]
+enum ApiRequest {
+    /// Messages and system prompt, and the chat generation they were sent in
+    Chat {
+        messages: Vec<ChatMessage>,
+        system: String,
+        generation: u64,
+    },
+    Summarize {
+        covers_until: usize,
+        prompt: String,
+    },
+    ListModels,
+}
+
 #[derive(Debug, Clone)]
 pub enum ChatResponse {
     Message(String),
@@ -61,102 +304,367 @@ pub enum ChatResponse {
     Error(String),
 }
 
-/// Set the API key for Claude
-pub fn set_api_key(key: String) {
-    *API_KEY.lock().unwrap() = key;
-}
+impl ChatClient {
+    /// Start a worker for API calls on the shared runtime; saved trace
+    /// queries run through `telemetry` (native)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn start(telemetry: Option<SignozBridge>) -> Self {
+        let shared = Arc::new(ChatShared {
+            telemetry,
+            ..ChatShared::default()
+        });
+        let (sender, receiver) = unbounded_channel::<ApiRequest>();
+        let worker = Arc::clone(&shared);
+        // Drive the worker from its own thread on the shared runtime: tool
+        // calls block while they run
+        std::thread::spawn(move || {
+            crate::tasks::runtime().block_on(run_worker(worker, receiver));
+        });
+        Self { sender, shared }
+    }
+
+    /// A client whose requests run as browser tasks (WASM)
+    #[cfg(target_arch = "wasm32")]
+    pub fn start() -> Self {
+        Self {
+            shared: Arc::new(ChatShared::default()),
+        }
+    }
+
+    /// Set the API key for Claude
+    pub fn set_api_key(&self, key: String) {
+        *self.shared.api_key.lock().unwrap() = key;
+    }
 
-/// Get the current API key
-pub fn get_api_key() -> String {
-    API_KEY.lock().unwrap().clone()
+    /// Get the current API key
+    pub fn api_key(&self) -> String {
+        self.shared.api_key()
+    }
+
+    /// Set the model for chat and summaries; the default model when empty
+    pub fn set_model(&self, model: &str) {
+        *self.shared.model.lock().unwrap() = model.trim().to_string();
+    }
+
+    /// Get the model requests are sent to
+    pub fn model(&self) -> String {
+        self.shared.model()
+    }
+
+    /// Send requests to `backend`; `local_url` is the base URL of the local
+    /// server, [`DEFAULT_LOCAL_URL`] when empty
+    pub fn set_backend(&self, backend: LlmBackend, local_url: &str) {
+        *self.shared.backend.lock().unwrap() = backend;
+        *self.shared.local_url.lock().unwrap() = local_url.trim().to_string();
+    }
+
+    pub fn backend(&self) -> LlmBackend {
+        self.shared.backend()
+    }
+
+    /// Whether requests can be sent: the local server needs no API key
+    pub fn is_configured(&self) -> bool {
+        self.backend() == LlmBackend::Local || !self.api_key().is_empty()
+    }
+
+    /// Set the trace queries the agent can run by name
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn set_saved_trace_queries(&self, queries: &[SavedTraceQuery]) {
+        *self.shared.saved_trace_queries.lock().unwrap() = queries.to_vec();
+    }
+
+    /// Initialize API key from environment variable, or from the secret
+    /// store when it is not set (see [`crate::secrets`])
+    pub fn init_api_key_from_env(&self) {
+        if let Ok(key) = std::env::var("ANTHROPIC_API_KEY") {
+            if !key.is_empty() {
+                tracing::info!(target: "API", "Loaded API key from env ({} chars)", key.len());
+                self.set_api_key(key);
+                return;
+            }
+            tracing::warn!(target: "API", "ANTHROPIC_API_KEY env var is empty");
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(key) = crate::secrets::get(crate::secrets::ANTHROPIC_API_KEY) {
+            tracing::info!(target: "API", "Loaded API key from the secret store");
+            self.set_api_key(key);
+            return;
+        }
+        tracing::warn!(target: "API", "ANTHROPIC_API_KEY env var not set");
+    }
+
+    /// Check if there's a pending response from the API
+    pub fn take_pending_response(&self) -> Option<ChatResponse> {
+        self.shared.pending_response.lock().unwrap().take()
+    }
+
+    /// Check if there's a pending summary from the API
+    pub fn take_pending_summary(&self) -> Option<SummaryResponse> {
+        self.shared.pending_summary.lock().unwrap().take()
+    }
+
+    /// Check if there's a pending model list from the API
+    pub fn take_pending_models(&self) -> Option<Result<Vec<String>, String>> {
+        self.shared.pending_models.lock().unwrap().take()
+    }
+
+    /// Tool calls made so far for the chat request in flight
+    pub fn tool_steps(&self) -> Vec<ToolStep> {
+        self.shared.tool_steps.lock().unwrap().clone()
+    }
+
+    /// Take the tool calls made for the reply just taken
+    pub fn take_tool_steps(&self) -> Vec<ToolStep> {
+        std::mem::take(&mut *self.shared.tool_steps.lock().unwrap())
+    }
+
+    /// Replace the stored tool results, e.g. with those of a loaded
+    /// transcript
+    pub fn set_tool_records(&self, mut records: Vec<ToolRecord>) {
+        drop_oldest_records(&mut records);
+        *self.shared.tool_records.lock().unwrap() = records;
+    }
+
+    /// Snapshot of the stored tool results
+    pub fn tool_records(&self) -> Vec<ToolRecord> {
+        self.shared.tool_records.lock().unwrap().clone()
+    }
+
+    /// Why a request is waiting to be sent again, e.g. `Rate limited,
+    /// retrying in 5s (1/3)`; `None` when no request is waiting
+    pub fn retry_notice(&self) -> Option<String> {
+        self.shared.retry_notice.lock().unwrap().clone()
+    }
+
+    /// Submit a chat request to the Claude API (native)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn submit_chat_request(&self, messages: Vec<ChatMessage>, system: String) {
+        self.send(ApiRequest::Chat {
+            messages,
+            system,
+            generation: self.shared.generation.load(Ordering::SeqCst),
+        });
+    }
+
+    /// Submit a chat request to the Claude API (WASM)
+    #[cfg(target_arch = "wasm32")]
+    pub fn submit_chat_request(&self, messages: Vec<ChatMessage>, system: String) {
+        let shared = Arc::clone(&self.shared);
+        let generation = shared.generation.load(Ordering::SeqCst);
+        wasm_bindgen_futures::spawn_local(async move {
+            let response = call_claude_api_simple(&shared, messages, &system).await;
+            shared.finish_chat(generation, response);
+        });
+    }
+
+    /// Cancel chat requests sent so far: the one in flight is aborted along
+    /// with any tool it is running, queued ones are skipped and replies not
+    /// yet taken are dropped.
+    pub fn cancel_chat_request(&self) {
+        let mut pending = self.shared.pending_response.lock().unwrap();
+        self.shared.generation.fetch_add(1, Ordering::SeqCst);
+        *pending = None;
+        drop(pending);
+        self.shared.retry_notice.lock().unwrap().take();
+        self.shared.tool_steps.lock().unwrap().clear();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(cancel) = self.shared.cancel.lock().unwrap().take() {
+                let _ = cancel.send(());
+            }
+            self.shared.tools.cancel();
+        }
+    }
+
+    /// Ask the Claude API to summarize the messages before `covers_until`
+    /// (native)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn submit_summary_request(&self, covers_until: usize, prompt: String) {
+        self.send(ApiRequest::Summarize {
+            covers_until,
+            prompt,
+        });
+    }
+
+    /// Ask the Claude API to summarize the messages before `covers_until`
+    /// (WASM)
+    #[cfg(target_arch = "wasm32")]
+    pub fn submit_summary_request(&self, covers_until: usize, prompt: String) {
+        let shared = Arc::clone(&self.shared);
+        wasm_bindgen_futures::spawn_local(async move {
+            let result = call_claude_summary(&shared, prompt).await;
+            *shared.pending_summary.lock().unwrap() = Some(SummaryResponse {
+                covers_until,
+                result,
+            });
+        });
+    }
+
+    /// Fetch the models the API key can use (native)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn submit_models_request(&self) {
+        self.send(ApiRequest::ListModels);
+    }
+
+    /// Fetch the models the API key can use (WASM)
+    #[cfg(target_arch = "wasm32")]
+    pub fn submit_models_request(&self) {
+        let shared = Arc::clone(&self.shared);
+        wasm_bindgen_futures::spawn_local(async move {
+            *shared.pending_models.lock().unwrap() = Some(list_models(&shared).await);
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn send(&self, request: ApiRequest) {
+        if self.sender.send(request).is_err() {
+            tracing::error!(target: "API", "API worker stopped");
+        }
+    }
 }
 
-/// Initialize API key from environment variable
-pub fn init_api_key_from_env() {
-    if let Ok(key) = std::env::var("ANTHROPIC_API_KEY") {
-        if !key.is_empty() {
-            eprintln!("[API] Loaded API key from env ({} chars)", key.len());
-            set_api_key(key);
+impl ChatShared {
+    fn api_key(&self) -> String {
+        self.api_key.lock().unwrap().clone()
+    }
+
+    fn model(&self) -> String {
+        let model = self.model.lock().unwrap();
+        if model.is_empty() {
+            DEFAULT_MODEL.to_string()
         } else {
-            eprintln!("[API] ANTHROPIC_API_KEY env var is empty");
+            model.clone()
         }
-    } else {
-        eprintln!("[API] ANTHROPIC_API_KEY env var not set");
     }
-}
 
-/// Initialize the async runtime for API calls (native only)
-#[cfg(not(target_arch = "wasm32"))]
-pub fn start_api_runtime() {
-    // Use a single lock to check and initialize atomically
-    let mut rt_lock = TOKIO_RUNTIME.lock().unwrap();
-    if rt_lock.is_some() {
-        return;
-    }
-
-    // Mark as initialized immediately to prevent race conditions
-    *rt_lock = Some(Runtime::new().expect("marker runtime"));
-    drop(rt_lock); // Release the lock before spawning
-
-    // Try to load API key from environment (fallback if startup didn't run)
-    init_api_key_from_env();
-
-    // Set up channel
-    let (sender, mut receiver) = unbounded_channel::<Vec<ChatMessage>>();
-    *REQUEST_SENDER.lock().unwrap() = Some(sender);
-
-    // Create runtime and run it on a background thread
-    std::thread::spawn(move || {
-        let rt = Runtime::new().expect("Failed to create Tokio runtime");
-
-        rt.block_on(async {
-            eprintln!("[API] Runtime started, waiting for requests...");
-            while let Some(messages) = receiver.recv().await {
-                eprintln!("[API] Received request with {} messages", messages.len());
-                eprintln!("[API] API key length: {}", get_api_key().len());
-                let response = call_claude_api_with_tools(messages).await;
-                eprintln!(
-                    "[API] Got response: {:?}",
-                    match &response {
-                        ChatResponse::Message(s) => format!("Message({} chars)", s.len()),
-                        ChatResponse::ToolExecution(s) => format!("Tool: {}", s),
-                        ChatResponse::Error(e) => format!("Error: {}", e),
-                    }
-                );
-                // Store response for polling instead of post_action
-                *PENDING_RESPONSE.lock().unwrap() = Some(response);
-                eprintln!("[API] Response stored for polling");
-            }
+    fn backend(&self) -> LlmBackend {
+        *self.backend.lock().unwrap()
+    }
+
+    /// URL of `path` under the local server's `/v1` API, e.g.
+    /// `chat/completions`
+    fn local_endpoint(&self, path: &str) -> String {
+        local_endpoint_of(&self.local_url.lock().unwrap(), path)
+    }
+
+    /// Look up a stored tool result by reference ID
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    fn tool_record(&self, id: &str) -> Option<ToolRecord> {
+        let id = id.trim().trim_start_matches('[').trim_end_matches(']');
+        self.tool_records
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|r| r.id.eq_ignore_ascii_case(id))
+            .cloned()
+    }
+
+    /// Store a tool result and return its reference ID, dropping the
+    /// oldest past [`MAX_TOOL_RECORDS`]. IDs keep counting up, so a dropped
+    /// result's ID is never reused.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    fn record_tool_result(&self, name: &str, content: &str, is_error: bool) -> String {
+        let mut records = self.tool_records.lock().unwrap();
+        let last = records
+            .last()
+            .and_then(|r| r.id.strip_prefix('T')?.parse::<usize>().ok())
+            .unwrap_or(0);
+        let id = format!("T{}", last + 1);
+        records.push(ToolRecord {
+            id: id.clone(),
+            name: name.to_string(),
+            content: content.to_string(),
+            is_error,
         });
-    });
+        drop_oldest_records(&mut records);
+        id
+    }
+
+    /// Leave `response` to be polled, unless the request was cancelled as
+    /// it finished
+    fn finish_chat(&self, generation: u64, response: ChatResponse) {
+        let mut pending = self.pending_response.lock().unwrap();
+        if generation == self.generation.load(Ordering::SeqCst) {
+            *pending = Some(response);
+            tracing::trace!(target: "API", "Response stored for polling");
+        }
+    }
 }
 
-/// Submit a chat request to the Claude API (native)
-#[cfg(not(target_arch = "wasm32"))]
-pub fn submit_chat_request(messages: Vec<ChatMessage>) {
-    eprintln!("[API] submit_chat_request called");
-    // Ensure runtime is started
-    start_api_runtime();
-    eprintln!("[API] runtime started");
-
-    eprintln!("[API] acquiring REQUEST_SENDER lock");
-    if let Some(sender) = REQUEST_SENDER.lock().unwrap().as_ref() {
-        eprintln!("[API] sending message");
-        let _ = sender.send(messages);
-        eprintln!("[API] message sent");
+fn local_endpoint_of(base: &str, path: &str) -> String {
+    let base = base.trim().trim_end_matches('/');
+    let base = if base.is_empty() {
+        DEFAULT_LOCAL_URL
     } else {
-        eprintln!("[API] no sender available!");
-    }
-    eprintln!("[API] submit_chat_request complete");
+        base
+    };
+    let base = base.strip_suffix("/v1").unwrap_or(base);
+    format!("{}/v1/{}", base, path)
 }
 
-/// Submit a chat request to the Claude API (WASM)
-#[cfg(target_arch = "wasm32")]
-pub fn submit_chat_request(messages: Vec<ChatMessage>) {
-    wasm_bindgen_futures::spawn_local(async move {
-        let response = call_claude_api_simple(messages).await;
-        Cx::post_action(response);
-    });
+/// Answer requests until every handle is dropped (native)
+#[cfg(not(target_arch = "wasm32"))]
+async fn run_worker(shared: Arc<ChatShared>, mut receiver: UnboundedReceiver<ApiRequest>) {
+    tracing::info!(target: "API", "Runtime started, waiting for requests...");
+    while let Some(request) = receiver.recv().await {
+        let _job = crate::tasks::BackgroundJob::start();
+        let (messages, system, generation) = match request {
+            ApiRequest::Chat {
+                messages,
+                system,
+                generation,
+            } => (messages, system, generation),
+            ApiRequest::Summarize {
+                covers_until,
+                prompt,
+            } => {
+                let result = call_claude_summary(&shared, prompt).await;
+                if let Err(e) = &result {
+                    tracing::warn!(target: "API", "Summary failed: {}", e);
+                }
+                *shared.pending_summary.lock().unwrap() = Some(SummaryResponse {
+                    covers_until,
+                    result,
+                });
+                continue;
+            }
+            ApiRequest::ListModels => {
+                *shared.pending_models.lock().unwrap() = Some(list_models(&shared).await);
+                continue;
+            }
+        };
+        tracing::debug!(target: "API", "Received request with {} messages", messages.len());
+        tracing::trace!(target: "API", "API key length: {}", shared.api_key().len());
+        if generation != shared.generation.load(Ordering::SeqCst) {
+            tracing::info!(target: "API", "Chat request cancelled before it started");
+            continue;
+        }
+        shared.tool_steps.lock().unwrap().clear();
+        let (cancel, cancelled) = oneshot::channel::<()>();
+        *shared.cancel.lock().unwrap() = Some(cancel);
+        // Dropping the API future on cancel aborts its HTTP request
+        let response = tokio::select! {
+            response = call_chat_api(&shared, messages, &system) => Some(response),
+            _ = cancelled => None,
+        };
+        shared.cancel.lock().unwrap().take();
+        let Some(response) = response else {
+            tracing::info!(target: "API", "Chat request cancelled");
+            continue;
+        };
+        match &response {
+            ChatResponse::Message(s) => {
+                tracing::debug!(target: "API", "Got response: Message({} chars)", s.len())
+            }
+            ChatResponse::ToolExecution(s) => {
+                tracing::debug!(target: "API", "Got response: Tool: {}", s)
+            }
+            ChatResponse::Error(e) => tracing::error!(target: "API", "Got response: Error: {}", e),
+        }
+        // Store response for polling instead of post_action
+        shared.finish_chat(generation, response);
+    }
 }
 
 // ============================================================================
@@ -259,6 +767,183 @@ struct ClaudeErrorResponse {
 #[derive(Deserialize)]
 struct ClaudeErrorDetail {
     message: String,
+    /// e.g. `rate_limit_error`, or OpenAI's `insufficient_quota`
+    #[serde(default, rename = "type")]
+    error_type: String,
+}
+
+// ============================================================================
+// Rate Limits and Retries
+// ============================================================================
+
+/// Retries of a rate limited or failed request before giving up
+const MAX_RETRIES: u32 = 3;
+
+/// Longest wait before a retry, whatever `Retry-After` asks for
+const MAX_RETRY_DELAY_SECS: u64 = 60;
+
+/// Why a request failed, from its status and error body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Failure {
+    /// Bad or missing API key, or no access to the model
+    Auth,
+    /// Out of credits or over the usage limit; retrying does not help
+    Quota,
+    /// Too many requests for now
+    RateLimited,
+    /// The server failed or is overloaded
+    Unavailable,
+    Other,
+}
+
+impl Failure {
+    fn classify(status: u16, error_type: &str, message: &str) -> Self {
+        let message = message.to_lowercase();
+        if matches!(status, 401 | 403)
+            || matches!(error_type, "authentication_error" | "permission_error")
+        {
+            Failure::Auth
+        } else if error_type == "insufficient_quota"
+            || message.contains("credit balance")
+            || message.contains("quota")
+        {
+            Failure::Quota
+        } else if status == 429 || error_type == "rate_limit_error" {
+            Failure::RateLimited
+        } else if status >= 500 || error_type == "overloaded_error" {
+            Failure::Unavailable
+        } else {
+            Failure::Other
+        }
+    }
+
+    fn is_retryable(self) -> bool {
+        matches!(self, Failure::RateLimited | Failure::Unavailable)
+    }
+}
+
+/// Type and message of an error body, e.g. `{"error": {"type": ...,
+/// "message": ...}}` as Claude and OpenAI-compatible servers send
+fn error_detail(body: &str) -> Option<ClaudeErrorDetail> {
+    serde_json::from_str::<ClaudeErrorResponse>(body)
+        .ok()
+        .map(|r| r.error)
+}
+
+fn classify_response(status: reqwest::StatusCode, body: &str) -> Failure {
+    let detail = error_detail(body);
+    Failure::classify(
+        status.as_u16(),
+        detail.as_ref().map_or("", |d| d.error_type.as_str()),
+        detail.as_ref().map_or(body, |d| d.message.as_str()),
+    )
+}
+
+/// Message for a failed request, saying what to do about it
+fn api_error(status: reqwest::StatusCode, body: &str) -> String {
+    let message = match error_detail(body) {
+        Some(detail) => detail.message,
+        None => format!("{} {}", status, body.trim()),
+    };
+    match classify_response(status, body) {
+        Failure::Auth => format!(
+            "Authentication failed: {}. Check the API key in the header",
+            message
+        ),
+        Failure::Quota => format!(
+            "Quota exhausted: {}. Add credits or raise the usage limit; retrying will not help",
+            message
+        ),
+        Failure::RateLimited => format!("Rate limited: {}. Try again in a minute", message),
+        Failure::Unavailable => format!("API unavailable: {}. Try again later", message),
+        Failure::Other => format!("API Error: {}", message),
+    }
+}
+
+/// Wait before retry `attempt` (from 1): what `Retry-After` asks for in
+/// seconds, else 1s, 2s, 4s...
+fn retry_delay(attempt: u32, retry_after: Option<&str>) -> std::time::Duration {
+    let secs = retry_after
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(|secs| secs.ceil() as u64)
+        .unwrap_or(1 << attempt.saturating_sub(1).min(6));
+    std::time::Duration::from_secs(secs.min(MAX_RETRY_DELAY_SECS))
+}
+
+/// Send the request `build` makes, sending it again after rate limits and
+/// server errors. Returns the status and body of the last response.
+async fn send_with_retry(
+    shared: &ChatShared,
+    build: impl Fn() -> reqwest::RequestBuilder,
+) -> Result<(reqwest::StatusCode, String), reqwest::Error> {
+    let mut span = instrument::client_span("llm.request");
+    span.attr("gen_ai.system", shared.backend().label());
+    span.attr("gen_ai.request.model", shared.model());
+    let mut attempt = 0;
+    loop {
+        let response = build().send().await;
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                shared.retry_notice.lock().unwrap().take();
+                span.fail(&e.to_string());
+                return Err(e);
+            }
+        };
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = response.text().await.unwrap_or_default();
+
+        let failure = (!status.is_success()).then(|| classify_response(status, &body));
+        if attempt < MAX_RETRIES && failure.is_some_and(Failure::is_retryable) {
+            attempt += 1;
+            let delay = retry_delay(attempt, retry_after.as_deref());
+            let reason = match failure {
+                Some(Failure::RateLimited) => "chat.rate_limited",
+                _ => "chat.api_unavailable",
+            };
+            let notice = i18n::tr_args(
+                "chat.retry_notice",
+                &[
+                    ("reason", &i18n::tr(reason)),
+                    ("seconds", &delay.as_secs().to_string()),
+                    ("attempt", &attempt.to_string()),
+                    ("max", &MAX_RETRIES.to_string()),
+                ],
+            );
+            tracing::warn!(target: "API", "{}: {}", status, notice);
+            *shared.retry_notice.lock().unwrap() = Some(notice);
+            if retry_sleep(delay).await {
+                continue;
+            }
+        }
+        shared.retry_notice.lock().unwrap().take();
+        span.attr("http.response.status_code", status.as_u16() as i64);
+        span.attr("http.request.resend_count", attempt as i64);
+        if !status.is_success() {
+            span.fail(&api_error(status, &body));
+        }
+        return Ok((status, body));
+    }
+}
+
+/// Wait `delay`; false where there is no timer to wait on (WASM)
+async fn retry_sleep(delay: std::time::Duration) -> bool {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        tokio::time::sleep(delay).await;
+        true
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        let _ = delay;
+        false
+    }
 }
 
 // ============================================================================
@@ -266,11 +951,28 @@ struct ClaudeErrorDetail {
 // ============================================================================
 
 #[cfg(target_arch = "wasm32")]
-const WASM_SYSTEM_PROMPT: &str = "You are Dora Studio Assistant. Be extremely concise. Short answers only. No unnecessary explanations. Note: Tools unavailable in web version - use desktop app for full features.";
+const WASM_TOOLS_NOTE: &str =
+    "Note: Tools unavailable in web version - use desktop app for full features.";
 
 #[cfg(target_arch = "wasm32")]
-async fn call_claude_api_simple(messages: Vec<ChatMessage>) -> ChatResponse {
-    let api_key = get_api_key();
+async fn call_claude_api_simple(
+    shared: &ChatShared,
+    messages: Vec<ChatMessage>,
+    system: &str,
+) -> ChatResponse {
+    if shared.backend() == LlmBackend::Local {
+        let messages = local_messages(&format!("{}\n\n{}", system, WASM_TOOLS_NOTE), &messages);
+        return match call_local(shared, &messages, &[], 4096).await {
+            Ok(LocalMessage {
+                content: Some(text),
+                ..
+            }) if !text.trim().is_empty() => ChatResponse::Message(text),
+            Ok(_) => ChatResponse::Error("Empty response from the local model".to_string()),
+            Err(e) => ChatResponse::Error(e),
+        };
+    }
+
+    let api_key = shared.api_key();
 
     if api_key.is_empty() {
         return ChatResponse::Error("Please enter your Claude API key in the header".to_string());
@@ -293,26 +995,24 @@ async fn call_claude_api_simple(messages: Vec<ChatMessage>) -> ChatResponse {
         .collect();
 
     let request = serde_json::json!({
-        "model": "claude-sonnet-4-20250514",
+        "model": shared.model(),
         "max_tokens": 4096,
-        "system": WASM_SYSTEM_PROMPT,
+        "system": format!("{}\n\n{}", system, WASM_TOOLS_NOTE),
         "messages": claude_messages
     });
 
-    let result = client
-        .post("https://api.anthropic.com/v1/messages")
-        .header("Content-Type", "application/json")
-        .header("x-api-key", &api_key)
-        .header("anthropic-version", "2023-06-01")
-        .json(&request)
-        .send()
-        .await;
+    let result = send_with_retry(shared, || {
+        client
+            .post("https://api.anthropic.com/v1/messages")
+            .header("Content-Type", "application/json")
+            .header("x-api-key", &api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&request)
+    })
+    .await;
 
     match result {
-        Ok(response) => {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-
+        Ok((status, body)) => {
             if status.is_success() {
                 match serde_json::from_str::<ClaudeResponse>(&body) {
                     Ok(claude_response) => {
@@ -332,42 +1032,152 @@ async fn call_claude_api_simple(messages: Vec<ChatMessage>) -> ChatResponse {
                     Err(e) => ChatResponse::Error(format!("Failed to parse response: {}", e)),
                 }
             } else {
-                match serde_json::from_str::<ClaudeErrorResponse>(&body) {
-                    Ok(error_response) => {
-                        ChatResponse::Error(format!("API Error: {}", error_response.error.message))
-                    }
-                    Err(_) => ChatResponse::Error(format!("API Error ({}): {}", status, body)),
-                }
+                ChatResponse::Error(api_error(status, &body))
             }
         }
         Err(e) => ChatResponse::Error(format!("Network error: {}", e)),
     }
 }
 
+// ============================================================================
+// Conversation Summaries
+// ============================================================================
+
+/// Summarize conversation turns with a plain (tool-free) request
+async fn call_claude_summary(shared: &ChatShared, prompt: String) -> Result<String, String> {
+    if shared.backend() == LlmBackend::Local {
+        let messages = local_messages(
+            SUMMARY_SYSTEM_PROMPT,
+            &[ChatMessage {
+                role: MessageRole::User,
+                content: prompt,
+                model: None,
+                tool_steps: Vec::new(),
+            }],
+        );
+        let reply = call_local(shared, &messages, &[], 1024).await?;
+        return match reply.content {
+            Some(text) if !text.trim().is_empty() => Ok(text),
+            _ => Err("Empty summary".to_string()),
+        };
+    }
+
+    let api_key = shared.api_key();
+    if api_key.is_empty() {
+        return Err("No API key".to_string());
+    }
+
+    let request = serde_json::json!({
+        "model": shared.model(),
+        "max_tokens": 1024,
+        "system": SUMMARY_SYSTEM_PROMPT,
+        "messages": [{ "role": "user", "content": prompt }]
+    });
+
+    let client = reqwest::Client::new();
+    let (status, body) = send_with_retry(shared, || {
+        client
+            .post("https://api.anthropic.com/v1/messages")
+            .header("Content-Type", "application/json")
+            .header("x-api-key", &api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&request)
+    })
+    .await
+    .map_err(|e| format!("Network error: {}", e))?;
+    if !status.is_success() {
+        return Err(api_error(status, &body));
+    }
+
+    let claude_response: ClaudeResponse =
+        serde_json::from_str(&body).map_err(|e| format!("Failed to parse response: {}", e))?;
+    let text = claude_response
+        .content
+        .iter()
+        .filter_map(|c| c.text.as_deref())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.trim().is_empty() {
+        Err("Empty summary".to_string())
+    } else {
+        Ok(text)
+    }
+}
+
+// ============================================================================
+// Model List
+// ============================================================================
+
+#[derive(Deserialize)]
+struct ModelList {
+    data: Vec<ModelInfo>,
+}
+
+#[derive(Deserialize)]
+struct ModelInfo {
+    id: String,
+}
+
+/// Model ids from `GET /v1/models`, newest first
+async fn list_models(shared: &ChatShared) -> Result<Vec<String>, String> {
+    if shared.backend() == LlmBackend::Local {
+        let client = reqwest::Client::new();
+        let (status, body) =
+            send_with_retry(shared, || client.get(shared.local_endpoint("models")))
+                .await
+                .map_err(|e| format!("Network error: {}", e))?;
+        if !status.is_success() {
+            return Err(api_error(status, &body));
+        }
+        return parse_model_list(&body);
+    }
+
+    let api_key = shared.api_key();
+    if api_key.is_empty() {
+        return Err("No API key".to_string());
+    }
+
+    let client = reqwest::Client::new();
+    let (status, body) = send_with_retry(shared, || {
+        client
+            .get("https://api.anthropic.com/v1/models?limit=100")
+            .header("x-api-key", &api_key)
+            .header("anthropic-version", "2023-06-01")
+    })
+    .await
+    .map_err(|e| format!("Network error: {}", e))?;
+    if !status.is_success() {
+        return Err(api_error(status, &body));
+    }
+    parse_model_list(&body)
+}
+
+fn parse_model_list(body: &str) -> Result<Vec<String>, String> {
+    let list: ModelList =
+        serde_json::from_str(body).map_err(|e| format!("Failed to parse model list: {}", e))?;
+    Ok(list.data.into_iter().map(|m| m.id).collect())
+}
+
 // ============================================================================
 // Native API Call Implementation with Tool Loop
 // ============================================================================
 
 /// Call Claude API with tools support - implements the agentic loop
 #[cfg(not(target_arch = "wasm32"))]
-async fn call_claude_api_with_tools(messages: Vec<ChatMessage>) -> ChatResponse {
-    let api_key = get_api_key();
+async fn call_claude_api_with_tools(
+    shared: &ChatShared,
+    messages: Vec<ChatMessage>,
+    system: &str,
+) -> ChatResponse {
+    let api_key = shared.api_key();
 
     if api_key.is_empty() {
         return ChatResponse::Error("Please enter your Claude API key in the header".to_string());
     }
 
     let client = reqwest::Client::new();
-
-    // Convert tools to Claude format
-    let tools: Vec<ClaudeTool> = get_dora_tools()
-        .into_iter()
-        .map(|t| ClaudeTool {
-            name: t.name,
-            description: t.description,
-            input_schema: t.input_schema,
-        })
-        .collect();
+    let saved_queries = shared.saved_trace_queries.lock().unwrap().clone();
+    let tools = agent_tools(&saved_queries);
 
     // Convert initial messages to Claude format
     let mut claude_messages: Vec<ClaudeMessage> = messages
@@ -384,49 +1194,41 @@ async fn call_claude_api_with_tools(messages: Vec<ChatMessage>) -> ChatResponse
     // Collect all text responses and tool executions
     let mut final_response = String::new();
     let mut iteration = 0;
-    const MAX_ITERATIONS: u32 = 10; // Prevent infinite loops
 
     loop {
         iteration += 1;
-        eprintln!("[API] Iteration {}", iteration);
-        if iteration > MAX_ITERATIONS {
+        tracing::debug!(target: "API", "Iteration {}", iteration);
+        if iteration > MAX_TOOL_ITERATIONS {
             final_response.push_str("\n\n[Reached maximum tool iterations]");
             break;
         }
 
         let request = ClaudeRequest {
-            model: "claude-sonnet-4-20250514".to_string(),
+            model: shared.model(),
             max_tokens: 4096,
-            system: SYSTEM_PROMPT.to_string(),
+            system: format!("{}\n\n{}", system, TOOLS_PROMPT),
             messages: claude_messages.clone(),
             tools: tools.clone(),
         };
 
-        eprintln!("[API] Sending HTTP request...");
-        let result = client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("Content-Type", "application/json")
-            .header("x-api-key", &api_key)
-            .header("anthropic-version", "2023-06-01")
-            .json(&request)
-            .send()
-            .await;
+        tracing::debug!(target: "API", "Sending HTTP request...");
+        let result = send_with_retry(shared, || {
+            client
+                .post("https://api.anthropic.com/v1/messages")
+                .header("Content-Type", "application/json")
+                .header("x-api-key", &api_key)
+                .header("anthropic-version", "2023-06-01")
+                .json(&request)
+        })
+        .await;
 
-        let response = match result {
-            Ok(resp) => resp,
+        let (status, body) = match result {
+            Ok(response) => response,
             Err(e) => return ChatResponse::Error(format!("Network error: {}", e)),
         };
 
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-
         if !status.is_success() {
-            return match serde_json::from_str::<ClaudeErrorResponse>(&body) {
-                Ok(error_response) => {
-                    ChatResponse::Error(format!("API Error: {}", error_response.error.message))
-                }
-                Err(_) => ChatResponse::Error(format!("API Error ({}): {}", status, body)),
-            };
+            return ChatResponse::Error(api_error(status, &body));
         }
 
         let claude_response: ClaudeResponse = match serde_json::from_str(&body) {
@@ -507,27 +1309,7 @@ async fn call_claude_api_with_tools(messages: Vec<ChatMessage>) -> ChatResponse
             let mut tool_results: Vec<ContentBlock> = Vec::new();
 
             for (id, name, input) in &tool_uses {
-                // Add tool execution info to response
-                if !final_response.is_empty() {
-                    final_response.push_str("\n\n");
-                }
-                final_response.push_str(&format!("🔧 Executing: {}", name));
-
-                let result = execute_tool(name, id, input);
-
-                // Show result preview in final response
-                let preview = if result.content.len() > 200 {
-                    format!("{}...", &result.content[..200])
-                } else {
-                    result.content.clone()
-                };
-
-                if result.is_error {
-                    final_response.push_str(&format!("\n❌ Error: {}", preview));
-                } else {
-                    final_response.push_str(&format!("\n✅ Result: {}", preview));
-                }
-
+                let result = run_agent_tool(shared, id, name, input, &saved_queries).await;
                 tool_results.push(ContentBlock::ToolResult {
                     tool_use_id: result.tool_use_id,
                     content: result.content,
@@ -546,10 +1328,379 @@ async fn call_claude_api_with_tools(messages: Vec<ChatMessage>) -> ChatResponse
         }
     }
 
-    if final_response.is_empty() {
-        ChatResponse::Error("Empty response from Claude".to_string())
+    finish_reply(shared, final_response, "Empty response from Claude")
+}
+
+/// Send the chat to the configured backend (native)
+#[cfg(not(target_arch = "wasm32"))]
+async fn call_chat_api(
+    shared: &ChatShared,
+    messages: Vec<ChatMessage>,
+    system: &str,
+) -> ChatResponse {
+    match shared.backend() {
+        LlmBackend::Anthropic => call_claude_api_with_tools(shared, messages, system).await,
+        LlmBackend::Local => call_local_api_with_tools(shared, messages, system).await,
+    }
+}
+
+/// Dora tools and the built-in ones, in Claude's format
+#[cfg(not(target_arch = "wasm32"))]
+fn agent_tools(saved_queries: &[SavedTraceQuery]) -> Vec<ClaudeTool> {
+    let mut tools: Vec<ClaudeTool> = get_dora_tools()
+        .into_iter()
+        .map(|t| ClaudeTool {
+            name: t.name,
+            description: t.description,
+            input_schema: t.input_schema,
+        })
+        .collect();
+    tools.push(ClaudeTool {
+        name: TOOL_RESULT_TOOL.to_string(),
+        description: "Get the full output of an earlier tool call by its reference ID (e.g. T3)."
+            .to_string(),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "ref_id": {
+                    "type": "string",
+                    "description": "Reference ID of the tool result, e.g. T3"
+                }
+            },
+            "required": ["ref_id"]
+        }),
+    });
+    if !saved_queries.is_empty() {
+        tools.push(ClaudeTool {
+            name: SAVED_TRACE_QUERY_TOOL.to_string(),
+            description: format!(
+                "Run a trace query the user saved in the traces panel and list the slowest \
+                 matching spans. Saved queries:\n{}",
+                saved::describe_queries(saved_queries)
+            ),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Name of the saved query"
+                    }
+                },
+                "required": ["name"]
+            }),
+        });
+    }
+
+    tools
+}
+
+/// Run a tool the model asked for and record its result, showing its
+/// progress as a [`ToolStep`]
+#[cfg(not(target_arch = "wasm32"))]
+async fn run_agent_tool(
+    shared: &ChatShared,
+    id: &str,
+    name: &str,
+    input: &serde_json::Value,
+    saved_queries: &[SavedTraceQuery],
+) -> crate::tools::ToolResult {
+    let mut span = instrument::span(&format!("execute_tool {}", name));
+    span.attr("gen_ai.tool.name", name);
+    span.attr("gen_ai.tool.call.id", id);
+    let step = {
+        let mut steps = shared.tool_steps.lock().unwrap();
+        steps.push(ToolStep {
+            name: name.to_string(),
+            arguments: input.to_string(),
+            status: ToolStepStatus::Running,
+            ref_id: None,
+            summary: String::new(),
+            output: String::new(),
+        });
+        steps.len() - 1
+    };
+
+    let result = if name == TOOL_RESULT_TOOL {
+        lookup_tool_result(shared, id, input)
+    } else if name == SAVED_TRACE_QUERY_TOOL {
+        run_saved_trace_query(shared, id, input, saved_queries).await
+    } else {
+        execute_tool_in(&shared.tools, name, id, input)
+    };
+    let ref_id = shared.record_tool_result(name, &result.content, result.is_error);
+
+    let summary = match &result.payload {
+        Some(payload) => payload.summary(),
+        None => text::truncate(&result.content, 200),
+    };
+    let status = match (result.is_error, &result.error) {
+        (false, _) => ToolStepStatus::Ok,
+        (true, Some(ToolError::TimedOut { .. })) => ToolStepStatus::TimedOut,
+        (true, Some(ToolError::Cancelled)) => ToolStepStatus::Cancelled,
+        (true, _) => ToolStepStatus::Error,
+    };
+    if result.is_error {
+        span.fail(&summary);
+    }
+    if let Some(step) = shared.tool_steps.lock().unwrap().get_mut(step) {
+        step.status = status;
+        step.ref_id = Some(ref_id);
+        step.summary = summary;
+        step.output = text::truncate(&result.content, TOOL_OUTPUT_CHARS);
+    }
+    result
+}
+
+/// The reply, unless there is neither text nor a tool call to show
+#[cfg(not(target_arch = "wasm32"))]
+fn finish_reply(shared: &ChatShared, final_response: String, empty_error: &str) -> ChatResponse {
+    let final_response = final_response.trim_start();
+    if final_response.is_empty() && shared.tool_steps.lock().unwrap().is_empty() {
+        ChatResponse::Error(empty_error.to_string())
     } else {
-        ChatResponse::Message(final_response)
+        ChatResponse::Message(final_response.to_string())
+    }
+}
+
+// ============================================================================
+// Local Inference Server (OpenAI-compatible)
+// ============================================================================
+
+#[derive(Deserialize)]
+struct LocalResponse {
+    choices: Vec<LocalChoice>,
+}
+
+#[derive(Deserialize)]
+struct LocalChoice {
+    message: LocalMessage,
+}
+
+/// An assistant message from the local server
+#[derive(Deserialize, Debug, Default)]
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+struct LocalMessage {
+    #[serde(default)]
+    content: Option<String>,
+    /// Servers send `null` as well as leaving it out
+    #[serde(default)]
+    tool_calls: Option<Vec<LocalToolCall>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LocalToolCall {
+    #[serde(default)]
+    id: String,
+    #[serde(rename = "type", default = "function_type")]
+    call_type: String,
+    function: LocalFunctionCall,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LocalFunctionCall {
+    name: String,
+    /// JSON-encoded arguments
+    #[serde(default)]
+    arguments: String,
+}
+
+fn function_type() -> String {
+    "function".to_string()
+}
+
+/// `system` then `messages`, in the chat completions format
+fn local_messages(system: &str, messages: &[ChatMessage]) -> Vec<serde_json::Value> {
+    std::iter::once(serde_json::json!({ "role": "system", "content": system }))
+        .chain(messages.iter().map(|m| {
+            serde_json::json!({
+                "role": match m.role {
+                    MessageRole::User => "user",
+                    MessageRole::Assistant => "assistant",
+                },
+                "content": m.content
+            })
+        }))
+        .collect()
+}
+
+fn parse_local_response(body: &str) -> Result<LocalMessage, String> {
+    let response: LocalResponse = serde_json::from_str(body)
+        .map_err(|e| format!("Failed to parse response: {}\nBody: {}", e, body))?;
+    response
+        .choices
+        .into_iter()
+        .next()
+        .map(|choice| choice.message)
+        .ok_or_else(|| "Empty response from the local model".to_string())
+}
+
+/// One `POST /v1/chat/completions` to the local server
+async fn call_local(
+    shared: &ChatShared,
+    messages: &[serde_json::Value],
+    tools: &[serde_json::Value],
+    max_tokens: u32,
+) -> Result<LocalMessage, String> {
+    let mut request = serde_json::json!({
+        "model": shared.model(),
+        "max_tokens": max_tokens,
+        "messages": messages
+    });
+    if !tools.is_empty() {
+        request["tools"] = serde_json::json!(tools);
+    }
+
+    let client = reqwest::Client::new();
+    let (status, body) = send_with_retry(shared, || {
+        client
+            .post(shared.local_endpoint("chat/completions"))
+            .json(&request)
+    })
+    .await
+    .map_err(|e| format!("Network error (is the local server running?): {}", e))?;
+    if !status.is_success() {
+        return Err(api_error(status, &body));
+    }
+    parse_local_response(&body)
+}
+
+/// The agentic loop against the local server, with the same tools as Claude
+#[cfg(not(target_arch = "wasm32"))]
+async fn call_local_api_with_tools(
+    shared: &ChatShared,
+    messages: Vec<ChatMessage>,
+    system: &str,
+) -> ChatResponse {
+    let saved_queries = shared.saved_trace_queries.lock().unwrap().clone();
+    let tools: Vec<serde_json::Value> = agent_tools(&saved_queries)
+        .into_iter()
+        .map(|t| {
+            serde_json::json!({
+                "type": "function",
+                "function": {
+                    "name": t.name,
+                    "description": t.description,
+                    "parameters": t.input_schema
+                }
+            })
+        })
+        .collect();
+    let mut conversation = local_messages(&format!("{}\n\n{}", system, TOOLS_PROMPT), &messages);
+    let mut final_response = String::new();
+
+    for iteration in 1..=MAX_TOOL_ITERATIONS {
+        tracing::debug!(target: "API", "Local iteration {}", iteration);
+        let reply = match call_local(shared, &conversation, &tools, 4096).await {
+            Ok(reply) => reply,
+            Err(e) => return ChatResponse::Error(e),
+        };
+        if let Some(text) = reply.content.as_deref().filter(|t| !t.trim().is_empty()) {
+            if !final_response.is_empty() {
+                final_response.push_str("\n\n");
+            }
+            final_response.push_str(text);
+        }
+        let mut calls = reply.tool_calls.unwrap_or_default();
+        if calls.is_empty() {
+            return finish_reply(
+                shared,
+                final_response,
+                "Empty response from the local model",
+            );
+        }
+        for (index, call) in calls.iter_mut().enumerate() {
+            if call.id.is_empty() {
+                call.id = format!("call_{}_{}", iteration, index);
+            }
+        }
+        conversation.push(serde_json::json!({
+            "role": "assistant",
+            "content": reply.content,
+            "tool_calls": calls
+        }));
+
+        for call in &calls {
+            // Small models sometimes send arguments that are not JSON
+            let input = serde_json::from_str(&call.function.arguments)
+                .unwrap_or_else(|_| serde_json::json!({}));
+            let result = run_agent_tool(
+                shared,
+                &call.id,
+                &call.function.name,
+                &input,
+                &saved_queries,
+            )
+            .await;
+            conversation.push(serde_json::json!({
+                "role": "tool",
+                "tool_call_id": call.id,
+                "content": result.content
+            }));
+        }
+    }
+
+    final_response.push_str("\n\n[Reached maximum tool iterations]");
+    finish_reply(
+        shared,
+        final_response,
+        "Empty response from the local model",
+    )
+}
+
+/// Execute the built-in `get_tool_result` tool
+#[cfg(not(target_arch = "wasm32"))]
+fn lookup_tool_result(
+    shared: &ChatShared,
+    tool_use_id: &str,
+    input: &serde_json::Value,
+) -> crate::tools::ToolResult {
+    let ref_id = input.get("ref_id").and_then(|v| v.as_str()).unwrap_or("");
+    let (content, is_error) = match shared.tool_record(ref_id) {
+        Some(record) => (
+            format!("[{}] {}: {}", record.id, record.name, record.content),
+            false,
+        ),
+        None => (
+            format!("No tool result with reference ID '{}'", ref_id),
+            true,
+        ),
+    };
+    crate::tools::ToolResult {
+        tool_use_id: tool_use_id.to_string(),
+        error: is_error.then(|| content.clone().into()),
+        content,
+        is_error,
+        payload: None,
+    }
+}
+
+/// Execute the built-in `run_saved_trace_query` tool
+#[cfg(not(target_arch = "wasm32"))]
+async fn run_saved_trace_query(
+    shared: &ChatShared,
+    tool_use_id: &str,
+    input: &serde_json::Value,
+    saved_queries: &[SavedTraceQuery],
+) -> crate::tools::ToolResult {
+    let name = input.get("name").and_then(|v| v.as_str()).unwrap_or("");
+    let result = match (saved::find_query(saved_queries, name), &shared.telemetry) {
+        (None, _) => Err(format!("No saved trace query named '{}'", name)),
+        (Some(_), None) => Err("No telemetry backend to query".to_string()),
+        (Some(query), Some(telemetry)) => telemetry
+            .query_traces(query.filter.to_query(logging::now_ms()))
+            .await
+            .map(|spans| saved::describe_results(query, &spans)),
+    };
+    let (content, is_error) = match result {
+        Ok(content) => (content, false),
+        Err(e) => (e, true),
+    };
+    crate::tools::ToolResult {
+        tool_use_id: tool_use_id.to_string(),
+        error: is_error.then(|| content.clone().into()),
+        content,
+        is_error,
+        payload: None,
     }
 }
 
@@ -566,6 +1717,8 @@ mod tests {
         let msg = ChatMessage {
             role: MessageRole::User,
             content: "Hello, Claude!".to_string(),
+            model: None,
+            tool_steps: Vec::new(),
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("\"role\":\"user\""));
@@ -578,6 +1731,47 @@ mod tests {
         let msg: ChatMessage = serde_json::from_str(json).unwrap();
         assert_eq!(msg.role, MessageRole::Assistant);
         assert_eq!(msg.content, "Hello!");
+        assert_eq!(msg.model, None);
+        assert!(msg.tool_steps.is_empty());
+    }
+
+    #[test]
+    fn test_tool_step_text() {
+        let mut step = ToolStep {
+            name: "dora_logs".to_string(),
+            arguments: r#"{"dataflow":"camera","lines":200}"#.to_string(),
+            status: ToolStepStatus::Running,
+            ref_id: None,
+            summary: String::new(),
+            output: String::new(),
+        };
+        assert_eq!(
+            step.title(),
+            "Tool: dora_logs (dataflow: camera, lines: 200)"
+        );
+        assert_eq!(step.status_text(), "running…");
+
+        step.status = ToolStepStatus::Ok;
+        step.ref_id = Some("T2".to_string());
+        step.summary = "40 lines".to_string();
+        assert_eq!(step.status_text(), "✅ T2");
+        assert_eq!(
+            step.context_line(),
+            "🔧 Executing: dora_logs\n✅ Result [T2]: 40 lines"
+        );
+
+        let msg = ChatMessage {
+            role: MessageRole::Assistant,
+            content: "Camera crashed [T2]".to_string(),
+            model: None,
+            tool_steps: vec![step],
+        };
+        assert_eq!(
+            msg.context_text(),
+            "🔧 Executing: dora_logs\n✅ Result [T2]: 40 lines\n\nCamera crashed [T2]"
+        );
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"status\":\"ok\""));
     }
 
     #[test]
@@ -602,26 +1796,72 @@ mod tests {
 
     #[test]
     fn test_set_and_get_api_key() {
+        let client = ChatClient::start(None);
         let test_key = "sk-ant-test-key-12345";
-        set_api_key(test_key.to_string());
-        assert_eq!(get_api_key(), test_key);
-        // Reset for other tests
-        set_api_key(String::new());
+        client.set_api_key(test_key.to_string());
+        assert_eq!(client.api_key(), test_key);
+        // Clones share the settings
+        assert_eq!(client.clone().api_key(), test_key);
+        assert!(client.is_configured());
     }
 
     #[test]
     fn test_empty_api_key() {
-        set_api_key(String::new());
-        assert!(get_api_key().is_empty());
+        let client = ChatClient::start(None);
+        assert!(client.api_key().is_empty());
+        assert!(!client.is_configured());
+        client.set_backend(LlmBackend::Local, "");
+        assert!(client.is_configured());
     }
 
     #[test]
     fn test_api_key_overwrites_previous() {
-        set_api_key("first-key".to_string());
-        set_api_key("second-key".to_string());
-        assert_eq!(get_api_key(), "second-key");
-        // Reset for other tests
-        set_api_key(String::new());
+        let client = ChatClient::start(None);
+        client.set_api_key("first-key".to_string());
+        client.set_api_key("second-key".to_string());
+        assert_eq!(client.api_key(), "second-key");
+    }
+
+    #[test]
+    fn test_model_defaults() {
+        let client = ChatClient::start(None);
+        assert_eq!(client.model(), DEFAULT_MODEL);
+        client.set_model(" claude-opus-4-20250514 ");
+        assert_eq!(client.model(), "claude-opus-4-20250514");
+        client.set_model("");
+        assert_eq!(client.model(), DEFAULT_MODEL);
+    }
+
+    // ============================================================================
+    // Tool Record Tests
+    // ============================================================================
+
+    #[test]
+    fn test_tool_records_are_addressable_by_reference() {
+        let shared = ChatShared::default();
+        let first = shared.record_tool_result("dora_list", "df-1 Running", false);
+        let second = shared.record_tool_result("dora_logs", "boom", true);
+        assert_eq!((first.as_str(), second.as_str()), ("T1", "T2"));
+
+        assert_eq!(shared.tool_record("[t2]").unwrap().content, "boom");
+        assert!(shared.tool_record("T9").is_none());
+        assert_eq!(shared.tool_records.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_tool_records_are_capped() {
+        let shared = ChatShared::default();
+        for i in 0..MAX_TOOL_RECORDS + 5 {
+            shared.record_tool_result("dora_list", &i.to_string(), false);
+        }
+        let records = shared.tool_records.lock().unwrap().clone();
+        assert_eq!(records.len(), MAX_TOOL_RECORDS);
+        assert_eq!(records[0].id, "T6");
+        assert!(shared.tool_record("T1").is_none());
+
+        // Numbering goes on from the newest, not the count
+        let next = shared.record_tool_result("dora_list", "", false);
+        assert_eq!(next, format!("T{}", MAX_TOOL_RECORDS + 6));
     }
 
     // ============================================================================
@@ -714,10 +1954,14 @@ mod tests {
             ChatMessage {
                 role: MessageRole::User,
                 content: "Hello".to_string(),
+                model: None,
+                tool_steps: Vec::new(),
             },
             ChatMessage {
                 role: MessageRole::Assistant,
                 content: "Hi there!".to_string(),
+                model: None,
+                tool_steps: Vec::new(),
             },
         ];
 
@@ -740,15 +1984,17 @@ mod tests {
     #[cfg(not(target_arch = "wasm32"))]
     #[tokio::test]
     async fn test_call_claude_api_without_key() {
-        // Ensure API key is empty
-        set_api_key(String::new());
+        // A fresh client has no API key
+        let shared = ChatShared::default();
 
         let messages = vec![ChatMessage {
             role: MessageRole::User,
             content: "Hello".to_string(),
+            model: None,
+            tool_steps: Vec::new(),
         }];
 
-        let response = call_claude_api_with_tools(messages).await;
+        let response = call_claude_api_with_tools(&shared, messages, "").await;
         match response {
             ChatResponse::Error(err) => {
                 assert!(err.contains("API key"));
@@ -758,20 +2004,138 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_model_list() {
+        let body = r#"{"data":[
+            {"type":"model","id":"claude-opus-4-20250514","display_name":"Claude Opus 4"},
+            {"type":"model","id":"claude-sonnet-4-20250514","display_name":"Claude Sonnet 4"}
+        ],"has_more":false}"#;
+        assert_eq!(
+            parse_model_list(body).unwrap(),
+            vec!["claude-opus-4-20250514", "claude-sonnet-4-20250514"]
+        );
+        assert!(parse_model_list("{}").is_err());
+    }
+
+    #[test]
+    fn test_classify_failures() {
+        let classify = |status: u16, body: &str| {
+            classify_response(reqwest::StatusCode::from_u16(status).unwrap(), body)
+        };
+        let body = |kind: &str, message: &str| {
+            format!(
+                r#"{{"type":"error","error":{{"type":"{}","message":"{}"}}}}"#,
+                kind, message
+            )
+        };
+        assert_eq!(
+            classify(401, &body("authentication_error", "invalid x-api-key")),
+            Failure::Auth
+        );
+        assert_eq!(
+            classify(
+                429,
+                &body("rate_limit_error", "Number of requests exceeded")
+            ),
+            Failure::RateLimited
+        );
+        assert_eq!(
+            classify(529, &body("overloaded_error", "Overloaded")),
+            Failure::Unavailable
+        );
+        assert_eq!(classify(502, "Bad Gateway"), Failure::Unavailable);
+        // Quota errors come as 400 from Claude and 429 from OpenAI-compatible
+        // servers, and are not retried
+        let credit = body("invalid_request_error", "Your credit balance is too low");
+        assert_eq!(classify(400, &credit), Failure::Quota);
+        assert_eq!(
+            classify(429, &body("insufficient_quota", "Over quota")),
+            Failure::Quota
+        );
+        assert!(!Failure::Quota.is_retryable());
+        assert_eq!(
+            classify(400, &body("invalid_request_error", "bad")),
+            Failure::Other
+        );
+
+        let status = reqwest::StatusCode::from_u16(400).unwrap();
+        assert!(api_error(status, &credit).starts_with("Quota exhausted: Your credit balance"));
+        assert_eq!(api_error(status, &body("x", "bad")), "API Error: bad");
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(1, None).as_secs(), 1);
+        assert_eq!(retry_delay(3, Some("junk")).as_secs(), 4);
+        assert_eq!(retry_delay(1, Some(" 12 ")).as_secs(), 12);
+        assert_eq!(retry_delay(1, Some("0.2")).as_secs(), 1);
+        assert_eq!(retry_delay(1, Some("3600")).as_secs(), MAX_RETRY_DELAY_SECS);
+    }
+
+    #[test]
+    fn test_local_endpoint() {
+        assert_eq!(
+            local_endpoint_of("", "models"),
+            "http://127.0.0.1:8080/v1/models"
+        );
+        assert_eq!(
+            local_endpoint_of(" http://robot:11434/v1/ ", "chat/completions"),
+            "http://robot:11434/v1/chat/completions"
+        );
+    }
+
+    #[test]
+    fn test_parse_local_response() {
+        let body = r#"{"choices":[{"index":0,"finish_reason":"tool_calls","message":{
+            "role":"assistant","content":null,
+            "tool_calls":[{"id":"c1","type":"function",
+                "function":{"name":"dora_list","arguments":"{}"}}]}}]}"#;
+        let message = parse_local_response(body).unwrap();
+        assert_eq!(message.content, None);
+        let calls = message.tool_calls.unwrap();
+        assert_eq!(calls[0].function.name, "dora_list");
+        assert_eq!(calls[0].call_type, "function");
+
+        // `tool_calls: null` is the same as none
+        let body = r#"{"choices":[{"message":{"content":"Hi","tool_calls":null}}]}"#;
+        let message = parse_local_response(body).unwrap();
+        assert_eq!(message.content.as_deref(), Some("Hi"));
+        assert!(message.tool_calls.is_none());
+        assert!(parse_local_response(r#"{"choices":[]}"#).is_err());
+
+        let messages = local_messages(
+            "Be brief",
+            &[ChatMessage {
+                role: MessageRole::User,
+                content: "hello".to_string(),
+                model: None,
+                tool_steps: Vec::new(),
+            }],
+        );
+        assert_eq!(messages[0]["role"], "system");
+        assert_eq!(messages[1]["content"], "hello");
+    }
+
     #[test]
     fn test_multiple_messages_conversion() {
         let conversation = vec![
             ChatMessage {
                 role: MessageRole::User,
                 content: "What is Rust?".to_string(),
+                model: None,
+                tool_steps: Vec::new(),
             },
             ChatMessage {
                 role: MessageRole::Assistant,
                 content: "Rust is a systems programming language.".to_string(),
+                model: None,
+                tool_steps: Vec::new(),
             },
             ChatMessage {
                 role: MessageRole::User,
                 content: "Tell me more.".to_string(),
+                model: None,
+                tool_steps: Vec::new(),
             },
         ];
 
@@ -1,34 +1,157 @@
-use crate::dataflow::{DataflowInfo, DataflowTableWidgetRefExt};
-use crate::tools::execute_tool;
+use crate::api::{ChatClient, LlmBackend};
+use crate::chat::chat_screen::ChatScreenWidgetRefExt;
+use crate::chat::prompts;
+use crate::config_bundle::{ConfigBundle, Resolution};
+use crate::dashboard;
+use crate::dataflow::connection::{error_summary, is_unreachable, RefreshBackoff};
+use crate::dataflow::golden_signals::GoldenSignals;
+use crate::dataflow::{
+    newly_failed, CoordinatorState, DataflowDetailAction, DataflowDetailWidgetRefExt, DataflowInfo,
+    DataflowTableWidgetRefExt, EnvEditorAction, EnvEditorWidgetRefExt, RunHistory, RunRecord,
+    StartSpec, UndoKind, UndoStack,
+};
+use crate::datetime;
+use crate::diagnostics::{DebugConsoleWidgetRefExt, DiagnosticsPanelWidgetRefExt};
+use crate::i18n::{self, Locale};
+use crate::layout::{self, LayoutPreset, Session, WindowGeometry};
+use crate::logging;
+use crate::notifications::{
+    NotificationCenter, NotificationDrawerWidgetRefExt, NotificationToastsWidgetRefExt, Severity,
+};
+use crate::palette::{
+    self, Command, CommandPaletteAction, CommandPaletteWidgetRefExt, PaletteEntry,
+};
+use crate::settings::{self, Settings};
+use crate::shortcuts::{self, KeyChord, Keymap, ShortcutAction};
+use crate::snapshot::Envelope;
+use crate::status_bar::{LinkTiming, StatusBarWidgetRefExt};
+use crate::text;
+use crate::traces::TracesPanelWidgetRefExt;
 use makepad_widgets::*;
+use std::collections::{HashMap, HashSet};
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 #[cfg(not(target_arch = "wasm32"))]
-use crate::otlp::bridge;
+use crate::alerts::{self, AlertEngine, AlertRule, AlertState, AlertsPanelWidgetRefExt};
 #[cfg(not(target_arch = "wasm32"))]
-use crate::traces::TracesPanelWidgetRefExt;
+use crate::crash::{self, CrashDialogAction, CrashDialogWidgetRefExt};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::dataflow::client::CliClient;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::dataflow::env;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::dataflow::liveness::{LivenessTracker, ACTIVE_CPU_PERCENT};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::dataflow::{
+    is_dataflow_file, DoraClient, NodeMetricsPanelWidgetRefExt, StartDialogAction,
+    StartDialogWidgetRefExt,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::deep_link::{self, DeepLink};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::desktop_notify;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::instrument::{self, SpanKind};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::metrics::{self, MetricsHistory};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::monitors;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::otlp::bridge::{ConnectionStatus, SignozBridge};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::query::query_panel::MAX_COLUMNS;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::query::QueryPanelWidgetRefExt;
+#[cfg(target_arch = "wasm32")]
+use crate::remote::{self, RemoteRequest, RemoteResponse};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::scaffold::{self, NewProjectAction, NewProjectWizardWidgetRefExt, ProjectSpec};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::serve::events::{self, EventWatcher, StudioEvent};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::serve::websocket;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::status_bar::offline_text;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::storage::retention::{self, PruneSummary};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::storage::{
+    run_query, QueryResult, Storage, StoredSpans, LOGS_TABLE, METRICS_TABLE, SPANS_TABLE,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::tasks::{self, TaskContext, TaskId, TaskManager, TaskOutcome};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::tools::payload::{BuildDiagnostic, CheckDiagnostic};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::tools::version::{self, DoraVersion, MIN_DORA_VERSION};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::tools::{
+    self, dora_cli, execute_tool, platform, process, Limits, ToolPayload, ToolResult,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::watcher;
 
 // Auto-refresh interval in seconds
 const AUTO_REFRESH_INTERVAL: f64 = 5.0;
+// How often the traces panel polls for new spans in live mode, in seconds
+const LIVE_TAIL_INTERVAL: f64 = 2.0;
+// How far each live tail query reaches back before the newest span shown, for
+// spans in the same millisecond or stored late; those shown are dropped by ID
+const LIVE_TAIL_OVERLAP_MS: u64 = 5_000;
+// How long the window or a splitter must stay put before its size is saved
+const LAYOUT_SAVE_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+// Stored spans and node samples shown while a backend is unreachable
+const OFFLINE_SPANS: usize = 100;
+// Newest stored spans the traces filter is applied to, without a time range
+const OFFLINE_SCANNED_SPANS: usize = 5_000;
+const OFFLINE_METRIC_SAMPLES: usize = 2_000;
+// Characters of dora console output shown in the Diagnostics panel
+const CLI_OUTPUT_CHARS: usize = 4_000;
+// How often expired records are dropped from local storage
+const PRUNE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+// Problems listed in the notification when a build or dataflow check fails
+const DIAGNOSTICS_SHOWN: usize = 5;
+// Spans of a large trace result added to the traces panel per frame
+const SPANS_PER_FRAME: usize = 500;
 
 live_design! {
     use link::theme::*;
     use link::shaders::*;
     use link::widgets::*;
 
+    use crate::alerts::alerts_panel::AlertsPanel;
     use crate::chat::chat_screen::ChatScreen;
+    use crate::crash::crash_dialog::CrashDialog;
+    use crate::dataflow::dataflow_detail::DataflowDetail;
     use crate::dataflow::dataflow_table::DataflowTable;
+    use crate::dataflow::env_editor::EnvEditor;
+    use crate::dataflow::node_metrics_panel::NodeMetricsPanel;
+    use crate::dataflow::start_dialog::StartDialog;
+    use crate::diagnostics::debug_console::DebugConsole;
+    use crate::diagnostics::diagnostics_panel::DiagnosticsPanel;
+    use crate::notifications::drawer::NotificationDrawer;
+    use crate::notifications::toasts::NotificationToasts;
+    use crate::palette::command_palette::CommandPalette;
+    use crate::query::query_panel::QueryPanel;
+    use crate::scaffold::new_project_wizard::NewProjectWizard;
+    use crate::status_bar::StatusBar;
     use crate::traces::traces_panel::TracesPanel;
 
     // Colors
     SIDEBAR_BG = #1e293b
     MAIN_BG = #f8fafc
-    DIVIDER_COLOR = #e2e8f0
     HEADER_BG = #1e3a5f
     HEADER_TEXT = #ffffff
     TAB_ACTIVE_BG = #2d4a6f
     TAB_INACTIVE_BG = #1e3a5f
     CONNECTION_OK = #4ade80
     CONNECTION_ERR = #f87171
+    SNAP_BG = #ede9fe
+    SNAP_TEXT = #5b21b6
+    OFFLINE_BG = #e2e8f0
+    OFFLINE_TEXT = #334155
 
     App = {{App}} {
         ui: <Root> {
@@ -36,104 +159,367 @@ live_design! {
                 window: { title: "Dora Studio" }
                 body = <View> {
                     width: Fill, height: Fill
-                    flow: Down
-                    show_bg: true
-                    draw_bg: { color: (MAIN_BG) }
+                    flow: Overlay
 
-                    // Shared title bar with tabs
-                    <View> {
-                        width: Fill, height: 48
-                        flow: Right
+                    content = <View> {
+                        width: Fill, height: Fill
+                        flow: Down
                         show_bg: true
-                        draw_bg: { color: (HEADER_BG) }
-                        padding: { left: 16, right: 16 }
-                        align: { y: 0.5 }
-                        spacing: 8
+                        draw_bg: { color: (MAIN_BG) }
 
-                        <Label> {
-                            width: Fit, height: Fit
-                            draw_text: {
-                                color: (HEADER_TEXT),
-                                text_style: { font_size: 16.0 }
+                        // Shared title bar with tabs
+                        <View> {
+                            width: Fill, height: 48
+                            flow: Right
+                            show_bg: true
+                            draw_bg: { color: (HEADER_BG) }
+                            padding: { left: 16, right: 16 }
+                            align: { y: 0.5 }
+                            spacing: 8
+
+                            <Label> {
+                                width: Fit, height: Fit
+                                draw_text: {
+                                    color: (HEADER_TEXT),
+                                    text_style: { font_size: 16.0 }
+                                }
+                                text: "Dora Studio"
+                            }
+
+                            // Spacer between title and tabs
+                            <View> { width: 16, height: Fit }
+
+                            tab_dataflows = <Button> {
+                                width: 100, height: 32
+                                text: "Dataflows"
+                                draw_text: { text_style: { font_size: 12.0 } }
+                            }
+
+                            tab_traces = <Button> {
+                                width: 80, height: 32
+                                text: "Traces"
+                                draw_text: { text_style: { font_size: 12.0 } }
+                            }
+
+                            tab_alerts = <Button> {
+                                width: 80, height: 32
+                                text: "Alerts"
+                                draw_text: { text_style: { font_size: 12.0 } }
+                            }
+
+                            tab_diagnostics = <Button> {
+                                width: 100, height: 32
+                                text: "Diagnostics"
+                                draw_text: { text_style: { font_size: 12.0 } }
+                            }
+
+                            tab_query = <Button> {
+                                width: 80, height: 32
+                                text: "Query"
+                                draw_text: { text_style: { font_size: 12.0 } }
+                            }
+
+                            // Spacer to push right-side items
+                            <View> { width: Fill, height: Fit }
+
+                            new_project_button = <Button> {
+                                width: 60, height: 32
+                                text: "New"
+                                draw_text: { text_style: { font_size: 12.0 } }
+                            }
+
+                            start_path_input = <TextInput> {
+                                width: 220, height: 32
+                                empty_text: "path/to/dataflow.yml"
+                                draw_text: { text_style: { font_size: 11.0 } }
+                            }
+
+                            validate_button = <Button> {
+                                width: 80, height: 32
+                                text: "Validate"
+                                draw_text: { text_style: { font_size: 12.0 } }
+                            }
+
+                            start_button = <Button> {
+                                width: 70, height: 32
+                                text: "Start"
+                                draw_text: { text_style: { font_size: 12.0 } }
+                            }
+
+                            env_button = <Button> {
+                                width: 60, height: 32
+                                text: "Env"
+                                draw_text: { text_style: { font_size: 12.0 } }
+                            }
+
+                            recent_dropdown = <DropDown> {
+                                width: 100, height: 32
+                                labels: ["Recent"]
+                                draw_text: { text_style: { font_size: 11.0 } }
+                            }
+
+                            run_dropdown = <DropDown> {
+                                width: 120, height: 32
+                                labels: ["Runs"]
+                                draw_text: { text_style: { font_size: 11.0 } }
+                            }
+
+                            refresh_button = <Button> {
+                                width: 80, height: 32
+                                text: "Refresh"
+                                draw_text: { text_style: { font_size: 12.0 } }
+                            }
+
+                            notifications_button = <Button> {
+                                width: 110, height: 32
+                                text: "Notifications"
+                                draw_text: { text_style: { font_size: 11.0 } }
+                            }
+
+                            layout_dropdown = <DropDown> {
+                                width: 120, height: 32
+                                labels: ["Layout"]
+                                draw_text: { text_style: { font_size: 11.0 } }
+                            }
+                        }
+
+                        // Banner shown while `dora list` fails, e.g. no coordinator is reachable
+                        refresh_error_bar = <View> {
+                            width: Fill, height: 0
+                            flow: Right
+                            show_bg: true
+                            draw_bg: { color: (OFFLINE_BG) }
+                            padding: { left: 16, right: 16 }
+                            align: { y: 0.5 }
+                            spacing: 8
+
+                            refresh_error_label = <Label> {
+                                width: Fill, height: Fit
+                                draw_text: {
+                                    color: (OFFLINE_TEXT),
+                                    text_style: { font_size: 11.0 }
+                                }
+                                text: ""
+                            }
+
+                            retry_label = <Label> {
+                                width: Fit, height: Fit
+                                draw_text: {
+                                    color: (OFFLINE_TEXT),
+                                    text_style: { font_size: 10.0 }
+                                }
+                                text: ""
+                            }
+
+                            start_runtime_button = <Button> {
+                                width: 110, height: 28
+                                text: "Start runtime"
+                                draw_text: { text_style: { font_size: 11.0 } }
+                            }
+
+                            refresh_retry_button = <Button> {
+                                width: 80, height: 28
+                                text: "Retry"
+                                draw_text: { text_style: { font_size: 11.0 } }
                             }
-                            text: "Dora Studio"
                         }
 
-                        // Spacer between title and tabs
-                        <View> { width: 16, height: Fit }
+                        // Banner shown while telemetry is snapped to a run window
+                        snap_bar = <View> {
+                            width: Fill, height: 0
+                            flow: Right
+                            show_bg: true
+                            draw_bg: { color: (SNAP_BG) }
+                            padding: { left: 16, right: 16 }
+                            align: { y: 0.5 }
+                            spacing: 8
+
+                            snap_label = <Label> {
+                                width: Fit, height: Fit
+                                draw_text: {
+                                    color: (SNAP_TEXT),
+                                    text_style: { font_size: 11.0 }
+                                }
+                                text: ""
+                            }
+
+                            <View> { width: Fill, height: Fit }
 
-                        tab_dataflows = <Button> {
-                            width: 100, height: 32
-                            text: "Dataflows"
-                            draw_text: { text_style: { font_size: 12.0 } }
+                            snap_clear_button = <Button> {
+                                width: 80, height: 28
+                                text: "Clear"
+                                draw_text: { text_style: { font_size: 11.0 } }
+                            }
                         }
 
-                        tab_traces = <Button> {
-                            width: 80, height: 32
-                            text: "Traces"
-                            draw_text: { text_style: { font_size: 12.0 } }
+                        // Main panels above, chat below; drag the bar to resize
+                        main_splitter = <Splitter> {
+                            axis: Vertical
+                            align: FromB(300.0)
+
+                            // Panels container
+                            a: <View> {
+                                width: Fill, height: Fill
+                                flow: Down
+
+                                // Dataflow panel (visible by default)
+                                dataflow_view = <View> {
+                                    width: Fill, height: Fill
+                                    flow: Down
+                                    align: { x: 0.0, y: 0.0 }
+                                    padding: { top: 0, left: 16, right: 16, bottom: 16 }
+
+                                    dataflow_splitter = <Splitter> {
+                                        axis: Vertical
+                                        align: FromA(360.0)
+
+                                        a: <View> {
+                                            width: Fill, height: Fill
+                                            flow: Right
+
+                                            dataflow_table = <DataflowTable> {}
+                                            dataflow_detail = <DataflowDetail> {}
+                                            env_editor = <EnvEditor> {}
+                                        }
+
+                                        b: <View> {
+                                            width: Fill, height: Fill
+                                            flow: Down
+
+                                            node_metrics_panel = <NodeMetricsPanel> {}
+                                        }
+                                    }
+                                }
+
+                                // Traces panel (hidden by default)
+                                traces_view = <View> {
+                                    width: Fill, height: 0
+                                    flow: Down
+                                    align: { x: 0.0, y: 0.0 }
+                                    padding: { top: 0, left: 16, right: 16, bottom: 16 }
+
+                                    traces_panel = <TracesPanel> {}
+                                }
+
+                                // Alerts panel (hidden by default)
+                                alerts_view = <View> {
+                                    width: Fill, height: 0
+                                    flow: Down
+                                    align: { x: 0.0, y: 0.0 }
+                                    padding: { top: 0, left: 16, right: 16, bottom: 16 }
+
+                                    alerts_panel = <AlertsPanel> {}
+                                }
+
+                                // Diagnostics panel (hidden by default)
+                                diagnostics_view = <View> {
+                                    width: Fill, height: 0
+                                    flow: Down
+                                    align: { x: 0.0, y: 0.0 }
+                                    padding: { top: 0, left: 16, right: 16, bottom: 16 }
+
+                                    diagnostics_panel = <DiagnosticsPanel> {}
+                                }
+
+                                // SQL console over local storage (hidden by default)
+                                query_view = <View> {
+                                    width: Fill, height: 0
+                                    flow: Down
+                                    align: { x: 0.0, y: 0.0 }
+                                    padding: { top: 0, left: 16, right: 16, bottom: 16 }
+
+                                    query_panel = <QueryPanel> {}
+                                }
+                            }
+
+                            // Bottom panel - Chat
+                            b: <View> {
+                                width: Fill, height: Fill
+                                flow: Down
+                                show_bg: true
+                                draw_bg: { color: #ffffff }
+
+                                chat_screen = <ChatScreen> {}
+                            }
                         }
 
-                        // Spacer to push right-side items
-                        <View> { width: Fill, height: Fit }
+                        // Runtime and backend health, always shown
+                        status_bar = <StatusBar> {}
+                    }
+
+                    // Toasts and notification history drawer, layered over the content
+                    notification_layer = <View> {
+                        width: Fill, height: Fill
+                        flow: Right
+                        align: { x: 1.0, y: 0.0 }
+                        padding: { top: 48 }
 
-                        connection_label = <Label> {
+                        <View> {
                             width: Fit, height: Fit
-                            draw_text: {
-                                color: (HEADER_TEXT),
-                                text_style: { font_size: 11.0 }
-                            }
-                            text: ""
+                            padding: { top: 8, right: 16 }
+
+                            notification_toasts = <NotificationToasts> {}
                         }
 
-                        refresh_button = <Button> {
-                            width: 80, height: 32
-                            text: "Refresh"
-                            draw_text: { text_style: { font_size: 12.0 } }
+                        notification_drawer = <NotificationDrawer> {
+                            visible: false
                         }
                     }
 
-                    // Panels container
-                    <View> {
+                    // Debug console (Ctrl+Shift+D), docked above the status bar
+                    debug_console_layer = <View> {
                         width: Fill, height: Fill
                         flow: Down
+                        align: { y: 1.0 }
+                        padding: { bottom: 28 }
 
-                        // Dataflow panel (visible by default)
-                        dataflow_view = <View> {
-                            width: Fill, height: Fill
-                            flow: Down
-                            align: { x: 0.0, y: 0.0 }
-                            padding: { top: 0, left: 16, right: 16, bottom: 16 }
+                        debug_console = <DebugConsole> {
+                            visible: false
+                        }
+                    }
+
+                    // Command palette (Ctrl+K), drawn above everything else
+                    palette_layer = <View> {
+                        width: Fill, height: Fill
+                        align: { x: 0.5, y: 0.0 }
+                        padding: { top: 80 }
 
-                            dataflow_table = <DataflowTable> {}
+                        command_palette = <CommandPalette> {
+                            visible: false
                         }
+                    }
 
-                        // Traces panel (hidden by default)
-                        traces_view = <View> {
-                            width: Fill, height: 0
-                            flow: Down
-                            align: { x: 0.0, y: 0.0 }
-                            padding: { top: 0, left: 16, right: 16, bottom: 16 }
+                    // New project wizard, drawn above everything else
+                    wizard_layer = <View> {
+                        width: Fill, height: Fill
+                        align: { x: 0.5, y: 0.0 }
+                        padding: { top: 80 }
 
-                            traces_panel = <TracesPanel> {}
+                        new_project_wizard = <NewProjectWizard> {
+                            visible: false
                         }
                     }
 
-                    // Divider line
-                    <View> {
-                        width: Fill, height: 1
-                        show_bg: true
-                        draw_bg: { color: (DIVIDER_COLOR) }
+                    // Offered on launch after a crash, drawn above everything else
+                    crash_layer = <View> {
+                        width: Fill, height: Fill
+                        align: { x: 0.5, y: 0.0 }
+                        padding: { top: 80 }
+
+                        crash_dialog = <CrashDialog> {
+                            visible: false
+                        }
                     }
 
-                    // Bottom panel - Chat
-                    <View> {
-                        width: Fill, height: 300
-                        flow: Down
-                        show_bg: true
-                        draw_bg: { color: #ffffff }
+                    // Asks before starting a dataflow dropped onto the window
+                    start_layer = <View> {
+                        width: Fill, height: Fill
+                        align: { x: 0.5, y: 0.0 }
+                        padding: { top: 80 }
 
-                        <ChatScreen> {}
+                        start_dialog = <StartDialog> {
+                            visible: false
+                        }
                     }
                 }
             }
@@ -148,6 +534,37 @@ enum ActivePanel {
     #[default]
     Dataflows,
     Traces,
+    Alerts,
+    Diagnostics,
+    /// The diagnostics panel's settings, without its log
+    Settings,
+    Query,
+}
+
+impl ActivePanel {
+    /// Name used in layout presets.
+    fn as_str(&self) -> &'static str {
+        match self {
+            ActivePanel::Dataflows => "dataflows",
+            ActivePanel::Traces => "traces",
+            ActivePanel::Alerts => "alerts",
+            ActivePanel::Diagnostics => "diagnostics",
+            ActivePanel::Settings => "settings",
+            ActivePanel::Query => "query",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dataflows" => Some(ActivePanel::Dataflows),
+            "traces" => Some(ActivePanel::Traces),
+            "alerts" => Some(ActivePanel::Alerts),
+            "diagnostics" => Some(ActivePanel::Diagnostics),
+            "settings" => Some(ActivePanel::Settings),
+            "query" => Some(ActivePanel::Query),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Live, LiveHook)]
@@ -161,278 +578,4760 @@ pub struct App {
     #[rust]
     last_refresh_time: f64,
     #[rust]
+    last_tail_time: f64,
+    /// When `dora list` last succeeded, in ms since the epoch.
+    #[rust]
+    last_refreshed_ms: Option<u64>,
+    /// Round trip of the last `dora list`, unmeasured in the web build.
+    #[rust]
+    coordinator_latency_ms: Option<u64>,
+    #[rust]
     active_panel: ActivePanel,
+    /// Whether traces can be queried through the telemetry bridge.
     #[rust]
     signoz_available: bool,
+    /// The telemetry bridge, once started.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    telemetry: Option<SignozBridge>,
+    /// The chat agent, once started.
+    #[rust]
+    chat: Option<ChatClient>,
+    /// Report left by a crash on the previous run, while the dialog offers it.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    crash_report: Option<std::path::PathBuf>,
     #[rust]
     traces_loaded_once: bool,
+    #[rust]
+    settings: Settings,
+    #[rust]
+    undo_stack: UndoStack,
+    /// Notifications offering to restart a stopped or destroyed dataflow:
+    /// (notification id, dataflow uuid).
+    #[rust]
+    undo_notices: Vec<(u64, String)>,
+    /// Running dataflows started from a known YAML path: (uuid, path).
+    #[rust]
+    running_started: Vec<(String, String)>,
+    /// YAML path that changed on disk and awaits a rebuild decision, with
+    /// the id of the notification offering it.
+    #[rust]
+    pending_reload: Option<(u64, String)>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    metrics_history: MetricsHistory,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    storage: Option<Storage>,
+    /// Spans recently written to local storage, loaded on first use.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    stored_spans: Option<StoredSpans>,
+    /// When SigNoz last returned traces, in ms since the epoch.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    traces_live_ms: Option<u64>,
+    /// Rest of a large trace result, added to the traces panel a chunk per
+    /// frame so one result does not stall the UI.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    pending_spans: Vec<crate::otlp::types::Span>,
+    /// Trace a link asked for, focused once the traces it needs arrive.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    linked_trace: Option<String>,
+    /// Dataflow a link asked for, opened once the next list has it.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    linked_dataflow: Option<String>,
+    /// When the last pruning of local storage was started.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    pruned_at: Option<Instant>,
+    /// Running tasks and tools, and the worker requests in flight, last
+    /// shown in the status bar.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    task_status: (String, usize),
+    #[rust]
+    run_history: RunHistory,
+    /// Whether runs left open by the last session were checked against a
+    /// dataflow listing yet
+    #[rust]
+    run_history_reconciled: bool,
+    /// Run whose window traces and metrics are currently snapped to.
+    #[rust]
+    snapped_run: Option<RunRecord>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    snapped_metrics: MetricsHistory,
+    /// Dataflow names from the last refresh, keyed by UUID.
+    #[rust]
+    dataflow_names: HashMap<String, String>,
+    /// Dataflow statuses from the last successful refresh, keyed by UUID.
+    /// `None` until the first refresh, so pre-existing failures are not reported.
+    #[rust]
+    dataflow_statuses: Option<HashMap<String, String>>,
+    /// Error from the last `dora list`, used to report only new failures.
+    #[rust]
+    refresh_error: Option<String>,
+    /// Consecutive `dora list` failures, for the retry schedule and banner.
+    #[rust]
+    refresh_backoff: RefreshBackoff,
+    /// When to retry a failing `dora list`.
+    #[rust]
+    retry_at: Option<Instant>,
+    /// Countdown last shown in the refresh error banner.
+    #[rust]
+    retry_text: String,
+    /// Last SigNoz health state, used to report only transitions.
+    #[rust]
+    signoz_healthy: Option<bool>,
+    #[rust]
+    coordinator: CoordinatorState,
+    /// How dataflows are listed and controlled: the `dora` CLI, or a mock
+    /// in tests.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust(Arc::new(CliClient))]
+    dora: Arc<dyn DoraClient>,
+    /// Dataflow whose logs the web build fetches to summarize them.
+    #[cfg(target_arch = "wasm32")]
+    #[rust]
+    summarize_pending: Option<String>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    alert_rules: Vec<AlertRule>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    alert_engine: AlertEngine,
+    /// Publishes dataflow changes to event stream subscribers
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    event_watcher: Arc<Mutex<EventWatcher>>,
+    #[rust]
+    notifications: NotificationCenter,
+    #[rust]
+    drawer_open: bool,
+    #[rust]
+    debug_console_open: bool,
+    /// `logging::record_count()` when the debug console was last filled.
+    #[rust]
+    debug_console_count: Option<u64>,
+    /// Whether the window has focus; desktop notifications are sent only
+    /// while it does not.
+    #[rust(true)]
+    window_focused: bool,
+    /// Current window geometry and the monitor configuration it belongs to.
+    #[rust]
+    window_geometry: Option<(String, WindowGeometry)>,
+    /// Monitor DPI before the UI scale is applied, known after the first
+    /// window geometry event.
+    #[rust]
+    system_dpi: Option<f64>,
+    /// UI scale the window DPI currently includes, as a factor.
+    #[rust(1.0)]
+    ui_scale_applied: f64,
+    /// Set once the saved geometry for the monitors was looked up, after
+    /// the first window geometry event and monitor detection.
+    #[rust]
+    geometry_restored: bool,
+    /// When the window last moved or resized; saved once it settles.
+    #[rust]
+    geometry_changed_at: Option<Instant>,
+    /// When a splitter was last dragged; sizes are saved once it settles.
+    #[rust]
+    splits_changed_at: Option<Instant>,
+    /// Keyboard shortcuts with the settings overrides applied.
+    #[rust]
+    keymap: Keymap,
+    /// Telemetry heartbeat per running dataflow.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    liveness: LivenessTracker,
+    /// Running dataflows currently flagged as stale.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    stale_dataflows: HashSet<String>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    tasks: TaskManager,
+    /// What each running task's result is for.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[rust]
+    task_jobs: HashMap<TaskId, TaskJob>,
+}
+
+/// What a background task's result is for.
+#[cfg(not(target_arch = "wasm32"))]
+enum TaskJob {
+    ExportConfig,
+    /// Importing the capture at this path.
+    ImportRecording(String),
+    /// `dora up` from the refresh error banner.
+    StartRuntime,
+    RunQuery,
+    ExportQueryResults,
+    PruneStorage,
+    /// A command from the dora console.
+    DoraCli,
+    DetectDoraVersion,
+    ListDataflows,
+    /// Node IDs of this dataflow, for the detail panel.
+    ListNodes(String),
+    /// Logs of this dataflow, for the detail panel.
+    DetailLogs(String),
+    /// Logs of this dataflow, for the console.
+    ViewLogs(String),
+    /// Logs of this dataflow, for the chat agent to summarize.
+    SummarizeLogs(String),
+    /// `dora check` on this path, from the Validate button.
+    CheckDataflow(String),
+    /// `dora check`, then a start, of this path.
+    StartDataflow(String),
+    StopDataflow(String, UndoKind),
+    /// Stopping the dataflows started from this path to start it again.
+    StopForRestart(String),
+    /// `dora build` of this path, and what to do once it succeeds.
+    BuildDataflow(String, AfterBuild),
+    /// Spans of the alert rules' windows.
+    CheckAlerts,
+}
+
+/// What follows a successful `dora build` from the UI.
+#[cfg(not(target_arch = "wasm32"))]
+enum AfterBuild {
+    /// Stop the running instances and start the dataflow again.
+    Restart,
+    Start,
+}
+
+/// How a start from the UI ended.
+#[cfg(not(target_arch = "wasm32"))]
+enum StartOutcome {
+    /// `dora check` found problems, so nothing was started.
+    CheckFailed(Vec<CheckDiagnostic>),
+    Started(Result<Option<String>, String>),
 }
 
 impl LiveRegister for App {
     fn live_register(cx: &mut Cx) {
         crate::makepad_widgets::live_design(cx);
+        crate::chart::live_design(cx);
         crate::chat::live_design(cx);
         crate::dataflow::live_design(cx);
-        #[cfg(not(target_arch = "wasm32"))]
+        crate::diagnostics::live_design(cx);
+        crate::notifications::live_design(cx);
+        crate::palette::live_design(cx);
+        crate::status_bar::live_design(cx);
+        // Empty stand-ins on the web, see `web_stubs`
+        crate::alerts::live_design(cx);
+        crate::crash::live_design(cx);
+        crate::query::live_design(cx);
+        crate::scaffold::live_design(cx);
         crate::traces::live_design(cx);
         // Light theme
         cx.link(live_id!(theme), live_id!(theme_desktop_light));
     }
-}
+}
+
+impl MatchEvent for App {
+    fn handle_startup(&mut self, cx: &mut Cx) {
+        // Load persisted settings and start internal logging before the bridges
+        self.settings = Settings::load();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            tools::set_dora_path(self.settings.dora_path.as_deref());
+            if let Err(e) = tools::set_ssh_host(self.settings.ssh_host.as_deref()) {
+                tracing::warn!(target: "App", "Running dora locally: {}", e);
+            }
+            if let Err(e) = tools::set_coordinator_addr(self.settings.coordinator_addr.as_deref()) {
+                tracing::warn!(target: "App", "Using the default coordinator: {}", e);
+            }
+            self.ui
+                .diagnostics_panel(ids!(diagnostics_panel))
+                .set_dora_target(
+                    cx,
+                    self.settings.dora_path.as_deref().unwrap_or(""),
+                    self.settings.ssh_host.as_deref().unwrap_or(""),
+                );
+        }
+        logging::init(settings::log_dir(), self.settings.log_level);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            monitors::start_detection();
+            crash::install_hook();
+            if let Some(path) = crash::take_pending() {
+                tracing::warn!(
+                    target: "App",
+                    "Studio crashed last run, report at {}",
+                    path.display()
+                );
+                self.ui
+                    .crash_dialog(ids!(crash_dialog))
+                    .open(cx, &path.to_string_lossy());
+                self.crash_report = Some(path);
+            }
+        }
+        // The chat agent runs saved trace queries through the bridge
+        #[cfg(not(target_arch = "wasm32"))]
+        let telemetry = SignozBridge::start(self.settings.telemetry_backend.clone());
+        #[cfg(not(target_arch = "wasm32"))]
+        let chat = ChatClient::start(Some(telemetry.clone()));
+        #[cfg(target_arch = "wasm32")]
+        let chat = ChatClient::start();
+        chat.init_api_key_from_env();
+        self.ui
+            .chat_screen(ids!(chat_screen))
+            .set_client(cx, chat.clone());
+        self.chat = Some(chat);
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_level(cx, self.settings.log_level);
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_language(cx, self.settings.language);
+        self.set_locale(cx, self.settings.language);
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_ui_scale(cx, self.settings.ui_scale_percent);
+        datetime::set_display_zone(self.settings.time_zone);
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_time_zone(cx, self.settings.time_zone);
+        datetime::set_time_style(self.settings.time_style);
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_time_style(cx, self.settings.time_style);
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_desktop_notifications(cx, self.settings.desktop_notifications);
+        self.update_recent_dropdown(cx);
+        self.run_history = RunHistory::load();
+        self.update_run_dropdown(cx);
+        self.update_layout_dropdown(cx);
+        self.restore_splits(cx);
+        self.load_shortcuts(cx);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.ui
+            .node_metrics_panel(ids!(node_metrics_panel))
+            .set_unit_overrides(cx, self.settings.metric_units.clone());
+        #[cfg(not(target_arch = "wasm32"))]
+        self.load_alert_rules(cx);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.ui
+            .query_panel(ids!(query_panel))
+            .set_history(cx, &self.settings.query_history);
+        self.ui
+            .traces_panel(ids!(traces_panel))
+            .set_columns(cx, &self.settings.trace_columns);
+        #[cfg(not(target_arch = "wasm32"))]
+        self.update_saved_trace_queries(cx);
+        self.update_prompt_templates(cx);
+        self.update_llm_backend(cx);
+        self.ui
+            .chat_screen(ids!(chat_screen))
+            .set_model(cx, self.settings.chat_model.as_deref().unwrap_or(""));
+        self.ui
+            .dataflow_table(ids!(dataflow_table))
+            .set_sort(cx, self.settings.dataflow_sort);
+        self.ui
+            .dataflow_detail(ids!(dataflow_detail))
+            .set_highlight_rules(cx, self.settings.log_highlights.clone());
+        self.ui
+            .dataflow_detail(ids!(dataflow_detail))
+            .set_buffer_lines(cx, self.settings.log_buffer_lines);
+
+        // Start per-node metrics sampling, persisted to local storage
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let storage = settings::data_dir().and_then(|d| {
+                Storage::open(&d.join("storage"))
+                    .map_err(|e| tracing::warn!(target: "App", "Storage unavailable: {}", e))
+                    .ok()
+            });
+            self.storage = storage.clone();
+            metrics::start_sampler(storage);
+            self.load_retention(cx);
+            self.prune_storage(cx);
+            self.detect_dora_version(cx);
+        }
+
+        // Initialize SigNoz bridge from env vars
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(endpoint) = instrument::start_exporter_from_env() {
+                tracing::info!(target: "App", "Exporting studio spans to {}", endpoint);
+            }
+            if let Some(addr) = websocket::listen_from_env() {
+                tracing::info!(target: "App", "Streaming events on ws://{}/events", addr);
+            }
+            deep_link::listen();
+            telemetry.request_health_check();
+            self.telemetry = Some(telemetry);
+            self.signoz_available = true;
+            watcher::sync_watched(&self.watched_paths());
+            self.update_telemetry_status(cx, None);
+        }
+
+        self.restore_session(cx);
+
+        // Schedule initial data load for next frame (after UI is ready)
+        self.next_frame = cx.new_next_frame();
+    }
+
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions) {
+        // Handle tab buttons
+        if self.ui.button(ids!(tab_dataflows)).clicked(actions) {
+            self.switch_to_panel(cx, ActivePanel::Dataflows);
+        }
+
+        if self.ui.button(ids!(tab_traces)).clicked(actions) {
+            self.show_traces(cx);
+        }
+
+        if self.ui.button(ids!(tab_alerts)).clicked(actions) {
+            self.switch_to_panel(cx, ActivePanel::Alerts);
+        }
+
+        if self.ui.button(ids!(tab_diagnostics)).clicked(actions) {
+            self.switch_to_panel(cx, ActivePanel::Diagnostics);
+            self.refresh_diagnostics(cx);
+        }
+
+        if self.ui.button(ids!(tab_query)).clicked(actions) {
+            self.switch_to_panel(cx, ActivePanel::Query);
+        }
+
+        // Handle log level changes from the diagnostics panel
+        let diagnostics = self.ui.diagnostics_panel(ids!(diagnostics_panel));
+        if let Some(level) = diagnostics.level_changed(actions) {
+            logging::set_level(level);
+            self.settings.log_level = level;
+            self.save_settings();
+        }
+
+        if let Some(locale) = diagnostics.language_changed(actions) {
+            self.settings.language = locale;
+            self.save_settings();
+            self.set_locale(cx, locale);
+        }
+
+        if let Some(percent) = diagnostics.ui_scale_changed(actions) {
+            self.settings.ui_scale_percent = percent;
+            self.save_settings();
+            self.apply_ui_scale(cx);
+        }
+
+        // Lists format times as they draw, so a redraw shows the new zone
+        if let Some(zone) = diagnostics.time_zone_changed(actions) {
+            self.settings.time_zone = zone;
+            self.save_settings();
+            datetime::set_display_zone(zone);
+            self.ui.redraw(cx);
+        }
+        if let Some(style) = diagnostics.time_style_changed(actions) {
+            self.settings.time_style = style;
+            self.save_settings();
+            datetime::set_time_style(style);
+            self.ui.redraw(cx);
+        }
+
+        if let Some(enabled) = diagnostics.desktop_notifications_changed(actions) {
+            self.settings.desktop_notifications = enabled;
+            self.save_settings();
+        }
+
+        // Persist the traces table columns, fetching any new attribute columns
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(keys) = self
+            .ui
+            .traces_panel(ids!(traces_panel))
+            .columns_changed(actions)
+        {
+            self.settings.trace_columns = keys;
+            self.save_settings();
+            if self.signoz_available {
+                self.refresh_traces(cx);
+            }
+        }
+
+        // Remember where the metrics charts are zoomed to
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let metrics = self.ui.node_metrics_panel(ids!(node_metrics_panel));
+            if let Some(range) = metrics.range_changed(actions) {
+                // Both charts show it whichever order the panel sees it in
+                metrics.set_range(cx, range);
+                self.save_session();
+            }
+        }
+
+        // Trace filters: apply, save by name, or report what is wrong
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let panel = self.ui.traces_panel(ids!(traces_panel));
+            if panel.filter_changed(actions).is_some() {
+                self.save_session();
+                if self.signoz_available {
+                    self.refresh_traces(cx);
+                }
+            }
+            if let Some(query) = panel.query_saved(actions) {
+                self.save_trace_query(cx, query);
+            }
+            if let Some(reason) = panel.filter_invalid(actions) {
+                self.notify(
+                    cx,
+                    Severity::Warning,
+                    &i18n::tr("notify.invalid_trace_filter"),
+                    &reason,
+                );
+            }
+            // Logs sharing an attribute with the inspected span, from storage
+            if let Some((key, value)) = panel.filter_logs_clicked(actions) {
+                let sql = crate::storage::query::attribute_query(LOGS_TABLE, &key, &value);
+                self.ui.query_panel(ids!(query_panel)).set_query(cx, &sql);
+                self.switch_to_panel(cx, ActivePanel::Query);
+                self.start_query(cx, &sql);
+            }
+        }
+        // The web build fetches traces from the server; saved queries and
+        // stored logs are desktop only
+        #[cfg(target_arch = "wasm32")]
+        {
+            let panel = self.ui.traces_panel(ids!(traces_panel));
+            if panel.filter_changed(actions).is_some() {
+                self.save_session();
+                self.refresh_traces(cx);
+            }
+            if let Some(reason) = panel.filter_invalid(actions) {
+                self.notify(
+                    cx,
+                    Severity::Warning,
+                    &i18n::tr("notify.invalid_trace_filter"),
+                    &reason,
+                );
+            }
+        }
+
+        // Handle alert rule editing
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let alerts = self.ui.alerts_panel(ids!(alerts_panel));
+            if let Some(expression) = alerts.add_clicked(actions) {
+                self.add_alert_rule(cx, &expression);
+            }
+            if let Some(index) = alerts.remove_clicked(actions) {
+                self.remove_alert_rule(cx, index);
+            }
+        }
+
+        // Handle the SQL console
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let query = self.ui.query_panel(ids!(query_panel));
+            query.handle_history_selection(cx, actions);
+            if let Some(sql) = query.run_clicked(actions) {
+                self.start_query(cx, &sql);
+            }
+            if let Some(path) = query.export_clicked(actions) {
+                self.export_query_results(cx, &path);
+            }
+        }
+
+        // Handle config bundle export/import
+        if let Some(path) = diagnostics.export_clicked(actions) {
+            self.export_config(cx, &path);
+        }
+
+        if let Some(path) = diagnostics.preview_clicked(actions) {
+            self.preview_config_import(cx, &path);
+        }
+
+        if let Some((path, resolution)) = diagnostics.import_clicked(actions) {
+            self.import_config(cx, &path, resolution);
+        }
+
+        let status_bar = self.ui.status_bar(ids!(status_bar));
+        if status_bar.retest_clicked(actions) {
+            self.retest_connections(cx);
+        }
+        if status_bar.edit_connection_clicked(actions) {
+            self.edit_connection(cx);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if self
+            .ui
+            .status_bar(ids!(status_bar))
+            .cancel_tasks_clicked(actions)
+        {
+            let ids: Vec<TaskId> = self.tasks.running().iter().map(|t| t.id).collect();
+            for id in ids {
+                self.tasks.cancel(id);
+            }
+            let tools = process::cancel_all();
+            if tools > 0 {
+                tracing::info!(target: "App", "Cancelled {} running tool(s)", tools);
+            }
+            self.update_task_status(cx);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = diagnostics.import_recording_clicked(actions) {
+            self.import_recording(cx, &path);
+        }
+
+        if let Some(path) = diagnostics.import_grafana_clicked(actions) {
+            self.import_grafana_dashboard(cx, &path);
+        }
+
+        if let Some(model) = self
+            .ui
+            .chat_screen(ids!(chat_screen))
+            .model_changed(actions)
+        {
+            self.settings.chat_model = Some(model);
+            self.save_settings();
+        }
+
+        // Dataflow YAML from the chat: preview the change, then write it
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let chat = self.ui.chat_screen(ids!(chat_screen));
+            if let Some(yaml) = chat.use_dataflow_clicked(actions) {
+                self.preview_agent_dataflow(cx, &yaml);
+            }
+            if let Some((path, yaml)) = chat.write_dataflow_clicked(actions) {
+                self.write_agent_dataflow(cx, &path, &yaml);
+            }
+        }
+
+        // Handle dataflow start controls
+        if self.ui.button(ids!(validate_button)).clicked(actions) {
+            let path = self.ui.text_input(ids!(start_path_input)).text();
+            #[cfg(not(target_arch = "wasm32"))]
+            self.validate_dataflow(cx, &path);
+            #[cfg(target_arch = "wasm32")]
+            if !path.trim().is_empty() {
+                self.notify(
+                    cx,
+                    Severity::Info,
+                    &i18n::tr("notify.dataflow_check_unavailable"),
+                    &i18n::tr("notify.dataflow_check_unavailable_message"),
+                );
+            }
+        }
+
+        if self.ui.button(ids!(start_button)).clicked(actions) {
+            let path = self.ui.text_input(ids!(start_path_input)).text();
+            self.start_dataflow(cx, &path);
+        }
+
+        if self.ui.button(ids!(new_project_button)).clicked(actions) {
+            self.open_new_project(cx);
+        }
+
+        if self.ui.button(ids!(env_button)).clicked(actions) {
+            let path = self.ui.text_input(ids!(start_path_input)).text();
+            self.open_env_editor(cx, &path);
+        }
+
+        if let Some(index) = self.ui.drop_down(ids!(recent_dropdown)).selected(actions) {
+            // Index 0 is the "Recent" placeholder label
+            let path = index
+                .checked_sub(1)
+                .and_then(|i| self.settings.recent_dataflows.get(i))
+                .cloned();
+            if let Some(path) = path {
+                self.start_recent_dataflow(cx, &path);
+            }
+            // Back to the placeholder, so the same entry can start again
+            self.ui
+                .drop_down(ids!(recent_dropdown))
+                .set_selected_item(cx, 0);
+        }
+
+        // Handle run history selection and snap banner
+        if let Some(index) = self.ui.drop_down(ids!(run_dropdown)).selected(actions) {
+            // Index 0 is the "Runs" placeholder label
+            let run = index
+                .checked_sub(1)
+                .and_then(|i| self.run_history.runs().get(i))
+                .cloned();
+            if let Some(run) = run {
+                self.snap_to_run(cx, run);
+            }
+        }
+
+        if self.ui.button(ids!(snap_clear_button)).clicked(actions) {
+            self.clear_snap(cx);
+        }
+
+        if self.ui.button(ids!(refresh_retry_button)).clicked(actions) {
+            self.refresh_dataflows(cx);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.ui.button(ids!(start_runtime_button)).clicked(actions) {
+            self.start_runtime(cx);
+        }
+
+        // Handle toasts and the notification drawer
+        if let Some(id) = self
+            .ui
+            .notification_toasts(ids!(notification_toasts))
+            .dismissed(actions)
+        {
+            if self.pending_reload.as_ref().is_some_and(|(n, _)| *n == id) {
+                self.pending_reload = None;
+            }
+            // Dismissing a restart offer gives up on restarting
+            if let Some(uuid) = self.take_undo_notice(id) {
+                self.undo_stack.take(&uuid);
+            }
+            self.notifications.dismiss(id);
+            self.update_notifications(cx);
+        }
+        if let Some(id) = self
+            .ui
+            .notification_toasts(ids!(notification_toasts))
+            .action_clicked(actions)
+        {
+            self.notifications.dismiss(id);
+            self.update_notifications(cx);
+            #[cfg(not(target_arch = "wasm32"))]
+            if self.pending_reload.as_ref().is_some_and(|(n, _)| *n == id) {
+                self.rebuild_and_restart(cx);
+            }
+            if let Some(uuid) = self.take_undo_notice(id) {
+                self.restart_undo(cx, &uuid);
+            }
+        }
+
+        if self.ui.button(ids!(notifications_button)).clicked(actions) {
+            self.set_drawer_open(cx, !self.drawer_open);
+        }
+
+        if let Some(index) = self.ui.drop_down(ids!(layout_dropdown)).selected(actions) {
+            // Index 0 is the "Layout" placeholder label
+            let name = index
+                .checked_sub(1)
+                .and_then(|i| {
+                    layout::all_presets(&self.settings.layout_presets)
+                        .get(i)
+                        .cloned()
+                })
+                .map(|p| p.name);
+            if let Some(name) = name {
+                self.apply_layout(cx, &name);
+            }
+            self.ui
+                .drop_down(ids!(layout_dropdown))
+                .set_selected_item(cx, 0);
+        }
+
+        if let Some(name) = self
+            .ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .save_layout_clicked(actions)
+        {
+            self.save_layout(cx, &name);
+        }
+
+        if let Some(text) = self
+            .ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .apply_shortcuts_clicked(actions)
+        {
+            self.apply_shortcuts(cx, &text);
+        }
+
+        if let Some(name) = self
+            .ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .edit_prompt_clicked(actions)
+        {
+            self.edit_prompt_template(cx, &name);
+        }
+
+        if let Some((name, prompt)) = self
+            .ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .save_prompt_clicked(actions)
+        {
+            self.save_prompt_template(cx, &name, &prompt);
+        }
+
+        if let Some((backend, url)) = self
+            .ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .apply_llm_clicked(actions)
+        {
+            self.apply_llm_backend(cx, backend, &url);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let diagnostics = self.ui.diagnostics_panel(ids!(diagnostics_panel));
+            if let Some(text) = diagnostics.apply_retention_clicked(actions) {
+                self.apply_retention(cx, &text);
+            }
+            if diagnostics.prune_clicked(actions) {
+                self.prune_storage(cx);
+            }
+            if let Some(line) = diagnostics.run_cli_clicked(actions) {
+                self.run_dora_cli(cx, &line);
+            }
+            if let Some((path, ssh_host)) = diagnostics.apply_dora_target_clicked(actions) {
+                self.apply_dora_target(cx, &path, &ssh_host);
+            }
+        }
+
+        self.handle_split_changes(actions);
+
+        // Handle command palette
+        let command_palette = self.ui.command_palette(ids!(command_palette));
+        match command_palette.action(actions) {
+            Some(CommandPaletteAction::Run(command)) => {
+                command_palette.close(cx);
+                self.run_command(cx, command);
+            }
+            Some(CommandPaletteAction::Dismissed) => command_palette.close(cx),
+            _ => {}
+        }
+
+        let drawer = self.ui.notification_drawer(ids!(notification_drawer));
+        if drawer.clear_clicked(actions) {
+            self.notifications.clear_history();
+            self.update_notifications(cx);
+        }
+
+        if drawer.close_clicked(actions) {
+            self.set_drawer_open(cx, false);
+        }
+
+        if self
+            .ui
+            .debug_console(ids!(debug_console))
+            .close_clicked(actions)
+        {
+            self.set_debug_console_open(cx, false);
+        }
+
+        // Handle shared refresh button
+        if self.ui.button(ids!(refresh_button)).clicked(actions) {
+            self.refresh_active_panel(cx);
+        }
+
+        // Handle DataflowTable row actions
+        let table = self.ui.dataflow_table(ids!(dataflow_table));
+
+        if let Some(uuid) = table.stop_clicked(actions) {
+            tracing::debug!(target: "App", "Stop button clicked for {}", uuid);
+            self.stop_dataflow(cx, &uuid);
+        }
+
+        if let Some(uuid) = table.destroy_clicked(actions) {
+            tracing::debug!(target: "App", "Destroy button clicked for {}", uuid);
+            self.destroy_dataflow(cx, &uuid);
+        }
+
+        if let Some(uuid) = table.logs_clicked(actions) {
+            tracing::debug!(target: "App", "Logs button clicked for {}", uuid);
+            self.view_dataflow_logs(cx, &uuid);
+        }
+
+        if let Some(uuid) = table.summarize_logs_clicked(actions) {
+            tracing::debug!(target: "App", "Summarize button clicked for {}", uuid);
+            self.summarize_dataflow_logs(cx, &uuid);
+        }
+
+        // Persist the table order as the default sort
+        if let Some(sort) = table.sort_changed(actions) {
+            self.settings.dataflow_sort = Some(sort);
+            self.save_settings();
+        }
+
+        if let Some(uuid) = table.selected_changed(actions) {
+            self.show_dataflow_detail(cx, &uuid);
+        }
+
+        // Handle detail panel quick actions
+        match self
+            .ui
+            .dataflow_detail(ids!(dataflow_detail))
+            .action(actions)
+        {
+            Some(DataflowDetailAction::Refresh(uuid)) => self.show_dataflow_detail(cx, &uuid),
+            Some(DataflowDetailAction::Stop(uuid)) => self.stop_dataflow(cx, &uuid),
+            Some(DataflowDetailAction::Destroy(uuid)) => self.destroy_dataflow(cx, &uuid),
+            Some(DataflowDetailAction::SummarizeLogs(uuid)) => {
+                self.summarize_dataflow_logs(cx, &uuid)
+            }
+            Some(DataflowDetailAction::ExportLogs(path)) => self.export_dataflow_logs(cx, &path),
+            Some(DataflowDetailAction::HighlightsChanged(rules)) => {
+                self.settings.log_highlights = rules;
+                self.save_settings();
+            }
+            _ => {}
+        }
+
+        // Handle environment editor changes
+        match self.ui.env_editor(ids!(env_editor)).action(actions) {
+            Some(EnvEditorAction::Changed(path, vars)) => {
+                self.settings.set_dataflow_env(&path, vars);
+                self.save_settings();
+            }
+            Some(EnvEditorAction::Load(path, file)) => self.load_env_file(cx, &path, &file),
+            _ => {}
+        }
+
+        // Handle the new project wizard
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let wizard = self.ui.new_project_wizard(ids!(new_project_wizard));
+            match wizard.action(actions) {
+                Some(NewProjectAction::Create(spec)) => self.create_project(cx, &spec),
+                Some(NewProjectAction::Dismissed) => wizard.close(cx),
+                _ => {}
+            }
+        }
+
+        // Handle the crash recovery dialog
+        #[cfg(not(target_arch = "wasm32"))]
+        match self.ui.crash_dialog(ids!(crash_dialog)).action(actions) {
+            Some(CrashDialogAction::OpenReport) => self.open_crash_report(cx),
+            Some(CrashDialogAction::Dismissed) => {
+                self.crash_report = None;
+                self.ui.crash_dialog(ids!(crash_dialog)).close(cx);
+            }
+            _ => {}
+        }
+
+        // Handle the dropped dataflow dialog
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let dialog = self.ui.start_dialog(ids!(start_dialog));
+            match dialog.action(actions) {
+                Some(StartDialogAction::Start { path, build }) => {
+                    dialog.close(cx);
+                    self.start_dropped_dataflow(cx, &path, build);
+                }
+                Some(StartDialogAction::Edit(path)) => {
+                    dialog.close(cx);
+                    self.edit_dataflow(cx, &path);
+                }
+                Some(StartDialogAction::Dismissed) => dialog.close(cx),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl AppMain for App {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
+        self.match_event(cx, event);
+
+        match event {
+            Event::AppGotFocus => self.window_focused = true,
+            Event::AppLostFocus => self.window_focused = false,
+            Event::WindowGeomChange(ev) => self.handle_window_geom(cx, &ev.new_geom),
+            Event::KeyDown(ke) => self.handle_shortcut(cx, ke),
+            // Dataflow files dragged in from the file manager. Browsers hand
+            // over file contents rather than paths, so only on native
+            #[cfg(not(target_arch = "wasm32"))]
+            Event::Drag(de) => {
+                if dropped_dataflow(&de.items).is_some() {
+                    de.response.set(DragResponse::Copy);
+                }
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Event::Drop(de) => self.handle_drop(cx, &de.items),
+            _ => {}
+        }
+
+        // Handle next frame for initialization and auto-refresh
+        if let Some(ne) = self.next_frame.is_event(event) {
+            if !self.initialized {
+                self.initialized = true;
+                self.last_refresh_time = ne.time;
+                tracing::debug!(target: "App", "Initializing dataflow table on first frame");
+                self.refresh_dataflows(cx);
+            } else {
+                // Check if it's time for auto-refresh
+                let elapsed = ne.time - self.last_refresh_time;
+                if elapsed >= AUTO_REFRESH_INTERVAL {
+                    self.last_refresh_time = ne.time;
+
+                    match self.active_panel {
+                        // A failing refresh is retried on its own schedule below
+                        ActivePanel::Dataflows if self.refresh_backoff.is_failing() => {}
+                        ActivePanel::Dataflows => {
+                            tracing::debug!(
+                                target: "App",
+                                "Auto-refresh triggered after {:.1}s",
+                                elapsed
+                            );
+                            self.refresh_dataflows(cx);
+                        }
+                        ActivePanel::Traces | ActivePanel::Alerts | ActivePanel::Query => {}
+                        ActivePanel::Diagnostics | ActivePanel::Settings => {
+                            self.refresh_diagnostics(cx);
+                        }
+                    }
+
+                    // Traces feed both the traces panel and alert evaluation;
+                    // the live tail covers both while it runs
+                    #[cfg(not(target_arch = "wasm32"))]
+                    if self.signoz_available
+                        && !self.live_tailing()
+                        && (self.active_panel == ActivePanel::Traces
+                            || !self.alert_rules.is_empty())
+                    {
+                        tracing::debug!(target: "App", "Auto-refresh traces after {:.1}s", elapsed);
+                        self.refresh_traces(cx);
+                    }
+                    #[cfg(target_arch = "wasm32")]
+                    if self.active_panel == ActivePanel::Traces {
+                        self.refresh_traces(cx);
+                    }
+                }
+
+                #[cfg(not(target_arch = "wasm32"))]
+                if self.live_tailing() && ne.time - self.last_tail_time >= LIVE_TAIL_INTERVAL {
+                    self.last_tail_time = ne.time;
+                    self.tail_traces(cx);
+                }
+
+                // Retry a failing `dora list` with backoff, whatever the panel
+                if self.retry_at.is_some_and(|at| Instant::now() >= at) {
+                    tracing::debug!(
+                        target: "App",
+                        "Retrying dataflow refresh after {} failures",
+                        self.refresh_backoff.failures()
+                    );
+                    self.refresh_dataflows(cx);
+                }
+                self.update_retry_countdown(cx);
+            }
+
+            // Persist window geometry once a move/resize has settled
+            if self
+                .geometry_changed_at
+                .is_some_and(|t| t.elapsed() >= LAYOUT_SAVE_DELAY)
+            {
+                self.geometry_changed_at = None;
+                if let Some((key, geometry)) = self.window_geometry.clone() {
+                    self.settings.window_geometry.insert(key, geometry);
+                    self.save_settings();
+                }
+            }
+
+            // Drop expired records from local storage
+            #[cfg(not(target_arch = "wasm32"))]
+            if self
+                .pruned_at
+                .is_some_and(|t| t.elapsed() >= PRUNE_INTERVAL)
+            {
+                self.prune_storage(cx);
+            }
+
+            // Persist splitter sizes once dragging has settled
+            if self
+                .splits_changed_at
+                .is_some_and(|t| t.elapsed() >= LAYOUT_SAVE_DELAY)
+            {
+                self.splits_changed_at = None;
+                self.save_settings();
+            }
+
+            // Monitors may be detected after the window first reported
+            if !self.geometry_restored {
+                self.restore_window_geometry(cx);
+            }
+
+            // Drop undo entries whose grace window has passed
+            if self.undo_stack.expire(Instant::now()) {
+                self.dismiss_expired_undo_notices(cx);
+            }
+
+            // Auto-dismiss toasts
+            if self.notifications.expire(logging::now_ms()) {
+                self.update_notifications(cx);
+            }
+
+            self.update_debug_console(cx);
+
+            // Poll SigNoz responses, YAML changes and opened links
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                for link in deep_link::take_links() {
+                    self.open_link(cx, link);
+                }
+                for path in watcher::take_changed_paths() {
+                    if Settings::path().is_some_and(|p| watcher::canonical(&p) == path) {
+                        self.settings_file_changed(cx);
+                    } else {
+                        self.handle_yaml_changed(cx, &path);
+                    }
+                }
+                let samples = metrics::take_node_metrics();
+                if !samples.is_empty() {
+                    for sample in samples {
+                        self.liveness
+                            .learn_node(&sample.node_id, &sample.dataflow_id);
+                        if sample.cpu_percent > ACTIVE_CPU_PERCENT {
+                            self.liveness
+                                .record(&sample.dataflow_id, sample.timestamp_ms);
+                        }
+                        self.metrics_history.push(sample);
+                    }
+                    self.update_node_metrics(cx);
+                }
+                let telemetry = self.telemetry.clone();
+                for response in telemetry.iter().flat_map(|t| t.take_responses()) {
+                    self.handle_signoz_response(cx, response);
+                }
+                self.show_pending_spans(cx);
+                let (success_ms, latency_ms) = telemetry.and_then(|t| t.last_success()).unzip();
+                self.ui
+                    .status_bar(ids!(status_bar))
+                    .set_telemetry_timing(LinkTiming {
+                        success_ms,
+                        latency_ms,
+                    });
+                let (_, finished) = self.tasks.poll();
+                for task in finished {
+                    self.handle_task_finished(cx, task.id, &task.name, task.outcome);
+                }
+                // Tools run by the agent are not tasks; refresh every frame
+                // so their elapsed time ticks
+                self.update_task_status(cx);
+            }
+
+            // Poll the server the web build talks to
+            #[cfg(target_arch = "wasm32")]
+            for response in remote::take_responses() {
+                self.handle_remote_response(cx, response);
+            }
+
+            self.ui.status_bar(ids!(status_bar)).set_last_refresh(
+                cx,
+                self.last_refreshed_ms,
+                logging::now_ms(),
+            );
+
+            // Schedule the next frame to keep auto-refresh running
+            self.next_frame = cx.new_next_frame();
+        }
+
+        self.ui.handle_event(cx, event, &mut Scope::empty());
+    }
+}
+
+impl App {
+    fn refresh_active_panel(&mut self, cx: &mut Cx) {
+        match self.active_panel {
+            ActivePanel::Dataflows => {
+                tracing::debug!(target: "App", "Refreshing dataflows");
+                self.refresh_dataflows(cx);
+            }
+            ActivePanel::Traces | ActivePanel::Alerts => {
+                tracing::debug!(target: "App", "Refreshing traces");
+                self.refresh_traces(cx);
+            }
+            ActivePanel::Diagnostics | ActivePanel::Settings => {
+                self.refresh_diagnostics(cx);
+            }
+            // Queries only run when asked to
+            ActivePanel::Query => {}
+        }
+    }
+
+    /// Build the keymap from the saved overrides and show it in settings.
+    fn load_shortcuts(&mut self, cx: &mut Cx) {
+        let (keymap, errors) = Keymap::new(&self.settings.shortcuts);
+        for error in &errors {
+            tracing::warn!(target: "App", "Shortcut ignored: {}", error);
+        }
+        self.keymap = keymap;
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_shortcuts(
+                cx,
+                &shortcuts::format_overrides(&self.settings.shortcuts),
+                &self.keymap.describe(),
+            );
+    }
+
+    fn apply_shortcuts(&mut self, cx: &mut Cx, text: &str) {
+        let overrides = match shortcuts::parse_overrides(text) {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                self.notify(
+                    cx,
+                    Severity::Error,
+                    &i18n::tr("notify.invalid_shortcuts"),
+                    &e,
+                );
+                return;
+            }
+        };
+        let (_, errors) = Keymap::new(&overrides);
+        if !errors.is_empty() {
+            self.notify(
+                cx,
+                Severity::Error,
+                &i18n::tr("notify.invalid_shortcuts"),
+                &errors.join("; "),
+            );
+            return;
+        }
+        self.settings.shortcuts = overrides;
+        self.save_settings();
+        self.load_shortcuts(cx);
+        self.notify(
+            cx,
+            Severity::Success,
+            &i18n::tr("notify.shortcuts_saved"),
+            &self.keymap.describe(),
+        );
+    }
+
+    fn handle_shortcut(&mut self, cx: &mut Cx, ke: &KeyEvent) {
+        let Some(chord) = KeyChord::from_event(ke) else {
+            return;
+        };
+        let Some(action) = self.keymap.action(&chord) else {
+            return;
+        };
+        let command_palette = self.ui.command_palette(ids!(command_palette));
+        // Plain keys are typing while a text input has focus, and opening
+        // the palette would take the focus away mid-word
+        let typing = !cx.has_key_focus(Area::Empty);
+        if typing && (chord.is_plain() || (action.opens_palette() && !command_palette.is_open())) {
+            return;
+        }
+        tracing::debug!(target: "App", "Shortcut {} -> {}", chord, action.id());
+        match action {
+            ShortcutAction::Refresh => self.refresh_active_panel(cx),
+            ShortcutAction::ShowDataflows => self.run_command(cx, Command::ShowDataflows),
+            ShortcutAction::ShowTraces => self.run_command(cx, Command::ShowTraces),
+            ShortcutAction::ShowAlerts => self.run_command(cx, Command::ShowAlerts),
+            ShortcutAction::ShowDiagnostics => self.run_command(cx, Command::ShowDiagnostics),
+            ShortcutAction::ShowQuery => self.run_command(cx, Command::ShowQuery),
+            ShortcutAction::FocusSearch => {
+                if !command_palette.is_open() {
+                    command_palette.open(cx, self.palette_entries());
+                }
+            }
+            ShortcutAction::CloseDialog => {
+                if command_palette.is_open() {
+                    // Esc also reaches the palette, which reports itself
+                    // dismissed; closing it here too would handle it twice
+                    if chord.key != "Esc" {
+                        command_palette.close(cx);
+                    }
+                } else if self.drawer_open {
+                    self.set_drawer_open(cx, false);
+                } else if self.debug_console_open {
+                    self.set_debug_console_open(cx, false);
+                }
+            }
+            ShortcutAction::SendChat => self.ui.chat_screen(ids!(chat_screen)).send(cx),
+            ShortcutAction::CommandPalette => self.toggle_command_palette(cx),
+            ShortcutAction::ToggleDebugConsole => {
+                self.set_debug_console_open(cx, !self.debug_console_open)
+            }
+        }
+    }
+
+    /// Switch to traces, loading them the first time the panel is shown.
+    fn show_traces(&mut self, cx: &mut Cx) {
+        self.switch_to_panel(cx, ActivePanel::Traces);
+        if (self.signoz_available || cfg!(target_arch = "wasm32")) && !self.traces_loaded_once {
+            self.refresh_traces(cx);
+        }
+    }
+
+    fn start_recent_dataflow(&mut self, cx: &mut Cx, path: &str) {
+        tracing::debug!(target: "App", "Restarting recent dataflow {}", path);
+        self.ui
+            .text_input(ids!(start_path_input))
+            .set_text(cx, path);
+        self.start_dataflow(cx, path);
+    }
+
+    fn toggle_command_palette(&mut self, cx: &mut Cx) {
+        let command_palette = self.ui.command_palette(ids!(command_palette));
+        if command_palette.is_open() {
+            command_palette.close(cx);
+        } else {
+            command_palette.open(cx, self.palette_entries());
+        }
+    }
+
+    /// Static commands plus recent dataflows to start and running ones to stop.
+    fn palette_entries(&self) -> Vec<PaletteEntry> {
+        let mut entries = palette::static_entries();
+        for entry in &mut entries {
+            let action = match entry.command {
+                Command::ShowDataflows => ShortcutAction::ShowDataflows,
+                Command::ShowTraces => ShortcutAction::ShowTraces,
+                Command::ShowAlerts => ShortcutAction::ShowAlerts,
+                Command::ShowDiagnostics => ShortcutAction::ShowDiagnostics,
+                Command::ShowQuery => ShortcutAction::ShowQuery,
+                Command::ToggleDebugConsole => ShortcutAction::ToggleDebugConsole,
+                _ => continue,
+            };
+            if let Some(chord) = self.keymap.chord(action) {
+                entry.detail = chord.to_string();
+            }
+        }
+        for path in &self.settings.recent_dataflows {
+            let name = std::path::Path::new(path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(path);
+            entries.push(
+                PaletteEntry::new(
+                    &i18n::tr_args("palette.start_recent", &[("name", name)]),
+                    Command::StartRecent(path.clone()),
+                )
+                .with_detail(path),
+            );
+        }
+        for preset in layout::all_presets(&self.settings.layout_presets) {
+            entries.push(PaletteEntry::new(
+                &i18n::tr_args("palette.apply_layout", &[("name", &preset.name)]),
+                Command::ApplyLayout(preset.name.clone()),
+            ));
+        }
+        let mut running: Vec<(&String, &String)> = self
+            .dataflow_statuses
+            .iter()
+            .flatten()
+            .filter(|(_, status)| status.eq_ignore_ascii_case("running"))
+            .filter_map(|(uuid, _)| Some((self.dataflow_names.get(uuid)?, uuid)))
+            .collect();
+        running.sort();
+        for (name, uuid) in running {
+            entries.push(
+                PaletteEntry::new(
+                    &i18n::tr_args("palette.stop_dataflow", &[("name", name)]),
+                    Command::StopDataflow(uuid.clone()),
+                )
+                .with_detail(&text::truncate(uuid, 8)),
+            );
+        }
+        entries
+    }
+
+    /// Dispatch a palette command to the handler its button uses.
+    fn run_command(&mut self, cx: &mut Cx, command: Command) {
+        tracing::debug!(target: "App", "Palette command: {:?}", command);
+        match command {
+            Command::RefreshDataflows => self.refresh_dataflows(cx),
+            Command::ShowDataflows => self.switch_to_panel(cx, ActivePanel::Dataflows),
+            Command::ShowTraces => self.show_traces(cx),
+            Command::ShowAlerts => self.switch_to_panel(cx, ActivePanel::Alerts),
+            Command::ShowQuery => self.switch_to_panel(cx, ActivePanel::Query),
+            Command::ShowDiagnostics => {
+                self.switch_to_panel(cx, ActivePanel::Diagnostics);
+                self.refresh_diagnostics(cx);
+            }
+            Command::OpenSettings => {
+                self.switch_to_panel(cx, ActivePanel::Settings);
+                self.refresh_diagnostics(cx);
+            }
+            Command::StartDataflow => {
+                self.switch_to_panel(cx, ActivePanel::Dataflows);
+                self.ui.text_input(ids!(start_path_input)).set_key_focus(cx);
+            }
+            Command::NewProject => self.open_new_project(cx),
+            Command::StartRecent(path) => {
+                self.switch_to_panel(cx, ActivePanel::Dataflows);
+                self.start_recent_dataflow(cx, &path);
+            }
+            Command::StopDataflow(uuid) => self.stop_dataflow(cx, &uuid),
+            Command::ToggleNotifications => self.set_drawer_open(cx, !self.drawer_open),
+            Command::ToggleDebugConsole => {
+                self.set_debug_console_open(cx, !self.debug_console_open)
+            }
+            Command::ApplyLayout(name) => self.apply_layout(cx, &name),
+        }
+    }
+
+    fn switch_to_panel(&mut self, cx: &mut Cx, panel: ActivePanel) {
+        self.active_panel = panel;
+        // Settings are shown in the diagnostics view, its log hidden
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_settings_only(cx, panel == ActivePanel::Settings);
+        let shown = match panel {
+            ActivePanel::Settings => ActivePanel::Diagnostics,
+            panel => panel,
+        };
+        let views = [
+            (ActivePanel::Dataflows, self.ui.view(ids!(dataflow_view))),
+            (ActivePanel::Traces, self.ui.view(ids!(traces_view))),
+            (ActivePanel::Alerts, self.ui.view(ids!(alerts_view))),
+            (
+                ActivePanel::Diagnostics,
+                self.ui.view(ids!(diagnostics_view)),
+            ),
+            (ActivePanel::Query, self.ui.view(ids!(query_view))),
+        ];
+        for (view_panel, view) in views {
+            if view_panel == shown {
+                view.apply_over(cx, live! { height: Fill });
+            } else {
+                view.apply_over(cx, live! { height: 0 });
+            }
+        }
+        self.save_session();
+        self.ui.redraw(cx);
+    }
+
+    fn refresh_diagnostics(&mut self, cx: &mut Cx) {
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_records(cx, logging::recent_records());
+        #[cfg(not(target_arch = "wasm32"))]
+        self.update_storage_usage(cx);
+    }
+
+    fn refresh_dataflows(&mut self, cx: &mut Cx) {
+        tracing::debug!(target: "App", "refresh_dataflows called");
+        if !self.coordinator.is_offline() {
+            self.ui.dataflow_table(ids!(dataflow_table)).set_loading(cx);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // The listing in flight will show the latest state soon enough
+            if self.task_pending(|job| matches!(job, TaskJob::ListDataflows)) {
+                return;
+            }
+            let job = TaskJob::ListDataflows;
+            self.spawn_dora(cx, &i18n::tr("task.list_dataflows"), job, |dora| {
+                let mut span = instrument::span("dataflow.refresh");
+                let started = Instant::now();
+                let result = {
+                    let _list = span.child("dora.list", SpanKind::Client);
+                    dora.list_dataflows()
+                };
+                match &result {
+                    Ok(dataflows) => span.attr("dataflow.count", dataflows.len()),
+                    Err(e) => span.fail(e),
+                }
+                (result, started.elapsed().as_millis() as u64)
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        remote::submit(RemoteRequest::ListDataflows);
+    }
+
+    /// Show a dataflow listing, or why listing failed.
+    fn dataflows_listed(&mut self, cx: &mut Cx, result: Result<Vec<DataflowInfo>, String>) {
+        let table = self.ui.dataflow_table(ids!(dataflow_table));
+        if let Err(e) = &result {
+            tracing::warn!(target: "App", "Listing dataflows failed: {}", e);
+            let delay = self.refresh_backoff.fail(e);
+            self.retry_at = Some(Instant::now() + delay);
+        } else {
+            self.refresh_backoff.succeed();
+            self.retry_at = None;
+        }
+
+        match result {
+            Err(e) if is_unreachable(&e) => {
+                table.set_offline(cx);
+                self.ui.dataflow_detail(ids!(dataflow_detail)).close(cx);
+                self.set_coordinator_state(cx, CoordinatorState::Offline);
+            }
+            Err(e) => {
+                table.set_error(cx, &e);
+                if self.refresh_error.as_ref() != Some(&e) {
+                    let title = i18n::tr("error.list_dataflows");
+                    self.notify(cx, Severity::Error, &title, &e);
+                    self.refresh_error = Some(e);
+                }
+            }
+            Ok(mut dataflows) => {
+                self.set_coordinator_state(cx, CoordinatorState::Online);
+                if self.refresh_error.take().is_some() {
+                    self.notify(
+                        cx,
+                        Severity::Success,
+                        &i18n::tr("notify.dataflow_list_available_again"),
+                        "",
+                    );
+                }
+                tracing::debug!(target: "App", "Parsed {} dataflows", dataflows.len());
+                let statuses = dataflows
+                    .iter()
+                    .map(|df| (df.uuid.clone(), df.status.clone()))
+                    .collect();
+                if let Some(previous) = self.dataflow_statuses.replace(statuses) {
+                    for df in newly_failed(&previous, &dataflows) {
+                        let msg = i18n::tr_args(
+                            "notify.dataflow_failed_message",
+                            &[("name", &df.name), ("uuid", &df.uuid_short())],
+                        );
+                        self.notify(
+                            cx,
+                            Severity::Error,
+                            &i18n::tr("notify.dataflow_failed"),
+                            &msg,
+                        );
+                        self.notify_desktop(&i18n::tr("notify.dataflow_failed"), &msg);
+                    }
+                }
+                let changed = if self.run_history_reconciled {
+                    self.run_history.observe(&dataflows, logging::now_ms())
+                } else {
+                    self.run_history_reconciled = true;
+                    self.run_history.reconcile(&dataflows, logging::now_ms())
+                };
+                if changed {
+                    if let Err(e) = self.run_history.save() {
+                        tracing::warn!(target: "App", "Failed to save run history: {}", e);
+                    }
+                    self.update_run_dropdown(cx);
+                }
+                self.running_started = dataflows
+                    .iter()
+                    .filter(|df| df.is_running())
+                    .filter_map(|df| {
+                        let spec = self.undo_stack.started_spec(&df.uuid)?;
+                        Some((df.uuid.clone(), spec.dataflow_path.clone()))
+                    })
+                    .collect();
+                self.dataflow_names = dataflows
+                    .iter()
+                    .map(|df| (df.uuid.clone(), df.name.clone()))
+                    .collect();
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    let running: Vec<String> = dataflows
+                        .iter()
+                        .filter(|df| df.is_running())
+                        .map(|df| df.uuid.clone())
+                        .collect();
+                    metrics::set_active_dataflows(running.clone());
+                    self.observe_events(&dataflows);
+                    self.metrics_history.retain_dataflows(&running);
+                    self.update_node_metrics(cx);
+                    self.update_liveness(cx, &running, &mut dataflows);
+
+                    watcher::sync_watched(&self.watched_paths());
+                }
+                let running = dataflows.iter().filter(|df| df.is_running());
+                let stale = running.clone().filter(|df| df.stale).count();
+                self.ui
+                    .status_bar(ids!(status_bar))
+                    .set_running(cx, running.count(), stale);
+                self.last_refreshed_ms = Some(logging::now_ms());
+                self.ui
+                    .status_bar(ids!(status_bar))
+                    .set_coordinator_timing(LinkTiming {
+                        success_ms: self.last_refreshed_ms,
+                        latency_ms: self.coordinator_latency_ms,
+                    });
+                table.set_dataflows(cx, dataflows);
+                self.update_dataflow_detail(cx);
+                #[cfg(not(target_arch = "wasm32"))]
+                self.open_linked_dataflow(cx);
+            }
+        }
+        self.update_refresh_banner(cx);
+    }
+
+    /// Open the detail panel on a dataflow, fetching its nodes and recent
+    /// logs.
+    fn show_dataflow_detail(&mut self, cx: &mut Cx, uuid: &str) {
+        let Some(df) = self
+            .ui
+            .dataflow_table(ids!(dataflow_table))
+            .dataflow_by_uuid(uuid)
+        else {
+            return;
+        };
+        self.ui.env_editor(ids!(env_editor)).close(cx);
+        let detail = self.ui.dataflow_detail(ids!(dataflow_detail));
+        detail.set_dataflow(cx, &df, logging::now_ms());
+        if !self.require_coordinator(cx, "coordinator_action.fetch_details") {
+            return;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let (job, uuid) = (TaskJob::ListNodes(uuid.to_string()), uuid.to_string());
+            self.spawn_dora(cx, &i18n::tr("task.list_nodes"), job, move |dora| {
+                dora.list_nodes(&uuid)
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        remote::submit(RemoteRequest::Nodes(uuid.to_string()));
+        self.fetch_detail_logs(cx, uuid);
+        self.update_golden_signals(cx);
+    }
+
+    /// Compute the golden signals of the dataflow in the detail panel from
+    /// the spans in the traces panel and the CPU samples of its nodes.
+    fn update_golden_signals(&mut self, cx: &mut Cx) {
+        let detail = self.ui.dataflow_detail(ids!(dataflow_detail));
+        let Some(uuid) = detail.uuid() else {
+            return;
+        };
+        let (nodes, cpu) = self.dataflow_node_cpu(&uuid, detail.nodes());
+        let spans = self.ui.traces_panel(ids!(traces_panel)).spans();
+        let signals = GoldenSignals::compute(&spans, &nodes, &cpu, logging::now_ms());
+        detail.set_golden_signals(cx, &signals);
+    }
+
+    /// `nodes` with the others sampled for dataflow `uuid`, and the CPU
+    /// samples of each.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn dataflow_node_cpu(
+        &self,
+        uuid: &str,
+        mut nodes: Vec<String>,
+    ) -> (Vec<String>, Vec<Vec<f64>>) {
+        let mut cpu = Vec::new();
+        for series in self.metrics_history.series() {
+            if series.dataflow_id != uuid {
+                continue;
+            }
+            if !nodes.contains(&series.node_id) {
+                nodes.push(series.node_id.clone());
+            }
+            cpu.push(series.cpu.into_iter().collect());
+        }
+        (nodes, cpu)
+    }
+
+    /// The web build has no metrics sampler.
+    #[cfg(target_arch = "wasm32")]
+    fn dataflow_node_cpu(&self, _uuid: &str, nodes: Vec<String>) -> (Vec<String>, Vec<Vec<f64>>) {
+        (nodes, Vec::new())
+    }
+
+    /// Fetch the logs of the dataflow in the detail panel, which adds the
+    /// lines logged since the last fetch.
+    fn fetch_detail_logs(&mut self, cx: &mut Cx, uuid: &str) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // A slow `dora logs` is not stacked up by every refresh
+            if self.task_pending(|job| matches!(job, TaskJob::DetailLogs(u) if u == uuid)) {
+                return;
+            }
+            let (job, uuid) = (TaskJob::DetailLogs(uuid.to_string()), uuid.to_string());
+            self.spawn_dora(cx, &i18n::tr("task.fetch_logs"), job, move |dora| {
+                dora.logs(&uuid, None)
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = cx;
+            remote::submit(RemoteRequest::Logs(uuid.to_string()));
+        }
+    }
+
+    /// Write the log lines the detail panel no longer holds to local
+    /// storage, where the Query panel can still find them.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn spill_detail_logs(&mut self, uuid: &str) {
+        let spilled = self
+            .ui
+            .dataflow_detail(ids!(dataflow_detail))
+            .take_spilled_logs();
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        if spilled.is_empty() {
+            return;
+        }
+        let now = logging::now_ms();
+        let entries: Vec<_> = spilled
+            .iter()
+            .map(|line| crate::dataflow::log_buffer::log_entry(uuid, line, now))
+            .collect();
+        if let Err(e) = storage.append(LOGS_TABLE, &entries) {
+            tracing::warn!(target: "App", "Failed to spill dataflow logs: {}", e);
+        }
+    }
+
+    /// Keep the detail panel in step with the table after a refresh, closing
+    /// it when its dataflow is no longer listed.
+    fn update_dataflow_detail(&mut self, cx: &mut Cx) {
+        let detail = self.ui.dataflow_detail(ids!(dataflow_detail));
+        let Some(uuid) = detail.uuid() else {
+            return;
+        };
+        match self
+            .ui
+            .dataflow_table(ids!(dataflow_table))
+            .dataflow_by_uuid(&uuid)
+        {
+            Some(df) => {
+                detail.set_dataflow(cx, &df, logging::now_ms());
+                // Follow the logs of a running dataflow
+                if df.is_running() && !self.coordinator.is_offline() {
+                    self.fetch_detail_logs(cx, &uuid);
+                }
+                self.update_golden_signals(cx);
+            }
+            None => detail.close(cx),
+        }
+    }
+
+    /// Show or hide the banner explaining why `dora list` fails.
+    fn update_refresh_banner(&mut self, cx: &mut Cx) {
+        let bar = self.ui.view(ids!(refresh_error_bar));
+        let Some(error) = self.refresh_backoff.last_error() else {
+            bar.apply_over(cx, live! { height: 0 });
+            self.ui.redraw(cx);
+            return;
+        };
+        let summary = text::truncate(error_summary(error), 120);
+        let message = if self.coordinator.is_offline() {
+            i18n::tr_args("error.offline_detail", &[("error", &summary)])
+        } else {
+            i18n::tr_args("error.list_dataflows_detail", &[("error", &summary)])
+        };
+        self.ui
+            .label(ids!(refresh_error_label))
+            .set_text(cx, &message);
+        // Starting the runtime only helps when no coordinator is reachable
+        self.ui.button(ids!(start_runtime_button)).set_visible(
+            cx,
+            cfg!(not(target_arch = "wasm32")) && self.coordinator.is_offline(),
+        );
+        bar.apply_over(cx, live! { height: 36 });
+        self.update_retry_countdown(cx);
+        self.ui.redraw(cx);
+    }
+
+    /// Update the time until the next automatic retry in the banner.
+    fn update_retry_countdown(&mut self, cx: &mut Cx) {
+        let text = match self.retry_at {
+            Some(at) => {
+                let secs = at.saturating_duration_since(Instant::now()).as_secs_f64();
+                format!("Retrying in {}s", secs.ceil() as u64)
+            }
+            None => String::new(),
+        };
+        if text != self.retry_text {
+            self.ui.label(ids!(retry_label)).set_text(cx, &text);
+            self.retry_text = text;
+            self.ui.redraw(cx);
+        }
+    }
+
+    /// Start the dora coordinator and daemon with `dora up`, then refresh.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start_runtime(&mut self, cx: &mut Cx) {
+        self.spawn_task(
+            cx,
+            &i18n::tr("task.start_runtime"),
+            TaskJob::StartRuntime,
+            |_| {
+                let result = execute_tool("dora_up", "start-runtime", &serde_json::json!({}));
+                if result.is_error {
+                    Err(error_summary(&result.content).to_string())
+                } else {
+                    Ok(())
+                }
+            },
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn refresh_traces(&mut self, cx: &mut Cx) {
+        tracing::debug!(target: "App", "refresh_traces called");
+        // Keep stored spans on screen until SigNoz answers again
+        if self.signoz_healthy != Some(false) {
+            self.ui.traces_panel(ids!(traces_panel)).set_loading(cx);
+        }
+        if let Some(telemetry) = &self.telemetry {
+            telemetry.request_traces(self.trace_query());
+        }
+    }
+
+    /// Fetch traces through the studio server (web build).
+    #[cfg(target_arch = "wasm32")]
+    fn refresh_traces(&mut self, cx: &mut Cx) {
+        self.ui.traces_panel(ids!(traces_panel)).set_loading(cx);
+        remote::submit(RemoteRequest::Traces(self.trace_query()));
+    }
+
+    /// The traces panel's filter and columns as a query.
+    fn trace_query(&self) -> crate::otlp::types::TraceQuery {
+        let panel = self.ui.traces_panel(ids!(traces_panel));
+        // A snapped run's window takes the place of the filter's time range
+        let mut query = panel.filter().to_query(logging::now_ms());
+        if let Some(run) = &self.snapped_run {
+            let (start_ms, end_ms) = run.window(logging::now_ms());
+            query.time_range = Some(crate::otlp::types::TimeRange { start_ms, end_ms });
+        }
+        query.attributes = panel.columns().attribute_keys();
+        query
+    }
+
+    /// Apply what the studio server answered (web build).
+    #[cfg(target_arch = "wasm32")]
+    fn handle_remote_response(&mut self, cx: &mut Cx, response: RemoteResponse) {
+        let detail = self.ui.dataflow_detail(ids!(dataflow_detail));
+        match response {
+            RemoteResponse::Dataflows(result) => self.dataflows_listed(cx, result),
+            RemoteResponse::Started(path, result) => self.dataflow_started(cx, &path, result),
+            RemoteResponse::Stopped(uuid, result) => {
+                self.dataflow_stopped(cx, &uuid, UndoKind::Stopped, result)
+            }
+            RemoteResponse::Destroyed(uuid, result) => {
+                self.dataflow_stopped(cx, &uuid, UndoKind::Destroyed, result)
+            }
+            RemoteResponse::Nodes(uuid, nodes) => {
+                if detail.uuid().as_deref() == Some(uuid.as_str()) {
+                    detail.set_nodes(cx, nodes);
+                    self.update_golden_signals(cx);
+                }
+            }
+            RemoteResponse::Logs(uuid, logs) => {
+                if detail.uuid().as_deref() == Some(uuid.as_str()) {
+                    detail.set_logs(cx, logs.clone());
+                    // The web build has no local storage to spill to
+                    detail.take_spilled_logs();
+                }
+                if self.summarize_pending.as_deref() == Some(uuid.as_str()) {
+                    self.summarize_pending = None;
+                    self.summarize_logs(cx, &uuid, logs);
+                }
+            }
+            RemoteResponse::Traces(Ok(spans)) => {
+                tracing::debug!(target: "App", "Received {} trace spans", spans.len());
+                self.traces_loaded_once = true;
+                let panel = self.ui.traces_panel(ids!(traces_panel));
+                panel.set_stale(cx, None);
+                panel.set_spans(cx, spans);
+            }
+            RemoteResponse::Traces(Err(e)) => {
+                self.ui.traces_panel(ids!(traces_panel)).set_error(cx, &e);
+                self.notify(
+                    cx,
+                    Severity::Warning,
+                    &i18n::tr("notify.trace_query_failed"),
+                    &e,
+                );
+            }
+        }
+    }
+
+    /// Whether the traces panel is in live mode and there is something to
+    /// tail: SigNoz is reachable and no run window is snapped.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn live_tailing(&self) -> bool {
+        self.signoz_available
+            && self.signoz_healthy != Some(false)
+            && self.snapped_run.is_none()
+            && self.ui.traces_panel(ids!(traces_panel)).is_live()
+    }
+
+    /// Ask SigNoz for spans newer than the newest one shown, and for those
+    /// slightly older that may not have been stored yet when it was fetched.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn tail_traces(&mut self, cx: &mut Cx) {
+        let panel = self.ui.traces_panel(ids!(traces_panel));
+        let Some(newest) = panel.newest_start_ms() else {
+            // Nothing to continue from yet
+            self.refresh_traces(cx);
+            return;
+        };
+        let mut query = panel.filter().to_query(logging::now_ms());
+        query.time_range = Some(crate::otlp::types::TimeRange {
+            start_ms: newest.saturating_sub(LIVE_TAIL_OVERLAP_MS),
+            end_ms: logging::now_ms(),
+        });
+        query.attributes = panel.columns().attribute_keys();
+        if let Some(telemetry) = &self.telemetry {
+            telemetry.request_trace_tail(query);
+        }
+    }
+
+    /// Show the SigNoz connection status, with the last health check error.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_telemetry_status(&mut self, cx: &mut Cx, error: Option<&str>) {
+        let status = self
+            .telemetry
+            .as_ref()
+            .map_or(ConnectionStatus::Unknown, |t| t.status());
+        let label = match error {
+            Some(e) => i18n::tr_args(
+                "status.signoz_error",
+                &[
+                    ("status", &status.label()),
+                    ("error", &text::truncate(e, 40)),
+                ],
+            ),
+            None => i18n::tr_args("status.signoz", &[("status", &status.label())]),
+        };
+        self.ui
+            .status_bar(ids!(status_bar))
+            .set_telemetry(cx, &label, status.is_healthy());
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn handle_signoz_response(&mut self, cx: &mut Cx, response: crate::otlp::SignozResponse) {
+        match response {
+            crate::otlp::SignozResponse::Capabilities(capabilities) => {
+                self.apply_telemetry_capabilities(cx, capabilities);
+            }
+            crate::otlp::SignozResponse::HealthOk => {
+                tracing::debug!(target: "App", "SigNoz connected");
+                self.update_telemetry_status(cx, None);
+                if self.signoz_healthy == Some(false) {
+                    self.notify(
+                        cx,
+                        Severity::Success,
+                        &i18n::tr("notify.signoz_connection_restored"),
+                        "",
+                    );
+                }
+                self.signoz_healthy = Some(true);
+            }
+            crate::otlp::SignozResponse::HealthError(e) => {
+                tracing::warn!(target: "App", "SigNoz health error: {}", e);
+                self.update_telemetry_status(cx, Some(&e));
+                self.show_stored_spans(cx);
+                if self.signoz_healthy != Some(false) {
+                    self.notify(
+                        cx,
+                        Severity::Warning,
+                        &i18n::tr("notify.signoz_unreachable"),
+                        &e,
+                    );
+                }
+                if self.signoz_healthy == Some(true) {
+                    self.notify_desktop(&i18n::tr("notify.signoz_connection_lost"), &e);
+                }
+                self.signoz_healthy = Some(false);
+            }
+            crate::otlp::SignozResponse::Traces(spans) => {
+                tracing::debug!(target: "App", "Received {} trace spans", spans.len());
+                self.traces_loaded_once = true;
+                self.ingest_spans(cx, &spans);
+                self.ui.traces_panel(ids!(traces_panel)).set_stale(cx, None);
+                self.show_spans(cx, spans);
+            }
+            crate::otlp::SignozResponse::TraceTail(spans) => {
+                let panel = self.ui.traces_panel(ids!(traces_panel));
+                panel.set_stale(cx, None);
+                panel.prepend_spans(cx, spans);
+                // Alert windows need the recent spans, not just the new ones
+                self.ingest_spans(cx, &panel.spans());
+            }
+            crate::otlp::SignozResponse::TracesError(e) => {
+                tracing::warn!(target: "App", "Traces query error: {}", e);
+                if !self.show_stored_spans(cx) {
+                    self.pending_spans.clear();
+                    self.ui.traces_panel(ids!(traces_panel)).set_error(cx, &e);
+                }
+                self.notify(
+                    cx,
+                    Severity::Warning,
+                    &i18n::tr("notify.trace_query_failed"),
+                    &e,
+                );
+            }
+        }
+    }
+
+    /// Hide what the telemetry backend cannot serve. Trace queries are all
+    /// the app sends through the bridge, so a backend without traces (e.g.
+    /// Elasticsearch with no traces index) leaves nothing to ask it.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn apply_telemetry_capabilities(
+        &mut self,
+        cx: &mut Cx,
+        capabilities: crate::otlp::Capabilities,
+    ) {
+        if capabilities.traces {
+            return;
+        }
+        tracing::info!(target: "App", "Telemetry backend serves no traces, hiding the traces tab");
+        self.signoz_available = false;
+        self.ui.button(ids!(tab_traces)).set_visible(cx, false);
+        if self.active_panel == ActivePanel::Traces {
+            self.switch_to_panel(cx, ActivePanel::Dataflows);
+        }
+    }
+
+    /// Feed spans fetched from SigNoz to alerts, node liveness and local
+    /// storage.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn ingest_spans(&mut self, cx: &mut Cx, spans: &[crate::otlp::types::Span]) {
+        // Alerts track live telemetry, not a snapped run window
+        if self.snapped_run.is_none() {
+            self.check_alerts(cx);
+            for span in spans {
+                self.liveness
+                    .record_node(&span.service_name, span.start_time_ms + span.duration_ms);
+            }
+            self.store_spans(spans);
+        }
+        self.traces_live_ms = Some(logging::now_ms());
+    }
+
+    /// Write spans fetched from SigNoz to local storage so they can still be
+    /// shown while SigNoz is unreachable, skipping those stored already.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn store_spans(&mut self, spans: &[crate::otlp::types::Span]) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        let stored = self.stored_spans.get_or_insert_with(|| {
+            StoredSpans::load(storage).unwrap_or_else(|e| {
+                tracing::warn!(target: "App", "Failed to read stored spans: {}", e);
+                StoredSpans::default()
+            })
+        });
+        let new = stored.unstored(spans);
+        if new.is_empty() {
+            return;
+        }
+        match storage.append(SPANS_TABLE, &new) {
+            Ok(()) => stored.insert(&new),
+            Err(e) => tracing::warn!(target: "App", "Failed to store spans: {}", e),
+        }
+    }
+
+    /// Show the most recent stored spans the traces filter lets through,
+    /// marked as stale, while SigNoz is unreachable. Returns false when there
+    /// are none to show.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn show_stored_spans(&mut self, cx: &mut Cx) -> bool {
+        let Some(storage) = &self.storage else {
+            return false;
+        };
+        let filter = self.ui.traces_panel(ids!(traces_panel)).filter();
+        let now = logging::now_ms();
+        let stored = match filter.last_minutes {
+            Some(minutes) => storage.read::<crate::otlp::types::Span>(
+                SPANS_TABLE,
+                now.saturating_sub(minutes * 60_000),
+                now + 1,
+            ),
+            None => storage.latest::<crate::otlp::types::Span>(SPANS_TABLE, OFFLINE_SCANNED_SPANS),
+        };
+        let mut spans = match stored {
+            Ok(mut spans) => {
+                spans.retain(|s| filter.matches(s, now));
+                spans.drain(..spans.len().saturating_sub(OFFLINE_SPANS));
+                spans
+            }
+            Err(e) => {
+                tracing::warn!(target: "App", "Failed to read stored spans: {}", e);
+                return false;
+            }
+        };
+        if spans.is_empty() {
+            return false;
+        }
+        let since = self
+            .traces_live_ms
+            .or_else(|| spans.last().map(|s| s.start_time_ms));
+        spans.reverse();
+        self.show_spans(cx, spans);
+        self.ui
+            .traces_panel(ids!(traces_panel))
+            .set_stale(cx, Some(&offline_text(since, logging::now_ms())));
+        true
+    }
+
+    /// Replace the spans in the traces panel, showing the first chunk now
+    /// and the rest over the following frames.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn show_spans(&mut self, cx: &mut Cx, mut spans: Vec<crate::otlp::types::Span>) {
+        self.pending_spans = spans.split_off(SPANS_PER_FRAME.min(spans.len()));
+        self.ui
+            .traces_panel(ids!(traces_panel))
+            .set_spans(cx, spans);
+        self.focus_linked_trace(cx);
+    }
+
+    /// Add the next chunk of a large trace result to the traces panel.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn show_pending_spans(&mut self, cx: &mut Cx) {
+        if self.pending_spans.is_empty() {
+            return;
+        }
+        let rest = self
+            .pending_spans
+            .split_off(SPANS_PER_FRAME.min(self.pending_spans.len()));
+        let chunk = std::mem::replace(&mut self.pending_spans, rest);
+        self.ui
+            .traces_panel(ids!(traces_panel))
+            .append_spans(cx, chunk);
+        self.focus_linked_trace(cx);
+    }
+
+    /// Show what a `dora-studio://` link points at, fetching it first if it
+    /// is not on screen yet.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_link(&mut self, cx: &mut Cx, link: DeepLink) {
+        tracing::info!(target: "App", "Opening {}", link.to_url());
+        match link {
+            DeepLink::Trace(trace_id) => {
+                let loaded = self.traces_loaded_once;
+                self.show_traces(cx);
+                if loaded
+                    && self.pending_spans.is_empty()
+                    && self
+                        .ui
+                        .traces_panel(ids!(traces_panel))
+                        .focus_trace(cx, &trace_id)
+                {
+                    return;
+                }
+                self.linked_trace = Some(trace_id);
+                // The first visit to the panel fetches traces already
+                if loaded {
+                    self.refresh_traces(cx);
+                }
+            }
+            DeepLink::Dataflow(uuid) => {
+                self.switch_to_panel(cx, ActivePanel::Dataflows);
+                self.linked_dataflow = Some(uuid);
+                self.open_linked_dataflow(cx);
+                if self.linked_dataflow.is_some() {
+                    // Listing is synchronous here, so a miss is final
+                    self.refresh_dataflows(cx);
+                }
+                if let Some(uuid) = self.linked_dataflow.take() {
+                    self.notify(
+                        cx,
+                        Severity::Info,
+                        &i18n::tr("notify.dataflow_not_found"),
+                        &i18n::tr_args("notify.dataflow_not_found_message", &[("uuid", &uuid)]),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Focus the linked trace once every span of the last result is shown,
+    /// or say it was not found.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn focus_linked_trace(&mut self, cx: &mut Cx) {
+        if !self.pending_spans.is_empty() {
+            return;
+        }
+        let Some(trace_id) = self.linked_trace.take() else {
+            return;
+        };
+        if !self
+            .ui
+            .traces_panel(ids!(traces_panel))
+            .focus_trace(cx, &trace_id)
+        {
+            self.notify(
+                cx,
+                Severity::Info,
+                &i18n::tr("notify.trace_not_found"),
+                &i18n::tr_args(
+                    "notify.trace_not_found_message",
+                    &[("trace", &text::truncate_middle(&trace_id, 16))],
+                ),
+            );
+        }
+    }
+
+    /// Select and open the linked dataflow if the table lists it.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_linked_dataflow(&mut self, cx: &mut Cx) {
+        let Some(uuid) = self.linked_dataflow.clone() else {
+            return;
+        };
+        let table = self.ui.dataflow_table(ids!(dataflow_table));
+        if table.dataflow_by_uuid(&uuid).is_none() {
+            return;
+        }
+        self.linked_dataflow = None;
+        table.select(cx, &uuid);
+        self.show_dataflow_detail(cx, &uuid);
+    }
+
+    /// Show the most recent stored node samples, marked as stale, while the
+    /// coordinator is unreachable.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn show_stored_metrics(&mut self, cx: &mut Cx) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        let samples = storage
+            .latest::<metrics::NodeMetrics>(METRICS_TABLE, OFFLINE_METRIC_SAMPLES)
+            .unwrap_or_else(|e| {
+                tracing::warn!(target: "App", "Failed to read stored metrics: {}", e);
+                Vec::new()
+            });
+        let since = samples.last().map(|s| s.timestamp_ms);
+        if self.metrics_history.is_empty() {
+            for sample in samples {
+                self.metrics_history.push(sample);
+            }
+            self.update_node_metrics(cx);
+        }
+        self.ui
+            .node_metrics_panel(ids!(node_metrics_panel))
+            .set_stale(cx, Some(&offline_text(since, logging::now_ms())));
+    }
+
+    fn start_dataflow(&mut self, cx: &mut Cx, path: &str) {
+        let path = path.trim();
+        if path.is_empty() || !self.require_coordinator(cx, "coordinator_action.start") {
+            return;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let env = self.settings.dataflow_env(path);
+            let (job, path) = (TaskJob::StartDataflow(path.to_string()), path.to_string());
+            self.spawn_dora(cx, &i18n::tr("task.start_dataflow"), job, move |dora| {
+                match dora.check(&path) {
+                    Ok(problems) if !problems.is_empty() => {
+                        return StartOutcome::CheckFailed(problems);
+                    }
+                    Ok(_) => {}
+                    // dora may only be reachable through the coordinator; let the start report errors
+                    Err(e) => tracing::warn!(target: "App", "Starting without dora check: {}", e),
+                }
+                let result =
+                    env::prepare_start(&path, &env).and_then(|start_path| dora.start(&start_path));
+                StartOutcome::Started(result)
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        remote::submit(RemoteRequest::Start(
+            path.to_string(),
+            self.settings.dataflow_env(path),
+        ));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_new_project(&mut self, cx: &mut Cx) {
+        self.ui
+            .new_project_wizard(ids!(new_project_wizard))
+            .open(cx);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn open_new_project(&mut self, cx: &mut Cx) {
+        self.notify(
+            cx,
+            Severity::Info,
+            &i18n::tr("notify.new_projects_unavailable"),
+            &i18n::tr("notify.new_projects_unavailable_message"),
+        );
+    }
+
+    /// Check the coordinator and the telemetry backend again now, without
+    /// waiting for the refresh schedule.
+    fn retest_connections(&mut self, cx: &mut Cx) {
+        tracing::info!(target: "App", "Retesting connections");
+        self.refresh_backoff.succeed();
+        self.retry_at = None;
+        self.refresh_dataflows(cx);
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(telemetry) = &self.telemetry {
+            telemetry.request_health_check();
+        }
+    }
+
+    /// Open the settings file, where the coordinator address and telemetry
+    /// backend are configured. Saving it reconnects the telemetry backend.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn edit_connection(&mut self, cx: &mut Cx) {
+        let Some(path) = Settings::path() else {
+            self.notify(
+                cx,
+                Severity::Warning,
+                &i18n::tr("notify.no_settings_file"),
+                &i18n::tr("notify.no_settings_file_message"),
+            );
+            return;
+        };
+        if !path.exists() {
+            if let Err(e) = self.settings.save() {
+                self.notify(
+                    cx,
+                    Severity::Error,
+                    &i18n::tr("notify.failed_to_create_settings"),
+                    &e,
+                );
+                return;
+            }
+        }
+        match platform::open_path(&path) {
+            Ok(()) => self.notify(
+                cx,
+                Severity::Info,
+                &i18n::tr("notify.editing_connection_settings"),
+                &i18n::tr("notify.editing_connection_settings_message"),
+            ),
+            Err(e) => self.notify(
+                cx,
+                Severity::Warning,
+                &i18n::tr("notify.failed_to_open_settings"),
+                &e,
+            ),
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn edit_connection(&mut self, cx: &mut Cx) {
+        self.notify(
+            cx,
+            Severity::Info,
+            &i18n::tr("notify.connection_settings_unavailable"),
+            &i18n::tr("notify.connection_settings_unavailable_message"),
+        );
+    }
+
+    /// Open the report from the last crash and close the recovery dialog.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_crash_report(&mut self, cx: &mut Cx) {
+        self.ui.crash_dialog(ids!(crash_dialog)).close(cx);
+        let Some(path) = self.crash_report.take() else {
+            return;
+        };
+        if let Err(e) = platform::open_path(&path) {
+            self.notify(
+                cx,
+                Severity::Warning,
+                &i18n::tr("notify.failed_to_open_crash_report"),
+                &e,
+            );
+        }
+    }
+
+    /// Scaffold the project and open its dataflow, ready to start.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn create_project(&mut self, cx: &mut Cx, spec: &ProjectSpec) {
+        let wizard = self.ui.new_project_wizard(ids!(new_project_wizard));
+        let dataflow = match scaffold::create(spec) {
+            Ok(dataflow) => dataflow,
+            Err(e) => return wizard.set_error(cx, &e),
+        };
+        wizard.close(cx);
+        let path = dataflow.display().to_string();
+        tracing::info!(target: "App", "Created project {}", path);
+        self.switch_to_panel(cx, ActivePanel::Dataflows);
+        self.ui
+            .text_input(ids!(start_path_input))
+            .set_text(cx, &path);
+        match platform::open_path(&dataflow) {
+            Ok(()) => self.notify(
+                cx,
+                Severity::Success,
+                &i18n::tr("notify.project_created"),
+                &i18n::tr_args(
+                    "notify.press_start_message",
+                    &[("path", &text::truncate(&path, 60))],
+                ),
+            ),
+            Err(e) => self.notify(
+                cx,
+                Severity::Warning,
+                &i18n::tr("notify.project_created"),
+                &e,
+            ),
+        }
+    }
+
+    /// Edit the environment variables of the dataflow at `path`.
+    fn open_env_editor(&mut self, cx: &mut Cx, path: &str) {
+        let path = path.trim();
+        if path.is_empty() {
+            return self.notify(
+                cx,
+                Severity::Info,
+                &i18n::tr("notify.no_dataflow_to_configure"),
+                &i18n::tr("notify.no_dataflow_to_configure_message"),
+            );
+        }
+        self.ui.dataflow_detail(ids!(dataflow_detail)).close(cx);
+        self.ui
+            .env_editor(ids!(env_editor))
+            .open(cx, path, self.settings.dataflow_env(path));
+    }
+
+    /// Add the variables of a `.env` file to those of the dataflow at `path`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_env_file(&mut self, cx: &mut Cx, path: &str, file: &str) {
+        let loaded = match env::load_dotenv(file) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                return self.notify(
+                    cx,
+                    Severity::Error,
+                    &i18n::tr("notify.failed_to_load_env_file"),
+                    &e,
+                )
+            }
+        };
+        let mut vars = self.settings.dataflow_env(path);
+        for (key, value) in &loaded {
+            env::set_var(&mut vars, key, value);
+        }
+        self.settings.set_dataflow_env(path, vars.clone());
+        self.save_settings();
+        self.ui.env_editor(ids!(env_editor)).set_env(cx, path, vars);
+        self.notify(
+            cx,
+            Severity::Success,
+            &i18n::tr("notify.environment_loaded"),
+            &i18n::tr_args(
+                "notify.environment_loaded_message",
+                &[
+                    ("count", &loaded.len().to_string()),
+                    ("file", &text::truncate(file, 60)),
+                ],
+            ),
+        );
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn load_env_file(&mut self, cx: &mut Cx, _path: &str, _file: &str) {
+        self.notify(
+            cx,
+            Severity::Info,
+            &i18n::tr("notify.env_files_unavailable"),
+            &i18n::tr("notify.env_files_unavailable_message"),
+        );
+    }
+
+    /// Record a started dataflow for undo and the recent list, or report why
+    /// it did not start.
+    fn dataflow_started(
+        &mut self,
+        cx: &mut Cx,
+        path: &str,
+        result: Result<Option<String>, String>,
+    ) {
+        match result {
+            Err(e) => {
+                let title = i18n::tr("error.start_dataflow");
+                self.notify(cx, Severity::Error, &title, &e)
+            }
+            Ok(uuid) => {
+                self.settings.push_recent_dataflow(path);
+                self.save_settings();
+                if let Some(uuid) = uuid {
+                    self.undo_stack.record_start(
+                        &uuid,
+                        StartSpec {
+                            dataflow_path: path.to_string(),
+                            env: self.settings.dataflow_env(path),
+                        },
+                    );
+                }
+            }
+        }
+        self.update_recent_dropdown(cx);
+
+        // Refresh the table after starting
+        self.refresh_dataflows(cx);
+    }
+
+    /// Run `dora check` on the dataflow and report the result.
+    /// Show what writing a dataflow from the chat to the start path would
+    /// change.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn preview_agent_dataflow(&mut self, cx: &mut Cx, yaml: &str) {
+        let input = self.ui.text_input(ids!(start_path_input)).text();
+        let path = match agent_dataflow_path(&input) {
+            Ok(path) => path,
+            Err(e) => {
+                return self.notify(
+                    cx,
+                    Severity::Warning,
+                    &i18n::tr("notify.dataflow_not_written"),
+                    &e,
+                )
+            }
+        };
+        let current = std::fs::read_to_string(&path).unwrap_or_default();
+        self.ui
+            .chat_screen(ids!(chat_screen))
+            .show_dataflow_preview(
+                cx,
+                &path.display().to_string(),
+                yaml,
+                &text::line_diff(&current, yaml),
+            );
+    }
+
+    /// Write a confirmed dataflow from the chat and check it, ready to start.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write_agent_dataflow(&mut self, cx: &mut Cx, path: &str, yaml: &str) {
+        let target = std::path::Path::new(path);
+        let written = target
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(target, format!("{}\n", yaml.trim_end())));
+        if let Err(e) = written {
+            let message = format!("{}: {}", path, e);
+            return self.notify(
+                cx,
+                Severity::Error,
+                &i18n::tr("notify.failed_to_write_dataflow"),
+                &message,
+            );
+        }
+        self.ui
+            .text_input(ids!(start_path_input))
+            .set_text(cx, path);
+        self.notify(
+            cx,
+            Severity::Success,
+            &i18n::tr("notify.dataflow_written"),
+            &i18n::tr_args(
+                "notify.press_start_message",
+                &[("path", &text::truncate(path, 60))],
+            ),
+        );
+        self.validate_dataflow(cx, path);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn validate_dataflow(&mut self, cx: &mut Cx, path: &str) {
+        let path = path.trim();
+        if path.is_empty() {
+            return;
+        }
+        let (job, path) = (TaskJob::CheckDataflow(path.to_string()), path.to_string());
+        self.spawn_dora(cx, &i18n::tr("task.check_dataflow"), job, move |dora| {
+            dora.check(&path)
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn dataflow_checked(
+        &mut self,
+        cx: &mut Cx,
+        path: &str,
+        result: Result<Vec<CheckDiagnostic>, String>,
+    ) {
+        match result {
+            Ok(problems) if problems.is_empty() => self.notify(
+                cx,
+                Severity::Info,
+                &i18n::tr("notify.dataflow_check_passed"),
+                &text::truncate(path, 60),
+            ),
+            Ok(problems) => self.notify_check_problems(cx, path, &problems),
+            Err(e) => self.notify(
+                cx,
+                Severity::Error,
+                &i18n::tr("notify.could_not_run_dora_check"),
+                &e,
+            ),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn notify_check_problems(&mut self, cx: &mut Cx, path: &str, problems: &[CheckDiagnostic]) {
+        let details: Vec<String> = problems
+            .iter()
+            .take(DIAGNOSTICS_SHOWN)
+            .map(CheckDiagnostic::describe)
+            .collect();
+        self.notify(
+            cx,
+            Severity::Error,
+            &i18n::tr_args(
+                "notify.check_failed",
+                &[("path", &text::truncate(path, 60))],
+            ),
+            &details.join("\n"),
+        );
+    }
+
+    fn update_recent_dropdown(&mut self, cx: &mut Cx) {
+        let mut labels = vec![i18n::tr("header.recent")];
+        labels.extend(self.settings.recent_dataflows.iter().cloned());
+        let dropdown = self.ui.drop_down(ids!(recent_dropdown));
+        dropdown.set_labels(cx, labels);
+        dropdown.set_selected_item(cx, 0);
+    }
+
+    fn export_config(&mut self, cx: &mut Cx, path: &str) {
+        let path = match bundle_path(path) {
+            Ok(path) => path,
+            Err(e) => return self.set_bundle_status(cx, &e),
+        };
+        let bundle = ConfigBundle::export(&self.settings, logging::now_ms());
+        let write = move || {
+            bundle
+                .write_to(&path)
+                .map(|()| format!("Exported settings to {}", path.display()))
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.spawn_task(
+                cx,
+                &i18n::tr("task.export_settings"),
+                TaskJob::ExportConfig,
+                move |_| write(),
+            );
+            self.set_bundle_status(cx, "Exporting settings…");
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let status = write().unwrap_or_else(|e| e);
+            self.set_bundle_status(cx, &status);
+        }
+    }
+
+    fn set_bundle_status(&mut self, cx: &mut Cx, status: &str) {
+        tracing::info!(target: "App", "{}", status);
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_bundle_status(cx, status);
+    }
+
+    /// List the settings an import would conflict on, without applying it.
+    fn preview_config_import(&mut self, cx: &mut Cx, path: &str) {
+        let status = match bundle_path(path).and_then(|p| ConfigBundle::read_from(&p)) {
+            Ok(bundle) => {
+                let conflicts = bundle.conflicts(&self.settings);
+                if conflicts.is_empty() {
+                    "No conflicts; import will not change any settings".to_string()
+                } else {
+                    let lines: Vec<String> = conflicts
+                        .iter()
+                        .map(|c| {
+                            format!(
+                                "{}: {} -> {}",
+                                c.key,
+                                text::truncate(&c.local.to_string(), 60),
+                                text::truncate(&c.incoming.to_string(), 60)
+                            )
+                        })
+                        .collect();
+                    format!("{} conflict(s)\n{}", conflicts.len(), lines.join("\n"))
+                }
+            }
+            Err(e) => e,
+        };
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_bundle_status(cx, &status);
+    }
+
+    /// Convert a Grafana dashboard and save it to the dashboards directory.
+    fn import_grafana_dashboard(&mut self, cx: &mut Cx, path: &str) {
+        let path = path.trim();
+        let status = if path.is_empty() {
+            "Enter the path of a Grafana dashboard JSON file".to_string()
+        } else {
+            import_grafana(path).unwrap_or_else(|e| e)
+        };
+        tracing::info!(target: "App", "{}", status);
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_grafana_status(cx, &status);
+    }
+
+    fn import_config(&mut self, cx: &mut Cx, path: &str, resolution: Resolution) {
+        let result = bundle_path(path)
+            .and_then(|p| ConfigBundle::read_from(&p))
+            .and_then(|bundle| bundle.apply(&self.settings, resolution));
+
+        let status = match result {
+            Ok((settings, summary)) => {
+                self.settings = settings;
+                self.save_settings();
+                logging::set_level(self.settings.log_level);
+                self.ui
+                    .diagnostics_panel(ids!(diagnostics_panel))
+                    .set_level(cx, self.settings.log_level);
+                self.update_recent_dropdown(cx);
+                #[cfg(not(target_arch = "wasm32"))]
+                self.update_saved_trace_queries(cx);
+                self.update_prompt_templates(cx);
+                self.update_llm_backend(cx);
+                self.ui
+                    .chat_screen(ids!(chat_screen))
+                    .set_model(cx, self.settings.chat_model.as_deref().unwrap_or(""));
+                self.ui
+                    .dataflow_table(ids!(dataflow_table))
+                    .set_sort(cx, self.settings.dataflow_sort);
+                self.load_shortcuts(cx);
+                summary.describe()
+            }
+            Err(e) => e,
+        };
+        tracing::info!(target: "App", "{}", status);
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_bundle_status(cx, &status);
+    }
+
+    /// Import a recorded capture into storage and show its spans in the
+    /// traces panel.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn import_recording(&mut self, cx: &mut Cx, path: &str) {
+        let diagnostics = self.ui.diagnostics_panel(ids!(diagnostics_panel));
+        let path = path.trim().to_string();
+        if path.is_empty() {
+            diagnostics.set_recording_status(cx, "Enter the path of a capture file");
+            return;
+        }
+        let Some(storage) = self.storage.clone() else {
+            diagnostics.set_recording_status(cx, "Local storage is not available");
+            return;
+        };
+
+        diagnostics.set_recording_status(cx, &format!("Importing {}…", path));
+        let job = TaskJob::ImportRecording(path.clone());
+        self.spawn_task(cx, &i18n::tr("task.import_capture"), job, move |ctx| {
+            ctx.progress(None, &path);
+            let summary = crate::storage::import_capture(&storage, std::path::Path::new(&path))
+                .map_err(|e| format!("Failed to import {}: {}", path, e))?;
+            ctx.check_cancelled()?;
+            let spans = match summary.range {
+                Some((start_ms, end_ms)) => storage
+                    .read::<crate::otlp::types::Span>(crate::storage::SPANS_TABLE, start_ms, end_ms)
+                    .unwrap_or_else(|e| {
+                        tracing::warn!(target: "App", "Failed to read imported spans: {}", e);
+                        Vec::new()
+                    }),
+                None => Vec::new(),
+            };
+            Ok((summary, spans))
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn recording_imported(
+        &mut self,
+        cx: &mut Cx,
+        path: &str,
+        summary: crate::storage::ImportSummary,
+        spans: Vec<crate::otlp::types::Span>,
+    ) {
+        let status = summary.describe();
+        tracing::info!(target: "App", "{} from {}", status, path);
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_recording_status(cx, &status);
+        if !spans.is_empty() {
+            self.show_spans(cx, spans);
+            self.switch_to_panel(cx, ActivePanel::Traces);
+        }
+    }
+
+    /// Run a query against local storage on a background task.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start_query(&mut self, cx: &mut Cx, sql: &str) {
+        let panel = self.ui.query_panel(ids!(query_panel));
+        let sql = sql.trim().to_string();
+        if sql.is_empty() {
+            panel.set_status(cx, &i18n::tr("query.enter_query"));
+            return;
+        }
+        let Some(storage) = self.storage.clone() else {
+            panel.set_status(cx, &i18n::tr("query.storage_unavailable"));
+            return;
+        };
+
+        self.settings.push_query_history(&sql);
+        self.save_settings();
+        panel.set_history(cx, &self.settings.query_history);
+        panel.set_status(cx, &i18n::tr("query.running"));
+        self.spawn_task(
+            cx,
+            &i18n::tr("task.run_query"),
+            TaskJob::RunQuery,
+            move |_| {
+                let started = Instant::now();
+                // Query errors are shown in the panel rather than as a failed task
+                let outcome = run_query(&storage, &sql)
+                    .map(|result| (result, started.elapsed().as_millis() as u64))
+                    .map_err(|e| e.to_string());
+                Ok(outcome)
+            },
+        );
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn query_finished(&mut self, cx: &mut Cx, outcome: Result<(QueryResult, u64), String>) {
+        let panel = self.ui.query_panel(ids!(query_panel));
+        match outcome {
+            Err(e) => panel.set_status(cx, &e),
+            Ok((result, elapsed_ms)) => {
+                let elapsed = elapsed_ms.to_string();
+                let mut status = match result.rows.len() {
+                    1 => i18n::tr_args("query.result_row", &[("ms", &elapsed)]),
+                    n => i18n::tr_args(
+                        "query.result_rows",
+                        &[("count", &n.to_string()), ("ms", &elapsed)],
+                    ),
+                };
+                if result.columns.len() > MAX_COLUMNS {
+                    status.push_str(&i18n::tr_args(
+                        "query.columns_truncated",
+                        &[
+                            ("shown", &MAX_COLUMNS.to_string()),
+                            ("total", &result.columns.len().to_string()),
+                        ],
+                    ));
+                }
+                panel.set_status(cx, &status);
+                panel.set_result(cx, result);
+            }
+        }
+    }
+
+    /// Write the rows shown in the Query panel to a CSV file.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_query_results(&mut self, cx: &mut Cx, path: &str) {
+        let panel = self.ui.query_panel(ids!(query_panel));
+        let result = panel.result();
+        if result.columns.is_empty() {
+            panel.set_status(cx, &i18n::tr("query.run_before_export"));
+            return;
+        }
+        let path = match query_export_path(path) {
+            Ok(path) => path,
+            Err(e) => return panel.set_status(cx, &e),
+        };
+
+        panel.set_status(cx, &i18n::tr("query.exporting"));
+        let job = TaskJob::ExportQueryResults;
+        self.spawn_task(cx, &i18n::tr("task.export_query_results"), job, move |_| {
+            result
+                .write_csv(&path)
+                .map(|()| {
+                    i18n::tr_args(
+                        "query.exported",
+                        &[
+                            ("count", &result.rows.len().to_string()),
+                            ("path", &path.display().to_string()),
+                        ],
+                    )
+                })
+                .map_err(|e| write_error(&path, e))
+        });
+    }
+
+    /// Show the retention overrides and current disk use in settings.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_retention(&mut self, cx: &mut Cx) {
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_retention(
+                cx,
+                &retention::format_retention(&self.settings.retention_hours),
+            );
+        self.update_storage_usage(cx);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn apply_retention(&mut self, cx: &mut Cx, text: &str) {
+        let overrides = match retention::parse_retention(text) {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                self.notify(
+                    cx,
+                    Severity::Error,
+                    &i18n::tr("notify.invalid_retention"),
+                    &e,
+                );
+                return;
+            }
+        };
+        self.settings.retention_hours = overrides;
+        self.save_settings();
+        self.load_retention(cx);
+        self.prune_storage(cx);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_storage_usage(&mut self, cx: &mut Cx) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        let text = match storage.usage() {
+            Ok(usage) => retention::describe_usage(&usage),
+            Err(e) => i18n::tr_args("error.storage_usage", &[("error", &e.to_string())]),
+        };
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_storage_usage(cx, &text);
+    }
+
+    /// Apply the retention settings to local storage on a background task.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn prune_storage(&mut self, cx: &mut Cx) {
+        let Some(storage) = self.storage.clone() else {
+            return;
+        };
+        if self
+            .task_jobs
+            .values()
+            .any(|job| matches!(job, TaskJob::PruneStorage))
+        {
+            return;
+        }
+        self.pruned_at = Some(Instant::now());
+        let retention = retention::retention_hours(&self.settings.retention_hours);
+        self.spawn_task(
+            cx,
+            &i18n::tr("task.prune_storage"),
+            TaskJob::PruneStorage,
+            move |_| {
+                storage
+                    .prune(&retention, logging::now_ms())
+                    .map_err(|e| e.to_string())
+            },
+        );
+    }
+
+    /// Run a whitelisted dora subcommand typed into the Diagnostics console.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run_dora_cli(&mut self, cx: &mut Cx, line: &str) {
+        let diagnostics = self.ui.diagnostics_panel(ids!(diagnostics_panel));
+        let argv = dora_cli::parse_command_line(line)
+            .and_then(|(subcommand, args)| dora_cli::validate(&subcommand, &args));
+        let argv = match argv {
+            Ok(argv) => argv,
+            Err(e) => return diagnostics.set_cli_output(cx, &e),
+        };
+        let name = text::truncate(&format!("dora {}", argv.join(" ")), 40);
+        diagnostics.set_cli_output(
+            cx,
+            &i18n::tr_args("diagnostics.cli_running", &[("command", &name)]),
+        );
+        self.spawn_task(cx, &name, TaskJob::DoraCli, move |_| {
+            let limits = Limits::register("dora_cli", tools::tool_timeout("dora_cli"));
+            dora_cli::run(&argv[0], &argv[1..], &limits).map_err(String::from)
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn apply_dora_target(&mut self, cx: &mut Cx, path: &str, ssh_host: &str) {
+        let ssh_host = ssh_host.trim();
+        if let Err(e) = tools::set_ssh_host(Some(ssh_host)) {
+            self.notify(
+                cx,
+                Severity::Error,
+                &i18n::tr("notify.invalid_ssh_host"),
+                &e,
+            );
+            return;
+        }
+        let path = path.trim();
+        self.settings.dora_path = (!path.is_empty()).then(|| path.to_string());
+        self.settings.ssh_host = (!ssh_host.is_empty()).then(|| ssh_host.to_string());
+        self.save_settings();
+        tools::set_dora_path(self.settings.dora_path.as_deref());
+        self.detect_dora_version(cx);
+        self.refresh_dataflows(cx);
+    }
+
+    /// Run `dora --version` in the background to show it in the status bar.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn detect_dora_version(&mut self, cx: &mut Cx) {
+        self.ui
+            .status_bar(ids!(status_bar))
+            .set_dora(cx, &i18n::tr("status.dora_detecting"), None);
+        let job = TaskJob::DetectDoraVersion;
+        self.spawn_task(cx, &i18n::tr("task.detect_dora_version"), job, |_| {
+            version::detect()
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn dora_version_detected(&mut self, cx: &mut Cx, version: DoraVersion) {
+        let target = tools::ssh_target();
+        let host = target
+            .as_ref()
+            .map(|target| format!(" on {}", target))
+            .unwrap_or_default();
+        tracing::info!(target: "App", "Using dora {}{}", version, host);
+        let version_text = version.to_string();
+        let dora = match &target {
+            Some(target) => i18n::tr_args(
+                "status.dora_on_host",
+                &[("version", &version_text), ("host", &target.to_string())],
+            ),
+            None => i18n::tr_args("status.dora", &[("version", &version_text)]),
+        };
+        let status_bar = self.ui.status_bar(ids!(status_bar));
+        if version.is_supported() {
+            status_bar.set_dora(cx, &dora, Some(true));
+            return;
+        }
+        status_bar.set_dora(
+            cx,
+            &i18n::tr_args(
+                "status.dora_outdated",
+                &[("dora", &dora), ("min", &MIN_DORA_VERSION.to_string())],
+            ),
+            Some(false),
+        );
+        self.notify(
+            cx,
+            Severity::Warning,
+            &i18n::tr("notify.dora_is_out_of_date"),
+            &i18n::tr_args(
+                "notify.dora_is_out_of_date_message",
+                &[
+                    ("version", &version_text),
+                    ("min", &MIN_DORA_VERSION.to_string()),
+                ],
+            ),
+        );
+    }
+
+    /// Publish what changed in `dataflows` to event stream subscribers, off
+    /// the UI thread since it fetches the logs of running dataflows.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn observe_events(&self, dataflows: &[DataflowInfo]) {
+        let watcher = Arc::clone(&self.event_watcher);
+        let dora = Arc::clone(&self.dora);
+        let dataflows = dataflows.to_vec();
+        tasks::runtime().spawn_blocking(move || {
+            let _job = tasks::BackgroundJob::start();
+            // Skip this listing while the last one is still fetching logs
+            if let Ok(mut watcher) = watcher.try_lock() {
+                watcher.observe(&*dora, &dataflows);
+            }
+        });
+    }
+
+    /// Run `work` as a background task whose result is handled as `job`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn spawn_task<T, F>(&mut self, cx: &mut Cx, name: &str, job: TaskJob, work: F)
+    where
+        T: Send + 'static,
+        F: FnOnce(&TaskContext) -> Result<T, String> + Send + 'static,
+    {
+        let id = self.tasks.spawn(name, work);
+        self.task_jobs.insert(id, job);
+        self.update_task_status(cx);
+    }
+
+    /// Run `call` on the coordinator client as a background task whose
+    /// result is handled as `job`, so a slow or remote `dora` never blocks
+    /// the UI thread.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn spawn_dora<T, F>(&mut self, cx: &mut Cx, name: &str, job: TaskJob, call: F)
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn DoraClient) -> T + Send + 'static,
+    {
+        let dora = self.dora.clone();
+        self.spawn_task(cx, name, job, move |_| Ok(call(&*dora)));
+    }
+
+    /// Whether a running task's result is for a job matching `is_job`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn task_pending(&self, is_job: impl Fn(&TaskJob) -> bool) -> bool {
+        self.task_jobs.values().any(is_job)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn handle_task_finished(&mut self, cx: &mut Cx, id: TaskId, name: &str, outcome: TaskOutcome) {
+        let Some(job) = self.task_jobs.remove(&id) else {
+            return;
+        };
+        let value = match outcome {
+            TaskOutcome::Done(value) => value,
+            TaskOutcome::Failed(e) => {
+                tracing::warn!(target: "App", "{} failed: {}", name, e);
+                match job {
+                    TaskJob::ExportConfig => self.set_bundle_status(cx, &e),
+                    TaskJob::ImportRecording(_) => self
+                        .ui
+                        .diagnostics_panel(ids!(diagnostics_panel))
+                        .set_recording_status(cx, &e),
+                    TaskJob::StartRuntime => {}
+                    TaskJob::RunQuery | TaskJob::ExportQueryResults => {
+                        self.ui.query_panel(ids!(query_panel)).set_status(cx, &e)
+                    }
+                    TaskJob::PruneStorage => self
+                        .ui
+                        .diagnostics_panel(ids!(diagnostics_panel))
+                        .set_storage_usage(cx, &e),
+                    TaskJob::DoraCli => self
+                        .ui
+                        .diagnostics_panel(ids!(diagnostics_panel))
+                        .set_cli_output(cx, &text::truncate(&e, CLI_OUTPUT_CHARS)),
+                    TaskJob::DetectDoraVersion => {
+                        let text = match tools::ssh_target() {
+                            Some(target) => i18n::tr_args(
+                                "status.dora_unreachable",
+                                &[("host", &target.to_string())],
+                            ),
+                            None => i18n::tr("status.dora_not_found"),
+                        };
+                        self.ui
+                            .status_bar(ids!(status_bar))
+                            .set_dora(cx, &text, Some(false));
+                    }
+                    // Client errors come back as results; this is a panic
+                    TaskJob::ListDataflows => self.dataflows_listed(cx, Err(e.clone())),
+                    TaskJob::ListNodes(_)
+                    | TaskJob::DetailLogs(_)
+                    | TaskJob::ViewLogs(_)
+                    | TaskJob::SummarizeLogs(_)
+                    | TaskJob::CheckDataflow(_)
+                    | TaskJob::StartDataflow(_)
+                    | TaskJob::StopDataflow(..)
+                    | TaskJob::StopForRestart(_)
+                    | TaskJob::BuildDataflow(..)
+                    | TaskJob::CheckAlerts => {}
+                }
+                self.notify(
+                    cx,
+                    Severity::Error,
+                    &i18n::tr_args("notify.task_failed", &[("name", &name)]),
+                    &e,
+                );
+                return;
+            }
+            TaskOutcome::Cancelled => {
+                self.notify(
+                    cx,
+                    Severity::Info,
+                    &i18n::tr_args("notify.task_cancelled", &[("name", &name)]),
+                    "",
+                );
+                return;
+            }
+        };
+        match job {
+            TaskJob::ExportConfig => {
+                if let Ok(status) = value.downcast::<String>() {
+                    self.set_bundle_status(cx, &status);
+                }
+            }
+            TaskJob::StartRuntime => {
+                self.notify(
+                    cx,
+                    Severity::Success,
+                    &i18n::tr("notify.dora_runtime_started"),
+                    "",
+                );
+                self.refresh_dataflows(cx);
+            }
+            TaskJob::ImportRecording(path) => {
+                if let Ok(result) = value
+                    .downcast::<(crate::storage::ImportSummary, Vec<crate::otlp::types::Span>)>()
+                {
+                    let (summary, spans) = *result;
+                    self.recording_imported(cx, &path, summary, spans);
+                }
+            }
+            TaskJob::RunQuery => {
+                if let Ok(outcome) = value.downcast::<Result<(QueryResult, u64), String>>() {
+                    self.query_finished(cx, *outcome);
+                }
+            }
+            TaskJob::ExportQueryResults => {
+                if let Ok(status) = value.downcast::<String>() {
+                    self.ui
+                        .query_panel(ids!(query_panel))
+                        .set_status(cx, &status);
+                }
+            }
+            TaskJob::PruneStorage => {
+                if let Ok(summary) = value.downcast::<PruneSummary>() {
+                    if *summary != PruneSummary::default() {
+                        tracing::info!(target: "App", "{}", summary.describe());
+                    }
+                    self.update_storage_usage(cx);
+                }
+            }
+            TaskJob::DoraCli => {
+                if let Ok(output) = value.downcast::<String>() {
+                    let output = if output.trim().is_empty() {
+                        i18n::tr("diagnostics.cli_no_output")
+                    } else {
+                        text::truncate(&output, CLI_OUTPUT_CHARS)
+                    };
+                    self.ui
+                        .diagnostics_panel(ids!(diagnostics_panel))
+                        .set_cli_output(cx, &output);
+                }
+            }
+            TaskJob::DetectDoraVersion => {
+                if let Ok(version) = value.downcast::<DoraVersion>() {
+                    self.dora_version_detected(cx, *version);
+                }
+            }
+            TaskJob::ListDataflows => {
+                if let Ok(listing) = value.downcast::<(Result<Vec<DataflowInfo>, String>, u64)>() {
+                    let (result, latency_ms) = *listing;
+                    self.coordinator_latency_ms = Some(latency_ms);
+                    self.dataflows_listed(cx, result);
+                }
+            }
+            TaskJob::ListNodes(uuid) => {
+                if let Ok(nodes) = value.downcast::<Result<Vec<String>, String>>() {
+                    let detail = self.ui.dataflow_detail(ids!(dataflow_detail));
+                    if detail.uuid().as_deref() == Some(uuid.as_str()) {
+                        detail.set_nodes(cx, *nodes);
+                        self.update_golden_signals(cx);
+                    }
+                }
+            }
+            TaskJob::DetailLogs(uuid) => {
+                if let Ok(logs) = value.downcast::<Result<String, String>>() {
+                    let detail = self.ui.dataflow_detail(ids!(dataflow_detail));
+                    if detail.uuid().as_deref() == Some(uuid.as_str()) {
+                        // New log lines show the dataflow is still at work
+                        if detail.set_logs(cx, *logs) > 0 {
+                            self.liveness.record(&uuid, logging::now_ms());
+                        }
+                        self.spill_detail_logs(&uuid);
+                    }
+                }
+            }
+            TaskJob::ViewLogs(uuid) => {
+                if let Ok(logs) = value.downcast::<Result<String, String>>() {
+                    match *logs {
+                        Err(e) => {
+                            let title = i18n::tr("error.get_logs");
+                            self.notify(cx, Severity::Error, &title, &e)
+                        }
+                        Ok(logs) => {
+                            tracing::info!(target: "App", "Dataflow logs for {}:\n{}", uuid, logs)
+                        }
+                    }
+                }
+            }
+            TaskJob::SummarizeLogs(uuid) => {
+                if let Ok(logs) = value.downcast::<Result<String, String>>() {
+                    self.summarize_logs(cx, &uuid, *logs);
+                }
+            }
+            TaskJob::CheckDataflow(path) => {
+                if let Ok(result) = value.downcast::<Result<Vec<CheckDiagnostic>, String>>() {
+                    self.dataflow_checked(cx, &path, *result);
+                }
+            }
+            TaskJob::StartDataflow(path) => match value.downcast::<StartOutcome>().map(|o| *o) {
+                Ok(StartOutcome::CheckFailed(problems)) => {
+                    self.notify_check_problems(cx, &path, &problems)
+                }
+                Ok(StartOutcome::Started(result)) => self.dataflow_started(cx, &path, result),
+                Err(_) => {}
+            },
+            TaskJob::StopDataflow(uuid, kind) => {
+                if let Ok(result) = value.downcast::<Result<(), String>>() {
+                    self.dataflow_stopped(cx, &uuid, kind, *result);
+                }
+            }
+            TaskJob::StopForRestart(path) => {
+                if let Ok(failures) = value.downcast::<Vec<(String, String)>>() {
+                    self.stopped_for_restart(cx, &path, *failures);
+                }
+            }
+            TaskJob::BuildDataflow(path, then) => {
+                if let Ok(result) = value.downcast::<ToolResult>() {
+                    self.dataflow_built(cx, path, then, *result);
+                }
+            }
+            TaskJob::CheckAlerts => {
+                type Window = (Result<Vec<crate::otlp::types::Span>, String>, u64);
+                if let Ok(window) = value.downcast::<Window>() {
+                    match *window {
+                        (Ok(spans), now_ms) => self.evaluate_alerts(cx, &spans, now_ms),
+                        (Err(e), _) => tracing::debug!(target: "App", "Alert query failed: {}", e),
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_task_status(&mut self, cx: &mut Cx) {
+        let status = [self.tasks.describe(), process::describe_running()]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" · ");
+        let status = (status, tasks::background_jobs());
+        if status != self.task_status {
+            self.ui
+                .status_bar(ids!(status_bar))
+                .set_tasks(cx, &status.0, status.1);
+            self.task_status = status;
+        }
+    }
+
+    fn save_settings(&self) {
+        if let Err(e) = self.settings.save() {
+            tracing::warn!(target: "App", "Failed to save settings: {}", e);
+        }
+    }
+
+    fn stop_dataflow(&mut self, cx: &mut Cx, uuid: &str) {
+        if !self.require_coordinator(cx, "coordinator_action.stop") {
+            return;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let job = TaskJob::StopDataflow(uuid.to_string(), UndoKind::Stopped);
+            let uuid = uuid.to_string();
+            self.spawn_dora(cx, &i18n::tr("task.stop_dataflow"), job, move |dora| {
+                dora.stop(&uuid)
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        remote::submit(RemoteRequest::Stop(uuid.to_string()));
+    }
+
+    fn destroy_dataflow(&mut self, cx: &mut Cx, uuid: &str) {
+        if !self.require_coordinator(cx, "coordinator_action.destroy") {
+            return;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let job = TaskJob::StopDataflow(uuid.to_string(), UndoKind::Destroyed);
+            let uuid = uuid.to_string();
+            self.spawn_dora(cx, &i18n::tr("task.destroy_dataflow"), job, move |dora| {
+                dora.destroy(&uuid)
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        remote::submit(RemoteRequest::Destroy(uuid.to_string()));
+    }
+
+    /// Offer to restart a stopped or destroyed dataflow, or report why it
+    /// could not be, then refresh the table.
+    fn dataflow_stopped(
+        &mut self,
+        cx: &mut Cx,
+        uuid: &str,
+        kind: UndoKind,
+        result: Result<(), String>,
+    ) {
+        match result {
+            Err(e) => {
+                let title = i18n::tr(match kind {
+                    UndoKind::Stopped => "error.stop_dataflow",
+                    UndoKind::Destroyed => "error.destroy_dataflow",
+                });
+                self.notify(cx, Severity::Error, &title, &e);
+            }
+            Ok(()) => self.record_undo(cx, uuid, kind),
+        }
+        self.refresh_dataflows(cx);
+    }
+
+    /// Flag running dataflows without recent telemetry, warning once when
+    /// one goes quiet.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_liveness(&mut self, cx: &mut Cx, running: &[String], dataflows: &mut [DataflowInfo]) {
+        let now = logging::now_ms();
+        self.liveness.observe_running(running, now);
+        let stale_after_ms = self.settings.stale_after_minutes * 60_000;
+        // Only dataflows with local node samples are judged, see
+        // [`LivenessTracker::is_stale`]
+        let mut stale = HashSet::new();
+        for df in dataflows.iter_mut().filter(|df| df.is_running()) {
+            df.stale = self.liveness.is_stale(&df.uuid, now, stale_after_ms);
+            if !df.stale {
+                continue;
+            }
+            if !self.stale_dataflows.contains(&df.uuid) {
+                let msg = i18n::tr_args(
+                    "notify.dataflow_stale_message",
+                    &[
+                        ("name", &df.name),
+                        ("uuid", &df.uuid_short()),
+                        ("minutes", &self.settings.stale_after_minutes.to_string()),
+                    ],
+                );
+                self.notify(
+                    cx,
+                    Severity::Warning,
+                    &i18n::tr("notify.dataflow_stale"),
+                    &msg,
+                );
+            }
+            stale.insert(df.uuid.clone());
+        }
+        self.stale_dataflows = stale;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_node_metrics(&mut self, cx: &mut Cx) {
+        let series = if self.snapped_run.is_some() {
+            self.snapped_metrics.series()
+        } else {
+            self.metrics_history.series()
+        };
+        self.ui
+            .node_metrics_panel(ids!(node_metrics_panel))
+            .set_series(cx, series, self.dataflow_names.clone());
+    }
+
+    fn update_run_dropdown(&mut self, cx: &mut Cx) {
+        let mut labels = vec![i18n::tr("header.runs")];
+        labels.extend(self.run_history.runs().iter().map(|r| r.label()));
+        let dropdown = self.ui.drop_down(ids!(run_dropdown));
+        dropdown.set_labels(cx, labels);
+        dropdown.set_selected_item(cx, 0);
+    }
+
+    /// Restrict traces and node metrics to the window of `run`.
+    fn snap_to_run(&mut self, cx: &mut Cx, run: RunRecord) {
+        let end_text = match run.ended_ms {
+            Some(ended) => datetime::format_clock(ended),
+            None => i18n::tr("snap.now"),
+        };
+        let msg = i18n::tr_args(
+            "snap.showing_run",
+            &[
+                ("run", &run.label()),
+                ("start", &datetime::format_clock(run.started_ms)),
+                ("end", &end_text),
+                ("zone", datetime::display_zone().label()),
+            ],
+        );
+        self.ui.label(ids!(snap_label)).set_text(cx, &msg);
+        self.ui
+            .view(ids!(snap_bar))
+            .apply_over(cx, live! { height: 36 });
+        tracing::info!(target: "App", "Snapped time range to run {}", run.uuid);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.snapped_metrics = self.load_run_metrics(&run);
+        }
+        self.snapped_run = Some(run);
+        self.save_session();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.update_node_metrics(cx);
+            if self.signoz_available {
+                self.refresh_traces(cx);
+            }
+        }
+        self.ui.redraw(cx);
+    }
+
+    fn clear_snap(&mut self, cx: &mut Cx) {
+        self.snapped_run = None;
+        self.save_session();
+        self.ui
+            .view(ids!(snap_bar))
+            .apply_over(cx, live! { height: 0 });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.snapped_metrics = MetricsHistory::default();
+            self.update_node_metrics(cx);
+            if self.signoz_available {
+                self.refresh_traces(cx);
+            }
+        }
+        self.ui.redraw(cx);
+    }
+
+    /// Load stored node samples for the window of `run`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_run_metrics(&self, run: &RunRecord) -> MetricsHistory {
+        let mut history = MetricsHistory::default();
+        let Some(storage) = &self.storage else {
+            return history;
+        };
+        let (start_ms, end_ms) = run.window(logging::now_ms());
+        match storage.read::<metrics::NodeMetrics>(crate::storage::METRICS_TABLE, start_ms, end_ms)
+        {
+            Ok(samples) => samples
+                .into_iter()
+                .filter(|s| s.dataflow_id == run.uuid)
+                .for_each(|s| history.push(s)),
+            Err(e) => tracing::warn!(target: "App", "Failed to read stored metrics: {}", e),
+        }
+        history
+    }
+
+    /// The YAML of running dataflows, and the settings file for edits to the
+    /// telemetry backend.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn watched_paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .running_started
+            .iter()
+            .map(|(_, p)| p.clone())
+            .collect();
+        if let Some(path) = Settings::path() {
+            paths.push(path.to_string_lossy().into_owned());
+        }
+        paths
+    }
+
+    /// Reconnect when the telemetry backend in the settings file was edited.
+    /// The app's own saves land here too and change nothing.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn settings_file_changed(&mut self, cx: &mut Cx) {
+        let Some(text) = Settings::path().and_then(|p| std::fs::read_to_string(p).ok()) else {
+            return;
+        };
+        // Keep the current backend while the file does not parse, e.g.
+        // halfway through an edit
+        let backend = match serde_json::from_str::<Settings>(&text) {
+            Ok(settings) => settings.telemetry_backend,
+            Err(e) => {
+                tracing::warn!(target: "App", "Ignoring unreadable settings file: {}", e);
+                return;
+            }
+        };
+        if backend == self.settings.telemetry_backend {
+            return;
+        }
+        tracing::info!(target: "App", "Telemetry backend changed in settings, reconnecting");
+        self.settings.telemetry_backend = backend.clone();
+        if let Some(telemetry) = &self.telemetry {
+            telemetry.reconfigure(backend);
+        }
+        self.signoz_available = true;
+        self.signoz_healthy = None;
+        self.traces_loaded_once = false;
+        self.ui.button(ids!(tab_traces)).set_visible(cx, true);
+        self.update_telemetry_status(cx, None);
+        self.notify(
+            cx,
+            Severity::Info,
+            &i18n::tr("notify.telemetry_backend_reloaded"),
+            "",
+        );
+        if self.active_panel == ActivePanel::Traces {
+            self.refresh_traces(cx);
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn handle_yaml_changed(&mut self, cx: &mut Cx, changed: &std::path::Path) {
+        let path = self
+            .running_started
+            .iter()
+            .map(|(_, p)| p)
+            .find(|p| watcher::canonical(std::path::Path::new(p)) == changed)
+            .cloned();
+        if let Some(path) = path {
+            tracing::info!(target: "App", "Dataflow YAML changed: {}", path);
+            // One offer per path; a later change replaces the earlier one
+            if let Some((id, _)) = self.pending_reload.take() {
+                self.notifications.dismiss(id);
+            }
+            let title = i18n::tr_args(
+                "notify.dataflow_changed",
+                &[("path", &text::truncate(&path, 60))],
+            );
+            let id = self.notifications.push_action(
+                Severity::Info,
+                &title,
+                &i18n::tr("notify.dataflow_changed_message"),
+                &i18n::tr("notify.rebuild_restart"),
+                logging::now_ms(),
+            );
+            self.pending_reload = Some((id, path));
+            self.update_notifications(cx);
+        }
+    }
+
+    /// Build the changed dataflow, stop its running instances and start it again.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn rebuild_and_restart(&mut self, cx: &mut Cx) {
+        if !self.require_coordinator(cx, "coordinator_action.restart") {
+            return;
+        }
+        let Some((_, path)) = self.pending_reload.take() else {
+            return;
+        };
+        self.build_dataflow(cx, &path, AfterBuild::Restart);
+    }
+
+    /// Stop the running instances of the dataflow at `path`, then start it
+    /// again.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn restart_dataflow(&mut self, cx: &mut Cx, path: String) {
+        let uuids: Vec<String> = self
+            .running_started
+            .iter()
+            .filter(|(_, p)| *p == path)
+            .map(|(uuid, _)| uuid.clone())
+            .collect();
+        let job = TaskJob::StopForRestart(path);
+        self.spawn_dora(cx, &i18n::tr("task.stop_for_restart"), job, move |dora| {
+            uuids
+                .into_iter()
+                .filter_map(|uuid| dora.stop(&uuid).err().map(|e| (uuid, e)))
+                .collect::<Vec<_>>()
+        });
+    }
+
+    /// Start `path` again once the dataflows started from it are stopped,
+    /// reporting the ones that would not stop.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn stopped_for_restart(&mut self, cx: &mut Cx, path: &str, failures: Vec<(String, String)>) {
+        for (uuid, e) in failures {
+            self.notify(
+                cx,
+                Severity::Warning,
+                &i18n::tr_args("error.stop_named", &[("name", &text::truncate(&uuid, 8))]),
+                &e,
+            );
+        }
+        self.start_dataflow(cx, path);
+    }
+
+    /// Run `dora build` on the dataflow at `path` in the background, then
+    /// carry on with `then` if it succeeds.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn build_dataflow(&mut self, cx: &mut Cx, path: &str, then: AfterBuild) {
+        // A second drop or reload of the same file while it builds would
+        // start it twice
+        if self.task_pending(|job| matches!(job, TaskJob::BuildDataflow(p, _) if p == path)) {
+            let body = i18n::tr_args(
+                "notify.build_in_progress_message",
+                &[("path", &text::truncate(path, 60))],
+            );
+            return self.notify(
+                cx,
+                Severity::Info,
+                &i18n::tr("notify.build_in_progress"),
+                &body,
+            );
+        }
+        let args = serde_json::json!({ "dataflow_path": path });
+        let job = TaskJob::BuildDataflow(path.to_string(), then);
+        self.spawn_task(cx, &i18n::tr("task.build_dataflow"), job, move |_| {
+            Ok(execute_tool("dora_build", "build", &args))
+        });
+    }
+
+    /// Report compiler errors if the build of `path` failed, else carry on
+    /// with `then`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn dataflow_built(&mut self, cx: &mut Cx, path: String, then: AfterBuild, result: ToolResult) {
+        if result.is_error {
+            // Show the compiler errors rather than the whole build log
+            let errors: Vec<String> = match &result.payload {
+                Some(ToolPayload::Build(diagnostics)) => diagnostics
+                    .iter()
+                    .filter(|d| d.level == logging::Level::Error)
+                    .take(DIAGNOSTICS_SHOWN)
+                    .map(BuildDiagnostic::describe)
+                    .collect(),
+                _ => Vec::new(),
+            };
+            let details = if errors.is_empty() {
+                result.content
+            } else {
+                errors.join("\n")
+            };
+            self.notify(
+                cx,
+                Severity::Error,
+                &i18n::tr_args(
+                    "notify.build_failed",
+                    &[("path", &text::truncate(&path, 60))],
+                ),
+                &details,
+            );
+            return;
+        }
+        match then {
+            AfterBuild::Restart => self.restart_dataflow(cx, path),
+            AfterBuild::Start => self.start_dataflow(cx, &path),
+        }
+    }
+
+    /// Offer to start the first dataflow file dropped onto the window.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn handle_drop(&mut self, cx: &mut Cx, items: &[DragItem]) {
+        match dropped_dataflow(items) {
+            Some(path) => {
+                tracing::info!(target: "App", "Dataflow dropped: {}", path);
+                self.ui.start_dialog(ids!(start_dialog)).open(cx, &path);
+            }
+            None => self.notify(
+                cx,
+                Severity::Info,
+                &i18n::tr("notify.not_a_dataflow"),
+                &i18n::tr("notify.not_a_dataflow_message"),
+            ),
+        }
+    }
+
+    /// Start a dataflow confirmed in the drop dialog, building it first if
+    /// asked to. Both run in the background; the start follows a successful
+    /// build from its completion handler.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start_dropped_dataflow(&mut self, cx: &mut Cx, path: &str, build: bool) {
+        if !self.require_coordinator(cx, "coordinator_action.start") {
+            return;
+        }
+        self.switch_to_panel(cx, ActivePanel::Dataflows);
+        self.ui
+            .text_input(ids!(start_path_input))
+            .set_text(cx, path);
+        if build {
+            return self.build_dataflow(cx, path, AfterBuild::Start);
+        }
+        self.start_dataflow(cx, path);
+    }
+
+    /// Put the dataflow at `path` in the start box and open it in the
+    /// system editor.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn edit_dataflow(&mut self, cx: &mut Cx, path: &str) {
+        self.switch_to_panel(cx, ActivePanel::Dataflows);
+        self.ui
+            .text_input(ids!(start_path_input))
+            .set_text(cx, path);
+        if let Err(e) = platform::open_path(std::path::Path::new(path)) {
+            self.notify(
+                cx,
+                Severity::Warning,
+                &i18n::tr("notify.failed_to_open_dataflow"),
+                &e,
+            );
+        }
+    }
+
+    fn record_undo(&mut self, cx: &mut Cx, uuid: &str, kind: UndoKind) {
+        let name = self
+            .ui
+            .dataflow_table(ids!(dataflow_table))
+            .dataflow_by_uuid(uuid)
+            .map(|df| df.name)
+            .unwrap_or_default();
+        self.undo_stack.record(uuid, &name, kind, Instant::now());
+        // One offer per dataflow; stopping then destroying it replaces it
+        if let Some(index) = self.undo_notices.iter().position(|(_, u)| u == uuid) {
+            let (id, _) = self.undo_notices.remove(index);
+            self.notifications.dismiss(id);
+        }
+        let Some(entry) = self.undo_stack.entries().iter().find(|e| e.uuid == uuid) else {
+            return;
+        };
+        let Some(path) = entry.dataflow_path.as_deref() else {
+            self.update_notifications(cx);
+            return;
+        };
+        let id = self.notifications.push_action(
+            Severity::Info,
+            &entry.label(),
+            &i18n::tr_args(
+                "notify.undo_message",
+                &[("path", &text::truncate(path, 60))],
+            ),
+            &i18n::tr("notify.undo_restart"),
+            logging::now_ms(),
+        );
+        self.undo_notices.push((id, uuid.to_string()));
+        self.update_notifications(cx);
+    }
+
+    /// The dataflow whose restart offer is notification `id`, forgetting
+    /// the offer.
+    fn take_undo_notice(&mut self, id: u64) -> Option<String> {
+        let index = self.undo_notices.iter().position(|(n, _)| *n == id)?;
+        Some(self.undo_notices.remove(index).1)
+    }
+
+    /// Withdraw the restart offers whose grace window has passed.
+    fn dismiss_expired_undo_notices(&mut self, cx: &mut Cx) {
+        let entries = self.undo_stack.entries();
+        let (kept, expired): (Vec<_>, Vec<_>) = std::mem::take(&mut self.undo_notices)
+            .into_iter()
+            .partition(|(_, uuid)| entries.iter().any(|e| e.uuid == *uuid));
+        self.undo_notices = kept;
+        for (id, _) in expired {
+            self.notifications.dismiss(id);
+        }
+        self.update_notifications(cx);
+    }
+
+    fn restart_undo(&mut self, cx: &mut Cx, uuid: &str) {
+        let Some(entry) = self.undo_stack.take(uuid) else {
+            return;
+        };
+        if let Some(path) = entry.dataflow_path {
+            tracing::info!(target: "App", "Restarting {} from {}", entry.name, path);
+            self.start_dataflow(cx, &path);
+        }
+    }
+
+    fn view_dataflow_logs(&mut self, cx: &mut Cx, uuid: &str) {
+        if !self.require_coordinator(cx, "coordinator_action.fetch_logs") {
+            return;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let (job, uuid) = (TaskJob::ViewLogs(uuid.to_string()), uuid.to_string());
+            self.spawn_dora(cx, &i18n::tr("task.fetch_logs"), job, move |dora| {
+                dora.logs(&uuid, None)
+            });
+        }
+        // There is no console to print to; the detail panel shows them
+        #[cfg(target_arch = "wasm32")]
+        self.show_dataflow_detail(cx, uuid);
+    }
+
+    /// Write the logs shown in the detail panel, as filtered there, to a
+    /// text file.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn export_dataflow_logs(&mut self, cx: &mut Cx, path: &str) {
+        let Some(text) = self.ui.dataflow_detail(ids!(dataflow_detail)).export_text() else {
+            return;
+        };
+        let result = logs_export_path(path).and_then(|path| {
+            std::fs::write(&path, text)
+                .map(|()| path)
+                .map_err(|e| write_error(&path, e))
+        });
+        match result {
+            Ok(path) => {
+                tracing::info!(target: "App", "Exported logs to {}", path.display());
+                let body = i18n::tr_args(
+                    "notify.logs_exported_message",
+                    &[("path", &path.display().to_string())],
+                );
+                self.notify(
+                    cx,
+                    Severity::Success,
+                    &i18n::tr("notify.logs_exported"),
+                    &body,
+                );
+            }
+            Err(e) => self.notify(
+                cx,
+                Severity::Error,
+                &i18n::tr("notify.failed_to_export_logs"),
+                &e,
+            ),
+        }
+    }
 
-impl MatchEvent for App {
-    fn handle_startup(&mut self, cx: &mut Cx) {
-        // Initialize API key from environment variable
-        crate::api::init_api_key_from_env();
+    #[cfg(target_arch = "wasm32")]
+    fn export_dataflow_logs(&mut self, cx: &mut Cx, _path: &str) {
+        self.notify(
+            cx,
+            Severity::Info,
+            &i18n::tr("notify.log_export_unavailable"),
+            &i18n::tr("notify.log_export_unavailable_message"),
+        );
+    }
 
-        // Initialize SigNoz bridge from env vars
+    /// Ask the chat agent for the probable root cause of a dataflow's
+    /// problems, from its recent logs.
+    fn summarize_dataflow_logs(&mut self, cx: &mut Cx, uuid: &str) {
+        if !self.require_coordinator(cx, "coordinator_action.fetch_logs") {
+            return;
+        }
         #[cfg(not(target_arch = "wasm32"))]
         {
-            self.signoz_available = bridge::init_signoz_from_env();
-            if self.signoz_available {
-                bridge::request_health_check();
+            let (job, uuid) = (TaskJob::SummarizeLogs(uuid.to_string()), uuid.to_string());
+            self.spawn_dora(cx, &i18n::tr("task.fetch_logs"), job, move |dora| {
+                dora.logs(&uuid, None)
+            });
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.summarize_pending = Some(uuid.to_string());
+            remote::submit(RemoteRequest::Logs(uuid.to_string()));
+        }
+    }
+
+    /// Hand fetched logs of a dataflow to the chat agent to summarize.
+    fn summarize_logs(&mut self, cx: &mut Cx, uuid: &str, logs: Result<String, String>) {
+        let logs = match logs {
+            Ok(logs) => logs,
+            Err(e) => {
+                let title = i18n::tr("error.get_logs");
+                self.notify(cx, Severity::Error, &title, &e);
+                return;
             }
+        };
+        let name = self
+            .ui
+            .dataflow_table(ids!(dataflow_table))
+            .dataflow_by_uuid(uuid)
+            .map(|df| df.name)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| uuid.to_string());
+        let label = format!("Summarize the logs of {}", name);
+        let request = prompts::log_summary_request(&name, &logs);
+        if !self
+            .ui
+            .chat_screen(ids!(chat_screen))
+            .summarize_logs(cx, &label, request)
+        {
+            self.notify(
+                cx,
+                Severity::Warning,
+                &i18n::tr("notify.chat_is_busy"),
+                &i18n::tr("notify.chat_is_busy_message"),
+            );
         }
+    }
 
-        // Schedule initial data load for next frame (after UI is ready)
-        self.next_frame = cx.new_next_frame();
+    /// Parse the alert rules saved in settings, skipping invalid ones.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load_alert_rules(&mut self, cx: &mut Cx) {
+        self.alert_rules = self
+            .settings
+            .alert_rules
+            .iter()
+            .filter_map(|expression| match AlertRule::parse(expression) {
+                Ok(rule) => Some(rule),
+                Err(e) => {
+                    tracing::warn!(target: "Alerts", "Ignoring alert rule '{}': {}", expression, e);
+                    None
+                }
+            })
+            .collect();
+        self.update_alerts_panel(cx);
     }
 
-    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions) {
-        // Handle tab buttons
-        if self.ui.button(ids!(tab_dataflows)).clicked(actions) {
-            self.switch_to_panel(cx, ActivePanel::Dataflows);
+    #[cfg(not(target_arch = "wasm32"))]
+    fn add_alert_rule(&mut self, cx: &mut Cx, expression: &str) {
+        let alerts = self.ui.alerts_panel(ids!(alerts_panel));
+        let rule = match AlertRule::parse(expression) {
+            Ok(rule) => rule,
+            Err(e) => {
+                alerts.set_rule_status(cx, &e);
+                return;
+            }
+        };
+        if self.alert_rules.contains(&rule) {
+            alerts.set_rule_status(cx, &format!("Rule already exists: {}", rule));
+            return;
         }
+        alerts.set_rule_status(cx, &format!("Added rule: {}", rule));
+        alerts.clear_rule_input(cx);
+        self.alert_rules.push(rule);
+        self.save_alert_rules();
+        self.update_alerts_panel(cx);
+    }
 
-        if self.ui.button(ids!(tab_traces)).clicked(actions) {
-            self.switch_to_panel(cx, ActivePanel::Traces);
-            #[cfg(not(target_arch = "wasm32"))]
-            if self.signoz_available && !self.traces_loaded_once {
-                self.refresh_traces(cx);
+    #[cfg(not(target_arch = "wasm32"))]
+    fn remove_alert_rule(&mut self, cx: &mut Cx, index: usize) {
+        if index >= self.alert_rules.len() {
+            return;
+        }
+        let rule = self.alert_rules.remove(index);
+        self.alert_engine.remove(&rule);
+        self.save_alert_rules();
+        self.update_alerts_panel(cx);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_alert_rules(&mut self) {
+        self.settings.alert_rules = self.alert_rules.iter().map(|r| r.to_string()).collect();
+        self.save_settings();
+    }
+
+    /// Fetch the spans of the alert rules' windows, unfiltered by the traces
+    /// panel, to evaluate the rules on once they arrive.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn check_alerts(&mut self, cx: &mut Cx) {
+        if self.alert_rules.is_empty()
+            || self.task_pending(|job| matches!(job, TaskJob::CheckAlerts))
+        {
+            return;
+        }
+        let Some(telemetry) = self.telemetry.clone() else {
+            return;
+        };
+        let now_ms = logging::now_ms();
+        let query = alerts::window_query(&self.alert_rules, now_ms);
+        let id = self
+            .tasks
+            .spawn_async(&i18n::tr("task.check_alerts"), move |_| async move {
+                Ok((telemetry.query_traces(query).await, now_ms))
+            });
+        self.task_jobs.insert(id, TaskJob::CheckAlerts);
+        self.update_task_status(cx);
+    }
+
+    /// Evaluate alert rules on the spans of their windows at `now_ms` and
+    /// raise notifications for rules that started or stopped firing.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn evaluate_alerts(&mut self, cx: &mut Cx, spans: &[crate::otlp::types::Span], now_ms: u64) {
+        if self.alert_rules.is_empty() {
+            return;
+        }
+        let changes = self.alert_engine.evaluate(&self.alert_rules, spans, now_ms);
+        for event in changes {
+            events::publish(&StudioEvent::from(&event));
+            let msg = i18n::tr_args(
+                "notify.alert_message",
+                &[("rule", &event.rule), ("value", &event.value)],
+            );
+            match event.state {
+                AlertState::Firing => {
+                    self.notify(cx, Severity::Error, &i18n::tr("notify.alert_firing"), &msg);
+                    self.notify_desktop(&i18n::tr("notify.alert_firing"), &msg);
+                }
+                AlertState::Resolved => {
+                    self.notify(
+                        cx,
+                        Severity::Success,
+                        &i18n::tr("notify.alert_resolved"),
+                        &msg,
+                    );
+                }
             }
         }
+        self.update_alerts_panel(cx);
+    }
 
-        // Handle shared refresh button
-        if self.ui.button(ids!(refresh_button)).clicked(actions) {
-            match self.active_panel {
-                ActivePanel::Dataflows => {
-                    log!("[App] Refresh button clicked - refreshing dataflows");
-                    self.refresh_dataflows(cx);
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_alerts_panel(&mut self, cx: &mut Cx) {
+        let rules = self
+            .alert_rules
+            .iter()
+            .map(|rule| (rule.to_string(), self.alert_engine.status(rule)))
+            .collect();
+        let alerts = self.ui.alerts_panel(ids!(alerts_panel));
+        alerts.set_rules(cx, rules);
+        alerts.set_history(cx, self.alert_engine.history());
+    }
+
+    /// Switch coordinator state, notifying on transitions.
+    fn set_coordinator_state(&mut self, cx: &mut Cx, state: CoordinatorState) {
+        let previous = std::mem::replace(&mut self.coordinator, state);
+        if previous == state {
+            return;
+        }
+        self.ui
+            .status_bar(ids!(status_bar))
+            .set_coordinator(cx, state);
+        match state {
+            CoordinatorState::Offline => {
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    metrics::set_active_dataflows(Vec::new());
+                    self.show_stored_metrics(cx);
                 }
-                ActivePanel::Traces => {
+                self.notify(
+                    cx,
+                    Severity::Warning,
+                    &i18n::tr("notify.working_offline"),
+                    &i18n::tr("notify.working_offline_message"),
+                );
+            }
+            _ => {
+                if previous.is_offline() {
                     #[cfg(not(target_arch = "wasm32"))]
-                    {
-                        log!("[App] Refresh button clicked - refreshing traces");
-                        self.refresh_traces(cx);
-                    }
+                    self.ui
+                        .node_metrics_panel(ids!(node_metrics_panel))
+                        .set_stale(cx, None);
+                    self.notify(
+                        cx,
+                        Severity::Success,
+                        &i18n::tr("notify.coordinator_reachable_again"),
+                        "",
+                    );
                 }
             }
         }
+        self.ui.redraw(cx);
+    }
 
-        // Handle DataflowTable row actions
-        let table = self.ui.dataflow_table(ids!(dataflow_table));
+    /// Whether coordinator-backed actions can run; explains why not when
+    /// offline. `action` is the catalog key of what was asked for.
+    fn require_coordinator(&mut self, cx: &mut Cx, action: &str) -> bool {
+        if !self.coordinator.is_offline() {
+            return true;
+        }
+        self.notify(
+            cx,
+            Severity::Warning,
+            &i18n::tr("notify.coordinator_offline"),
+            &i18n::tr_args(
+                "notify.coordinator_offline_message",
+                &[("action", &i18n::tr(action))],
+            ),
+        );
+        false
+    }
 
-        if let Some(uuid) = table.stop_clicked(actions) {
-            log!("[App] Stop button clicked for {}", uuid);
-            self.stop_dataflow(cx, &uuid);
+    /// Show a toast and write it to the studio log.
+    fn notify(&mut self, cx: &mut Cx, severity: Severity, title: &str, message: &str) {
+        let line = if message.is_empty() {
+            title.to_string()
+        } else {
+            format!("{}: {}", title, message)
+        };
+        match severity.log_level() {
+            logging::Level::Error => tracing::error!(target: "Notify", "{}", line),
+            logging::Level::Warn => tracing::warn!(target: "Notify", "{}", line),
+            _ => tracing::info!(target: "Notify", "{}", line),
         }
+        self.notifications
+            .push(severity, title, message, logging::now_ms());
+        self.update_notifications(cx);
+    }
 
-        if let Some(uuid) = table.destroy_clicked(actions) {
-            log!("[App] Destroy button clicked for {}", uuid);
-            self.destroy_dataflow(cx, &uuid);
+    /// Mirror an important event as an OS notification when enabled and the
+    /// window is in the background.
+    fn notify_desktop(&self, title: &str, message: &str) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if desktop_notify::should_send(self.settings.desktop_notifications, self.window_focused) {
+            desktop_notify::send(title, message);
         }
+        #[cfg(target_arch = "wasm32")]
+        let _ = (title, message);
+    }
 
-        if let Some(uuid) = table.logs_clicked(actions) {
-            log!("[App] Logs button clicked for {}", uuid);
-            self.view_dataflow_logs(&uuid);
+    fn update_notifications(&mut self, cx: &mut Cx) {
+        self.ui
+            .notification_toasts(ids!(notification_toasts))
+            .set_notifications(cx, self.notifications.active());
+        if self.drawer_open {
+            self.notifications.mark_read();
+            self.ui
+                .notification_drawer(ids!(notification_drawer))
+                .set_notifications(cx, self.notifications.history());
         }
+        let label = match self.notifications.unread() {
+            0 => i18n::tr("header.notifications"),
+            n => i18n::tr_args("header.notifications_unread", &[("count", &n.to_string())]),
+        };
+        self.ui
+            .button(ids!(notifications_button))
+            .set_text(cx, &label);
+        self.ui.redraw(cx);
     }
-}
 
-impl AppMain for App {
-    fn handle_event(&mut self, cx: &mut Cx, event: &Event) {
-        self.match_event(cx, event);
+    /// Track window moves/resizes and restore the saved geometry for the
+    /// monitor configuration once it is known.
+    fn handle_window_geom(&mut self, cx: &mut Cx, geom: &WindowGeom) {
+        // The reported DPI includes the UI scale; key and store geometry
+        // unscaled so changing the scale doesn't lose the saved layout.
+        let system_dpi = geom.dpi_factor / self.ui_scale_applied;
+        if self.system_dpi.is_none() {
+            self.system_dpi = Some(system_dpi);
+            self.apply_ui_scale(cx);
+        }
+        self.system_dpi = Some(system_dpi);
+        if !self.geometry_restored && self.restore_window_geometry(cx) {
+            return;
+        }
+        // Geometry is keyed by monitors, so not tracked before they are known
+        #[cfg(not(target_arch = "wasm32"))]
+        let Some(monitors) = monitors::detected() else {
+            return;
+        };
+        #[cfg(target_arch = "wasm32")]
+        let monitors = Vec::new();
+        let key = layout::monitor_key(&monitors, system_dpi);
+        let scale = self.ui_scale_applied;
+        let geometry = WindowGeometry {
+            width: geom.inner_size.x * scale,
+            height: geom.inner_size.y * scale,
+            x: geom.position.x * scale,
+            y: geom.position.y * scale,
+        };
+        if self.window_geometry.as_ref() != Some(&(key.clone(), geometry)) {
+            self.window_geometry = Some((key, geometry));
+            self.geometry_changed_at = Some(Instant::now());
+        }
+    }
 
-        // Handle next frame for initialization and auto-refresh
-        if let Some(ne) = self.next_frame.is_event(event) {
-            if !self.initialized {
-                self.initialized = true;
-                self.last_refresh_time = ne.time;
-                log!("[App] Initializing dataflow table on first frame");
-                self.refresh_dataflows(cx);
-            } else {
-                // Check if it's time for auto-refresh
-                let elapsed = ne.time - self.last_refresh_time;
-                if elapsed >= AUTO_REFRESH_INTERVAL {
-                    self.last_refresh_time = ne.time;
+    /// Once the DPI and monitors are known, move the window to the geometry
+    /// saved for those monitors, kept on one of them. Returns whether it
+    /// was moved.
+    fn restore_window_geometry(&mut self, cx: &mut Cx) -> bool {
+        let Some(system_dpi) = self.system_dpi else {
+            return false;
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let Some(monitors) = monitors::detected() else {
+            return false;
+        };
+        #[cfg(target_arch = "wasm32")]
+        let monitors = Vec::new();
+        self.geometry_restored = true;
+        let key = layout::monitor_key(&monitors, system_dpi);
+        let saved = self
+            .settings
+            .window_geometry
+            .get(&key)
+            .or_else(|| {
+                let legacy = layout::legacy_monitor_key(system_dpi);
+                self.settings.window_geometry.get(&legacy)
+            })
+            .copied();
+        let Some(saved) = saved.filter(|g| g.is_valid()) else {
+            return false;
+        };
+        let saved = layout::clamp_to_monitors(saved, &monitors, system_dpi);
+        tracing::debug!(target: "App", "Restoring window geometry for {}", key);
+        self.window_geometry = Some((key, saved));
+        self.apply_window_geometry(cx, saved);
+        true
+    }
 
-                    match self.active_panel {
-                        ActivePanel::Dataflows => {
-                            log!("[App] Auto-refresh triggered after {:.1}s", elapsed);
-                            self.refresh_dataflows(cx);
-                        }
-                        ActivePanel::Traces =>
-                        {
-                            #[cfg(not(target_arch = "wasm32"))]
-                            if self.signoz_available {
-                                log!("[App] Auto-refresh traces after {:.1}s", elapsed);
-                                self.refresh_traces(cx);
-                            }
-                        }
-                    }
-                }
+    /// Move the splitters to their saved positions.
+    fn restore_splits(&mut self, cx: &mut Cx) {
+        let sizes = &self.settings.split_sizes;
+        let splits = [
+            (
+                ids!(main_splitter),
+                SplitterAlign::FromB(layout::split_size(sizes, layout::CHAT_SPLIT)),
+            ),
+            (
+                ids!(dataflow_splitter),
+                SplitterAlign::FromA(layout::split_size(sizes, layout::DATAFLOW_SPLIT)),
+            ),
+        ];
+        for (id, align) in splits {
+            if let Some(mut splitter) = self.ui.widget(id).borrow_mut::<Splitter>() {
+                splitter.set_align(align);
             }
+        }
+        self.ui.redraw(cx);
+    }
 
-            // Poll SigNoz responses
-            #[cfg(not(target_arch = "wasm32"))]
+    /// Remember splitter positions as they are dragged.
+    fn handle_split_changes(&mut self, actions: &Actions) {
+        let splits = [
+            (layout::CHAT_SPLIT, self.ui.widget(ids!(main_splitter))),
+            (
+                layout::DATAFLOW_SPLIT,
+                self.ui.widget(ids!(dataflow_splitter)),
+            ),
+        ];
+        for (key, splitter) in splits {
+            let Some(action) = actions.find_widget_action(splitter.widget_uid()) else {
+                continue;
+            };
+            if let SplitterAction::Changed {
+                align: SplitterAlign::FromA(size) | SplitterAlign::FromB(size),
+                ..
+            } = action.cast()
             {
-                for response in bridge::take_signoz_responses() {
-                    self.handle_signoz_response(cx, response);
-                }
+                self.settings.split_sizes.insert(key.to_string(), size);
+                self.splits_changed_at = Some(Instant::now());
             }
-
-            // Schedule the next frame to keep auto-refresh running
-            self.next_frame = cx.new_next_frame();
         }
+    }
 
-        self.ui.handle_event(cx, event, &mut Scope::empty());
+    fn apply_window_geometry(&mut self, cx: &mut Cx, geometry: WindowGeometry) {
+        let scale = self.ui_scale_applied;
+        let window = self.ui.window(ids!(main_window));
+        window.resize(cx, dvec2(geometry.width, geometry.height) / scale);
+        window.reposition(cx, dvec2(geometry.x, geometry.y) / scale);
     }
-}
 
-impl App {
-    fn switch_to_panel(&mut self, cx: &mut Cx, panel: ActivePanel) {
-        self.active_panel = panel;
-        match panel {
-            ActivePanel::Dataflows => {
-                self.ui
-                    .view(ids!(dataflow_view))
-                    .apply_over(cx, live! { height: Fill });
-                self.ui
-                    .view(ids!(traces_view))
-                    .apply_over(cx, live! { height: 0 });
-            }
-            ActivePanel::Traces => {
-                self.ui
-                    .view(ids!(dataflow_view))
-                    .apply_over(cx, live! { height: 0 });
-                self.ui
-                    .view(ids!(traces_view))
-                    .apply_over(cx, live! { height: Fill });
-            }
-        }
+    /// Scale the whole UI by overriding the window DPI with the monitor DPI
+    /// times the UI scale setting.
+    fn apply_ui_scale(&mut self, cx: &mut Cx) {
+        let Some(system_dpi) = self.system_dpi else {
+            return;
+        };
+        let percent = layout::clamp_ui_scale(self.settings.ui_scale_percent);
+        let dpi = layout::scaled_dpi(system_dpi, percent);
+        self.ui.window(ids!(main_window)).apply_over(
+            cx,
+            live! {
+                window: { dpi_override: (dpi) }
+            },
+        );
+        self.ui_scale_applied = percent as f64 / 100.0;
         self.ui.redraw(cx);
     }
 
-    fn refresh_dataflows(&mut self, cx: &mut Cx) {
-        log!("[App] refresh_dataflows called");
-        let table = self.ui.dataflow_table(ids!(dataflow_table));
-        table.set_loading(cx);
-
-        // Execute dora list command
-        let result = execute_tool("dora_list", "refresh", &serde_json::json!({}));
-        log!(
-            "[App] dora_list result: is_error={}, content={}",
-            result.is_error,
-            &result.content
+    fn update_layout_dropdown(&mut self, cx: &mut Cx) {
+        let mut labels = vec![i18n::tr("header.layout")];
+        labels.extend(
+            layout::all_presets(&self.settings.layout_presets)
+                .into_iter()
+                .map(|p| p.name),
         );
+        let dropdown = self.ui.drop_down(ids!(layout_dropdown));
+        dropdown.set_labels(cx, labels);
+        dropdown.set_selected_item(cx, 0);
+    }
 
-        if result.is_error {
-            table.set_error(cx, &result.content);
-        } else {
-            // Try parsing as JSON array first, then NDJSON
-            let dataflows = if result.content.trim().starts_with('[') {
-                DataflowInfo::parse_json_array(&result.content)
-            } else {
-                DataflowInfo::parse_ndjson(&result.content)
-            };
-            log!("[App] Parsed {} dataflows", dataflows.len());
-            table.set_dataflows(cx, dataflows);
+    /// Restore a preset's panel, drawer state and window geometry.
+    fn apply_layout(&mut self, cx: &mut Cx, name: &str) {
+        let Some(preset) = layout::all_presets(&self.settings.layout_presets)
+            .into_iter()
+            .find(|p| p.name == name)
+        else {
+            return;
+        };
+        tracing::info!(target: "App", "Applying layout '{}'", preset.name);
+        match ActivePanel::from_name(&preset.panel) {
+            Some(ActivePanel::Traces) => self.show_traces(cx),
+            Some(panel) => self.switch_to_panel(cx, panel),
+            None => tracing::warn!(
+                target: "App",
+                "Layout '{}' has unknown panel '{}'",
+                preset.name,
+                preset.panel
+            ),
+        }
+        self.set_drawer_open(cx, preset.drawer_open);
+        let Some(geometry) = preset.window.filter(|g| g.is_valid()) else {
+            return;
+        };
+        if preset.size_only {
+            let scale = self.ui_scale_applied;
+            let window = self.ui.window(ids!(main_window));
+            window.resize(cx, dvec2(geometry.width, geometry.height) / scale);
+            return;
         }
+        #[cfg(not(target_arch = "wasm32"))]
+        let geometry = match (self.system_dpi, monitors::detected()) {
+            (Some(system_dpi), Some(monitors)) => {
+                layout::clamp_to_monitors(geometry, &monitors, system_dpi)
+            }
+            _ => geometry,
+        };
+        self.apply_window_geometry(cx, geometry);
     }
 
-    #[cfg(not(target_arch = "wasm32"))]
-    fn refresh_traces(&mut self, cx: &mut Cx) {
-        log!("[App] refresh_traces called");
-        let panel = self.ui.traces_panel(ids!(traces_panel));
-        panel.set_loading(cx);
-
-        let query = crate::otlp::types::TraceQuery {
-            limit: Some(100),
-            ..Default::default()
+    /// Save the current panel, drawer state and window geometry as a preset.
+    fn save_layout(&mut self, cx: &mut Cx, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            self.notify(
+                cx,
+                Severity::Warning,
+                &i18n::tr("notify.layout_not_saved"),
+                &i18n::tr("notify.layout_not_saved_message"),
+            );
+            return;
+        }
+        let preset = LayoutPreset {
+            name: name.to_string(),
+            panel: self.active_panel.as_str().to_string(),
+            drawer_open: self.drawer_open,
+            window: self.window_geometry.as_ref().map(|(_, g)| *g),
+            size_only: false,
         };
-        bridge::request_traces(query);
+        layout::upsert_preset(&mut self.settings.layout_presets, preset);
+        self.save_settings();
+        self.update_layout_dropdown(cx);
+        self.notify(
+            cx,
+            Severity::Success,
+            &i18n::tr("notify.layout_saved"),
+            &i18n::tr_args("notify.layout_saved_message", &[("name", &name)]),
+        );
     }
 
+    /// Save the traces panel filter under a name, replacing any query of
+    /// that name.
     #[cfg(not(target_arch = "wasm32"))]
-    fn handle_signoz_response(&mut self, cx: &mut Cx, response: crate::otlp::SignozResponse) {
-        match response {
-            crate::otlp::SignozResponse::HealthOk => {
-                log!("[App] SigNoz connected");
+    fn save_trace_query(&mut self, cx: &mut Cx, query: crate::traces::SavedTraceQuery) {
+        let message = i18n::tr_args(
+            "notify.trace_query_saved_message",
+            &[("name", &query.name), ("filter", &query.filter.describe())],
+        );
+        crate::traces::saved::upsert_query(&mut self.settings.saved_trace_queries, query);
+        self.save_settings();
+        self.update_saved_trace_queries(cx);
+        self.notify(
+            cx,
+            Severity::Success,
+            &i18n::tr("notify.trace_query_saved"),
+            &message,
+        );
+    }
+
+    /// Show the saved trace queries in the traces panel and offer them to
+    /// the chat agent.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn update_saved_trace_queries(&mut self, cx: &mut Cx) {
+        self.ui
+            .traces_panel(ids!(traces_panel))
+            .set_saved_queries(cx, &self.settings.saved_trace_queries);
+        if let Some(chat) = &self.chat {
+            chat.set_saved_trace_queries(&self.settings.saved_trace_queries);
+        }
+    }
+
+    /// Offer the built-in and saved prompt templates to the chat.
+    fn update_prompt_templates(&mut self, cx: &mut Cx) {
+        let saved = &self.settings.prompt_templates;
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_prompt_templates(
+                cx,
+                &format!("Templates: {}", prompts::describe_templates(saved)),
+            );
+        self.ui
+            .chat_screen(ids!(chat_screen))
+            .set_prompt_templates(cx, prompts::templates(saved));
+    }
+
+    /// Send chat requests where the settings say.
+    fn update_llm_backend(&mut self, cx: &mut Cx) {
+        let url = self.settings.local_llm_url.as_deref().unwrap_or("");
+        if let Some(chat) = &self.chat {
+            chat.set_backend(self.settings.llm_backend, url);
+        }
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .set_llm_backend(cx, self.settings.llm_backend, url);
+    }
+
+    fn apply_llm_backend(&mut self, cx: &mut Cx, backend: LlmBackend, url: &str) {
+        let url = url.trim();
+        if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+            self.notify(
+                cx,
+                Severity::Error,
+                &i18n::tr("notify.invalid_local_server_url"),
+                &i18n::tr("notify.invalid_local_server_url_message"),
+            );
+            return;
+        }
+        self.settings.llm_backend = backend;
+        self.settings.local_llm_url = (!url.is_empty()).then(|| url.to_string());
+        self.save_settings();
+        self.update_llm_backend(cx);
+        // The models to pick from depend on the backend
+        self.ui.chat_screen(ids!(chat_screen)).fetch_models(cx);
+        self.notify(
+            cx,
+            Severity::Success,
+            &i18n::tr("notify.chat_backend_saved"),
+            &i18n::tr_args(
+                "notify.chat_backend_saved_message",
+                &[("backend", &i18n::tr(backend.label_key()))],
+            ),
+        );
+    }
+
+    /// Show the system prompt of a template for editing.
+    fn edit_prompt_template(&mut self, cx: &mut Cx, name: &str) {
+        let name = Some(name.trim())
+            .filter(|n| !n.is_empty())
+            .unwrap_or(prompts::DEFAULT_TEMPLATE);
+        let templates = prompts::templates(&self.settings.prompt_templates);
+        match prompts::find_template(&templates, name) {
+            Some(template) => self
+                .ui
+                .diagnostics_panel(ids!(diagnostics_panel))
+                .set_prompt(cx, &template.name, &template.prompt),
+            None => self.notify(
+                cx,
+                Severity::Warning,
+                &i18n::tr("notify.unknown_prompt_template"),
+                &i18n::tr_args("notify.unknown_prompt_template_message", &[("name", name)]),
+            ),
+        }
+    }
+
+    fn save_prompt_template(&mut self, cx: &mut Cx, name: &str, prompt: &str) {
+        if let Err(e) = prompts::save_template(&mut self.settings.prompt_templates, name, prompt) {
+            self.notify(
+                cx,
+                Severity::Warning,
+                &i18n::tr("notify.prompt_template_not_saved"),
+                &e,
+            );
+            return;
+        }
+        self.save_settings();
+        self.update_prompt_templates(cx);
+        if prompt.trim().is_empty() {
+            // Show what an edited built-in went back to
+            let templates = prompts::templates(&self.settings.prompt_templates);
+            if let Some(template) = prompts::find_template(&templates, name) {
                 self.ui
-                    .label(ids!(connection_label))
-                    .set_text(cx, "Connected");
-            }
-            crate::otlp::SignozResponse::HealthError(e) => {
-                log!("[App] SigNoz health error: {}", e);
-                let msg = format!("SigNoz: {}", truncate_str(&e, 40));
-                self.ui.label(ids!(connection_label)).set_text(cx, &msg);
-            }
-            crate::otlp::SignozResponse::Traces(spans) => {
-                log!("[App] Received {} trace spans", spans.len());
-                self.traces_loaded_once = true;
-                let panel = self.ui.traces_panel(ids!(traces_panel));
-                panel.set_spans(cx, spans);
-            }
-            crate::otlp::SignozResponse::TracesError(e) => {
-                log!("[App] Traces query error: {}", e);
-                let panel = self.ui.traces_panel(ids!(traces_panel));
-                panel.set_error(cx, &e);
+                    .diagnostics_panel(ids!(diagnostics_panel))
+                    .set_prompt(cx, &template.name, &template.prompt);
             }
+            self.notify(
+                cx,
+                Severity::Success,
+                &i18n::tr("notify.prompt_template_reset"),
+                name.trim(),
+            );
+        } else {
+            self.notify(
+                cx,
+                Severity::Success,
+                &i18n::tr("notify.prompt_template_saved"),
+                name.trim(),
+            );
         }
     }
 
-    fn stop_dataflow(&mut self, cx: &mut Cx, uuid: &str) {
-        let args = serde_json::json!({ "dataflow_id": uuid });
-        let result = execute_tool("dora_stop", "stop", &args);
+    /// Restore the time range, trace filter, drawer and panel of the last
+    /// session.
+    fn restore_session(&mut self, cx: &mut Cx) {
+        // Each step saves the session, so read it all up front
+        let session = self.settings.session.clone();
+        self.ui
+            .traces_panel(ids!(traces_panel))
+            .set_filter(cx, session.trace_filter.clone());
+        #[cfg(not(target_arch = "wasm32"))]
+        self.ui
+            .node_metrics_panel(ids!(node_metrics_panel))
+            .set_range(cx, session.time_range.clone());
+        let run = session.snapped_run.as_ref().and_then(|uuid| {
+            self.run_history
+                .runs()
+                .iter()
+                .find(|run| &run.uuid == uuid)
+                .cloned()
+        });
+        if let Some(run) = run {
+            self.snap_to_run(cx, run);
+        }
+        self.set_drawer_open(cx, session.drawer_open);
+        match ActivePanel::from_name(&session.panel) {
+            Some(ActivePanel::Traces) => self.show_traces(cx),
+            Some(panel) => self.switch_to_panel(cx, panel),
+            None => self.save_session(),
+        }
+    }
 
-        if result.is_error {
-            log!("Error stopping dataflow: {}", result.content);
+    /// Remember the current view for the next launch.
+    fn save_session(&mut self) {
+        // Node metrics are desktop only; the web build keeps the saved range
+        #[cfg(not(target_arch = "wasm32"))]
+        let time_range = self.ui.node_metrics_panel(ids!(node_metrics_panel)).range();
+        #[cfg(target_arch = "wasm32")]
+        let time_range = self.settings.session.time_range.clone();
+        let session = Session {
+            panel: self.active_panel.as_str().to_string(),
+            drawer_open: self.drawer_open,
+            snapped_run: self.snapped_run.as_ref().map(|run| run.uuid.clone()),
+            trace_filter: self.ui.traces_panel(ids!(traces_panel)).filter(),
+            time_range,
+        };
+        if session != self.settings.session {
+            self.settings.session = session;
+            self.save_settings();
         }
+    }
 
-        // Refresh the table after stopping
-        self.refresh_dataflows(cx);
+    fn set_drawer_open(&mut self, cx: &mut Cx, open: bool) {
+        self.drawer_open = open;
+        self.save_session();
+        self.ui
+            .notification_drawer(ids!(notification_drawer))
+            .set_visible(cx, open);
+        self.update_notifications(cx);
     }
 
-    fn destroy_dataflow(&mut self, cx: &mut Cx, uuid: &str) {
-        let args = serde_json::json!({ "dataflow_id": uuid });
-        let result = execute_tool("dora_destroy", "destroy", &args);
+    /// Switch the UI language and re-read the strings set from code.
+    fn set_locale(&mut self, cx: &mut Cx, locale: Locale) {
+        i18n::set_locale(locale);
+        i18n::localize(
+            cx,
+            &[
+                (ids!(tab_dataflows), "tab.dataflows"),
+                (ids!(tab_traces), "tab.traces"),
+                (ids!(tab_alerts), "tab.alerts"),
+                (ids!(tab_diagnostics), "tab.diagnostics"),
+                (ids!(tab_query), "tab.query"),
+                (ids!(new_project_button), "header.new"),
+                (ids!(validate_button), "header.validate"),
+                (ids!(start_button), "action.start"),
+                (ids!(env_button), "header.env"),
+                (ids!(refresh_button), "action.refresh"),
+                (ids!(start_runtime_button), "header.start_runtime"),
+                (ids!(refresh_retry_button), "action.retry"),
+                (ids!(snap_clear_button), "action.clear"),
+            ],
+            |path| self.ui.widget(path),
+        );
+        self.ui
+            .diagnostics_panel(ids!(diagnostics_panel))
+            .localize(cx);
+        self.ui.dataflow_table(ids!(dataflow_table)).localize(cx);
+        self.ui.dataflow_detail(ids!(dataflow_detail)).localize(cx);
+        self.ui
+            .node_metrics_panel(ids!(node_metrics_panel))
+            .localize(cx);
+        self.ui.traces_panel(ids!(traces_panel)).localize(cx);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.ui.alerts_panel(ids!(alerts_panel)).localize(cx);
+            self.ui.query_panel(ids!(query_panel)).localize(cx);
+        }
+        self.ui.chat_screen(ids!(chat_screen)).localize(cx);
+        self.ui.env_editor(ids!(env_editor)).localize(cx);
+        self.ui.status_bar(ids!(status_bar)).localize(cx);
+        self.ui.debug_console(ids!(debug_console)).localize(cx);
+        self.ui
+            .notification_drawer(ids!(notification_drawer))
+            .localize(cx);
+        self.update_notifications(cx);
+        // Force the task summary to be shown again in the new language
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.task_status = (String::new(), usize::MAX);
+            self.update_task_status(cx);
+        }
+        self.update_recent_dropdown(cx);
+        self.update_run_dropdown(cx);
+        self.update_layout_dropdown(cx);
+        self.update_refresh_banner(cx);
+        self.ui.redraw(cx);
+    }
 
-        if result.is_error {
-            log!("Error destroying dataflow: {}", result.content);
+    fn set_debug_console_open(&mut self, cx: &mut Cx, open: bool) {
+        self.debug_console_open = open;
+        self.debug_console_count = None;
+        self.ui
+            .debug_console(ids!(debug_console))
+            .set_visible(cx, open);
+        self.update_debug_console(cx);
+    }
+
+    /// Show records logged since the last fill while the console is open.
+    fn update_debug_console(&mut self, cx: &mut Cx) {
+        if !self.debug_console_open {
+            return;
         }
+        let count = logging::record_count();
+        if self.debug_console_count == Some(count) {
+            return;
+        }
+        self.debug_console_count = Some(count);
+        self.ui
+            .debug_console(ids!(debug_console))
+            .set_records(cx, logging::recent_records());
+    }
+}
 
-        // Refresh the table after destroying
-        self.refresh_dataflows(cx);
+/// Hooks for the headless test harness ([`crate::harness`]).
+#[cfg(all(not(target_arch = "wasm32"), any(test, feature = "test-harness")))]
+impl App {
+    pub fn ui(&self) -> &WidgetRef {
+        &self.ui
     }
 
-    fn view_dataflow_logs(&self, uuid: &str) {
-        let args = serde_json::json!({ "dataflow_id": uuid });
-        let result = execute_tool("dora_logs", "logs", &args);
+    pub fn notifications(&self) -> &NotificationCenter {
+        &self.notifications
+    }
 
-        if result.is_error {
-            log!("Error getting logs: {}", result.content);
-        } else {
-            log!("Dataflow logs for {}:\n{}", uuid, result.content);
-        }
+    pub fn set_dora_client(&mut self, client: Box<dyn DoraClient>) {
+        self.dora = client.into();
+    }
+
+    /// Whether a background task the app waits on is still running.
+    pub fn has_pending_tasks(&self) -> bool {
+        !self.task_jobs.is_empty()
+    }
+
+    /// Frame the app waits for, to address `Event::NextFrame` to it.
+    pub fn next_frame_id(&self) -> NextFrame {
+        self.next_frame
+    }
+}
+
+/// Resolve the bundle path entered by the user, defaulting to the data directory.
+fn bundle_path(input: &str) -> Result<std::path::PathBuf, String> {
+    let input = input.trim();
+    if !input.is_empty() {
+        return Ok(std::path::PathBuf::from(input));
+    }
+    settings::data_dir()
+        .map(|d| d.join("dora-studio-config.json"))
+        .ok_or_else(|| "Enter a path for the config bundle".to_string())
+}
+
+/// Import the Grafana dashboard at `path` into the dashboards directory,
+/// returning what was imported and skipped.
+fn import_grafana(path: &str) -> Result<String, String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let import = dashboard::grafana::import(&text)?;
+    let dir = dashboard::dashboards_dir().ok_or("No data directory to save dashboards in")?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let target = dir.join(dashboard::file_name(&import.dashboard.title));
+    let summary = import.describe();
+    Envelope::new(import.dashboard, logging::now_ms()).write_to(&target)?;
+    Ok(format!("{}\nSaved to {}", summary, target.display()))
+}
+
+/// Where to export query results: the entered path, or the data directory.
+#[cfg(not(target_arch = "wasm32"))]
+fn query_export_path(input: &str) -> Result<std::path::PathBuf, String> {
+    let input = input.trim();
+    if !input.is_empty() {
+        return Ok(std::path::PathBuf::from(input));
+    }
+    settings::data_dir()
+        .map(|d| d.join("query-results.csv"))
+        .ok_or_else(|| i18n::tr("query.enter_csv_path"))
+}
+
+/// `Failed to write <path>: <error>`, in the active language.
+#[cfg(not(target_arch = "wasm32"))]
+fn write_error(path: &std::path::Path, error: impl std::fmt::Display) -> String {
+    i18n::tr_args(
+        "error.write_file",
+        &[
+            ("path", &path.display().to_string()),
+            ("error", &error.to_string()),
+        ],
+    )
+}
+
+/// Where to export a dataflow's logs: the entered path, or the data
+/// directory.
+#[cfg(not(target_arch = "wasm32"))]
+fn logs_export_path(input: &str) -> Result<std::path::PathBuf, String> {
+    let input = input.trim();
+    if !input.is_empty() {
+        return Ok(std::path::PathBuf::from(input));
     }
+    settings::data_dir()
+        .map(|d| d.join("dataflow-logs.txt"))
+        .ok_or_else(|| i18n::tr("detail.enter_log_path"))
+}
+
+/// The first dataflow file among dropped items, if any.
+#[cfg(not(target_arch = "wasm32"))]
+fn dropped_dataflow(items: &[DragItem]) -> Option<String> {
+    items.iter().find_map(|item| match item {
+        DragItem::FilePath { path, .. } if is_dataflow_file(path) => Some(path.clone()),
+        _ => None,
+    })
 }
 
-fn truncate_str(s: &str, max: usize) -> String {
-    if s.len() <= max {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max])
+/// Where to write a dataflow from the chat: the start path, or the data
+/// directory.
+#[cfg(not(target_arch = "wasm32"))]
+fn agent_dataflow_path(input: &str) -> Result<std::path::PathBuf, String> {
+    let input = input.trim();
+    if !input.is_empty() {
+        return Ok(std::path::PathBuf::from(input));
     }
+    settings::data_dir()
+        .map(|d| d.join("dataflows").join("agent-dataflow.yml"))
+        .ok_or_else(|| "Enter a path for the dataflow".to_string())
 }
 
 #[cfg(test)]
@@ -511,12 +5410,6 @@ mod tests {
         assert_eq!(panel, ActivePanel::Dataflows);
     }
 
-    #[test]
-    fn test_truncate_str() {
-        assert_eq!(truncate_str("hello", 10), "hello");
-        assert_eq!(truncate_str("hello world", 5), "hello...");
-    }
-
     // ============================================================================
     // App Module Structure Tests
     // ============================================================================
@@ -0,0 +1,15 @@
+//! Time-series charts shared by the metrics views.
+
+pub mod model;
+pub mod time_series_chart;
+
+pub use model::{ChartPoint, ChartSeries, Viewport};
+pub use time_series_chart::{
+    TimeSeriesChart, TimeSeriesChartAction, TimeSeriesChartRef, TimeSeriesChartWidgetRefExt,
+};
+
+use makepad_widgets::*;
+
+pub fn live_design(cx: &mut Cx) {
+    time_series_chart::live_design(cx);
+}
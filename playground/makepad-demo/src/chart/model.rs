@@ -0,0 +1,253 @@
+//! Data and geometry of a time-series chart, apart from drawing: which part
+//! of the data is in view, where a time or value falls in the plot, axis
+//! ticks and the values under the crosshair.
+
+use crate::otlp::types::TimeRange;
+
+/// Narrowest time range a drag zooms to.
+pub const MIN_ZOOM_MS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChartPoint {
+    pub time_ms: u64,
+    pub value: f64,
+}
+
+/// One line of a chart, its points in time order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartSeries {
+    pub name: String,
+    /// rgb
+    pub color: u32,
+    pub points: Vec<ChartPoint>,
+}
+
+impl ChartSeries {
+    /// A series of `values` sampled at `times_ms`.
+    pub fn new(
+        name: &str,
+        color: u32,
+        times_ms: impl IntoIterator<Item = u64>,
+        values: impl IntoIterator<Item = f64>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            color,
+            points: times_ms
+                .into_iter()
+                .zip(values)
+                .map(|(time_ms, value)| ChartPoint { time_ms, value })
+                .collect(),
+        }
+    }
+}
+
+/// The times and values in view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Viewport {
+    /// The view of `series` over `range`, or over all their points when
+    /// unset; values from 0 (or the lowest, if negative) to the highest in
+    /// view, plus a little headroom. `None` without points to show.
+    pub fn fit(series: &[ChartSeries], range: Option<&TimeRange>) -> Option<Self> {
+        let points = || series.iter().flat_map(|s| s.points.iter());
+        let (start_ms, end_ms) = match range {
+            Some(range) => (range.start_ms, range.end_ms.max(range.start_ms + 1)),
+            None => {
+                let start = points().map(|p| p.time_ms).min()?;
+                let end = points().map(|p| p.time_ms).max()?;
+                (start, end.max(start + 1))
+            }
+        };
+        let values: Vec<f64> = points()
+            .filter(|p| (start_ms..=end_ms).contains(&p.time_ms) && p.value.is_finite())
+            .map(|p| p.value)
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+        let min = values.iter().copied().fold(0.0, f64::min);
+        let max = values.iter().copied().fold(f64::MIN, f64::max);
+        let headroom = if max > min { (max - min) * 0.1 } else { 1.0 };
+        Some(Self {
+            start_ms,
+            end_ms,
+            min,
+            max: max + headroom,
+        })
+    }
+
+    /// Where `time_ms` falls across the plot, 0 at the left edge and 1 at
+    /// the right.
+    pub fn time_fraction(&self, time_ms: u64) -> f64 {
+        (time_ms as f64 - self.start_ms as f64) / (self.end_ms - self.start_ms) as f64
+    }
+
+    /// Where `value` falls up the plot, 0 at the bottom and 1 at the top.
+    pub fn value_fraction(&self, value: f64) -> f64 {
+        (value - self.min) / (self.max - self.min)
+    }
+
+    /// Time at `fraction` across the plot.
+    pub fn time_at(&self, fraction: f64) -> u64 {
+        let span = (self.end_ms - self.start_ms) as f64;
+        (self.start_ms as f64 + fraction.clamp(0.0, 1.0) * span).round() as u64
+    }
+
+    /// The range between two fractions across the plot, in either order,
+    /// widened to [`MIN_ZOOM_MS`].
+    pub fn zoom(&self, from: f64, to: f64) -> TimeRange {
+        let (start, end) = (self.time_at(from.min(to)), self.time_at(from.max(to)));
+        let missing = MIN_ZOOM_MS.saturating_sub(end - start);
+        let start = start.saturating_sub(missing / 2);
+        TimeRange {
+            start_ms: start,
+            end_ms: start + (end - start).max(MIN_ZOOM_MS),
+        }
+    }
+
+    /// The range moved by `fraction` of its width, later when positive.
+    pub fn pan(&self, fraction: f64) -> TimeRange {
+        let shift = (fraction * (self.end_ms - self.start_ms) as f64).round() as i64;
+        let start = self.start_ms.saturating_add_signed(shift);
+        TimeRange {
+            start_ms: start,
+            end_ms: start + (self.end_ms - self.start_ms),
+        }
+    }
+
+    /// About `count` round values between `min` and `max` for the value
+    /// axis, e.g. 0, 20, 40.
+    pub fn value_ticks(&self, count: usize) -> Vec<f64> {
+        let span = self.max - self.min;
+        if count == 0 || span <= 0.0 || !span.is_finite() {
+            return Vec::new();
+        }
+        let rough = span / count as f64;
+        let magnitude = 10f64.powf(rough.log10().floor());
+        let step = [1.0, 2.0, 5.0, 10.0]
+            .iter()
+            .map(|m| m * magnitude)
+            .find(|step| *step >= rough)
+            .unwrap_or(10.0 * magnitude);
+        let first = (self.min / step).ceil() as i64;
+        let last = (self.max / step).floor() as i64;
+        (first..=last).map(|i| i as f64 * step).collect()
+    }
+}
+
+/// The point of each series nearest to `time_ms` within the view, with the
+/// index of its series, for the crosshair.
+pub fn values_at(
+    series: &[ChartSeries],
+    viewport: &Viewport,
+    time_ms: u64,
+) -> Vec<(usize, ChartPoint)> {
+    series
+        .iter()
+        .enumerate()
+        .filter_map(|(i, s)| {
+            s.points
+                .iter()
+                .filter(|p| (viewport.start_ms..=viewport.end_ms).contains(&p.time_ms))
+                .min_by_key(|p| p.time_ms.abs_diff(time_ms))
+                .map(|p| (i, *p))
+        })
+        .collect()
+}
+
+/// Indices of `count` series in drawing order: the highlighted one last,
+/// so it is drawn over the others.
+pub fn draw_order(count: usize, highlighted: Option<usize>) -> Vec<usize> {
+    let highlighted = highlighted.filter(|&i| i < count);
+    (0..count)
+        .filter(|&i| Some(i) != highlighted)
+        .chain(highlighted)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series() -> Vec<ChartSeries> {
+        vec![
+            ChartSeries::new("a", 0xff0000, [1_000, 2_000, 3_000], [10.0, 30.0, 20.0]),
+            ChartSeries::new("b", 0x0000ff, [1_500, 5_000], [5.0, 90.0]),
+        ]
+    }
+
+    #[test]
+    fn test_fit_and_map() {
+        let view = Viewport::fit(&series(), None).unwrap();
+        assert_eq!((view.start_ms, view.end_ms), (1_000, 5_000));
+        assert_eq!(view.min, 0.0);
+        assert_eq!(view.max, 99.0);
+        assert_eq!(view.time_fraction(3_000), 0.5);
+        assert_eq!(view.time_at(0.25), 2_000);
+        assert_eq!(view.value_fraction(49.5), 0.5);
+
+        // A narrower range only fits the values in it
+        let range = TimeRange {
+            start_ms: 1_000,
+            end_ms: 3_000,
+        };
+        let view = Viewport::fit(&series(), Some(&range)).unwrap();
+        assert_eq!(view.max, 33.0);
+        assert!(Viewport::fit(&[], None).is_none());
+        assert_eq!(view.value_ticks(4), [0.0, 10.0, 20.0, 30.0]);
+    }
+
+    #[test]
+    fn test_zoom_and_pan() {
+        let view = Viewport::fit(&series(), None).unwrap();
+        let zoomed = view.zoom(0.75, 0.25);
+        assert_eq!(
+            zoomed,
+            TimeRange {
+                start_ms: 2_000,
+                end_ms: 4_000
+            }
+        );
+        // Too narrow a drag still shows a second
+        let narrow = view.zoom(0.5, 0.5);
+        assert_eq!(narrow.end_ms - narrow.start_ms, MIN_ZOOM_MS);
+        assert_eq!(narrow.start_ms, 2_500);
+
+        let panned = view.pan(-0.25);
+        assert_eq!((panned.start_ms, panned.end_ms), (0, 4_000));
+    }
+
+    #[test]
+    fn test_values_at_crosshair() {
+        let series = series();
+        let view = Viewport::fit(&series, None).unwrap();
+        let values = values_at(&series, &view, 2_400);
+        assert_eq!(values.len(), 2);
+        assert_eq!(
+            values[0],
+            (
+                0,
+                ChartPoint {
+                    time_ms: 2_000,
+                    value: 30.0
+                }
+            )
+        );
+        assert_eq!(values[1].1.value, 5.0);
+    }
+
+    #[test]
+    fn test_draw_order() {
+        assert_eq!(draw_order(3, None), [0, 1, 2]);
+        assert_eq!(draw_order(3, Some(0)), [1, 2, 0]);
+        // A stale index, after series were removed, is ignored
+        assert_eq!(draw_order(2, Some(5)), [0, 1]);
+    }
+}
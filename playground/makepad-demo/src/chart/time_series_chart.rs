@@ -0,0 +1,515 @@
+//! Time-series chart widget: one line (or filled area) per series, a value
+//! axis with round ticks and the time span at the bottom.
+//!
+//! Hovering shows a crosshair with the value of each series at that time,
+//! and emits [`TimeSeriesChartAction::Hovered`] so stacked charts can show
+//! theirs at the same time. One series can be highlighted, dimming the rest.
+//! Dragging across the plot zooms to the times dragged over, shift-drag pans
+//! and a double click shows all the data again; each emits
+//! [`TimeSeriesChartAction::RangeChanged`] so views backed by a query can
+//! fetch the new range.
+
+use makepad_widgets::*;
+
+use super::model::{self, ChartSeries, Viewport};
+use crate::datetime;
+use crate::otlp::types::TimeRange;
+use crate::text;
+use crate::units::{self, Unit};
+
+/// Space around the plot for the axis labels.
+const PLOT_LEFT: f64 = 48.0;
+const PLOT_RIGHT: f64 = 8.0;
+const PLOT_TOP: f64 = 6.0;
+const PLOT_BOTTOM: f64 = 18.0;
+/// Value axis ticks aimed for.
+const VALUE_TICKS: usize = 4;
+/// Shorter drags are clicks, not zooms.
+const MIN_DRAG_PX: f64 = 4.0;
+/// Opacity of an area series' fill.
+const AREA_ALPHA: f32 = 0.15;
+/// Opacity of the series that are not highlighted.
+const DIMMED_ALPHA: f32 = 0.25;
+const LINE_WIDTH: f64 = 2.0;
+const GRID_COLOR: u32 = 0xe2e8f0;
+const CROSSHAIR_COLOR: u32 = 0x94a3b8;
+const SELECTION_COLOR: u32 = 0x3b82f6;
+/// Crosshair readout size per line of text.
+const READOUT_WIDTH: f64 = 150.0;
+const READOUT_LINE_HEIGHT: f64 = 13.0;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    pub TimeSeriesChart = {{TimeSeriesChart}} {
+        width: Fill, height: 160
+        draw_bg: { color: #ffffff }
+        draw_plot: { color: #x0ea5e9 }
+        draw_label: {
+            color: #64748b
+            text_style: { font_size: 8.0 }
+        }
+    }
+}
+
+#[derive(Clone, Debug, DefaultNone)]
+pub enum TimeSeriesChartAction {
+    None,
+    /// The times shown after a zoom or pan; `None` after a double click
+    /// back to all the data.
+    RangeChanged(Option<TimeRange>),
+    /// The time under the pointer; `None` once it leaves the plot.
+    Hovered(Option<u64>),
+}
+
+/// A drag in progress, from where it started.
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    start_x: f64,
+    x: f64,
+    /// Shift was held: pan instead of zoom
+    pan: bool,
+    viewport: Viewport,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct TimeSeriesChart {
+    #[redraw]
+    #[live]
+    draw_bg: DrawColor,
+    /// Lines, fills, grid and crosshair
+    #[live]
+    draw_plot: DrawColor,
+    #[live]
+    draw_label: DrawText,
+    #[walk]
+    walk: Walk,
+    /// Fill below each line
+    #[live]
+    area: bool,
+    #[rust]
+    series: Vec<ChartSeries>,
+    #[rust]
+    unit: Unit,
+    /// Times zoomed or panned to; all the data when unset
+    #[rust]
+    range: Option<TimeRange>,
+    /// Plot area as last drawn, to map the pointer to times
+    #[rust]
+    plot: Rect,
+    #[rust]
+    viewport: Option<Viewport>,
+    #[rust]
+    hover_x: Option<f64>,
+    /// Time hovered on another chart, shown while this one is not hovered
+    #[rust]
+    crosshair_ms: Option<u64>,
+    /// Index into `series` drawn on top, the others dimmed
+    #[rust]
+    highlighted: Option<usize>,
+    #[rust]
+    drag: Option<Drag>,
+}
+
+impl Widget for TimeSeriesChart {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        match event.hits(cx, self.draw_bg.area()) {
+            Hit::FingerHoverIn(e) | Hit::FingerHoverOver(e) => {
+                self.hover_x = Some(e.abs.x);
+                self.redraw(cx);
+                self.hovered(cx, scope);
+            }
+            Hit::FingerHoverOut(_) => {
+                self.hover_x = None;
+                self.redraw(cx);
+                self.hovered(cx, scope);
+            }
+            Hit::FingerDown(e) => {
+                if e.tap_count == 2 {
+                    self.drag = None;
+                    self.set_range(cx, None);
+                    self.range_changed(cx, scope);
+                } else if let Some(viewport) = self.viewport {
+                    self.drag = Some(Drag {
+                        start_x: e.abs.x,
+                        x: e.abs.x,
+                        pan: e.modifiers.shift,
+                        viewport,
+                    });
+                }
+            }
+            Hit::FingerMove(e) => {
+                self.hover_x = Some(e.abs.x);
+                if let Some(drag) = &mut self.drag {
+                    drag.x = e.abs.x;
+                    if drag.pan {
+                        let shift = (drag.start_x - drag.x) / self.plot.size.x.max(1.0);
+                        self.range = Some(drag.viewport.pan(shift));
+                    }
+                }
+                self.redraw(cx);
+                self.hovered(cx, scope);
+            }
+            Hit::FingerUp(_) => {
+                let Some(drag) = self.drag.take() else {
+                    return;
+                };
+                if (drag.x - drag.start_x).abs() < MIN_DRAG_PX {
+                    self.redraw(cx);
+                    return;
+                }
+                if !drag.pan {
+                    let range = drag
+                        .viewport
+                        .zoom(self.plot_fraction(drag.start_x), self.plot_fraction(drag.x));
+                    self.range = Some(range);
+                }
+                self.redraw(cx);
+                self.range_changed(cx, scope);
+            }
+            _ => {}
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, _scope: &mut Scope, walk: Walk) -> DrawStep {
+        let rect = cx.walk_turtle(walk);
+        self.draw_bg.draw_abs(cx, rect);
+        self.plot = Rect {
+            pos: dvec2(rect.pos.x + PLOT_LEFT, rect.pos.y + PLOT_TOP),
+            size: dvec2(
+                (rect.size.x - PLOT_LEFT - PLOT_RIGHT).max(1.0),
+                (rect.size.y - PLOT_TOP - PLOT_BOTTOM).max(1.0),
+            ),
+        };
+        self.viewport = Viewport::fit(&self.series, self.range.as_ref());
+        let Some(viewport) = self.viewport else {
+            let pos = dvec2(self.plot.pos.x, self.plot.pos.y + self.plot.size.y / 2.0);
+            self.draw_label.draw_abs(cx, pos, "No data");
+            return DrawStep::done();
+        };
+        self.draw_axes(cx, &viewport);
+        for index in model::draw_order(self.series.len(), self.highlighted) {
+            self.draw_series(cx, &viewport, index);
+        }
+        self.draw_selection(cx);
+        self.draw_crosshair(cx, &viewport);
+        DrawStep::done()
+    }
+}
+
+impl TimeSeriesChart {
+    pub fn set_series(&mut self, cx: &mut Cx, series: Vec<ChartSeries>) {
+        self.series = series;
+        self.redraw(cx);
+    }
+
+    /// Unit of the values, for the axis and crosshair labels.
+    pub fn set_unit(&mut self, cx: &mut Cx, unit: Unit) {
+        self.unit = unit;
+        self.redraw(cx);
+    }
+
+    /// Show `range`, or all the data when `None`.
+    pub fn set_range(&mut self, cx: &mut Cx, range: Option<TimeRange>) {
+        self.range = range;
+        self.redraw(cx);
+    }
+
+    /// Show the crosshair at `time_ms`, as hovered on another chart, or
+    /// hide it.
+    pub fn set_crosshair(&mut self, cx: &mut Cx, time_ms: Option<u64>) {
+        if self.crosshair_ms != time_ms {
+            self.crosshair_ms = time_ms;
+            self.redraw(cx);
+        }
+    }
+
+    /// Draw the series at `index` on top and dim the others, or draw them
+    /// all alike when `None`.
+    pub fn set_highlighted(&mut self, cx: &mut Cx, index: Option<usize>) {
+        if self.highlighted != index {
+            self.highlighted = index;
+            self.redraw(cx);
+        }
+    }
+
+    /// The time under the pointer, if it is over the plot.
+    fn hover_time(&self) -> Option<u64> {
+        let x = self.hover_x?;
+        let plot = self.plot;
+        if x < plot.pos.x || x > plot.pos.x + plot.size.x {
+            return None;
+        }
+        Some(self.viewport?.time_at(self.plot_fraction(x)))
+    }
+
+    fn hovered(&self, cx: &mut Cx, scope: &Scope) {
+        cx.widget_action(
+            self.widget_uid(),
+            &scope.path,
+            TimeSeriesChartAction::Hovered(self.hover_time()),
+        );
+    }
+
+    fn range_changed(&self, cx: &mut Cx, scope: &Scope) {
+        cx.widget_action(
+            self.widget_uid(),
+            &scope.path,
+            TimeSeriesChartAction::RangeChanged(self.range.clone()),
+        );
+    }
+
+    /// Where `x` falls across the plot, 0 at its left edge and 1 at its right.
+    fn plot_fraction(&self, x: f64) -> f64 {
+        ((x - self.plot.pos.x) / self.plot.size.x).clamp(0.0, 1.0)
+    }
+
+    fn point_pos(&self, viewport: &Viewport, time_ms: u64, value: f64) -> DVec2 {
+        dvec2(
+            self.plot.pos.x + viewport.time_fraction(time_ms) * self.plot.size.x,
+            self.plot.pos.y + (1.0 - viewport.value_fraction(value)) * self.plot.size.y,
+        )
+    }
+
+    fn fill(&mut self, cx: &mut Cx2d, rgb: u32, alpha: f32, pos: DVec2, size: DVec2) {
+        self.draw_plot.color = rgb_color(rgb, alpha);
+        self.draw_plot.draw_abs(cx, Rect { pos, size });
+    }
+
+    /// Grid lines at the value ticks, labelled on the left, and the times
+    /// at either end below the plot.
+    fn draw_axes(&mut self, cx: &mut Cx2d, viewport: &Viewport) {
+        let plot = self.plot;
+        for tick in viewport.value_ticks(VALUE_TICKS) {
+            let y = self.point_pos(viewport, viewport.start_ms, tick).y;
+            self.fill(
+                cx,
+                GRID_COLOR,
+                1.0,
+                dvec2(plot.pos.x, y),
+                dvec2(plot.size.x, 1.0),
+            );
+            let label = units::format_axis(tick, self.unit);
+            self.draw_label
+                .draw_abs(cx, dvec2(plot.pos.x - PLOT_LEFT + 2.0, y - 5.0), &label);
+        }
+        let bottom = plot.pos.y + plot.size.y + 4.0;
+        let end = datetime::format_clock(viewport.end_ms);
+        self.draw_label.draw_abs(
+            cx,
+            dvec2(plot.pos.x, bottom),
+            &datetime::format_clock(viewport.start_ms),
+        );
+        self.draw_label.draw_abs(
+            cx,
+            dvec2(plot.pos.x + plot.size.x - 6.0 * end.len() as f64, bottom),
+            &end,
+        );
+    }
+
+    /// Draw the series a pixel column at a time: the line as a bar spanning
+    /// from its height in the column before, so steep slopes stay joined.
+    fn draw_series(&mut self, cx: &mut Cx2d, viewport: &Viewport, index: usize) {
+        let series = &self.series[index];
+        let color = series.color;
+        let alpha = match self.highlighted {
+            Some(highlighted) if highlighted != index => DIMMED_ALPHA,
+            _ => 1.0,
+        };
+        let points: Vec<DVec2> = series
+            .points
+            .iter()
+            .filter(|p| p.value.is_finite())
+            .map(|p| self.point_pos(viewport, p.time_ms, p.value))
+            .collect();
+        let (left, right) = (self.plot.pos.x, self.plot.pos.x + self.plot.size.x);
+        let bottom = self.plot.pos.y + self.plot.size.y;
+        let top = self.plot.pos.y;
+        if let [point] = points[..] {
+            if (left..=right).contains(&point.x) {
+                self.fill(cx, color, alpha, point - dvec2(2.0, 2.0), dvec2(4.0, 4.0));
+            }
+            return;
+        }
+        let mut previous_y = None;
+        for pair in points.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            let mut x = a.x.max(left).floor();
+            while x < b.x.min(right) {
+                let t = ((x - a.x) / (b.x - a.x).max(f64::EPSILON)).clamp(0.0, 1.0);
+                let y = (a.y + (b.y - a.y) * t).clamp(top, bottom);
+                if self.area {
+                    let fill_alpha = AREA_ALPHA * alpha;
+                    self.fill(cx, color, fill_alpha, dvec2(x, y), dvec2(1.0, bottom - y));
+                }
+                let from = previous_y.unwrap_or(y);
+                let (y0, y1) = (f64::min(from, y), f64::max(from, y));
+                let half = LINE_WIDTH / 2.0;
+                self.fill(
+                    cx,
+                    color,
+                    alpha,
+                    dvec2(x, y0 - half),
+                    dvec2(1.0, y1 - y0 + LINE_WIDTH),
+                );
+                previous_y = Some(y);
+                x += 1.0;
+            }
+        }
+    }
+
+    /// The times a zoom drag covers so far.
+    fn draw_selection(&mut self, cx: &mut Cx2d) {
+        let Some(drag) = self.drag.filter(|d| !d.pan) else {
+            return;
+        };
+        let x0 = drag.start_x.min(drag.x).max(self.plot.pos.x);
+        let x1 = drag
+            .start_x
+            .max(drag.x)
+            .min(self.plot.pos.x + self.plot.size.x);
+        if x1 > x0 {
+            let (y, height) = (self.plot.pos.y, self.plot.size.y);
+            self.fill(
+                cx,
+                SELECTION_COLOR,
+                0.15,
+                dvec2(x0, y),
+                dvec2(x1 - x0, height),
+            );
+        }
+    }
+
+    /// A vertical line at the pointer, or at the time hovered on another
+    /// chart, and the value of each series there.
+    fn draw_crosshair(&mut self, cx: &mut Cx2d, viewport: &Viewport) {
+        let plot = self.plot;
+        let x = match (self.hover_x, self.crosshair_ms) {
+            (Some(x), _) => x,
+            (None, Some(time_ms)) => plot.pos.x + viewport.time_fraction(time_ms) * plot.size.x,
+            (None, None) => return,
+        };
+        if x < plot.pos.x || x > plot.pos.x + plot.size.x {
+            return;
+        }
+        self.fill(
+            cx,
+            CROSSHAIR_COLOR,
+            1.0,
+            dvec2(x, plot.pos.y),
+            dvec2(1.0, plot.size.y),
+        );
+        let time_ms = viewport.time_at(self.plot_fraction(x));
+        let values = model::values_at(&self.series, viewport, time_ms);
+        let mut lines = vec![(CROSSHAIR_COLOR, datetime::format_clock(time_ms))];
+        for (index, point) in values {
+            let color = self.series[index].color;
+            let name = text::truncate(&self.series[index].name, 14);
+            let pos = self.point_pos(viewport, point.time_ms, point.value);
+            self.fill(cx, color, 1.0, pos - dvec2(2.5, 2.5), dvec2(5.0, 5.0));
+            lines.push((
+                color,
+                format!("{}: {}", name, units::format(point.value, self.unit)),
+            ));
+        }
+        // Keep the readout inside the plot, left of the line near the right
+        let readout_x = if x + 6.0 + READOUT_WIDTH > plot.pos.x + plot.size.x {
+            x - 6.0 - READOUT_WIDTH
+        } else {
+            x + 6.0
+        };
+        let height = lines.len() as f64 * READOUT_LINE_HEIGHT + 4.0;
+        self.fill(
+            cx,
+            0xffffff,
+            0.9,
+            dvec2(readout_x, plot.pos.y),
+            dvec2(READOUT_WIDTH, height),
+        );
+        let label_color = self.draw_label.color;
+        for (i, (rgb, line)) in lines.iter().enumerate() {
+            self.draw_label.color = rgb_color(*rgb, 1.0);
+            let pos = dvec2(
+                readout_x + 4.0,
+                plot.pos.y + 2.0 + i as f64 * READOUT_LINE_HEIGHT,
+            );
+            self.draw_label.draw_abs(cx, pos, line);
+        }
+        self.draw_label.color = label_color;
+    }
+}
+
+fn rgb_color(rgb: u32, alpha: f32) -> Vec4 {
+    vec4(
+        ((rgb >> 16) & 0xff) as f32 / 255.0,
+        ((rgb >> 8) & 0xff) as f32 / 255.0,
+        (rgb & 0xff) as f32 / 255.0,
+        alpha,
+    )
+}
+
+impl TimeSeriesChartRef {
+    pub fn set_series(&self, cx: &mut Cx, series: Vec<ChartSeries>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_series(cx, series);
+        }
+    }
+
+    pub fn set_unit(&self, cx: &mut Cx, unit: Unit) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_unit(cx, unit);
+        }
+    }
+
+    pub fn set_range(&self, cx: &mut Cx, range: Option<TimeRange>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_range(cx, range);
+        }
+    }
+
+    /// Times zoomed or panned to, `None` when showing all the data
+    pub fn range(&self) -> Option<TimeRange> {
+        self.borrow()?.range.clone()
+    }
+
+    pub fn set_crosshair(&self, cx: &mut Cx, time_ms: Option<u64>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_crosshair(cx, time_ms);
+        }
+    }
+
+    /// Time of the crosshair hovered on another chart, if shown
+    pub fn crosshair(&self) -> Option<u64> {
+        self.borrow()?.crosshair_ms
+    }
+
+    pub fn set_highlighted(&self, cx: &mut Cx, index: Option<usize>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_highlighted(cx, index);
+        }
+    }
+
+    /// Index of the series drawn on top, if any
+    pub fn highlighted(&self) -> Option<usize> {
+        self.borrow()?.highlighted
+    }
+
+    /// The range shown after a zoom, pan or reset in `actions`, if any.
+    pub fn range_changed(&self, actions: &Actions) -> Option<Option<TimeRange>> {
+        match actions.find_widget_action(self.widget_uid())?.cast() {
+            TimeSeriesChartAction::RangeChanged(range) => Some(range),
+            _ => None,
+        }
+    }
+
+    /// The time hovered, or `None` once the pointer left, if `actions` has
+    /// a hover over the plot.
+    pub fn hovered(&self, actions: &Actions) -> Option<Option<u64>> {
+        match actions.find_widget_action(self.widget_uid())?.cast() {
+            TimeSeriesChartAction::Hovered(time_ms) => Some(time_ms),
+            _ => None,
+        }
+    }
+}
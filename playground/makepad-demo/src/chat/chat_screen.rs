@@ -1,8 +1,14 @@
-use crate::api::{
-    submit_chat_request, take_pending_response, ChatMessage, ChatResponse, MessageRole,
-};
+use crate::api::{ChatClient, ChatMessage, ChatResponse, MessageRole, ToolRecord, ToolStep};
+use crate::chat::context::Transcript;
+use crate::chat::markdown::{self, Block};
+use crate::chat::prompts::{self, PromptTemplate, DEFAULT_TEMPLATE};
+use crate::chat::session;
+use crate::i18n;
+use crate::logging;
+use crate::text;
 use makepad_widgets::*;
 use std::cell::RefMut;
+use std::collections::HashSet;
 
 live_design! {
     use link::theme::*;
@@ -61,6 +67,207 @@ live_design! {
         }
     }
 
+    // Markdown blocks of assistant messages, one row each, indented like
+    // the assistant bubble
+    AssistantHeading = <View> {
+        width: Fill, height: Fit
+        padding: { left: 20, right: 60, top: 8, bottom: 2 }
+
+        label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                text_style: { font_size: 16.0 }
+                color: #111827
+                wrap: Word
+            }
+        }
+    }
+
+    AssistantListItem = <View> {
+        width: Fill, height: Fit
+        flow: Right
+        padding: { left: 28, right: 60, top: 1, bottom: 1 }
+        spacing: 6
+
+        marker_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                text_style: { font_size: 14.0 }
+                color: #6b7280
+            }
+        }
+        label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                text_style: { font_size: 14.0 }
+                color: #1f2937
+                wrap: Word
+            }
+        }
+    }
+
+    CodeBlock = <View> {
+        width: Fill, height: Fit
+        padding: { left: 16, right: 60, top: 4, bottom: 4 }
+
+        <RoundedView> {
+            width: Fill, height: Fit
+            flow: Down
+            draw_bg: { color: #1f2937 }
+            padding: { left: 12, right: 12, top: 6, bottom: 10 }
+            spacing: 6
+
+            <View> {
+                width: Fill, height: Fit
+                flow: Right
+                align: { y: 0.5 }
+
+                language_label = <Label> {
+                    width: Fill, height: Fit
+                    draw_text: {
+                        text_style: { font_size: 10.0 }
+                        color: #9ca3af
+                    }
+                }
+                use_dataflow_button = <Button> {
+                    width: Fit, height: 24
+                    visible: false
+                    draw_text: { text_style: { font_size: 10.0 } }
+                    text: "Use as dataflow"
+                }
+                copy_button = <Button> {
+                    width: Fit, height: 24
+                    draw_text: { text_style: { font_size: 10.0 } }
+                    text: "Copy"
+                }
+            }
+            code_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    text_style: <THEME_FONT_CODE> { font_size: 12.0 }
+                    color: #f9fafb
+                    wrap: Line
+                }
+            }
+        }
+    }
+
+    // A tool call made by the agent; expands to its arguments and output
+    ToolCard = <View> {
+        width: Fill, height: Fit
+        padding: { left: 16, right: 60, top: 2, bottom: 2 }
+
+        <RoundedView> {
+            width: Fill, height: Fit
+            flow: Down
+            draw_bg: { color: #f3f4f6 }
+            padding: { left: 8, right: 12, top: 4, bottom: 4 }
+            spacing: 4
+
+            <View> {
+                width: Fill, height: Fit
+                flow: Right
+                align: { y: 0.5 }
+                spacing: 6
+
+                toggle_button = <Button> {
+                    width: 24, height: 22
+                    draw_text: { text_style: { font_size: 10.0 } }
+                    text: "▸"
+                }
+                title_label = <Label> {
+                    width: Fill, height: Fit
+                    draw_text: {
+                        text_style: { font_size: 11.0 }
+                        color: #374151
+                        wrap: Word
+                    }
+                }
+                step_status_label = <Label> {
+                    width: Fit, height: Fit
+                    draw_text: {
+                        text_style: { font_size: 11.0 }
+                        color: #6b7280
+                    }
+                }
+            }
+            details = <View> {
+                width: Fill, height: Fit
+                flow: Down
+                visible: false
+                spacing: 4
+
+                arguments_label = <Label> {
+                    width: Fill, height: Fit
+                    draw_text: {
+                        text_style: <THEME_FONT_CODE> { font_size: 11.0 }
+                        color: #4b5563
+                        wrap: Line
+                    }
+                }
+                output_label = <Label> {
+                    width: Fill, height: Fit
+                    draw_text: {
+                        text_style: <THEME_FONT_CODE> { font_size: 11.0 }
+                        color: #1f2937
+                        wrap: Line
+                    }
+                }
+            }
+        }
+    }
+
+    // Marks where older turns were folded into a summary
+    SummaryMarker = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        align: { x: 0.5 }
+        padding: { left: 40, right: 40, top: 8, bottom: 8 }
+        spacing: 4
+
+        marker_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                text_style: { font_size: 11.0 }
+                color: #6b7280
+            }
+        }
+        summary_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                text_style: { font_size: 11.0 }
+                color: #9ca3af
+                wrap: Word
+            }
+        }
+    }
+
+    // Under each reply: the model that wrote it
+    ModelCaption = <View> {
+        width: Fill, height: Fit
+        padding: { left: 20, right: 60, top: 0, bottom: 4 }
+
+        label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                text_style: { font_size: 10.0 }
+                color: #9ca3af
+            }
+        }
+    }
+
+    // Under the last reply: asks for the last prompt to be answered again
+    RegenerateRow = <View> {
+        width: Fill, height: Fit
+        padding: { left: 16, right: 60, top: 2, bottom: 4 }
+
+        regenerate_button = <Button> {
+            width: Fit, height: 24
+            draw_text: { text_style: { font_size: 10.0 } }
+            text: "Regenerate"
+        }
+    }
+
     // Loading indicator bubble
     LoadingBubble = <View> {
         width: Fill, height: Fit
@@ -73,7 +280,7 @@ live_design! {
             draw_bg: { color: (ASSISTANT_BUBBLE_COLOR) }
             padding: { left: 16, right: 16, top: 10, bottom: 10 }
 
-            <Label> {
+            thinking_label = <Label> {
                 width: Fit, height: Fit
                 draw_text: {
                     text_style: { font_size: 14.0 }
@@ -91,12 +298,61 @@ live_design! {
         show_bg: true
         draw_bg: { color: (BG_COLOR) }
 
-        // Status bar
-        status_label = <Label> {
+        // Status bar, with the model and the prompt template of this
+        // conversation
+        <View> {
             width: Fill, height: Fit
-            padding: { left: 20, top: 8, bottom: 8 }
-            draw_text: { color: #6b7280, text_style: { font_size: 12.0 } }
-            text: "Ready"
+            flow: Right
+            align: { y: 0.5 }
+            padding: { right: 16 }
+            spacing: 8
+
+            status_label = <Label> {
+                width: Fill, height: Fit
+                padding: { left: 20, top: 8, bottom: 8 }
+                draw_text: { color: #6b7280, text_style: { font_size: 12.0 } }
+                text: "Ready"
+            }
+            model_dropdown = <DropDown> {
+                width: 220, height: 28
+                labels: ["claude-sonnet-4-20250514"]
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            refresh_models_button = <Button> {
+                width: Fit, height: 28
+                draw_text: { text_style: { font_size: 11.0 } }
+                text: "Refresh models"
+            }
+            template_dropdown = <DropDown> {
+                width: 200, height: 28
+                labels: ["Dora Studio Assistant"]
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        // Export the conversation to share it, or import one shared
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { y: 0.5 }
+            padding: { left: 16, right: 16, bottom: 6 }
+            spacing: 8
+
+            session_path_input = <TextInput> {
+                width: Fill, height: 28
+                empty_text: "Session file (.json, or .md to read)"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            export_session_button = <Button> {
+                width: Fit, height: 28
+                draw_text: { text_style: { font_size: 11.0 } }
+                text: "Export"
+            }
+            import_session_button = <Button> {
+                width: Fit, height: 28
+                draw_text: { text_style: { font_size: 11.0 } }
+                text: "Import"
+            }
         }
 
         // Messages area with PortalList for dynamic rendering
@@ -107,7 +363,66 @@ live_design! {
 
             UserBubble = <UserBubble> {}
             AssistantBubble = <AssistantBubble> {}
+            AssistantHeading = <AssistantHeading> {}
+            AssistantListItem = <AssistantListItem> {}
+            CodeBlock = <CodeBlock> {}
+            ToolCard = <ToolCard> {}
             LoadingBubble = <LoadingBubble> {}
+            SummaryMarker = <SummaryMarker> {}
+            ModelCaption = <ModelCaption> {}
+            RegenerateRow = <RegenerateRow> {}
+        }
+
+        // Changes a dataflow YAML from a reply would make to the file it is
+        // written to
+        dataflow_preview = <View> {
+            width: Fill, height: Fit
+            visible: false
+            flow: Down
+            show_bg: true
+            draw_bg: { color: #ffffff }
+            padding: { left: 16, right: 16, top: 8, bottom: 8 }
+            spacing: 6
+
+            <View> {
+                width: Fill, height: Fit
+                flow: Right
+                align: { y: 0.5 }
+                spacing: 8
+
+                preview_title = <Label> {
+                    width: Fill, height: Fit
+                    draw_text: {
+                        text_style: { font_size: 12.0 }
+                        color: #1f2937
+                    }
+                }
+                write_dataflow_button = <Button> {
+                    width: Fit, height: 28
+                    draw_text: { text_style: { font_size: 11.0 } }
+                    text: "Write"
+                }
+                cancel_dataflow_button = <Button> {
+                    width: Fit, height: 28
+                    draw_text: { text_style: { font_size: 11.0 } }
+                    text: "Cancel"
+                }
+            }
+            <ScrollYView> {
+                width: Fill, height: 200
+                show_bg: true
+                draw_bg: { color: #f3f4f6 }
+                padding: 8
+
+                preview_diff = <Label> {
+                    width: Fill, height: Fit
+                    draw_text: {
+                        text_style: <THEME_FONT_CODE> { font_size: 11.0 }
+                        color: #1f2937
+                        wrap: Line
+                    }
+                }
+            }
         }
 
         // Input area
@@ -136,40 +451,181 @@ live_design! {
                 width: 80, height: 48
                 text: "Send"
             }
+
+            // Shown instead of Send while a reply is pending
+            stop_button = <Button> {
+                width: 80, height: 48
+                visible: false
+                text: "Stop"
+            }
         }
     }
 }
 
+/// Actions emitted by the ChatScreen
+#[derive(Clone, Debug, DefaultNone)]
+pub enum ChatScreenAction {
+    None,
+    /// "Use as dataflow" was clicked on a YAML code block; the YAML
+    UseDataflow(String),
+    /// The dataflow preview was confirmed: the path and YAML to write
+    WriteDataflow {
+        path: String,
+        yaml: String,
+    },
+    /// A model was picked
+    ModelChanged(String),
+}
+
+/// A row of the message list.
+#[derive(Debug, Clone, Copy)]
+enum ChatRow {
+    /// Index into the transcript messages.
+    Message(usize),
+    /// A markdown block of an assistant message: indices into the
+    /// transcript messages and that message's blocks.
+    Block(usize, usize),
+    /// A tool call made for an assistant message: indices into the
+    /// transcript messages and that message's tool steps. The message index
+    /// is one past the last message for the reply being written.
+    ToolStep(usize, usize),
+    /// Index into the transcript summaries.
+    Summary(usize),
+    /// The model that wrote an assistant message, by message index.
+    ModelCaption(usize),
+    /// The regenerate button, after the last message.
+    Regenerate,
+}
+
 #[derive(Live, LiveHook, Widget)]
 pub struct ChatScreen {
     #[deref]
     view: View,
+    /// The chat agent, handed over by the app at startup
+    #[rust]
+    client: Option<ChatClient>,
+    /// Full transcript; only the unsummarized tail is sent to the model.
+    #[rust]
+    transcript: Transcript,
     #[rust]
-    messages: Vec<ChatMessage>,
+    transcript_loaded: bool,
+    /// Markdown blocks of each message; empty for user messages.
+    #[rust]
+    blocks: Vec<Vec<Block>>,
     #[rust]
     is_loading: bool,
+    /// A summary request is in flight.
+    #[rust]
+    is_summarizing: bool,
     #[rust]
     next_frame: NextFrame,
+    /// Path and YAML of the dataflow being previewed
+    #[rust]
+    pending_dataflow: Option<(String, String)>,
+    /// Prompt templates to pick from (see [`prompts::templates`])
+    #[rust]
+    templates: Vec<PromptTemplate>,
+    /// Models in the model dropdown
+    #[rust]
+    models: Vec<String>,
+    /// A model list request is in flight.
+    #[rust]
+    is_fetching_models: bool,
+    /// Model the pending reply was asked of
+    #[rust]
+    request_model: Option<String>,
+    /// Why the pending request waits to be sent again (see
+    /// [`ChatClient::retry_notice`])
+    #[rust]
+    retry_notice: Option<String>,
+    /// Tool calls made so far for the pending reply
+    #[rust]
+    live_steps: Vec<ToolStep>,
+    /// Tool cards showing their arguments and output, as [`ChatRow::ToolStep`]
+    /// indices
+    #[rust]
+    expanded_steps: HashSet<(usize, usize)>,
 }
 
 impl Widget for ChatScreen {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        let Some(client) = self.client.clone() else {
+            return self.view.handle_event(cx, event, scope);
+        };
+        if !self.transcript_loaded {
+            self.load_transcript(cx);
+        }
+
         // Poll for API responses
         if self.next_frame.is_event(event).is_some() {
-            if let Some(resp) = take_pending_response() {
+            if let Some(resp) = client.take_pending_response() {
                 self.is_loading = false;
+                self.live_steps.clear();
                 let content = match resp {
                     ChatResponse::Message(s) => s,
                     ChatResponse::ToolExecution(s) => s,
-                    ChatResponse::Error(e) => format!("Error: {}", e),
+                    ChatResponse::Error(e) => i18n::tr_args("chat.error", &[("error", &e)]),
                 };
-                self.messages.push(ChatMessage {
+                self.transcript.messages.push(ChatMessage {
                     role: MessageRole::Assistant,
                     content,
+                    model: self.request_model.take(),
+                    tool_steps: client.take_tool_steps(),
                 });
+                self.save_transcript();
+                self.maybe_summarize(cx);
+                self.update_display(cx);
+            }
+            if let Some(summary) = client.take_pending_summary() {
+                self.is_summarizing = false;
+                match summary.result {
+                    Ok(text) => {
+                        if self.transcript.apply_summary(
+                            summary.covers_until,
+                            text,
+                            logging::now_ms(),
+                        ) {
+                            tracing::info!(
+                                target: "Chat",
+                                "Summarized {} messages",
+                                summary.covers_until
+                            );
+                            self.save_transcript();
+                        }
+                    }
+                    // Retried after the next response
+                    Err(e) => tracing::warn!(target: "Chat", "Summarization failed: {}", e),
+                }
                 self.update_display(cx);
             }
+            if let Some(result) = client.take_pending_models() {
+                self.is_fetching_models = false;
+                match result {
+                    Ok(models) => {
+                        self.models = models;
+                        self.show_models(cx);
+                    }
+                    Err(e) => {
+                        tracing::warn!(target: "Chat", "Failed to list models: {}", e);
+                        self.view
+                            .label(ids!(status_label))
+                            .set_text(cx, &i18n::tr_args("chat.models_failed", &[("error", &e)]));
+                    }
+                }
+            }
             if self.is_loading {
+                let steps = client.tool_steps();
+                if steps != self.live_steps {
+                    self.live_steps = steps;
+                    self.redraw(cx);
+                }
+            }
+            let retry_notice = client.retry_notice().filter(|_| self.is_loading);
+            if retry_notice != self.retry_notice {
+                self.retry_notice = retry_notice;
+                self.update_display(cx);
+            }
+            if self.is_loading || self.is_summarizing || self.is_fetching_models {
                 self.next_frame = cx.new_next_frame();
             }
         }
@@ -190,11 +646,124 @@ impl Widget for ChatScreen {
 }
 
 impl WidgetMatchEvent for ChatScreen {
-    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope) {
+        let rows = self.rows();
+        let message_list = self.view.portal_list(ids!(message_list));
+        for (item_id, item) in message_list.items_with_actions(actions) {
+            if matches!(rows.get(item_id), Some(ChatRow::Regenerate))
+                && item.button(ids!(regenerate_button)).clicked(actions)
+            {
+                self.regenerate(cx);
+            }
+            if let Some(ChatRow::ToolStep(message, step)) = rows.get(item_id) {
+                if item.button(ids!(toggle_button)).clicked(actions) {
+                    let key = (*message, *step);
+                    if !self.expanded_steps.remove(&key) {
+                        self.expanded_steps.insert(key);
+                    }
+                    self.redraw(cx);
+                }
+            }
+            if let Some(Block::Code { code, .. }) = rows.get(item_id).and_then(|r| self.block(*r)) {
+                if item.button(ids!(copy_button)).clicked(actions) {
+                    cx.copy_to_clipboard(code);
+                    self.view
+                        .label(ids!(status_label))
+                        .set_text(cx, &i18n::tr("chat.copied_code"));
+                }
+                if item.button(ids!(use_dataflow_button)).clicked(actions) {
+                    cx.widget_action(
+                        self.widget_uid(),
+                        &scope.path,
+                        ChatScreenAction::UseDataflow(code.clone()),
+                    );
+                }
+            }
+        }
+
+        if self
+            .view
+            .button(ids!(write_dataflow_button))
+            .clicked(actions)
+        {
+            if let Some((path, yaml)) = self.pending_dataflow.take() {
+                cx.widget_action(
+                    self.widget_uid(),
+                    &scope.path,
+                    ChatScreenAction::WriteDataflow { path, yaml },
+                );
+            }
+            self.show_dataflow_preview(cx, None);
+        }
+        if self
+            .view
+            .button(ids!(cancel_dataflow_button))
+            .clicked(actions)
+        {
+            self.show_dataflow_preview(cx, None);
+        }
+
         if self.view.button(ids!(send_button)).clicked(actions) {
             self.send_message(cx);
         }
 
+        if self
+            .view
+            .button(ids!(export_session_button))
+            .clicked(actions)
+        {
+            self.export_session(cx);
+        }
+
+        if self
+            .view
+            .button(ids!(import_session_button))
+            .clicked(actions)
+        {
+            self.import_session(cx);
+        }
+
+        if self.view.button(ids!(stop_button)).clicked(actions) {
+            self.stop(cx);
+        }
+
+        if self
+            .view
+            .button(ids!(refresh_models_button))
+            .clicked(actions)
+        {
+            self.fetch_models(cx);
+        }
+
+        if let Some(index) = self.view.drop_down(ids!(model_dropdown)).selected(actions) {
+            if let (Some(model), Some(client)) = (self.models.get(index).cloned(), &self.client) {
+                client.set_model(&model);
+                self.view
+                    .label(ids!(status_label))
+                    .set_text(cx, &i18n::tr_args("chat.using", &[("name", &model)]));
+                cx.widget_action(
+                    self.widget_uid(),
+                    &scope.path,
+                    ChatScreenAction::ModelChanged(model),
+                );
+            }
+        }
+
+        if let Some(index) = self
+            .view
+            .drop_down(ids!(template_dropdown))
+            .selected(actions)
+        {
+            if let Some(template) = self.templates.get(index) {
+                self.transcript.template = Some(template.name.clone());
+                self.save_transcript();
+                self.view.label(ids!(status_label)).set_text(
+                    cx,
+                    &i18n::tr_args("chat.using", &[("name", &template.name)]),
+                );
+            }
+        }
+
         if self
             .view
             .text_input(ids!(message_input))
@@ -207,40 +776,244 @@ impl WidgetMatchEvent for ChatScreen {
 }
 
 impl ChatScreen {
+    /// Show the text set in `live_design` in the current language
+    pub fn localize(&mut self, cx: &mut Cx) {
+        i18n::localize(
+            cx,
+            &[
+                (ids!(refresh_models_button), "chat.refresh_models"),
+                (ids!(export_session_button), "action.export"),
+                (ids!(import_session_button), "action.import"),
+                (ids!(write_dataflow_button), "chat.write"),
+                (ids!(cancel_dataflow_button), "action.cancel"),
+                (ids!(send_button), "chat.send"),
+                (ids!(stop_button), "action.stop"),
+            ],
+            |path| self.view.widget(path),
+        );
+        self.update_display(cx);
+        self.view.portal_list(ids!(message_list)).redraw(cx);
+    }
+
     fn draw_messages(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
-        // Calculate total items: messages + loading indicator if loading
-        let item_count = self.messages.len() + if self.is_loading { 1 } else { 0 };
+        let rows = self.rows();
+        // Calculate total items: rows + loading indicator if loading
+        let item_count = rows.len() + if self.is_loading { 1 } else { 0 };
 
         list.set_item_range(cx, 0, item_count);
 
         while let Some(item_id) = list.next_visible_item(cx) {
-            if item_id < self.messages.len() {
-                // Render actual message
-                let msg = &self.messages[item_id];
-                let template = match msg.role {
-                    MessageRole::User => live_id!(UserBubble),
-                    MessageRole::Assistant => live_id!(AssistantBubble),
-                };
+            if let Some(row) = rows.get(item_id) {
+                match *row {
+                    ChatRow::Message(index) => {
+                        // Render actual message
+                        let msg = &self.transcript.messages[index];
+                        let template = match msg.role {
+                            MessageRole::User => live_id!(UserBubble),
+                            MessageRole::Assistant => live_id!(AssistantBubble),
+                        };
 
-                let item = list.item(cx, item_id, template);
-                item.label(ids!(label)).set_text(cx, &msg.content);
-                item.draw_all(cx, &mut Scope::empty());
-            } else if self.is_loading && item_id == self.messages.len() {
+                        let item = list.item(cx, item_id, template);
+                        item.label(ids!(label)).set_text(cx, &msg.content);
+                        item.draw_all(cx, &mut Scope::empty());
+                    }
+                    ChatRow::Block(..) => {
+                        let Some(block) = self.block(*row) else {
+                            continue;
+                        };
+                        let template = match block {
+                            Block::Heading { .. } => live_id!(AssistantHeading),
+                            Block::Paragraph(_) => live_id!(AssistantBubble),
+                            Block::ListItem { .. } => live_id!(AssistantListItem),
+                            Block::Code { .. } => live_id!(CodeBlock),
+                        };
+                        let item = list.item(cx, item_id, template);
+                        match block {
+                            Block::Heading { text, .. } | Block::Paragraph(text) => {
+                                item.label(ids!(label)).set_text(cx, text);
+                            }
+                            Block::ListItem {
+                                marker,
+                                indent,
+                                text,
+                            } => {
+                                let marker = format!("{}{}", "    ".repeat(*indent), marker);
+                                item.label(ids!(marker_label)).set_text(cx, &marker);
+                                item.label(ids!(label)).set_text(cx, text);
+                            }
+                            Block::Code { language, code } => {
+                                item.label(ids!(language_label))
+                                    .set_text(cx, language.as_deref().unwrap_or(""));
+                                item.label(ids!(code_label)).set_text(cx, code);
+                                item.button(ids!(use_dataflow_button))
+                                    .set_visible(cx, block.is_dataflow_yaml());
+                                item.button(ids!(use_dataflow_button))
+                                    .set_text(cx, &i18n::tr("chat.use_as_dataflow"));
+                                item.button(ids!(copy_button))
+                                    .set_text(cx, &i18n::tr("chat.copy"));
+                            }
+                        }
+                        item.draw_all(cx, &mut Scope::empty());
+                    }
+                    ChatRow::ToolStep(message, index) => {
+                        let Some(step) = self.step(message, index) else {
+                            continue;
+                        };
+                        let expanded = self.expanded_steps.contains(&(message, index));
+                        let item = list.item(cx, item_id, live_id!(ToolCard));
+                        item.button(ids!(toggle_button))
+                            .set_text(cx, if expanded { "▾" } else { "▸" });
+                        item.label(ids!(title_label)).set_text(cx, &step.title());
+                        item.label(ids!(step_status_label))
+                            .set_text(cx, &step.status_text());
+                        item.view(ids!(details)).set_visible(cx, expanded);
+                        if expanded {
+                            item.label(ids!(arguments_label)).set_text(
+                                cx,
+                                &i18n::tr_args("chat.arguments", &[("arguments", &step.arguments)]),
+                            );
+                            item.label(ids!(output_label)).set_text(cx, &step.output);
+                        }
+                        item.draw_all(cx, &mut Scope::empty());
+                    }
+                    ChatRow::Summary(index) => {
+                        let summary = &self.transcript.summaries[index];
+                        let item = list.item(cx, item_id, live_id!(SummaryMarker));
+                        item.label(ids!(marker_label)).set_text(
+                            cx,
+                            &i18n::tr_args(
+                                "chat.summary_marker",
+                                &[("count", &summary.covers_until.to_string())],
+                            ),
+                        );
+                        item.label(ids!(summary_label)).set_text(cx, &summary.text);
+                        item.draw_all(cx, &mut Scope::empty());
+                    }
+                    ChatRow::ModelCaption(index) => {
+                        let model = self.transcript.messages[index].model.as_deref();
+                        let item = list.item(cx, item_id, live_id!(ModelCaption));
+                        item.label(ids!(label)).set_text(cx, model.unwrap_or(""));
+                        item.draw_all(cx, &mut Scope::empty());
+                    }
+                    ChatRow::Regenerate => {
+                        let item = list.item(cx, item_id, live_id!(RegenerateRow));
+                        item.button(ids!(regenerate_button))
+                            .set_text(cx, &i18n::tr("chat.regenerate"));
+                        item.draw_all(cx, &mut Scope::empty());
+                    }
+                }
+            } else if self.is_loading && item_id == rows.len() {
                 // Render loading indicator (only one, right after messages)
                 let item = list.item(cx, item_id, live_id!(LoadingBubble));
+                item.label(ids!(thinking_label))
+                    .set_text(cx, &i18n::tr("chat.thinking"));
                 item.draw_all(cx, &mut Scope::empty());
             }
         }
     }
 
+    /// Messages with a summary marker before the first message after each
+    /// summary and the tool calls of a reply before it, then the tool calls
+    /// of the pending reply, or the regenerate button when the last prompt
+    /// can be sent again.
+    fn rows(&self) -> Vec<ChatRow> {
+        let mut rows = Vec::with_capacity(self.transcript.messages.len());
+        let mut summaries = self.transcript.summaries.iter().enumerate().peekable();
+        for index in 0..self.transcript.messages.len() {
+            while let Some((i, _)) = summaries.next_if(|(_, s)| s.covers_until <= index) {
+                rows.push(ChatRow::Summary(i));
+            }
+            let message = &self.transcript.messages[index];
+            rows.extend((0..message.tool_steps.len()).map(|s| ChatRow::ToolStep(index, s)));
+            match self.blocks.get(index).filter(|b| !b.is_empty()) {
+                Some(blocks) => rows.extend((0..blocks.len()).map(|b| ChatRow::Block(index, b))),
+                // A reply made of tool calls only
+                None if message.content.is_empty() && !message.tool_steps.is_empty() => {}
+                None => rows.push(ChatRow::Message(index)),
+            }
+            if message.model.is_some() {
+                rows.push(ChatRow::ModelCaption(index));
+            }
+        }
+        rows.extend(summaries.map(|(i, _)| ChatRow::Summary(i)));
+        if self.is_loading {
+            let pending = self.transcript.messages.len();
+            rows.extend((0..self.live_steps.len()).map(|s| ChatRow::ToolStep(pending, s)));
+        } else if self.transcript.last_prompt().is_some() {
+            rows.push(ChatRow::Regenerate);
+        }
+        rows
+    }
+
+    /// Show what writing `yaml` to `path` would change, as `(path, yaml,
+    /// diff)`, or hide the preview.
+    fn show_dataflow_preview(&mut self, cx: &mut Cx, preview: Option<(&str, &str, &str)>) {
+        self.pending_dataflow = preview.map(|(path, yaml, _)| (path.to_string(), yaml.to_string()));
+        if let Some((path, _, diff)) = preview {
+            let added = diff.lines().filter(|l| l.starts_with('+')).count();
+            let removed = diff.lines().filter(|l| l.starts_with('-')).count();
+            self.view.label(ids!(preview_title)).set_text(
+                cx,
+                &i18n::tr_args(
+                    "chat.preview_title",
+                    &[
+                        ("path", &text::truncate(path, 60)),
+                        ("added", &added.to_string()),
+                        ("removed", &removed.to_string()),
+                    ],
+                ),
+            );
+            self.view.label(ids!(preview_diff)).set_text(cx, diff);
+        }
+        self.view
+            .view(ids!(dataflow_preview))
+            .set_visible(cx, preview.is_some());
+        self.redraw(cx);
+    }
+
+    /// A tool call of a message, or of the pending reply.
+    fn step(&self, message: usize, step: usize) -> Option<&ToolStep> {
+        match self.transcript.messages.get(message) {
+            Some(message) => message.tool_steps.get(step),
+            None => self.live_steps.get(step),
+        }
+    }
+
+    fn block(&self, row: ChatRow) -> Option<&Block> {
+        match row {
+            ChatRow::Block(message, block) => self.blocks.get(message)?.get(block),
+            _ => None,
+        }
+    }
+
     fn update_display(&mut self, cx: &mut Cx) {
+        // Parse the messages added since the last update
+        self.blocks.truncate(self.transcript.messages.len());
+        for message in &self.transcript.messages[self.blocks.len()..] {
+            self.blocks.push(match message.role {
+                MessageRole::User => Vec::new(),
+                MessageRole::Assistant => markdown::parse(&message.content),
+            });
+        }
+
         // Update status label
-        let status = if self.is_loading {
-            "Thinking...".to_string()
+        let count = self.transcript.messages.len().to_string();
+        let status = if let Some(notice) = self.retry_notice.as_ref().filter(|_| self.is_loading) {
+            i18n::tr_args("chat.queued", &[("notice", notice)])
+        } else if self.is_loading {
+            i18n::tr("chat.thinking")
+        } else if self.is_summarizing {
+            i18n::tr_args("chat.messages_summarizing", &[("count", &count)])
         } else {
-            format!("{} messages", self.messages.len())
+            i18n::tr_args("chat.messages", &[("count", &count)])
         };
         self.view.label(ids!(status_label)).set_text(cx, &status);
+        self.view
+            .button(ids!(send_button))
+            .set_visible(cx, !self.is_loading);
+        self.view
+            .button(ids!(stop_button))
+            .set_visible(cx, self.is_loading);
         self.redraw(cx);
     }
 
@@ -251,12 +1024,33 @@ impl ChatScreen {
             return;
         }
 
-        self.messages.push(ChatMessage {
+        self.transcript.messages.push(ChatMessage {
             role: MessageRole::User,
             content: text.clone(),
+            model: None,
+            tool_steps: Vec::new(),
         });
+        self.save_transcript();
 
         input.set_text(cx, "");
+        self.request_reply(cx);
+    }
+
+    /// Ask for the last prompt to be answered again, in place of its reply.
+    fn regenerate(&mut self, cx: &mut Cx) {
+        if self.is_loading || !self.transcript.drop_last_reply() {
+            return;
+        }
+        self.expanded_steps
+            .retain(|&(message, _)| message < self.transcript.messages.len());
+        self.save_transcript();
+        self.request_reply(cx);
+    }
+
+    fn request_reply(&mut self, cx: &mut Cx) {
+        let Some(client) = self.client.clone() else {
+            return;
+        };
         self.is_loading = true;
 
         // Update display immediately
@@ -264,6 +1058,356 @@ impl ChatScreen {
 
         // Start polling and send request
         self.next_frame = cx.new_next_frame();
-        submit_chat_request(self.messages.clone());
+        self.request_model = Some(client.model());
+        let system = prompts::system_prompt(&self.templates, self.transcript.template.as_deref());
+        client.submit_chat_request(self.transcript.context_messages(), system);
+    }
+
+    /// Post `label` as a prompt and have `request` answered with the log
+    /// diagnosis prompt, without the rest of the conversation. Returns
+    /// false while another reply is pending.
+    fn summarize_logs(&mut self, cx: &mut Cx, label: &str, request: String) -> bool {
+        let Some(client) = self.client.clone() else {
+            return false;
+        };
+        if self.is_loading {
+            return false;
+        }
+        self.transcript.messages.push(ChatMessage {
+            role: MessageRole::User,
+            content: label.to_string(),
+            model: None,
+            tool_steps: Vec::new(),
+        });
+        self.save_transcript();
+
+        self.is_loading = true;
+        self.update_display(cx);
+        self.next_frame = cx.new_next_frame();
+        self.request_model = Some(client.model());
+        client.submit_chat_request(
+            vec![ChatMessage {
+                role: MessageRole::User,
+                content: request,
+                model: None,
+                tool_steps: Vec::new(),
+            }],
+            prompts::LOG_SUMMARY_PROMPT.to_string(),
+        );
+        true
+    }
+
+    /// Abort the pending reply; the prompt stays and can be regenerated.
+    fn stop(&mut self, cx: &mut Cx) {
+        if !self.is_loading {
+            return;
+        }
+        if let Some(client) = &self.client {
+            client.cancel_chat_request();
+        }
+        self.is_loading = false;
+        self.live_steps.clear();
+        self.update_display(cx);
+        self.view.label(ids!(status_label)).set_text(
+            cx,
+            &i18n::tr_args(
+                "chat.messages_stopped",
+                &[("count", &self.transcript.messages.len().to_string())],
+            ),
+        );
+    }
+
+    fn load_transcript(&mut self, cx: &mut Cx) {
+        self.transcript_loaded = true;
+        self.transcript = Transcript::load();
+        if let Some(client) = &self.client {
+            client.set_tool_records(std::mem::take(&mut self.transcript.tool_results));
+        }
+        if self.templates.is_empty() {
+            self.set_templates(cx, prompts::builtin_templates());
+        } else {
+            self.select_template(cx);
+        }
+        self.show_models(cx);
+        if self.client.as_ref().is_some_and(ChatClient::is_configured) {
+            self.fetch_models(cx);
+        }
+        self.update_display(cx);
+    }
+
+    /// Ask the API which models there are to pick from.
+    fn fetch_models(&mut self, cx: &mut Cx) {
+        let Some(client) = &self.client else {
+            return;
+        };
+        if self.is_fetching_models {
+            return;
+        }
+        self.is_fetching_models = true;
+        client.submit_models_request();
+        self.next_frame = cx.new_next_frame();
+    }
+
+    /// Fill the model dropdown, keeping the model in use listed and
+    /// selected.
+    fn show_models(&mut self, cx: &mut Cx) {
+        let Some(model) = self.client.as_ref().map(ChatClient::model) else {
+            return;
+        };
+        if !self.models.contains(&model) {
+            self.models.insert(0, model.clone());
+        }
+        let dropdown = self.view.drop_down(ids!(model_dropdown));
+        dropdown.set_labels(cx, self.models.clone());
+        let index = self.models.iter().position(|m| *m == model).unwrap_or(0);
+        dropdown.set_selected_item(cx, index);
+    }
+
+    fn set_templates(&mut self, cx: &mut Cx, templates: Vec<PromptTemplate>) {
+        self.templates = templates;
+        self.view
+            .drop_down(ids!(template_dropdown))
+            .set_labels(cx, self.templates.iter().map(|t| t.name.clone()).collect());
+        self.select_template(cx);
+    }
+
+    /// Show the template of this conversation in the dropdown.
+    fn select_template(&mut self, cx: &mut Cx) {
+        let name = self
+            .transcript
+            .template
+            .as_deref()
+            .unwrap_or(DEFAULT_TEMPLATE);
+        let index = self
+            .templates
+            .iter()
+            .position(|t| t.name.eq_ignore_ascii_case(name))
+            .unwrap_or(0);
+        self.view
+            .drop_down(ids!(template_dropdown))
+            .set_selected_item(cx, index);
+    }
+
+    /// Write the conversation, with its tool results, to the entered file.
+    fn export_session(&mut self, cx: &mut Cx) {
+        let input = self.view.text_input(ids!(session_path_input)).text();
+        let mut transcript = self.transcript.clone();
+        transcript.tool_results = self.tool_records();
+        let status = session::session_path(&input)
+            .and_then(|path| {
+                session::export_session(&transcript, &path, logging::now_ms()).map(|()| {
+                    tracing::info!(target: "Chat", "Exported chat to {}", path.display());
+                    i18n::tr_args("chat.exported", &[("path", &path.display().to_string())])
+                })
+            })
+            .unwrap_or_else(|e| {
+                tracing::warn!(target: "Chat", "{}", e);
+                e
+            });
+        self.view.label(ids!(status_label)).set_text(cx, &status);
+    }
+
+    /// Replace the conversation with one exported as JSON.
+    fn import_session(&mut self, cx: &mut Cx) {
+        if self.is_loading || self.is_summarizing {
+            self.view
+                .label(ids!(status_label))
+                .set_text(cx, &i18n::tr("chat.import_busy"));
+            return;
+        }
+        let input = self.view.text_input(ids!(session_path_input)).text();
+        let path = match session::session_path(&input) {
+            Ok(path) => path,
+            Err(e) => return self.view.label(ids!(status_label)).set_text(cx, &e),
+        };
+        let mut transcript = match session::import_session(&path) {
+            Ok(transcript) => transcript,
+            Err(e) => {
+                tracing::warn!(target: "Chat", "{}", e);
+                return self.view.label(ids!(status_label)).set_text(cx, &e);
+            }
+        };
+        if let Some(client) = &self.client {
+            client.set_tool_records(std::mem::take(&mut transcript.tool_results));
+        }
+        self.transcript = transcript;
+        self.expanded_steps.clear();
+        self.save_transcript();
+        self.select_template(cx);
+        self.show_dataflow_preview(cx, None);
+        self.update_display(cx);
+
+        let count = self.transcript.messages.len().to_string();
+        tracing::info!(target: "Chat", "Imported {} messages from {}", count, path.display());
+        let status = i18n::tr_args(
+            "chat.imported",
+            &[("count", &count), ("path", &path.display().to_string())],
+        );
+        self.view.label(ids!(status_label)).set_text(cx, &status);
+    }
+
+    /// The tool results of this conversation, which the agent keeps
+    fn tool_records(&self) -> Vec<ToolRecord> {
+        self.client
+            .as_ref()
+            .map(ChatClient::tool_records)
+            .unwrap_or_default()
+    }
+
+    fn save_transcript(&mut self) {
+        self.transcript.tool_results = self.tool_records();
+        if let Err(e) = self.transcript.save() {
+            tracing::warn!(target: "Chat", "Failed to save transcript: {}", e);
+        }
+        self.transcript.tool_results.clear();
+    }
+
+    /// Fold older turns into a summary once the context is over budget.
+    fn maybe_summarize(&mut self, cx: &mut Cx) {
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        if self.is_summarizing {
+            return;
+        }
+        if let Some(covers_until) = self.transcript.next_summary_boundary() {
+            tracing::debug!(target: "Chat", "Summarizing messages before {}", covers_until);
+            self.is_summarizing = true;
+            client
+                .submit_summary_request(covers_until, self.transcript.summary_prompt(covers_until));
+            self.next_frame = cx.new_next_frame();
+        }
+    }
+}
+
+impl ChatScreenRef {
+    pub fn localize(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.localize(cx);
+        }
+    }
+
+    /// Send requests through `client`; the screen stays idle until it has
+    /// one
+    pub fn set_client(&self, cx: &mut Cx, client: ChatClient) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.client = Some(client);
+            inner.redraw(cx);
+        }
+    }
+
+    /// Send the typed message, as the send button does
+    pub fn send(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.send_message(cx);
+        }
+    }
+
+    /// Abort the pending reply, as the stop button does
+    pub fn stop(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.stop(cx);
+        }
+    }
+
+    /// Answer the last prompt again, as the regenerate button does
+    pub fn regenerate(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.regenerate(cx);
+        }
+    }
+
+    /// Post `label` and ask for a diagnosis of the logs in `request`.
+    /// Returns false while a reply is pending.
+    pub fn summarize_logs(&self, cx: &mut Cx, label: &str, request: String) -> bool {
+        self.borrow_mut()
+            .map(|mut inner| inner.summarize_logs(cx, label, request))
+            .unwrap_or(false)
+    }
+
+    /// Fetch the models to pick from again, as the refresh button does
+    pub fn fetch_models(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.fetch_models(cx);
+        }
+    }
+
+    /// Use `model` for chat, or the default model when empty
+    pub fn set_model(&self, cx: &mut Cx, model: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            if let Some(client) = &inner.client {
+                client.set_model(model);
+            }
+            inner.show_models(cx);
+        }
+    }
+
+    /// Check if a model was picked, returns it if so
+    pub fn model_changed(&self, actions: &Actions) -> Option<String> {
+        match actions.find_widget_action(self.widget_uid())?.cast() {
+            ChatScreenAction::ModelChanged(model) => Some(model),
+            _ => None,
+        }
+    }
+
+    /// Set the prompt templates to pick from
+    pub fn set_prompt_templates(&self, cx: &mut Cx, templates: Vec<PromptTemplate>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_templates(cx, templates);
+        }
+    }
+
+    /// Name of the prompt template this conversation uses
+    pub fn prompt_template(&self) -> Option<String> {
+        let inner = self.borrow()?;
+        let name = inner
+            .transcript
+            .template
+            .as_deref()
+            .unwrap_or(DEFAULT_TEMPLATE);
+        prompts::find_template(&inner.templates, name).map(|t| t.name.clone())
+    }
+
+    /// Get a copy of the transcript messages
+    pub fn messages(&self) -> Vec<ChatMessage> {
+        self.borrow()
+            .map(|inner| inner.transcript.messages.clone())
+            .unwrap_or_default()
+    }
+
+    /// Check if a reply is pending
+    pub fn is_loading(&self) -> bool {
+        self.borrow().is_some_and(|inner| inner.is_loading)
+    }
+
+    /// Ask to confirm writing `yaml` to `path`, showing the line `diff`
+    /// against what is there now (see [`crate::text::line_diff`])
+    pub fn show_dataflow_preview(&self, cx: &mut Cx, path: &str, yaml: &str, diff: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.show_dataflow_preview(cx, Some((path, yaml, diff)));
+        }
+    }
+
+    /// Path and YAML of the dataflow awaiting confirmation
+    pub fn pending_dataflow(&self) -> Option<(String, String)> {
+        self.borrow()?.pending_dataflow.clone()
+    }
+
+    /// Check if "Use as dataflow" was clicked on a code block, returns its
+    /// YAML if so
+    pub fn use_dataflow_clicked(&self, actions: &Actions) -> Option<String> {
+        match actions.find_widget_action(self.widget_uid())?.cast() {
+            ChatScreenAction::UseDataflow(yaml) => Some(yaml),
+            _ => None,
+        }
+    }
+
+    /// Check if a previewed dataflow was confirmed, returns its path and
+    /// YAML if so
+    pub fn write_dataflow_clicked(&self, actions: &Actions) -> Option<(String, String)> {
+        match actions.find_widget_action(self.widget_uid())?.cast() {
+            ChatScreenAction::WriteDataflow { path, yaml } => Some((path, yaml)),
+            _ => None,
+        }
     }
 }
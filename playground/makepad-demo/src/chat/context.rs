@@ -0,0 +1,330 @@
+//! Bounded chat context with rolling summarization.
+//!
+//! The full transcript is kept and persisted, but only the turns after the
+//! latest summary are sent to the model, with the summary prepended to the
+//! first of them. When those turns exceed [`CONTEXT_BUDGET_TOKENS`], all but
+//! the most recent [`KEEP_RECENT_MESSAGES`] are summarized and the summary is
+//! stored next to the transcript. Tool results are kept in full under
+//! reference IDs (`[T3]`) that survive summarization, so the model can fetch
+//! them again with the `get_tool_result` tool; the saved transcript keeps the
+//! first [`SAVED_TOOL_RESULT_CHARS`] of each.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::api::{ChatMessage, MessageRole, ToolRecord};
+use crate::settings;
+use crate::text;
+
+/// Approximate token budget for the turns sent with each request.
+pub const CONTEXT_BUDGET_TOKENS: usize = 24_000;
+/// Turns always sent verbatim, never summarized.
+pub const KEEP_RECENT_MESSAGES: usize = 6;
+
+/// Characters of each tool result written to the transcript file.
+pub const SAVED_TOOL_RESULT_CHARS: usize = 8_000;
+
+const TRANSCRIPT_FILE: &str = "chat_transcript.json";
+
+/// Rough token estimate (~4 characters per token), good enough for budgeting.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Summary of the transcript up to a message index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    /// Messages before this index are covered by the summary.
+    pub covers_until: usize,
+    pub text: String,
+    pub created_ms: u64,
+}
+
+/// Chat transcript with its summaries and referenced tool results.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Transcript {
+    pub messages: Vec<ChatMessage>,
+    /// Rolling summaries, oldest first; each covers everything before its
+    /// `covers_until`, including earlier summaries.
+    pub summaries: Vec<Summary>,
+    pub tool_results: Vec<ToolRecord>,
+    /// Prompt template picked for this conversation; the default template
+    /// when unset (see [`crate::chat::prompts`]).
+    pub template: Option<String>,
+}
+
+impl Transcript {
+    /// Path of the transcript file, if a data directory is available.
+    pub fn path() -> Option<PathBuf> {
+        settings::data_dir().map(|d| d.join(TRANSCRIPT_FILE))
+    }
+
+    /// Load the saved transcript, starting empty if missing or unreadable.
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path().ok_or("No data directory available")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        }
+        let json = serde_json::to_string(&self.for_saving())
+            .map_err(|e| format!("Failed to serialize transcript: {}", e))?;
+        std::fs::write(&path, json).map_err(|e| format!("Failed to write transcript: {}", e))
+    }
+
+    /// Copy to write to disk, long tool results cut to
+    /// [`SAVED_TOOL_RESULT_CHARS`].
+    fn for_saving(&self) -> Self {
+        let mut saved = self.clone();
+        for record in &mut saved.tool_results {
+            record.content = text::truncate(&record.content, SAVED_TOOL_RESULT_CHARS);
+        }
+        saved
+    }
+
+    pub fn latest_summary(&self) -> Option<&Summary> {
+        self.summaries.last()
+    }
+
+    /// Index of the first message not covered by a summary.
+    pub fn summarized_until(&self) -> usize {
+        self.latest_summary().map_or(0, |s| s.covers_until)
+    }
+
+    /// Messages to send to the model: unsummarized turns with their tool
+    /// calls written out, and the latest summary prepended to the first.
+    pub fn context_messages(&self) -> Vec<ChatMessage> {
+        let mut messages: Vec<ChatMessage> = self.messages[self.summarized_until()..]
+            .iter()
+            .map(|m| ChatMessage {
+                role: m.role,
+                content: m.context_text(),
+                model: m.model.clone(),
+                tool_steps: Vec::new(),
+            })
+            .collect();
+        if let (Some(summary), Some(first)) = (self.latest_summary(), messages.first_mut()) {
+            first.content = format!(
+                "[Summary of the earlier conversation]\n{}\n\n{}",
+                summary.text, first.content
+            );
+        }
+        messages
+    }
+
+    /// Index of the last user message, if it is not yet summarized and so
+    /// can be sent again.
+    pub fn last_prompt(&self) -> Option<usize> {
+        self.messages
+            .iter()
+            .rposition(|m| m.role == MessageRole::User)
+            .filter(|&i| i >= self.summarized_until())
+    }
+
+    /// Drop the replies to the last prompt so it can be sent again.
+    pub fn drop_last_reply(&mut self) -> bool {
+        match self.last_prompt() {
+            Some(prompt) => {
+                self.messages.truncate(prompt + 1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Estimated tokens of the context that would be sent.
+    pub fn context_tokens(&self) -> usize {
+        self.context_messages()
+            .iter()
+            .map(|m| estimate_tokens(&m.content))
+            .sum()
+    }
+
+    /// Where the next summary should end, if the context is over budget.
+    ///
+    /// The boundary always lands on a user message so the remaining context
+    /// starts with a user turn, as the API requires.
+    pub fn next_summary_boundary(&self) -> Option<usize> {
+        if self.context_tokens() <= CONTEXT_BUDGET_TOKENS {
+            return None;
+        }
+        let start = self.summarized_until();
+        let latest = self.messages.len().checked_sub(KEEP_RECENT_MESSAGES)?;
+        (start + 1..=latest)
+            .rev()
+            .find(|&i| self.messages[i].role == MessageRole::User)
+    }
+
+    /// Prompt asking the model to summarize messages up to `covers_until`,
+    /// folding in the previous summary.
+    pub fn summary_prompt(&self, covers_until: usize) -> String {
+        let mut prompt = String::new();
+        if let Some(summary) = self.latest_summary() {
+            prompt.push_str("Previous summary:\n");
+            prompt.push_str(&summary.text);
+            prompt.push_str("\n\n");
+        }
+        prompt.push_str("Conversation to add:\n");
+        for message in &self.messages[self.summarized_until()..covers_until] {
+            let role = match message.role {
+                MessageRole::User => "User",
+                MessageRole::Assistant => "Assistant",
+            };
+            prompt.push_str(&format!("{}: {}\n", role, message.context_text()));
+        }
+        prompt
+    }
+
+    /// Record a summary; stale ones (from before a newer summary or for a
+    /// transcript that has since been cleared) are ignored.
+    pub fn apply_summary(&mut self, covers_until: usize, text: String, now_ms: u64) -> bool {
+        if covers_until <= self.summarized_until() || covers_until > self.messages.len() {
+            return false;
+        }
+        self.summaries.push(Summary {
+            covers_until,
+            text,
+            created_ms: now_ms,
+        });
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: MessageRole, chars: usize) -> ChatMessage {
+        ChatMessage {
+            role,
+            content: "x".repeat(chars),
+            model: None,
+            tool_steps: Vec::new(),
+        }
+    }
+
+    /// Alternating user/assistant turns of `chars` characters each.
+    fn transcript(turns: usize, chars: usize) -> Transcript {
+        let messages = (0..turns)
+            .map(|i| {
+                let role = if i % 2 == 0 {
+                    MessageRole::User
+                } else {
+                    MessageRole::Assistant
+                };
+                message(role, chars)
+            })
+            .collect();
+        Transcript {
+            messages,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_saved_tool_results_are_truncated() {
+        let record = |content: String| ToolRecord {
+            id: "T1".to_string(),
+            name: "dora_logs".to_string(),
+            content,
+            is_error: false,
+        };
+        let transcript = Transcript {
+            tool_results: vec![
+                record("x".repeat(SAVED_TOOL_RESULT_CHARS * 2)),
+                record("short".to_string()),
+            ],
+            ..Default::default()
+        };
+        let saved = transcript.for_saving();
+        assert!(saved.tool_results[0].content.len() <= SAVED_TOOL_RESULT_CHARS + 3);
+        assert!(saved.tool_results[0].content.ends_with("..."));
+        assert_eq!(saved.tool_results[1].content, "short");
+        // The transcript in memory keeps the full output
+        assert_eq!(
+            transcript.tool_results[0].content.len(),
+            SAVED_TOOL_RESULT_CHARS * 2
+        );
+    }
+
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+
+    #[test]
+    fn test_small_context_needs_no_summary() {
+        let transcript = transcript(10, 100);
+        assert_eq!(transcript.next_summary_boundary(), None);
+        assert_eq!(transcript.context_messages().len(), 10);
+    }
+
+    #[test]
+    fn test_boundary_keeps_recent_turns_and_lands_on_user() {
+        // 20 turns of ~2000 tokens each exceed the budget
+        let transcript = transcript(20, 8_000);
+        let boundary = transcript.next_summary_boundary().unwrap();
+        assert_eq!(boundary, 14);
+        assert_eq!(transcript.messages[boundary].role, MessageRole::User);
+        assert!(transcript.messages.len() - boundary >= KEEP_RECENT_MESSAGES);
+    }
+
+    #[test]
+    fn test_summary_bounds_context() {
+        let mut transcript = transcript(20, 8_000);
+        let boundary = transcript.next_summary_boundary().unwrap();
+        assert!(transcript.apply_summary(boundary, "Earlier: started [T1]".to_string(), 5));
+
+        let context = transcript.context_messages();
+        assert_eq!(context.len(), 20 - boundary);
+        assert_eq!(context[0].role, MessageRole::User);
+        assert!(context[0].content.contains("Earlier: started [T1]"));
+        assert_eq!(transcript.next_summary_boundary(), None);
+
+        // The full transcript is kept
+        assert_eq!(transcript.messages.len(), 20);
+    }
+
+    #[test]
+    fn test_stale_summary_is_ignored() {
+        let mut transcript = transcript(20, 8_000);
+        assert!(transcript.apply_summary(14, "a".to_string(), 1));
+        assert!(!transcript.apply_summary(10, "b".to_string(), 2));
+        assert!(!transcript.apply_summary(30, "c".to_string(), 3));
+        assert_eq!(transcript.summaries.len(), 1);
+    }
+
+    #[test]
+    fn test_drop_last_reply() {
+        let mut transcript = transcript(4, 4);
+        assert!(transcript.drop_last_reply());
+        assert_eq!(transcript.messages.len(), 3);
+        // An unanswered prompt is kept
+        assert!(transcript.drop_last_reply());
+        assert_eq!(transcript.messages.len(), 3);
+
+        transcript.apply_summary(3, "all of it".to_string(), 1);
+        assert_eq!(transcript.last_prompt(), None);
+        assert!(!transcript.drop_last_reply());
+        assert!(!Transcript::default().drop_last_reply());
+    }
+
+    #[test]
+    fn test_summary_prompt_folds_previous_summary() {
+        let mut transcript = transcript(8, 4);
+        transcript.apply_summary(2, "earlier".to_string(), 1);
+        let prompt = transcript.summary_prompt(4);
+        assert!(prompt.starts_with("Previous summary:\nearlier"));
+        assert_eq!(prompt.matches("User: ").count(), 1);
+        assert_eq!(prompt.matches("Assistant: ").count(), 1);
+    }
+}
@@ -0,0 +1,184 @@
+//! Markdown in assistant messages, split into blocks the chat draws one
+//! row each.
+//!
+//! Covers what agent replies use: ATX headings, `-`/`*`/`+` and numbered
+//! list items, fenced code blocks and paragraphs. Inline emphasis and code
+//! spans lose their markers, as labels cannot style part of their text.
+
+/// A block of a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    /// `#` to `######`
+    Heading { level: u8, text: String },
+    /// Lines up to a blank line or another block; line breaks are kept
+    Paragraph(String),
+    /// `marker` is `•` or the item number, e.g. `2.`
+    ListItem {
+        marker: String,
+        indent: usize,
+        text: String,
+    },
+    /// Fenced with ``` or ~~~; an unclosed fence runs to the end
+    Code {
+        language: Option<String>,
+        code: String,
+    },
+}
+
+impl Block {
+    /// A YAML code block describing a dora dataflow
+    pub fn is_dataflow_yaml(&self) -> bool {
+        match self {
+            Block::Code {
+                language: Some(language),
+                code,
+            } => {
+                matches!(language.as_str(), "yaml" | "yml")
+                    && code.lines().any(|line| line.starts_with("nodes:"))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Split `text` into blocks.
+pub fn parse(text: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut lines = text.lines();
+
+    let flush = |paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>| {
+        if !paragraph.is_empty() {
+            blocks.push(Block::Paragraph(inline(&paragraph.join("\n"))));
+            paragraph.clear();
+        }
+    };
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        if let Some(fence) = ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f)) {
+            flush(&mut paragraph, &mut blocks);
+            let language =
+                Some(trimmed[fence.len()..].trim().to_string()).filter(|l| !l.is_empty());
+            let code: Vec<&str> = lines
+                .by_ref()
+                .take_while(|l| !l.trim_start().starts_with(fence))
+                .collect();
+            blocks.push(Block::Code {
+                language,
+                code: code.join("\n"),
+            });
+        } else if trimmed.is_empty() {
+            flush(&mut paragraph, &mut blocks);
+        } else if let Some((level, text)) = heading(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading {
+                level,
+                text: inline(text),
+            });
+        } else if let Some((marker, text)) = list_item(trimmed) {
+            flush(&mut paragraph, &mut blocks);
+            blocks.push(Block::ListItem {
+                marker,
+                indent: (line.len() - trimmed.len()) / 2,
+                text: inline(text),
+            });
+        } else {
+            paragraph.push(line.trim_end());
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+    blocks
+}
+
+fn heading(line: &str) -> Option<(u8, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    (1..=6).contains(&level).then(|| (level as u8, text.trim()))
+}
+
+fn list_item(line: &str) -> Option<(String, &str)> {
+    if let Some(text) = ["- ", "* ", "+ "].iter().find_map(|m| line.strip_prefix(m)) {
+        return Some(("•".to_string(), text.trim()));
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let text = line[digits..]
+        .strip_prefix(". ")
+        .or_else(|| line[digits..].strip_prefix(") "))?;
+    (digits > 0).then(|| (format!("{}.", &line[..digits]), text.trim()))
+}
+
+/// Drop emphasis and code span markers.
+fn inline(text: &str) -> String {
+    text.replace("**", "").replace("__", "").replace('`', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_blocks() {
+        let text = "## Plan\n\
+                    Start the **camera** node\n\
+                    then `plot`.\n\
+                    \n\
+                    - check the YAML\n  \
+                    - run `dora up`\n\
+                    2) start it\n\
+                    ```yaml\n\
+                    nodes:\n  \
+                    - id: camera\n\
+                    ```\n\
+                    Done.";
+        assert_eq!(
+            parse(text),
+            vec![
+                Block::Heading {
+                    level: 2,
+                    text: "Plan".to_string()
+                },
+                Block::Paragraph("Start the camera node\nthen plot.".to_string()),
+                Block::ListItem {
+                    marker: "•".to_string(),
+                    indent: 0,
+                    text: "check the YAML".to_string()
+                },
+                Block::ListItem {
+                    marker: "•".to_string(),
+                    indent: 1,
+                    text: "run dora up".to_string()
+                },
+                Block::ListItem {
+                    marker: "2.".to_string(),
+                    indent: 0,
+                    text: "start it".to_string()
+                },
+                Block::Code {
+                    language: Some("yaml".to_string()),
+                    code: "nodes:\n  - id: camera".to_string()
+                },
+                Block::Paragraph("Done.".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_edge_cases() {
+        assert!(parse("").is_empty());
+        // Not headings or list items without the space
+        assert_eq!(
+            parse("#hashtag\n-5 degrees"),
+            vec![Block::Paragraph("#hashtag\n-5 degrees".to_string())]
+        );
+        assert!(parse("```yml\nnodes:\n  - id: a\n```")[0].is_dataflow_yaml());
+        assert!(!parse("```yaml\nkey: value\n```")[0].is_dataflow_yaml());
+        assert_eq!(
+            parse("~~~\nls -la"),
+            vec![Block::Code {
+                language: None,
+                code: "ls -la".to_string()
+            }]
+        );
+    }
+}
@@ -1,6 +1,10 @@
 use makepad_widgets::Cx;
 
 pub mod chat_screen;
+pub mod context;
+pub mod markdown;
+pub mod prompts;
+pub mod session;
 
 pub fn live_design(cx: &mut Cx) {
     self::chat_screen::live_design(cx);
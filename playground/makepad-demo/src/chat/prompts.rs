@@ -0,0 +1,211 @@
+//! System prompt templates for the chat agent.
+//!
+//! A few templates are built in. Templates saved in settings replace the
+//! built-in of the same name or add a new one, and each conversation picks
+//! one by name. What the agent needs to know about its tools is appended by
+//! [`crate::api`] whatever the template.
+
+use serde::{Deserialize, Serialize};
+
+/// Template used when a conversation has not picked one.
+pub const DEFAULT_TEMPLATE: &str = "Dora Studio Assistant";
+
+/// Log lines sent when asking for a log summary.
+pub const LOG_SUMMARY_LINES: usize = 200;
+
+/// System prompt for summarizing a dataflow's logs.
+pub const LOG_SUMMARY_PROMPT: &str =
+    "You are Dora Studio Assistant, diagnosing a dora dataflow from its logs. Be concise.
+
+Reply with:
+- One line on what the dataflow was doing
+- ## Probable root cause: the failing node and why, quoting the log lines that show it
+- ## Suggested fix: concrete steps, such as YAML or code changes
+Say so when the logs show no problem.";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub prompt: String,
+}
+
+impl PromptTemplate {
+    fn new(name: &str, prompt: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            prompt: prompt.to_string(),
+        }
+    }
+}
+
+pub fn builtin_templates() -> Vec<PromptTemplate> {
+    vec![
+        PromptTemplate::new(
+            DEFAULT_TEMPLATE,
+            "You are Dora Studio Assistant. Be extremely concise and succinct.
+
+Rules:
+- Give short, direct answers
+- No unnecessary explanations or preambles
+- Only provide details when specifically asked
+- Use bullet points for lists
+- Skip pleasantries",
+        ),
+        PromptTemplate::new(
+            "Dora debugging assistant",
+            "You are Dora Studio Assistant, helping debug running dora dataflows. Be concise.
+
+Rules:
+- Look before guessing: check dataflow status, node logs and traces first
+- Name the node and the result each finding rests on
+- Suggest one fix at a time and how to verify it
+- Say so when the evidence is not enough",
+        ),
+        PromptTemplate::new(
+            "Pipeline author",
+            "You are Dora Studio Assistant, helping write dora dataflow YAML. Be concise.
+
+Rules:
+- Ask about inputs, outputs or node sources only when they cannot be inferred
+- Reply with the complete dataflow in one ```yaml code block
+- Follow it with one line per node saying what it does
+- Keep node ids short, lowercase and hyphenated",
+        ),
+    ]
+}
+
+/// Built-in templates as edited by `saved`, then the other saved templates.
+pub fn templates(saved: &[PromptTemplate]) -> Vec<PromptTemplate> {
+    let mut templates = builtin_templates();
+    for template in saved {
+        match templates
+            .iter_mut()
+            .find(|t| t.name.eq_ignore_ascii_case(&template.name))
+        {
+            Some(existing) => existing.prompt = template.prompt.clone(),
+            None => templates.push(template.clone()),
+        }
+    }
+    templates
+}
+
+/// Template names, marking built-ins that `saved` edits, e.g.
+/// `Pipeline author (edited), Reviewer`.
+pub fn describe_templates(saved: &[PromptTemplate]) -> String {
+    let builtin = builtin_templates();
+    templates(saved)
+        .iter()
+        .map(|t| match find_template(&builtin, &t.name) {
+            Some(original) if original.prompt != t.prompt => format!("{} (edited)", t.name),
+            _ => t.name.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Message asking for a summary of the last [`LOG_SUMMARY_LINES`] lines of
+/// `logs` from the dataflow `name`.
+pub fn log_summary_request(name: &str, logs: &str) -> String {
+    let lines: Vec<&str> = logs.lines().filter(|l| !l.trim().is_empty()).collect();
+    let shown = &lines[lines.len().saturating_sub(LOG_SUMMARY_LINES)..];
+    format!(
+        "Summarize the logs of dataflow {} ({} of {} lines):\n```\n{}\n```",
+        name,
+        shown.len(),
+        lines.len(),
+        shown.join("\n")
+    )
+}
+
+/// Look up a template by name, ignoring case.
+pub fn find_template<'a>(
+    templates: &'a [PromptTemplate],
+    name: &str,
+) -> Option<&'a PromptTemplate> {
+    let name = name.trim();
+    templates.iter().find(|t| t.name.eq_ignore_ascii_case(name))
+}
+
+/// Prompt of the template named `name`, or of the default template when
+/// unset or no longer there.
+pub fn system_prompt(templates: &[PromptTemplate], name: Option<&str>) -> String {
+    name.and_then(|name| find_template(templates, name))
+        .or_else(|| find_template(templates, DEFAULT_TEMPLATE))
+        .or(templates.first())
+        .map(|t| t.prompt.clone())
+        .unwrap_or_default()
+}
+
+/// Save `prompt` as the template `name`. An empty prompt deletes a saved
+/// template, so an edited built-in goes back to its original text.
+pub fn save_template(
+    saved: &mut Vec<PromptTemplate>,
+    name: &str,
+    prompt: &str,
+) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Enter a template name".to_string());
+    }
+    let prompt = prompt.trim();
+    let existing = saved.iter().position(|t| t.name.eq_ignore_ascii_case(name));
+    match (existing, prompt.is_empty()) {
+        (Some(index), true) => {
+            saved.remove(index);
+        }
+        (None, true) => return Err(format!("No saved template named '{}'", name)),
+        (Some(index), false) => saved[index].prompt = prompt.to_string(),
+        (None, false) => saved.push(PromptTemplate::new(name, prompt)),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_templates_edit_builtins() {
+        let mut saved = Vec::new();
+        save_template(&mut saved, "pipeline author", "Write YAML.").unwrap();
+        save_template(&mut saved, "Reviewer", "Review the dataflow.").unwrap();
+        assert!(save_template(&mut saved, " ", "x").is_err());
+
+        let all = templates(&saved);
+        assert_eq!(all.len(), 4);
+        // Names match ignoring case, keeping the built-in's
+        assert_eq!(all[2].name, "Pipeline author");
+        assert_eq!(all[2].prompt, "Write YAML.");
+        assert_eq!(all[3].name, "Reviewer");
+        assert_eq!(
+            describe_templates(&saved),
+            "Dora Studio Assistant, Dora debugging assistant, Pipeline author (edited), Reviewer"
+        );
+
+        // An empty prompt deletes the saved template
+        save_template(&mut saved, "REVIEWER", "").unwrap();
+        assert_eq!(saved.len(), 1);
+        assert!(save_template(&mut saved, "Reviewer", "").is_err());
+    }
+
+    #[test]
+    fn test_log_summary_request_keeps_last_lines() {
+        let logs: String = (1..=250).map(|i| format!("line {}\n\n", i)).collect();
+        let request = log_summary_request("camera", &logs);
+        assert!(request.starts_with("Summarize the logs of dataflow camera (200 of 250 lines)"));
+        assert!(request.contains("```\nline 51\n"));
+        assert!(request.ends_with("line 250\n```"));
+        assert!(!request.contains("line 50\n"));
+    }
+
+    #[test]
+    fn test_system_prompt_falls_back_to_default() {
+        let saved = vec![PromptTemplate::new(DEFAULT_TEMPLATE, "Be brief.")];
+        let all = templates(&saved);
+        assert_eq!(all.len(), 3);
+        assert_eq!(system_prompt(&all, None), "Be brief.");
+        assert_eq!(system_prompt(&all, Some("gone")), "Be brief.");
+        assert!(system_prompt(&all, Some("pipeline AUTHOR")).contains("```yaml"));
+        assert_eq!(system_prompt(&[], None), "");
+    }
+}
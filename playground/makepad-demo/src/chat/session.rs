@@ -0,0 +1,278 @@
+//! Chat sessions exported to a file to share, and imported back.
+//!
+//! A session is exported as JSON, holding the whole transcript with its
+//! summaries and tool results in an [`Envelope`] of kind `chat_session`, or
+//! as Markdown for reading. Only JSON exports can be imported, including
+//! those written before the envelope (format `dora-studio-chat`).
+
+use serde::Deserialize;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use super::context::Transcript;
+use crate::api::MessageRole;
+use crate::i18n;
+use crate::settings;
+use crate::snapshot::{self, Artifact, Envelope};
+
+/// Format of exports written before the envelope.
+const LEGACY_FORMAT: &str = "dora-studio-chat";
+
+impl Artifact for Transcript {
+    const KIND: &'static str = "chat_session";
+    const VERSION: u32 = 1;
+}
+
+/// An export written before the envelope.
+#[derive(Deserialize)]
+struct LegacyExport {
+    version: u32,
+    transcript: Transcript,
+}
+
+/// Where to export a session to or import it from: the entered path, or
+/// the data directory.
+pub fn session_path(input: &str) -> Result<PathBuf, String> {
+    let input = input.trim();
+    if !input.is_empty() {
+        return Ok(PathBuf::from(input));
+    }
+    settings::data_dir()
+        .map(|d| d.join("chat-session.json"))
+        .ok_or_else(|| i18n::tr("chat.enter_session_path"))
+}
+
+/// Whether `path` names a Markdown file rather than JSON.
+fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("md") || e.eq_ignore_ascii_case("markdown"))
+}
+
+/// Write `transcript` to `path`, as Markdown for `.md` files and JSON
+/// otherwise.
+pub fn export_session(
+    transcript: &Transcript,
+    path: &Path,
+    exported_at_ms: u64,
+) -> Result<(), String> {
+    let text = if is_markdown(path) {
+        to_markdown(transcript)
+    } else {
+        Envelope::new(transcript.clone(), exported_at_ms).to_json()?
+    };
+    std::fs::write(path, text).map_err(|e| {
+        i18n::tr_args(
+            "error.write_file",
+            &[
+                ("path", &path.display().to_string()),
+                ("error", &e.to_string()),
+            ],
+        )
+    })
+}
+
+/// Read a session exported as JSON.
+pub fn import_session(path: &Path) -> Result<Transcript, String> {
+    if is_markdown(path) {
+        return Err(i18n::tr("chat.import_markdown"));
+    }
+    let text = std::fs::read_to_string(path).map_err(|e| {
+        i18n::tr_args(
+            "error.read_file",
+            &[
+                ("path", &path.display().to_string()),
+                ("error", &e.to_string()),
+            ],
+        )
+    })?;
+    from_json(&text)
+}
+
+/// Parse and validate a JSON export.
+pub fn from_json(text: &str) -> Result<Transcript, String> {
+    if !snapshot::has_format(text, LEGACY_FORMAT) {
+        return Envelope::<Transcript>::from_json(text).map(|envelope| envelope.payload);
+    }
+    let export: LegacyExport = serde_json::from_str(text)
+        .map_err(|e| i18n::tr_args("chat.invalid_session", &[("error", &e.to_string())]))?;
+    // Legacy exports were all version 1, the envelope's first too
+    if export.version > Transcript::VERSION {
+        return Err(i18n::tr_args(
+            "chat.session_too_new",
+            &[
+                ("version", &export.version.to_string()),
+                ("supported", &Transcript::VERSION.to_string()),
+            ],
+        ));
+    }
+    Ok(export.transcript)
+}
+
+/// The conversation as Markdown: each message under a heading after the
+/// tool calls made for it, summaries where they apply, then the tool
+/// results the messages refer to.
+pub fn to_markdown(transcript: &Transcript) -> String {
+    let mut out = String::from("# Dora Studio chat session\n\n");
+    if let Some(template) = &transcript.template {
+        let _ = writeln!(out, "Prompt template: {}\n", template);
+    }
+    for (index, message) in transcript.messages.iter().enumerate() {
+        for summary in transcript
+            .summaries
+            .iter()
+            .filter(|s| s.covers_until == index)
+        {
+            let _ = writeln!(out, "> **Summary of the conversation so far**");
+            for line in summary.text.lines() {
+                let _ = writeln!(out, "> {}", line);
+            }
+            out.push('\n');
+        }
+        let heading = match (message.role, &message.model) {
+            (MessageRole::User, _) => "User".to_string(),
+            (MessageRole::Assistant, Some(model)) => format!("Assistant ({})", model),
+            (MessageRole::Assistant, None) => "Assistant".to_string(),
+        };
+        let _ = writeln!(out, "## {}\n", heading);
+        for step in &message.tool_steps {
+            let _ = writeln!(out, "- {} {}", step.title(), step.status_text());
+        }
+        if !message.tool_steps.is_empty() {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "{}\n", message.content.trim_end());
+    }
+    if !transcript.tool_results.is_empty() {
+        out.push_str("## Tool results\n\n");
+        for record in &transcript.tool_results {
+            let error = if record.is_error { " (error)" } else { "" };
+            let fence = fence_for(&record.content);
+            let _ = writeln!(
+                out,
+                "### [{}] {}{}\n\n{}\n{}\n{}\n",
+                record.id,
+                record.name,
+                error,
+                fence,
+                record.content.trim_end(),
+                fence
+            );
+        }
+    }
+    out
+}
+
+/// A code fence longer than any run of backticks in `content`.
+fn fence_for(content: &str) -> String {
+    let longest = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{ChatMessage, ToolRecord, ToolStep, ToolStepStatus};
+    use crate::chat::context::Summary;
+
+    fn transcript() -> Transcript {
+        let message = |role, content: &str, model: Option<&str>| ChatMessage {
+            role,
+            content: content.to_string(),
+            model: model.map(str::to_string),
+            tool_steps: Vec::new(),
+        };
+        let mut reply = message(MessageRole::Assistant, "Checked logs [T1]", Some("m1"));
+        reply.tool_steps.push(ToolStep {
+            name: "dora_logs".to_string(),
+            arguments: r#"{"dataflow":"camera"}"#.to_string(),
+            status: ToolStepStatus::Error,
+            ref_id: Some("T1".to_string()),
+            summary: "x".to_string(),
+            output: "```x```".to_string(),
+        });
+        Transcript {
+            messages: vec![
+                message(MessageRole::User, "Why did camera fail?", None),
+                reply,
+                message(MessageRole::User, "Fix it", None),
+            ],
+            summaries: vec![Summary {
+                covers_until: 2,
+                text: "Camera failed".to_string(),
+                created_ms: 5,
+            }],
+            tool_results: vec![ToolRecord {
+                id: "T1".to_string(),
+                name: "dora_logs".to_string(),
+                content: "```x```".to_string(),
+                is_error: true,
+            }],
+            template: Some("Pipeline author".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_session_markdown() {
+        assert_eq!(
+            to_markdown(&transcript()),
+            "# Dora Studio chat session\n\n\
+             Prompt template: Pipeline author\n\n\
+             ## User\n\nWhy did camera fail?\n\n\
+             ## Assistant (m1)\n\n\
+             - Tool: dora_logs (dataflow: camera) ❌ Error T1\n\n\
+             Checked logs [T1]\n\n\
+             > **Summary of the conversation so far**\n> Camera failed\n\n\
+             ## User\n\nFix it\n\n\
+             ## Tool results\n\n\
+             ### [T1] dora_logs (error)\n\n````\n```x```\n````\n\n"
+        );
+    }
+
+    #[test]
+    fn test_session_json_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("dora-session-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.json");
+        export_session(&transcript(), &path, 42).unwrap();
+        let imported = import_session(&path).unwrap();
+        assert_eq!(imported.messages.len(), 3);
+        assert_eq!(imported.messages[1].model.as_deref(), Some("m1"));
+        assert_eq!(
+            imported.messages[1].tool_steps,
+            transcript().messages[1].tool_steps
+        );
+        assert_eq!(imported.summaries, transcript().summaries);
+        assert_eq!(imported.tool_results, transcript().tool_results);
+        assert_eq!(imported.template.as_deref(), Some("Pipeline author"));
+
+        let markdown = dir.join("session.MD");
+        export_session(&transcript(), &markdown, 42).unwrap();
+        assert!(import_session(&markdown).is_err());
+        assert!(
+            from_json(r#"{"format":"other","version":1,"exported_at_ms":0,"transcript":{}}"#)
+                .is_err()
+        );
+        assert!(from_json(
+            r#"{"format":"dora-studio-chat","version":9,"exported_at_ms":0,"transcript":{}}"#
+        )
+        .is_err());
+        assert!(from_json(
+            r#"{"format":"dora-studio","kind":"chat_session","version":9,
+                "min_reader_version":9,"created_at_ms":0,"payload":{}}"#
+        )
+        .is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Exports written before the envelope must keep importing.
+    #[test]
+    fn test_legacy_session_fixture() {
+        let legacy = r#"{"format":"dora-studio-chat","version":1,"exported_at_ms":0,
+            "transcript":{"messages":[{"role":"user","content":"Hi"}],
+                          "template":"Pipeline author"}}"#;
+        let transcript = from_json(legacy).unwrap();
+        assert_eq!(transcript.messages[0].content, "Hi");
+        assert_eq!(transcript.template.as_deref(), Some("Pipeline author"));
+    }
+}
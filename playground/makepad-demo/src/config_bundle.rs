@@ -0,0 +1,459 @@
+//! Shareable configuration bundles.
+//!
+//! A bundle is a single JSON file holding every non-secret setting so a team
+//! can standardise studio setups across machines. Settings are carried as raw
+//! JSON so bundles written by newer versions still import (unknown fields are
+//! ignored) and so new `Settings` fields are exported without extra work.
+//! The file is an [`Envelope`] of kind `config_bundle`; bundles written
+//! before the envelope (format `dora-studio-config`) still import.
+//!
+//! Secrets belong in the secret store rather than `Settings`, but a telemetry
+//! backend can still be configured with credentials inline, so they are
+//! removed from it on both export and import (see
+//! [`BackendConfig::without_secrets`]). Per-dataflow environment variables
+//! often hold tokens too, and are machine-specific anyway, so they are left
+//! out.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::otlp::config::BackendConfig;
+use crate::settings::Settings;
+use crate::snapshot::{self, Artifact, Envelope};
+
+/// Format of bundles written before the envelope.
+const LEGACY_FORMAT: &str = "dora-studio-config";
+
+/// Setting holding the telemetry backend, whose credentials are removed.
+const BACKEND_KEY: &str = "telemetry_backend";
+/// Settings never carried by a bundle.
+const LOCAL_ONLY_KEYS: [&str; 1] = ["dataflow_env"];
+
+/// A bundle, written as the payload of an [`Envelope`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigBundle {
+    /// The envelope's `created_at_ms` on disk
+    #[serde(skip)]
+    pub exported_at_ms: u64,
+    /// Serialized `Settings`, secrets removed.
+    pub settings: serde_json::Value,
+}
+
+impl Artifact for ConfigBundle {
+    const KIND: &'static str = "config_bundle";
+    const VERSION: u32 = 1;
+}
+
+/// A bundle written before the envelope.
+#[derive(Deserialize)]
+struct LegacyBundle {
+    version: u32,
+    exported_at_ms: u64,
+    settings: serde_json::Value,
+}
+
+/// How to resolve settings that differ between the local setup and a bundle.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Resolution {
+    /// Keep local values for conflicting settings.
+    KeepLocal,
+    /// Replace local values with the bundle's.
+    UseIncoming,
+    /// Merge lists (local first, then new incoming entries) and objects
+    /// (incoming keys win); other values are replaced by the bundle's.
+    #[default]
+    Merge,
+}
+
+impl Resolution {
+    pub const ALL: [Resolution; 3] = [
+        Resolution::Merge,
+        Resolution::KeepLocal,
+        Resolution::UseIncoming,
+    ];
+}
+
+/// A top-level setting whose local and incoming values differ.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub key: String,
+    pub local: serde_json::Value,
+    pub incoming: serde_json::Value,
+}
+
+/// Outcome of an import.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ImportSummary {
+    /// Settings changed by the import.
+    pub changed: Vec<String>,
+    /// Conflicting settings left at their local value.
+    pub kept: Vec<String>,
+}
+
+impl ImportSummary {
+    pub fn describe(&self) -> String {
+        format!(
+            "Imported: {} changed, {} kept",
+            self.changed.len(),
+            self.kept.len()
+        )
+    }
+}
+
+impl ConfigBundle {
+    /// Build a bundle from the current settings.
+    pub fn export(settings: &Settings, exported_at_ms: u64) -> Self {
+        let mut value = serde_json::to_value(settings).unwrap_or_default();
+        strip_secrets(&mut value);
+        Self {
+            exported_at_ms,
+            settings: value,
+        }
+    }
+
+    /// Parse and validate a bundle.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let mut bundle = if snapshot::has_format(text, LEGACY_FORMAT) {
+            let legacy: LegacyBundle =
+                serde_json::from_str(text).map_err(|e| format!("Invalid config bundle: {}", e))?;
+            // Legacy bundles were all version 1, the envelope's first too
+            if legacy.version > Self::VERSION {
+                return Err(format!(
+                    "Bundle version {} is newer than supported version {}",
+                    legacy.version,
+                    Self::VERSION
+                ));
+            }
+            Self {
+                exported_at_ms: legacy.exported_at_ms,
+                settings: legacy.settings,
+            }
+        } else {
+            let envelope = Envelope::<Self>::from_json(text)?;
+            Self {
+                exported_at_ms: envelope.created_at_ms,
+                ..envelope.payload
+            }
+        };
+        if !bundle.settings.is_object() {
+            return Err("Config bundle settings must be an object".to_string());
+        }
+        strip_secrets(&mut bundle.settings);
+        Ok(bundle)
+    }
+
+    pub fn read_from(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_json(&text)
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        Envelope::new(self.clone(), self.exported_at_ms).to_json()
+    }
+
+    pub fn write_to(&self, path: &Path) -> Result<(), String> {
+        Envelope::new(self.clone(), self.exported_at_ms).write_to(path)
+    }
+
+    /// Top-level settings present in the bundle that differ from `local`.
+    pub fn conflicts(&self, local: &Settings) -> Vec<Conflict> {
+        let local = serde_json::to_value(local).unwrap_or_default();
+        let (Some(local), Some(incoming)) = (local.as_object(), self.settings.as_object()) else {
+            return Vec::new();
+        };
+        let mut conflicts: Vec<Conflict> = incoming
+            .iter()
+            .filter_map(|(key, value)| {
+                let current = local.get(key)?;
+                (current != value).then(|| Conflict {
+                    key: key.clone(),
+                    local: current.clone(),
+                    incoming: value.clone(),
+                })
+            })
+            .collect();
+        conflicts.sort_by(|a, b| a.key.cmp(&b.key));
+        conflicts
+    }
+
+    /// Apply the bundle on top of `local` using `resolution` for conflicts.
+    pub fn apply(
+        &self,
+        local: &Settings,
+        resolution: Resolution,
+    ) -> Result<(Settings, ImportSummary), String> {
+        let mut merged = serde_json::to_value(local).unwrap_or_default();
+        let mut summary = ImportSummary::default();
+
+        if let Some(target) = merged.as_object_mut() {
+            for conflict in self.conflicts(local) {
+                let value = match resolution {
+                    Resolution::KeepLocal => {
+                        summary.kept.push(conflict.key);
+                        continue;
+                    }
+                    Resolution::UseIncoming => conflict.incoming,
+                    Resolution::Merge => merge_values(conflict.local, conflict.incoming),
+                };
+                target.insert(conflict.key.clone(), value);
+                summary.changed.push(conflict.key);
+            }
+        }
+
+        let settings = serde_json::from_value(merged)
+            .map_err(|e| format!("Config bundle has invalid settings: {}", e))?;
+        Ok((settings, summary))
+    }
+}
+
+fn merge_values(local: serde_json::Value, incoming: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match (local, incoming) {
+        (Value::Array(mut local), Value::Array(incoming)) => {
+            for item in incoming {
+                if !local.contains(&item) {
+                    local.push(item);
+                }
+            }
+            Value::Array(local)
+        }
+        (Value::Object(mut local), Value::Object(incoming)) => {
+            local.extend(incoming);
+            Value::Object(local)
+        }
+        (_, incoming) => incoming,
+    }
+}
+
+/// Remove credentials and local-only settings from serialized settings. A
+/// backend that no longer parses, e.g. one written by a newer version, is
+/// dropped whole since its credentials cannot be told apart.
+fn strip_secrets(settings: &mut serde_json::Value) {
+    let Some(map) = settings.as_object_mut() else {
+        return;
+    };
+    for key in LOCAL_ONLY_KEYS {
+        map.remove(key);
+    }
+    let Some(backend) = map.remove(BACKEND_KEY) else {
+        return;
+    };
+    let backend = match serde_json::from_value::<Option<BackendConfig>>(backend) {
+        Ok(backend) => backend.map(BackendConfig::without_secrets),
+        Err(_) => return,
+    };
+    if let Ok(value) = serde_json::to_value(backend) {
+        map.insert(BACKEND_KEY.to_string(), value);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging::Level;
+
+    fn local() -> Settings {
+        Settings {
+            log_level: Level::Info,
+            recent_dataflows: vec!["a.yml".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn incoming() -> ConfigBundle {
+        ConfigBundle::export(
+            &Settings {
+                log_level: Level::Debug,
+                recent_dataflows: vec!["b.yml".to_string(), "a.yml".to_string()],
+                ..Default::default()
+            },
+            1,
+        )
+    }
+
+    #[test]
+    fn test_export_roundtrip() {
+        let bundle = ConfigBundle::export(&local(), 42);
+        let json = bundle.to_json().unwrap();
+        let parsed = ConfigBundle::from_json(&json).unwrap();
+        assert_eq!(parsed, bundle);
+        assert!(parsed.conflicts(&local()).is_empty());
+    }
+
+    #[test]
+    fn test_from_json_rejects_other_formats() {
+        let err = ConfigBundle::from_json(
+            r#"{"format":"x","version":1,"exported_at_ms":0,"settings":{}}"#,
+        )
+        .unwrap_err();
+        assert!(err.contains("Not a Dora Studio file"), "{}", err);
+        let err = ConfigBundle::from_json(
+            r#"{"format":"dora-studio","kind":"dashboard","version":1,"created_at_ms":0,"payload":{}}"#,
+        )
+        .unwrap_err();
+        assert!(err.contains("Expected a config_bundle"), "{}", err);
+    }
+
+    #[test]
+    fn test_from_json_rejects_newer_version() {
+        let json = format!(
+            r#"{{"format":"dora-studio","kind":"config_bundle","version":{0},
+                "min_reader_version":{0},"created_at_ms":0,"payload":{{"settings":{{}}}}}}"#,
+            ConfigBundle::VERSION + 1
+        );
+        assert!(ConfigBundle::from_json(&json)
+            .unwrap_err()
+            .contains("needs version"));
+        let legacy = format!(
+            r#"{{"format":"{}","version":2,"exported_at_ms":0,"settings":{{}}}}"#,
+            LEGACY_FORMAT
+        );
+        assert!(ConfigBundle::from_json(&legacy)
+            .unwrap_err()
+            .contains("newer"));
+    }
+
+    /// Bundles written before the envelope must keep importing.
+    #[test]
+    fn test_legacy_bundle_fixture() {
+        let legacy = r#"{"format":"dora-studio-config","version":1,"exported_at_ms":7,
+            "settings":{"log_level":"debug","recent_dataflows":["b.yml"]}}"#;
+        let bundle = ConfigBundle::from_json(legacy).unwrap();
+        assert_eq!(bundle.exported_at_ms, 7);
+        let (settings, _) = bundle.apply(&local(), Resolution::UseIncoming).unwrap();
+        assert_eq!(settings.log_level, Level::Debug);
+    }
+
+    #[test]
+    fn test_strip_secrets_keeps_unknown_backends_out() {
+        let mut value = serde_json::json!({
+            "log_level": "info",
+            "telemetry_backend": { "backend": "tempo", "url": "http://x", "token": "t" },
+        });
+        strip_secrets(&mut value);
+        assert_eq!(value, serde_json::json!({ "log_level": "info" }));
+    }
+
+    #[test]
+    fn test_dataflow_env_is_not_exported() {
+        let mut settings = local();
+        settings.dataflow_env.insert(
+            "robot.yml".to_string(),
+            vec![("HF_TOKEN".to_string(), "hunter2".to_string())],
+        );
+        let bundle = ConfigBundle::export(&settings, 1);
+        assert!(bundle.settings.get("dataflow_env").is_none());
+
+        // Importing keeps the local variables
+        let (imported, _) = bundle.apply(&settings, Resolution::UseIncoming).unwrap();
+        assert_eq!(imported.dataflow_env, settings.dataflow_env);
+    }
+
+    #[test]
+    fn test_credentials_do_not_round_trip() {
+        use crate::otlp::config::{
+            AuthMethod, ClickHouseConfig, ElasticConfig, SigNozConfig, ZipkinConfig,
+        };
+        let signoz = |auth| {
+            BackendConfig::SigNoz(SigNozConfig {
+                base_url: "http://signoz:8080".to_string(),
+                auth,
+                timeout_secs: 30,
+            })
+        };
+        let backends = [
+            signoz(AuthMethod::ApiKey {
+                header_name: "SIGNOZ-API-KEY".to_string(),
+                key: "hunter2".to_string(),
+            }),
+            signoz(AuthMethod::BearerToken {
+                token: "hunter2".to_string(),
+            }),
+            BackendConfig::ClickHouse(ClickHouseConfig {
+                url: "http://clickhouse:8123".to_string(),
+                user: Some("reader".to_string()),
+                password: Some("hunter2".to_string()),
+                traces_table: "traces".to_string(),
+                logs_table: "logs".to_string(),
+                timeout_secs: 30,
+            }),
+            BackendConfig::Elastic(ElasticConfig {
+                url: "http://elastic:9200".to_string(),
+                api_key: Some("hunter2".to_string()),
+                user: Some("elastic".to_string()),
+                password: Some("hunter2".to_string()),
+                logs_index: "logs-*".to_string(),
+                traces_index: None,
+                timeout_secs: 30,
+            }),
+            BackendConfig::Zipkin(ZipkinConfig {
+                url: "http://zipkin:9411".to_string(),
+                timeout_secs: 30,
+            }),
+        ];
+        for backend in backends {
+            let settings = Settings {
+                telemetry_backend: Some(backend.clone()),
+                ..Default::default()
+            };
+            let json = ConfigBundle::export(&settings, 1).to_json().unwrap();
+            assert!(!json.contains("hunter2"), "secret exported: {}", json);
+
+            let bundle = ConfigBundle::from_json(&json).unwrap();
+            let (imported, _) = bundle
+                .apply(&Settings::default(), Resolution::UseIncoming)
+                .unwrap();
+            assert_eq!(imported.telemetry_backend, Some(backend.without_secrets()));
+        }
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let conflicts = incoming().conflicts(&local());
+        let keys: Vec<&str> = conflicts.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(keys, vec!["log_level", "recent_dataflows"]);
+    }
+
+    #[test]
+    fn test_apply_keep_local() {
+        let (settings, summary) = incoming().apply(&local(), Resolution::KeepLocal).unwrap();
+        assert_eq!(settings, local());
+        assert_eq!(summary.kept.len(), 2);
+        assert!(summary.changed.is_empty());
+    }
+
+    #[test]
+    fn test_apply_use_incoming() {
+        let (settings, summary) = incoming().apply(&local(), Resolution::UseIncoming).unwrap();
+        assert_eq!(settings.log_level, Level::Debug);
+        assert_eq!(settings.recent_dataflows, vec!["b.yml", "a.yml"]);
+        assert_eq!(summary.changed.len(), 2);
+    }
+
+    #[test]
+    fn test_apply_merge_unions_lists() {
+        let (settings, _) = incoming().apply(&local(), Resolution::Merge).unwrap();
+        assert_eq!(settings.log_level, Level::Debug);
+        assert_eq!(settings.recent_dataflows, vec!["a.yml", "b.yml"]);
+    }
+
+    #[test]
+    fn test_apply_ignores_unknown_keys() {
+        let mut bundle = incoming();
+        bundle.settings["future_setting"] = serde_json::json!(true);
+        let (settings, _) = bundle.apply(&local(), Resolution::UseIncoming).unwrap();
+        assert_eq!(settings.log_level, Level::Debug);
+    }
+
+    #[test]
+    fn test_apply_rejects_invalid_values() {
+        let mut bundle = incoming();
+        bundle.settings["log_level"] = serde_json::json!("loud");
+        assert!(bundle.apply(&local(), Resolution::UseIncoming).is_err());
+    }
+}
@@ -0,0 +1,171 @@
+//! Dialog shown on launch after a crash, offering to open the report
+//! [`super::install_hook`] wrote.
+
+use makepad_widgets::*;
+
+use crate::i18n;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+    DIALOG_BORDER = #cbd5e1
+
+    pub CrashDialog = {{CrashDialog}} {
+        width: 440, height: Fit
+        flow: Down
+        show_bg: true
+        draw_bg: { color: (DIALOG_BORDER) }
+        padding: 1
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Down
+            show_bg: true
+            draw_bg: { color: #ffffff }
+            padding: 12
+            spacing: 6
+
+            title_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 14.0 }
+                }
+                text: "Studio closed unexpectedly"
+            }
+
+            body_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                    wrap: Word
+                }
+                text: "A crash report with a backtrace and recent log records was saved. Attach it when reporting the problem."
+            }
+
+            report_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 10.0 }
+                    wrap: Word
+                }
+            }
+
+            <View> {
+                width: Fill, height: Fit
+                flow: Right
+                align: { x: 1.0 }
+                spacing: 8
+                padding: { top: 6 }
+
+                dismiss_button = <Button> {
+                    width: 80, height: 30
+                    text: "Dismiss"
+                    draw_text: { text_style: { font_size: 11.0 } }
+                }
+                open_report_button = <Button> {
+                    width: 110, height: 30
+                    text: "Open report"
+                    draw_text: { text_style: { font_size: 11.0 } }
+                }
+            }
+        }
+    }
+}
+
+/// Actions emitted by the CrashDialog
+#[derive(Clone, Debug, DefaultNone)]
+pub enum CrashDialogAction {
+    None,
+    OpenReport,
+    Dismissed,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct CrashDialog {
+    #[deref]
+    view: View,
+}
+
+impl Widget for CrashDialog {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if !self.view.visible() {
+            return;
+        }
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+
+        if let Event::KeyDown(ke) = event {
+            if ke.key_code == KeyCode::Escape {
+                cx.widget_action(self.widget_uid(), &scope.path, CrashDialogAction::Dismissed);
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for CrashDialog {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope) {
+        if self.view.button(ids!(dismiss_button)).clicked(actions) {
+            cx.widget_action(self.widget_uid(), &scope.path, CrashDialogAction::Dismissed);
+        } else if self.view.button(ids!(open_report_button)).clicked(actions) {
+            cx.widget_action(
+                self.widget_uid(),
+                &scope.path,
+                CrashDialogAction::OpenReport,
+            );
+        }
+    }
+}
+
+impl CrashDialog {
+    /// Show the dialog for the report at `path`.
+    pub fn open(&mut self, cx: &mut Cx, path: &str) {
+        i18n::localize(
+            cx,
+            &[
+                (ids!(title_label), "crash.title"),
+                (ids!(body_label), "crash.body"),
+                (ids!(dismiss_button), "action.dismiss"),
+                (ids!(open_report_button), "crash.open_report"),
+            ],
+            |path| self.view.widget(path),
+        );
+        self.view.label(ids!(report_label)).set_text(cx, path);
+        self.view.set_visible(cx, true);
+        self.redraw(cx);
+    }
+
+    pub fn close(&mut self, cx: &mut Cx) {
+        self.view.set_visible(cx, false);
+        self.redraw(cx);
+    }
+}
+
+impl CrashDialogRef {
+    pub fn open(&self, cx: &mut Cx, path: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.open(cx, path);
+        }
+    }
+
+    pub fn close(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.close(cx);
+        }
+    }
+
+    /// Check if a CrashDialogAction was triggered
+    pub fn action(&self, actions: &Actions) -> Option<CrashDialogAction> {
+        actions.find_widget_action(self.widget_uid())?.cast()
+    }
+}
@@ -0,0 +1,222 @@
+//! Crash reports for panics.
+//!
+//! [`install_hook`] adds a panic hook that writes `crash-<ms>.txt` to the
+//! `crashes` directory under the data dir: the panic message and location,
+//! a backtrace, the app version and platform, and the most recent internal
+//! log records. A panic on the main thread takes the app down, so that
+//! report is also marked pending; the next launch picks it up with
+//! [`take_pending`] and the [`crash_dialog::CrashDialog`] offers to open it.
+
+pub mod crash_dialog;
+
+pub use crash_dialog::{CrashDialog, CrashDialogAction, CrashDialogRef, CrashDialogWidgetRefExt};
+
+use std::path::{Path, PathBuf};
+
+use makepad_widgets::*;
+
+use crate::datetime::{format_clock_in, format_datetime_in, DisplayZone};
+use crate::logging::{self, LogRecord};
+use crate::settings;
+
+const CRASH_DIR: &str = "crashes";
+/// Holds the path of the report the next launch should offer.
+const PENDING_FILE: &str = "pending";
+/// Reports kept on disk; older ones are removed when a new one is written.
+const MAX_REPORTS: usize = 10;
+/// Log records included at the end of a report.
+const REPORT_LOG_RECORDS: usize = 200;
+
+pub fn live_design(cx: &mut Cx) {
+    crash_dialog::live_design(cx);
+}
+
+/// `<data dir>/crashes`.
+pub fn crash_dir() -> Option<PathBuf> {
+    settings::data_dir().map(|d| d.join(CRASH_DIR))
+}
+
+/// Write a crash report on every panic, then run the previous hook so the
+/// panic is still printed to stderr.
+pub fn install_hook() {
+    let Some(dir) = crash_dir() else {
+        return;
+    };
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let thread = thread.name().unwrap_or("unnamed");
+        let backtrace = std::backtrace::Backtrace::force_capture();
+        let timestamp_ms = logging::now_ms();
+        let report = format_report(
+            timestamp_ms,
+            thread,
+            &info.to_string(),
+            &backtrace.to_string(),
+            &logging::try_recent_records(),
+        );
+        match write_report(&dir, &report, timestamp_ms, thread == "main") {
+            Ok(path) => {
+                tracing::error!(target: "Crash", "Report written to {}", path.display())
+            }
+            Err(e) => tracing::error!(target: "Crash", "Failed to write report: {}", e),
+        }
+        previous(info);
+    }));
+}
+
+/// Take the report left by a crash on the previous run, if any.
+pub fn take_pending() -> Option<PathBuf> {
+    take_pending_in(&crash_dir()?)
+}
+
+/// Plain-text crash report.
+pub fn format_report(
+    timestamp_ms: u64,
+    thread: &str,
+    panic: &str,
+    backtrace: &str,
+    records: &[LogRecord],
+) -> String {
+    let mut report = format!(
+        "dora-studio crash report\n\n\
+         Version: {}\n\
+         Platform: {} {}\n\
+         Time: {} ({} ms since epoch)\n\
+         Thread: {}\n\n\
+         {}\n\n\
+         Backtrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        format_datetime_in(timestamp_ms, DisplayZone::Utc),
+        timestamp_ms,
+        thread,
+        panic,
+        backtrace.trim_end(),
+    );
+    let skip = records.len().saturating_sub(REPORT_LOG_RECORDS);
+    report.push_str(&format!(
+        "\nRecent log ({} records):\n",
+        records.len() - skip
+    ));
+    for record in &records[skip..] {
+        report.push_str(&format!(
+            "{} {:5} [{}] {}\n",
+            format_clock_in(record.timestamp_ms, DisplayZone::Utc),
+            record.level.as_str(),
+            record.target,
+            record.message
+        ));
+    }
+    report
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+/// Write `report` to `dir`, pruning old reports, and mark it pending if
+/// asked to.
+fn write_report(
+    dir: &Path,
+    report: &str,
+    timestamp_ms: u64,
+    pending: bool,
+) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("crash-{}.txt", timestamp_ms));
+    std::fs::write(&path, report)?;
+    prune_reports(dir, MAX_REPORTS);
+    if pending {
+        std::fs::write(dir.join(PENDING_FILE), path.to_string_lossy().as_bytes())?;
+    }
+    Ok(path)
+}
+
+fn take_pending_in(dir: &Path) -> Option<PathBuf> {
+    let marker = dir.join(PENDING_FILE);
+    let contents = std::fs::read_to_string(&marker).ok()?;
+    let _ = std::fs::remove_file(&marker);
+    let path = PathBuf::from(contents.trim());
+    path.exists().then_some(path)
+}
+
+/// Remove all but the newest `keep` reports. Report names sort by time.
+fn prune_reports(dir: &Path, keep: usize) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut reports: Vec<(u64, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let ms = name
+                .to_str()?
+                .strip_prefix("crash-")?
+                .strip_suffix(".txt")?
+                .parse()
+                .ok()?;
+            Some((ms, entry.path()))
+        })
+        .collect();
+    reports.sort();
+    let excess = reports.len().saturating_sub(keep);
+    for (_, path) in reports.into_iter().take(excess) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_report() {
+        let records = logging::parse_log_lines(
+            r#"{"timestamp_ms":1000,"level":"warn","target":"App","message":"slow refresh"}"#,
+        );
+        let report = format_report(
+            0,
+            "main",
+            "panicked at src/app.rs:1:1:\nboom",
+            "   0: dora_studio::app\n",
+            &records,
+        );
+        assert!(report.starts_with("dora-studio crash report"));
+        assert!(report.contains(&format!("Version: {}", env!("CARGO_PKG_VERSION"))));
+        assert!(report.contains("Thread: main"));
+        assert!(report.contains("panicked at src/app.rs:1:1:\nboom"));
+        assert!(report.contains("Backtrace:\n   0: dora_studio::app\n"));
+        assert!(report.contains("Recent log (1 records):"));
+        assert!(report.contains("00:00:01.000 WARN  [App] slow refresh"));
+    }
+
+    #[test]
+    fn test_pending_report_is_taken_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_report(dir.path(), "report", 1, true).unwrap();
+        assert_eq!(take_pending_in(dir.path()), Some(path));
+        assert_eq!(take_pending_in(dir.path()), None);
+
+        // Worker thread panics are reported but not offered on next launch
+        write_report(dir.path(), "report", 2, false).unwrap();
+        assert_eq!(take_pending_in(dir.path()), None);
+    }
+
+    #[test]
+    fn test_prune_reports_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        for ms in [5, 30, 100] {
+            write_report(dir.path(), "report", ms, false).unwrap();
+        }
+        prune_reports(dir.path(), 2);
+        assert!(!dir.path().join("crash-5.txt").exists());
+        assert!(dir.path().join("crash-30.txt").exists());
+        assert!(dir.path().join("crash-100.txt").exists());
+    }
+}
@@ -0,0 +1,723 @@
+//! Import of Grafana dashboards.
+//!
+//! Time series (and the older graph), stat (and singlestat) and logs panels
+//! are imported; rows are flattened into the panels they hold. Prometheus
+//! targets are converted to [`MetricQuery`] and Loki targets to
+//! [`LogQuery`], for the query shapes dashboards mostly use:
+//!
+//! - `metric{label="value"}`
+//! - `rate(metric[5m])`, or `irate`
+//! - `sum by (label) (...)`, `avg`, `max`, `min` and `count`
+//! - `histogram_quantile(0.95, sum by (le) (rate(metric_bucket[5m])))`
+//! - `{service_name="camera"} |= "error"` for logs
+//!
+//! Anything else (arithmetic, other functions, regex matchers) cannot be
+//! expressed as a query here; such queries, and panels left without any,
+//! are reported in [`GrafanaImport::skipped`] rather than failing the
+//! import. Filters on dashboard variables (`$node`) are dropped.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::{Dashboard, DashboardPanel, PanelKind, PanelPosition, PanelQuery, StatReduce};
+use crate::otlp::types::{LogQuery, MetricQuery};
+use crate::units::Unit;
+
+/// Outcome of an import.
+#[derive(Debug, Clone)]
+pub struct GrafanaImport {
+    pub dashboard: Dashboard,
+    /// What was left out and why, one line each.
+    pub skipped: Vec<String>,
+}
+
+impl GrafanaImport {
+    /// e.g. `Imported 'Nodes': 4 panels, 2 skipped`.
+    pub fn describe(&self) -> String {
+        let panels = self.dashboard.panels.len();
+        let mut text = format!(
+            "Imported '{}': {} panel{}",
+            self.dashboard.title,
+            panels,
+            if panels == 1 { "" } else { "s" }
+        );
+        if !self.skipped.is_empty() {
+            text.push_str(&format!(", {} skipped\n", self.skipped.len()));
+            text.push_str(&self.skipped.join("\n"));
+        }
+        text
+    }
+}
+
+/// Read a dashboard exported from Grafana, either its JSON model or the
+/// API response wrapping it in `dashboard`.
+pub fn import(json: &str) -> Result<GrafanaImport, String> {
+    let root: Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid Grafana dashboard: {}", e))?;
+    let model = root.get("dashboard").unwrap_or(&root);
+    let Some(panels) = model.get("panels").and_then(Value::as_array) else {
+        return Err("Not a Grafana dashboard (no panels)".to_string());
+    };
+
+    let mut skipped = Vec::new();
+    let variables: Vec<&str> = model["templating"]["list"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v["name"].as_str())
+        .collect();
+    if !variables.is_empty() {
+        skipped.push(format!(
+            "Variables ${} are not supported; filters on them were dropped",
+            variables.join(", $")
+        ));
+    }
+
+    let mut imported = Vec::new();
+    for panel in flatten_rows(panels) {
+        match import_panel(panel, &mut skipped) {
+            Ok(panel) => imported.push(panel),
+            Err(reason) => skipped.push(format!("Panel '{}': {}", panel_title(panel), reason)),
+        }
+    }
+    imported.sort_by_key(|p| (p.position.y, p.position.x));
+
+    Ok(GrafanaImport {
+        dashboard: Dashboard {
+            title: model["title"]
+                .as_str()
+                .unwrap_or("Imported dashboard")
+                .to_string(),
+            time_span_ms: time_span_ms(&model["time"]),
+            panels: imported,
+        },
+        skipped,
+    })
+}
+
+/// Convert a PromQL expression to a metric query.
+pub fn metric_query(expr: &str) -> Result<MetricQuery, String> {
+    let mut parser = Parser::new(expr);
+    let parsed = parser.expr()?;
+    parser.end()?;
+
+    let metric = parsed.metric.ok_or("no metric name")?;
+    let aggregation = match (&parsed.quantile, &parsed.aggregation, parsed.rate) {
+        (Some(q), _, _) => Some(format!("hist_quantile_{}", q)),
+        (None, Some(agg), true) if agg == "count" => {
+            return Err("count of a rate is not supported".to_string())
+        }
+        (None, Some(agg), true) => Some(format!("{}_rate", agg)),
+        (None, Some(agg), false) => Some(agg.clone()),
+        (None, None, true) => Some("rate".to_string()),
+        (None, None, false) => None,
+    };
+    let (service_name, filters) = equality_filters(parsed.matchers)?;
+    Ok(MetricQuery {
+        metric_name: Some(metric),
+        service_name,
+        time_range: None,
+        step_seconds: parsed.window_ms.map(|ms| (ms / 1000).max(1)),
+        aggregation,
+        group_by: parsed.group_by,
+        filters,
+    })
+}
+
+/// Convert a LogQL expression, a stream selector and line filters, to a
+/// log query. `level` or `severity` labels select the severity.
+pub fn log_query(expr: &str) -> Result<LogQuery, String> {
+    let mut parser = Parser::new(expr);
+    if !parser.eat("{") {
+        return Err("expected a stream selector".to_string());
+    }
+    let matchers = parser.matchers()?;
+    let mut body_contains = None;
+    loop {
+        if parser.eat("|=") {
+            if body_contains.is_some() {
+                return Err("only one line filter is supported".to_string());
+            }
+            body_contains = Some(parser.string()?);
+        } else if parser.eat("|") {
+            // Parsers only shape labels for later stages
+            match parser.ident() {
+                Some("json" | "logfmt") => {}
+                Some(stage) => return Err(format!("'{}' stages are not supported", stage)),
+                None => return Err("unsupported pipeline stage".to_string()),
+            }
+        } else {
+            break;
+        }
+    }
+    parser.end()?;
+
+    let (service_name, mut attributes) = equality_filters(matchers)?;
+    let severity = ["level", "severity", "detected_level"]
+        .iter()
+        .find_map(|label| attributes.remove(*label));
+    Ok(LogQuery {
+        service_name,
+        severity,
+        body_contains,
+        attributes,
+        ..Default::default()
+    })
+}
+
+/// Panels in order, with the panels of collapsed rows in place of the row.
+fn flatten_rows(panels: &[Value]) -> Vec<&Value> {
+    let mut flat = Vec::new();
+    for panel in panels {
+        if panel["type"] == "row" {
+            flat.extend(panel["panels"].as_array().into_iter().flatten());
+        } else {
+            flat.push(panel);
+        }
+    }
+    flat
+}
+
+fn panel_title(panel: &Value) -> &str {
+    panel["title"]
+        .as_str()
+        .filter(|t| !t.is_empty())
+        .unwrap_or("untitled")
+}
+
+/// `panel` as a dashboard panel; queries that do not convert are added to
+/// `skipped`. Errs when the panel is not supported or has no query left.
+fn import_panel(panel: &Value, skipped: &mut Vec<String>) -> Result<DashboardPanel, String> {
+    let kind = match panel["type"].as_str().unwrap_or_default() {
+        "timeseries" | "graph" => PanelKind::TimeSeries,
+        "stat" | "singlestat" => PanelKind::Stat(stat_reduce(panel)),
+        "logs" => PanelKind::Logs,
+        other => return Err(format!("{} panels are not supported", other)),
+    };
+
+    let mut queries = Vec::new();
+    for (i, target) in panel["targets"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .enumerate()
+    {
+        if target["hide"].as_bool() == Some(true) {
+            continue;
+        }
+        let ref_id = target["refId"]
+            .as_str()
+            .map_or(i.to_string(), str::to_string);
+        let Some(expr) = target["expr"].as_str().filter(|e| !e.trim().is_empty()) else {
+            skipped.push(format!(
+                "Panel '{}', query {}: not a Prometheus or Loki query",
+                panel_title(panel),
+                ref_id
+            ));
+            continue;
+        };
+        let query = match kind {
+            PanelKind::Logs => log_query(expr).map(PanelQuery::Logs),
+            _ => metric_query(expr).map(|mut query| {
+                let interval = target["interval"].as_str().and_then(duration_ms);
+                if let Some(ms) = interval {
+                    query.step_seconds = Some((ms / 1000).max(1));
+                }
+                PanelQuery::Metric(query)
+            }),
+        };
+        match query {
+            Ok(query) => queries.push(query),
+            Err(e) => skipped.push(format!(
+                "Panel '{}', query {}: {}",
+                panel_title(panel),
+                ref_id,
+                e
+            )),
+        }
+    }
+    if queries.is_empty() {
+        return Err("no query could be imported".to_string());
+    }
+
+    let grid = &panel["gridPos"];
+    let cell = |key: &str, default: u32| grid[key].as_u64().map_or(default, |v| v as u32);
+    Ok(DashboardPanel {
+        title: panel_title(panel).to_string(),
+        kind,
+        unit: panel["fieldConfig"]["defaults"]["unit"]
+            .as_str()
+            .or(panel["format"].as_str())
+            .and_then(unit),
+        queries,
+        position: PanelPosition {
+            x: cell("x", 0),
+            y: cell("y", 0),
+            width: cell("w", 12),
+            height: cell("h", 8),
+        },
+    })
+}
+
+fn stat_reduce(panel: &Value) -> StatReduce {
+    let calc = panel["options"]["reduceOptions"]["calcs"][0]
+        .as_str()
+        .or(panel["valueName"].as_str());
+    match calc {
+        Some("mean" | "avg") => StatReduce::Mean,
+        Some("max") => StatReduce::Max,
+        Some("min") => StatReduce::Min,
+        Some("sum" | "total") => StatReduce::Sum,
+        _ => StatReduce::Last,
+    }
+}
+
+/// The unit for a Grafana unit id, if there is one.
+fn unit(id: &str) -> Option<Unit> {
+    match id {
+        "bytes" | "decbytes" => Some(Unit::Bytes),
+        "s" => Some(Unit::Seconds),
+        "ms" => Some(Unit::Milliseconds),
+        "percent" => Some(Unit::Percent),
+        "percentunit" => Some(Unit::Ratio),
+        "short" | "none" | "reqps" | "ops" | "cps" => Some(Unit::Count),
+        _ => None,
+    }
+}
+
+/// Span of a relative time picker setting, e.g. `now-6h` to `now`.
+fn time_span_ms(time: &Value) -> Option<u64> {
+    if time["to"] != "now" {
+        return None;
+    }
+    duration_ms(time["from"].as_str()?.strip_prefix("now-")?)
+}
+
+/// A Prometheus duration, e.g. `5m` or `1h30m`, in ms.
+fn duration_ms(text: &str) -> Option<u64> {
+    let mut total = 0;
+    let mut rest = text.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let value: u64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let unit_ms = match &rest[..unit_len] {
+            "ms" => 1,
+            "s" => 1000,
+            "m" => 60_000,
+            "h" => 3_600_000,
+            "d" => 86_400_000,
+            "w" => 7 * 86_400_000,
+            "y" => 365 * 86_400_000,
+            _ => return None,
+        };
+        total += value * unit_ms;
+        rest = &rest[unit_len..];
+    }
+    Some(total)
+}
+
+#[derive(Debug)]
+struct Matcher {
+    label: String,
+    op: &'static str,
+    value: String,
+}
+
+/// `service_name` and the other equality matchers as filters. Matchers on
+/// variables and match-anything regexes are dropped; other matchers cannot
+/// be expressed.
+fn equality_filters(
+    matchers: Vec<Matcher>,
+) -> Result<(Option<String>, HashMap<String, String>), String> {
+    let mut service_name = None;
+    let mut filters = HashMap::new();
+    for m in matchers {
+        if m.value.contains('$') || (m.op == "=~" && matches!(m.value.as_str(), ".*" | ".+")) {
+            continue;
+        }
+        if m.op != "=" {
+            return Err(format!(
+                "'{}{}\"{}\"' is not supported, only label=\"value\" filters",
+                m.label, m.op, m.value
+            ));
+        }
+        if m.label == "service_name" {
+            service_name = Some(m.value);
+        } else {
+            filters.insert(m.label, m.value);
+        }
+    }
+    Ok((service_name, filters))
+}
+
+/// What an expression selects, from its innermost selector out.
+#[derive(Debug, Default)]
+struct Parsed {
+    metric: Option<String>,
+    matchers: Vec<Matcher>,
+    /// Whether it has a range, e.g. `[5m]`
+    ranged: bool,
+    /// The range, unless a variable
+    window_ms: Option<u64>,
+    rate: bool,
+    aggregation: Option<String>,
+    group_by: Vec<String>,
+    /// Percentile of `histogram_quantile`
+    quantile: Option<u32>,
+}
+
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { rest: text }
+    }
+
+    fn skip_space(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    /// Consume `token` if it comes next.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_space();
+        match self.rest.strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(format!("expected '{}' at '{}'", token, self.rest))
+        }
+    }
+
+    fn end(&mut self) -> Result<(), String> {
+        self.skip_space();
+        if self.rest.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("'{}' is not supported", self.rest))
+        }
+    }
+
+    fn ident(&mut self) -> Option<&'a str> {
+        self.skip_space();
+        let len = self
+            .rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == ':'))
+            .unwrap_or(self.rest.len());
+        if len == 0 || self.rest.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+        let (ident, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some(ident)
+    }
+
+    /// A quoted string, without escapes other than `\"`.
+    fn string(&mut self) -> Result<String, String> {
+        self.skip_space();
+        let quote = self
+            .rest
+            .chars()
+            .next()
+            .filter(|c| matches!(c, '"' | '\'' | '`'))
+            .ok_or_else(|| format!("expected a string at '{}'", self.rest))?;
+        let mut value = String::new();
+        let mut chars = self.rest[1..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' if quote != '`' => value.extend(chars.next().map(|(_, c)| c)),
+                c if c == quote => {
+                    self.rest = &self.rest[1 + i + 1..];
+                    return Ok(value);
+                }
+                c => value.push(c),
+            }
+        }
+        Err("unterminated string".to_string())
+    }
+
+    fn expr(&mut self) -> Result<Parsed, String> {
+        self.skip_space();
+        if self.rest.starts_with('{') {
+            return self.selector(None);
+        }
+        let name = self
+            .ident()
+            .ok_or_else(|| format!("unexpected '{}'", self.rest))?;
+        match name {
+            "sum" | "avg" | "max" | "min" | "count" => self.aggregation(name),
+            "rate" | "irate" => {
+                self.expect("(")?;
+                let mut inner = self.expr()?;
+                self.expect(")")?;
+                if !inner.ranged || inner.rate || inner.aggregation.is_some() {
+                    return Err(format!("{} needs a range selector, e.g. [5m]", name));
+                }
+                inner.rate = true;
+                Ok(inner)
+            }
+            "histogram_quantile" => {
+                self.expect("(")?;
+                self.skip_space();
+                let len = self
+                    .rest
+                    .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                    .unwrap_or(self.rest.len());
+                let q: f64 = self.rest[..len]
+                    .parse()
+                    .map_err(|_| "expected a quantile, e.g. 0.95".to_string())?;
+                self.rest = &self.rest[len..];
+                self.expect(",")?;
+                let mut inner = self.expr()?;
+                self.expect(")")?;
+                if !(0.0..=1.0).contains(&q) || inner.quantile.is_some() {
+                    return Err("unsupported histogram_quantile".to_string());
+                }
+                inner.quantile = Some((q * 100.0).round() as u32);
+                inner.aggregation = None;
+                inner.rate = false;
+                inner.group_by.retain(|label| label != "le");
+                Ok(inner)
+            }
+            _ if self.rest.trim_start().starts_with('(') => {
+                Err(format!("{}() is not supported", name))
+            }
+            _ => self.selector(Some(name)),
+        }
+    }
+
+    /// `agg [by (labels)] (expr) [by (labels)]`, after `agg`.
+    fn aggregation(&mut self, name: &str) -> Result<Parsed, String> {
+        let mut group_by = self.grouping()?;
+        self.expect("(")?;
+        let mut inner = self.expr()?;
+        self.expect(")")?;
+        if group_by.is_empty() {
+            group_by = self.grouping()?;
+        }
+        if inner.aggregation.is_some() || inner.quantile.is_some() {
+            return Err("nested aggregations are not supported".to_string());
+        }
+        inner.aggregation = Some(name.to_string());
+        inner.group_by = group_by;
+        Ok(inner)
+    }
+
+    /// `by (labels)`, if next.
+    fn grouping(&mut self) -> Result<Vec<String>, String> {
+        if self.eat("without") {
+            return Err("'without' grouping is not supported".to_string());
+        }
+        if !self.eat("by") {
+            return Ok(Vec::new());
+        }
+        self.expect("(")?;
+        let mut labels = Vec::new();
+        while !self.eat(")") {
+            labels.push(self.ident().ok_or("expected a label")?.to_string());
+            if !self.eat(",") {
+                self.expect(")")?;
+                break;
+            }
+        }
+        Ok(labels)
+    }
+
+    /// `[metric][{matchers}][[window]]`, after the metric name if any.
+    fn selector(&mut self, metric: Option<&str>) -> Result<Parsed, String> {
+        let mut parsed = Parsed {
+            metric: metric.map(str::to_string),
+            ..Default::default()
+        };
+        if self.eat("{") {
+            for m in self.matchers()? {
+                if m.label == "__name__" && m.op == "=" {
+                    parsed.metric = Some(m.value);
+                } else {
+                    parsed.matchers.push(m);
+                }
+            }
+        }
+        if self.eat("[") {
+            let end = self.rest.find(']').ok_or("unterminated range")?;
+            let window = self.rest[..end].trim();
+            // None for Grafana's $__rate_interval and the like
+            parsed.window_ms = duration_ms(window);
+            parsed.ranged = true;
+            self.rest = &self.rest[end + 1..];
+        }
+        Ok(parsed)
+    }
+
+    /// `label op "value", ...}`, after the `{`.
+    fn matchers(&mut self) -> Result<Vec<Matcher>, String> {
+        let mut matchers = Vec::new();
+        while !self.eat("}") {
+            let label = self.ident().ok_or("expected a label")?.to_string();
+            let op = ["=~", "!~", "!=", "="]
+                .into_iter()
+                .find(|op| self.eat(op))
+                .ok_or_else(|| format!("expected a matcher after '{}'", label))?;
+            let value = self.string()?;
+            matchers.push(Matcher { label, op, value });
+            if !self.eat(",") {
+                self.expect("}")?;
+                break;
+            }
+        }
+        Ok(matchers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metric_query() {
+        let q = metric_query(
+            r#"sum by (node_id) (rate(dora_messages_total{service_name="camera", output="image"}[5m]))"#,
+        )
+        .unwrap();
+        assert_eq!(q.metric_name.as_deref(), Some("dora_messages_total"));
+        assert_eq!(q.service_name.as_deref(), Some("camera"));
+        assert_eq!(q.filters["output"], "image");
+        assert_eq!(q.aggregation.as_deref(), Some("sum_rate"));
+        assert_eq!(q.group_by, ["node_id"]);
+        assert_eq!(q.step_seconds, Some(300));
+
+        let q = metric_query("avg(process_cpu_percent) by (node)").unwrap();
+        assert_eq!(q.aggregation.as_deref(), Some("avg"));
+        assert_eq!(q.group_by, ["node"]);
+        assert_eq!(q.step_seconds, None);
+
+        let q = metric_query(
+            "histogram_quantile(0.95, sum by (le, node) (rate(latency_bucket{node=~\"$node\"}[$__rate_interval])))",
+        )
+        .unwrap();
+        assert_eq!(q.aggregation.as_deref(), Some("hist_quantile_95"));
+        assert_eq!(q.group_by, ["node"]);
+        assert!(q.filters.is_empty());
+
+        let q = metric_query(r#"{__name__="up", job='dora'}"#).unwrap();
+        assert_eq!(q.metric_name.as_deref(), Some("up"));
+        assert_eq!(q.aggregation, None);
+        assert_eq!(q.filters["job"], "dora");
+
+        for unsupported in [
+            "rate(a[5m]) * 100",
+            "sum(rate(a[5m])) / sum(rate(b[5m]))",
+            "topk(5, a)",
+            r#"a{node!="camera"}"#,
+            "sum without (node) (a)",
+            "rate(a)",
+            "count(rate(a[1m]))",
+        ] {
+            assert!(metric_query(unsupported).is_err(), "{}", unsupported);
+        }
+    }
+
+    #[test]
+    fn test_log_query() {
+        let q = log_query(r#"{service_name="camera", level="error"} | json |= "timeout""#).unwrap();
+        assert_eq!(q.service_name.as_deref(), Some("camera"));
+        assert_eq!(q.severity.as_deref(), Some("error"));
+        assert_eq!(q.body_contains.as_deref(), Some("timeout"));
+        assert!(q.attributes.is_empty());
+
+        assert!(log_query(r#"{node="a"} |~ "err.*""#).is_err());
+        assert!(log_query("rate({node=\"a\"}[1m])").is_err());
+    }
+
+    #[test]
+    fn test_import_dashboard() {
+        let json = r#"{
+            "meta": {"slug": "dora"},
+            "dashboard": {
+                "title": "Dora nodes",
+                "time": {"from": "now-6h", "to": "now"},
+                "templating": {"list": [{"name": "node"}]},
+                "panels": [
+                    {"type": "stat", "title": "Frames", "gridPos": {"x": 12, "y": 0, "w": 12, "h": 4},
+                     "options": {"reduceOptions": {"calcs": ["max"]}},
+                     "targets": [{"refId": "A", "expr": "sum(frames_total)"}]},
+                    {"type": "timeseries", "title": "CPU", "gridPos": {"x": 0, "y": 0, "w": 12, "h": 8},
+                     "fieldConfig": {"defaults": {"unit": "percent"}},
+                     "targets": [
+                        {"refId": "A", "expr": "avg by (node) (cpu{node=~\"$node\"})", "interval": "30s"},
+                        {"refId": "B", "expr": "cpu / 2"},
+                        {"refId": "C", "expr": "cpu", "hide": true}
+                     ]},
+                    {"type": "row", "title": "Logs", "collapsed": true, "panels": [
+                        {"type": "logs", "title": "Errors", "gridPos": {"x": 0, "y": 9, "w": 24, "h": 8},
+                         "targets": [{"refId": "A", "expr": "{service_name=\"camera\"} |= \"error\""}]}
+                    ]},
+                    {"type": "piechart", "title": "Share", "targets": []},
+                    {"type": "stat", "title": "SQL", "targets": [{"refId": "A", "rawSql": "select 1"}]}
+                ]
+            }
+        }"#;
+        let import = import(json).unwrap();
+        let dashboard = &import.dashboard;
+        assert_eq!(dashboard.title, "Dora nodes");
+        assert_eq!(dashboard.time_span_ms, Some(6 * 3_600_000));
+
+        let titles: Vec<&str> = dashboard.panels.iter().map(|p| p.title.as_str()).collect();
+        assert_eq!(titles, ["CPU", "Frames", "Errors"]);
+        let cpu = &dashboard.panels[0];
+        assert_eq!(cpu.kind, PanelKind::TimeSeries);
+        assert_eq!(cpu.unit, Some(Unit::Percent));
+        assert_eq!(cpu.queries.len(), 1);
+        let PanelQuery::Metric(query) = &cpu.queries[0] else {
+            panic!("expected a metric query");
+        };
+        assert_eq!(query.step_seconds, Some(30));
+        assert_eq!(dashboard.panels[1].kind, PanelKind::Stat(StatReduce::Max));
+        assert_eq!(dashboard.panels[2].kind, PanelKind::Logs);
+        assert_eq!(dashboard.panels[2].position.width, 24);
+
+        assert_eq!(
+            import.skipped,
+            [
+                "Variables $node are not supported; filters on them were dropped",
+                "Panel 'CPU', query B: '/ 2' is not supported",
+                "Panel 'Share': piechart panels are not supported",
+                "Panel 'SQL', query A: not a Prometheus or Loki query",
+                "Panel 'SQL': no query could be imported",
+            ]
+        );
+        assert!(import
+            .describe()
+            .starts_with("Imported 'Dora nodes': 3 panels, 5 skipped\n"));
+
+        assert!(super::import(r#"{"title": "x"}"#).is_err());
+    }
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(duration_ms("5m"), Some(300_000));
+        assert_eq!(duration_ms("1h30m"), Some(5_400_000));
+        assert_eq!(duration_ms("250ms"), Some(250));
+        assert_eq!(duration_ms("$__interval"), None);
+        assert_eq!(duration_ms(""), None);
+    }
+}
@@ -0,0 +1,153 @@
+//! Dashboards: panels of metric charts, stats and logs, each with the
+//! queries it shows, laid out on a grid.
+//!
+//! Dashboards are saved as `dashboard` artifacts (see [`crate::snapshot`])
+//! under `dashboards` in the data directory, one file each. They can be
+//! imported from Grafana ([`grafana`]).
+
+pub mod grafana;
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::otlp::types::{LogQuery, MetricQuery};
+use crate::settings;
+use crate::snapshot::Artifact;
+use crate::units::Unit;
+
+/// Columns of the dashboard grid, as in Grafana.
+pub const GRID_COLUMNS: u32 = 24;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Dashboard {
+    pub title: String,
+    /// Time shown, back from now, e.g. 6 hours; `None` for the app's
+    /// current range.
+    #[serde(default)]
+    pub time_span_ms: Option<u64>,
+    /// Top to bottom, then left to right.
+    pub panels: Vec<DashboardPanel>,
+}
+
+impl Artifact for Dashboard {
+    const KIND: &'static str = "dashboard";
+    const VERSION: u32 = 1;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardPanel {
+    pub title: String,
+    pub kind: PanelKind,
+    /// Unit of the values; inferred from the metric name when unset.
+    #[serde(default)]
+    pub unit: Option<Unit>,
+    pub queries: Vec<PanelQuery>,
+    pub position: PanelPosition,
+}
+
+/// The widget a panel is shown with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelKind {
+    /// A time-series chart of every series of its queries
+    TimeSeries,
+    /// A single number reduced from the first series
+    Stat(StatReduce),
+    /// Matching log lines
+    Logs,
+}
+
+/// How a stat panel reduces a series to one number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatReduce {
+    #[default]
+    Last,
+    Mean,
+    Max,
+    Min,
+    Sum,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelQuery {
+    Metric(MetricQuery),
+    Logs(LogQuery),
+}
+
+/// Cell of a panel in the grid, in columns of [`GRID_COLUMNS`] across and
+/// rows down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PanelPosition {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Directory dashboards are saved in.
+pub fn dashboards_dir() -> Option<PathBuf> {
+    settings::data_dir().map(|d| d.join("dashboards"))
+}
+
+/// File name for a dashboard titled `title`, e.g. `node-latency.json`.
+pub fn file_name(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.trim().chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "dashboard.json".to_string()
+    } else {
+        format!("{}.json", slug)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::Envelope;
+
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("Node latency"), "node-latency.json");
+        assert_eq!(file_name("  CPU / Memory (prod) "), "cpu-memory-prod.json");
+        assert_eq!(file_name("???"), "dashboard.json");
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let dashboard = Dashboard {
+            title: "Camera".to_string(),
+            time_span_ms: Some(3_600_000),
+            panels: vec![DashboardPanel {
+                title: "Frames".to_string(),
+                kind: PanelKind::Stat(StatReduce::Max),
+                unit: Some(Unit::Count),
+                queries: vec![PanelQuery::Metric(MetricQuery {
+                    metric_name: Some("frames_total".to_string()),
+                    ..Default::default()
+                })],
+                position: PanelPosition {
+                    x: 0,
+                    y: 0,
+                    width: 12,
+                    height: 8,
+                },
+            }],
+        };
+        let json = Envelope::new(dashboard, 0).to_json().unwrap();
+        let parsed = Envelope::<Dashboard>::from_json(&json).unwrap().payload;
+        assert_eq!(parsed.panels[0].kind, PanelKind::Stat(StatReduce::Max));
+        assert!(matches!(
+            &parsed.panels[0].queries[0],
+            PanelQuery::Metric(q) if q.metric_name.as_deref() == Some("frames_total")
+        ));
+    }
+}
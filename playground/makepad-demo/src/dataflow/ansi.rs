@@ -0,0 +1,204 @@
+//! ANSI escape codes in node output, e.g. `\x1b[31merror\x1b[0m` from a
+//! coloured logger. Colour codes (SGR) are read into spans of text with the
+//! colour they set; every other escape sequence, such as cursor moves or
+//! window titles, is dropped.
+
+/// Colours of the 16 basic codes (30–37, then 90–97), darkened to read on
+/// the light log background; "white" is a grey for the same reason.
+const BASIC_COLORS: [u32; 16] = [
+    0x1f2937, 0xdc2626, 0x16a34a, 0xca8a04, 0x2563eb, 0xc026d3, 0x0891b2, 0x94a3b8, //
+    0x4b5563, 0xef4444, 0x22c55e, 0xeab308, 0x3b82f6, 0xd946ef, 0x06b6d4, 0xcbd5e1,
+];
+
+/// Text and the foreground colour set for it, `None` for the default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnsiSpan {
+    pub text: String,
+    pub color: Option<u32>,
+}
+
+/// Split `line` into spans of one colour each, without the escape codes.
+pub fn parse(line: &str) -> Vec<AnsiSpan> {
+    let mut spans: Vec<AnsiSpan> = Vec::new();
+    let mut color = None;
+    let mut text = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            // Control sequence: parameters, then a final byte in @–~
+            Some('[') => {
+                let mut params = String::new();
+                let mut last = None;
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        last = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+                if last == Some('m') {
+                    let next = apply_sgr(color, &params);
+                    if next != color && !text.is_empty() {
+                        spans.push(AnsiSpan {
+                            text: std::mem::take(&mut text),
+                            color,
+                        });
+                    }
+                    color = next;
+                }
+            }
+            // Operating system command, ended by BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Two-character sequences, e.g. ESC 7
+            _ => {}
+        }
+    }
+    if !text.is_empty() {
+        spans.push(AnsiSpan { text, color });
+    }
+    spans
+}
+
+/// `line` without its escape codes.
+pub fn strip(line: &str) -> String {
+    if !line.contains('\x1b') {
+        return line.to_string();
+    }
+    parse(line).into_iter().map(|s| s.text).collect()
+}
+
+/// Colour set on most of the visible text of `spans`, if any: a line is
+/// drawn in one colour, so a coloured level tag alone does not colour the
+/// whole line.
+pub fn main_color(spans: &[AnsiSpan]) -> Option<u32> {
+    let visible = |s: &AnsiSpan| s.text.chars().filter(|c| !c.is_whitespace()).count();
+    let total: usize = spans.iter().map(visible).sum();
+    let mut colors: Vec<(u32, usize)> = Vec::new();
+    for span in spans {
+        let Some(color) = span.color else {
+            continue;
+        };
+        match colors.iter_mut().find(|(c, _)| *c == color) {
+            Some((_, count)) => *count += visible(span),
+            None => colors.push((color, visible(span))),
+        }
+    }
+    colors
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .filter(|(_, count)| *count * 2 > total)
+        .map(|(color, _)| color)
+}
+
+/// Foreground colour after the SGR parameters `params`, e.g. `1;31`.
+fn apply_sgr(mut color: Option<u32>, params: &str) -> Option<u32> {
+    let codes: Vec<u32> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+    let mut codes = codes.iter().copied();
+    while let Some(code) = codes.next() {
+        match code {
+            0 | 39 => color = None,
+            30..=37 => color = Some(BASIC_COLORS[(code - 30) as usize]),
+            90..=97 => color = Some(BASIC_COLORS[(code - 90 + 8) as usize]),
+            38 => match codes.next() {
+                Some(5) => color = codes.next().map(palette_color).or(color),
+                Some(2) => {
+                    let mut channel = || codes.next().unwrap_or(0).min(255);
+                    color = Some((channel() << 16) | (channel() << 8) | channel());
+                }
+                _ => {}
+            },
+            // Background colours, skipped with their arguments
+            48 => match codes.next() {
+                Some(5) => {
+                    codes.next();
+                }
+                Some(2) => {
+                    codes.nth(2);
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    color
+}
+
+/// Colour `index` of the 256-colour palette.
+fn palette_color(index: u32) -> u32 {
+    match index {
+        0..=15 => BASIC_COLORS[index as usize],
+        16..=231 => {
+            let level = |v: u32| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = index - 16;
+            (level(i / 36) << 16) | (level(i / 6 % 6) << 8) | level(i % 6)
+        }
+        _ => {
+            let grey = 8 + (index.min(255) - 232) * 10;
+            (grey << 16) | (grey << 8) | grey
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_colour_codes() {
+        let spans = parse("\x1b[32mINFO\x1b[0m camera: \x1b[1;31mno device\x1b[m");
+        assert_eq!(
+            spans,
+            [
+                AnsiSpan {
+                    text: "INFO".to_string(),
+                    color: Some(0x16a34a)
+                },
+                AnsiSpan {
+                    text: " camera: ".to_string(),
+                    color: None
+                },
+                AnsiSpan {
+                    text: "no device".to_string(),
+                    color: Some(0xdc2626)
+                },
+            ]
+        );
+        // No colour covers most of the line
+        assert_eq!(main_color(&spans), None);
+        assert_eq!(
+            main_color(&parse("\x1b[31mthread 'main' panicked\x1b[0m at 12")),
+            Some(0xdc2626)
+        );
+        assert_eq!(
+            parse("\x1b[38;5;196mx\x1b[38;2;1;2;3my")
+                .iter()
+                .map(|s| s.color)
+                .collect::<Vec<_>>(),
+            [Some(0xff0000), Some(0x010203)]
+        );
+    }
+
+    #[test]
+    fn test_strip_other_sequences() {
+        assert_eq!(strip("\x1b[2K\x1b[1Gframe 12"), "frame 12");
+        assert_eq!(strip("\x1b]0;title\x07done"), "done");
+        assert_eq!(strip("\x1b]8;;http://x\x1b\\link\x1b]8;;\x1b\\"), "link");
+        assert_eq!(strip("\x1b[48;5;1mplain"), "plain");
+        assert!(parse("\x1b[48;2;9;9;9mplain")[0].color.is_none());
+        assert_eq!(strip("no codes"), "no codes");
+    }
+}
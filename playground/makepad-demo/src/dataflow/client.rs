@@ -0,0 +1,133 @@
+//! Access to the dora coordinator behind a trait, so the app can talk to it
+//! through the `dora` CLI or through a mock in tests.
+//!
+//! [`CliClient`] runs the same `dora` commands as the chat tools. The CLI
+//! reaches the coordinator set by `coordinator_addr` (see
+//! [`crate::tools::set_coordinator_addr`]) and speaks its control protocol,
+//! so the studio does not have to track that protocol across dora releases.
+//!
+//! Building, `dora check` and `dora up` run locally rather than through the
+//! coordinator.
+
+use crate::dataflow::DataflowInfo;
+use crate::tools::payload::CheckDiagnostic;
+use crate::tools::{execute_tool, run_dora, tool_timeout, Limits, ToolPayload, ToolResult};
+
+/// Most of a dataflow's log fetched at once, from its end. dora returns the
+/// whole log each time, which grows for as long as the dataflow runs.
+pub const LOG_TAIL_BYTES: usize = 4 * 1024 * 1024;
+use serde_json::{json, Value};
+
+/// Operations on dataflows managed by a coordinator.
+pub trait DoraClient: Send + Sync {
+    fn list_dataflows(&self) -> Result<Vec<DataflowInfo>, String>;
+    /// Problems `dora check` finds in the dataflow at `dataflow_path`, empty
+    /// when it passes; `Err` when the check could not run.
+    fn check(&self, dataflow_path: &str) -> Result<Vec<CheckDiagnostic>, String>;
+    /// Start the dataflow at `dataflow_path`, returning its UUID when the
+    /// coordinator reports one.
+    fn start(&self, dataflow_path: &str) -> Result<Option<String>, String>;
+    fn stop(&self, uuid: &str) -> Result<(), String>;
+    fn destroy(&self, uuid: &str) -> Result<(), String>;
+    /// Logs of a dataflow, or of one of its nodes: the last
+    /// [`LOG_TAIL_BYTES`] of them.
+    fn logs(&self, uuid: &str, node: Option<&str>) -> Result<String, String>;
+    /// Node IDs of a dataflow.
+    fn list_nodes(&self, uuid: &str) -> Result<Vec<String>, String>;
+}
+
+/// Runs `dora` commands through [`crate::tools`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CliClient;
+
+impl CliClient {
+    fn call(&self, tool: &str, args: Value) -> Result<ToolResult, String> {
+        let result = execute_tool(tool, tool, &args);
+        if result.is_error {
+            Err(result.content)
+        } else {
+            Ok(result)
+        }
+    }
+
+    fn run(&self, tool: &str, args: Value) -> Result<String, String> {
+        self.call(tool, args).map(|result| result.content)
+    }
+}
+
+impl DoraClient for CliClient {
+    fn list_dataflows(&self) -> Result<Vec<DataflowInfo>, String> {
+        match self.call("dora_list", json!({}))?.payload {
+            Some(ToolPayload::Dataflows(dataflows)) => Ok(dataflows),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn check(&self, dataflow_path: &str) -> Result<Vec<CheckDiagnostic>, String> {
+        let args = json!({ "dataflow_path": dataflow_path });
+        // A failed check still carries its diagnostics, so skip `call`
+        let result = execute_tool("dora_check", "dora_check", &args);
+        match result.payload {
+            Some(ToolPayload::Check(problems)) => Ok(problems),
+            _ => Err(result.content),
+        }
+    }
+
+    fn start(&self, dataflow_path: &str) -> Result<Option<String>, String> {
+        match self
+            .call("dora_start", json!({ "dataflow_path": dataflow_path }))?
+            .payload
+        {
+            Some(ToolPayload::Started(uuid)) => Ok(uuid),
+            _ => Ok(None),
+        }
+    }
+
+    fn stop(&self, uuid: &str) -> Result<(), String> {
+        self.run("dora_stop", json!({ "dataflow_id": uuid }))
+            .map(|_| ())
+    }
+
+    fn destroy(&self, uuid: &str) -> Result<(), String> {
+        self.run("dora_destroy", json!({ "dataflow_id": uuid }))
+            .map(|_| ())
+    }
+
+    fn logs(&self, uuid: &str, node: Option<&str>) -> Result<String, String> {
+        let mut args = vec!["logs", uuid];
+        if let Some(node) = node {
+            args.extend(["--node", node]);
+        }
+        let limits =
+            Limits::register("dora_logs", tool_timeout("dora_logs")).with_tail(LOG_TAIL_BYTES);
+        Ok(run_dora(&args, &limits)?)
+    }
+
+    fn list_nodes(&self, uuid: &str) -> Result<Vec<String>, String> {
+        let limits = Limits::register("dora node list", tool_timeout("dora_list"));
+        let output = run_dora(&["node", "list", "--dataflow", uuid], &limits)?;
+        Ok(parse_node_table(&output))
+    }
+}
+
+/// Node IDs from `dora node list` output: the first column of each row,
+/// skipping the header.
+pub fn parse_node_table(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|id| !matches!(*id, "NODE" | "ID" | "NAME"))
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_node_table() {
+        let output = "NODE       STATUS\ncamera     Running\n\ndetector   Running\n";
+        assert_eq!(parse_node_table(output), vec!["camera", "detector"]);
+    }
+}
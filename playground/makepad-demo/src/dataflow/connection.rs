@@ -0,0 +1,167 @@
+//! Coordinator connection state.
+//!
+//! The studio talks to the coordinator only through `dora list`, so
+//! reachability is inferred from that command's errors. While offline, the
+//! dataflow controls are paused; everything that works from local files
+//! (YAML paths, run history, stored metrics, diagnostics, chat) stays
+//! available.
+//!
+//! While `dora list` keeps failing, for whatever reason, it is retried with
+//! exponential backoff ([`RefreshBackoff`]) instead of on every refresh tick.
+
+use std::time::Duration;
+
+/// Delay before the first automatic retry after `dora list` fails.
+pub const RETRY_BASE: Duration = Duration::from_secs(5);
+/// Longest delay between automatic retries.
+pub const RETRY_MAX: Duration = Duration::from_secs(120);
+
+/// Error fragments (lowercase) that mean no coordinator could be reached,
+/// including the dora CLI itself being unavailable.
+const UNREACHABLE_PATTERNS: [&str; 6] = [
+    "could not connect",
+    "failed to connect",
+    "connection refused",
+    "connect to coordinator",
+    "timed out",
+    "failed to execute dora",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoordinatorState {
+    /// No `dora list` has completed yet.
+    #[default]
+    Unknown,
+    Online,
+    Offline,
+}
+
+impl CoordinatorState {
+    pub fn is_offline(&self) -> bool {
+        *self == CoordinatorState::Offline
+    }
+}
+
+/// Whether a `dora list` error means the coordinator is unreachable, as
+/// opposed to a failure of the command itself.
+pub fn is_unreachable(error: &str) -> bool {
+    let error = error.to_lowercase();
+    UNREACHABLE_PATTERNS.iter().any(|p| error.contains(p))
+}
+
+/// The line of a dora CLI error worth showing, e.g. `failed to connect to
+/// dora coordinator` out of the full exit code/stdout/stderr dump.
+pub fn error_summary(error: &str) -> &str {
+    let lines = || error.lines().map(str::trim).filter(|l| !l.is_empty());
+    lines()
+        .find_map(|line| {
+            let start = line.find("Error:").or_else(|| line.find("error:"))?;
+            Some(line[start + "error:".len()..].trim())
+        })
+        .filter(|l| !l.is_empty())
+        .or_else(|| lines().next())
+        .unwrap_or(error)
+}
+
+/// Consecutive `dora list` failures and the latest error.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RefreshBackoff {
+    failures: u32,
+    last_error: Option<String>,
+}
+
+impl RefreshBackoff {
+    /// Record a failure; returns the delay before the next automatic retry.
+    pub fn fail(&mut self, error: &str) -> Duration {
+        self.failures = self.failures.saturating_add(1);
+        self.last_error = Some(error.to_string());
+        retry_delay(self.failures)
+    }
+
+    /// Record a success; returns whether refreshes were failing before.
+    pub fn succeed(&mut self) -> bool {
+        let was_failing = self.is_failing();
+        *self = Self::default();
+        was_failing
+    }
+
+    pub fn is_failing(&self) -> bool {
+        self.failures > 0
+    }
+
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+}
+
+/// Delay after `failures` consecutive failures: [`RETRY_BASE`] doubling per
+/// failure, capped at [`RETRY_MAX`].
+pub fn retry_delay(failures: u32) -> Duration {
+    let doublings = failures.saturating_sub(1).min(16);
+    (RETRY_BASE * 2u32.pow(doublings)).min(RETRY_MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_unreachable() {
+        assert!(is_unreachable(
+            "dora failed with exit code Some(1)\nstdout: \nstderr: Error: failed to connect to dora coordinator\n\nCaused by:\n    Connection refused (os error 111)"
+        ));
+        assert!(is_unreachable(
+            "Failed to execute dora: No such file or directory (os error 2)"
+        ));
+        assert!(!is_unreachable(
+            "dora failed with exit code Some(2)\nstderr: error: unexpected argument '--format'"
+        ));
+    }
+
+    #[test]
+    fn test_error_summary() {
+        assert_eq!(
+            error_summary(
+                "dora failed with exit code Some(1)\nstdout: \nstderr: Error: failed to connect to dora coordinator\n\nCaused by:\n    Connection refused (os error 111)"
+            ),
+            "failed to connect to dora coordinator"
+        );
+        assert_eq!(
+            error_summary("\nFailed to execute dora: No such file or directory (os error 2)"),
+            "Failed to execute dora: No such file or directory (os error 2)"
+        );
+        assert_eq!(error_summary(""), "");
+    }
+
+    #[test]
+    fn test_default_state() {
+        assert_eq!(CoordinatorState::default(), CoordinatorState::Unknown);
+        assert!(!CoordinatorState::Unknown.is_offline());
+        assert!(CoordinatorState::Offline.is_offline());
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_max() {
+        assert_eq!(retry_delay(1), Duration::from_secs(5));
+        assert_eq!(retry_delay(2), Duration::from_secs(10));
+        assert_eq!(retry_delay(4), Duration::from_secs(40));
+        assert_eq!(retry_delay(6), RETRY_MAX);
+        assert_eq!(retry_delay(u32::MAX), RETRY_MAX);
+    }
+
+    #[test]
+    fn test_backoff_resets_on_success() {
+        let mut backoff = RefreshBackoff::default();
+        assert!(!backoff.succeed());
+        backoff.fail("connection refused");
+        assert_eq!(backoff.fail("timed out"), Duration::from_secs(10));
+        assert_eq!(backoff.last_error(), Some("timed out"));
+        assert!(backoff.succeed());
+        assert!(!backoff.is_failing());
+        assert_eq!(backoff.last_error(), None);
+    }
+}
@@ -0,0 +1,1065 @@
+//! Side panel with the details of the dataflow selected in the table:
+//! its metadata, nodes and recent logs, with quick actions.
+//!
+//! The panel only displays what it is given; the app fetches nodes and logs
+//! through its [`super::DoraClient`] when the selection changes or the
+//! Refresh button is clicked. Log lines are attributed to their node and
+//! level (see [`super::node_logs`]), coloured by node and can be narrowed to
+//! one node and a minimum level; lines matching a highlight rule (see
+//! [`super::highlight`]) get its colour as background. Exports hold the
+//! lines the filter lets through. The search bar (see [`super::log_search`])
+//! steps through the lines shown that match, scrolling to each. Lines
+//! coloured with ANSI codes (see [`super::ansi`]) are drawn in their colour
+//! unless "Plain" is ticked.
+//!
+//! Each fetch only adds the lines logged since the last one to a bounded
+//! [`LogBuffer`]; the list draws just the rows in view, so a busy dataflow
+//! can be followed for long. Lines pushed out of the buffer are left for the
+//! app to spill to local storage ([`DataflowDetailRef::take_spilled_logs`]).
+
+use makepad_widgets::*;
+
+use super::golden_signals::{GoldenSignals, Signal, SignalLevel};
+use super::highlight::{self, HighlightRule, Highlighter, HIGHLIGHT_COLORS};
+use super::log_buffer::{LogAnchor, LogBuffer};
+use super::log_search::{self, LogSearch};
+use super::node_logs::{self, LogFilter, LogLevel, NodeLogLine};
+use super::DataflowInfo;
+use crate::datetime;
+use crate::i18n;
+use crate::text;
+use crate::units::{self, Unit};
+
+/// Colour of lines not attributed to a node.
+const LOG_TEXT_COLOR: u32 = 0x1f2937;
+/// Opacity of a highlight rule's colour behind the lines it matches.
+const HIGHLIGHT_ALPHA: f32 = 0.18;
+/// Background of search matches, stronger for the current one.
+const SEARCH_MATCH_COLOR: u32 = 0xfacc15;
+const SEARCH_MATCH_ALPHA: f32 = 0.25;
+const SEARCH_CURRENT_ALPHA: f32 = 0.6;
+/// Lines left above a match scrolled to, for context.
+const SEARCH_CONTEXT_LINES: usize = 2;
+/// Golden signals by level (see [`SignalLevel`]), and without data.
+const SIGNAL_NORMAL_COLOR: u32 = 0x22c55e;
+const SIGNAL_WARNING_COLOR: u32 = 0xd97706;
+const SIGNAL_CRITICAL_COLOR: u32 = 0xef4444;
+const SIGNAL_NO_DATA_COLOR: u32 = 0x64748b;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+    BORDER_COLOR = #e2e8f0
+
+    DetailHeading = <Label> {
+        width: Fill, height: Fit
+        margin: { top: 4 }
+        draw_text: {
+            color: (TEXT_SECONDARY),
+            text_style: { font_size: 10.0 }
+        }
+    }
+
+    LogRow = <View> {
+        width: Fill, height: Fit
+        show_bg: true
+        draw_bg: { color: #0000 }
+        log_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                text_style: <THEME_FONT_CODE> { font_size: 10.0 }
+                color: #1f2937
+                wrap: Line
+            }
+        }
+    }
+
+    SignalRow = <View> {
+        width: Fill, height: Fit
+        flow: Right
+        align: { y: 0.5 }
+        spacing: 6
+        name_label = <Label> {
+            width: 70, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 10.0 }
+            }
+        }
+        value_label = <Label> {
+            width: 70, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        trend_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: <THEME_FONT_CODE> { font_size: 11.0 }
+            }
+        }
+    }
+
+    DetailButton = <Button> {
+        width: Fit, height: 24
+        draw_text: { text_style: { font_size: 10.0 } }
+        padding: { left: 8, right: 8 }
+    }
+
+    DetailInput = <TextInput> {
+        width: Fill, height: 24
+        draw_text: { text_style: { font_size: 10.0 } }
+    }
+
+    pub DataflowDetail = {{DataflowDetail}} {
+        width: 320, height: Fill
+        visible: false
+        flow: Down
+        show_bg: true
+        draw_bg: { color: #ffffff }
+        padding: { left: 12, right: 12, top: 8, bottom: 8 }
+        spacing: 6
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { y: 0.5 }
+
+            title_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 13.0 }
+                    wrap: Word
+                }
+            }
+            close_button = <DetailButton> {
+                text: "×"
+            }
+        }
+
+        metadata_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 11.0 }
+                wrap: Word
+            }
+        }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 4
+
+            refresh_button = <DetailButton> { text: "Refresh" }
+            summarize_button = <DetailButton> { text: "Summarize" }
+            stop_button = <DetailButton> { text: "Stop" }
+            destroy_button = <DetailButton> { text: "Kill" }
+        }
+
+        signals_heading = <DetailHeading> { text: "GOLDEN SIGNALS" }
+        latency_row = <SignalRow> { name_label = { text: "Latency p95" } }
+        traffic_row = <SignalRow> { name_label = { text: "Traffic" } }
+        errors_row = <SignalRow> { name_label = { text: "Errors" } }
+        saturation_row = <SignalRow> { name_label = { text: "Saturation" } }
+        signals_summary_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 10.0 }
+                wrap: Word
+            }
+        }
+
+        nodes_heading = <DetailHeading> { text: "NODES" }
+        nodes_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 11.0 }
+                wrap: Word
+            }
+        }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { y: 0.5 }
+
+            logs_heading = <DetailHeading> { text: "RECENT LOGS" }
+            node_filter = <DropDown> {
+                width: 100, height: 24
+                labels: ["All nodes"]
+                draw_text: { text_style: { font_size: 10.0 } }
+            }
+            level_filter = <DropDown> {
+                width: 80, height: 24
+                labels: ["All levels", "Debug+", "Info+", "Warn+", "Error"]
+                draw_text: { text_style: { font_size: 10.0 } }
+            }
+            plain_toggle = <CheckBox> {
+                text: "Plain"
+                draw_text: { text_style: { font_size: 10.0 } }
+            }
+        }
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 4
+
+            highlight_input = <DetailInput> {
+                empty_text: "Highlight regex, e.g. timeout|dropped"
+            }
+            highlight_color = <DropDown> {
+                width: 70, height: 24
+                labels: ["Red", "Orange", "Yellow", "Green", "Blue", "Purple"]
+                draw_text: { text_style: { font_size: 10.0 } }
+            }
+            add_highlight_button = <DetailButton> { text: "Add" }
+            clear_highlights_button = <DetailButton> { text: "Clear" }
+        }
+        highlights_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 10.0 }
+                wrap: Word
+            }
+        }
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 4
+
+            search_input = <DetailInput> {
+                empty_text: "Search logs"
+            }
+            regex_toggle = <CheckBox> {
+                text: "Regex"
+                draw_text: { text_style: { font_size: 10.0 } }
+            }
+            search_count_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 10.0 }
+                }
+            }
+            prev_match_button = <DetailButton> { text: "‹" }
+            next_match_button = <DetailButton> { text: "›" }
+        }
+        logs_list = <PortalList> {
+            width: Fill, height: Fill
+            flow: Down
+            // Follow new lines while scrolled to the end
+            auto_tail: true
+            show_bg: true
+            draw_bg: { color: #f8fafc }
+            padding: 6
+
+            LogRow = <LogRow> {}
+        }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 4
+
+            export_path_input = <DetailInput> {
+                empty_text: "dataflow-logs.txt"
+            }
+            export_logs_button = <DetailButton> { text: "Export" }
+        }
+    }
+}
+
+/// Metadata rows of the panel, e.g. `("Uptime", "5m 03s")`; fields dora did
+/// not report are left out.
+pub fn metadata_rows(df: &DataflowInfo, now_ms: u64) -> Vec<(&'static str, String)> {
+    let mut rows = vec![
+        ("UUID", df.uuid.clone()),
+        ("Status", df.display_status()),
+        ("Nodes", df.nodes_formatted()),
+    ];
+    if df.started_at_ms.is_some() {
+        rows.push((
+            "Started",
+            format!(
+                "{} {}",
+                df.started_formatted(),
+                datetime::display_zone().label()
+            ),
+        ));
+    }
+    if df.uptime_at(now_ms).is_some() {
+        rows.push(("Uptime", df.uptime_formatted(now_ms)));
+    }
+    if df.daemon_id.is_some() {
+        rows.push(("Daemon", df.daemon_id.clone().unwrap_or_default()));
+    }
+    rows.push(("CPU", df.cpu_formatted()));
+    rows.push(("Memory", df.memory_formatted()));
+    rows
+}
+
+/// A [`metadata_rows`] field name in the current language.
+fn field_label(field: &str) -> String {
+    match field {
+        "UUID" => i18n::tr("detail.field.uuid"),
+        "Status" => i18n::tr("detail.field.status"),
+        "Nodes" => i18n::tr("detail.field.nodes"),
+        "Started" => i18n::tr("detail.field.started"),
+        "Uptime" => i18n::tr("detail.field.uptime"),
+        "Daemon" => i18n::tr("detail.field.daemon"),
+        "CPU" => i18n::tr("detail.field.cpu"),
+        "Memory" => i18n::tr("detail.field.memory"),
+        other => other.to_string(),
+    }
+}
+
+/// The last `lines` non-blank lines of `logs`.
+pub fn recent_log_lines(logs: &str, lines: usize) -> String {
+    let all: Vec<&str> = logs.lines().filter(|l| !l.trim().is_empty()).collect();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
+
+/// Actions emitted by the DataflowDetail panel, quick actions with the
+/// dataflow UUID
+#[derive(Clone, Debug, DefaultNone)]
+pub enum DataflowDetailAction {
+    None,
+    Refresh(String),
+    Stop(String),
+    Destroy(String),
+    SummarizeLogs(String),
+    /// Write the filtered logs ([`DataflowDetailRef::export_text`]) to the
+    /// entered path
+    ExportLogs(String),
+    /// The highlight rules were edited, for the app to save
+    HighlightsChanged(Vec<HighlightRule>),
+    Close,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct DataflowDetail {
+    #[deref]
+    view: View,
+    /// UUID of the dataflow shown
+    #[rust]
+    uuid: Option<String>,
+    /// Nodes listed for the dataflow, empty until listed
+    #[rust]
+    nodes: Vec<String>,
+    /// Where the logs as last fetched ended, to tell which lines the next
+    /// fetch adds; `None` until fetched
+    #[rust]
+    last_logs: Option<LogAnchor>,
+    #[rust]
+    buffer: LogBuffer,
+    /// Indices in `buffer` of the lines the filter lets through
+    #[rust]
+    shown: Vec<usize>,
+    /// Nodes offered by the filter, after "All nodes"
+    #[rust]
+    filter_nodes: Vec<String>,
+    /// Node and level the logs are narrowed to
+    #[rust]
+    filter: LogFilter,
+    #[rust]
+    highlight_rules: Vec<HighlightRule>,
+    #[rust]
+    highlighter: Highlighter,
+    /// Shown instead of the logs, e.g. why they could not be fetched
+    #[rust]
+    logs_message: Option<String>,
+    #[rust]
+    search: Option<LogSearch>,
+    #[rust]
+    search_regex: bool,
+    /// Ignore ANSI colours, colouring lines by node only
+    #[rust]
+    plain_logs: bool,
+    /// Rows of the lines shown that match the search, in order
+    #[rust]
+    search_matches: Vec<usize>,
+    /// Index in `search_matches` of the match scrolled to
+    #[rust]
+    search_current: Option<usize>,
+}
+
+impl Widget for DataflowDetail {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                self.draw_log_rows(cx, &mut list);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for DataflowDetail {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope) {
+        if self.view.button(ids!(close_button)).clicked(actions) {
+            self.close(cx);
+            cx.widget_action(self.widget_uid(), &scope.path, DataflowDetailAction::Close);
+            return;
+        }
+        if let Some(index) = self.view.drop_down(ids!(node_filter)).selected(actions) {
+            self.filter.node = index
+                .checked_sub(1)
+                .and_then(|i| self.filter_nodes.get(i))
+                .cloned();
+            self.update_rows(cx);
+        }
+        if let Some(index) = self.view.drop_down(ids!(level_filter)).selected(actions) {
+            self.filter.min_level = LogLevel::ALL.get(index).copied().unwrap_or_default();
+            self.update_rows(cx);
+        }
+
+        if let Some(plain) = self.view.check_box(ids!(plain_toggle)).changed(actions) {
+            self.plain_logs = plain;
+            self.redraw(cx);
+        }
+
+        let search_input = self.view.text_input(ids!(search_input));
+        let regex_changed = self.view.check_box(ids!(regex_toggle)).changed(actions);
+        if let Some(regex) = regex_changed {
+            self.search_regex = regex;
+        }
+        if search_input.changed(actions).is_some() || regex_changed.is_some() {
+            self.set_search(cx, &search_input.text());
+        }
+        if self.view.button(ids!(next_match_button)).clicked(actions)
+            || search_input.returned(actions).is_some()
+        {
+            self.step_search(cx, true);
+        }
+        if self.view.button(ids!(prev_match_button)).clicked(actions) {
+            self.step_search(cx, false);
+        }
+        if let Some(rules) = self.edited_highlights(cx, actions) {
+            self.set_highlight_rules(cx, rules.clone());
+            cx.widget_action(
+                self.widget_uid(),
+                &scope.path,
+                DataflowDetailAction::HighlightsChanged(rules),
+            );
+        }
+        let Some(uuid) = self.uuid.clone() else {
+            return;
+        };
+        let action = if self.view.button(ids!(refresh_button)).clicked(actions) {
+            DataflowDetailAction::Refresh(uuid)
+        } else if self.view.button(ids!(summarize_button)).clicked(actions) {
+            DataflowDetailAction::SummarizeLogs(uuid)
+        } else if self.view.button(ids!(stop_button)).clicked(actions) {
+            DataflowDetailAction::Stop(uuid)
+        } else if self.view.button(ids!(destroy_button)).clicked(actions) {
+            DataflowDetailAction::Destroy(uuid)
+        } else if self.view.button(ids!(export_logs_button)).clicked(actions) {
+            let path = self.view.text_input(ids!(export_path_input)).text();
+            DataflowDetailAction::ExportLogs(path)
+        } else {
+            return;
+        };
+        cx.widget_action(self.widget_uid(), &scope.path, action);
+    }
+}
+
+impl DataflowDetail {
+    /// Show the metadata of `df`, keeping the nodes and logs shown when it
+    /// is the dataflow already open.
+    pub fn set_dataflow(&mut self, cx: &mut Cx, df: &DataflowInfo, now_ms: u64) {
+        if self.uuid.as_deref() != Some(df.uuid.as_str()) {
+            self.uuid = Some(df.uuid.clone());
+            self.view
+                .label(ids!(nodes_label))
+                .set_text(cx, &i18n::tr("detail.loading"));
+            self.nodes.clear();
+            self.last_logs = None;
+            self.buffer.clear();
+            self.filter.node = None;
+            self.logs_message = None;
+            self.logs_changed(cx);
+            self.set_golden_signals(cx, &GoldenSignals::default());
+        }
+        let title = if df.name.is_empty() {
+            df.uuid_short()
+        } else {
+            df.name.clone()
+        };
+        self.view.label(ids!(title_label)).set_text(cx, &title);
+        let metadata = metadata_rows(df, now_ms)
+            .into_iter()
+            .map(|(key, value)| format!("{}: {}", field_label(key), value))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.view
+            .label(ids!(metadata_label))
+            .set_text(cx, &metadata);
+        self.view.set_visible(cx, true);
+        self.redraw(cx);
+    }
+
+    /// Show the node IDs, or why they could not be listed.
+    pub fn set_nodes(&mut self, cx: &mut Cx, nodes: Result<Vec<String>, String>) {
+        let text = match &nodes {
+            Ok(nodes) if nodes.is_empty() => "No nodes listed".to_string(),
+            Ok(nodes) => nodes.join("\n"),
+            Err(e) => format!("Could not list nodes: {}", e),
+        };
+        self.view.label(ids!(nodes_label)).set_text(cx, &text);
+        self.nodes = nodes.unwrap_or_default();
+        self.buffer.reattribute(&self.nodes);
+        self.logs_changed(cx);
+    }
+
+    /// Add the lines of `logs` not shown yet, or show why they could not be
+    /// fetched.
+    /// Show fetched logs. Returns how many lines were written since the
+    /// previous fetch, none for the first one.
+    pub fn set_logs(&mut self, cx: &mut Cx, logs: Result<String, String>) -> usize {
+        let mut written = 0;
+        match logs {
+            Ok(logs) => {
+                let lines: Vec<String> = match &self.last_logs {
+                    Some(anchor) => anchor.new_lines(&logs),
+                    // The first fetch has the whole history, which dora
+                    // keeps anyway, so only what fits is taken
+                    None => recent_log_lines(&logs, self.buffer.capacity())
+                        .lines()
+                        .map(str::to_string)
+                        .collect(),
+                };
+                if self.last_logs.is_some() {
+                    written = lines.len();
+                }
+                self.buffer.push_lines(lines, &self.nodes);
+                self.last_logs = Some(LogAnchor::new(&logs));
+                self.logs_message = None;
+            }
+            Err(e) => self.logs_message = Some(format!("Could not fetch logs: {}", e)),
+        }
+        self.logs_changed(cx);
+        written
+    }
+
+    /// Hold at most `lines` log lines.
+    pub fn set_buffer_lines(&mut self, cx: &mut Cx, lines: usize) {
+        self.buffer.set_capacity(lines);
+        self.logs_changed(cx);
+    }
+
+    /// Offer the listed nodes to filter on, or the nodes the logs name when
+    /// none are listed, and update the rows shown.
+    fn logs_changed(&mut self, cx: &mut Cx) {
+        let filter_nodes = if self.nodes.is_empty() {
+            node_logs::log_nodes(self.buffer.iter())
+        } else {
+            self.nodes.clone()
+        };
+        if filter_nodes != self.filter_nodes {
+            let dropdown = self.view.drop_down(ids!(node_filter));
+            dropdown.set_labels(cx, node_filter_labels(&filter_nodes));
+            // Keep the node filtered on if it is still there
+            let selected = self
+                .filter
+                .node
+                .as_ref()
+                .and_then(|node| filter_nodes.iter().position(|n| n == node));
+            if selected.is_none() {
+                self.filter.node = None;
+            }
+            dropdown.set_selected_item(cx, selected.map_or(0, |i| i + 1));
+            self.filter_nodes = filter_nodes;
+        }
+        self.update_rows(cx);
+        self.redraw(cx);
+    }
+
+    /// Filter the lines shown as the dropdowns would. A node not offered
+    /// to filter on filters on none.
+    pub fn set_filter(&mut self, cx: &mut Cx, mut filter: LogFilter) {
+        let node = filter
+            .node
+            .as_ref()
+            .and_then(|node| self.filter_nodes.iter().position(|n| n == node));
+        if node.is_none() {
+            filter.node = None;
+        }
+        self.view
+            .drop_down(ids!(node_filter))
+            .set_selected_item(cx, node.map_or(0, |i| i + 1));
+        let level = LogLevel::ALL.iter().position(|l| *l == filter.min_level);
+        self.view
+            .drop_down(ids!(level_filter))
+            .set_selected_item(cx, level.unwrap_or(0));
+        self.filter = filter;
+        self.update_rows(cx);
+        self.redraw(cx);
+    }
+
+    /// Search the lines shown for `query`, scrolling to the first match.
+    fn set_search(&mut self, cx: &mut Cx, query: &str) {
+        self.search_current = None;
+        match LogSearch::new(query, self.search_regex) {
+            Ok(search) => {
+                self.search = search;
+                self.update_rows(cx);
+                self.step_search(cx, true);
+            }
+            Err(e) => {
+                self.search = None;
+                self.update_rows(cx);
+                self.view.label(ids!(search_count_label)).set_text(cx, &e);
+            }
+        }
+    }
+
+    /// Pick the lines to show and match the search against them, after
+    /// the logs, the filter or the search changed.
+    fn update_rows(&mut self, cx: &mut Cx) {
+        self.shown = node_logs::matching_rows(self.buffer.iter(), &self.filter);
+        self.search_matches = match &self.search {
+            Some(search) => log_search::match_indices(
+                self.shown
+                    .iter()
+                    .filter_map(|&i| self.buffer.get(i))
+                    .map(|l| l.text.as_str()),
+                search,
+            ),
+            None => Vec::new(),
+        };
+        self.search_current = self
+            .search_current
+            .filter(|&i| i < self.search_matches.len());
+        self.show_match_count(cx);
+        self.view.portal_list(ids!(logs_list)).redraw(cx);
+    }
+
+    /// Scroll to the next or previous match.
+    fn step_search(&mut self, cx: &mut Cx, forward: bool) {
+        self.search_current =
+            log_search::step_match(self.search_current, self.search_matches.len(), forward);
+        let list = self.view.portal_list(ids!(logs_list));
+        if let Some(&row) = self.search_current.and_then(|i| self.search_matches.get(i)) {
+            list.set_first_id_and_scroll(row.saturating_sub(SEARCH_CONTEXT_LINES), 0.0);
+        }
+        self.show_match_count(cx);
+        list.redraw(cx);
+    }
+
+    fn show_match_count(&mut self, cx: &mut Cx) {
+        let text = match self.search {
+            Some(_) => log_search::match_count_text(self.search_current, self.search_matches.len()),
+            None => String::new(),
+        };
+        self.view
+            .label(ids!(search_count_label))
+            .set_text(cx, &text);
+    }
+
+    fn draw_log_rows(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        let message = match &self.logs_message {
+            Some(message) => Some(message.clone()),
+            None if self.last_logs.is_none() || !self.shown.is_empty() => None,
+            None if self.filter == LogFilter::default() => Some("No logs yet".to_string()),
+            None => Some(format!("No logs match {}", self.filter.describe())),
+        };
+        let count = if message.is_some() {
+            1
+        } else {
+            self.shown.len()
+        };
+        list.set_item_range(cx, 0, count);
+        while let Some(item_id) = list.next_visible_item(cx) {
+            let line = self.shown.get(item_id).and_then(|&i| self.buffer.get(i));
+            let (text, rgb) = match &message {
+                Some(message) => (message.as_str(), LOG_TEXT_COLOR),
+                None => match line {
+                    Some(line) => (
+                        line.text.as_str(),
+                        line.color.filter(|_| !self.plain_logs).unwrap_or_else(|| {
+                            line.node
+                                .as_deref()
+                                .map_or(LOG_TEXT_COLOR, node_logs::node_color)
+                        }),
+                    ),
+                    None => continue,
+                },
+            };
+            let current = self
+                .search_current
+                .and_then(|i| self.search_matches.get(i))
+                .copied();
+            let background = if message.is_some() {
+                vec4(0.0, 0.0, 0.0, 0.0)
+            } else if current == Some(item_id) {
+                rgb_color(SEARCH_MATCH_COLOR, SEARCH_CURRENT_ALPHA)
+            } else if self.search_matches.binary_search(&item_id).is_ok() {
+                rgb_color(SEARCH_MATCH_COLOR, SEARCH_MATCH_ALPHA)
+            } else {
+                match self.highlighter.color(text) {
+                    Some(rgb) => rgb_color(rgb, HIGHLIGHT_ALPHA),
+                    None => vec4(0.0, 0.0, 0.0, 0.0),
+                }
+            };
+            let item = list.item(cx, item_id, live_id!(LogRow));
+            item.apply_over(cx, live! { draw_bg: { color: (background) } });
+            let label = item.label(ids!(log_label));
+            let color = rgb_color(rgb, 1.0);
+            label.apply_over(cx, live! { draw_text: { color: (color) } });
+            label.set_text(cx, text);
+            item.draw_all(cx, &mut Scope::empty());
+        }
+    }
+
+    /// The highlight rules after an Add or Clear click, if one was valid.
+    fn edited_highlights(&mut self, cx: &mut Cx, actions: &Actions) -> Option<Vec<HighlightRule>> {
+        if self
+            .view
+            .button(ids!(clear_highlights_button))
+            .clicked(actions)
+        {
+            return Some(Vec::new());
+        }
+        let input = self.view.text_input(ids!(highlight_input));
+        let add = self
+            .view
+            .button(ids!(add_highlight_button))
+            .clicked(actions)
+            || input.returned(actions).is_some();
+        if !add {
+            return None;
+        }
+        let (color, _) = HIGHLIGHT_COLORS
+            .get(self.view.drop_down(ids!(highlight_color)).selected_item())
+            .copied()
+            .unwrap_or(HIGHLIGHT_COLORS[0]);
+        match HighlightRule::new(&input.text(), color) {
+            Ok(rule) => {
+                input.set_text(cx, "");
+                let mut rules = self.highlight_rules.clone();
+                rules.retain(|r| r.pattern != rule.pattern);
+                rules.push(rule);
+                Some(rules)
+            }
+            Err(e) => {
+                self.view.label(ids!(highlights_label)).set_text(cx, &e);
+                None
+            }
+        }
+    }
+
+    /// Show the golden signals of the dataflow, each coloured by its level.
+    pub fn set_golden_signals(&mut self, cx: &mut Cx, signals: &GoldenSignals) {
+        let percent = |v: f64| units::format(v, Unit::Percent);
+        let rows = [
+            (
+                self.view.view(ids!(latency_row)),
+                &signals.latency,
+                signals
+                    .latency
+                    .value
+                    .map(|v| units::format(v, Unit::Milliseconds)),
+            ),
+            (
+                self.view.view(ids!(traffic_row)),
+                &signals.traffic,
+                signals
+                    .traffic
+                    .value
+                    .map(|v| format!("{}/s", units::format(v, Unit::Count))),
+            ),
+            (
+                self.view.view(ids!(errors_row)),
+                &signals.errors,
+                signals.errors.value.map(percent),
+            ),
+            (
+                self.view.view(ids!(saturation_row)),
+                &signals.saturation,
+                signals.saturation.value.map(percent),
+            ),
+        ];
+        for (row, signal, value) in rows {
+            show_signal(cx, &row, signal, value.as_deref());
+        }
+        self.view
+            .label(ids!(signals_summary_label))
+            .set_text(cx, &signals.summary());
+        self.redraw(cx);
+    }
+
+    /// Highlight the lines matching `rules`; invalid ones are skipped.
+    pub fn set_highlight_rules(&mut self, cx: &mut Cx, rules: Vec<HighlightRule>) {
+        let (highlighter, errors) = Highlighter::new(&rules);
+        for e in &errors {
+            tracing::warn!(target: "DataflowDetail", "Skipping highlight rule: {}", e);
+        }
+        let text = if rules.is_empty() {
+            "No highlight rules".to_string()
+        } else {
+            format!("Highlights: {}", highlight::rules_text(&rules))
+        };
+        self.view.label(ids!(highlights_label)).set_text(cx, &text);
+        self.highlighter = highlighter;
+        self.highlight_rules = rules;
+        self.view.portal_list(ids!(logs_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    /// Show the text set in `live_design` in the current language
+    pub fn localize(&mut self, cx: &mut Cx) {
+        i18n::localize(
+            cx,
+            &[
+                (ids!(refresh_button), "action.refresh"),
+                (ids!(summarize_button), "action.summarize"),
+                (ids!(stop_button), "action.stop"),
+                (ids!(destroy_button), "action.kill"),
+                (ids!(signals_heading), "detail.signals"),
+                (ids!(latency_row.name_label), "detail.signal.latency"),
+                (ids!(traffic_row.name_label), "detail.signal.traffic"),
+                (ids!(errors_row.name_label), "detail.signal.errors"),
+                (ids!(saturation_row.name_label), "detail.signal.saturation"),
+                (ids!(nodes_heading), "detail.nodes"),
+                (ids!(logs_heading), "detail.logs"),
+                (ids!(plain_toggle), "detail.plain"),
+                (ids!(add_highlight_button), "action.add"),
+                (ids!(clear_highlights_button), "action.clear"),
+                (ids!(regex_toggle), "detail.regex"),
+                (ids!(export_logs_button), "action.export"),
+            ],
+            |path| self.view.widget(path),
+        );
+        let node_filter = self.view.drop_down(ids!(node_filter));
+        let selected = node_filter.selected_item();
+        node_filter.set_labels(cx, node_filter_labels(&self.filter_nodes));
+        node_filter.set_selected_item(cx, selected);
+        i18n::localize_labels(
+            cx,
+            &self.view.drop_down(ids!(level_filter)),
+            &[
+                "detail.level.all",
+                "detail.level.debug",
+                "detail.level.info",
+                "detail.level.warn",
+                "detail.level.error",
+            ],
+        );
+        i18n::localize_labels(
+            cx,
+            &self.view.drop_down(ids!(highlight_color)),
+            &[
+                "color.red",
+                "color.orange",
+                "color.yellow",
+                "color.green",
+                "color.blue",
+                "color.purple",
+            ],
+        );
+    }
+
+    pub fn close(&mut self, cx: &mut Cx) {
+        self.uuid = None;
+        self.view.set_visible(cx, false);
+        self.redraw(cx);
+    }
+}
+
+/// Labels of the node filter: "All nodes", then each of `nodes`.
+fn node_filter_labels(nodes: &[String]) -> Vec<String> {
+    let mut labels = vec![i18n::tr("detail.all_nodes")];
+    labels.extend(nodes.iter().cloned());
+    labels
+}
+
+fn show_signal(cx: &mut Cx, row: &ViewRef, signal: &Signal, value: Option<&str>) {
+    let rgb = match (value, signal.level) {
+        (None, _) => SIGNAL_NO_DATA_COLOR,
+        (Some(_), SignalLevel::Normal) => SIGNAL_NORMAL_COLOR,
+        (Some(_), SignalLevel::Warning) => SIGNAL_WARNING_COLOR,
+        (Some(_), SignalLevel::Critical) => SIGNAL_CRITICAL_COLOR,
+    };
+    let color = rgb_color(rgb, 1.0);
+    for (id, shown) in [
+        (ids!(value_label), value.unwrap_or("–").to_string()),
+        (
+            ids!(trend_label),
+            text::sparkline(signal.trend.iter().copied()),
+        ),
+    ] {
+        let label = row.label(id);
+        label.apply_over(cx, live! { draw_text: { color: (color) } });
+        label.set_text(cx, &shown);
+    }
+}
+
+fn rgb_color(rgb: u32, alpha: f32) -> Vec4 {
+    vec4(
+        ((rgb >> 16) & 0xff) as f32 / 255.0,
+        ((rgb >> 8) & 0xff) as f32 / 255.0,
+        (rgb & 0xff) as f32 / 255.0,
+        alpha,
+    )
+}
+
+impl DataflowDetailRef {
+    pub fn localize(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.localize(cx);
+        }
+    }
+
+    pub fn set_dataflow(&self, cx: &mut Cx, df: &DataflowInfo, now_ms: u64) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_dataflow(cx, df, now_ms);
+        }
+    }
+
+    pub fn set_nodes(&self, cx: &mut Cx, nodes: Result<Vec<String>, String>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_nodes(cx, nodes);
+        }
+    }
+
+    /// See [`DataflowDetail::set_logs`].
+    pub fn set_logs(&self, cx: &mut Cx, logs: Result<String, String>) -> usize {
+        self.borrow_mut()
+            .map_or(0, |mut inner| inner.set_logs(cx, logs))
+    }
+
+    pub fn set_golden_signals(&self, cx: &mut Cx, signals: &GoldenSignals) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_golden_signals(cx, signals);
+        }
+    }
+
+    pub fn set_highlight_rules(&self, cx: &mut Cx, rules: Vec<HighlightRule>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_highlight_rules(cx, rules);
+        }
+    }
+
+    pub fn set_buffer_lines(&self, cx: &mut Cx, lines: usize) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_buffer_lines(cx, lines);
+        }
+    }
+
+    /// Log lines pushed out of the buffer since the last call, oldest first.
+    pub fn take_spilled_logs(&self) -> Vec<NodeLogLine> {
+        self.borrow_mut()
+            .map(|mut inner| inner.buffer.take_spilled())
+            .unwrap_or_default()
+    }
+
+    pub fn close(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.close(cx);
+        }
+    }
+
+    /// See [`DataflowDetail::set_filter`].
+    pub fn set_filter(&self, cx: &mut Cx, filter: LogFilter) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_filter(cx, filter);
+        }
+    }
+
+    /// The log lines the filter lets through, in order
+    pub fn shown_lines(&self) -> Vec<NodeLogLine> {
+        let Some(inner) = self.borrow() else {
+            return Vec::new();
+        };
+        inner
+            .shown
+            .iter()
+            .filter_map(|&i| inner.buffer.get(i))
+            .cloned()
+            .collect()
+    }
+
+    /// The logs of the dataflow shown that the filter lets through, for
+    /// [`DataflowDetailAction::ExportLogs`].
+    pub fn export_text(&self) -> Option<String> {
+        let inner = self.borrow()?;
+        let uuid = inner.uuid.as_deref()?;
+        Some(node_logs::export_text(
+            uuid,
+            inner.buffer.iter(),
+            &inner.filter,
+        ))
+    }
+
+    /// UUID of the dataflow shown, if the panel is open
+    pub fn uuid(&self) -> Option<String> {
+        self.borrow()?.uuid.clone()
+    }
+
+    /// Nodes listed for the dataflow shown, empty until listed
+    pub fn nodes(&self) -> Vec<String> {
+        self.borrow()
+            .map(|inner| inner.nodes.clone())
+            .unwrap_or_default()
+    }
+
+    /// Check if a DataflowDetailAction was triggered
+    pub fn action(&self, actions: &Actions) -> Option<DataflowDetailAction> {
+        actions.find_widget_action(self.widget_uid())?.cast()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_rows_skip_unreported_fields() {
+        let df = DataflowInfo {
+            uuid: "0191".to_string(),
+            status: "Running".to_string(),
+            nodes: 3,
+            started_at_ms: Some(1_000),
+            daemon_id: Some("lab-1".to_string()),
+            ..Default::default()
+        };
+        let keys = |rows: Vec<(&str, String)>| rows.into_iter().map(|(k, _)| k).collect::<Vec<_>>();
+        let rows = metadata_rows(&df, 43_000);
+        assert_eq!(rows[4], ("Uptime", "42s".to_string()));
+        assert_eq!(
+            keys(rows),
+            ["UUID", "Status", "Nodes", "Started", "Uptime", "Daemon", "CPU", "Memory"]
+        );
+        assert_eq!(
+            keys(metadata_rows(&DataflowInfo::default(), 0)),
+            ["UUID", "Status", "Nodes", "CPU", "Memory"]
+        );
+    }
+
+    #[test]
+    fn test_recent_log_lines() {
+        let logs = "a\n\nb\nc\n  \nd\n";
+        assert_eq!(recent_log_lines(logs, 2), "c\nd");
+        assert_eq!(recent_log_lines(logs, 10), "a\nb\nc\nd");
+        assert_eq!(recent_log_lines("", 5), "");
+    }
+}
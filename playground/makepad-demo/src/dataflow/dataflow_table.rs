@@ -1,6 +1,13 @@
+use crate::datetime::{self, parse_rfc3339_ms};
+use crate::i18n;
+use crate::logging;
+use crate::text;
 use makepad_widgets::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::cell::RefMut;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 
 live_design! {
     use link::theme::*;
@@ -33,6 +40,17 @@ live_design! {
     BTN_LOGS_BG = #e0e7ff
     BTN_LOGS_TEXT = #4f46e5
 
+    // Header of a column the table can be sorted by
+    SortButton = <Button> {
+        height: 24
+        padding: { left: 0, right: 0 }
+        align: { x: 0.0 }
+        draw_text: {
+            color: (TEXT_SECONDARY),
+            text_style: { font_size: 11.0 }
+        }
+    }
+
     // Table header row
     TableHeader = <View> {
         width: Fill, height: 40
@@ -43,7 +61,7 @@ live_design! {
         align: { y: 0.5 }
         spacing: 8
 
-        <Label> {
+        uuid_header = <Label> {
             width: 90, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
@@ -51,23 +69,39 @@ live_design! {
             }
             text: "UUID"
         }
-        <Label> {
-            width: Fill, height: Fit
+        sort_name_button = <SortButton> {
+            width: Fill
+            text: "NAME"
+        }
+        sort_status_button = <SortButton> {
+            width: 70
+            text: "STATUS"
+        }
+        sort_nodes_button = <SortButton> {
+            width: 45
+            text: "NODES"
+        }
+        sort_started_button = <SortButton> {
+            width: 60
+            text: "STARTED"
+        }
+        uptime_header = <Label> {
+            width: 60, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
                 text_style: { font_size: 11.0 }
             }
-            text: "NAME"
+            text: "UPTIME"
         }
-        <Label> {
-            width: 70, height: Fit
+        daemon_header = <Label> {
+            width: 80, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
                 text_style: { font_size: 11.0 }
             }
-            text: "STATUS"
+            text: "DAEMON"
         }
-        <Label> {
+        cpu_header = <Label> {
             width: 50, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
@@ -75,7 +109,7 @@ live_design! {
             }
             text: "CPU"
         }
-        <Label> {
+        memory_header = <Label> {
             width: 60, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
@@ -83,8 +117,8 @@ live_design! {
             }
             text: "MEM"
         }
-        <Label> {
-            width: 110, height: Fit
+        actions_header = <Label> {
+            width: 190, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
                 text_style: { font_size: 11.0 }
@@ -148,6 +182,40 @@ live_design! {
                 text_style: { font_size: 12.0 }
             }
         }
+        nodes_label = <Label> {
+            width: 45, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        // A cursor makes the cell report hover in/out, for the exact-time
+        // tooltip
+        started_cell = <View> {
+            width: 60, height: Fit
+            cursor: Default
+            started_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+            }
+        }
+        uptime_label = <Label> {
+            width: 60, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        daemon_label = <Label> {
+            width: 80, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
         cpu_label = <Label> {
             width: 50, height: Fit
             draw_text: {
@@ -165,11 +233,15 @@ live_design! {
 
         // Action buttons container
         actions = <View> {
-            width: 110, height: Fit
+            width: 190, height: Fit
             flow: Right
             align: { x: 1.0, y: 0.5 }
             spacing: 4
 
+            summarize_button = <ActionButton> {
+                width: Fit
+                text: "Summarize"
+            }
             stop_button = <ActionButton> {
                 text: "Stop"
             }
@@ -210,6 +282,40 @@ live_design! {
                 text_style: { font_size: 12.0 }
             }
         }
+        nodes_label = <Label> {
+            width: 45, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        // A cursor makes the cell report hover in/out, for the exact-time
+        // tooltip
+        started_cell = <View> {
+            width: 60, height: Fit
+            cursor: Default
+            started_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+            }
+        }
+        uptime_label = <Label> {
+            width: 60, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        daemon_label = <Label> {
+            width: 80, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
         cpu_label = <Label> {
             width: 50, height: Fit
             draw_text: {
@@ -227,11 +333,15 @@ live_design! {
 
         // Action buttons container
         actions = <View> {
-            width: 110, height: Fit
+            width: 190, height: Fit
             flow: Right
             align: { x: 1.0, y: 0.5 }
             spacing: 4
 
+            summarize_button = <ActionButton> {
+                width: Fit
+                text: "Summarize"
+            }
             stop_button = <ActionButton> {
                 text: "Stop"
             }
@@ -249,7 +359,7 @@ live_design! {
         show_bg: true
         draw_bg: { color: (ROW_BG) }
 
-        <Label> {
+        title_label = <Label> {
             width: Fit, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
@@ -257,7 +367,7 @@ live_design! {
             }
             text: "No dataflows running"
         }
-        <Label> {
+        hint_label = <Label> {
             width: Fit, height: Fit
             margin: { top: 8 }
             draw_text: {
@@ -276,7 +386,7 @@ live_design! {
         show_bg: true
         draw_bg: { color: (ROW_BG) }
 
-        <Label> {
+        loading_label = <Label> {
             width: Fit, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
@@ -286,41 +396,101 @@ live_design! {
         }
     }
 
+    // Offline state view (no coordinator reachable)
+    OfflineState = <View> {
+        width: Fill, height: 120
+        flow: Down
+        align: { x: 0.5, y: 0.5 }
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+
+        title_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 14.0 }
+            }
+            text: "Coordinator offline"
+        }
+        hint_label = <Label> {
+            width: Fit, height: Fit
+            margin: { top: 8 }
+            draw_text: {
+                color: #94a3b8,
+                text_style: { font_size: 12.0 }
+            }
+            text: "Run history, stored metrics and YAML files are still available"
+        }
+    }
+
     pub DataflowTable = {{DataflowTable}} {
-        width: Fill, height: Fit
+        width: Fill, height: Fill
         flow: Down
 
+        // Search and status filter
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            padding: { left: 16, right: 16, top: 6, bottom: 6 }
+            align: { y: 0.5 }
+            spacing: 8
+
+            search_input = <TextInput> {
+                width: 240, height: Fit
+                draw_text: { text_style: { font_size: 11.0 } }
+                empty_text: "Search name, UUID or daemon"
+            }
+            status_filter_dropdown = <DropDown> {
+                width: 100, height: 28
+                labels: ["All", "Running", "Finished", "Failed"]
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+            filter_count_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+            }
+        }
+
         // Header
-        <TableHeader> {}
+        header = <TableHeader> {}
 
         // Data rows via PortalList
         table_list = <PortalList> {
-            width: Fill, height: 300
+            width: Fill, height: Fill
             flow: Down
 
             TableRow = <TableRow> {}
             TableRowAlt = <TableRowAlt> {}
             EmptyState = <EmptyState> {}
             LoadingState = <LoadingState> {}
+            OfflineState = <OfflineState> {}
         }
+
+        started_tooltip = <Tooltip> {}
     }
 }
 
 /// Dataflow information from dora list command
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct DataflowInfo {
-    #[serde(default)]
     pub uuid: String,
-    #[serde(default)]
     pub name: String,
-    #[serde(default)]
     pub status: String,
-    #[serde(default)]
     pub nodes: u32,
-    #[serde(default)]
     pub cpu: f64,
-    #[serde(default)]
     pub memory: f64,
+    /// When the dataflow was started, in milliseconds since the epoch
+    pub started_at_ms: Option<u64>,
+    /// Seconds running, as reported by dora
+    pub uptime_secs: Option<u64>,
+    /// Daemon (machine) the dataflow runs on; several are joined with `, `
+    pub daemon_id: Option<String>,
+    /// Running but without telemetry activity for a while (see
+    /// [`super::liveness`]); not part of `dora list` output.
+    pub stale: bool,
 }
 
 impl DataflowInfo {
@@ -329,13 +499,108 @@ impl DataflowInfo {
         input
             .lines()
             .filter(|line| !line.trim().is_empty())
-            .filter_map(|line| serde_json::from_str(line).ok())
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .filter_map(|entry| Self::from_value(&entry))
             .collect()
     }
 
     /// Parse JSON array into a vector of DataflowInfo
     pub fn parse_json_array(input: &str) -> Vec<Self> {
-        serde_json::from_str(input).unwrap_or_default()
+        match serde_json::from_str::<Value>(input) {
+            Ok(Value::Array(entries)) => entries.iter().filter_map(Self::from_value).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// A `dora list` or coordinator entry, whichever names the dora version
+    /// uses for its fields, e.g. `id` or `uuid`, `nodes` as a count or a
+    /// list, and `status` as a string or `{"Failed": ".."}`. Fields that are
+    /// missing or of an unexpected type are left unset.
+    pub fn from_value(entry: &Value) -> Option<Self> {
+        let entry = entry.as_object()?;
+        let status = match field(entry, &["status", "state"]) {
+            Some(Value::String(status)) => status.clone(),
+            Some(Value::Object(status)) => status.keys().next().cloned().unwrap_or_default(),
+            _ => String::new(),
+        };
+        let nodes = match field(entry, &["nodes", "node_count", "num_nodes"]) {
+            Some(Value::Array(nodes)) => nodes.len() as u64,
+            Some(value) => number(value).map_or(0, |n| n as u64),
+            None => 0,
+        };
+        let daemon_id = match field(entry, &["daemon_id", "daemon", "daemons", "machine_id"]) {
+            Some(Value::Array(daemons)) => {
+                let ids: Vec<String> = daemons.iter().filter_map(daemon_name).collect();
+                (!ids.is_empty()).then(|| ids.join(", "))
+            }
+            Some(value) => daemon_name(value),
+            None => None,
+        };
+        Some(Self {
+            uuid: string(field(entry, &["uuid", "id"])).unwrap_or_default(),
+            name: string(field(entry, &["name"])).unwrap_or_default(),
+            status,
+            nodes: u32::try_from(nodes).unwrap_or(u32::MAX),
+            cpu: field(entry, &["cpu", "cpu_usage"])
+                .and_then(number)
+                .unwrap_or(0.0),
+            memory: field(entry, &["memory", "mem", "memory_usage", "memory_gb"])
+                .and_then(number)
+                .unwrap_or(0.0),
+            started_at_ms: field(
+                entry,
+                &["started_at", "start_time", "started", "started_at_ms"],
+            )
+            .and_then(timestamp_ms),
+            uptime_secs: field(entry, &["uptime", "uptime_secs"]).and_then(duration_secs),
+            daemon_id,
+            stale: false,
+        })
+    }
+
+    /// Node count, or `-` when dora did not report any
+    pub fn nodes_formatted(&self) -> String {
+        match self.nodes {
+            0 => "-".to_string(),
+            n => n.to_string(),
+        }
+    }
+
+    /// Start time as `HH:MM:SS` in the display zone, or `-`
+    pub fn started_formatted(&self) -> String {
+        match self.started_at_ms {
+            Some(at) => datetime::format_clock_secs(at),
+            None => "-".to_string(),
+        }
+    }
+
+    /// Seconds running as of `now_ms`: as reported by dora, or since the
+    /// start time while running.
+    pub fn uptime_at(&self, now_ms: u64) -> Option<u64> {
+        self.uptime_secs.or_else(|| {
+            self.started_at_ms
+                .filter(|_| self.is_running())
+                .map(|at| now_ms.saturating_sub(at) / 1000)
+        })
+    }
+
+    /// Uptime as of `now_ms`, e.g. `42s`, `5m 03s`, `2h 10m` or `3d 4h`
+    pub fn uptime_formatted(&self, now_ms: u64) -> String {
+        match self.uptime_at(now_ms) {
+            Some(secs) if secs < 60 => format!("{}s", secs),
+            Some(secs) if secs < 3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+            Some(secs) if secs < 86_400 => format!("{}h {:02}m", secs / 3600, secs / 60 % 60),
+            Some(secs) => format!("{}d {}h", secs / 86_400, secs / 3600 % 24),
+            None => "-".to_string(),
+        }
+    }
+
+    /// Daemon ID, or `-`
+    pub fn daemon_formatted(&self) -> String {
+        match &self.daemon_id {
+            Some(daemon) => text::truncate_middle(daemon, 12),
+            None => "-".to_string(),
+        }
     }
 
     /// Format memory in human-readable format
@@ -356,17 +621,366 @@ impl DataflowInfo {
 
     /// Get short UUID (first 8 characters)
     pub fn uuid_short(&self) -> String {
-        if self.uuid.len() > 8 {
-            format!("{}...", &self.uuid[..8])
-        } else {
-            self.uuid.clone()
-        }
+        text::truncate(&self.uuid, 8)
     }
 
     /// Check if dataflow is running
     pub fn is_running(&self) -> bool {
         self.status.to_lowercase() == "running"
     }
+
+    /// Status shown in the table, flagging running dataflows that went quiet
+    pub fn display_status(&self) -> String {
+        if self.stale && self.is_running() {
+            format!("{} (stale)", self.status)
+        } else {
+            self.status.clone()
+        }
+    }
+
+    /// Check if dataflow has failed
+    pub fn is_failed(&self) -> bool {
+        self.status.eq_ignore_ascii_case("failed")
+    }
+
+    /// Whether the name, UUID or daemon ID contains `query`, ignoring case;
+    /// a blank query matches everything
+    pub fn matches_search(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || [Some(&self.name), Some(&self.uuid), self.daemon_id.as_ref()]
+                .into_iter()
+                .flatten()
+                .any(|field| field.to_lowercase().contains(&query))
+    }
+}
+
+/// The first of `keys` present and not null.
+fn field<'a>(entry: &'a Map<String, Value>, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter()
+        .find_map(|key| entry.get(*key).filter(|v| !v.is_null()))
+}
+
+fn string(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// A number, or a string holding one such as `"12.5"` or `"12.5%"`.
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().trim_end_matches('%').trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// A daemon ID, or an object holding one, e.g. `{"machine_id": "lab-1"}`.
+fn daemon_name(value: &Value) -> Option<String> {
+    match value {
+        Value::Object(daemon) => string(field(daemon, &["machine_id", "id", "name"])),
+        value => string(Some(value)),
+    }
+    .filter(|id| !id.is_empty())
+}
+
+/// Milliseconds since the epoch from seconds, milliseconds or nanoseconds
+/// since the epoch, or an RFC 3339 time such as `2024-05-01T10:00:00.5Z`.
+fn timestamp_ms(value: &Value) -> Option<u64> {
+    let n = match value {
+        Value::String(s) => match s.trim().parse::<f64>() {
+            Ok(n) => n,
+            Err(_) => return parse_rfc3339_ms(s),
+        },
+        value => value.as_f64()?,
+    };
+    if !n.is_finite() || n <= 0.0 {
+        None
+    } else if n > 1e15 {
+        Some((n / 1e6) as u64)
+    } else if n > 1e11 {
+        Some(n as u64)
+    } else {
+        Some((n * 1000.0) as u64)
+    }
+}
+
+/// Seconds from a number of seconds, or text such as `1h 2m 3s`, `90s`
+/// or `01:02:03`.
+fn duration_secs(value: &Value) -> Option<u64> {
+    let s = match value {
+        Value::String(s) => s.trim(),
+        value => return value.as_f64().filter(|n| *n >= 0.0).map(|n| n as u64),
+    };
+    if let Ok(n) = s.parse::<f64>() {
+        return (n >= 0.0).then_some(n as u64);
+    }
+    if s.contains(':') {
+        return s.split(':').try_fold(0, |total: u64, part| {
+            Some(total * 60 + part.parse::<f64>().ok()? as u64)
+        });
+    }
+    let mut total = 0.0;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .filter(|&i| i > 0)?;
+        let amount: f64 = rest[..split].parse().ok()?;
+        let unit_end = rest[split..]
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .map_or(rest.len(), |i| split + i);
+        total += amount
+            * match &rest[split..unit_end] {
+                "ms" => 0.001,
+                "s" => 1.0,
+                "m" | "min" => 60.0,
+                "h" => 3600.0,
+                "d" => 86_400.0,
+                _ => return None,
+            };
+        rest = rest[unit_end..].trim_start();
+    }
+    Some(total as u64)
+}
+
+/// Dataflows that are failed now but were not in `previous` (uuid -> status).
+pub fn newly_failed<'a>(
+    previous: &HashMap<String, String>,
+    current: &'a [DataflowInfo],
+) -> Vec<&'a DataflowInfo> {
+    current
+        .iter()
+        .filter(|df| df.is_failed())
+        .filter(|df| {
+            previous
+                .get(&df.uuid)
+                .is_none_or(|status| !status.eq_ignore_ascii_case("failed"))
+        })
+        .collect()
+}
+
+/// Statuses the table can be narrowed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    Running,
+    Finished,
+    Failed,
+}
+
+impl StatusFilter {
+    /// In the order of the status filter dropdown
+    pub const ALL: [StatusFilter; 4] = [
+        StatusFilter::All,
+        StatusFilter::Running,
+        StatusFilter::Finished,
+        StatusFilter::Failed,
+    ];
+
+    pub fn matches(self, df: &DataflowInfo) -> bool {
+        match self {
+            StatusFilter::All => true,
+            StatusFilter::Running => df.is_running(),
+            StatusFilter::Finished => df.status.eq_ignore_ascii_case("finished"),
+            StatusFilter::Failed => df.is_failed(),
+        }
+    }
+}
+
+/// Indices of the dataflows matching `search` (see
+/// [`DataflowInfo::matches_search`]) and `status`.
+pub fn filter_dataflows(
+    dataflows: &[DataflowInfo],
+    search: &str,
+    status: StatusFilter,
+) -> Vec<usize> {
+    dataflows
+        .iter()
+        .enumerate()
+        .filter(|(_, df)| status.matches(df) && df.matches_search(search))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Columns the table can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortColumn {
+    Name,
+    Status,
+    Nodes,
+    Started,
+}
+
+impl SortColumn {
+    pub const ALL: [SortColumn; 4] = [
+        SortColumn::Name,
+        SortColumn::Status,
+        SortColumn::Nodes,
+        SortColumn::Started,
+    ];
+
+    pub fn label(self) -> String {
+        i18n::tr(match self {
+            SortColumn::Name => "dataflows.column.name",
+            SortColumn::Status => "dataflows.column.status",
+            SortColumn::Nodes => "dataflows.column.nodes",
+            SortColumn::Started => "dataflows.column.started",
+        })
+    }
+
+    /// Running first, then failed, finished and any other status
+    fn status_rank(df: &DataflowInfo) -> u8 {
+        if df.is_running() {
+            0
+        } else if df.is_failed() {
+            1
+        } else if df.status.eq_ignore_ascii_case("finished") {
+            2
+        } else {
+            3
+        }
+    }
+
+    fn compare(self, a: &DataflowInfo, b: &DataflowInfo) -> Ordering {
+        let by_name = || a.name.to_lowercase().cmp(&b.name.to_lowercase());
+        match self {
+            SortColumn::Name => by_name(),
+            SortColumn::Status => Self::status_rank(a)
+                .cmp(&Self::status_rank(b))
+                .then_with(by_name),
+            SortColumn::Nodes => a.nodes.cmp(&b.nodes).then_with(by_name),
+            // Unknown start times last
+            SortColumn::Started => match (a.started_at_ms, b.started_at_ms) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+            .then_with(by_name),
+        }
+    }
+}
+
+/// Order of the table rows, persisted as settings `dataflow_sort`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataflowSort {
+    pub column: SortColumn,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+impl DataflowSort {
+    /// Sort after clicking the header of `column`: ascending, then the
+    /// other way round on a second click
+    pub fn clicked(current: Option<DataflowSort>, column: SortColumn) -> DataflowSort {
+        DataflowSort {
+            column,
+            descending: current.is_some_and(|s| s.column == column && !s.descending),
+        }
+    }
+}
+
+/// Put the rows at `indices` in `sort` order; in listing order when unset.
+pub fn sort_rows(dataflows: &[DataflowInfo], indices: &mut [usize], sort: Option<DataflowSort>) {
+    let Some(sort) = sort else {
+        return;
+    };
+    indices.sort_by(|&a, &b| {
+        let order = sort.column.compare(&dataflows[a], &dataflows[b]);
+        if sort.descending {
+            order.reverse()
+        } else {
+            order
+        }
+    });
+}
+
+/// How long a row stays highlighted after its status changed
+const FLASH_MS: u64 = 1_500;
+
+// ROW_BG, ROW_ALT_BG, ROW_SELECTED_BG and a pale yellow, for rows coloured
+// per state
+const ROW_COLOR: Vec4 = Vec4 {
+    x: 1.0,
+    y: 1.0,
+    z: 1.0,
+    w: 1.0,
+};
+const ROW_ALT_COLOR: Vec4 = Vec4 {
+    x: 0.973,
+    y: 0.980,
+    z: 0.988,
+    w: 1.0,
+};
+const ROW_SELECTED_COLOR: Vec4 = Vec4 {
+    x: 0.749,
+    y: 0.859,
+    z: 0.996,
+    w: 1.0,
+};
+const ROW_FLASH_COLOR: Vec4 = Vec4 {
+    x: 0.996,
+    y: 0.976,
+    z: 0.765,
+    w: 1.0,
+};
+
+/// What merging a new listing into the table changed.
+#[derive(Debug, Default, PartialEq)]
+pub struct TableDiff {
+    /// Rows that differ from before, as indices into the merged list
+    pub changed_rows: Vec<usize>,
+    /// UUIDs of dataflows that were listed before with another status
+    pub status_changed: Vec<String>,
+    /// Rows were added or removed
+    pub reshaped: bool,
+}
+
+impl TableDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changed_rows.is_empty() && !self.reshaped
+    }
+}
+
+/// Merge `listed` into `rows` by UUID: rows keep their place and are updated
+/// in place, vanished ones are dropped and new ones appended in listed
+/// order.
+pub fn merge_dataflows(rows: &mut Vec<DataflowInfo>, listed: Vec<DataflowInfo>) -> TableDiff {
+    let mut diff = TableDiff::default();
+    let index: HashMap<String, usize> = listed
+        .iter()
+        .enumerate()
+        .map(|(i, df)| (df.uuid.clone(), i))
+        .collect();
+    let mut listed: Vec<Option<DataflowInfo>> = listed.into_iter().map(Some).collect();
+
+    let before = rows.len();
+    rows.retain(|df| index.contains_key(&df.uuid));
+    diff.reshaped = rows.len() != before;
+    for (row, current) in rows.iter_mut().enumerate() {
+        let Some(new) = index.get(&current.uuid).and_then(|&i| listed[i].take()) else {
+            continue;
+        };
+        if new != *current {
+            if new.status != current.status {
+                diff.status_changed.push(new.uuid.clone());
+            }
+            *current = new;
+            diff.changed_rows.push(row);
+        }
+    }
+    for new in listed.into_iter().flatten() {
+        diff.changed_rows.push(rows.len());
+        diff.reshaped = true;
+        rows.push(new);
+    }
+    diff
 }
 
 /// Actions emitted by the DataflowTable
@@ -374,10 +988,12 @@ impl DataflowInfo {
 pub enum DataflowTableAction {
     None,
     Refresh,
-    Stop(String),     // uuid
-    Destroy(String),  // uuid
-    ViewLogs(String), // uuid
-    SelectRow(usize), // row index
+    Stop(String),             // uuid
+    Destroy(String),          // uuid
+    ViewLogs(String),         // uuid
+    SummarizeLogs(String),    // uuid
+    DataflowSelected(String), // uuid
+    SortChanged(DataflowSort),
 }
 
 /// Loading state for the table
@@ -387,6 +1003,7 @@ pub enum TableLoadingState {
     Idle,
     Loading,
     Error,
+    Offline,
 }
 
 #[derive(Live, LiveHook, Widget)]
@@ -397,14 +1014,40 @@ pub struct DataflowTable {
     dataflows: Vec<DataflowInfo>,
     #[rust]
     loading_state: TableLoadingState,
+    /// UUID of the selected dataflow, kept across refreshes
+    #[rust]
+    selected_uuid: Option<String>,
+    /// Rows whose status just changed, by UUID, until when to highlight
+    /// them (ms)
+    #[rust]
+    flash_until: HashMap<String, u64>,
     #[rust]
-    selected_row: Option<usize>,
+    next_frame: NextFrame,
+    /// Text in the search box
+    #[rust]
+    search: String,
+    #[rust]
+    status_filter: StatusFilter,
+    /// Row order; listing order when unset
+    #[rust]
+    sort: Option<DataflowSort>,
     #[rust]
     error_message: String,
 }
 
 impl Widget for DataflowTable {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if self.next_frame.is_event(event).is_some() && !self.flash_until.is_empty() {
+            let now_ms = logging::now_ms();
+            let before = self.flash_until.len();
+            self.flash_until.retain(|_, until| *until > now_ms);
+            if self.flash_until.len() != before {
+                self.view.portal_list(ids!(table_list)).redraw(cx);
+            }
+            if !self.flash_until.is_empty() {
+                self.next_frame = cx.new_next_frame();
+            }
+        }
         self.view.handle_event(cx, event, scope);
         self.widget_match_event(cx, event, scope);
     }
@@ -421,16 +1064,79 @@ impl Widget for DataflowTable {
 
 impl WidgetMatchEvent for DataflowTable {
     fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope) {
-        log!(
-            "[DataflowTable] handle_actions called, actions count: {}",
+        tracing::trace!(
+            target: "DataflowTable",
+            "handle_actions called, actions count: {}",
             actions.len()
         );
 
         // Handle row action buttons via PortalList
+        if let Some(search) = self.view.text_input(ids!(search_input)).changed(actions) {
+            self.search = search;
+            self.filter_changed(cx);
+        }
+        if let Some(index) = self
+            .view
+            .drop_down(ids!(status_filter_dropdown))
+            .selected(actions)
+        {
+            self.status_filter = StatusFilter::ALL.get(index).copied().unwrap_or_default();
+            self.filter_changed(cx);
+        }
+
+        let header = self.view.view(ids!(header));
+        let clicked = [
+            header.button(ids!(sort_name_button)).clicked(actions),
+            header.button(ids!(sort_status_button)).clicked(actions),
+            header.button(ids!(sort_nodes_button)).clicked(actions),
+            header.button(ids!(sort_started_button)).clicked(actions),
+        ];
+        if let Some(column) = SortColumn::ALL
+            .into_iter()
+            .zip(clicked)
+            .find_map(|(column, clicked)| clicked.then_some(column))
+        {
+            let sort = DataflowSort::clicked(self.sort, column);
+            self.set_sort(cx, Some(sort));
+            cx.widget_action(
+                self.widget_uid(),
+                &scope.path,
+                DataflowTableAction::SortChanged(sort),
+            );
+        }
+
+        let rows = self.visible_rows();
         let table_list = self.view.portal_list(ids!(table_list));
         for (item_id, item) in table_list.items_with_actions(actions) {
-            if item_id < self.dataflows.len() {
-                let uuid = self.dataflows[item_id].uuid.clone();
+            if let Some(&index) = rows.get(item_id) {
+                let uuid = self.dataflows[index].uuid.clone();
+
+                let started_cell = item.view(ids!(started_cell));
+                let tooltip = self.view.tooltip(ids!(started_tooltip));
+                if let Some(hover) = started_cell.finger_hover_in(actions) {
+                    if let Some(at) = self.dataflows[index].started_at_ms {
+                        tooltip.show_with_options(cx, hover.abs, &datetime::format_rfc3339(at));
+                    }
+                }
+                if started_cell.finger_hover_out(actions).is_some() {
+                    tooltip.hide(cx);
+                }
+
+                // Clicking a row or acting on it selects it
+                let clicked = item.as_view().finger_down(actions).is_some()
+                    || item.button(ids!(stop_button)).clicked(actions)
+                    || item.button(ids!(destroy_button)).clicked(actions)
+                    || item.button(ids!(logs_button)).clicked(actions)
+                    || item.button(ids!(summarize_button)).clicked(actions);
+                if clicked && self.selected_uuid.as_ref() != Some(&uuid) {
+                    self.selected_uuid = Some(uuid.clone());
+                    self.view.portal_list(ids!(table_list)).redraw(cx);
+                    cx.widget_action(
+                        self.widget_uid(),
+                        &scope.path,
+                        DataflowTableAction::DataflowSelected(uuid.clone()),
+                    );
+                }
 
                 if item.button(ids!(stop_button)).clicked(actions) {
                     cx.widget_action(
@@ -455,18 +1161,52 @@ impl WidgetMatchEvent for DataflowTable {
                         DataflowTableAction::ViewLogs(uuid.clone()),
                     );
                 }
+
+                if item.button(ids!(summarize_button)).clicked(actions) {
+                    cx.widget_action(
+                        self.widget_uid(),
+                        &scope.path,
+                        DataflowTableAction::SummarizeLogs(uuid.clone()),
+                    );
+                }
             }
         }
     }
 }
 
 impl DataflowTable {
-    /// Set the dataflows to display
+    /// Set the dataflows to display, updating rows in place so that the
+    /// scroll position and selection survive a refresh
     pub fn set_dataflows(&mut self, cx: &mut Cx, dataflows: Vec<DataflowInfo>) {
-        log!("[DataflowTable] set_dataflows: {} items", dataflows.len());
-        self.dataflows = dataflows;
+        tracing::debug!(target: "DataflowTable", "set_dataflows: {} items", dataflows.len());
+        let was_idle = self.loading_state == TableLoadingState::Idle;
         self.loading_state = TableLoadingState::Idle;
-        log!("[DataflowTable] calling redraw");
+        let diff = merge_dataflows(&mut self.dataflows, dataflows);
+
+        if !diff.status_changed.is_empty() {
+            let until = logging::now_ms() + FLASH_MS;
+            for uuid in diff.status_changed {
+                self.flash_until.insert(uuid, until);
+            }
+            self.next_frame = cx.new_next_frame();
+        }
+        if diff.reshaped {
+            let listed: HashSet<&str> = self.dataflows.iter().map(|df| df.uuid.as_str()).collect();
+            self.flash_until
+                .retain(|uuid, _| listed.contains(uuid.as_str()));
+            if self
+                .selected_uuid
+                .as_ref()
+                .is_some_and(|uuid| !listed.contains(uuid.as_str()))
+            {
+                self.selected_uuid = None;
+            }
+        }
+        if was_idle && diff.is_empty() {
+            return;
+        }
+        self.update_filter_count(cx);
+        tracing::trace!(target: "DataflowTable", "calling redraw");
         // Redraw the PortalList specifically to ensure it updates
         self.view.portal_list(ids!(table_list)).redraw(cx);
         self.redraw(cx);
@@ -488,8 +1228,11 @@ impl DataflowTable {
         self.redraw(cx);
     }
 
-    /// Set loading state
+    /// Set loading state; rows already listed stay shown while they refresh
     pub fn set_loading(&mut self, cx: &mut Cx) {
+        if !self.dataflows.is_empty() {
+            return;
+        }
         self.loading_state = TableLoadingState::Loading;
         self.view.portal_list(ids!(table_list)).redraw(cx);
         self.redraw(cx);
@@ -503,11 +1246,27 @@ impl DataflowTable {
         self.redraw(cx);
     }
 
+    /// Set offline state, dropping dataflows that can no longer be managed
+    pub fn set_offline(&mut self, cx: &mut Cx) {
+        self.loading_state = TableLoadingState::Offline;
+        self.dataflows.clear();
+        self.selected_uuid = None;
+        self.flash_until.clear();
+        self.update_filter_count(cx);
+        self.view.portal_list(ids!(table_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
     /// Get current dataflows
     pub fn get_dataflows(&self) -> &[DataflowInfo] {
         &self.dataflows
     }
 
+    /// Get the loading state
+    pub fn loading_state(&self) -> TableLoadingState {
+        self.loading_state
+    }
+
     /// Get dataflow by index
     pub fn get_dataflow(&self, index: usize) -> Option<&DataflowInfo> {
         self.dataflows.get(index)
@@ -518,42 +1277,163 @@ impl DataflowTable {
         self.dataflows.iter().find(|df| df.uuid == uuid)
     }
 
+    /// Indices of the dataflows shown, as narrowed by the search box and
+    /// status filter, in sort order
+    fn visible_rows(&self) -> Vec<usize> {
+        let mut rows = filter_dataflows(&self.dataflows, &self.search, self.status_filter);
+        sort_rows(&self.dataflows, &mut rows, self.sort);
+        rows
+    }
+
+    /// Show the text set in `live_design` in the current language
+    pub fn localize(&mut self, cx: &mut Cx) {
+        i18n::localize(
+            cx,
+            &[
+                (ids!(uuid_header), "dataflows.column.uuid"),
+                (ids!(uptime_header), "dataflows.column.uptime"),
+                (ids!(daemon_header), "dataflows.column.daemon"),
+                (ids!(cpu_header), "dataflows.column.cpu"),
+                (ids!(memory_header), "dataflows.column.memory"),
+                (ids!(actions_header), "dataflows.column.actions"),
+            ],
+            |path| self.view.widget(path),
+        );
+        i18n::localize_labels(
+            cx,
+            &self.view.drop_down(ids!(status_filter_dropdown)),
+            &[
+                "filter.all",
+                "filter.running",
+                "filter.finished",
+                "filter.failed",
+            ],
+        );
+        self.set_sort(cx, self.sort);
+    }
+
+    /// Sort the rows, marking the sorted column's header with ▲ or ▼
+    pub fn set_sort(&mut self, cx: &mut Cx, sort: Option<DataflowSort>) {
+        self.sort = sort;
+        let header = self.view.view(ids!(header));
+        let buttons = [
+            header.button(ids!(sort_name_button)),
+            header.button(ids!(sort_status_button)),
+            header.button(ids!(sort_nodes_button)),
+            header.button(ids!(sort_started_button)),
+        ];
+        for (column, button) in SortColumn::ALL.into_iter().zip(buttons) {
+            let text = match sort {
+                Some(sort) if sort.column == column => {
+                    format!(
+                        "{} {}",
+                        column.label(),
+                        if sort.descending { "▼" } else { "▲" }
+                    )
+                }
+                _ => column.label(),
+            };
+            button.set_text(cx, &text);
+        }
+        self.view.portal_list(ids!(table_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    fn filter_changed(&mut self, cx: &mut Cx) {
+        self.update_filter_count(cx);
+        self.view.portal_list(ids!(table_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    /// e.g. `3 of 24`, while the search or status filter hides some rows
+    fn update_filter_count(&mut self, cx: &mut Cx) {
+        let shown = self.visible_rows().len();
+        let text = if shown == self.dataflows.len() {
+            String::new()
+        } else {
+            format!("{} of {}", shown, self.dataflows.len())
+        };
+        self.view
+            .label(ids!(filter_count_label))
+            .set_text(cx, &text);
+    }
+
+    /// UUID of the selected dataflow
+    pub fn selected_uuid(&self) -> Option<&str> {
+        self.selected_uuid.as_deref()
+    }
+
+    /// Highlight the row of `uuid`, e.g. when opened from a link
+    pub fn select(&mut self, cx: &mut Cx, uuid: &str) {
+        self.selected_uuid = Some(uuid.to_string());
+        self.view.portal_list(ids!(table_list)).redraw(cx);
+    }
+
     /// Clear all dataflows
     pub fn clear(&mut self, cx: &mut Cx) {
         self.dataflows.clear();
-        self.selected_row = None;
+        self.selected_uuid = None;
+        self.flash_until.clear();
+        self.update_filter_count(cx);
         self.loading_state = TableLoadingState::Idle;
         self.view.portal_list(ids!(table_list)).redraw(cx);
         self.redraw(cx);
     }
 
     fn draw_rows(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
-        log!(
-            "[DataflowTable] draw_rows called, loading_state={:?}, dataflows.len()={}",
+        tracing::trace!(
+            target: "DataflowTable",
+            "draw_rows called, loading_state={:?}, dataflows.len()={}",
             self.loading_state,
             self.dataflows.len()
         );
 
         // Show loading state
         if self.loading_state == TableLoadingState::Loading {
-            log!("[DataflowTable] showing loading state");
+            tracing::trace!(target: "DataflowTable", "showing loading state");
             list.set_item_range(cx, 0, 1);
             while let Some(item_id) = list.next_visible_item(cx) {
                 if item_id == 0 {
                     let item = list.item(cx, item_id, live_id!(LoadingState));
+                    item.label(ids!(loading_label))
+                        .set_text(cx, &i18n::tr("dataflows.loading"));
                     item.draw_all(cx, &mut Scope::empty());
                 }
             }
             return;
         }
 
-        // Show empty state if no dataflows
-        if self.dataflows.is_empty() {
-            log!("[DataflowTable] showing empty state");
+        // Show offline state when no coordinator is reachable
+        if self.loading_state == TableLoadingState::Offline {
+            list.set_item_range(cx, 0, 1);
+            while let Some(item_id) = list.next_visible_item(cx) {
+                if item_id == 0 {
+                    let item = list.item(cx, item_id, live_id!(OfflineState));
+                    item.label(ids!(title_label))
+                        .set_text(cx, &i18n::tr("dataflows.offline.title"));
+                    item.label(ids!(hint_label))
+                        .set_text(cx, &i18n::tr("dataflows.offline.hint"));
+                    item.draw_all(cx, &mut Scope::empty());
+                }
+            }
+            return;
+        }
+
+        // Show empty state if no dataflows, or none match the filter
+        let rows = self.visible_rows();
+        if rows.is_empty() {
+            tracing::trace!(target: "DataflowTable", "showing empty state");
+            let (title, hint) = if self.dataflows.is_empty() {
+                ("dataflows.empty.title", "dataflows.empty.hint")
+            } else {
+                ("dataflows.no_match.title", "dataflows.no_match.hint")
+            };
             list.set_item_range(cx, 0, 1);
             while let Some(item_id) = list.next_visible_item(cx) {
                 if item_id == 0 {
                     let item = list.item(cx, item_id, live_id!(EmptyState));
+                    item.label(ids!(title_label)).set_text(cx, &i18n::tr(title));
+                    item.label(ids!(hint_label)).set_text(cx, &i18n::tr(hint));
                     item.draw_all(cx, &mut Scope::empty());
                 }
             }
@@ -561,12 +1441,13 @@ impl DataflowTable {
         }
 
         // Draw data rows
-        log!("[DataflowTable] drawing {} data rows", self.dataflows.len());
-        list.set_item_range(cx, 0, self.dataflows.len());
+        tracing::trace!(target: "DataflowTable", "drawing {} data rows", rows.len());
+        list.set_item_range(cx, 0, rows.len());
+        let now_ms = logging::now_ms();
 
         while let Some(item_id) = list.next_visible_item(cx) {
-            if item_id < self.dataflows.len() {
-                let df = &self.dataflows[item_id];
+            if let Some(&index) = rows.get(item_id) {
+                let df = &self.dataflows[index];
 
                 // Alternate row colors
                 let template = if item_id % 2 == 0 {
@@ -576,18 +1457,44 @@ impl DataflowTable {
                 };
 
                 let item = list.item(cx, item_id, template);
+                let color = if self.flash_until.contains_key(&df.uuid) {
+                    ROW_FLASH_COLOR
+                } else if self.selected_uuid.as_ref() == Some(&df.uuid) {
+                    ROW_SELECTED_COLOR
+                } else if item_id % 2 == 0 {
+                    ROW_COLOR
+                } else {
+                    ROW_ALT_COLOR
+                };
+                item.apply_over(cx, live! { draw_bg: { color: (color) } });
 
                 // Set row data
                 item.label(ids!(uuid_label)).set_text(cx, &df.uuid_short());
                 item.label(ids!(name_label)).set_text(cx, &df.name);
-                item.label(ids!(status_label)).set_text(cx, &df.status);
+                item.label(ids!(status_label))
+                    .set_text(cx, &df.display_status());
+                item.label(ids!(nodes_label))
+                    .set_text(cx, &df.nodes_formatted());
+                item.label(ids!(started_label))
+                    .set_text(cx, &df.started_formatted());
+                item.label(ids!(uptime_label))
+                    .set_text(cx, &df.uptime_formatted(now_ms));
+                item.label(ids!(daemon_label))
+                    .set_text(cx, &df.daemon_formatted());
                 item.label(ids!(cpu_label))
                     .set_text(cx, &df.cpu_formatted());
                 item.label(ids!(memory_label))
                     .set_text(cx, &df.memory_formatted());
-
-                log!(
-                    "[DataflowTable] Drawing row {}: uuid={}, name={}, status={}, cpu={}, mem={}",
+                item.button(ids!(summarize_button))
+                    .set_text(cx, &i18n::tr("action.summarize"));
+                item.button(ids!(stop_button))
+                    .set_text(cx, &i18n::tr("action.stop"));
+                item.button(ids!(destroy_button))
+                    .set_text(cx, &i18n::tr("action.kill"));
+
+                tracing::trace!(
+                    target: "DataflowTable",
+                    "Drawing row {}: uuid={}, name={}, status={}, cpu={}, mem={}",
                     item_id,
                     df.uuid_short(),
                     df.name,
@@ -605,15 +1512,16 @@ impl DataflowTable {
 impl DataflowTableRef {
     /// Set the dataflows to display
     pub fn set_dataflows(&self, cx: &mut Cx, dataflows: Vec<DataflowInfo>) {
-        log!(
-            "[DataflowTableRef] set_dataflows called with {} items",
+        tracing::debug!(
+            target: "DataflowTableRef",
+            "set_dataflows called with {} items",
             dataflows.len()
         );
         if let Some(mut inner) = self.borrow_mut() {
-            log!("[DataflowTableRef] borrow_mut succeeded, setting dataflows");
+            tracing::trace!(target: "DataflowTableRef", "borrow_mut succeeded, setting dataflows");
             inner.set_dataflows(cx, dataflows);
         } else {
-            log!("[DataflowTableRef] borrow_mut returned None!");
+            tracing::warn!(target: "DataflowTableRef", "borrow_mut returned None!");
         }
     }
 
@@ -645,6 +1553,72 @@ impl DataflowTableRef {
         }
     }
 
+    /// Set offline state
+    pub fn set_offline(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_offline(cx);
+        }
+    }
+
+    pub fn localize(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.localize(cx);
+        }
+    }
+
+    /// Sort the rows; listing order when `None`
+    pub fn set_sort(&self, cx: &mut Cx, sort: Option<DataflowSort>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_sort(cx, sort);
+        }
+    }
+
+    /// The sort picked by clicking a column header, if it changed
+    pub fn sort_changed(&self, actions: &Actions) -> Option<DataflowSort> {
+        match self.action(actions)? {
+            DataflowTableAction::SortChanged(sort) => Some(sort),
+            _ => None,
+        }
+    }
+
+    /// Check if a row was selected, returns its UUID if so
+    pub fn selected_changed(&self, actions: &Actions) -> Option<String> {
+        match self.action(actions)? {
+            DataflowTableAction::DataflowSelected(uuid) => Some(uuid),
+            _ => None,
+        }
+    }
+
+    /// UUID of the selected dataflow
+    pub fn selected_uuid(&self) -> Option<String> {
+        self.borrow()?.selected_uuid().map(String::from)
+    }
+
+    pub fn select(&self, cx: &mut Cx, uuid: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.select(cx, uuid);
+        }
+    }
+
+    /// Get a copy of the dataflow with the given UUID
+    pub fn dataflow_by_uuid(&self, uuid: &str) -> Option<DataflowInfo> {
+        self.borrow()?.get_dataflow_by_uuid(uuid).cloned()
+    }
+
+    /// Get a copy of the current dataflows
+    pub fn dataflows(&self) -> Vec<DataflowInfo> {
+        self.borrow()
+            .map(|inner| inner.get_dataflows().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Get the loading state
+    pub fn loading_state(&self) -> TableLoadingState {
+        self.borrow()
+            .map(|inner| inner.loading_state())
+            .unwrap_or_default()
+    }
+
     /// Clear all dataflows
     pub fn clear(&self, cx: &mut Cx) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -664,12 +1638,13 @@ impl DataflowTableRef {
     /// Check if a stop button was clicked, returns the UUID if so
     pub fn stop_clicked(&self, actions: &Actions) -> Option<String> {
         if let Some(inner) = self.borrow() {
+            let rows = inner.visible_rows();
             let table_list = inner.view.portal_list(ids!(table_list));
             for (item_id, item) in table_list.items_with_actions(actions) {
-                if item_id < inner.dataflows.len()
-                    && item.button(ids!(stop_button)).clicked(actions)
-                {
-                    return Some(inner.dataflows[item_id].uuid.clone());
+                if let Some(&index) = rows.get(item_id) {
+                    if item.button(ids!(stop_button)).clicked(actions) {
+                        return Some(inner.dataflows[index].uuid.clone());
+                    }
                 }
             }
         }
@@ -679,12 +1654,13 @@ impl DataflowTableRef {
     /// Check if a destroy button was clicked, returns the UUID if so
     pub fn destroy_clicked(&self, actions: &Actions) -> Option<String> {
         if let Some(inner) = self.borrow() {
+            let rows = inner.visible_rows();
             let table_list = inner.view.portal_list(ids!(table_list));
             for (item_id, item) in table_list.items_with_actions(actions) {
-                if item_id < inner.dataflows.len()
-                    && item.button(ids!(destroy_button)).clicked(actions)
-                {
-                    return Some(inner.dataflows[item_id].uuid.clone());
+                if let Some(&index) = rows.get(item_id) {
+                    if item.button(ids!(destroy_button)).clicked(actions) {
+                        return Some(inner.dataflows[index].uuid.clone());
+                    }
                 }
             }
         }
@@ -694,12 +1670,29 @@ impl DataflowTableRef {
     /// Check if a logs button was clicked, returns the UUID if so
     pub fn logs_clicked(&self, actions: &Actions) -> Option<String> {
         if let Some(inner) = self.borrow() {
+            let rows = inner.visible_rows();
             let table_list = inner.view.portal_list(ids!(table_list));
             for (item_id, item) in table_list.items_with_actions(actions) {
-                if item_id < inner.dataflows.len()
-                    && item.button(ids!(logs_button)).clicked(actions)
-                {
-                    return Some(inner.dataflows[item_id].uuid.clone());
+                if let Some(&index) = rows.get(item_id) {
+                    if item.button(ids!(logs_button)).clicked(actions) {
+                        return Some(inner.dataflows[index].uuid.clone());
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Check if a summarize button was clicked, returns the UUID if so
+    pub fn summarize_logs_clicked(&self, actions: &Actions) -> Option<String> {
+        if let Some(inner) = self.borrow() {
+            let rows = inner.visible_rows();
+            let table_list = inner.view.portal_list(ids!(table_list));
+            for (item_id, item) in table_list.items_with_actions(actions) {
+                if let Some(&index) = rows.get(item_id) {
+                    if item.button(ids!(summarize_button)).clicked(actions) {
+                        return Some(inner.dataflows[index].uuid.clone());
+                    }
                 }
             }
         }
@@ -738,6 +1731,89 @@ mod tests {
         assert!(!dataflows[1].is_running());
     }
 
+    #[test]
+    fn test_parse_schema_variants() {
+        let input = r#"[
+            {"id":"a1","name":null,"status":{"Failed":"exit 1"},"nodes":["camera","plot"],
+             "started_at":"2024-05-01T10:00:00.250+02:00","uptime":"1h 2m 3s","daemon":{"machine_id":"lab-1"}},
+            {"uuid":"b2","status":"Running","node_count":"3","cpu":"12.5%","start_time":1714557600,
+             "uptime_secs":90,"daemons":["lab-1","lab-2"]},
+            {"uuid":"c3","status":"Stopped","nodes":{"camera":{}},"started":1714557600123,"uptime":"01:00:05"},
+            "not an object"
+        ]"#;
+        let dataflows = DataflowInfo::parse_json_array(input);
+        assert_eq!(dataflows.len(), 3);
+
+        let a = &dataflows[0];
+        assert_eq!(
+            (a.uuid.as_str(), a.name.as_str(), a.status.as_str()),
+            ("a1", "", "Failed")
+        );
+        assert_eq!(a.nodes, 2);
+        assert_eq!(a.started_at_ms, Some(1_714_550_400_250));
+        assert_eq!(a.uptime_secs, Some(3723));
+        assert_eq!(a.daemon_id.as_deref(), Some("lab-1"));
+
+        let b = &dataflows[1];
+        assert_eq!((b.nodes, b.cpu), (3, 12.5));
+        assert_eq!(b.started_at_ms, Some(1_714_557_600_000));
+        assert_eq!(b.uptime_secs, Some(90));
+        assert_eq!(b.daemon_id.as_deref(), Some("lab-1, lab-2"));
+
+        // Unexpected types are left unset
+        let c = &dataflows[2];
+        assert_eq!(c.nodes, 0);
+        assert_eq!(c.started_at_ms, Some(1_714_557_600_123));
+        assert_eq!(c.uptime_secs, Some(3605));
+        assert_eq!(c.daemon_id, None);
+    }
+
+    #[test]
+    fn test_timestamp_and_duration_parsing() {
+        assert_eq!(parse_rfc3339_ms("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            parse_rfc3339_ms("2024-02-29 23:59:59.5"),
+            Some(1_709_251_199_500)
+        );
+        assert_eq!(
+            parse_rfc3339_ms("2024-05-01T08:00:00-01:30"),
+            Some(1_714_555_800_000)
+        );
+        assert_eq!(parse_rfc3339_ms("yesterday"), None);
+        assert_eq!(duration_secs(&Value::from("2d 3h")), Some(183_600));
+        assert_eq!(duration_secs(&Value::from("1m30s")), Some(90));
+        assert_eq!(duration_secs(&Value::from("1500ms")), Some(1));
+        assert_eq!(duration_secs(&Value::from("soon")), None);
+        assert_eq!(duration_secs(&Value::from(-4)), None);
+    }
+
+    #[test]
+    fn test_uptime_formatted() {
+        let df = DataflowInfo {
+            status: "Running".to_string(),
+            started_at_ms: Some(1_000),
+            ..Default::default()
+        };
+        assert_eq!(df.uptime_formatted(43_000), "42s");
+        assert_eq!(df.uptime_formatted(1_000 + 303_000), "5m 03s");
+        assert_eq!(df.uptime_formatted(1_000 + 7_800_000), "2h 10m");
+        assert_eq!(df.started_formatted(), "00:00:01");
+
+        // Reported uptime wins; a stopped dataflow has none of its own
+        let reported = DataflowInfo {
+            uptime_secs: Some(273_600),
+            ..df.clone()
+        };
+        assert_eq!(reported.uptime_formatted(0), "3d 4h");
+        let stopped = DataflowInfo {
+            status: "Stopped".to_string(),
+            ..df.clone()
+        };
+        assert_eq!(stopped.uptime_formatted(43_000), "-");
+        assert_eq!(DataflowInfo::default().nodes_formatted(), "-");
+        assert_eq!(DataflowInfo::default().daemon_formatted(), "-");
+    }
+
     #[test]
     fn test_parse_json_array_empty() {
         let input = "[]";
@@ -761,6 +1837,7 @@ mod tests {
             nodes: 1,
             cpu: 0.0,
             memory: 0.036,
+            ..Default::default()
         };
         assert_eq!(df.memory_formatted(), "37 MB");
 
@@ -786,6 +1863,7 @@ mod tests {
             nodes: 1,
             cpu: 45.678,
             memory: 0.0,
+            ..Default::default()
         };
         assert_eq!(df.cpu_formatted(), "45.7%");
     }
@@ -799,6 +1877,7 @@ mod tests {
             nodes: 1,
             cpu: 0.0,
             memory: 0.0,
+            ..Default::default()
         };
         assert_eq!(df.uuid_short(), "abc123de...");
 
@@ -818,6 +1897,7 @@ mod tests {
             nodes: 1,
             cpu: 0.0,
             memory: 0.0,
+            ..Default::default()
         };
         assert!(running.is_running());
 
@@ -835,6 +1915,149 @@ mod tests {
         assert!(running_lower.is_running());
     }
 
+    #[test]
+    fn test_display_status_flags_stale_running() {
+        let df = DataflowInfo {
+            status: "Running".to_string(),
+            stale: true,
+            ..Default::default()
+        };
+        assert_eq!(df.display_status(), "Running (stale)");
+
+        let stopped = DataflowInfo {
+            status: "Stopped".to_string(),
+            ..df.clone()
+        };
+        assert_eq!(stopped.display_status(), "Stopped");
+    }
+
+    #[test]
+    fn test_merge_dataflows_updates_in_place() {
+        let df = |uuid: &str, status: &str| DataflowInfo {
+            uuid: uuid.to_string(),
+            status: status.to_string(),
+            ..Default::default()
+        };
+        let mut rows = vec![df("a", "Running"), df("b", "Running"), df("c", "Running")];
+
+        // Unchanged listing, in another order
+        let diff = merge_dataflows(
+            &mut rows,
+            vec![df("c", "Running"), df("a", "Running"), df("b", "Running")],
+        );
+        assert!(diff.is_empty());
+        let uuids =
+            |rows: &[DataflowInfo]| rows.iter().map(|df| df.uuid.clone()).collect::<Vec<_>>();
+        assert_eq!(uuids(&rows), ["a", "b", "c"]);
+
+        // b failed, a got new metrics, c vanished, d is new
+        let mut busy = df("a", "Running");
+        busy.cpu = 40.0;
+        let diff = merge_dataflows(&mut rows, vec![df("d", "Running"), df("b", "Failed"), busy]);
+        assert_eq!(uuids(&rows), ["a", "b", "d"]);
+        assert_eq!(rows[0].cpu, 40.0);
+        assert_eq!(
+            diff,
+            TableDiff {
+                changed_rows: vec![0, 1, 2],
+                status_changed: vec!["b".to_string()],
+                reshaped: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_filter_dataflows() {
+        let df = |name: &str, status: &str, daemon: Option<&str>| DataflowInfo {
+            uuid: format!("{}-0191", name),
+            name: name.to_string(),
+            status: status.to_string(),
+            daemon_id: daemon.map(str::to_string),
+            ..Default::default()
+        };
+        let dataflows = vec![
+            df("camera", "Running", Some("lab-1")),
+            df("planner", "Finished", None),
+            df("Camera-old", "Failed", Some("lab-2")),
+            df("lidar", "running", Some("lab-2")),
+        ];
+        assert_eq!(
+            filter_dataflows(&dataflows, "", StatusFilter::All),
+            [0, 1, 2, 3]
+        );
+        assert_eq!(
+            filter_dataflows(&dataflows, " CAMERA ", StatusFilter::All),
+            [0, 2]
+        );
+        assert_eq!(
+            filter_dataflows(&dataflows, "lab-2", StatusFilter::Running),
+            [3]
+        );
+        assert_eq!(
+            filter_dataflows(&dataflows, "0191", StatusFilter::Finished),
+            [1]
+        );
+        assert_eq!(filter_dataflows(&dataflows, "", StatusFilter::Failed), [2]);
+        assert!(filter_dataflows(&dataflows, "radar", StatusFilter::All).is_empty());
+    }
+
+    #[test]
+    fn test_sort_rows() {
+        let df = |name: &str, status: &str, nodes: u32, started: Option<u64>| DataflowInfo {
+            name: name.to_string(),
+            status: status.to_string(),
+            nodes,
+            started_at_ms: started,
+            ..Default::default()
+        };
+        let dataflows = vec![
+            df("planner", "Finished", 2, Some(300)),
+            df("Camera", "Running", 5, None),
+            df("lidar", "Failed", 2, Some(100)),
+            df("arm", "Running", 1, Some(200)),
+        ];
+        let sorted = |sort: Option<DataflowSort>| {
+            let mut rows = vec![0, 1, 2, 3];
+            sort_rows(&dataflows, &mut rows, sort);
+            rows
+        };
+        let sort = |column, descending| Some(DataflowSort { column, descending });
+
+        assert_eq!(sorted(None), [0, 1, 2, 3]);
+        assert_eq!(sorted(sort(SortColumn::Name, false)), [3, 1, 2, 0]);
+        assert_eq!(sorted(sort(SortColumn::Status, false)), [3, 1, 2, 0]);
+        assert_eq!(sorted(sort(SortColumn::Nodes, true)), [1, 0, 2, 3]);
+        assert_eq!(sorted(sort(SortColumn::Started, false)), [2, 3, 0, 1]);
+
+        // A second click on the same header reverses the order
+        let first = DataflowSort::clicked(None, SortColumn::Nodes);
+        assert!(!first.descending);
+        assert!(DataflowSort::clicked(Some(first), SortColumn::Nodes).descending);
+        assert!(!DataflowSort::clicked(Some(first), SortColumn::Name).descending);
+    }
+
+    #[test]
+    fn test_newly_failed() {
+        let df = |uuid: &str, status: &str| DataflowInfo {
+            uuid: uuid.to_string(),
+            status: status.to_string(),
+            ..Default::default()
+        };
+        let previous: HashMap<String, String> = [
+            ("a".to_string(), "Running".to_string()),
+            ("b".to_string(), "Failed".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let current = vec![df("a", "Failed"), df("b", "Failed"), df("c", "failed")];
+
+        let failed: Vec<&str> = newly_failed(&previous, &current)
+            .iter()
+            .map(|df| df.uuid.as_str())
+            .collect();
+        assert_eq!(failed, vec!["a", "c"]);
+    }
+
     #[test]
     fn test_dataflow_info_default() {
         let df = DataflowInfo::default();
@@ -0,0 +1,360 @@
+//! Environment variables set on a dataflow's nodes when it starts.
+//!
+//! Variables are kept per dataflow path in the settings and edited in the
+//! [`super::env_editor::EnvEditor`] panel, or loaded from a `.env` file. dora
+//! spawns nodes from its daemon, so variables in the studio's own
+//! environment never reach them; instead the dataflow is started from a
+//! copy of its YAML with the variables added to every node's `env:`
+//! ([`with_node_env`]).
+
+use std::path::{Path, PathBuf};
+
+/// Whether `key` is usable as a variable name: letters, digits and `_`,
+/// not starting with a digit.
+pub fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Set `key` to `value`, keeping its place if it is already set.
+pub fn set_var(vars: &mut Vec<(String, String)>, key: &str, value: &str) {
+    match vars.iter_mut().find(|(k, _)| k == key) {
+        Some((_, v)) => *v = value.to_string(),
+        None => vars.push((key.to_string(), value.to_string())),
+    }
+}
+
+/// Variables of a `.env` file: `KEY=value` lines, optionally prefixed with
+/// `export`, with `#` comments and single- or double-quoted values. Later
+/// lines win over earlier ones.
+pub fn parse_dotenv(text: &str) -> Result<Vec<(String, String)>, String> {
+    let mut vars = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("Line {}: expected KEY=value", i + 1));
+        };
+        let key = key.trim();
+        if !is_valid_key(key) {
+            return Err(format!("Line {}: invalid variable name \"{}\"", i + 1, key));
+        }
+        let value = parse_value(value.trim()).map_err(|e| format!("Line {}: {}", i + 1, e))?;
+        set_var(&mut vars, key, &value);
+    }
+    Ok(vars)
+}
+
+fn parse_value(raw: &str) -> Result<String, String> {
+    let Some(quote) = raw.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+        // Unquoted values end at a comment
+        let value = raw.find(" #").map_or(raw, |i| &raw[..i]);
+        return Ok(value.trim_end().to_string());
+    };
+    let mut value = String::new();
+    let mut chars = raw[1..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == quote {
+            let rest = raw[1 + i + 1..].trim_start();
+            if !rest.is_empty() && !rest.starts_with('#') {
+                return Err("unexpected text after the closing quote".to_string());
+            }
+            return Ok(value);
+        }
+        if c == '\\' && quote == '"' {
+            match chars.next().map(|(_, c)| c) {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some(c) => value.push(c),
+                None => break,
+            }
+        } else {
+            value.push(c);
+        }
+    }
+    Err("missing closing quote".to_string())
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Whether the line holds YAML rather than being blank or a comment.
+fn is_content(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+/// The value after `key:` when `text` (without indentation) starts with
+/// that mapping key, without a trailing comment.
+fn key_value<'a>(text: &'a str, key: &str) -> Option<&'a str> {
+    let rest = text.strip_prefix(key)?.trim_start_matches(' ');
+    let value = rest.strip_prefix(':')?;
+    if !value.is_empty() && !value.starts_with(' ') {
+        return None;
+    }
+    let value = value.trim();
+    Some(if value.starts_with('#') { "" } else { value })
+}
+
+/// The key of a `key: value` line, e.g. for the variables already in a
+/// node's `env:`.
+fn line_key(line: &str) -> Option<&str> {
+    let (key, _) = line.trim().split_once(':')?;
+    Some(key.trim().trim_matches(|c| c == '"' || c == '\''))
+}
+
+/// `value` as a double-quoted YAML string, so dora reads it as text.
+fn yaml_string(value: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// `yaml` with `vars` added to the `env:` of every node, replacing node
+/// values of the same name. Only block-style `nodes:` lists and `env:` maps
+/// are understood, as dora's examples write them.
+pub fn with_node_env(yaml: &str, vars: &[(String, String)]) -> Result<String, String> {
+    let mut lines: Vec<String> = yaml.lines().map(str::to_string).collect();
+    let nodes = lines
+        .iter()
+        .position(|l| indent(l) == 0 && key_value(l, "nodes") == Some(""))
+        .ok_or("The dataflow has no `nodes:` list to add variables to")?;
+    let first = (nodes + 1..lines.len())
+        .find(|&i| is_content(&lines[i]))
+        .filter(|&i| lines[i].trim_start().starts_with('-'))
+        .ok_or("The dataflow's `nodes:` list is empty")?;
+    let item_indent = indent(&lines[first]);
+    let is_item = |l: &str| indent(l) == item_indent && l.trim_start().starts_with('-');
+    let end = (first..lines.len())
+        .find(|&i| is_content(&lines[i]) && indent(&lines[i]) <= item_indent && !is_item(&lines[i]))
+        .unwrap_or(lines.len());
+    let items: Vec<usize> = (first..end).filter(|&i| is_item(&lines[i])).collect();
+
+    // From the last node so earlier line numbers stay valid
+    for (n, &start) in items.iter().enumerate().rev() {
+        let stop = items.get(n + 1).copied().unwrap_or(end);
+        let after_dash = lines[start].trim_start()[1..].trim_start_matches(' ');
+        let key_indent = if after_dash.is_empty() {
+            (start + 1..stop)
+                .find(|&i| is_content(&lines[i]))
+                .map_or(item_indent + 2, |i| indent(&lines[i]))
+        } else {
+            lines[start].len() - after_dash.len()
+        };
+        let key_text = |i: usize| -> Option<&str> {
+            if i == start {
+                Some(after_dash)
+            } else if indent(&lines[i]) == key_indent {
+                Some(lines[i].trim_start())
+            } else {
+                None
+            }
+        };
+        let env = (start..stop).find(|&i| key_text(i).and_then(|t| key_value(t, "env")).is_some());
+        let pad = |width: usize| " ".repeat(width);
+
+        let Some(env) = env else {
+            let last = (start..stop)
+                .rev()
+                .find(|&i| is_content(&lines[i]))
+                .unwrap_or(start);
+            let mut block = vec![format!("{}env:", pad(key_indent))];
+            block.extend(
+                vars.iter()
+                    .map(|(k, v)| format!("{}{}: {}", pad(key_indent + 2), k, yaml_string(v))),
+            );
+            lines.splice(last + 1..last + 1, block);
+            continue;
+        };
+        if key_text(env).and_then(|t| key_value(t, "env")) != Some("") {
+            return Err(
+                "A node's `env:` is written inline; write it as a block to add variables"
+                    .to_string(),
+            );
+        }
+        let entries_end = (env + 1..stop)
+            .take_while(|&i| !is_content(&lines[i]) || indent(&lines[i]) > key_indent)
+            .filter(|&i| is_content(&lines[i]))
+            .last()
+            .map_or(env + 1, |i| i + 1);
+        let entry_indent = (env + 1..entries_end)
+            .find(|&i| is_content(&lines[i]))
+            .map_or(key_indent + 2, |i| indent(&lines[i]));
+        // Keep the node's own entries, less those set here and their
+        // continuation lines
+        let mut kept = Vec::new();
+        let mut replaced = false;
+        for line in &lines[env + 1..entries_end] {
+            if is_content(line) && indent(line) == entry_indent {
+                replaced = line_key(line).is_some_and(|k| vars.iter().any(|(v, _)| v == k));
+            }
+            if !replaced {
+                kept.push(line.clone());
+            }
+        }
+        kept.extend(
+            vars.iter()
+                .map(|(k, v)| format!("{}{}: {}", pad(entry_indent), k, yaml_string(v))),
+        );
+        lines.splice(env + 1..entries_end, kept);
+    }
+
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+/// Where the copy of `path` with variables added is written: next to it, so
+/// relative node paths resolve as in the original, and hidden.
+pub fn env_copy_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.studio-env.yml", stem))
+}
+
+/// The dataflow to start for `path` with `vars`: `path` itself when there
+/// are none, else a copy written by [`with_node_env`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn prepare_start(path: &str, vars: &[(String, String)]) -> Result<String, String> {
+    if vars.is_empty() {
+        return Ok(path.to_string());
+    }
+    let yaml =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let yaml = with_node_env(&yaml, vars).map_err(|e| format!("{}: {}", path, e))?;
+    let copy = env_copy_path(Path::new(path));
+    std::fs::write(&copy, yaml)
+        .map_err(|e| format!("Failed to write {}: {}", copy.display(), e))?;
+    Ok(copy.display().to_string())
+}
+
+/// Variables of the `.env` file at `path`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_dotenv(path: &str) -> Result<Vec<(String, String)>, String> {
+    let text =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    parse_dotenv(&text).map_err(|e| format!("{}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_dotenv() {
+        let text = "# camera\nexport DEVICE=/dev/video0\nFPS = 30 # per second\n\
+                    NAME=\"front \\\"cam\\\"\"\nRAW='a\\nb'\nEMPTY=\nFPS=60\n";
+        assert_eq!(
+            parse_dotenv(text).unwrap(),
+            vars(&[
+                ("DEVICE", "/dev/video0"),
+                ("FPS", "60"),
+                ("NAME", "front \"cam\""),
+                ("RAW", "a\\nb"),
+                ("EMPTY", ""),
+            ])
+        );
+        assert_eq!(
+            parse_dotenv("A=1\nB\n").unwrap_err(),
+            "Line 2: expected KEY=value"
+        );
+        assert!(parse_dotenv("1A=x")
+            .unwrap_err()
+            .contains("invalid variable name"));
+        assert!(parse_dotenv("A=\"open")
+            .unwrap_err()
+            .contains("closing quote"));
+    }
+
+    #[test]
+    fn test_with_node_env() {
+        let yaml = "\
+nodes:
+  - id: camera
+    path: ./camera.py
+    env:
+      FPS: 10
+      DEVICE: /dev/video1
+    outputs:
+      - image
+
+  - id: plot
+    path: ./plot.py
+    inputs:
+      image: camera/image
+# trailing comment
+";
+        let env = vars(&[("DEVICE", "/dev/video0"), ("NOTE", "say \"hi\"")]);
+        assert_eq!(
+            with_node_env(yaml, &env).unwrap(),
+            "\
+nodes:
+  - id: camera
+    path: ./camera.py
+    env:
+      FPS: 10
+      DEVICE: \"/dev/video0\"
+      NOTE: \"say \\\"hi\\\"\"
+    outputs:
+      - image
+
+  - id: plot
+    path: ./plot.py
+    inputs:
+      image: camera/image
+    env:
+      DEVICE: \"/dev/video0\"
+      NOTE: \"say \\\"hi\\\"\"
+# trailing comment
+"
+        );
+    }
+
+    #[test]
+    fn test_with_node_env_unindented_list() {
+        let yaml = "nodes:\n- id: a\n  path: a.py\n- env:\n    X: 1\n  id: b\ncommunication: {}\n";
+        assert_eq!(
+            with_node_env(yaml, &vars(&[("X", "2")])).unwrap(),
+            "nodes:\n- id: a\n  path: a.py\n  env:\n    X: \"2\"\n- env:\n    X: \"2\"\n  id: b\ncommunication: {}\n"
+        );
+        assert!(with_node_env("nodes: []\n", &vars(&[("X", "1")])).is_err());
+        assert!(with_node_env(
+            "nodes:\n  - id: a\n    env: { X: 1 }\n",
+            &vars(&[("X", "1")])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_env_copy_path() {
+        assert_eq!(
+            env_copy_path(Path::new("robot/dataflow.yml")),
+            Path::new("robot/.dataflow.studio-env.yml")
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! Side panel editing the environment variables a dataflow starts with (see
+//! [`super::env`]): a key/value list with a row to add variables and one to
+//! load a `.env` file.
+//!
+//! The panel reports every change with [`EnvEditorAction::Changed`] for the
+//! app to save; loading a file is left to the app, which reads it and hands
+//! the merged variables back with [`EnvEditorRef::set_env`].
+
+use makepad_widgets::*;
+
+use super::env::{is_valid_key, set_var};
+use crate::i18n;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+
+    EditorButton = <Button> {
+        width: Fit, height: 24
+        draw_text: { text_style: { font_size: 10.0 } }
+        padding: { left: 8, right: 8 }
+    }
+
+    EnvRow = <View> {
+        width: Fill, height: Fit
+        flow: Right
+        align: { y: 0.5 }
+        padding: { top: 2, bottom: 2 }
+        spacing: 6
+
+        key_label = <Label> {
+            width: 110, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: <THEME_FONT_CODE> { font_size: 10.0 }
+            }
+        }
+        value_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: <THEME_FONT_CODE> { font_size: 10.0 }
+                wrap: Word
+            }
+        }
+        remove_button = <EditorButton> { text: "×" }
+    }
+
+    pub EnvEditor = {{EnvEditor}} {
+        width: 320, height: Fill
+        visible: false
+        flow: Down
+        show_bg: true
+        draw_bg: { color: #ffffff }
+        padding: { left: 12, right: 12, top: 8, bottom: 8 }
+        spacing: 6
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { y: 0.5 }
+
+            title_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 13.0 }
+                    wrap: Word
+                }
+            }
+            close_button = <EditorButton> { text: "×" }
+        }
+
+        status_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 10.0 }
+                wrap: Word
+            }
+        }
+
+        env_list = <PortalList> {
+            width: Fill, height: Fill
+            flow: Down
+
+            EnvRow = <EnvRow> {}
+        }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 4
+
+            key_input = <TextInput> {
+                width: 110, height: Fit
+                empty_text: "NAME"
+                draw_text: { text_style: { font_size: 10.0 } }
+            }
+            value_input = <TextInput> {
+                width: Fill, height: Fit
+                empty_text: "value"
+                draw_text: { text_style: { font_size: 10.0 } }
+            }
+            add_button = <EditorButton> { text: "Set" }
+        }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 4
+
+            file_input = <TextInput> {
+                width: Fill, height: Fit
+                empty_text: "path/to/.env"
+                draw_text: { text_style: { font_size: 10.0 } }
+            }
+            load_button = <EditorButton> { text: "Load" }
+            clear_button = <EditorButton> { text: "Clear" }
+        }
+    }
+}
+
+const HINT: &str = "set on every node when the dataflow starts";
+
+/// Actions emitted by the EnvEditor panel, with the dataflow path
+#[derive(Clone, Debug, DefaultNone)]
+pub enum EnvEditorAction {
+    None,
+    /// The variables were edited
+    Changed(String, Vec<(String, String)>),
+    /// Load the `.env` file at the second path
+    Load(String, String),
+    Close,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct EnvEditor {
+    #[deref]
+    view: View,
+    /// Dataflow path edited
+    #[rust]
+    path: Option<String>,
+    #[rust]
+    vars: Vec<(String, String)>,
+}
+
+impl Widget for EnvEditor {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                self.draw_rows(cx, &mut list);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for EnvEditor {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope) {
+        if self.view.button(ids!(close_button)).clicked(actions) {
+            self.close(cx);
+            cx.widget_action(self.widget_uid(), &scope.path, EnvEditorAction::Close);
+            return;
+        }
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+
+        let mut vars = self.vars.clone();
+        let env_list = self.view.portal_list(ids!(env_list));
+        for (item_id, item) in env_list.items_with_actions(actions) {
+            if item.button(ids!(remove_button)).clicked(actions) && item_id < vars.len() {
+                vars.remove(item_id);
+            }
+        }
+        let key_input = self.view.text_input(ids!(key_input));
+        let value_input = self.view.text_input(ids!(value_input));
+        let set = self.view.button(ids!(add_button)).clicked(actions)
+            || value_input.returned(actions).is_some();
+        if set {
+            let key = key_input.text().trim().to_string();
+            if is_valid_key(&key) {
+                set_var(&mut vars, &key, &value_input.text());
+                key_input.set_text(cx, "");
+                value_input.set_text(cx, "");
+            } else {
+                self.set_status(cx, &i18n::tr("env.invalid_name"));
+            }
+        }
+        if self.view.button(ids!(clear_button)).clicked(actions) {
+            vars.clear();
+        }
+        if vars != self.vars {
+            self.set_vars(cx, vars);
+            cx.widget_action(
+                self.widget_uid(),
+                &scope.path,
+                EnvEditorAction::Changed(path.clone(), self.vars.clone()),
+            );
+        }
+
+        let file_input = self.view.text_input(ids!(file_input));
+        let load = self.view.button(ids!(load_button)).clicked(actions)
+            || file_input.returned(actions).is_some();
+        let file = file_input.text().trim().to_string();
+        if load && !file.is_empty() {
+            cx.widget_action(
+                self.widget_uid(),
+                &scope.path,
+                EnvEditorAction::Load(path, file),
+            );
+        }
+    }
+}
+
+impl EnvEditor {
+    /// Show the text set in `live_design` in the current language
+    pub fn localize(&mut self, cx: &mut Cx) {
+        i18n::localize(
+            cx,
+            &[
+                (ids!(add_button), "env.set"),
+                (ids!(load_button), "env.load"),
+                (ids!(clear_button), "action.clear"),
+            ],
+            |path| self.view.widget(path),
+        );
+        if let Some(path) = self.path.clone() {
+            self.show_title(cx, &path);
+        }
+    }
+
+    fn show_title(&mut self, cx: &mut Cx, path: &str) {
+        self.view
+            .label(ids!(title_label))
+            .set_text(cx, &i18n::tr_args("env.title", &[("path", path)]));
+    }
+
+    /// Edit the variables of the dataflow at `path`.
+    pub fn open(&mut self, cx: &mut Cx, path: &str, vars: Vec<(String, String)>) {
+        self.path = Some(path.to_string());
+        self.show_title(cx, path);
+        self.set_vars(cx, vars);
+        self.view.set_visible(cx, true);
+        self.redraw(cx);
+    }
+
+    fn set_vars(&mut self, cx: &mut Cx, vars: Vec<(String, String)>) {
+        self.vars = vars;
+        let status = match self.vars.len() {
+            0 => format!("No variables · {}", HINT),
+            1 => format!("1 variable · {}", HINT),
+            n => format!("{} variables · {}", n, HINT),
+        };
+        self.set_status(cx, &status);
+        self.view.portal_list(ids!(env_list)).redraw(cx);
+    }
+
+    fn set_status(&mut self, cx: &mut Cx, status: &str) {
+        self.view.label(ids!(status_label)).set_text(cx, status);
+        self.redraw(cx);
+    }
+
+    pub fn close(&mut self, cx: &mut Cx) {
+        self.path = None;
+        self.view.set_visible(cx, false);
+        self.redraw(cx);
+    }
+
+    fn draw_rows(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        list.set_item_range(cx, 0, self.vars.len());
+        while let Some(item_id) = list.next_visible_item(cx) {
+            let Some((key, value)) = self.vars.get(item_id) else {
+                continue;
+            };
+            let item = list.item(cx, item_id, live_id!(EnvRow));
+            item.label(ids!(key_label)).set_text(cx, key);
+            item.label(ids!(value_label)).set_text(cx, value);
+            item.draw_all(cx, &mut Scope::empty());
+        }
+    }
+}
+
+impl EnvEditorRef {
+    pub fn localize(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.localize(cx);
+        }
+    }
+
+    pub fn open(&self, cx: &mut Cx, path: &str, vars: Vec<(String, String)>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.open(cx, path, vars);
+        }
+    }
+
+    /// Show `vars` for the dataflow at `path`, if it is the one open.
+    pub fn set_env(&self, cx: &mut Cx, path: &str, vars: Vec<(String, String)>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            if inner.path.as_deref() == Some(path) {
+                inner.set_vars(cx, vars);
+            }
+        }
+    }
+
+    pub fn close(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.close(cx);
+        }
+    }
+
+    /// Check if an EnvEditorAction was triggered
+    pub fn action(&self, actions: &Actions) -> Option<EnvEditorAction> {
+        actions.find_widget_action(self.widget_uid())?.cast()
+    }
+}
@@ -0,0 +1,248 @@
+//! The four golden signals of a dataflow: latency, traffic, errors and
+//! saturation.
+//!
+//! The first three come from the spans of the dataflow's nodes (spans whose
+//! service is one of its node ids) over the last [`SIGNAL_WINDOW_MS`];
+//! saturation is the busiest node's CPU from the metrics sampler. Each
+//! signal has a trend over the window for a sparkline and a level from
+//! fixed thresholds for colouring it.
+
+use crate::otlp::types::Span;
+use crate::traces::latency::LatencyStats;
+use crate::units::{self, Unit};
+
+/// Spans started longer ago than this are left out.
+pub const SIGNAL_WINDOW_MS: u64 = 5 * 60 * 1000;
+/// Points in each trend.
+pub const TREND_BUCKETS: usize = 12;
+
+/// p95 latency, in ms, above which the latency signal warns or is critical.
+const LATENCY_THRESHOLDS: (f64, f64) = (100.0, 500.0);
+/// Share of spans failing, in %.
+const ERROR_THRESHOLDS: (f64, f64) = (1.0, 5.0);
+/// CPU of the busiest node, in %.
+const SATURATION_THRESHOLDS: (f64, f64) = (70.0, 90.0);
+
+/// How a signal compares to its thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignalLevel {
+    /// Below the thresholds, or nothing to compare
+    #[default]
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl SignalLevel {
+    fn of(value: Option<f64>, (warning, critical): (f64, f64)) -> Self {
+        match value {
+            Some(v) if v >= critical => SignalLevel::Critical,
+            Some(v) if v >= warning => SignalLevel::Warning,
+            _ => SignalLevel::Normal,
+        }
+    }
+}
+
+/// One signal: its current value, `None` without data, and its trend,
+/// oldest first, with NaN where a bucket had no data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Signal {
+    pub value: Option<f64>,
+    pub trend: Vec<f64>,
+    pub level: SignalLevel,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GoldenSignals {
+    /// p95 span duration, in ms
+    pub latency: Signal,
+    pub p50_ms: Option<u64>,
+    pub p99_ms: Option<u64>,
+    /// Spans per second
+    pub traffic: Signal,
+    /// Share of spans failing, in %
+    pub errors: Signal,
+    /// CPU of the busiest node, in %
+    pub saturation: Signal,
+    /// Spans the signals were computed from
+    pub span_count: usize,
+}
+
+impl GoldenSignals {
+    /// Signals of the dataflow with `nodes` at `now_ms`, from `spans` and
+    /// the CPU samples of each of its nodes, oldest first.
+    pub fn compute(spans: &[Span], nodes: &[String], cpu: &[Vec<f64>], now_ms: u64) -> Self {
+        let since = now_ms.saturating_sub(SIGNAL_WINDOW_MS);
+        let spans: Vec<&Span> = spans
+            .iter()
+            .filter(|s| s.start_time_ms >= since && s.start_time_ms <= now_ms)
+            .filter(|s| nodes.contains(&s.service_name))
+            .collect();
+
+        let stats = LatencyStats::new(spans.iter().map(|s| s.duration_ms));
+        let p95 = stats.percentile(95).map(|ms| ms as f64);
+        // Over the time the spans cover, as the traces query is capped
+        let covered_ms = spans
+            .iter()
+            .map(|s| now_ms - s.start_time_ms)
+            .max()
+            .unwrap_or(0)
+            .max(1000);
+        let traffic = (!spans.is_empty()).then(|| spans.len() as f64 * 1000.0 / covered_ms as f64);
+        let errors = error_percent(&spans);
+        let saturation = busiest(cpu, 0);
+
+        let buckets = bucket_spans(&spans, since, now_ms);
+        let bucket_secs = SIGNAL_WINDOW_MS as f64 / TREND_BUCKETS as f64 / 1000.0;
+        let trend = |f: &dyn Fn(&[&Span]) -> Option<f64>| -> Vec<f64> {
+            buckets.iter().map(|b| f(b).unwrap_or(f64::NAN)).collect()
+        };
+        let cpu_points = cpu.iter().map(Vec::len).max().unwrap_or(0);
+        Self {
+            latency: Signal {
+                value: p95,
+                trend: trend(&|b| {
+                    LatencyStats::new(b.iter().map(|s| s.duration_ms))
+                        .percentile(95)
+                        .map(|ms| ms as f64)
+                }),
+                level: SignalLevel::of(p95, LATENCY_THRESHOLDS),
+            },
+            p50_ms: stats.percentile(50),
+            p99_ms: stats.percentile(99),
+            traffic: Signal {
+                value: traffic,
+                trend: buckets
+                    .iter()
+                    .map(|b| b.len() as f64 / bucket_secs)
+                    .collect(),
+                level: SignalLevel::Normal,
+            },
+            errors: Signal {
+                value: errors,
+                trend: trend(&error_percent),
+                level: SignalLevel::of(errors, ERROR_THRESHOLDS),
+            },
+            saturation: Signal {
+                value: saturation,
+                trend: (0..cpu_points)
+                    .rev()
+                    .map(|back| busiest(cpu, back).unwrap_or(f64::NAN))
+                    .collect(),
+                level: SignalLevel::of(saturation, SATURATION_THRESHOLDS),
+            },
+            span_count: spans.len(),
+        }
+    }
+
+    /// e.g. `p50 12 ms · p99 40 ms · 120 spans`.
+    pub fn summary(&self) -> String {
+        let (Some(p50), Some(p99)) = (self.p50_ms, self.p99_ms) else {
+            return format!(
+                "No spans from its nodes in the last {} min",
+                SIGNAL_WINDOW_MS / 60_000
+            );
+        };
+        format!(
+            "p50 {} · p99 {} · {} span{}",
+            units::format(p50 as f64, Unit::Milliseconds),
+            units::format(p99 as f64, Unit::Milliseconds),
+            self.span_count,
+            if self.span_count == 1 { "" } else { "s" }
+        )
+    }
+}
+
+fn error_percent(spans: &[&Span]) -> Option<f64> {
+    if spans.is_empty() {
+        return None;
+    }
+    let failed = spans.iter().filter(|s| s.has_error).count();
+    Some(failed as f64 * 100.0 / spans.len() as f64)
+}
+
+/// Highest CPU among the nodes `back` samples before their latest.
+fn busiest(cpu: &[Vec<f64>], back: usize) -> Option<f64> {
+    cpu.iter()
+        .filter_map(|samples| samples.len().checked_sub(back + 1).map(|i| samples[i]))
+        .reduce(f64::max)
+}
+
+/// `spans` in [`TREND_BUCKETS`] equal steps between `since` and `now_ms`.
+fn bucket_spans<'a>(spans: &[&'a Span], since: u64, now_ms: u64) -> Vec<Vec<&'a Span>> {
+    let mut buckets = vec![Vec::new(); TREND_BUCKETS];
+    let width = (now_ms - since).max(1) as f64 / TREND_BUCKETS as f64;
+    for span in spans {
+        let bucket = ((span.start_time_ms - since) as f64 / width) as usize;
+        buckets[bucket.min(TREND_BUCKETS - 1)].push(*span);
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(service: &str, start_time_ms: u64, duration_ms: u64, has_error: bool) -> Span {
+        Span {
+            trace_id: "trace-1".to_string(),
+            span_id: format!("span-{}", start_time_ms),
+            parent_span_id: None,
+            service_name: service.to_string(),
+            operation_name: "run".to_string(),
+            start_time_ms,
+            duration_ms,
+            status_code: if has_error { 2 } else { 0 },
+            has_error,
+            attributes: Default::default(),
+            events: Vec::new(),
+            links: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_signals_scoped_to_nodes() {
+        let now = 1_000_000;
+        let mut spans: Vec<Span> = (0..100)
+            .map(|i| span("camera", now - 10_000 + i * 100, 10 + i, i % 10 == 0))
+            .collect();
+        // Another dataflow's node, and a span from before the window
+        spans.push(span("plot", now - 1_000, 9_000, true));
+        spans.push(span("camera", now - SIGNAL_WINDOW_MS - 1, 9_000, true));
+        let nodes = ["camera".to_string()];
+        let cpu = [vec![20.0, 40.0], vec![95.0]];
+        let signals = GoldenSignals::compute(&spans, &nodes, &cpu, now);
+
+        assert_eq!(signals.span_count, 100);
+        assert_eq!(signals.latency.value, Some(104.0));
+        assert_eq!(signals.latency.level, SignalLevel::Warning);
+        assert_eq!(signals.p50_ms, Some(59));
+        assert_eq!(signals.p99_ms, Some(108));
+        assert_eq!(signals.traffic.value, Some(10.0));
+        assert_eq!(signals.errors.value, Some(10.0));
+        assert_eq!(signals.errors.level, SignalLevel::Critical);
+        assert_eq!(signals.saturation.value, Some(95.0));
+        assert_eq!(signals.saturation.trend, [20.0, 95.0]);
+        assert_eq!(signals.saturation.level, SignalLevel::Critical);
+
+        // All spans fell in the last bucket
+        assert_eq!(signals.latency.trend.len(), TREND_BUCKETS);
+        assert!(signals.latency.trend[0].is_nan());
+        assert_eq!(signals.latency.trend[TREND_BUCKETS - 1], 104.0);
+        assert_eq!(signals.summary(), "p50 59 ms · p99 108 ms · 100 spans");
+    }
+
+    #[test]
+    fn test_signals_without_data() {
+        let signals = GoldenSignals::compute(&[], &[], &[], 5_000);
+        assert_eq!(signals.latency.value, None);
+        assert_eq!(signals.traffic.value, None);
+        assert_eq!(signals.errors.level, SignalLevel::Normal);
+        assert_eq!(signals.saturation.value, None);
+        assert!(signals.saturation.trend.is_empty());
+        assert_eq!(
+            signals.summary(),
+            "No spans from its nodes in the last 5 min"
+        );
+    }
+}
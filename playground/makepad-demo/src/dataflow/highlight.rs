@@ -0,0 +1,140 @@
+//! User-defined highlight rules for the log viewer: log lines matching a
+//! rule's regex are shown on a background of its colour, e.g.
+//! `timeout|dropped` in orange. The first matching rule wins.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Colours a rule can pick, by name, as rgb.
+pub const HIGHLIGHT_COLORS: [(&str, u32); 6] = [
+    ("Red", 0xdc2626),
+    ("Orange", 0xea580c),
+    ("Yellow", 0xca8a04),
+    ("Green", 0x16a34a),
+    ("Blue", 0x2563eb),
+    ("Purple", 0x9333ea),
+];
+
+/// A highlight rule as saved in settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightRule {
+    /// Regex matched anywhere in the line
+    pub pattern: String,
+    /// `#rrggbb`, or one of the [`HIGHLIGHT_COLORS`] names
+    pub color: String,
+}
+
+impl HighlightRule {
+    /// Validate a rule entered by the user.
+    pub fn new(pattern: &str, color: &str) -> Result<Self, String> {
+        let rule = Self {
+            pattern: pattern.to_string(),
+            color: color.to_string(),
+        };
+        rule.compile()?;
+        Ok(rule)
+    }
+
+    fn compile(&self) -> Result<(Regex, u32), String> {
+        if self.pattern.is_empty() {
+            return Err("Enter a pattern to highlight".to_string());
+        }
+        let regex = Regex::new(&self.pattern)
+            .map_err(|e| format!("Invalid pattern \"{}\": {}", self.pattern, e))?;
+        let color =
+            parse_color(&self.color).ok_or_else(|| format!("Unknown colour \"{}\"", self.color))?;
+        Ok((regex, color))
+    }
+}
+
+/// `#rrggbb` or a [`HIGHLIGHT_COLORS`] name, in any case, as rgb.
+pub fn parse_color(color: &str) -> Option<u32> {
+    let color = color.trim();
+    if let Some(hex) = color.strip_prefix('#') {
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        return u32::from_str_radix(hex, 16).ok();
+    }
+    HIGHLIGHT_COLORS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(color))
+        .map(|(_, rgb)| *rgb)
+}
+
+/// e.g. `timeout|dropped → Orange · panic → #ff0000`.
+pub fn rules_text(rules: &[HighlightRule]) -> String {
+    rules
+        .iter()
+        .map(|r| format!("{} → {}", r.pattern, r.color))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// Compiled rules, ready to match lines against.
+#[derive(Debug, Clone, Default)]
+pub struct Highlighter {
+    rules: Vec<(Regex, u32)>,
+}
+
+impl Highlighter {
+    /// Compile `rules`, leaving out invalid ones, e.g. from a hand-edited
+    /// settings file, with the reasons why.
+    pub fn new(rules: &[HighlightRule]) -> (Self, Vec<String>) {
+        let mut compiled = Vec::new();
+        let mut errors = Vec::new();
+        for rule in rules {
+            match rule.compile() {
+                Ok(rule) => compiled.push(rule),
+                Err(e) => errors.push(e),
+            }
+        }
+        (Self { rules: compiled }, errors)
+    }
+
+    /// Colour of the first rule matching `line`.
+    pub fn color(&self, line: &str) -> Option<u32> {
+        self.rules
+            .iter()
+            .find(|(regex, _)| regex.is_match(line))
+            .map(|(_, color)| *color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlighter_first_match_wins() {
+        let rules = vec![
+            HighlightRule::new("(?i)panic|error", "red").unwrap(),
+            HighlightRule::new("timeout", "#0000ff").unwrap(),
+            HighlightRule {
+                pattern: "(unclosed".to_string(),
+                color: "Green".to_string(),
+            },
+        ];
+        let (highlighter, errors) = Highlighter::new(&rules);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("Invalid pattern \"(unclosed\""));
+        assert_eq!(highlighter.color("thread panicked"), Some(0xdc2626));
+        assert_eq!(highlighter.color("ERROR timeout"), Some(0xdc2626));
+        assert_eq!(highlighter.color("read timeout"), Some(0x0000ff));
+        assert_eq!(highlighter.color("frame 12"), None);
+        assert_eq!(
+            rules_text(&rules[..2]),
+            "(?i)panic|error → red · timeout → #0000ff"
+        );
+    }
+
+    #[test]
+    fn test_rule_validation() {
+        assert!(HighlightRule::new("", "Red").is_err());
+        assert!(HighlightRule::new("a[", "Red").is_err());
+        assert!(HighlightRule::new("a", "#12345").is_err());
+        assert!(HighlightRule::new("a", "teal").is_err());
+        assert_eq!(parse_color(" Blue "), Some(0x2563eb));
+        assert_eq!(parse_color("#1e293b"), Some(0x1e293b));
+    }
+}
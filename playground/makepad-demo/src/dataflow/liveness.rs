@@ -0,0 +1,165 @@
+//! Heartbeat-based liveness for running dataflows.
+//!
+//! `dora list` reports a dataflow as running for as long as its daemon does,
+//! including when every node has hung or stopped producing output. The
+//! tracker remembers when each running dataflow last showed activity — a span
+//! from one of its nodes, new log lines, or a node process using CPU — and a
+//! dataflow that has been quiet for longer than the configured period is
+//! flagged as stale.
+//!
+//! Only dataflows whose node processes the local sampler sees are judged.
+//! Those running on another machine, e.g. over SSH, have no samples and no
+//! way to tell which spans are theirs, so they are never flagged.
+
+use std::collections::{HashMap, HashSet};
+
+/// Default quiet period before a running dataflow is flagged as stale.
+pub const DEFAULT_STALE_AFTER_MINUTES: u64 = 5;
+
+/// A node sample above this CPU usage counts as activity; an idle or blocked
+/// process still reports a few tenths of a percent.
+pub const ACTIVE_CPU_PERCENT: f32 = 1.0;
+
+#[derive(Debug, Default)]
+pub struct LivenessTracker {
+    /// Last activity per dataflow UUID, in ms since the epoch.
+    last_activity: HashMap<String, u64>,
+    /// When each running dataflow was first seen; it is not stale before
+    /// the quiet period has passed since then.
+    running_since: HashMap<String, u64>,
+    /// Dataflow UUID per node id, learned from metrics samples, so spans
+    /// (whose service name is the node id) can be attributed. When two
+    /// dataflows share a node id the most recent sample wins.
+    node_dataflows: HashMap<String, String>,
+    /// Dataflows with at least one local node sample.
+    sampled: HashSet<String>,
+}
+
+impl LivenessTracker {
+    /// Record activity for a dataflow.
+    pub fn record(&mut self, dataflow_id: &str, timestamp_ms: u64) {
+        let last = self
+            .last_activity
+            .entry(dataflow_id.to_string())
+            .or_default();
+        *last = (*last).max(timestamp_ms);
+    }
+
+    /// Remember which dataflow a node belongs to, from a local sample of
+    /// its process.
+    pub fn learn_node(&mut self, node_id: &str, dataflow_id: &str) {
+        self.node_dataflows
+            .insert(node_id.to_string(), dataflow_id.to_string());
+        self.sampled.insert(dataflow_id.to_string());
+    }
+
+    /// Record activity for a node; returns false if its dataflow is unknown.
+    pub fn record_node(&mut self, node_id: &str, timestamp_ms: u64) -> bool {
+        match self.node_dataflows.get(node_id).cloned() {
+            Some(dataflow_id) => {
+                self.record(&dataflow_id, timestamp_ms);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sync with the dataflows currently reported as running, forgetting the
+    /// others.
+    pub fn observe_running(&mut self, running: &[String], now_ms: u64) {
+        self.running_since.retain(|id, _| running.contains(id));
+        self.last_activity.retain(|id, _| running.contains(id));
+        self.node_dataflows.retain(|_, id| running.contains(id));
+        self.sampled.retain(|id| running.contains(id));
+        for id in running {
+            self.running_since.entry(id.clone()).or_insert(now_ms);
+        }
+    }
+
+    pub fn last_activity(&self, dataflow_id: &str) -> Option<u64> {
+        self.last_activity.get(dataflow_id).copied()
+    }
+
+    /// Whether a running dataflow has been quiet for at least
+    /// `stale_after_ms`. A zero period disables the check, and dataflows
+    /// without local samples are never stale.
+    pub fn is_stale(&self, dataflow_id: &str, now_ms: u64, stale_after_ms: u64) -> bool {
+        if stale_after_ms == 0 || !self.sampled.contains(dataflow_id) {
+            return false;
+        }
+        let Some(&since) = self.running_since.get(dataflow_id) else {
+            return false;
+        };
+        let since = self
+            .last_activity(dataflow_id)
+            .map_or(since, |t| t.max(since));
+        now_ms.saturating_sub(since) >= stale_after_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: u64 = 60_000;
+
+    fn running(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_new_dataflow_gets_a_grace_period() {
+        let mut tracker = LivenessTracker::default();
+        tracker.observe_running(&running(&["a"]), 0);
+        tracker.learn_node("camera", "a");
+        assert!(!tracker.is_stale("a", 4 * MINUTE, 5 * MINUTE));
+        assert!(tracker.is_stale("a", 5 * MINUTE, 5 * MINUTE));
+        // Unknown and disabled never report stale
+        assert!(!tracker.is_stale("b", 60 * MINUTE, 5 * MINUTE));
+        assert!(!tracker.is_stale("a", 60 * MINUTE, 0));
+    }
+
+    #[test]
+    fn test_activity_resets_staleness() {
+        let mut tracker = LivenessTracker::default();
+        tracker.observe_running(&running(&["a"]), 0);
+        tracker.learn_node("camera", "a");
+        tracker.record("a", 4 * MINUTE);
+        // An older signal arriving late does not move the heartbeat back
+        tracker.record("a", MINUTE);
+        assert_eq!(tracker.last_activity("a"), Some(4 * MINUTE));
+        assert!(!tracker.is_stale("a", 8 * MINUTE, 5 * MINUTE));
+        assert!(tracker.is_stale("a", 9 * MINUTE, 5 * MINUTE));
+    }
+
+    #[test]
+    fn test_node_activity_is_attributed_to_its_dataflow() {
+        let mut tracker = LivenessTracker::default();
+        tracker.observe_running(&running(&["a"]), 0);
+        assert!(!tracker.record_node("camera", MINUTE));
+        tracker.learn_node("camera", "a");
+        assert!(tracker.record_node("camera", 2 * MINUTE));
+        assert_eq!(tracker.last_activity("a"), Some(2 * MINUTE));
+    }
+
+    #[test]
+    fn test_stopped_dataflows_are_forgotten() {
+        let mut tracker = LivenessTracker::default();
+        tracker.observe_running(&running(&["a", "b"]), 0);
+        tracker.learn_node("camera", "a");
+        tracker.record("a", MINUTE);
+        tracker.observe_running(&running(&["b"]), 2 * MINUTE);
+        assert_eq!(tracker.last_activity("a"), None);
+        assert!(!tracker.record_node("camera", 3 * MINUTE));
+        assert!(!tracker.is_stale("a", 60 * MINUTE, 5 * MINUTE));
+    }
+
+    #[test]
+    fn test_unsampled_dataflows_are_not_judged() {
+        let mut tracker = LivenessTracker::default();
+        // Running on another machine: listed, but never sampled here
+        tracker.observe_running(&running(&["remote"]), 0);
+        tracker.record("remote", MINUTE);
+        assert!(!tracker.is_stale("remote", 60 * MINUTE, 5 * MINUTE));
+    }
+}
@@ -0,0 +1,247 @@
+//! Bounded buffer of the log lines a viewer holds.
+//!
+//! `dora logs` returns everything a dataflow logged so far, so the viewer
+//! keeps only what each fetch adds ([`LogAnchor`]) in a ring buffer of
+//! at most [`LogBuffer::capacity`] lines. Lines pushed out are kept aside
+//! for the app to spill to local storage ([`LogBuffer::take_spilled`]),
+//! where the Query panel can still find them.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::node_logs::{Attributor, NodeLogLine};
+use crate::otlp::types::LogEntry;
+
+/// Lines held when the settings do not say otherwise.
+pub const DEFAULT_LOG_BUFFER_LINES: usize = 100_000;
+/// Fewest lines a buffer holds, whatever the settings say.
+pub const MIN_LOG_BUFFER_LINES: usize = 1_000;
+
+/// Where a fetched log ended: enough to tell what the next fetch adds
+/// without keeping the text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogAnchor {
+    /// Length of the fetched text
+    len: usize,
+    /// Its last non-blank line
+    last_line: Option<String>,
+}
+
+impl LogAnchor {
+    pub fn new(text: &str) -> Self {
+        Self {
+            len: text.len(),
+            last_line: last_line(text).map(str::to_string),
+        }
+    }
+
+    /// Lines of `current` logged after the anchored text: what follows it
+    /// when `current` extends it, else what follows its last line (the log
+    /// is a moving tail), else all of `current` (the log restarted).
+    pub fn new_lines(&self, current: &str) -> Vec<String> {
+        let lines = |text: &str| -> Vec<String> {
+            text.lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        let extends = current
+            .get(..self.len)
+            .is_some_and(|head| last_line(head) == self.last_line.as_deref());
+        if extends {
+            return lines(&current[self.len..]);
+        }
+        let current = lines(current);
+        let after = self
+            .last_line
+            .as_ref()
+            .and_then(|last| current.iter().rposition(|l| l == last))
+            .map_or(0, |i| i + 1);
+        current[after..].to_vec()
+    }
+}
+
+fn last_line(text: &str) -> Option<&str> {
+    text.lines().rev().find(|l| !l.trim().is_empty())
+}
+
+/// A spilled `line` of dataflow `uuid` as stored in the logs table, with
+/// the node as service and `timestamp_ms` the time it was spilled.
+pub fn log_entry(uuid: &str, line: &NodeLogLine, timestamp_ms: u64) -> LogEntry {
+    let node = line.node.clone().unwrap_or_default();
+    let mut attributes = HashMap::from([("dataflow.uuid".to_string(), uuid.to_string())]);
+    if !node.is_empty() {
+        attributes.insert("node.id".to_string(), node.clone());
+    }
+    LogEntry {
+        timestamp_ms,
+        severity: line
+            .level
+            .map(|l| l.as_str().to_string())
+            .unwrap_or_default(),
+        body: line.text.clone(),
+        service_name: node,
+        attributes,
+    }
+}
+
+/// Ring buffer of attributed log lines, oldest first.
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    lines: VecDeque<NodeLogLine>,
+    capacity: usize,
+    attributor: Attributor,
+    /// Lines pushed out and not taken yet
+    spilled: Vec<NodeLogLine>,
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOG_BUFFER_LINES)
+    }
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            capacity: capacity.max(MIN_LOG_BUFFER_LINES),
+            attributor: Attributor::default(),
+            spilled: Vec::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Hold up to `capacity` lines from now on, spilling any over it.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(MIN_LOG_BUFFER_LINES);
+        self.evict();
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&NodeLogLine> {
+        self.lines.get(index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &NodeLogLine> {
+        self.lines.iter()
+    }
+
+    /// Drop every line, e.g. when another dataflow is shown. Nothing is
+    /// spilled: `dora` still has them.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.attributor = Attributor::default();
+    }
+
+    /// Attribute `lines` to `known` nodes and append them.
+    pub fn push_lines(&mut self, lines: impl IntoIterator<Item = String>, known: &[String]) {
+        for line in lines {
+            let line = self.attributor.attribute(&line, known);
+            self.lines.push_back(line);
+        }
+        self.evict();
+    }
+
+    /// Attribute the lines held again, once the dataflow's nodes are known.
+    pub fn reattribute(&mut self, known: &[String]) {
+        self.attributor = Attributor::default();
+        for line in self.lines.iter_mut() {
+            *line = self.attributor.attribute(&line.text, known);
+        }
+    }
+
+    /// The lines pushed out since the last call.
+    pub fn take_spilled(&mut self) -> Vec<NodeLogLine> {
+        std::mem::take(&mut self.spilled)
+    }
+
+    fn evict(&mut self) {
+        let over = self.lines.len().saturating_sub(self.capacity);
+        self.spilled.extend(self.lines.drain(..over));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_log_lines(previous: &str, current: &str) -> Vec<String> {
+        LogAnchor::new(previous).new_lines(current)
+    }
+
+    #[test]
+    fn test_new_log_lines() {
+        assert_eq!(new_log_lines("a\nb\n", "a\nb\nc\n\nd\n"), ["c", "d"]);
+        // A moving tail continues after the last line seen
+        assert_eq!(new_log_lines("a\nb\nc\n", "b\nc\nd\n"), ["d"]);
+        // A restarted log is new from the start
+        assert_eq!(new_log_lines("a\nb\n", "x\ny\n"), ["x", "y"]);
+        assert!(new_log_lines("a\n", "a\n").is_empty());
+    }
+
+    #[test]
+    fn test_anchor_keeps_no_text() {
+        let anchor = LogAnchor::new("a\nb\n\n");
+        assert_eq!(anchor.last_line.as_deref(), Some("b"));
+        assert_eq!(anchor.new_lines("a\nb\n\nc\n"), ["c"]);
+        assert_eq!(LogAnchor::default().new_lines("a\n"), ["a"]);
+        // Not a char boundary in the new text: fall back to the last line
+        assert_eq!(LogAnchor::new("ab\n").new_lines("éé\nab\nz\n"), ["z"]);
+    }
+
+    #[test]
+    fn test_buffer_spills_oldest_lines() {
+        let mut buffer = LogBuffer::new(0);
+        assert_eq!(buffer.capacity(), MIN_LOG_BUFFER_LINES);
+        let lines = |range: std::ops::Range<usize>| range.map(|i| format!("camera: frame {}", i));
+        buffer.push_lines(lines(0..600), &[]);
+        buffer.push_lines(lines(600..1_200), &[]);
+        assert_eq!(buffer.len(), MIN_LOG_BUFFER_LINES);
+        assert_eq!(buffer.get(0).unwrap().text, "camera: frame 200");
+        assert_eq!(buffer.get(0).unwrap().node.as_deref(), Some("camera"));
+
+        let spilled = buffer.take_spilled();
+        assert_eq!(spilled.len(), 200);
+        assert_eq!(spilled[199].text, "camera: frame 199");
+        assert!(buffer.take_spilled().is_empty());
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert!(buffer.take_spilled().is_empty());
+    }
+
+    #[test]
+    fn test_reattribute_to_known_nodes() {
+        let mut buffer = LogBuffer::default();
+        buffer.push_lines(["note: starting".to_string()], &[]);
+        // Continues the line before
+        buffer.push_lines(["  details".to_string()], &[]);
+        assert_eq!(buffer.get(1).unwrap().node.as_deref(), Some("note"));
+
+        buffer.reattribute(&["camera".to_string()]);
+        assert!(buffer.iter().all(|l| l.node.is_none()));
+    }
+
+    #[test]
+    fn test_log_entry() {
+        let mut buffer = LogBuffer::default();
+        buffer.push_lines(["camera: WARN frame dropped".to_string()], &[]);
+        let entry = log_entry("df-1", buffer.get(0).unwrap(), 42);
+        assert_eq!(entry.timestamp_ms, 42);
+        assert_eq!(entry.severity, "WARN");
+        assert_eq!(entry.service_name, "camera");
+        assert_eq!(entry.body, "camera: WARN frame dropped");
+        assert_eq!(entry.attributes["dataflow.uuid"], "df-1");
+        assert_eq!(entry.attributes["node.id"], "camera");
+    }
+}
@@ -0,0 +1,109 @@
+//! Searching the log viewer: plain text, ignoring case, or a regex as
+//! written, with the matching lines stepped through one at a time.
+
+use regex::Regex;
+
+/// What the search bar looks for.
+#[derive(Debug, Clone)]
+pub enum LogSearch {
+    /// Lower-cased text, matched ignoring case
+    Plain(String),
+    Regex(Regex),
+}
+
+impl LogSearch {
+    /// The search for `query`, or `None` when it is empty.
+    pub fn new(query: &str, regex: bool) -> Result<Option<Self>, String> {
+        if query.is_empty() {
+            return Ok(None);
+        }
+        if !regex {
+            return Ok(Some(LogSearch::Plain(query.to_lowercase())));
+        }
+        Regex::new(query)
+            .map(|r| Some(LogSearch::Regex(r)))
+            .map_err(|e| format!("Invalid regex: {}", e))
+    }
+
+    pub fn is_match(&self, line: &str) -> bool {
+        match self {
+            LogSearch::Plain(text) => line.to_lowercase().contains(text.as_str()),
+            LogSearch::Regex(regex) => regex.is_match(line),
+        }
+    }
+}
+
+/// Indices of the `lines` matching `search`.
+pub fn match_indices<'a>(
+    lines: impl IntoIterator<Item = &'a str>,
+    search: &LogSearch,
+) -> Vec<usize> {
+    lines
+        .into_iter()
+        .enumerate()
+        .filter(|(_, line)| search.is_match(line))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// The match after (or before) `current` out of `count`, wrapping around;
+/// the first (or last) one when none is current.
+pub fn step_match(current: Option<usize>, count: usize, forward: bool) -> Option<usize> {
+    if count == 0 {
+        return None;
+    }
+    Some(match (current, forward) {
+        (Some(i), true) => (i + 1) % count,
+        (Some(i), false) => (i + count - 1) % count,
+        (None, true) => 0,
+        (None, false) => count - 1,
+    })
+}
+
+/// e.g. `3 of 12` or `No matches`.
+pub fn match_count_text(current: Option<usize>, count: usize) -> String {
+    match (current, count) {
+        (_, 0) => "No matches".to_string(),
+        (Some(i), count) => format!("{} of {}", i + 1, count),
+        (None, 1) => "1 match".to_string(),
+        (None, count) => format!("{} matches", count),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_search_ignores_case() {
+        let lines = ["camera: Timeout", "plot: drew", "camera: timeout again"];
+        let search = LogSearch::new("TIMEOUT", false).unwrap().unwrap();
+        assert_eq!(match_indices(lines, &search), [0, 2]);
+        // Regex characters are taken literally
+        let search = LogSearch::new("drew|x", false).unwrap().unwrap();
+        assert!(match_indices(lines, &search).is_empty());
+        assert!(LogSearch::new("", true).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_regex_search() {
+        let lines = ["frame 12", "frame x", "Frame 3"];
+        let search = LogSearch::new(r"frame \d+", true).unwrap().unwrap();
+        assert_eq!(match_indices(lines, &search), [0]);
+        assert!(LogSearch::new("(", true)
+            .unwrap_err()
+            .starts_with("Invalid regex"));
+    }
+
+    #[test]
+    fn test_step_match_wraps() {
+        assert_eq!(step_match(None, 3, true), Some(0));
+        assert_eq!(step_match(None, 3, false), Some(2));
+        assert_eq!(step_match(Some(2), 3, true), Some(0));
+        assert_eq!(step_match(Some(0), 3, false), Some(2));
+        assert_eq!(step_match(Some(0), 0, true), None);
+        assert_eq!(match_count_text(Some(2), 12), "3 of 12");
+        assert_eq!(match_count_text(None, 0), "No matches");
+        assert_eq!(match_count_text(None, 2), "2 matches");
+    }
+}
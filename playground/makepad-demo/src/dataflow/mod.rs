@@ -1,12 +1,50 @@
+pub mod ansi;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod client;
+pub mod connection;
+pub mod dataflow_detail;
 pub mod dataflow_table;
+pub mod env;
+pub mod env_editor;
+pub mod golden_signals;
+pub mod highlight;
+pub mod liveness;
+pub mod log_buffer;
+pub mod log_search;
+pub mod node_logs;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod node_metrics_panel;
+pub mod run_history;
+pub mod start_dialog;
+pub mod undo;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub use client::DoraClient;
+pub use connection::CoordinatorState;
+pub use dataflow_detail::{
+    DataflowDetail, DataflowDetailAction, DataflowDetailRef, DataflowDetailWidgetRefExt,
+};
 pub use dataflow_table::{
-    DataflowInfo, DataflowTable, DataflowTableAction, DataflowTableRef, DataflowTableWidgetRefExt,
-    TableLoadingState,
+    newly_failed, DataflowInfo, DataflowSort, DataflowTable, DataflowTableAction, DataflowTableRef,
+    DataflowTableWidgetRefExt, SortColumn, TableLoadingState,
+};
+pub use env_editor::{EnvEditor, EnvEditorAction, EnvEditorRef, EnvEditorWidgetRefExt};
+pub use liveness::LivenessTracker;
+#[cfg(not(target_arch = "wasm32"))]
+pub use node_metrics_panel::{NodeMetricsPanel, NodeMetricsPanelRef, NodeMetricsPanelWidgetRefExt};
+pub use run_history::{RunHistory, RunRecord};
+pub use start_dialog::{
+    is_dataflow_file, StartDialog, StartDialogAction, StartDialogRef, StartDialogWidgetRefExt,
 };
+pub use undo::{StartSpec, UndoEntry, UndoKind, UndoStack};
 
 use makepad_widgets::*;
 
 pub fn live_design(cx: &mut Cx) {
     dataflow_table::live_design(cx);
+    dataflow_detail::live_design(cx);
+    env_editor::live_design(cx);
+    start_dialog::live_design(cx);
+    #[cfg(not(target_arch = "wasm32"))]
+    node_metrics_panel::live_design(cx);
 }
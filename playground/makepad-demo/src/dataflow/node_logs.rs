@@ -0,0 +1,400 @@
+//! Attributing dora log output to nodes and levels.
+//!
+//! `dora logs` interleaves the output of every node of a dataflow. Lines
+//! name their node in a prefix, after any timestamp or level:
+//! `camera: frame 12`, `[2024-05-01T10:00:00Z][INFO][camera] frame 12` or
+//! `INFO camera: frame 12`; an upper-case level may also follow the node,
+//! as in `camera: WARN slow frame`. Lines naming neither, such as the rest
+//! of a panic message, belong to the line before. ANSI colour codes are
+//! taken out first (see [`super::ansi`]).
+
+use super::ansi;
+
+/// A log line, the node it came from and its level, when known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeLogLine {
+    pub node: Option<String>,
+    pub level: Option<LogLevel>,
+    /// The line without ANSI escape codes
+    pub text: String,
+    /// ANSI colour of most of the line (see [`super::ansi::main_color`])
+    pub color: Option<u32>,
+}
+
+/// Severity of a log line, least severe first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum LogLevel {
+    #[default]
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// In the order the level filter lists them.
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Trace,
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Warn,
+        LogLevel::Error,
+    ];
+
+    /// The level `word` names, in any case, e.g. `warning` or `FATAL`.
+    pub fn parse(word: &str) -> Option<Self> {
+        match word.to_ascii_uppercase().as_str() {
+            "TRACE" => Some(LogLevel::Trace),
+            "DEBUG" => Some(LogLevel::Debug),
+            "INFO" => Some(LogLevel::Info),
+            "WARN" | "WARNING" => Some(LogLevel::Warn),
+            "ERROR" | "FATAL" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "TRACE",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+
+    /// Label in the level filter, which shows this level and above.
+    pub fn filter_label(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "All levels",
+            LogLevel::Debug => "Debug+",
+            LogLevel::Info => "Info+",
+            LogLevel::Warn => "Warn+",
+            LogLevel::Error => "Error",
+        }
+    }
+}
+
+/// Which log lines to show and export.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogFilter {
+    /// Only lines from this node; every node when `None`
+    pub node: Option<String>,
+    /// Only lines at this level or above; lines without a level count as
+    /// [`LogLevel::Info`], like the plain output of a node.
+    pub min_level: LogLevel,
+}
+
+impl LogFilter {
+    pub fn matches(&self, line: &NodeLogLine) -> bool {
+        let level = line.level.unwrap_or(LogLevel::Info);
+        (self.node.is_none() || line.node == self.node) && level >= self.min_level
+    }
+
+    /// e.g. `node camera, WARN and above` or `all nodes, all levels`.
+    pub fn describe(&self) -> String {
+        let node = match &self.node {
+            Some(node) => format!("node {}", node),
+            None => "all nodes".to_string(),
+        };
+        let level = match self.min_level {
+            LogLevel::Trace => "all levels".to_string(),
+            LogLevel::Error => "ERROR only".to_string(),
+            level => format!("{} and above", level.as_str()),
+        };
+        format!("{}, {}", node, level)
+    }
+}
+
+/// Colours the panel gives node names, as rgb; dark enough to read on its
+/// light background.
+pub const NODE_COLORS: [u32; 8] = [
+    0x1d4ed8, 0xb45309, 0x047857, 0x7c3aed, 0xbe185d, 0x0e7490, 0x4d7c0f, 0xc2410c,
+];
+
+/// Stream names that can precede the node in a prefix.
+const STREAM_WORDS: [&str; 2] = ["STDOUT", "STDERR"];
+
+/// A prefix word: the contents of `[...]` or a word, with `:` when it ended
+/// in one.
+struct PrefixToken<'a> {
+    word: &'a str,
+    bracketed: bool,
+    colon: bool,
+}
+
+/// The node and level `line` names in its prefix. With `known` nodes, only
+/// those count as one.
+pub fn parse_prefix<'a>(line: &'a str, known: &[String]) -> (Option<&'a str>, Option<LogLevel>) {
+    let mut tokens = prefix_tokens(line);
+    let mut level = None;
+    while let Some(token) = tokens.next() {
+        if let Some(parsed) = LogLevel::parse(token.word) {
+            level = level.or(Some(parsed));
+        } else if token.word.starts_with(|c: char| c.is_ascii_digit()) || is_stream(token.word) {
+            // Timestamps and stream names
+        } else if token.bracketed || token.colon {
+            let listed = known.is_empty() || known.iter().any(|k| k == token.word);
+            if !(is_node_id(token.word) && listed) {
+                break;
+            }
+            // An upper-case level right after the node
+            let after = tokens
+                .next()
+                .filter(|t| t.word.bytes().all(|b| b.is_ascii_uppercase()))
+                .and_then(|t| LogLevel::parse(t.word));
+            return (Some(token.word), level.or(after));
+        } else {
+            break;
+        }
+    }
+    (None, level)
+}
+
+fn prefix_tokens(line: &str) -> impl Iterator<Item = PrefixToken<'_>> {
+    let mut rest = line.trim_start();
+    std::iter::from_fn(move || {
+        let token = if let Some(bracketed) = rest.strip_prefix('[') {
+            let (inner, after) = bracketed.split_once(']')?;
+            rest = after;
+            PrefixToken {
+                word: inner.trim(),
+                bracketed: true,
+                colon: false,
+            }
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let (word, after) = rest.split_at(end);
+            rest = after;
+            let stripped = word.strip_suffix(':');
+            PrefixToken {
+                word: stripped.unwrap_or(word),
+                bracketed: false,
+                colon: stripped.is_some(),
+            }
+        };
+        rest = rest.trim_start();
+        (!token.word.is_empty()).then_some(token)
+    })
+}
+
+/// Attributes lines one at a time, remembering the node and level of the
+/// last one for lines that continue it.
+#[derive(Debug, Clone, Default)]
+pub struct Attributor {
+    node: Option<String>,
+    level: Option<LogLevel>,
+}
+
+impl Attributor {
+    pub fn attribute(&mut self, line: &str, known: &[String]) -> NodeLogLine {
+        let (line, color) = if line.contains('\x1b') {
+            let spans = ansi::parse(line);
+            let color = ansi::main_color(&spans);
+            (spans.into_iter().map(|s| s.text).collect(), color)
+        } else {
+            (line.to_string(), None)
+        };
+        let (named_node, named_level) = parse_prefix(&line, known);
+        if let Some(id) = named_node {
+            self.node = Some(id.to_string());
+        }
+        // A line naming neither continues the one before
+        if named_node.is_some() || named_level.is_some() {
+            self.level = named_level;
+        }
+        NodeLogLine {
+            node: self.node.clone(),
+            level: self.level,
+            text: line,
+            color,
+        }
+    }
+}
+
+/// Split `logs` into non-blank lines attributed to their node and level.
+pub fn attribute_lines(logs: &str, known: &[String]) -> Vec<NodeLogLine> {
+    let mut attributor = Attributor::default();
+    logs.lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|line| attributor.attribute(line, known))
+        .collect()
+}
+
+/// Nodes appearing in `lines`, in order of first appearance.
+pub fn log_nodes<'a>(lines: impl IntoIterator<Item = &'a NodeLogLine>) -> Vec<String> {
+    let mut nodes: Vec<String> = Vec::new();
+    for node in lines.into_iter().filter_map(|l| l.node.as_ref()) {
+        if !nodes.contains(node) {
+            nodes.push(node.clone());
+        }
+    }
+    nodes
+}
+
+/// Indices of the `lines` `filter` lets through.
+pub fn matching_rows<'a>(
+    lines: impl IntoIterator<Item = &'a NodeLogLine>,
+    filter: &LogFilter,
+) -> Vec<usize> {
+    lines
+        .into_iter()
+        .enumerate()
+        .filter(|(_, l)| filter.matches(l))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// The lines `filter` lets through as a text file, headed by what was
+/// filtered on so the export is not mistaken for the full logs.
+pub fn export_text<'a>(
+    uuid: &str,
+    lines: impl IntoIterator<Item = &'a NodeLogLine>,
+    filter: &LogFilter,
+) -> String {
+    let mut text = format!("# Logs of dataflow {} ({})\n", uuid, filter.describe());
+    for line in lines.into_iter().filter(|l| filter.matches(l)) {
+        text.push_str(&line.text);
+        text.push('\n');
+    }
+    text
+}
+
+/// Colour of `node`, the same on every refresh and in every dataflow.
+pub fn node_color(node: &str) -> u32 {
+    // FNV-1a, stable across runs unlike the std hasher
+    let hash = node.bytes().fold(0x811c_9dc5_u32, |hash, b| {
+        (hash ^ b as u32).wrapping_mul(0x0100_0193)
+    });
+    NODE_COLORS[hash as usize % NODE_COLORS.len()]
+}
+
+fn is_node_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.chars().any(|c| c.is_ascii_alphabetic())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'))
+}
+
+fn is_stream(word: &str) -> bool {
+    STREAM_WORDS.iter().any(|w| w.eq_ignore_ascii_case(word))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_prefix() {
+        let none: &[String] = &[];
+        let node = |line: &str| parse_prefix(line, none).0.map(str::to_string);
+        assert_eq!(node("camera: frame 12").as_deref(), Some("camera"));
+        assert_eq!(
+            parse_prefix(
+                "[2024-05-01T10:00:00Z][INFO][object-detector] boxes: 3",
+                none
+            ),
+            (Some("object-detector"), Some(LogLevel::Info))
+        );
+        assert_eq!(
+            parse_prefix("2024-05-01 10:00:00 WARN plot: slow frame", none),
+            (Some("plot"), Some(LogLevel::Warn))
+        );
+        assert_eq!(
+            parse_prefix("camera: ERROR no device", none),
+            (Some("camera"), Some(LogLevel::Error))
+        );
+        assert_eq!(
+            parse_prefix("camera: info about frames", none),
+            (Some("camera"), None)
+        );
+        assert_eq!(node("[stderr] [camera] oops").as_deref(), Some("camera"));
+        assert_eq!(
+            parse_prefix("ERROR: no camera", none),
+            (None, Some(LogLevel::Error))
+        );
+        assert_eq!(parse_prefix("thread 'main' panicked", none), (None, None));
+        assert_eq!(node("[12:00:01] "), None);
+        assert_eq!(node("42: answer"), None);
+
+        let known = ["camera".to_string()];
+        assert_eq!(parse_prefix("camera: frame 12", &known).0, Some("camera"));
+        assert_eq!(parse_prefix("note: something", &known).0, None);
+    }
+
+    #[test]
+    fn test_attribute_coloured_line() {
+        let line = Attributor::default().attribute(
+            "\x1b[33mcamera: WARN slow frame\x1b[0m",
+            &["camera".to_string()],
+        );
+        assert_eq!(line.node.as_deref(), Some("camera"));
+        assert_eq!(line.level, Some(LogLevel::Warn));
+        assert_eq!(line.text, "camera: WARN slow frame");
+        assert_eq!(line.color, Some(0xca8a04));
+    }
+
+    #[test]
+    fn test_attribute_and_filter() {
+        let logs = "starting\ncamera: frame 1\nplot: ERROR drew nothing\nthread 'main' panicked\n  at src/main.rs\n\ncamera: DEBUG frame 2\n";
+        let lines = attribute_lines(logs, &[]);
+        let attributed: Vec<(Option<&str>, Option<LogLevel>)> =
+            lines.iter().map(|l| (l.node.as_deref(), l.level)).collect();
+        assert_eq!(
+            attributed,
+            [
+                (None, None),
+                (Some("camera"), None),
+                (Some("plot"), Some(LogLevel::Error)),
+                (Some("plot"), Some(LogLevel::Error)),
+                (Some("plot"), Some(LogLevel::Error)),
+                (Some("camera"), Some(LogLevel::Debug))
+            ]
+        );
+        assert_eq!(log_nodes(&lines), ["camera", "plot"]);
+
+        let camera = LogFilter {
+            node: Some("camera".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(matching_rows(&lines, &camera), [1, 5]);
+        assert_eq!(
+            matching_rows(&lines, &LogFilter::default()),
+            [0, 1, 2, 3, 4, 5]
+        );
+        let missing = LogFilter {
+            node: Some("missing".to_string()),
+            ..Default::default()
+        };
+        assert!(matching_rows(&lines, &missing).is_empty());
+
+        // Lines without a level count as INFO
+        let info = LogFilter {
+            min_level: LogLevel::Info,
+            ..camera
+        };
+        assert_eq!(matching_rows(&lines, &info), [1]);
+        let errors = LogFilter {
+            node: None,
+            min_level: LogLevel::Error,
+        };
+        assert_eq!(matching_rows(&lines, &errors), [2, 3, 4]);
+        assert_eq!(
+            export_text("0191", &lines, &info),
+            "# Logs of dataflow 0191 (node camera, INFO and above)\ncamera: frame 1\n"
+        );
+    }
+
+    #[test]
+    fn test_node_color_is_stable() {
+        assert_eq!(node_color("camera"), node_color("camera"));
+        assert!(NODE_COLORS.contains(&node_color("plot")));
+        let colors: std::collections::HashSet<u32> = ["camera", "plot", "detector", "sink"]
+            .iter()
+            .map(|n| node_color(n))
+            .collect();
+        assert!(colors.len() > 1);
+    }
+}
@@ -0,0 +1,578 @@
+use makepad_widgets::*;
+use std::cell::RefMut;
+use std::collections::{BTreeMap, HashMap};
+
+use super::node_logs;
+use crate::chart::{ChartSeries, TimeSeriesChartRef, TimeSeriesChartWidgetRefExt};
+use crate::i18n;
+use crate::metrics::{Legend, NodeSeries};
+use crate::otlp::types::TimeRange;
+use crate::text;
+use crate::units::{self, Unit, MIB};
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::chart::time_series_chart::TimeSeriesChart;
+
+    // Colors (reused from dataflow_table)
+    ROW_BG = #ffffff
+    ROW_ALT_BG = #f8fafc
+    ROW_HIGHLIGHT_BG = #dbeafe
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+    SPARK_CPU = #x0ea5e9
+    SPARK_MEM = #8b5cf6
+
+    NodeMetricsHeader = <View> {
+        width: Fill, height: 32
+        flow: Right
+        show_bg: true
+        draw_bg: { color: #f1f5f9 }
+        padding: { left: 16, right: 16 }
+        align: { y: 0.5 }
+        spacing: 8
+
+        dataflow_header = <Label> {
+            width: 140, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "DATAFLOW"
+        }
+        node_header = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "NODE"
+        }
+        pid_header = <Label> {
+            width: 60, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "PID"
+        }
+        cpu_header = <Label> {
+            width: 200, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "CPU"
+        }
+        memory_header = <Label> {
+            width: 200, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "MEMORY"
+        }
+    }
+
+    NodeMetricsRow = <View> {
+        width: Fill, height: 32
+        flow: Right
+        // A cursor makes the row report hover in/out
+        cursor: Hand
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+        padding: { left: 16, right: 16 }
+        align: { y: 0.5 }
+        spacing: 8
+
+        dataflow_label = <Label> {
+            width: 140, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        // Legend entry: click isolates the node, shift-click compares
+        legend_button = <Button> {
+            width: Fill, height: 28
+            align: { x: 0.0, y: 0.5 }
+            draw_bg: {
+                fn pixel(self) -> vec4 {
+                    return vec4(0.0, 0.0, 0.0, 0.0);
+                }
+            }
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        pid_label = <Label> {
+            width: 60, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        <View> {
+            width: 200, height: Fit
+            flow: Right
+            spacing: 6
+            cpu_label = <Label> {
+                width: 50, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 11.0 }
+                }
+            }
+            cpu_spark = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (SPARK_CPU),
+                    text_style: { font_size: 11.0 }
+                }
+            }
+        }
+        <View> {
+            width: 200, height: Fit
+            flow: Right
+            spacing: 6
+            memory_label = <Label> {
+                width: 60, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 11.0 }
+                }
+            }
+            memory_spark = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (SPARK_MEM),
+                    text_style: { font_size: 11.0 }
+                }
+            }
+        }
+    }
+
+    NodeMetricsRowAlt = <NodeMetricsRow> {
+        draw_bg: { color: (ROW_ALT_BG) }
+    }
+
+    NodeMetricsRowHighlight = <NodeMetricsRow> {
+        draw_bg: { color: (ROW_HIGHLIGHT_BG) }
+    }
+
+    NodeMetricsEmpty = <View> {
+        width: Fill, height: 60
+        align: { x: 0.5, y: 0.5 }
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+
+        empty_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 12.0 }
+            }
+            text: "No node metrics yet"
+        }
+    }
+
+    pub NodeMetricsPanel = {{NodeMetricsPanel}} {
+        width: Fill, height: Fit
+        flow: Down
+        padding: { top: 12 }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 12
+            padding: { bottom: 6 }
+
+            title_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 13.0 }
+                }
+                text: "Node metrics"
+            }
+
+            legend_status = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "Click a node to isolate it, shift-click to compare"
+            }
+
+            // Shown while samples come from local storage
+            stale_label = <Label> {
+                width: Fit, height: Fit
+                visible: false
+                draw_text: {
+                    color: #b45309,
+                    text_style: { font_size: 11.0 }
+                }
+                text: ""
+            }
+        }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 12
+            padding: { bottom: 6 }
+
+            <View> {
+                width: Fill, height: Fit
+                flow: Down
+                cpu_title = <Label> {
+                    width: Fit, height: Fit
+                    draw_text: {
+                        color: (TEXT_SECONDARY),
+                        text_style: { font_size: 11.0 }
+                    }
+                    text: "CPU"
+                }
+                cpu_chart = <TimeSeriesChart> { height: 140 }
+            }
+            <View> {
+                width: Fill, height: Fit
+                flow: Down
+                memory_title = <Label> {
+                    width: Fit, height: Fit
+                    draw_text: {
+                        color: (TEXT_SECONDARY),
+                        text_style: { font_size: 11.0 }
+                    }
+                    text: "MEMORY"
+                }
+                memory_chart = <TimeSeriesChart> { height: 140, area: true }
+            }
+        }
+
+        <NodeMetricsHeader> {}
+
+        node_list = <PortalList> {
+            width: Fill, height: 200
+            flow: Down
+
+            NodeMetricsRow = <NodeMetricsRow> {}
+            NodeMetricsRowAlt = <NodeMetricsRowAlt> {}
+            NodeMetricsRowHighlight = <NodeMetricsRowHighlight> {}
+            NodeMetricsEmpty = <NodeMetricsEmpty> {}
+        }
+    }
+}
+
+/// Metric names used to look up unit overrides.
+const CPU_METRIC: &str = "cpu_percent";
+const MEMORY_METRIC: &str = "memory_bytes";
+
+#[derive(Live, LiveHook, Widget)]
+pub struct NodeMetricsPanel {
+    #[deref]
+    view: View,
+    #[rust]
+    series: Vec<NodeSeries>,
+    /// Dataflow display names keyed by UUID.
+    #[rust]
+    dataflow_names: HashMap<String, String>,
+    /// Unit overrides by metric name from settings.
+    #[rust]
+    unit_overrides: BTreeMap<String, Unit>,
+    #[rust]
+    legend: Legend,
+    /// Indices into `series` of the rows shown, after legend filtering.
+    #[rust]
+    shown: Vec<usize>,
+}
+
+impl Widget for NodeMetricsPanel {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                self.draw_rows(cx, &mut list);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for NodeMetricsPanel {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        // Zoom and pan both charts together
+        let (cpu_chart, memory_chart) = self.charts();
+        if let Some(range) = cpu_chart.range_changed(actions) {
+            memory_chart.set_range(cx, range);
+        }
+        if let Some(range) = memory_chart.range_changed(actions) {
+            cpu_chart.set_range(cx, range);
+        }
+        // Hovering either shows the crosshair at that time on both
+        if let Some(time_ms) = cpu_chart.hovered(actions) {
+            memory_chart.set_crosshair(cx, time_ms);
+        }
+        if let Some(time_ms) = memory_chart.hovered(actions) {
+            cpu_chart.set_crosshair(cx, time_ms);
+        }
+
+        let node_list = self.view.portal_list(ids!(node_list));
+        let mut changed = false;
+        for (item_id, item) in node_list.items_with_actions(actions) {
+            let Some(key) = self.shown.get(item_id).map(|&i| self.series[i].key()) else {
+                continue;
+            };
+            if let Some(modifiers) = item.button(ids!(legend_button)).clicked_modifiers(actions) {
+                self.legend.click(&key, modifiers.shift);
+                changed = true;
+            }
+            if item.as_view().finger_hover_in(actions).is_some() {
+                changed |= self.legend.hover(Some(&key));
+            }
+            if item.as_view().finger_hover_out(actions).is_some()
+                && self.legend.is_highlighted(&key)
+            {
+                changed |= self.legend.hover(None);
+            }
+        }
+        if changed {
+            self.update_shown(cx);
+        }
+    }
+}
+
+impl NodeMetricsPanel {
+    /// Replace the displayed node series.
+    pub fn set_series(
+        &mut self,
+        cx: &mut Cx,
+        series: Vec<NodeSeries>,
+        dataflow_names: HashMap<String, String>,
+    ) {
+        self.series = series;
+        self.dataflow_names = dataflow_names;
+        let keys: Vec<String> = self.series.iter().map(NodeSeries::key).collect();
+        self.legend.retain(keys.iter().map(String::as_str));
+        self.update_shown(cx);
+    }
+
+    /// Apply the legend filter and show how many nodes are visible.
+    fn update_shown(&mut self, cx: &mut Cx) {
+        self.shown = (0..self.series.len())
+            .filter(|&i| self.legend.is_visible(&self.series[i].key()))
+            .collect();
+        let status = if self.legend.is_filtered() {
+            i18n::tr_args(
+                "node_metrics.legend_filtered",
+                &[
+                    ("shown", &self.shown.len().to_string()),
+                    ("total", &self.series.len().to_string()),
+                ],
+            )
+        } else {
+            i18n::tr("node_metrics.legend_hint")
+        };
+        self.view.label(ids!(legend_status)).set_text(cx, &status);
+        self.view.portal_list(ids!(node_list)).redraw(cx);
+        self.update_charts(cx);
+        self.redraw(cx);
+    }
+
+    /// Show the text set in `live_design` in the current language
+    pub fn localize(&mut self, cx: &mut Cx) {
+        i18n::localize(
+            cx,
+            &[
+                (ids!(title_label), "node_metrics.title"),
+                (ids!(cpu_title), "node_metrics.cpu"),
+                (ids!(memory_title), "node_metrics.memory"),
+                (ids!(dataflow_header), "node_metrics.column.dataflow"),
+                (ids!(node_header), "node_metrics.column.node"),
+                (ids!(pid_header), "node_metrics.column.pid"),
+                (ids!(cpu_header), "node_metrics.cpu"),
+                (ids!(memory_header), "node_metrics.memory"),
+            ],
+            |path| self.view.widget(path),
+        );
+        self.update_shown(cx);
+    }
+
+    pub fn set_unit_overrides(&mut self, cx: &mut Cx, overrides: BTreeMap<String, Unit>) {
+        self.unit_overrides = overrides;
+        self.update_charts(cx);
+        self.redraw(cx);
+    }
+
+    fn charts(&self) -> (TimeSeriesChartRef, TimeSeriesChartRef) {
+        (
+            self.view.widget(ids!(cpu_chart)).as_time_series_chart(),
+            self.view.widget(ids!(memory_chart)).as_time_series_chart(),
+        )
+    }
+
+    /// Chart the nodes shown, each in the colour its logs have, the one
+    /// hovered in the legend on top.
+    fn update_charts(&self, cx: &mut Cx) {
+        let chart_series = |values: fn(&NodeSeries) -> Vec<f64>| -> Vec<ChartSeries> {
+            self.shown
+                .iter()
+                .map(|&i| &self.series[i])
+                .map(|s| {
+                    ChartSeries::new(
+                        &s.node_id,
+                        node_logs::node_color(&s.node_id),
+                        s.timestamps_ms.iter().copied(),
+                        values(s),
+                    )
+                })
+                .collect()
+        };
+        let cpu = chart_series(|s| s.cpu.iter().copied().collect());
+        let memory = chart_series(|s| s.memory_mb.iter().map(|mb| mb * MIB).collect());
+        let (cpu_chart, memory_chart) = self.charts();
+        cpu_chart.set_unit(cx, units::unit_for(CPU_METRIC, &self.unit_overrides));
+        cpu_chart.set_series(cx, cpu);
+        memory_chart.set_unit(cx, units::unit_for(MEMORY_METRIC, &self.unit_overrides));
+        memory_chart.set_series(cx, memory);
+        let highlighted = self
+            .shown
+            .iter()
+            .position(|&i| self.legend.is_highlighted(&self.series[i].key()));
+        cpu_chart.set_highlighted(cx, highlighted);
+        memory_chart.set_highlighted(cx, highlighted);
+    }
+
+    fn draw_rows(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        if self.series.is_empty() {
+            list.set_item_range(cx, 0, 1);
+            while let Some(item_id) = list.next_visible_item(cx) {
+                if item_id == 0 {
+                    let item = list.item(cx, item_id, live_id!(NodeMetricsEmpty));
+                    item.label(ids!(empty_label))
+                        .set_text(cx, &i18n::tr("node_metrics.empty"));
+                    item.draw_all(cx, &mut Scope::empty());
+                }
+            }
+            return;
+        }
+
+        list.set_item_range(cx, 0, self.shown.len());
+
+        let cpu_unit = units::unit_for(CPU_METRIC, &self.unit_overrides);
+        let memory_unit = units::unit_for(MEMORY_METRIC, &self.unit_overrides);
+
+        while let Some(item_id) = list.next_visible_item(cx) {
+            if let Some(&index) = self.shown.get(item_id) {
+                let series = &self.series[index];
+
+                let template = if self.legend.is_highlighted(&series.key()) {
+                    live_id!(NodeMetricsRowHighlight)
+                } else if item_id % 2 == 0 {
+                    live_id!(NodeMetricsRow)
+                } else {
+                    live_id!(NodeMetricsRowAlt)
+                };
+
+                let item = list.item(cx, item_id, template);
+
+                let dataflow = self
+                    .dataflow_names
+                    .get(&series.dataflow_id)
+                    .filter(|name| !name.is_empty())
+                    .cloned()
+                    .unwrap_or_else(|| text::truncate_middle(&series.dataflow_id, 12));
+
+                item.label(ids!(dataflow_label)).set_text(cx, &dataflow);
+                item.button(ids!(legend_button))
+                    .set_text(cx, &series.node_id);
+                item.label(ids!(pid_label))
+                    .set_text(cx, &series.pid.to_string());
+                item.label(ids!(cpu_label))
+                    .set_text(cx, &units::format(series.latest_cpu(), cpu_unit));
+                item.label(ids!(cpu_spark))
+                    .set_text(cx, &text::sparkline(series.cpu.iter().copied()));
+                item.label(ids!(memory_label)).set_text(
+                    cx,
+                    &units::format(series.latest_memory_mb() * MIB, memory_unit),
+                );
+                item.label(ids!(memory_spark))
+                    .set_text(cx, &text::sparkline(series.memory_mb.iter().copied()));
+
+                item.draw_all(cx, &mut Scope::empty());
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Ref wrapper (same pattern as DataflowTableRef)
+// ---------------------------------------------------------------------------
+
+impl NodeMetricsPanelRef {
+    pub fn localize(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.localize(cx);
+        }
+    }
+
+    pub fn set_unit_overrides(&self, cx: &mut Cx, overrides: BTreeMap<String, Unit>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_unit_overrides(cx, overrides);
+        }
+    }
+
+    /// Mark the samples shown as stored data; `None` hides the marker.
+    pub fn set_stale(&self, cx: &mut Cx, banner: Option<&str>) {
+        if let Some(inner) = self.borrow() {
+            let label = inner.view.label(ids!(stale_label));
+            label.set_text(cx, banner.unwrap_or(""));
+            label.set_visible(cx, banner.is_some());
+        }
+    }
+
+    pub fn set_series(
+        &self,
+        cx: &mut Cx,
+        series: Vec<NodeSeries>,
+        dataflow_names: HashMap<String, String>,
+    ) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_series(cx, series, dataflow_names);
+        }
+    }
+
+    /// Times both charts are zoomed to, `None` when showing all the data
+    pub fn range(&self) -> Option<TimeRange> {
+        self.borrow()?.charts().0.range()
+    }
+
+    /// Zoom both charts to `range`, or show all the data when `None`.
+    pub fn set_range(&self, cx: &mut Cx, range: Option<TimeRange>) {
+        if let Some(inner) = self.borrow() {
+            let (cpu_chart, memory_chart) = inner.charts();
+            cpu_chart.set_range(cx, range.clone());
+            memory_chart.set_range(cx, range);
+        }
+    }
+
+    /// The range shown after a zoom, pan or reset of either chart in
+    /// `actions`, if any.
+    pub fn range_changed(&self, actions: &Actions) -> Option<Option<TimeRange>> {
+        let inner = self.borrow()?;
+        let (cpu_chart, memory_chart) = inner.charts();
+        cpu_chart
+            .range_changed(actions)
+            .or_else(|| memory_chart.range_changed(actions))
+    }
+}
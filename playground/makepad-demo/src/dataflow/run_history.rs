@@ -0,0 +1,259 @@
+//! History of observed dataflow runs.
+//!
+//! `dora list` reports only current state, so runs are reconstructed by
+//! diffing successive refreshes: a run starts when a UUID is first seen
+//! running and ends when it disappears or leaves the running state. Start
+//! times of dataflows already running when the studio launched are therefore
+//! the time they were first observed. The history is persisted to
+//! `run_history.json` in the data directory.
+//!
+//! Runs still open when the studio last closed are reconciled with the first
+//! listing of the next launch ([`RunHistory::reconcile`]): those still
+//! running stay open, the others end when they were last seen running.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::DataflowInfo;
+use crate::settings;
+
+const HISTORY_FILE: &str = "run_history.json";
+const MAX_RUNS: usize = 200;
+/// Padding added around a run window so edge spans are not cut off.
+const WINDOW_PADDING_MS: u64 = 1_000;
+/// How stale the saved last-seen time of an active run may get.
+const SEEN_SAVE_INTERVAL_MS: u64 = 60_000;
+
+/// A single observed execution of a dataflow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub uuid: String,
+    pub name: String,
+    pub started_ms: u64,
+    /// `None` while the run is still active.
+    pub ended_ms: Option<u64>,
+    /// Last known status, e.g. `Running`, `Finished`, `Failed`.
+    pub status: String,
+    /// Last time the run was listed as running.
+    #[serde(default)]
+    pub seen_ms: u64,
+}
+
+impl RunRecord {
+    pub fn is_active(&self) -> bool {
+        self.ended_ms.is_none()
+    }
+
+    /// Time window covering the run, padded on both sides. Active runs
+    /// extend to `now_ms`.
+    pub fn window(&self, now_ms: u64) -> (u64, u64) {
+        let end = self.ended_ms.unwrap_or(now_ms);
+        (
+            self.started_ms.saturating_sub(WINDOW_PADDING_MS),
+            end.saturating_add(WINDOW_PADDING_MS),
+        )
+    }
+
+    /// Dropdown label such as `camera-pipeline 12:01:05 (Finished)`.
+    pub fn label(&self) -> String {
+        let name = if self.name.is_empty() {
+            crate::text::truncate(&self.uuid, 8)
+        } else {
+            self.name.clone()
+        };
+        format!(
+            "{} {} ({})",
+            name,
+            crate::datetime::format_clock(self.started_ms),
+            self.status
+        )
+    }
+}
+
+/// Observed runs, most recent first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunHistory {
+    runs: Vec<RunRecord>,
+}
+
+impl RunHistory {
+    pub fn path() -> Option<PathBuf> {
+        settings::data_dir().map(|d| d.join(HISTORY_FILE))
+    }
+
+    /// Load the persisted history, falling back to empty.
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path().ok_or("No data directory available")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize run history: {}", e))?;
+        std::fs::write(&path, json).map_err(|e| format!("Failed to write run history: {}", e))
+    }
+
+    pub fn runs(&self) -> &[RunRecord] {
+        &self.runs
+    }
+
+    /// Update runs from a fresh `dora list`. Returns true if anything changed.
+    pub fn observe(&mut self, dataflows: &[DataflowInfo], now_ms: u64) -> bool {
+        let mut changed = false;
+
+        // Close active runs whose dataflow vanished or stopped running
+        for run in self.runs.iter_mut().filter(|r| r.is_active()) {
+            match dataflows.iter().find(|df| df.uuid == run.uuid) {
+                Some(df) if df.is_running() => {
+                    changed |= now_ms >= run.seen_ms + SEEN_SAVE_INTERVAL_MS;
+                    run.seen_ms = now_ms;
+                }
+                df => {
+                    close(run, df, now_ms);
+                    changed = true;
+                }
+            }
+        }
+
+        // Open runs for newly running dataflows
+        for df in dataflows.iter().filter(|df| df.is_running()) {
+            let active = self.runs.iter().any(|r| r.uuid == df.uuid && r.is_active());
+            if !active {
+                self.runs.insert(
+                    0,
+                    RunRecord {
+                        uuid: df.uuid.clone(),
+                        name: df.name.clone(),
+                        started_ms: now_ms,
+                        ended_ms: None,
+                        status: df.status.clone(),
+                        seen_ms: now_ms,
+                    },
+                );
+                changed = true;
+            }
+        }
+
+        self.runs.truncate(MAX_RUNS);
+        changed
+    }
+
+    /// Update runs from the first `dora list` since launch. Runs left open
+    /// by the last session that are no longer running end when they were
+    /// last seen, not now; the rest is as [`observe`](Self::observe).
+    /// Returns true if anything changed.
+    pub fn reconcile(&mut self, dataflows: &[DataflowInfo], now_ms: u64) -> bool {
+        let mut changed = false;
+        for run in self.runs.iter_mut().filter(|r| r.is_active()) {
+            let df = dataflows.iter().find(|df| df.uuid == run.uuid);
+            if !df.is_some_and(DataflowInfo::is_running) {
+                let ended_ms = run.seen_ms.max(run.started_ms);
+                close(run, df, ended_ms);
+                changed = true;
+            }
+        }
+        self.observe(dataflows, now_ms) || changed
+    }
+}
+
+/// End `run` at `ended_ms`, with the status of its dataflow if still listed.
+fn close(run: &mut RunRecord, df: Option<&DataflowInfo>, ended_ms: u64) {
+    run.ended_ms = Some(ended_ms);
+    run.status = df.map_or_else(|| "Stopped".to_string(), |df| df.status.clone());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn df(uuid: &str, status: &str) -> DataflowInfo {
+        DataflowInfo {
+            uuid: uuid.to_string(),
+            name: format!("df-{}", uuid),
+            status: status.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_observe_opens_and_closes_runs() {
+        let mut history = RunHistory::default();
+        assert!(history.observe(&[df("a", "Running")], 1_000));
+        assert!(!history.observe(&[df("a", "Running")], 2_000));
+        assert!(history.observe(&[df("a", "Failed")], 3_000));
+
+        let run = &history.runs()[0];
+        assert_eq!(run.started_ms, 1_000);
+        assert_eq!(run.ended_ms, Some(3_000));
+        assert_eq!(run.status, "Failed");
+    }
+
+    #[test]
+    fn test_observe_vanished_dataflow_is_stopped() {
+        let mut history = RunHistory::default();
+        history.observe(&[df("a", "Running")], 1_000);
+        assert!(history.observe(&[], 5_000));
+        assert_eq!(history.runs()[0].status, "Stopped");
+        assert_eq!(history.runs()[0].ended_ms, Some(5_000));
+    }
+
+    #[test]
+    fn test_observe_restart_creates_new_run() {
+        let mut history = RunHistory::default();
+        history.observe(&[df("a", "Running")], 1_000);
+        history.observe(&[], 2_000);
+        history.observe(&[df("a", "Running")], 3_000);
+        assert_eq!(history.runs().len(), 2);
+        assert!(history.runs()[0].is_active());
+        assert_eq!(history.runs()[0].started_ms, 3_000);
+    }
+
+    #[test]
+    fn test_reconcile_ends_runs_when_last_seen() {
+        let mut history = RunHistory::default();
+        history.observe(&[df("a", "Running"), df("b", "Running")], 1_000);
+        history.observe(&[df("a", "Running"), df("b", "Running")], 70_000);
+        // The studio closes; "b" stops while it is closed
+        let json = serde_json::to_string(&history).unwrap();
+        let mut history: RunHistory = serde_json::from_str(&json).unwrap();
+
+        assert!(history.reconcile(&[df("a", "Running")], 500_000));
+        let run = |uuid: &str| history.runs().iter().find(|r| r.uuid == uuid).unwrap();
+        assert!(run("a").is_active());
+        assert_eq!(run("a").started_ms, 1_000);
+        assert_eq!(run("b").ended_ms, Some(70_000));
+        assert_eq!(run("b").status, "Stopped");
+        assert_eq!(history.runs().len(), 2);
+    }
+
+    #[test]
+    fn test_window_pads_and_extends_active_runs() {
+        let mut run = RunRecord {
+            uuid: "a".to_string(),
+            name: String::new(),
+            started_ms: 10_000,
+            ended_ms: None,
+            status: "Running".to_string(),
+            seen_ms: 10_000,
+        };
+        assert_eq!(run.window(20_000), (9_000, 21_000));
+        run.ended_ms = Some(15_000);
+        assert_eq!(run.window(20_000), (9_000, 16_000));
+    }
+
+    #[test]
+    fn test_history_serde_roundtrip() {
+        let mut history = RunHistory::default();
+        history.observe(&[df("a", "Running")], 1_000);
+        let json = serde_json::to_string(&history).unwrap();
+        let parsed: RunHistory = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, history);
+    }
+}
@@ -0,0 +1,222 @@
+//! Dialog shown when a dataflow YAML is dropped onto the window, asking
+//! before it is started and offering to build it first.
+
+use makepad_widgets::*;
+use std::path::Path;
+
+use crate::i18n;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+    DIALOG_BORDER = #cbd5e1
+
+    pub StartDialog = {{StartDialog}} {
+        width: 440, height: Fit
+        flow: Down
+        show_bg: true
+        draw_bg: { color: (DIALOG_BORDER) }
+        padding: 1
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Down
+            show_bg: true
+            draw_bg: { color: #ffffff }
+            padding: 12
+            spacing: 6
+
+            title_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 14.0 }
+                }
+                text: "Start dataflow?"
+            }
+
+            path_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 10.0 }
+                    wrap: Word
+                }
+            }
+
+            build_toggle = <CheckBox> {
+                text: "Build first (dora build)"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            <View> {
+                width: Fill, height: Fit
+                flow: Right
+                align: { x: 1.0 }
+                spacing: 8
+                padding: { top: 6 }
+
+                cancel_button = <Button> {
+                    width: 80, height: 30
+                    text: "Cancel"
+                    draw_text: { text_style: { font_size: 11.0 } }
+                }
+                edit_button = <Button> {
+                    width: 80, height: 30
+                    text: "Edit"
+                    draw_text: { text_style: { font_size: 11.0 } }
+                }
+                start_button = <Button> {
+                    width: 80, height: 30
+                    text: "Start"
+                    draw_text: { text_style: { font_size: 11.0 } }
+                }
+            }
+        }
+    }
+}
+
+/// Actions emitted by the StartDialog
+#[derive(Clone, Debug, DefaultNone)]
+pub enum StartDialogAction {
+    None,
+    /// Start the dataflow at the path, building it first if `build` is set
+    Start {
+        path: String,
+        build: bool,
+    },
+    /// Open the dataflow at the path for editing instead
+    Edit(String),
+    Dismissed,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct StartDialog {
+    #[deref]
+    view: View,
+    #[rust]
+    path: String,
+    /// Kept between drops, so a project that needs building stays ticked.
+    #[rust]
+    build: bool,
+}
+
+impl Widget for StartDialog {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if !self.view.visible() {
+            return;
+        }
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+
+        if let Event::KeyDown(ke) = event {
+            if ke.key_code == KeyCode::Escape {
+                cx.widget_action(self.widget_uid(), &scope.path, StartDialogAction::Dismissed);
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for StartDialog {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope) {
+        if let Some(build) = self.view.check_box(ids!(build_toggle)).changed(actions) {
+            self.build = build;
+        }
+
+        let action = if self.view.button(ids!(cancel_button)).clicked(actions) {
+            StartDialogAction::Dismissed
+        } else if self.view.button(ids!(edit_button)).clicked(actions) {
+            StartDialogAction::Edit(self.path.clone())
+        } else if self.view.button(ids!(start_button)).clicked(actions) {
+            StartDialogAction::Start {
+                path: self.path.clone(),
+                build: self.build,
+            }
+        } else {
+            return;
+        };
+        cx.widget_action(self.widget_uid(), &scope.path, action);
+    }
+}
+
+impl StartDialog {
+    /// Show the dialog for the dataflow at `path`.
+    pub fn open(&mut self, cx: &mut Cx, path: &str) {
+        self.path = path.to_string();
+        i18n::localize(
+            cx,
+            &[
+                (ids!(title_label), "start_dialog.title"),
+                (ids!(build_toggle), "start_dialog.build"),
+                (ids!(cancel_button), "action.cancel"),
+                (ids!(edit_button), "action.edit"),
+                (ids!(start_button), "action.start"),
+            ],
+            |path| self.view.widget(path),
+        );
+        self.view.label(ids!(path_label)).set_text(cx, path);
+        self.view
+            .check_box(ids!(build_toggle))
+            .set_active(cx, self.build);
+        self.view.set_visible(cx, true);
+        self.redraw(cx);
+    }
+
+    pub fn close(&mut self, cx: &mut Cx) {
+        self.view.set_visible(cx, false);
+        self.redraw(cx);
+    }
+}
+
+impl StartDialogRef {
+    pub fn open(&self, cx: &mut Cx, path: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.open(cx, path);
+        }
+    }
+
+    pub fn close(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.close(cx);
+        }
+    }
+
+    /// Check if a StartDialogAction was triggered
+    pub fn action(&self, actions: &Actions) -> Option<StartDialogAction> {
+        actions.find_widget_action(self.widget_uid())?.cast()
+    }
+}
+
+/// Whether `path` names a dataflow file, i.e. ends in `.yml` or `.yaml`.
+pub fn is_dataflow_file(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("yml") || ext.eq_ignore_ascii_case("yaml"))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_dataflow_file() {
+        assert!(is_dataflow_file("/home/me/robot/dataflow.yml"));
+        assert!(is_dataflow_file("C:\\flows\\Camera.YAML"));
+        assert!(!is_dataflow_file("/home/me/robot/node.py"));
+        assert!(!is_dataflow_file("/home/me/yml"));
+        assert!(!is_dataflow_file(""));
+    }
+}
@@ -0,0 +1,277 @@
+//! Session undo history for destructive dataflow operations.
+//!
+//! Stopping or destroying a dataflow pushes an entry recording what is needed
+//! to start it again. Entries stay restartable for a grace window and are
+//! discarded afterwards; nothing here is persisted across sessions.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Default grace window during which a stopped dataflow can be restarted.
+pub const DEFAULT_GRACE: Duration = Duration::from_secs(5 * 60);
+
+const MAX_ENTRIES: usize = 20;
+
+/// Which destructive operation produced an undo entry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UndoKind {
+    Stopped,
+    Destroyed,
+}
+
+impl UndoKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UndoKind::Stopped => "Stopped",
+            UndoKind::Destroyed => "Destroyed",
+        }
+    }
+}
+
+/// A stopped or destroyed dataflow that may be restarted.
+#[derive(Debug, Clone)]
+pub struct UndoEntry {
+    pub uuid: String,
+    pub name: String,
+    pub kind: UndoKind,
+    /// YAML path the dataflow was started from, if this session started it.
+    pub dataflow_path: Option<String>,
+    /// Environment variables applied at start, if known.
+    pub env: Vec<(String, String)>,
+    pub at: Instant,
+}
+
+impl UndoEntry {
+    /// Whether enough information is known to start the dataflow again.
+    pub fn is_restartable(&self) -> bool {
+        self.dataflow_path.is_some()
+    }
+
+    /// Short label such as `Stopped camera-pipeline`.
+    pub fn label(&self) -> String {
+        let name = if self.name.is_empty() {
+            &self.uuid
+        } else {
+            &self.name
+        };
+        format!("{} {}", self.kind.as_str(), name)
+    }
+}
+
+/// How a dataflow was started, remembered so it can be started again.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StartSpec {
+    pub dataflow_path: String,
+    pub env: Vec<(String, String)>,
+}
+
+/// Most-recent-first stack of undoable operations.
+#[derive(Debug, Clone)]
+pub struct UndoStack {
+    entries: Vec<UndoEntry>,
+    /// Start specs of dataflows started during this session, keyed by UUID.
+    started: HashMap<String, StartSpec>,
+    grace: Duration,
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self::new(DEFAULT_GRACE)
+    }
+}
+
+impl UndoStack {
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            entries: Vec::new(),
+            started: HashMap::new(),
+            grace,
+        }
+    }
+
+    /// Remember how the dataflow `uuid` was started.
+    pub fn record_start(&mut self, uuid: &str, spec: StartSpec) {
+        self.started.insert(uuid.to_string(), spec);
+    }
+
+    /// How the dataflow `uuid` was started, if this session started it.
+    pub fn started_spec(&self, uuid: &str) -> Option<&StartSpec> {
+        self.started.get(uuid)
+    }
+
+    /// Push an entry for a stopped or destroyed dataflow, attaching its start
+    /// spec if this session started it.
+    pub fn record(&mut self, uuid: &str, name: &str, kind: UndoKind, at: Instant) {
+        let spec = self.started.get(uuid).cloned();
+        self.push(UndoEntry {
+            uuid: uuid.to_string(),
+            name: name.to_string(),
+            kind,
+            dataflow_path: spec.as_ref().map(|s| s.dataflow_path.clone()),
+            env: spec.map(|s| s.env).unwrap_or_default(),
+            at,
+        });
+    }
+
+    pub fn push(&mut self, entry: UndoEntry) {
+        self.entries.retain(|e| e.uuid != entry.uuid);
+        self.entries.insert(0, entry);
+        self.entries.truncate(MAX_ENTRIES);
+    }
+
+    /// Drop entries older than the grace window. Returns true if any were removed.
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let before = self.entries.len();
+        let grace = self.grace;
+        self.entries
+            .retain(|e| now.saturating_duration_since(e.at) < grace);
+        self.entries.len() != before
+    }
+
+    /// The most recent entry that can still be restarted.
+    pub fn latest_restartable(&self) -> Option<&UndoEntry> {
+        self.entries.iter().find(|e| e.is_restartable())
+    }
+
+    /// Remove and return the entry for `uuid`.
+    pub fn take(&mut self, uuid: &str) -> Option<UndoEntry> {
+        let index = self.entries.iter().position(|e| e.uuid == uuid)?;
+        Some(self.entries.remove(index))
+    }
+
+    pub fn entries(&self) -> &[UndoEntry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Extract the dataflow UUID from `dora start` output.
+///
+/// The CLI prints a line such as `dataflow start triggered: <uuid>`; the first
+/// token shaped like a hyphenated UUID is returned.
+pub fn parse_started_uuid(output: &str) -> Option<String> {
+    output
+        .split(|c: char| c.is_whitespace() || c == ':' || c == '"')
+        .find(|token| is_uuid(token))
+        .map(String::from)
+}
+
+fn is_uuid(token: &str) -> bool {
+    let bytes = token.as_bytes();
+    bytes.len() == 36
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => *b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(uuid: &str, path: Option<&str>, at: Instant) -> UndoEntry {
+        UndoEntry {
+            uuid: uuid.to_string(),
+            name: format!("df-{}", uuid),
+            kind: UndoKind::Stopped,
+            dataflow_path: path.map(String::from),
+            env: Vec::new(),
+            at,
+        }
+    }
+
+    #[test]
+    fn test_push_most_recent_first() {
+        let now = Instant::now();
+        let mut stack = UndoStack::default();
+        stack.push(entry("a", Some("a.yml"), now));
+        stack.push(entry("b", Some("b.yml"), now));
+        assert_eq!(stack.entries()[0].uuid, "b");
+        assert_eq!(stack.latest_restartable().unwrap().uuid, "b");
+    }
+
+    #[test]
+    fn test_push_replaces_same_uuid() {
+        let now = Instant::now();
+        let mut stack = UndoStack::default();
+        stack.push(entry("a", Some("a.yml"), now));
+        stack.push(entry("a", Some("a.yml"), now));
+        assert_eq!(stack.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_latest_restartable_skips_unknown_path() {
+        let now = Instant::now();
+        let mut stack = UndoStack::default();
+        stack.push(entry("a", Some("a.yml"), now));
+        stack.push(entry("b", None, now));
+        assert_eq!(stack.latest_restartable().unwrap().uuid, "a");
+    }
+
+    #[test]
+    fn test_expire_after_grace() {
+        let start = Instant::now();
+        let mut stack = UndoStack::new(Duration::from_secs(10));
+        stack.push(entry("a", Some("a.yml"), start));
+        assert!(!stack.expire(start + Duration::from_secs(5)));
+        assert!(stack.expire(start + Duration::from_secs(11)));
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn test_take() {
+        let now = Instant::now();
+        let mut stack = UndoStack::default();
+        stack.push(entry("a", Some("a.yml"), now));
+        assert!(stack.take("missing").is_none());
+        assert_eq!(stack.take("a").unwrap().uuid, "a");
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn test_record_attaches_start_spec() {
+        let now = Instant::now();
+        let mut stack = UndoStack::default();
+        stack.record_start(
+            "a",
+            StartSpec {
+                dataflow_path: "a.yml".to_string(),
+                env: vec![("RUST_LOG".to_string(), "debug".to_string())],
+            },
+        );
+        stack.record("a", "camera", UndoKind::Destroyed, now);
+        stack.record("b", "lidar", UndoKind::Stopped, now);
+
+        let a = &stack.entries()[1];
+        assert_eq!(a.dataflow_path.as_deref(), Some("a.yml"));
+        assert_eq!(a.env.len(), 1);
+        assert!(!stack.entries()[0].is_restartable());
+        assert_eq!(stack.latest_restartable().unwrap().uuid, "a");
+    }
+
+    #[test]
+    fn test_parse_started_uuid() {
+        let output = "dataflow start triggered: 0193a1b2-7c3d-7e4f-8a9b-0c1d2e3f4a5b\n";
+        assert_eq!(
+            parse_started_uuid(output).as_deref(),
+            Some("0193a1b2-7c3d-7e4f-8a9b-0c1d2e3f4a5b")
+        );
+        assert_eq!(parse_started_uuid("dataflow started"), None);
+        assert_eq!(
+            parse_started_uuid("0193a1b2-7c3d-7e4f-8a9b-0c1d2e3f4a5z"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_label_falls_back_to_uuid() {
+        let mut e = entry("abc", None, Instant::now());
+        assert_eq!(e.label(), "Stopped df-abc");
+        e.name.clear();
+        e.kind = UndoKind::Destroyed;
+        assert_eq!(e.label(), "Destroyed abc");
+    }
+}
@@ -0,0 +1,383 @@
+//! Timestamps: parsing what backends and dora report, and formatting them
+//! for display.
+//!
+//! Timestamps travel through the app as milliseconds since the Unix epoch.
+//! [`parse_rfc3339_ms`] turns the strings backends send into that,
+//! honouring UTC offsets; [`parse_rfc3339_ns`] keeps sub-millisecond
+//! digits for callers that need them. Display goes through
+//! [`format_clock`] and [`format_datetime`], which render in the
+//! [`DisplayZone`] picked in settings. Anything persisted (storage
+//! partitions, crash reports) uses the `_in` variants with UTC so files
+//! don't depend on the viewer's zone. Table time columns follow the
+//! [`TimeStyle`] setting through [`format_timestamp`], and tooltips give
+//! the exact time with [`format_rfc3339`].
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::i18n;
+
+/// Zone timestamps are shown in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayZone {
+    #[default]
+    Utc,
+    Local,
+}
+
+impl DisplayZone {
+    /// In the order the time zone dropdown lists them.
+    pub const ALL: [DisplayZone; 2] = [DisplayZone::Utc, DisplayZone::Local];
+
+    /// Suffix for times shown in this zone, e.g. `12:00:00 UTC`.
+    pub fn label(self) -> &'static str {
+        match self {
+            DisplayZone::Utc => "UTC",
+            DisplayZone::Local => "local",
+        }
+    }
+}
+
+static DISPLAY_ZONE: Mutex<DisplayZone> = Mutex::new(DisplayZone::Utc);
+
+/// How table time columns show a timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeStyle {
+    /// `5m ago`
+    #[default]
+    Relative,
+    /// `14:03:07` today, `2024-05-01 14:03` on other days
+    Absolute,
+}
+
+impl TimeStyle {
+    /// In the order the time style dropdown lists them.
+    pub const ALL: [TimeStyle; 2] = [TimeStyle::Relative, TimeStyle::Absolute];
+}
+
+static TIME_STYLE: Mutex<TimeStyle> = Mutex::new(TimeStyle::Relative);
+
+/// Switch the zone [`format_clock`] and [`format_datetime`] use.
+pub fn set_display_zone(zone: DisplayZone) {
+    *DISPLAY_ZONE.lock().unwrap() = zone;
+}
+
+pub fn display_zone() -> DisplayZone {
+    *DISPLAY_ZONE.lock().unwrap()
+}
+
+/// Switch the style [`format_timestamp`] uses.
+pub fn set_time_style(style: TimeStyle) {
+    *TIME_STYLE.lock().unwrap() = style;
+}
+
+pub fn time_style() -> TimeStyle {
+    *TIME_STYLE.lock().unwrap()
+}
+
+/// Milliseconds since the epoch from an RFC 3339 time such as
+/// `2024-05-01T10:00:00.123+02:00`. Also accepts a space instead of the
+/// `T`, offsets without a colon or minutes, and no offset at all (UTC).
+/// Times before 1970 give `None`.
+pub fn parse_rfc3339_ms(s: &str) -> Option<u64> {
+    u64::try_from(parse_rfc3339(s)?.timestamp_millis()).ok()
+}
+
+/// [`parse_rfc3339_ms`] in nanoseconds, for times within 1970–2262.
+pub fn parse_rfc3339_ns(s: &str) -> Option<u64> {
+    u64::try_from(parse_rfc3339(s)?.timestamp_nanos_opt()?).ok()
+}
+
+/// `HH:MM:SS.mmm` in the display zone.
+pub fn format_clock(timestamp_ms: u64) -> String {
+    format_clock_in(timestamp_ms, display_zone())
+}
+
+pub fn format_clock_in(timestamp_ms: u64, zone: DisplayZone) -> String {
+    format_in(timestamp_ms, zone, "%H:%M:%S%.3f")
+}
+
+/// `HH:MM:SS` in the display zone.
+pub fn format_clock_secs(timestamp_ms: u64) -> String {
+    format_in(timestamp_ms, display_zone(), "%H:%M:%S")
+}
+
+/// `YYYY-MM-DD HH:MM:SS` and the zone, e.g. `UTC` or `+02:00`, in the
+/// display zone.
+pub fn format_datetime(timestamp_ms: u64) -> String {
+    format_datetime_in(timestamp_ms, display_zone())
+}
+
+pub fn format_datetime_in(timestamp_ms: u64, zone: DisplayZone) -> String {
+    let format = match zone {
+        DisplayZone::Utc => "%Y-%m-%d %H:%M:%S UTC",
+        DisplayZone::Local => "%Y-%m-%d %H:%M:%S %:z",
+    };
+    format_in(timestamp_ms, zone, format)
+}
+
+/// How long before `now_ms` a time was, e.g. `5s ago` or `3d ago`.
+pub fn format_ago(timestamp_ms: u64, now_ms: u64) -> String {
+    if timestamp_ms > now_ms {
+        return i18n::tr("time.just_now");
+    }
+    let diff_secs = (now_ms - timestamp_ms) / 1000;
+    let (key, count) = if diff_secs < 60 {
+        ("time.seconds_ago", diff_secs)
+    } else if diff_secs < 3600 {
+        ("time.minutes_ago", diff_secs / 60)
+    } else if diff_secs < 86400 {
+        ("time.hours_ago", diff_secs / 3600)
+    } else {
+        ("time.days_ago", diff_secs / 86400)
+    };
+    i18n::tr_args(key, &[("count", &count.to_string())])
+}
+
+/// A timestamp for a table time column, in the [`TimeStyle`] and display
+/// zone picked in settings.
+pub fn format_timestamp(timestamp_ms: u64, now_ms: u64) -> String {
+    format_timestamp_in(timestamp_ms, now_ms, time_style(), display_zone())
+}
+
+pub fn format_timestamp_in(
+    timestamp_ms: u64,
+    now_ms: u64,
+    style: TimeStyle,
+    zone: DisplayZone,
+) -> String {
+    match style {
+        TimeStyle::Relative => format_ago(timestamp_ms, now_ms),
+        TimeStyle::Absolute => {
+            let day = format_in(timestamp_ms, zone, "%Y-%m-%d");
+            if day == format_in(now_ms, zone, "%Y-%m-%d") {
+                format_in(timestamp_ms, zone, "%H:%M:%S")
+            } else {
+                format_in(timestamp_ms, zone, "%Y-%m-%d %H:%M")
+            }
+        }
+    }
+}
+
+/// The exact time as RFC 3339 with milliseconds in the display zone, e.g.
+/// `2024-05-01T10:00:00.123Z`, for tooltips.
+pub fn format_rfc3339(timestamp_ms: u64) -> String {
+    format_rfc3339_in(timestamp_ms, display_zone())
+}
+
+pub fn format_rfc3339_in(timestamp_ms: u64, zone: DisplayZone) -> String {
+    let Some(utc) = i64::try_from(timestamp_ms)
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+    else {
+        return "-".to_string();
+    };
+    match zone {
+        DisplayZone::Utc => utc.to_rfc3339_opts(SecondsFormat::Millis, true),
+        DisplayZone::Local => utc
+            .with_timezone(&Local)
+            .to_rfc3339_opts(SecondsFormat::Millis, true),
+    }
+}
+
+/// `YYYY-MM-DD` (UTC) for a millisecond timestamp.
+pub fn utc_date(timestamp_ms: u64) -> String {
+    format_in(timestamp_ms, DisplayZone::Utc, "%Y-%m-%d")
+}
+
+/// Start of a `YYYY-MM-DD` day (UTC) in milliseconds since the epoch.
+pub fn parse_utc_date_ms(day: &str) -> Option<u64> {
+    let start = NaiveDate::parse_from_str(day, "%Y-%m-%d")
+        .ok()?
+        .and_hms_opt(0, 0, 0)?
+        .and_utc();
+    u64::try_from(start.timestamp_millis()).ok()
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+fn parse_rfc3339(s: &str) -> Option<DateTime<FixedOffset>> {
+    let s = s.trim();
+    // RFC 3339 allows a space between date and time; chrono wants the `T`
+    let normalized;
+    let s = if s.get(10..11) == Some(" ") {
+        normalized = format!("{}T{}", &s[..10], &s[11..]);
+        normalized.as_str()
+    } else {
+        s
+    };
+    DateTime::parse_from_rfc3339(s)
+        .or_else(|_| DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f%#z"))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+                .map(|t| t.and_utc().fixed_offset())
+        })
+        .ok()
+}
+
+/// `timestamp_ms` formatted with a chrono `format` string in `zone`, `-`
+/// past the range chrono can represent.
+fn format_in(timestamp_ms: u64, zone: DisplayZone, format: &str) -> String {
+    let Some(utc) = i64::try_from(timestamp_ms)
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+    else {
+        return "-".to_string();
+    };
+    match zone {
+        DisplayZone::Utc => utc.format(format).to_string(),
+        DisplayZone::Local => utc.with_timezone(&Local).format(format).to_string(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_parse_rfc3339() {
+        assert_eq!(parse_rfc3339_ms("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            parse_rfc3339_ms("2026-02-02T19:40:37.126981Z"),
+            Some(1770061237126)
+        );
+        assert_eq!(
+            parse_rfc3339_ms("2026-02-02T19:40:37Z"),
+            Some(1770061237000)
+        );
+        assert_eq!(parse_rfc3339_ms("1969-12-31T23:59:59Z"), None);
+        assert_eq!(parse_rfc3339_ms("2024-13-01T00:00:00Z"), None);
+        assert_eq!(parse_rfc3339_ms("2024-01-01T24:00:00Z"), None);
+        assert_eq!(parse_rfc3339_ms("yesterday"), None);
+    }
+
+    #[test]
+    fn test_parse_rfc3339_offsets() {
+        let utc = Some(1_714_557_600_000);
+        assert_eq!(parse_rfc3339_ms("2024-05-01T10:00:00Z"), utc);
+        assert_eq!(parse_rfc3339_ms("2024-05-01T12:00:00+02:00"), utc);
+        assert_eq!(parse_rfc3339_ms("2024-05-01T12:00:00+0200"), utc);
+        assert_eq!(parse_rfc3339_ms("2024-05-01T08:30:00-01:30"), utc);
+        // No offset is UTC; a space may separate date and time
+        assert_eq!(parse_rfc3339_ms("2024-05-01 10:00:00"), utc);
+    }
+
+    #[test]
+    fn test_parse_rfc3339_ns_keeps_sub_ms() {
+        assert_eq!(
+            parse_rfc3339_ns("1970-01-01T00:00:01.123456789Z"),
+            Some(1_123_456_789)
+        );
+    }
+
+    #[test]
+    fn test_format_clock() {
+        assert_eq!(format_clock_in(0, DisplayZone::Utc), "00:00:00.000");
+        assert_eq!(
+            format_clock_in(1_700_000_000_123, DisplayZone::Utc),
+            "22:13:20.123"
+        );
+        assert_eq!(
+            format_clock_in(86_400_000 + 61_001, DisplayZone::Utc),
+            "00:01:01.001"
+        );
+        assert_eq!(format_in(61_001, DisplayZone::Utc, "%H:%M:%S"), "00:01:01");
+    }
+
+    #[test]
+    fn test_format_datetime() {
+        assert_eq!(
+            format_datetime_in(1_700_000_000_123, DisplayZone::Utc),
+            "2023-11-14 22:13:20 UTC"
+        );
+        assert_eq!(format_datetime_in(u64::MAX, DisplayZone::Utc), "-");
+    }
+
+    #[test]
+    fn test_format_ago() {
+        let now = 1_700_000_000_000;
+        assert_eq!(format_ago(now - 5_000, now), "5s ago");
+        assert_eq!(format_ago(now - 120_000, now), "2m ago");
+        assert_eq!(format_ago(now - 7_200_000, now), "2h ago");
+        assert_eq!(format_ago(now - 3 * 86_400_000, now), "3d ago");
+        assert_eq!(format_ago(now + 10_000, now), "just now");
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        let now = 1_700_000_000_000; // 2023-11-14 22:13:20 UTC
+        let utc = DisplayZone::Utc;
+        assert_eq!(
+            format_timestamp_in(now - 120_000, now, TimeStyle::Relative, utc),
+            "2m ago"
+        );
+        assert_eq!(
+            format_timestamp_in(now - 120_000, now, TimeStyle::Absolute, utc),
+            "22:11:20"
+        );
+        assert_eq!(
+            format_timestamp_in(now - 86_400_000, now, TimeStyle::Absolute, utc),
+            "2023-11-13 22:13"
+        );
+    }
+
+    #[test]
+    fn test_format_rfc3339() {
+        assert_eq!(
+            format_rfc3339_in(1_700_000_000_123, DisplayZone::Utc),
+            "2023-11-14T22:13:20.123Z"
+        );
+        assert_eq!(format_rfc3339_in(u64::MAX, DisplayZone::Utc), "-");
+        let local = format_rfc3339_in(1_700_000_000_123, DisplayZone::Local);
+        assert_eq!(parse_rfc3339_ms(&local), Some(1_700_000_000_123));
+    }
+
+    #[test]
+    fn test_time_style_serde() {
+        assert_eq!(
+            serde_json::to_string(&TimeStyle::Absolute).unwrap(),
+            "\"absolute\""
+        );
+    }
+
+    #[test]
+    fn test_utc_date() {
+        assert_eq!(utc_date(951_782_400_000), "2000-02-29");
+        assert_eq!(parse_utc_date_ms("2000-02-29"), Some(951_782_400_000));
+        assert_eq!(parse_utc_date_ms("2023-02-29"), None);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_rfc3339_roundtrip(
+            ms in 0i64..250_000_000_000_000,
+            offset_mins in -(14 * 60)..=(14 * 60),
+        ) {
+            let offset = chrono::FixedOffset::east_opt(offset_mins * 60).unwrap();
+            let time = DateTime::from_timestamp_millis(ms).unwrap().with_timezone(&offset);
+            let text = time.to_rfc3339_opts(chrono::SecondsFormat::Millis, false);
+            prop_assert_eq!(parse_rfc3339_ms(&text), Some(ms as u64));
+        }
+
+        #[test]
+        fn prop_parse_rfc3339_never_panics(
+            s in ".*",
+            date in "[0-9-]{0,12}",
+            time in "[0-9:.Z+]{0,16}",
+        ) {
+            parse_rfc3339_ns(&s);
+            parse_rfc3339_ns(&format!("{}T{}", date, time));
+            parse_rfc3339_ns(&format!("{} {}", date, time));
+        }
+    }
+}
@@ -0,0 +1,358 @@
+//! `dora-studio://` links that open the studio on a trace or dataflow, e.g.
+//! `dora-studio://trace/<id>` or `dora-studio://dataflow/<uuid>` pasted in
+//! chat.
+//!
+//! The desktop launches the studio with the link as its argument
+//! ([`link_arg`]). The first instance listens on a local port recorded in
+//! the data directory; a later launch hands its link over ([`hand_off`])
+//! and exits instead of opening a second window. Links received either way
+//! queue up for the UI to poll with [`take_links`] (same pattern as the
+//! file watcher).
+//!
+//! Packaged builds declare the scheme through `deep_link_protocols` in
+//! `Cargo.toml`; `dora-studio --register-url-scheme` registers a dev build
+//! for the current user on Linux and Windows. macOS hands links to a
+//! bundle as Apple Events rather than arguments, which Makepad does not
+//! forward yet, so there a link has to be passed on the command line, e.g.
+//! `dora-studio dora-studio://trace/<id>`.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::settings;
+
+pub const SCHEME: &str = "dora-studio";
+
+/// Holds the port of the instance receiving links.
+const PORT_FILE: &str = "instance.port";
+/// Longest link line read from another instance.
+const MAX_LINK_BYTES: u64 = 4096;
+const IO_TIMEOUT: Duration = Duration::from_millis(500);
+/// What the receiving instance answers once it queued a link.
+const ACK: &str = "ok";
+/// Desktop entry registered on Linux.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+const DESKTOP_FILE: &str = "dora-studio-url.desktop";
+
+static LINKS: Mutex<Vec<DeepLink>> = Mutex::new(Vec::new());
+
+/// What a link opens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    /// A trace by trace ID
+    Trace(String),
+    /// A dataflow by UUID
+    Dataflow(String),
+}
+
+impl DeepLink {
+    /// Parse `dora-studio://<trace|dataflow>/<id>`; a trailing slash, query
+    /// or fragment is ignored.
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let url = url.trim();
+        let rest = url
+            .split_once(':')
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case(SCHEME))
+            .map(|(_, rest)| rest.trim_start_matches('/'))
+            .ok_or_else(|| format!("Not a {}:// link: {}", SCHEME, url))?;
+        let rest = rest.split(['?', '#']).next().unwrap_or_default();
+        let (kind, id) = rest
+            .trim_end_matches('/')
+            .split_once('/')
+            .ok_or_else(|| format!("Link names no object: {}", url))?;
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return Err(format!("Invalid ID in link: {}", url));
+        }
+        match kind.to_ascii_lowercase().as_str() {
+            "trace" | "traces" => Ok(DeepLink::Trace(id.to_string())),
+            "dataflow" | "dataflows" => Ok(DeepLink::Dataflow(id.to_string())),
+            _ => Err(format!("Unknown link target '{}'", kind)),
+        }
+    }
+
+    pub fn to_url(&self) -> String {
+        match self {
+            DeepLink::Trace(id) => format!("{}://trace/{}", SCHEME, id),
+            DeepLink::Dataflow(uuid) => format!("{}://dataflow/{}", SCHEME, uuid),
+        }
+    }
+}
+
+/// The link among the command-line `args`, if the studio was launched to
+/// open one.
+pub fn link_arg(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let prefix = format!("{}:", SCHEME);
+    args.into_iter().find(|arg| {
+        arg.get(..prefix.len())
+            .is_some_and(|p| p.eq_ignore_ascii_case(&prefix))
+    })
+}
+
+/// Whether `args` ask to register the URL scheme (`--register-url-scheme`).
+pub fn register_requested(args: impl IntoIterator<Item = String>) -> bool {
+    args.into_iter().any(|arg| arg == "--register-url-scheme")
+}
+
+/// Queue `link` for the UI, e.g. the one this instance was launched with.
+pub fn push(link: DeepLink) {
+    LINKS.lock().unwrap().push(link);
+}
+
+/// Links received since the last call, oldest first.
+pub fn take_links() -> Vec<DeepLink> {
+    std::mem::take(&mut *LINKS.lock().unwrap())
+}
+
+/// Pass `link` to a studio that is already running. Fails when none
+/// answers, in which case this instance should open it itself.
+pub fn hand_off(link: &DeepLink) -> Result<(), String> {
+    let port_file = port_file().ok_or("No data directory")?;
+    let port: u16 = std::fs::read_to_string(&port_file)
+        .map_err(|e| format!("No running studio: {}", e))?
+        .trim()
+        .parse()
+        .map_err(|e| format!("Invalid {}: {}", PORT_FILE, e))?;
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let mut stream = TcpStream::connect_timeout(&addr, IO_TIMEOUT)
+        .map_err(|e| format!("No running studio: {}", e))?;
+    stream
+        .set_read_timeout(Some(IO_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    writeln!(stream, "{}", link.to_url()).map_err(|e| e.to_string())?;
+    let mut reply = String::new();
+    BufReader::new(stream.take(MAX_LINK_BYTES))
+        .read_line(&mut reply)
+        .map_err(|e| e.to_string())?;
+    // A stale port file may point at some other program now
+    if reply.trim() != ACK {
+        return Err(format!("Unexpected reply on port {}", port));
+    }
+    Ok(())
+}
+
+/// Receive links from later launches in the background and record where,
+/// so [`hand_off`] finds this instance.
+pub fn listen() {
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::warn!(target: "DeepLink", "Not receiving links: {}", e);
+            return;
+        }
+    };
+    let Some(port_file) = port_file() else {
+        return;
+    };
+    let written = listener
+        .local_addr()
+        .and_then(|addr| std::fs::write(&port_file, addr.port().to_string()));
+    if let Err(e) = written {
+        tracing::warn!(target: "DeepLink", "Not receiving links: {}", e);
+        return;
+    }
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            match receive(stream) {
+                Ok(link) => {
+                    tracing::info!(target: "DeepLink", "Received {}", link.to_url());
+                    push(link);
+                }
+                Err(e) => tracing::warn!(target: "DeepLink", "{}", e),
+            }
+        }
+    });
+}
+
+/// Register this executable as the handler for `dora-studio://` links for
+/// the current user. Returns what was done.
+pub fn register() -> Result<String, String> {
+    let exe = std::env::current_exe()
+        .map_err(|e| format!("Failed to find the studio executable: {}", e))?;
+    register_exe(&exe)
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+fn port_file() -> Option<PathBuf> {
+    let dir = settings::data_dir()?;
+    std::fs::create_dir_all(&dir).ok()?;
+    Some(dir.join(PORT_FILE))
+}
+
+/// Read one link from another instance and acknowledge it.
+fn receive(stream: TcpStream) -> Result<DeepLink, String> {
+    stream
+        .set_read_timeout(Some(IO_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let mut line = String::new();
+    BufReader::new((&stream).take(MAX_LINK_BYTES))
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read link: {}", e))?;
+    let link = DeepLink::parse(&line)?;
+    writeln!(&stream, "{}", ACK).map_err(|e| e.to_string())?;
+    Ok(link)
+}
+
+#[cfg(target_os = "linux")]
+fn register_exe(exe: &Path) -> Result<String, String> {
+    let dir = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))
+        .ok_or("Neither XDG_DATA_HOME nor HOME is set")?
+        .join("applications");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(DESKTOP_FILE);
+    std::fs::write(&path, desktop_entry(exe))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    let mime = format!("x-scheme-handler/{}", SCHEME);
+    run(crate::tools::platform::command("xdg-mime").args(["default", DESKTOP_FILE, &mime]))?;
+    Ok(format!(
+        "Registered {}:// links with {}",
+        SCHEME,
+        path.display()
+    ))
+}
+
+#[cfg(windows)]
+fn register_exe(exe: &Path) -> Result<String, String> {
+    let key = format!("HKCU\\Software\\Classes\\{}", SCHEME);
+    let open = format!("\"{}\" \"%1\"", exe.display());
+    let reg = |args: &[&str]| {
+        let mut cmd = crate::tools::platform::command("reg");
+        cmd.arg("add").args(args).arg("/f");
+        run(&mut cmd)
+    };
+    reg(&[&key, "/ve", "/d", "URL:Dora Studio"])?;
+    reg(&[&key, "/v", "URL Protocol", "/d", ""])?;
+    reg(&[
+        &format!("{}\\shell\\open\\command", key),
+        "/ve",
+        "/d",
+        &open,
+    ])?;
+    Ok(format!("Registered {}:// links under {}", SCHEME, key))
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn register_exe(_exe: &Path) -> Result<String, String> {
+    Err(format!(
+        "{}:// links are registered by the packaged app bundle on this platform",
+        SCHEME
+    ))
+}
+
+#[cfg(any(target_os = "linux", windows))]
+fn run(cmd: &mut std::process::Command) -> Result<(), String> {
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to run {:?}: {}", cmd.get_program(), e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{:?} failed: {}",
+            cmd.get_program(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Desktop entry handing `dora-studio://` links to `exe`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn desktop_entry(exe: &Path) -> String {
+    // Quoted Exec arguments escape `"`, `` ` ``, `$` and `\` with a backslash
+    let mut quoted = String::from("\"");
+    for c in exe.to_string_lossy().chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    format!(
+        "[Desktop Entry]\n\
+         Type=Application\n\
+         Name=Dora Studio\n\
+         Exec={} %u\n\
+         NoDisplay=true\n\
+         MimeType=x-scheme-handler/{};\n",
+        quoted, SCHEME
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            DeepLink::parse("dora-studio://trace/4bf92f3577b34da6a3ce929d0e0e4736"),
+            Ok(DeepLink::Trace(
+                "4bf92f3577b34da6a3ce929d0e0e4736".to_string()
+            ))
+        );
+        assert_eq!(
+            DeepLink::parse("Dora-Studio://dataflow/0192-abcd/?from=chat#top"),
+            Ok(DeepLink::Dataflow("0192-abcd".to_string()))
+        );
+        assert!(DeepLink::parse("https://trace/abc").is_err());
+        assert!(DeepLink::parse("dora-studio://trace/").is_err());
+        assert!(DeepLink::parse("dora-studio://trace/../settings").is_err());
+        assert!(DeepLink::parse("dora-studio://node/abc").is_err());
+    }
+
+    #[test]
+    fn test_url_roundtrip() {
+        for link in [
+            DeepLink::Trace("abc123".to_string()),
+            DeepLink::Dataflow("0192-abcd".to_string()),
+        ] {
+            assert_eq!(DeepLink::parse(&link.to_url()), Ok(link));
+        }
+    }
+
+    #[test]
+    fn test_link_arg() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            link_arg(args(&["--verbose", "dora-studio://trace/abc"])),
+            Some("dora-studio://trace/abc".to_string())
+        );
+        assert_eq!(link_arg(args(&["--serve"])), None);
+        assert!(register_requested(args(&["--register-url-scheme"])));
+    }
+
+    #[test]
+    fn test_receive_acknowledges_link() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            writeln!(stream, "dora-studio://dataflow/abc").unwrap();
+            let mut reply = String::new();
+            BufReader::new(stream).read_line(&mut reply).unwrap();
+            reply
+        });
+        let (stream, _) = listener.accept().unwrap();
+        assert_eq!(receive(stream), Ok(DeepLink::Dataflow("abc".to_string())));
+        assert_eq!(client.join().unwrap().trim(), ACK);
+    }
+
+    #[test]
+    fn test_desktop_entry_quotes_exec() {
+        let entry = desktop_entry(Path::new("/opt/My Apps/dora-studio"));
+        assert!(entry.contains("Exec=\"/opt/My Apps/dora-studio\" %u\n"));
+        assert!(entry.contains("MimeType=x-scheme-handler/dora-studio;\n"));
+    }
+}
@@ -0,0 +1,44 @@
+//! Native desktop notifications.
+//!
+//! Used for events worth surfacing while the studio window is in the
+//! background: a dataflow entering `Failed` and SigNoz becoming unreachable.
+//! Notifications are sent from a short-lived thread because some platforms
+//! block until the notification server answers.
+
+const APP_NAME: &str = "Dora Studio";
+
+/// Whether an OS notification should be shown for an event.
+///
+/// In-app toasts already cover the focused window, so OS notifications are
+/// only sent when enabled in settings and the window is in the background.
+pub fn should_send(enabled: bool, window_focused: bool) -> bool {
+    enabled && !window_focused
+}
+
+/// Show a desktop notification without blocking the UI thread.
+pub fn send(title: &str, body: &str) {
+    let title = title.to_string();
+    let body = body.to_string();
+    std::thread::spawn(move || {
+        let result = notify_rust::Notification::new()
+            .appname(APP_NAME)
+            .summary(&title)
+            .body(&body)
+            .show();
+        if let Err(e) = result {
+            tracing::warn!(target: "DesktopNotify", "Failed to show notification: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_send_only_in_background() {
+        assert!(should_send(true, false));
+        assert!(!should_send(true, true));
+        assert!(!should_send(false, false));
+    }
+}
@@ -0,0 +1,242 @@
+use makepad_widgets::*;
+use std::cell::RefMut;
+
+use super::diagnostics_panel;
+use crate::datetime::format_clock;
+use crate::i18n;
+use crate::logging::{self, Level, LogRecord};
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    use crate::diagnostics::diagnostics_panel::LogTableHeader;
+    use crate::diagnostics::diagnostics_panel::LogRow;
+    use crate::diagnostics::diagnostics_panel::LogRowAlt;
+    use crate::diagnostics::diagnostics_panel::LogEmptyState;
+
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+    BORDER = #e2e8f0
+
+    // Studio's own log, docked above the status bar (Ctrl+Shift+D)
+    pub DebugConsole = {{DebugConsole}} {
+        width: Fill, height: 320
+        flow: Down
+        show_bg: true
+        draw_bg: { color: #ffffff }
+
+        <View> {
+            width: Fill, height: 1
+            show_bg: true
+            draw_bg: { color: (BORDER) }
+        }
+
+        <View> {
+            width: Fill, height: 40
+            flow: Right
+            align: { y: 0.5 }
+            padding: { left: 16, right: 16 }
+            spacing: 8
+
+            title_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 12.0 }
+                }
+                text: "Debug Console"
+            }
+
+            count_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: ""
+            }
+
+            <View> { width: Fill, height: Fit }
+
+            show_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "Show"
+            }
+
+            level_filter = <DropDown> {
+                width: 100, height: 28
+                labels: ["Trace", "Debug", "Info", "Warn", "Error"]
+                selected_item: 0
+            }
+
+            close_button = <Button> {
+                text: "Close"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        <LogTableHeader> {}
+
+        record_list = <PortalList> {
+            width: Fill, height: Fill
+            flow: Down
+
+            LogRow = <LogRow> {}
+            LogRowAlt = <LogRowAlt> {}
+            LogEmptyState = <LogEmptyState> {}
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct DebugConsole {
+    #[deref]
+    view: View,
+    /// Every record given, oldest first.
+    #[rust]
+    records: Vec<LogRecord>,
+    /// Records passing the level filter, newest first.
+    #[rust]
+    shown: Vec<LogRecord>,
+    /// Index into `Level::ALL` of the lowest level shown.
+    #[rust]
+    min_level: usize,
+}
+
+impl Widget for DebugConsole {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                self.draw_rows(cx, &mut list);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for DebugConsole {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if let Some(index) = self.view.drop_down(ids!(level_filter)).selected(actions) {
+            self.min_level = index;
+            self.apply_filter(cx);
+        }
+    }
+}
+
+impl DebugConsole {
+    /// Replace the records (given oldest first), keeping the level filter.
+    pub fn set_records(&mut self, cx: &mut Cx, records: Vec<LogRecord>) {
+        self.records = records;
+        self.apply_filter(cx);
+    }
+
+    /// Show the text set in `live_design` in the current language
+    pub fn localize(&mut self, cx: &mut Cx) {
+        i18n::localize(
+            cx,
+            &[
+                (ids!(title_label), "debug_console.title"),
+                (ids!(show_label), "debug_console.show"),
+                (ids!(close_button), "action.close"),
+            ],
+            |path| self.view.widget(path),
+        );
+        diagnostics_panel::localize_log_header(cx, |path| self.view.widget(path));
+        self.view.portal_list(ids!(record_list)).redraw(cx);
+    }
+
+    fn apply_filter(&mut self, cx: &mut Cx) {
+        let level = Level::ALL
+            .get(self.min_level)
+            .copied()
+            .unwrap_or(Level::Trace);
+        self.shown = logging::at_least(&self.records, level);
+        self.shown.reverse();
+        let count = if self.shown.len() == self.records.len() {
+            format!("{} records", self.records.len())
+        } else {
+            format!("{} of {} records", self.shown.len(), self.records.len())
+        };
+        self.view.label(ids!(count_label)).set_text(cx, &count);
+        self.view.portal_list(ids!(record_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    fn draw_rows(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        if self.shown.is_empty() {
+            list.set_item_range(cx, 0, 1);
+            while let Some(item_id) = list.next_visible_item(cx) {
+                if item_id == 0 {
+                    let item = list.item(cx, item_id, live_id!(LogEmptyState));
+                    item.label(ids!(empty_label))
+                        .set_text(cx, &i18n::tr("diagnostics.logs_empty"));
+                    item.draw_all(cx, &mut Scope::empty());
+                }
+            }
+            return;
+        }
+
+        list.set_item_range(cx, 0, self.shown.len());
+
+        while let Some(item_id) = list.next_visible_item(cx) {
+            if item_id < self.shown.len() {
+                let record = &self.shown[item_id];
+
+                let template = if item_id % 2 == 0 {
+                    live_id!(LogRow)
+                } else {
+                    live_id!(LogRowAlt)
+                };
+
+                let item = list.item(cx, item_id, template);
+
+                item.label(ids!(time_label))
+                    .set_text(cx, &format_clock(record.timestamp_ms));
+                item.label(ids!(level_label))
+                    .set_text(cx, record.level.as_str());
+                item.label(ids!(target_label)).set_text(cx, &record.target);
+                item.label(ids!(message_label))
+                    .set_text(cx, &record.message);
+
+                item.draw_all(cx, &mut Scope::empty());
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Ref wrapper (same pattern as DiagnosticsPanelRef)
+// ---------------------------------------------------------------------------
+
+impl DebugConsoleRef {
+    pub fn localize(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.localize(cx);
+        }
+    }
+
+    pub fn set_records(&self, cx: &mut Cx, records: Vec<LogRecord>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_records(cx, records);
+        }
+    }
+
+    /// Check if the close button was clicked
+    pub fn close_clicked(&self, actions: &Actions) -> bool {
+        let Some(inner) = self.borrow() else {
+            return false;
+        };
+        inner.view.button(ids!(close_button)).clicked(actions)
+    }
+}
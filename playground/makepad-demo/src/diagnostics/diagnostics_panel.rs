@@ -0,0 +1,1405 @@
+use makepad_widgets::*;
+use std::cell::RefMut;
+
+use crate::api::LlmBackend;
+use crate::config_bundle::Resolution;
+use crate::datetime::{self, format_clock, DisplayZone, TimeStyle};
+use crate::i18n::{self, Locale};
+use crate::layout;
+use crate::logging::{Level, LogRecord};
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    // Colors (reused from traces_panel)
+    ROW_BG = #ffffff
+    ROW_ALT_BG = #f8fafc
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+    LEVEL_ERROR = #ef4444
+
+    // Toolbar with level selector
+    DiagnosticsToolbar = <View> {
+        width: Fill, height: 40
+        flow: Right
+        align: { y: 0.5 }
+        spacing: 8
+
+        log_level_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "Log level"
+        }
+
+        log_level_dropdown = <DropDown> {
+            width: 100, height: 28
+            labels: ["Trace", "Debug", "Info", "Warn", "Error"]
+            selected_item: 2
+        }
+
+        <View> { width: 16, height: Fit }
+
+        language_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "Language"
+        }
+
+        // Same order as `Locale::ALL`
+        language_dropdown = <DropDown> {
+            width: 100, height: 28
+            labels: ["English", "中文"]
+            selected_item: 0
+        }
+
+        <View> { width: 16, height: Fit }
+
+        ui_scale_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "UI scale"
+        }
+
+        // Same order as `layout::UI_SCALES`
+        ui_scale_dropdown = <DropDown> {
+            width: 80, height: 28
+            labels: ["80%", "90%", "100%", "110%", "125%", "150%", "175%", "200%"]
+            selected_item: 2
+        }
+
+        <View> { width: 16, height: Fit }
+
+        time_zone_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "Time zone"
+        }
+
+        // Same order as `DisplayZone::ALL`
+        time_zone_dropdown = <DropDown> {
+            width: 80, height: 28
+            labels: ["UTC", "Local"]
+            selected_item: 0
+        }
+
+        <View> { width: 8, height: Fit }
+
+        time_style_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "Times"
+        }
+
+        // Same order as `TimeStyle::ALL`
+        time_style_dropdown = <DropDown> {
+            width: 90, height: 28
+            labels: ["Relative", "Absolute"]
+            selected_item: 0
+        }
+
+        <View> { width: 16, height: Fit }
+
+        desktop_notify_toggle = <CheckBox> {
+            text: "Desktop notifications"
+            draw_text: { text_style: { font_size: 11.0 } }
+        }
+    }
+
+    // Config bundle import/export controls
+    ConfigToolbar = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        spacing: 4
+        padding: { bottom: 8 }
+
+        <View> {
+            width: Fill, height: 36
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 8
+
+            bundle_title = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "Config bundle"
+            }
+
+            bundle_path_input = <TextInput> {
+                width: 260, height: 28
+                empty_text: "dora-studio-config.json"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            export_button = <Button> {
+                width: 70, height: 28
+                text: "Export"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            preview_button = <Button> {
+                width: 80, height: 28
+                text: "Preview"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            conflict_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "On conflict"
+            }
+
+            conflict_dropdown = <DropDown> {
+                width: 120, height: 28
+                labels: ["Merge", "Keep mine", "Use imported"]
+                selected_item: 0
+            }
+
+            import_button = <Button> {
+                width: 70, height: 28
+                text: "Import"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        bundle_status_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                wrap: Word,
+                text_style: { font_size: 11.0 }
+            }
+            text: ""
+        }
+    }
+
+    // Recorded telemetry import controls
+    RecordingToolbar = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        spacing: 4
+        padding: { bottom: 8 }
+
+        <View> {
+            width: Fill, height: 36
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 8
+
+            recording_title = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "Recording"
+            }
+
+            recording_path_input = <TextInput> {
+                width: 260, height: 28
+                empty_text: "capture.jsonl"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            import_recording_button = <Button> {
+                width: 130, height: 28
+                text: "Import recording"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        recording_status_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                wrap: Word,
+                text_style: { font_size: 11.0 }
+            }
+            text: ""
+        }
+    }
+
+    // Grafana dashboard import controls
+    GrafanaToolbar = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        spacing: 4
+        padding: { bottom: 8 }
+
+        <View> {
+            width: Fill, height: 36
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 8
+
+            grafana_title = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "Grafana dashboard"
+            }
+
+            grafana_path_input = <TextInput> {
+                width: 260, height: 28
+                empty_text: "dashboard.json"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            import_grafana_button = <Button> {
+                width: 70, height: 28
+                text: "Import"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        grafana_status_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                wrap: Word,
+                text_style: { font_size: 11.0 }
+            }
+            text: ""
+        }
+    }
+
+    // Layout preset controls
+    LayoutToolbar = <View> {
+        width: Fill, height: 36
+        flow: Right
+        align: { y: 0.5 }
+        spacing: 8
+        padding: { bottom: 8 }
+
+        layout_title = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "Layout"
+        }
+
+        layout_name_input = <TextInput> {
+            width: 200, height: 28
+            empty_text: "control room"
+            draw_text: { text_style: { font_size: 11.0 } }
+        }
+
+        save_layout_button = <Button> {
+            width: 120, height: 28
+            text: "Save current"
+            draw_text: { text_style: { font_size: 11.0 } }
+        }
+    }
+
+    // Keyboard shortcut overrides
+    ShortcutsToolbar = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        spacing: 4
+        padding: { bottom: 8 }
+
+        <View> {
+            width: Fill, height: 28
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 8
+
+            shortcuts_title = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "Shortcuts"
+            }
+
+            shortcuts_input = <TextInput> {
+                width: 360, height: 28
+                empty_text: "refresh=F5, focus_search=Ctrl+F"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            apply_shortcuts_button = <Button> {
+                width: 80, height: 28
+                text: "Apply"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        shortcuts_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                wrap: Word,
+                text_style: { font_size: 11.0 }
+            }
+            text: ""
+        }
+    }
+
+    // Local storage retention and usage
+    StorageToolbar = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        spacing: 4
+        padding: { bottom: 8 }
+
+        <View> {
+            width: Fill, height: 28
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 8
+
+            retention_title = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "Retention"
+            }
+
+            retention_input = <TextInput> {
+                width: 360, height: 28
+                empty_text: "spans=7d, logs=24h, metrics=0"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            apply_retention_button = <Button> {
+                width: 80, height: 28
+                text: "Apply"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            prune_button = <Button> {
+                width: 110, height: 28
+                text: "Prune now"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        storage_usage_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                wrap: Word,
+                text_style: { font_size: 11.0 }
+            }
+            text: ""
+        }
+    }
+
+    // Console for whitelisted dora subcommands
+    CliToolbar = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        spacing: 4
+        padding: { bottom: 8 }
+
+        <View> {
+            width: Fill, height: 28
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 8
+
+            dora_path_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "dora executable"
+            }
+
+            dora_path_input = <TextInput> {
+                width: 360, height: 28
+                empty_text: "dora (from PATH)"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            ssh_host_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "on host"
+            }
+
+            ssh_host_input = <TextInput> {
+                width: 200, height: 28
+                empty_text: "this machine (or user@host)"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            apply_dora_path_button = <Button> {
+                width: 80, height: 28
+                text: "Apply"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        <View> {
+            width: Fill, height: 28
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 8
+
+            <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "dora"
+            }
+
+            cli_input = <TextInput> {
+                width: 360, height: 28
+                empty_text: "list --format json"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            run_cli_button = <Button> {
+                width: 80, height: 28
+                text: "Run"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        cli_output_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                wrap: Word,
+                text_style: { font_size: 11.0 }
+            }
+            text: ""
+        }
+    }
+
+    // Chat system prompt templates
+    PromptToolbar = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        spacing: 4
+        padding: { bottom: 8 }
+
+        // Where the chat agent's requests go
+        <View> {
+            width: Fill, height: 28
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 8
+
+            llm_backend_label = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "Chat model from"
+            }
+
+            llm_backend_dropdown = <DropDown> {
+                width: 120, height: 28
+                labels: ["Claude API", "Local server"]
+                selected_item: 0
+            }
+
+            local_llm_url_input = <TextInput> {
+                width: 240, height: 28
+                empty_text: "http://127.0.0.1:8080"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            apply_llm_button = <Button> {
+                width: 80, height: 28
+                text: "Apply"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        <View> {
+            width: Fill, height: 28
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 8
+
+            prompt_title = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: "Prompt template"
+            }
+
+            prompt_name_input = <TextInput> {
+                width: 200, height: 28
+                empty_text: "Dora Studio Assistant"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            edit_prompt_button = <Button> {
+                width: 80, height: 28
+                text: "Edit"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            save_prompt_button = <Button> {
+                width: 80, height: 28
+                text: "Save"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        system_prompt_input = <TextInput> {
+            width: Fill, height: 80
+            empty_text: "System prompt (save it empty to delete a saved template)"
+            draw_text: { text_style: { font_size: 11.0 } }
+        }
+
+        prompt_templates_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                wrap: Word,
+                text_style: { font_size: 11.0 }
+            }
+            text: ""
+        }
+    }
+
+    // Log table header, shared with the debug console
+    pub LogTableHeader = <View> {
+        width: Fill, height: 40
+        flow: Right
+        show_bg: true
+        draw_bg: { color: #f1f5f9 }
+        padding: { left: 16, right: 16 }
+        align: { y: 0.5 }
+        spacing: 8
+
+        time_header = <Label> {
+            width: 100, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "TIME (UTC)"
+        }
+        level_header = <Label> {
+            width: 60, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "LEVEL"
+        }
+        target_header = <Label> {
+            width: 100, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "TARGET"
+        }
+        message_header = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+            text: "MESSAGE"
+        }
+    }
+
+    // Log record row
+    pub LogRow = <View> {
+        width: Fill, height: 32
+        flow: Right
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+        padding: { left: 16, right: 16 }
+        align: { y: 0.5 }
+        spacing: 8
+
+        // A cursor makes the cell report hover in/out, for the exact-time
+        // tooltip
+        time_cell = <View> {
+            width: 100, height: Fit
+            cursor: Default
+            time_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+            }
+        }
+        level_label = <Label> {
+            width: 60, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        target_label = <Label> {
+            width: 100, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+        message_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 11.0 }
+            }
+        }
+    }
+
+    // Alternate log record row
+    pub LogRowAlt = <LogRow> {
+        draw_bg: { color: (ROW_ALT_BG) }
+    }
+
+    // Empty state
+    pub LogEmptyState = <View> {
+        width: Fill, height: 120
+        flow: Down
+        align: { x: 0.5, y: 0.5 }
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+
+        empty_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 14.0 }
+            }
+            text: "No log records yet"
+        }
+    }
+
+    pub DiagnosticsPanel = {{DiagnosticsPanel}} {
+        width: Fill, height: Fill
+        flow: Down
+
+        <DiagnosticsToolbar> {}
+
+        <ConfigToolbar> {}
+
+        <RecordingToolbar> {}
+
+        <GrafanaToolbar> {}
+
+        <LayoutToolbar> {}
+
+        <ShortcutsToolbar> {}
+
+        <StorageToolbar> {}
+
+        <CliToolbar> {}
+
+        <PromptToolbar> {}
+
+        log_header = <LogTableHeader> {}
+
+        record_list = <PortalList> {
+            width: Fill, height: Fill
+            flow: Down
+
+            LogRow = <LogRow> {}
+            LogRowAlt = <LogRowAlt> {}
+            LogEmptyState = <LogEmptyState> {}
+        }
+
+        time_tooltip = <Tooltip> {}
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct DiagnosticsPanel {
+    #[deref]
+    view: View,
+    /// Records shown newest first.
+    #[rust]
+    records: Vec<LogRecord>,
+}
+
+impl Widget for DiagnosticsPanel {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                self.draw_rows(cx, &mut list);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for DiagnosticsPanel {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        let record_list = self.view.portal_list(ids!(record_list));
+        let tooltip = self.view.tooltip(ids!(time_tooltip));
+        for (item_id, item) in record_list.items_with_actions(actions) {
+            let Some(record) = self.records.get(item_id) else {
+                continue;
+            };
+            let time_cell = item.view(ids!(time_cell));
+            if let Some(hover) = time_cell.finger_hover_in(actions) {
+                let exact = datetime::format_rfc3339(record.timestamp_ms);
+                tooltip.show_with_options(cx, hover.abs, &exact);
+            }
+            if time_cell.finger_hover_out(actions).is_some() {
+                tooltip.hide(cx);
+            }
+        }
+    }
+}
+
+impl DiagnosticsPanel {
+    /// Replace the displayed records (given oldest first).
+    pub fn set_records(&mut self, cx: &mut Cx, mut records: Vec<LogRecord>) {
+        records.reverse();
+        self.records = records;
+        self.view.portal_list(ids!(record_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    /// Sync the level selector with the active log level.
+    pub fn set_level(&mut self, cx: &mut Cx, level: Level) {
+        if let Some(index) = Level::ALL.iter().position(|l| *l == level) {
+            self.view
+                .drop_down(ids!(log_level_dropdown))
+                .set_selected_item(cx, index);
+        }
+    }
+
+    /// Sync the language selector with the UI language.
+    pub fn set_language(&mut self, cx: &mut Cx, locale: Locale) {
+        if let Some(index) = Locale::ALL.iter().position(|l| *l == locale) {
+            self.view
+                .drop_down(ids!(language_dropdown))
+                .set_selected_item(cx, index);
+        }
+    }
+
+    /// Sync the UI scale selector with the scale in use.
+    pub fn set_ui_scale(&mut self, cx: &mut Cx, percent: u32) {
+        self.view
+            .drop_down(ids!(ui_scale_dropdown))
+            .set_selected_item(cx, layout::ui_scale_index(percent));
+    }
+
+    /// Sync the time zone selector with the display zone.
+    pub fn set_time_zone(&mut self, cx: &mut Cx, zone: DisplayZone) {
+        if let Some(index) = DisplayZone::ALL.iter().position(|z| *z == zone) {
+            self.view
+                .drop_down(ids!(time_zone_dropdown))
+                .set_selected_item(cx, index);
+        }
+    }
+
+    /// Sync the time style selector with the style in use.
+    pub fn set_time_style(&mut self, cx: &mut Cx, style: TimeStyle) {
+        if let Some(index) = TimeStyle::ALL.iter().position(|s| *s == style) {
+            self.view
+                .drop_down(ids!(time_style_dropdown))
+                .set_selected_item(cx, index);
+        }
+    }
+
+    /// Show only the settings, hiding the log, or show both.
+    pub fn set_settings_only(&mut self, cx: &mut Cx, settings_only: bool) {
+        for path in [ids!(log_header), ids!(record_list)] {
+            self.view.widget(path).set_visible(cx, !settings_only);
+        }
+        self.redraw(cx);
+    }
+
+    /// Re-read the panel's strings after the UI language changed.
+    pub fn localize(&mut self, cx: &mut Cx) {
+        self.view
+            .label(ids!(log_level_label))
+            .set_text(cx, &i18n::tr("diagnostics.log_level"));
+        self.view
+            .label(ids!(language_label))
+            .set_text(cx, &i18n::tr("diagnostics.language"));
+        self.view
+            .label(ids!(ui_scale_label))
+            .set_text(cx, &i18n::tr("diagnostics.ui_scale"));
+        self.view
+            .label(ids!(time_zone_label))
+            .set_text(cx, &i18n::tr("diagnostics.time_zone"));
+        self.view
+            .label(ids!(time_style_label))
+            .set_text(cx, &i18n::tr("diagnostics.time_style"));
+        i18n::localize(
+            cx,
+            &[
+                (ids!(desktop_notify_toggle), "diagnostics.desktop_notify"),
+                (ids!(bundle_title), "diagnostics.bundle"),
+                (ids!(export_button), "action.export"),
+                (ids!(preview_button), "diagnostics.preview"),
+                (ids!(conflict_label), "diagnostics.on_conflict"),
+                (ids!(import_button), "action.import"),
+                (ids!(recording_title), "diagnostics.recording"),
+                (
+                    ids!(import_recording_button),
+                    "diagnostics.import_recording",
+                ),
+                (ids!(grafana_title), "diagnostics.grafana"),
+                (ids!(import_grafana_button), "action.import"),
+                (ids!(layout_title), "diagnostics.layout"),
+                (ids!(save_layout_button), "diagnostics.save_layout"),
+                (ids!(shortcuts_title), "diagnostics.shortcuts"),
+                (ids!(apply_shortcuts_button), "action.apply"),
+                (ids!(retention_title), "diagnostics.retention"),
+                (ids!(apply_retention_button), "action.apply"),
+                (ids!(prune_button), "diagnostics.prune"),
+                (ids!(dora_path_label), "diagnostics.dora_path"),
+                (ids!(ssh_host_label), "diagnostics.ssh_host"),
+                (ids!(apply_dora_path_button), "action.apply"),
+                (ids!(run_cli_button), "action.run"),
+                (ids!(llm_backend_label), "diagnostics.llm_backend"),
+                (ids!(apply_llm_button), "action.apply"),
+                (ids!(prompt_title), "diagnostics.prompt"),
+                (ids!(edit_prompt_button), "action.edit"),
+                (ids!(save_prompt_button), "action.save"),
+            ],
+            |path| self.view.widget(path),
+        );
+        localize_log_header(cx, |path| self.view.widget(path));
+        i18n::localize_labels(
+            cx,
+            &self.view.drop_down(ids!(time_zone_dropdown)),
+            &["diagnostics.zone.utc", "diagnostics.zone.local"],
+        );
+        i18n::localize_labels(
+            cx,
+            &self.view.drop_down(ids!(time_style_dropdown)),
+            &["diagnostics.times.relative", "diagnostics.times.absolute"],
+        );
+        i18n::localize_labels(
+            cx,
+            &self.view.drop_down(ids!(conflict_dropdown)),
+            &[
+                "diagnostics.conflict.merge",
+                "diagnostics.conflict.keep",
+                "diagnostics.conflict.imported",
+            ],
+        );
+        i18n::localize_labels(
+            cx,
+            &self.view.drop_down(ids!(llm_backend_dropdown)),
+            &["diagnostics.llm.claude", "diagnostics.llm.local"],
+        );
+        self.view.portal_list(ids!(record_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    fn draw_rows(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        if self.records.is_empty() {
+            list.set_item_range(cx, 0, 1);
+            while let Some(item_id) = list.next_visible_item(cx) {
+                if item_id == 0 {
+                    let item = list.item(cx, item_id, live_id!(LogEmptyState));
+                    item.label(ids!(empty_label))
+                        .set_text(cx, &i18n::tr("diagnostics.logs_empty"));
+                    item.draw_all(cx, &mut Scope::empty());
+                }
+            }
+            return;
+        }
+
+        list.set_item_range(cx, 0, self.records.len());
+
+        while let Some(item_id) = list.next_visible_item(cx) {
+            if item_id < self.records.len() {
+                let record = &self.records[item_id];
+
+                let template = if item_id % 2 == 0 {
+                    live_id!(LogRow)
+                } else {
+                    live_id!(LogRowAlt)
+                };
+
+                let item = list.item(cx, item_id, template);
+
+                item.label(ids!(time_label))
+                    .set_text(cx, &format_clock(record.timestamp_ms));
+                item.label(ids!(level_label))
+                    .set_text(cx, record.level.as_str());
+                item.label(ids!(target_label)).set_text(cx, &record.target);
+                item.label(ids!(message_label))
+                    .set_text(cx, &record.message);
+
+                item.draw_all(cx, &mut Scope::empty());
+            }
+        }
+    }
+}
+
+/// Show the column titles of a [`LogTableHeader`] in the current language.
+pub fn localize_log_header(cx: &mut Cx, find: impl Fn(&[&[LiveId]]) -> WidgetRef) {
+    i18n::localize(
+        cx,
+        &[
+            (ids!(time_header), "log.column.time"),
+            (ids!(level_header), "log.column.level"),
+            (ids!(target_header), "log.column.target"),
+            (ids!(message_header), "log.column.message"),
+        ],
+        find,
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Ref wrapper (same pattern as TracesPanelRef)
+// ---------------------------------------------------------------------------
+
+impl DiagnosticsPanelRef {
+    pub fn set_records(&self, cx: &mut Cx, records: Vec<LogRecord>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_records(cx, records);
+        }
+    }
+
+    pub fn set_level(&self, cx: &mut Cx, level: Level) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_level(cx, level);
+        }
+    }
+
+    pub fn set_language(&self, cx: &mut Cx, locale: Locale) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_language(cx, locale);
+        }
+    }
+
+    pub fn set_ui_scale(&self, cx: &mut Cx, percent: u32) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_ui_scale(cx, percent);
+        }
+    }
+
+    pub fn set_time_zone(&self, cx: &mut Cx, zone: DisplayZone) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_time_zone(cx, zone);
+        }
+    }
+
+    pub fn set_time_style(&self, cx: &mut Cx, style: TimeStyle) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_time_style(cx, style);
+        }
+    }
+
+    pub fn set_settings_only(&self, cx: &mut Cx, settings_only: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_settings_only(cx, settings_only);
+        }
+    }
+
+    pub fn localize(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.localize(cx);
+        }
+    }
+
+    /// Show the outcome of a config export/import
+    pub fn set_bundle_status(&self, cx: &mut Cx, message: &str) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .label(ids!(bundle_status_label))
+                .set_text(cx, message);
+        }
+    }
+
+    /// Check if export was clicked, returns the entered bundle path if so
+    pub fn export_clicked(&self, actions: &Actions) -> Option<String> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(export_button))
+            .clicked(actions)
+            .then(|| inner.view.text_input(ids!(bundle_path_input)).text())
+    }
+
+    /// Check if preview was clicked, returns the entered bundle path if so
+    pub fn preview_clicked(&self, actions: &Actions) -> Option<String> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(preview_button))
+            .clicked(actions)
+            .then(|| inner.view.text_input(ids!(bundle_path_input)).text())
+    }
+
+    /// Check if import was clicked, returns the bundle path and chosen
+    /// conflict resolution if so
+    pub fn import_clicked(&self, actions: &Actions) -> Option<(String, Resolution)> {
+        let inner = self.borrow()?;
+        if !inner.view.button(ids!(import_button)).clicked(actions) {
+            return None;
+        }
+        let path = inner.view.text_input(ids!(bundle_path_input)).text();
+        let index = inner
+            .view
+            .drop_down(ids!(conflict_dropdown))
+            .selected_item();
+        let resolution = Resolution::ALL.get(index).copied().unwrap_or_default();
+        Some((path, resolution))
+    }
+
+    /// Show the outcome of a recording import
+    pub fn set_recording_status(&self, cx: &mut Cx, message: &str) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .label(ids!(recording_status_label))
+                .set_text(cx, message);
+        }
+    }
+
+    /// Check if recording import was clicked, returns the entered path if so
+    pub fn import_recording_clicked(&self, actions: &Actions) -> Option<String> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(import_recording_button))
+            .clicked(actions)
+            .then(|| inner.view.text_input(ids!(recording_path_input)).text())
+    }
+
+    /// Show the outcome of a Grafana dashboard import
+    pub fn set_grafana_status(&self, cx: &mut Cx, message: &str) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .label(ids!(grafana_status_label))
+                .set_text(cx, message);
+        }
+    }
+
+    /// Check if Grafana import was clicked, returns the entered path if so
+    pub fn import_grafana_clicked(&self, actions: &Actions) -> Option<String> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(import_grafana_button))
+            .clicked(actions)
+            .then(|| inner.view.text_input(ids!(grafana_path_input)).text())
+    }
+
+    /// Check if save layout was clicked, returns the entered layout name if so
+    pub fn save_layout_clicked(&self, actions: &Actions) -> Option<String> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(save_layout_button))
+            .clicked(actions)
+            .then(|| inner.view.text_input(ids!(layout_name_input)).text())
+    }
+
+    /// Show the shortcut overrides and the resulting bindings
+    pub fn set_shortcuts(&self, cx: &mut Cx, overrides: &str, summary: &str) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .text_input(ids!(shortcuts_input))
+                .set_text(cx, overrides);
+            inner
+                .view
+                .label(ids!(shortcuts_label))
+                .set_text(cx, summary);
+        }
+    }
+
+    /// Check if apply shortcuts was clicked, returns the entered overrides if so
+    pub fn apply_shortcuts_clicked(&self, actions: &Actions) -> Option<String> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(apply_shortcuts_button))
+            .clicked(actions)
+            .then(|| inner.view.text_input(ids!(shortcuts_input)).text())
+    }
+
+    /// Show the retention overrides
+    pub fn set_retention(&self, cx: &mut Cx, overrides: &str) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .text_input(ids!(retention_input))
+                .set_text(cx, overrides);
+        }
+    }
+
+    /// Show the disk use of local storage, or the outcome of pruning
+    pub fn set_storage_usage(&self, cx: &mut Cx, message: &str) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .label(ids!(storage_usage_label))
+                .set_text(cx, message);
+        }
+    }
+
+    /// Check if apply retention was clicked, returns the entered overrides if so
+    pub fn apply_retention_clicked(&self, actions: &Actions) -> Option<String> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(apply_retention_button))
+            .clicked(actions)
+            .then(|| inner.view.text_input(ids!(retention_input)).text())
+    }
+
+    pub fn prune_clicked(&self, actions: &Actions) -> bool {
+        self.borrow()
+            .is_some_and(|inner| inner.view.button(ids!(prune_button)).clicked(actions))
+    }
+
+    pub fn set_dora_target(&self, cx: &mut Cx, path: &str, ssh_host: &str) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .text_input(ids!(dora_path_input))
+                .set_text(cx, path);
+            inner
+                .view
+                .text_input(ids!(ssh_host_input))
+                .set_text(cx, ssh_host);
+        }
+    }
+
+    /// Check if apply was clicked for the dora executable, returns the
+    /// entered path and SSH host if so
+    pub fn apply_dora_target_clicked(&self, actions: &Actions) -> Option<(String, String)> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(apply_dora_path_button))
+            .clicked(actions)
+            .then(|| {
+                (
+                    inner.view.text_input(ids!(dora_path_input)).text(),
+                    inner.view.text_input(ids!(ssh_host_input)).text(),
+                )
+            })
+    }
+
+    /// Show the output of a console command, or why it was rejected
+    pub fn set_cli_output(&self, cx: &mut Cx, output: &str) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .label(ids!(cli_output_label))
+                .set_text(cx, output);
+        }
+    }
+
+    /// Check if run was clicked in the dora console, returns the entered
+    /// command line if so
+    pub fn run_cli_clicked(&self, actions: &Actions) -> Option<String> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(run_cli_button))
+            .clicked(actions)
+            .then(|| inner.view.text_input(ids!(cli_input)).text())
+    }
+
+    /// Show where the chat agent's requests go
+    pub fn set_llm_backend(&self, cx: &mut Cx, backend: LlmBackend, local_url: &str) {
+        if let Some(inner) = self.borrow() {
+            let index = LlmBackend::ALL
+                .iter()
+                .position(|b| *b == backend)
+                .unwrap_or(0);
+            inner
+                .view
+                .drop_down(ids!(llm_backend_dropdown))
+                .set_selected_item(cx, index);
+            inner
+                .view
+                .text_input(ids!(local_llm_url_input))
+                .set_text(cx, local_url);
+        }
+    }
+
+    /// Check if apply was clicked for the chat backend, returns the chosen
+    /// backend and entered local server URL if so
+    pub fn apply_llm_clicked(&self, actions: &Actions) -> Option<(LlmBackend, String)> {
+        let inner = self.borrow()?;
+        if !inner.view.button(ids!(apply_llm_button)).clicked(actions) {
+            return None;
+        }
+        let index = inner
+            .view
+            .drop_down(ids!(llm_backend_dropdown))
+            .selected_item();
+        let backend = LlmBackend::ALL.get(index).copied().unwrap_or_default();
+        Some((
+            backend,
+            inner.view.text_input(ids!(local_llm_url_input)).text(),
+        ))
+    }
+
+    /// List the prompt templates available to the chat
+    pub fn set_prompt_templates(&self, cx: &mut Cx, summary: &str) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .label(ids!(prompt_templates_label))
+                .set_text(cx, summary);
+        }
+    }
+
+    /// Show a prompt template for editing
+    pub fn set_prompt(&self, cx: &mut Cx, name: &str, prompt: &str) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .text_input(ids!(prompt_name_input))
+                .set_text(cx, name);
+            inner
+                .view
+                .text_input(ids!(system_prompt_input))
+                .set_text(cx, prompt);
+        }
+    }
+
+    /// Check if edit was clicked for a prompt template, returns the entered
+    /// name if so
+    pub fn edit_prompt_clicked(&self, actions: &Actions) -> Option<String> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(edit_prompt_button))
+            .clicked(actions)
+            .then(|| inner.view.text_input(ids!(prompt_name_input)).text())
+    }
+
+    /// Check if save was clicked for a prompt template, returns the entered
+    /// name and system prompt if so
+    pub fn save_prompt_clicked(&self, actions: &Actions) -> Option<(String, String)> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(save_prompt_button))
+            .clicked(actions)
+            .then(|| {
+                (
+                    inner.view.text_input(ids!(prompt_name_input)).text(),
+                    inner.view.text_input(ids!(system_prompt_input)).text(),
+                )
+            })
+    }
+
+    /// Sync the desktop notifications toggle with the saved setting
+    pub fn set_desktop_notifications(&self, cx: &mut Cx, enabled: bool) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .check_box(ids!(desktop_notify_toggle))
+                .set_active(cx, enabled);
+        }
+    }
+
+    /// Check if desktop notifications were toggled, returns the new value if so
+    pub fn desktop_notifications_changed(&self, actions: &Actions) -> Option<bool> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .check_box(ids!(desktop_notify_toggle))
+            .changed(actions)
+    }
+
+    /// Check if a new log level was picked, returns the level if so
+    pub fn level_changed(&self, actions: &Actions) -> Option<Level> {
+        let inner = self.borrow()?;
+        let index = inner
+            .view
+            .drop_down(ids!(log_level_dropdown))
+            .selected(actions)?;
+        Level::ALL.get(index).copied()
+    }
+
+    /// Check if a new UI scale was picked, returns it in percent if so
+    pub fn ui_scale_changed(&self, actions: &Actions) -> Option<u32> {
+        let inner = self.borrow()?;
+        let index = inner
+            .view
+            .drop_down(ids!(ui_scale_dropdown))
+            .selected(actions)?;
+        layout::UI_SCALES.get(index).copied()
+    }
+
+    /// Check if a new time zone was picked, returns it if so
+    pub fn time_zone_changed(&self, actions: &Actions) -> Option<DisplayZone> {
+        let inner = self.borrow()?;
+        let index = inner
+            .view
+            .drop_down(ids!(time_zone_dropdown))
+            .selected(actions)?;
+        DisplayZone::ALL.get(index).copied()
+    }
+
+    /// Check if a new time style was picked, returns it if so
+    pub fn time_style_changed(&self, actions: &Actions) -> Option<TimeStyle> {
+        let inner = self.borrow()?;
+        let index = inner
+            .view
+            .drop_down(ids!(time_style_dropdown))
+            .selected(actions)?;
+        TimeStyle::ALL.get(index).copied()
+    }
+
+    /// Check if a new UI language was picked, returns the locale if so
+    pub fn language_changed(&self, actions: &Actions) -> Option<Locale> {
+        let inner = self.borrow()?;
+        let index = inner
+            .view
+            .drop_down(ids!(language_dropdown))
+            .selected(actions)?;
+        Locale::ALL.get(index).copied()
+    }
+}
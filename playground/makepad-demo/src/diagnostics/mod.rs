@@ -0,0 +1,12 @@
+pub mod debug_console;
+pub mod diagnostics_panel;
+
+pub use debug_console::{DebugConsole, DebugConsoleRef, DebugConsoleWidgetRefExt};
+pub use diagnostics_panel::{DiagnosticsPanel, DiagnosticsPanelRef, DiagnosticsPanelWidgetRefExt};
+
+use makepad_widgets::*;
+
+pub fn live_design(cx: &mut Cx) {
+    diagnostics_panel::live_design(cx);
+    debug_console::live_design(cx);
+}
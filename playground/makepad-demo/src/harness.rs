@@ -0,0 +1,204 @@
+//! Headless harness for driving the app and its widgets from tests.
+//!
+//! A [`Harness`] owns a Makepad [`Cx`] with the app's live design registered
+//! and expanded, and an [`App`] built from it, but no window or GPU: nothing
+//! is drawn, and events are fed by hand. Widget state is read back through
+//! the widgets' ref wrappers (`DataflowTableRef::dataflows`, ...).
+//!
+//! `Event::Startup` is not sent, so the app keeps default settings and no
+//! telemetry bridge or sampler threads are started. Everything the app
+//! persists goes to a per-process temporary data directory.
+//!
+//! Rows of portal lists only exist once drawn, so per-row buttons (stop,
+//! destroy, logs) cannot be clicked; their handlers are reachable through
+//! the app's own entry points instead.
+//!
+//! Coordinator calls run as background tasks, as in the app, and their
+//! results are applied on a later frame; [`Harness::settle`] sends frames
+//! until none are left.
+
+use crate::app::App;
+use crate::chart::{TimeSeriesChartAction, TimeSeriesChartWidgetRefExt};
+use crate::chat::chat_screen::{ChatScreenRef, ChatScreenWidgetRefExt};
+use crate::dataflow::{
+    DataflowDetailRef, DataflowDetailWidgetRefExt, DataflowTableAction, DataflowTableRef,
+    DataflowTableWidgetRefExt, DoraClient,
+};
+use crate::notifications::NotificationCenter;
+use crate::otlp::types::TimeRange;
+use crate::settings;
+use crate::traces::{TracesPanelRef, TracesPanelWidgetRefExt};
+use makepad_widgets::*;
+use std::sync::Once;
+
+/// Seconds between frames sent by [`Harness::frame`].
+const FRAME_TIME: f64 = 1.0 / 60.0;
+/// How long [`Harness::settle`] waits for background tasks.
+const SETTLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+pub struct Harness {
+    cx: Cx,
+    app: App,
+    time: f64,
+    frame: u64,
+}
+
+impl Harness {
+    /// Build the app with the given coordinator client.
+    pub fn new(client: Box<dyn DoraClient>) -> Self {
+        isolate_data_dir();
+        let mut cx = Cx::new(Box::new(|_, _| {}));
+        App::live_register(&mut cx);
+        crate::app::live_design(&mut cx);
+        cx.live_expand();
+        let mut app = App::new_main(&mut cx).expect("App is defined in the live design");
+        app.set_dora_client(client);
+        Self {
+            cx,
+            app,
+            time: 0.0,
+            frame: 0,
+        }
+    }
+
+    pub fn cx(&mut self) -> &mut Cx {
+        &mut self.cx
+    }
+
+    pub fn ui(&self) -> WidgetRef {
+        self.app.ui().clone()
+    }
+
+    pub fn notifications(&self) -> &NotificationCenter {
+        self.app.notifications()
+    }
+
+    /// Feed an event to the app, as the event loop would.
+    pub fn send(&mut self, event: &Event) {
+        self.app.handle_event(&mut self.cx, event);
+    }
+
+    /// Feed actions to the app, as if widgets had emitted them.
+    pub fn send_actions(&mut self, actions: Actions) {
+        self.send(&Event::Actions(actions));
+    }
+
+    /// Send the app's next frame, one frame after the previous one.
+    pub fn frame(&mut self) {
+        self.advance(FRAME_TIME);
+    }
+
+    /// Let `secs` pass, then send the app's next frame.
+    pub fn advance(&mut self, secs: f64) {
+        self.time += secs;
+        self.frame += 1;
+        let event = Event::NextFrame(NextFrameEvent {
+            frame: self.frame,
+            time: self.time,
+            set: [self.app.next_frame_id()].into_iter().collect(),
+        });
+        self.send(&event);
+    }
+
+    /// Send frames until the background tasks started so far, and the ones
+    /// their results start, have finished and been applied.
+    pub fn settle(&mut self) {
+        let deadline = std::time::Instant::now() + SETTLE_TIMEOUT;
+        while self.app.has_pending_tasks() {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "background tasks still running after {:?}",
+                SETTLE_TIMEOUT
+            );
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            self.frame();
+        }
+    }
+
+    /// Click the button at `path`, e.g. `ids!(start_button)`.
+    pub fn click(&mut self, path: &[&[LiveId]]) {
+        let uid = self.ui().widget(path).widget_uid();
+        let actions = self.cx.capture_actions(|cx| {
+            cx.widget_action(
+                uid,
+                &HeapLiveIdPath::default(),
+                ButtonAction::Clicked(KeyModifiers::default()),
+            );
+        });
+        self.send_actions(actions);
+    }
+
+    /// Select the dataflow `uuid` in the table, as clicking its row does.
+    pub fn select_dataflow(&mut self, uuid: &str) {
+        let uid = self.dataflow_table().widget_uid();
+        let actions = self.cx.capture_actions(|cx| {
+            cx.widget_action(
+                uid,
+                &HeapLiveIdPath::default(),
+                DataflowTableAction::DataflowSelected(uuid.to_string()),
+            );
+        });
+        self.send_actions(actions);
+    }
+
+    /// Zoom the chart at `path` to `range`, or back to all the data when
+    /// `None`, as dragging across it or double clicking does.
+    pub fn zoom_chart(&mut self, path: &[&[LiveId]], range: Option<TimeRange>) {
+        let chart = self.ui().time_series_chart(path);
+        chart.set_range(&mut self.cx, range.clone());
+        let uid = chart.widget_uid();
+        let actions = self.cx.capture_actions(|cx| {
+            cx.widget_action(
+                uid,
+                &HeapLiveIdPath::default(),
+                TimeSeriesChartAction::RangeChanged(range),
+            );
+        });
+        self.send_actions(actions);
+    }
+
+    /// Hover the chart at `path` at `time_ms`, or leave it when `None`.
+    pub fn hover_chart(&mut self, path: &[&[LiveId]], time_ms: Option<u64>) {
+        let uid = self.ui().time_series_chart(path).widget_uid();
+        let actions = self.cx.capture_actions(|cx| {
+            cx.widget_action(
+                uid,
+                &HeapLiveIdPath::default(),
+                TimeSeriesChartAction::Hovered(time_ms),
+            );
+        });
+        self.send_actions(actions);
+    }
+
+    /// Replace the text of the text input at `path`.
+    pub fn set_text(&mut self, path: &[&[LiveId]], text: &str) {
+        self.ui().text_input(path).set_text(&mut self.cx, text);
+    }
+
+    pub fn dataflow_table(&self) -> DataflowTableRef {
+        self.ui().dataflow_table(ids!(dataflow_table))
+    }
+
+    pub fn dataflow_detail(&self) -> DataflowDetailRef {
+        self.ui().dataflow_detail(ids!(dataflow_detail))
+    }
+
+    pub fn traces_panel(&self) -> TracesPanelRef {
+        self.ui().traces_panel(ids!(traces_panel))
+    }
+
+    pub fn chat_screen(&self) -> ChatScreenRef {
+        self.ui().chat_screen(ids!(chat_screen))
+    }
+}
+
+/// Point the data directory at a fresh temporary directory, once per
+/// process, so tests never touch the user's settings.
+fn isolate_data_dir() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("dora-studio-harness-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        settings::override_data_dir(dir);
+    });
+}
@@ -0,0 +1,204 @@
+//! UI strings by locale.
+//!
+//! Strings live in one JSON catalog per [`Locale`] under `locales/`, keyed
+//! by dotted ids like `tab.dataflows`. [`tr`] looks a key up in the active
+//! locale and falls back to English, then to the key itself, so a missing
+//! translation shows English rather than nothing. Templates take `{name}`
+//! placeholders, filled by [`tr_args`].
+//!
+//! Text declared in `live_design` is English; each widget re-sets its own
+//! through [`localize`] when the language changes, and list rows set theirs
+//! as they are drawn.
+//!
+//! To add a language, add a catalog with the same keys as `en.json` and a
+//! [`Locale`] variant for it.
+
+use makepad_widgets::{Cx, DropDownRef, LiveId, WidgetRef};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// A UI language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Zh,
+}
+
+impl Locale {
+    /// In the order the language dropdown lists them.
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::Zh];
+
+    fn source(self) -> &'static str {
+        match self {
+            Locale::En => include_str!("locales/en.json"),
+            Locale::Zh => include_str!("locales/zh.json"),
+        }
+    }
+}
+
+static ACTIVE: Mutex<Locale> = Mutex::new(Locale::En);
+
+/// Switch the language [`tr`] uses.
+pub fn set_locale(locale: Locale) {
+    *ACTIVE.lock().unwrap() = locale;
+}
+
+/// Language [`tr`] currently uses.
+pub fn locale() -> Locale {
+    *ACTIVE.lock().unwrap()
+}
+
+/// The string for `key` in the active locale.
+pub fn tr(key: &str) -> String {
+    resolve(locale(), key).to_string()
+}
+
+/// The template for `key` in the active locale, with `{name}` placeholders
+/// replaced from `args`.
+pub fn tr_args(key: &str, args: &[(&str, &str)]) -> String {
+    fill(resolve(locale(), key), args)
+}
+
+/// Set the text `live_design` gave each widget in `texts` to the string for
+/// its key; `find` looks a widget up by path, e.g. `|path| self.view.widget(path)`.
+pub fn localize(
+    cx: &mut Cx,
+    texts: &[(&[&[LiveId]], &str)],
+    find: impl Fn(&[&[LiveId]]) -> WidgetRef,
+) {
+    for (path, key) in texts {
+        find(path).set_text(cx, &tr(key));
+    }
+}
+
+/// Offer the strings for `keys` in `dropdown`, keeping the choice made.
+pub fn localize_labels(cx: &mut Cx, dropdown: &DropDownRef, keys: &[&str]) {
+    let selected = dropdown.selected_item();
+    dropdown.set_labels(cx, keys.iter().map(|key| tr(key)).collect());
+    dropdown.set_selected_item(cx, selected);
+}
+
+/// `key` in `locale`, then in English, then the key itself.
+pub fn resolve<'a>(locale: Locale, key: &'a str) -> &'a str {
+    lookup(locale, key)
+        .or_else(|| lookup(Locale::En, key))
+        .unwrap_or(key)
+}
+
+/// `key` in `locale` only.
+pub fn lookup(locale: Locale, key: &str) -> Option<&'static str> {
+    catalog(locale).get(key).map(String::as_str)
+}
+
+/// Replace each `{name}` in `template` with its value from `args`; unknown
+/// placeholders are left as they are.
+pub fn fill(template: &str, args: &[(&str, &str)]) -> String {
+    let mut text = template.to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+fn catalog(locale: Locale) -> &'static HashMap<String, String> {
+    static CATALOGS: [OnceLock<HashMap<String, String>>; Locale::ALL.len()] =
+        [OnceLock::new(), OnceLock::new()];
+    let index = Locale::ALL.iter().position(|l| *l == locale).unwrap_or(0);
+    CATALOGS[index].get_or_init(|| parse_catalog(locale.source()))
+}
+
+/// Parse a catalog, empty if it is malformed so lookups fall back to English.
+fn parse_catalog(source: &str) -> HashMap<String, String> {
+    serde_json::from_str(source).unwrap_or_default()
+}
+
+/// Placeholder names in a template, sorted.
+#[cfg(test)]
+fn placeholders(template: &str) -> Vec<&str> {
+    let mut names: Vec<&str> = template
+        .split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+        .collect();
+    names.sort();
+    names
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogs_parse() {
+        for locale in Locale::ALL {
+            let source = locale.source();
+            let parsed: Result<HashMap<String, String>, _> = serde_json::from_str(source);
+            assert!(parsed.is_ok(), "{:?} catalog is not valid JSON", locale);
+        }
+    }
+
+    #[test]
+    fn test_catalogs_have_the_same_keys_and_placeholders() {
+        let en = catalog(Locale::En);
+        for locale in Locale::ALL {
+            let other = catalog(locale);
+            let mut missing: Vec<&String> = en.keys().filter(|k| !other.contains_key(*k)).collect();
+            let mut extra: Vec<&String> = other.keys().filter(|k| !en.contains_key(*k)).collect();
+            missing.sort();
+            extra.sort();
+            assert!(missing.is_empty(), "{:?} is missing {:?}", locale, missing);
+            assert!(
+                extra.is_empty(),
+                "{:?} has unknown keys {:?}",
+                locale,
+                extra
+            );
+            for (key, template) in other {
+                assert_eq!(
+                    placeholders(template),
+                    placeholders(&en[key]),
+                    "{:?} placeholders differ for {}",
+                    locale,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_resolve_falls_back() {
+        assert_eq!(resolve(Locale::Zh, "tab.dataflows"), "数据流");
+        assert_eq!(resolve(Locale::En, "tab.dataflows"), "Dataflows");
+        assert_eq!(resolve(Locale::Zh, "no.such.key"), "no.such.key");
+    }
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(
+            fill("Failed to stop {name}", &[("name", "camera")]),
+            "Failed to stop camera"
+        );
+        assert_eq!(
+            fill("{count} of {total}", &[("count", "3")]),
+            "3 of {total}"
+        );
+    }
+
+    #[test]
+    fn test_locale_serde() {
+        assert_eq!(serde_json::to_string(&Locale::Zh).unwrap(), "\"zh\"");
+        let locale: Locale = serde_json::from_str("\"en\"").unwrap();
+        assert_eq!(locale, Locale::En);
+    }
+}
@@ -0,0 +1,399 @@
+//! Self-instrumentation: spans around the studio's own slow paths, exported
+//! over OTLP/HTTP so they can be inspected in the SigNoz instance the traces
+//! panel reads from.
+//!
+//! Spans are recorded only after [`start_exporter`] has been called, so the
+//! guards are free to create when export is off. A [`SpanGuard`] ends its
+//! span when dropped; the exporter sends finished spans every
+//! [`EXPORT_INTERVAL`] as OTLP JSON, which needs no protobuf or SDK
+//! dependencies.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How often finished spans are sent.
+pub const EXPORT_INTERVAL: Duration = Duration::from_secs(5);
+/// Finished spans kept while the collector is unreachable; newer spans are
+/// dropped beyond this.
+const MAX_PENDING_SPANS: usize = 2048;
+/// OTLP/HTTP port of the SigNoz collector.
+const OTLP_HTTP_PORT: u16 = 4318;
+const DEFAULT_SERVICE_NAME: &str = "dora-studio";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static FINISHED_SPANS: Mutex<Vec<SpanData>> = Mutex::new(Vec::new());
+static ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// OTLP span kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    Internal,
+    /// An outgoing request, e.g. to the LLM or SigNoz
+    Client,
+}
+
+impl SpanKind {
+    fn code(self) -> u8 {
+        match self {
+            SpanKind::Internal => 1,
+            SpanKind::Client => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttrValue {
+    String(String),
+    Int(i64),
+}
+
+impl From<&str> for AttrValue {
+    fn from(value: &str) -> Self {
+        AttrValue::String(value.to_string())
+    }
+}
+
+impl From<String> for AttrValue {
+    fn from(value: String) -> Self {
+        AttrValue::String(value)
+    }
+}
+
+impl From<i64> for AttrValue {
+    fn from(value: i64) -> Self {
+        AttrValue::Int(value)
+    }
+}
+
+impl From<usize> for AttrValue {
+    fn from(value: usize) -> Self {
+        AttrValue::Int(value as i64)
+    }
+}
+
+/// A finished or in-flight span
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanData {
+    pub trace_id: String,
+    pub span_id: String,
+    pub parent_span_id: Option<String>,
+    pub name: String,
+    pub kind: SpanKind,
+    pub start_ns: u64,
+    pub end_ns: u64,
+    pub attributes: Vec<(String, AttrValue)>,
+    /// Error message, if the operation failed
+    pub error: Option<String>,
+}
+
+/// Open span, ended and queued for export when dropped. Inert when export
+/// is off.
+#[must_use = "the span ends when the guard is dropped"]
+pub struct SpanGuard {
+    span: Option<SpanData>,
+}
+
+/// Start a root span.
+pub fn span(name: &str) -> SpanGuard {
+    start(name, SpanKind::Internal, None)
+}
+
+/// Start a root span for an outgoing request.
+pub fn client_span(name: &str) -> SpanGuard {
+    start(name, SpanKind::Client, None)
+}
+
+fn start(name: &str, kind: SpanKind, parent: Option<&SpanData>) -> SpanGuard {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return SpanGuard { span: None };
+    }
+    let trace_id = match parent {
+        Some(parent) => parent.trace_id.clone(),
+        None => format!("{}{}", random_id(), random_id()),
+    };
+    SpanGuard {
+        span: Some(SpanData {
+            trace_id,
+            span_id: random_id(),
+            parent_span_id: parent.map(|p| p.span_id.clone()),
+            name: name.to_string(),
+            kind,
+            start_ns: now_ns(),
+            end_ns: 0,
+            attributes: Vec::new(),
+            error: None,
+        }),
+    }
+}
+
+impl SpanGuard {
+    /// Start a span nested in this one.
+    pub fn child(&self, name: &str, kind: SpanKind) -> SpanGuard {
+        match &self.span {
+            Some(span) => start(name, kind, Some(span)),
+            None => SpanGuard { span: None },
+        }
+    }
+
+    pub fn attr(&mut self, key: &str, value: impl Into<AttrValue>) {
+        if let Some(span) = &mut self.span {
+            span.attributes.push((key.to_string(), value.into()));
+        }
+    }
+
+    /// Mark the span as failed.
+    pub fn fail(&mut self, message: &str) {
+        if let Some(span) = &mut self.span {
+            span.error = Some(message.to_string());
+        }
+    }
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        let Some(mut span) = self.span.take() else {
+            return;
+        };
+        span.end_ns = now_ns().max(span.start_ns);
+        let mut finished = FINISHED_SPANS.lock().unwrap();
+        if finished.len() < MAX_PENDING_SPANS {
+            finished.push(span);
+        }
+    }
+}
+
+fn now_ns() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+}
+
+/// 16 random hex digits; two make a trace ID.
+fn random_id() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(ID_COUNTER.fetch_add(1, Ordering::Relaxed));
+    hasher.write_u64(now_ns());
+    // All-zero IDs are invalid in OTLP
+    format!("{:016x}", hasher.finish().max(1))
+}
+
+/// Take the spans finished since the last call.
+pub fn take_finished_spans() -> Vec<SpanData> {
+    std::mem::take(&mut *FINISHED_SPANS.lock().unwrap())
+}
+
+/// Where to send spans: `OTEL_EXPORTER_OTLP_ENDPOINT` when set, else the
+/// OTLP/HTTP port on the SigNoz host. `None` leaves export off.
+pub fn traces_endpoint(
+    otlp_endpoint: Option<&str>,
+    signoz_base_url: Option<&str>,
+) -> Option<String> {
+    if let Some(endpoint) = otlp_endpoint.map(str::trim).filter(|e| !e.is_empty()) {
+        return Some(format!("{}/v1/traces", endpoint.trim_end_matches('/')));
+    }
+    let base = signoz_base_url.map(str::trim).filter(|b| !b.is_empty())?;
+    let (scheme, rest) = base.split_once("://").unwrap_or(("http", base));
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let host = match authority.rsplit_once(':') {
+        // Keep IPv6 literals such as `[::1]` whole
+        Some((host, port)) if !port.contains(']') => host,
+        _ => authority,
+    };
+    if host.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{}://{}:{}/v1/traces",
+        scheme, host, OTLP_HTTP_PORT
+    ))
+}
+
+/// OTLP/HTTP JSON request body for `spans`.
+pub fn export_body(spans: &[SpanData], service_name: &str) -> serde_json::Value {
+    let spans: Vec<serde_json::Value> = spans
+        .iter()
+        .map(|span| {
+            let attributes: Vec<serde_json::Value> = span
+                .attributes
+                .iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        AttrValue::String(s) => serde_json::json!({ "stringValue": s }),
+                        // 64-bit integers are strings in OTLP JSON
+                        AttrValue::Int(i) => serde_json::json!({ "intValue": i.to_string() }),
+                    };
+                    serde_json::json!({ "key": key, "value": value })
+                })
+                .collect();
+            let status = match &span.error {
+                Some(message) => serde_json::json!({ "code": 2, "message": message }),
+                None => serde_json::json!({ "code": 1 }),
+            };
+            serde_json::json!({
+                "traceId": span.trace_id,
+                "spanId": span.span_id,
+                "parentSpanId": span.parent_span_id.as_deref().unwrap_or(""),
+                "name": span.name,
+                "kind": span.kind.code(),
+                "startTimeUnixNano": span.start_ns.to_string(),
+                "endTimeUnixNano": span.end_ns.to_string(),
+                "attributes": attributes,
+                "status": status,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    { "key": "service.name", "value": { "stringValue": service_name } }
+                ]
+            },
+            "scopeSpans": [{
+                "scope": { "name": DEFAULT_SERVICE_NAME },
+                "spans": spans,
+            }]
+        }]
+    })
+}
+
+/// Start recording spans and sending them to the endpoint configured in the
+/// environment (see [`traces_endpoint`]). Returns the endpoint, or `None`
+/// when export is off.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn start_exporter_from_env() -> Option<String> {
+    let env = |name: &str| std::env::var(name).ok();
+    let endpoint = traces_endpoint(
+        env("OTEL_EXPORTER_OTLP_ENDPOINT").as_deref(),
+        env("SIGNOZ_BASE_URL").as_deref(),
+    )?;
+    let service_name = env("OTEL_SERVICE_NAME")
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string());
+    start_exporter(endpoint.clone(), service_name);
+    Some(endpoint)
+}
+
+/// Start recording spans and sending them to `endpoint` on a background
+/// thread.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn start_exporter(endpoint: String, service_name: String) {
+    if ENABLED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().expect("Failed to create span export runtime");
+        rt.block_on(async {
+            let client = reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default();
+            // Warn on the first failure, not on every interval after it
+            let mut failing = false;
+            loop {
+                tokio::time::sleep(EXPORT_INTERVAL).await;
+                let spans = take_finished_spans();
+                if spans.is_empty() {
+                    continue;
+                }
+                let result = client
+                    .post(&endpoint)
+                    .json(&export_body(&spans, &service_name))
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status());
+                match result {
+                    Ok(_) if failing => {
+                        failing = false;
+                        tracing::info!(target: "Instrument", "Span export recovered");
+                    }
+                    Ok(_) => {}
+                    Err(e) if !failing => {
+                        failing = true;
+                        tracing::warn!(
+                            target: "Instrument",
+                            "Failed to export spans to {}: {}",
+                            endpoint,
+                            e
+                        );
+                    }
+                    Err(_) => {}
+                }
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traces_endpoint() {
+        assert_eq!(
+            traces_endpoint(Some("http://collector:4318/"), Some("http://signoz:8080")),
+            Some("http://collector:4318/v1/traces".to_string())
+        );
+        assert_eq!(
+            traces_endpoint(None, Some("https://signoz.lab:8080/api")),
+            Some("https://signoz.lab:4318/v1/traces".to_string())
+        );
+        assert_eq!(
+            traces_endpoint(Some(" "), Some("localhost")),
+            Some("http://localhost:4318/v1/traces".to_string())
+        );
+        assert_eq!(
+            traces_endpoint(None, Some("http://[::1]")),
+            Some("http://[::1]:4318/v1/traces".to_string())
+        );
+        assert_eq!(traces_endpoint(None, None), None);
+        assert_eq!(traces_endpoint(None, Some("")), None);
+    }
+
+    #[test]
+    fn test_export_body() {
+        let span = SpanData {
+            trace_id: "a".repeat(32),
+            span_id: "b".repeat(16),
+            parent_span_id: None,
+            name: "execute_tool dora_list".to_string(),
+            kind: SpanKind::Internal,
+            start_ns: 1_000,
+            end_ns: 2_000,
+            attributes: vec![
+                ("gen_ai.tool.name".to_string(), "dora_list".into()),
+                ("output.bytes".to_string(), 42usize.into()),
+            ],
+            error: Some("timed out".to_string()),
+        };
+        let body = export_body(&[span], "studio");
+        let resource = &body["resourceSpans"][0];
+        assert_eq!(
+            resource["resource"]["attributes"][0]["value"]["stringValue"],
+            "studio"
+        );
+        let span = &resource["scopeSpans"][0]["spans"][0];
+        assert_eq!(span["parentSpanId"], "");
+        assert_eq!(span["kind"], 1);
+        assert_eq!(span["startTimeUnixNano"], "1000");
+        assert_eq!(span["attributes"][0]["value"]["stringValue"], "dora_list");
+        assert_eq!(span["attributes"][1]["value"]["intValue"], "42");
+        assert_eq!(span["status"]["code"], 2);
+        assert_eq!(span["status"]["message"], "timed out");
+    }
+
+    #[test]
+    fn test_spans_are_inert_until_export_starts() {
+        let mut guard = span("refresh");
+        guard.attr("count", 3usize);
+        guard.fail("boom");
+        let child = guard.child("list", SpanKind::Client);
+        drop(child);
+        drop(guard);
+        assert!(take_finished_spans().is_empty());
+    }
+}
@@ -0,0 +1,425 @@
+//! Window geometry and named layout presets.
+//!
+//! Window size and position are remembered per monitor configuration so a
+//! laptop screen and an external display each get their own geometry. The
+//! configuration is identified by the size and arrangement of the attached
+//! monitors ([`monitor_key`]), and a restored window is kept on one of them
+//! ([`clamp_to_monitors`]). Where the monitors are unknown the window's DPI
+//! factor stands in.
+//!
+//! A layout preset bundles the window geometry with the visible panel and
+//! whether the notification drawer is open. Built-in presets only set the
+//! window size. Built-in presets can be overridden by saving a preset with
+//! the same name.
+//!
+//! Splitter positions are remembered as the size of the pane they resize,
+//! keyed by [`CHAT_SPLIT`] and [`DATAFLOW_SPLIT`].
+//!
+//! The rest of the view ([`Session`]: visible panel, drawer, trace filter,
+//! time range) is saved as it changes and restored on the next launch.
+//!
+//! The UI scale multiplies the monitor's DPI factor, so the font sizes, row
+//! heights and spacing in every `live_design!` block grow or shrink together.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::otlp::types::TimeRange;
+use crate::traces::TraceFilter;
+
+/// Window size and position in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: f64,
+    pub height: f64,
+    pub x: f64,
+    pub y: f64,
+}
+
+impl WindowGeometry {
+    /// Smallest size restored, so a bogus saved geometry cannot hide the window.
+    const MIN_SIZE: f64 = 320.0;
+
+    /// Whether the geometry is worth restoring.
+    pub fn is_valid(&self) -> bool {
+        [self.width, self.height, self.x, self.y]
+            .iter()
+            .all(|v| v.is_finite())
+            && self.width >= Self::MIN_SIZE
+            && self.height >= Self::MIN_SIZE
+    }
+}
+
+/// A monitor's bounds in pixels, on the desktop spanning all monitors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Monitor {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Monitor {
+    /// Area of `geometry` on this monitor, both in logical pixels.
+    fn overlap(&self, geometry: &WindowGeometry) -> f64 {
+        let width = (self.x + self.width).min(geometry.x + geometry.width) - self.x.max(geometry.x);
+        let height =
+            (self.y + self.height).min(geometry.y + geometry.height) - self.y.max(geometry.y);
+        width.max(0.0) * height.max(0.0)
+    }
+
+    fn to_logical(self, dpi_factor: f64) -> Self {
+        Self {
+            x: self.x / dpi_factor,
+            y: self.y / dpi_factor,
+            width: self.width / dpi_factor,
+            height: self.height / dpi_factor,
+        }
+    }
+}
+
+/// Key identifying a monitor configuration by the size and position of
+/// each monitor, e.g. `1920x1080+0+0,2560x1440+1920+0`; `dpi-2.00` when
+/// the monitors are unknown.
+pub fn monitor_key(monitors: &[Monitor], dpi_factor: f64) -> String {
+    if monitors.is_empty() {
+        return legacy_monitor_key(dpi_factor);
+    }
+    let mut rects: Vec<String> = monitors
+        .iter()
+        .map(|m| format!("{:.0}x{:.0}{:+.0}{:+.0}", m.width, m.height, m.x, m.y))
+        .collect();
+    rects.sort();
+    rects.join(",")
+}
+
+/// Key geometry was saved under before monitors were detected, e.g.
+/// `dpi-2.00`.
+pub fn legacy_monitor_key(dpi_factor: f64) -> String {
+    format!("dpi-{:.2}", dpi_factor)
+}
+
+/// `geometry` moved and shrunk, if need be, to lie on the monitor showing
+/// most of it, or on the first monitor when none does. Left as it is when
+/// the monitors are unknown.
+pub fn clamp_to_monitors(
+    geometry: WindowGeometry,
+    monitors: &[Monitor],
+    dpi_factor: f64,
+) -> WindowGeometry {
+    let screens: Vec<Monitor> = monitors.iter().map(|m| m.to_logical(dpi_factor)).collect();
+    let Some(screen) = screens
+        .iter()
+        .filter(|s| s.overlap(&geometry) > 0.0)
+        .max_by(|a, b| a.overlap(&geometry).total_cmp(&b.overlap(&geometry)))
+        .or(screens.first())
+    else {
+        return geometry;
+    };
+    let width = geometry.width.min(screen.width);
+    let height = geometry.height.min(screen.height);
+    WindowGeometry {
+        width,
+        height,
+        x: geometry.x.clamp(screen.x, screen.x + screen.width - width),
+        y: geometry
+            .y
+            .clamp(screen.y, screen.y + screen.height - height),
+    }
+}
+
+/// UI scales offered in the diagnostics panel, in percent.
+pub const UI_SCALES: [u32; 8] = [80, 90, 100, 110, 125, 150, 175, 200];
+
+/// Keep a UI scale within the supported 80–200%.
+pub fn clamp_ui_scale(percent: u32) -> u32 {
+    percent.clamp(UI_SCALES[0], UI_SCALES[UI_SCALES.len() - 1])
+}
+
+/// DPI factor to draw at on a monitor with `system_dpi` at a UI scale.
+pub fn scaled_dpi(system_dpi: f64, percent: u32) -> f64 {
+    system_dpi * clamp_ui_scale(percent) as f64 / 100.0
+}
+
+/// Index in [`UI_SCALES`] of the choice closest to `percent`.
+pub fn ui_scale_index(percent: u32) -> usize {
+    (0..UI_SCALES.len())
+        .min_by_key(|&i| UI_SCALES[i].abs_diff(percent))
+        .unwrap_or(0)
+}
+
+/// Height of the chat area below the main panels.
+pub const CHAT_SPLIT: &str = "chat";
+/// Height of the dataflow table above the node metrics.
+pub const DATAFLOW_SPLIT: &str = "dataflow_table";
+
+/// Smallest pane size restored, so a pane cannot be dragged out of reach.
+pub const MIN_PANE_SIZE: f64 = 80.0;
+
+fn default_split(key: &str) -> f64 {
+    match key {
+        CHAT_SPLIT => 300.0,
+        _ => 360.0,
+    }
+}
+
+/// Saved size for a splitter, or its default if unset or unusable.
+pub fn split_size(saved: &BTreeMap<String, f64>, key: &str) -> f64 {
+    saved
+        .get(key)
+        .copied()
+        .filter(|size| size.is_finite() && *size >= MIN_PANE_SIZE)
+        .unwrap_or_else(|| default_split(key))
+}
+
+/// A named window layout.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutPreset {
+    pub name: String,
+    /// Panel shown after applying: `dataflows`, `traces`, `alerts`,
+    /// `diagnostics`, `settings` or `query`.
+    pub panel: String,
+    #[serde(default)]
+    pub drawer_open: bool,
+    /// Window geometry to restore; the current geometry is kept when `None`.
+    #[serde(default)]
+    pub window: Option<WindowGeometry>,
+    /// Only resize the window, leaving it where it is.
+    #[serde(default)]
+    pub size_only: bool,
+}
+
+/// Presets available out of the box.
+pub fn builtin_presets() -> Vec<LayoutPreset> {
+    vec![
+        LayoutPreset {
+            name: "laptop".to_string(),
+            panel: "dataflows".to_string(),
+            drawer_open: false,
+            window: Some(WindowGeometry {
+                width: 1280.0,
+                height: 800.0,
+                x: 0.0,
+                y: 0.0,
+            }),
+            size_only: true,
+        },
+        LayoutPreset {
+            name: "control room".to_string(),
+            panel: "traces".to_string(),
+            drawer_open: true,
+            window: Some(WindowGeometry {
+                width: 1920.0,
+                height: 1080.0,
+                x: 0.0,
+                y: 0.0,
+            }),
+            size_only: true,
+        },
+    ]
+}
+
+/// Built-in presets followed by saved ones; a saved preset replaces the
+/// built-in preset of the same name in place.
+pub fn all_presets(saved: &[LayoutPreset]) -> Vec<LayoutPreset> {
+    let mut presets = builtin_presets();
+    for preset in saved {
+        match presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset.clone(),
+            None => presets.push(preset.clone()),
+        }
+    }
+    presets
+}
+
+/// Insert or replace the saved preset with the same name.
+pub fn upsert_preset(saved: &mut Vec<LayoutPreset>, preset: LayoutPreset) {
+    match saved.iter_mut().find(|p| p.name == preset.name) {
+        Some(existing) => *existing = preset,
+        None => saved.push(preset),
+    }
+}
+
+/// View state restored on launch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Visible panel, named as in [`LayoutPreset::panel`].
+    pub panel: String,
+    pub drawer_open: bool,
+    /// UUID of the run the time range is snapped to, if any.
+    pub snapped_run: Option<String>,
+    /// Filter of the traces panel, with its time range.
+    pub trace_filter: TraceFilter,
+    /// Times the node metrics charts are zoomed to; all the data when unset.
+    pub time_range: Option<TimeRange>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            panel: "dataflows".to_string(),
+            drawer_open: false,
+            snapped_run: None,
+            trace_filter: TraceFilter::default(),
+            time_range: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str, panel: &str) -> LayoutPreset {
+        LayoutPreset {
+            name: name.to_string(),
+            panel: panel.to_string(),
+            drawer_open: false,
+            window: None,
+            size_only: false,
+        }
+    }
+
+    fn monitor(x: f64, y: f64, width: f64, height: f64) -> Monitor {
+        Monitor {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_monitor_key() {
+        assert_eq!(monitor_key(&[], 2.0), "dpi-2.00");
+        let laptop = monitor(0.0, 0.0, 1920.0, 1080.0);
+        let external = monitor(-2560.0, 0.0, 2560.0, 1440.0);
+        assert_eq!(
+            monitor_key(&[laptop, external], 1.0),
+            "1920x1080+0+0,2560x1440-2560+0"
+        );
+        // Same monitors listed in another order, at another scale
+        assert_eq!(
+            monitor_key(&[external, laptop], 2.0),
+            monitor_key(&[laptop, external], 1.0)
+        );
+        assert_ne!(monitor_key(&[laptop], 1.0), monitor_key(&[external], 1.0));
+    }
+
+    #[test]
+    fn test_clamp_to_monitors() {
+        let geometry = WindowGeometry {
+            width: 800.0,
+            height: 600.0,
+            x: 3000.0,
+            y: 100.0,
+        };
+        assert_eq!(clamp_to_monitors(geometry, &[], 1.0), geometry);
+
+        // Saved on a monitor since unplugged: moved onto the remaining one
+        let laptop = [monitor(0.0, 0.0, 2880.0, 1800.0)];
+        let restored = clamp_to_monitors(geometry, &laptop, 2.0);
+        assert_eq!((restored.x, restored.y), (640.0, 100.0));
+
+        // Partly off the right monitor: pulled fully onto it
+        let monitors = [laptop[0], monitor(2880.0, 0.0, 1920.0, 1080.0)];
+        let partly = WindowGeometry {
+            x: 2700.0,
+            ..geometry
+        };
+        assert_eq!(clamp_to_monitors(partly, &monitors, 1.0).x, 2880.0);
+
+        // Too large for the monitor: shrunk to fit
+        let huge = WindowGeometry {
+            width: 5000.0,
+            ..geometry
+        };
+        let restored = clamp_to_monitors(huge, &laptop, 1.0);
+        assert_eq!((restored.x, restored.width), (0.0, 2880.0));
+    }
+
+    #[test]
+    fn test_ui_scale() {
+        assert_eq!(clamp_ui_scale(50), 80);
+        assert_eq!(clamp_ui_scale(300), 200);
+        assert_eq!(scaled_dpi(2.0, 150), 3.0);
+        assert_eq!(scaled_dpi(1.0, 20), 0.8);
+        assert_eq!(UI_SCALES[ui_scale_index(100)], 100);
+        assert_eq!(UI_SCALES[ui_scale_index(120)], 125);
+    }
+
+    #[test]
+    fn test_geometry_validity() {
+        let geometry = WindowGeometry {
+            width: 800.0,
+            height: 600.0,
+            x: -100.0,
+            y: 0.0,
+        };
+        assert!(geometry.is_valid());
+        assert!(!WindowGeometry {
+            width: 10.0,
+            ..geometry
+        }
+        .is_valid());
+        assert!(!WindowGeometry {
+            x: f64::NAN,
+            ..geometry
+        }
+        .is_valid());
+    }
+
+    #[test]
+    fn test_split_size_falls_back_to_default() {
+        let saved = BTreeMap::from([
+            (CHAT_SPLIT.to_string(), 420.0),
+            (DATAFLOW_SPLIT.to_string(), 12.0),
+        ]);
+        assert_eq!(split_size(&saved, CHAT_SPLIT), 420.0);
+        assert_eq!(split_size(&saved, DATAFLOW_SPLIT), 360.0);
+        assert_eq!(split_size(&BTreeMap::new(), CHAT_SPLIT), 300.0);
+    }
+
+    #[test]
+    fn test_saved_preset_overrides_builtin() {
+        let saved = vec![preset("laptop", "traces"), preset("desk", "alerts")];
+        let presets = all_presets(&saved);
+        let names: Vec<&str> = presets.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["laptop", "control room", "desk"]);
+        assert_eq!(presets[0].panel, "traces");
+    }
+
+    #[test]
+    fn test_upsert_preset_replaces_by_name() {
+        let mut saved = vec![preset("desk", "alerts")];
+        upsert_preset(&mut saved, preset("desk", "traces"));
+        upsert_preset(&mut saved, preset("wall", "dataflows"));
+        assert_eq!(saved.len(), 2);
+        assert_eq!(saved[0].panel, "traces");
+    }
+
+    #[test]
+    fn test_partial_session_uses_defaults() {
+        let session: Session = serde_json::from_str(r#"{"drawer_open":true}"#).unwrap();
+        assert_eq!(session.panel, "dataflows");
+        assert!(session.drawer_open);
+        assert_eq!(session.snapped_run, None);
+        assert_eq!(session.trace_filter, TraceFilter::default());
+        assert_eq!(session.time_range, None);
+    }
+
+    #[test]
+    fn test_session_round_trips_filter_and_range() {
+        let session = Session {
+            trace_filter: TraceFilter::parse("camera", "", "15m", "node.id=camera").unwrap(),
+            time_range: Some(TimeRange {
+                start_ms: 1_000,
+                end_ms: 61_000,
+            }),
+            ..Session::default()
+        };
+        let json = serde_json::to_string(&session).unwrap();
+        assert_eq!(serde_json::from_str::<Session>(&json).unwrap(), session);
+    }
+}
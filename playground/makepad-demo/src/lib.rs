@@ -2,17 +2,101 @@ pub use makepad_widgets;
 
 pub mod api;
 pub mod app;
+pub mod chart;
 pub mod chat;
+pub mod config_bundle;
+pub mod dashboard;
 pub mod dataflow;
+pub mod datetime;
+pub mod diagnostics;
+pub mod i18n;
+pub mod instrument;
+pub mod layout;
+pub mod logging;
+pub mod notifications;
+pub mod otlp;
+pub mod palette;
+pub mod remote;
+pub mod settings;
+pub mod shortcuts;
+pub mod snapshot;
+pub mod status_bar;
+pub mod text;
+pub mod traces;
+pub mod units;
 
 // Tools module only available on native platforms (uses shell commands)
 #[cfg(not(target_arch = "wasm32"))]
 pub mod tools;
 
-// OTLP telemetry client module only available on native platforms
+// Alerting rules evaluate trace spans, so only available on native platforms
 #[cfg(not(target_arch = "wasm32"))]
-pub mod otlp;
+pub mod alerts;
 
-// Traces panel module only available on native platforms
+// Local storage and node metrics sampling only available on native platforms
 #[cfg(not(target_arch = "wasm32"))]
-pub mod traces;
+pub mod metrics;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod storage;
+
+// SQL console over local storage, so only available on native platforms
+#[cfg(not(target_arch = "wasm32"))]
+pub mod query;
+
+// Project scaffolding runs `dora new`, so only available on native platforms
+#[cfg(not(target_arch = "wasm32"))]
+pub mod scaffold;
+
+// Headless API server over the native subsystems
+#[cfg(not(target_arch = "wasm32"))]
+pub mod serve;
+
+// Background tasks run on threads, so only available on native platforms
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tasks;
+
+// Desktop notifications only available on native platforms
+#[cfg(not(target_arch = "wasm32"))]
+pub mod desktop_notify;
+
+// Headless test harness, native only like the coordinator clients it uses;
+// integration tests enable it with the `test-harness` feature
+#[cfg(all(not(target_arch = "wasm32"), any(test, feature = "test-harness")))]
+pub mod harness;
+
+// YAML file watcher only available on native platforms
+#[cfg(not(target_arch = "wasm32"))]
+pub mod watcher;
+
+// Crash reports are written to the data directory, so only available on
+// native platforms
+#[cfg(not(target_arch = "wasm32"))]
+pub mod crash;
+
+// The app layout names widgets of the native-only modules above; on the web
+// they are empty stand-ins so the layout still resolves
+#[cfg(target_arch = "wasm32")]
+#[macro_use]
+mod web_stubs;
+#[cfg(target_arch = "wasm32")]
+web_stub!(alerts::alerts_panel::AlertsPanel);
+#[cfg(target_arch = "wasm32")]
+web_stub!(crash::crash_dialog::CrashDialog);
+#[cfg(target_arch = "wasm32")]
+web_stub!(query::query_panel::QueryPanel);
+#[cfg(target_arch = "wasm32")]
+web_stub!(scaffold::new_project_wizard::NewProjectWizard);
+
+// Monitors are asked of the desktop with its own commands, so only
+// available on native platforms
+#[cfg(not(target_arch = "wasm32"))]
+pub mod monitors;
+
+// The OS credential store and the secrets file are only available on native platforms
+#[cfg(not(target_arch = "wasm32"))]
+pub mod secrets;
+
+// URL scheme links are handed between local processes, so only available on
+// native platforms
+#[cfg(not(target_arch = "wasm32"))]
+pub mod deep_link;
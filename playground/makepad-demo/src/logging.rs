@@ -0,0 +1,435 @@
+//! Structured logging for studio internals.
+//!
+//! Records are written as JSON lines to `studio.jsonl` in the log directory,
+//! rotated by size, echoed to stderr, and kept in a small in-memory ring so
+//! the diagnostics panel can show recent activity without touching disk.
+//! Code logs through the `tracing` macros, e.g.
+//! `tracing::info!(target: "App", "...")`; [`StudioSubscriber`] routes
+//! their events here.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, TryLockError};
+use tracing::span;
+
+const LOG_FILE_STEM: &str = "studio";
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_ROTATED_FILES: usize = 5;
+const RECENT_CAPACITY: usize = 1000;
+
+/// Severity of a log record, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    pub const ALL: [Level; 5] = [
+        Level::Trace,
+        Level::Debug,
+        Level::Info,
+        Level::Warn,
+        Level::Error,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Trace => "TRACE",
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+        }
+    }
+}
+
+/// A single structured log record.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub timestamp_ms: u64,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+struct Logger {
+    level: Level,
+    dir: Option<PathBuf>,
+    file: Option<File>,
+    file_bytes: u64,
+    recent: VecDeque<LogRecord>,
+    /// Records kept since startup, to tell when `recent` changed.
+    total: u64,
+}
+
+impl Logger {
+    const fn new() -> Self {
+        Self {
+            level: Level::Info,
+            dir: None,
+            file: None,
+            file_bytes: 0,
+            recent: VecDeque::new(),
+            total: 0,
+        }
+    }
+
+    fn write_line(&mut self, line: &str) {
+        let Some(dir) = self.dir.clone() else {
+            return;
+        };
+
+        let line_bytes = line.len() as u64 + 1;
+        if self.file.is_some() && self.file_bytes + line_bytes > MAX_FILE_BYTES {
+            self.file = None;
+            rotate_files(&dir, MAX_ROTATED_FILES);
+            self.file_bytes = 0;
+        }
+
+        if self.file.is_none() {
+            let path = log_file_path(&dir, 0);
+            match OpenOptions::new().create(true).append(true).open(&path) {
+                Ok(f) => {
+                    self.file_bytes = f.metadata().map(|m| m.len()).unwrap_or(0);
+                    self.file = Some(f);
+                }
+                Err(e) => {
+                    let _ = writeln!(
+                        std::io::stderr(),
+                        "[Logging] Failed to open {}: {}",
+                        path.display(),
+                        e
+                    );
+                    self.dir = None;
+                    return;
+                }
+            }
+        }
+
+        if let Some(file) = self.file.as_mut() {
+            if writeln!(file, "{}", line).is_ok() {
+                self.file_bytes += line_bytes;
+            }
+        }
+    }
+}
+
+static LOGGER: Mutex<Logger> = Mutex::new(Logger::new());
+
+/// Initialise file output and the minimum level, and route `tracing`
+/// events into the log.
+///
+/// Passing `None` for `dir` keeps logging in-memory and on stderr only.
+pub fn init(dir: Option<PathBuf>, level: Level) {
+    let dir = dir.filter(|d| std::fs::create_dir_all(d).is_ok());
+    {
+        let mut logger = LOGGER.lock().unwrap();
+        logger.level = level;
+        logger.dir = dir;
+        logger.file = None;
+        logger.file_bytes = 0;
+    }
+    // Fails only if a subscriber is already installed, e.g. on a second init
+    let _ = tracing::subscriber::set_global_default(StudioSubscriber);
+}
+
+/// Change the minimum level at runtime.
+pub fn set_level(level: Level) {
+    LOGGER.lock().unwrap().level = level;
+}
+
+/// Current minimum level.
+pub fn level() -> Level {
+    LOGGER.lock().unwrap().level
+}
+
+/// Record a message if `level` passes the configured threshold. Code logs
+/// through the `tracing` macros, which [`StudioSubscriber`] forwards here.
+///
+/// Never panics while holding the logger, so the panic hook can log too.
+fn log(level: Level, target: &str, message: &str) {
+    let mut logger = LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+    if level < logger.level {
+        return;
+    }
+
+    let record = LogRecord {
+        timestamp_ms: now_ms(),
+        level,
+        target: target.to_string(),
+        message: message.to_string(),
+    };
+
+    let _ = writeln!(std::io::stderr(), "[{}] {}", target, message);
+
+    if let Ok(line) = serde_json::to_string(&record) {
+        logger.write_line(&line);
+    }
+
+    if logger.recent.len() == RECENT_CAPACITY {
+        logger.recent.pop_front();
+    }
+    logger.recent.push_back(record);
+    logger.total += 1;
+}
+
+/// Snapshot of the most recent records, oldest first.
+pub fn recent_records() -> Vec<LogRecord> {
+    LOGGER.lock().unwrap().recent.iter().cloned().collect()
+}
+
+/// Like [`recent_records`], but never blocks: for the panic hook, which
+/// may run while the panicking thread holds the logger.
+pub fn try_recent_records() -> Vec<LogRecord> {
+    match LOGGER.try_lock() {
+        Ok(logger) => logger.recent.iter().cloned().collect(),
+        Err(TryLockError::Poisoned(poisoned)) => {
+            poisoned.into_inner().recent.iter().cloned().collect()
+        }
+        Err(TryLockError::WouldBlock) => Vec::new(),
+    }
+}
+
+/// Number of records kept since startup; changes whenever
+/// [`recent_records`] does.
+pub fn record_count() -> u64 {
+    LOGGER.lock().unwrap().total
+}
+
+/// Records at `level` or above, keeping their order.
+pub fn at_least(records: &[LogRecord], level: Level) -> Vec<LogRecord> {
+    records
+        .iter()
+        .filter(|record| record.level >= level)
+        .cloned()
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// tracing integration
+// ---------------------------------------------------------------------------
+
+impl From<tracing::Level> for Level {
+    fn from(level: tracing::Level) -> Self {
+        if level == tracing::Level::ERROR {
+            Level::Error
+        } else if level == tracing::Level::WARN {
+            Level::Warn
+        } else if level == tracing::Level::INFO {
+            Level::Info
+        } else if level == tracing::Level::DEBUG {
+            Level::Debug
+        } else {
+            Level::Trace
+        }
+    }
+}
+
+/// Forwards `tracing` events to [`log`], using the event target as the
+/// record target (`tracing::debug!(target: "App", ...)`).
+///
+/// Spans are not tracked. Events from dependencies (hyper, h2, reqwest) are
+/// kept only from `Warn` up so they do not drown out studio's own records.
+pub struct StudioSubscriber;
+
+impl tracing::Subscriber for StudioSubscriber {
+    fn register_callsite(
+        &self,
+        _metadata: &'static tracing::Metadata<'static>,
+    ) -> tracing::subscriber::Interest {
+        // The level can change at runtime, so never cache the decision
+        tracing::subscriber::Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+        Level::from(*metadata.level()) >= threshold(metadata.target(), level())
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        let mut message = EventMessage::default();
+        event.record(&mut message);
+        let metadata = event.metadata();
+        log(
+            Level::from(*metadata.level()),
+            metadata.target(),
+            &message.text,
+        );
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+/// Collects an event's `message` followed by its other fields as `key=value`.
+#[derive(Default)]
+struct EventMessage {
+    text: String,
+}
+
+impl EventMessage {
+    fn push(&mut self, field: &tracing::field::Field, value: &str) {
+        if field.name() == "message" {
+            self.text.insert_str(0, value);
+        } else {
+            self.text.push_str(&format!(" {}={}", field.name(), value));
+        }
+    }
+}
+
+impl tracing::field::Visit for EventMessage {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.push(field, value);
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.push(field, &format!("{:?}", value));
+    }
+}
+
+/// Minimum level for a target: the configured level for studio's own
+/// targets, at least `Warn` for module paths of other crates.
+fn threshold(target: &str, configured: Level) -> Level {
+    let external = target.contains("::") && !target.starts_with("dora_studio");
+    if external {
+        configured.max(Level::Warn)
+    } else {
+        configured
+    }
+}
+
+/// Parse a JSONL log file back into records, skipping malformed lines.
+pub fn parse_log_lines(input: &str) -> Vec<LogRecord> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Internal helpers
+// ---------------------------------------------------------------------------
+
+/// `studio.jsonl` for index 0, `studio.N.jsonl` for rotated files.
+fn log_file_path(dir: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        dir.join(format!("{}.jsonl", LOG_FILE_STEM))
+    } else {
+        dir.join(format!("{}.{}.jsonl", LOG_FILE_STEM, index))
+    }
+}
+
+/// Shift `studio.jsonl` → `studio.1.jsonl` → … dropping the oldest file.
+fn rotate_files(dir: &Path, max_files: usize) {
+    let _ = std::fs::remove_file(log_file_path(dir, max_files));
+    for index in (0..max_files).rev() {
+        let from = log_file_path(dir, index);
+        if from.exists() {
+            let _ = std::fs::rename(&from, log_file_path(dir, index + 1));
+        }
+    }
+}
+
+/// Current wall-clock time in milliseconds since the Unix epoch.
+pub fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_ordering() {
+        assert!(Level::Trace < Level::Debug);
+        assert!(Level::Info < Level::Warn);
+        assert!(Level::Warn < Level::Error);
+    }
+
+    #[test]
+    fn test_level_serde() {
+        assert_eq!(serde_json::to_string(&Level::Warn).unwrap(), "\"warn\"");
+        let level: Level = serde_json::from_str("\"error\"").unwrap();
+        assert_eq!(level, Level::Error);
+    }
+
+    #[test]
+    fn test_parse_log_lines_skips_malformed() {
+        let input = r#"{"timestamp_ms":1,"level":"info","target":"api","message":"hello"}
+not json
+{"timestamp_ms":2,"level":"error","target":"signoz","message":"boom"}"#;
+        let records = parse_log_lines(input);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].target, "api");
+        assert_eq!(records[1].level, Level::Error);
+    }
+
+    #[test]
+    fn test_threshold_quiets_dependencies() {
+        assert_eq!(threshold("App", Level::Debug), Level::Debug);
+        assert_eq!(threshold("dora_studio::app", Level::Debug), Level::Debug);
+        assert_eq!(threshold("hyper_util::client", Level::Debug), Level::Warn);
+        assert_eq!(threshold("hyper_util::client", Level::Error), Level::Error);
+    }
+
+    #[test]
+    fn test_at_least() {
+        let records = parse_log_lines(
+            r#"{"timestamp_ms":1,"level":"debug","target":"App","message":"a"}
+{"timestamp_ms":2,"level":"warn","target":"App","message":"b"}
+{"timestamp_ms":3,"level":"error","target":"App","message":"c"}"#,
+        );
+        let messages: Vec<String> = at_least(&records, Level::Warn)
+            .into_iter()
+            .map(|r| r.message)
+            .collect();
+        assert_eq!(messages, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn test_log_file_path() {
+        let dir = Path::new("/tmp/logs");
+        assert_eq!(log_file_path(dir, 0), dir.join("studio.jsonl"));
+        assert_eq!(log_file_path(dir, 2), dir.join("studio.2.jsonl"));
+    }
+
+    #[test]
+    fn test_rotate_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(log_file_path(dir.path(), 0), "current").unwrap();
+        std::fs::write(log_file_path(dir.path(), 1), "older").unwrap();
+        std::fs::write(log_file_path(dir.path(), 2), "oldest").unwrap();
+
+        rotate_files(dir.path(), 2);
+
+        assert!(!log_file_path(dir.path(), 0).exists());
+        let first = std::fs::read_to_string(log_file_path(dir.path(), 1)).unwrap();
+        let second = std::fs::read_to_string(log_file_path(dir.path(), 2)).unwrap();
+        assert_eq!(first, "current");
+        assert_eq!(second, "older");
+    }
+}
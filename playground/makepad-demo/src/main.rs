@@ -1,3 +1,44 @@
 fn main() {
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(command) = dora_studio::secrets::command(std::env::args().skip(1)) {
+        match command.run() {
+            Ok(message) => println!("{}", message),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(addr) = dora_studio::serve::serve_addr(std::env::args().skip(1)) {
+        if let Err(e) = dora_studio::serve::run(&addr) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        use dora_studio::deep_link::{self, DeepLink};
+        if deep_link::register_requested(std::env::args().skip(1)) {
+            match deep_link::register() {
+                Ok(message) => println!("{}", message),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        // Open the link in the studio already running, if there is one
+        if let Some(url) = deep_link::link_arg(std::env::args().skip(1)) {
+            match DeepLink::parse(&url) {
+                Ok(link) if deep_link::hand_off(&link).is_ok() => return,
+                Ok(link) => deep_link::push(link),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+    }
     dora_studio::app::app_main()
 }
@@ -0,0 +1,130 @@
+//! In-memory recent series per node, used for sparklines.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use super::NodeMetrics;
+
+/// Samples kept per node (about two minutes at the default interval).
+const MAX_POINTS: usize = 60;
+
+/// Recent samples of one node.
+#[derive(Debug, Clone, Default)]
+pub struct NodeSeries {
+    pub dataflow_id: String,
+    pub node_id: String,
+    pub pid: u32,
+    /// When each sample was taken
+    pub timestamps_ms: VecDeque<u64>,
+    pub cpu: VecDeque<f64>,
+    pub memory_mb: VecDeque<f64>,
+}
+
+impl NodeSeries {
+    /// Identifies the series in a legend.
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.dataflow_id, self.node_id)
+    }
+
+    pub fn latest_cpu(&self) -> f64 {
+        self.cpu.back().copied().unwrap_or_default()
+    }
+
+    pub fn latest_memory_mb(&self) -> f64 {
+        self.memory_mb.back().copied().unwrap_or_default()
+    }
+}
+
+/// Rolling series keyed by `(dataflow_id, node_id)`.
+#[derive(Debug, Clone, Default)]
+pub struct MetricsHistory {
+    series: BTreeMap<(String, String), NodeSeries>,
+}
+
+impl MetricsHistory {
+    pub fn push(&mut self, sample: NodeMetrics) {
+        let key = (sample.dataflow_id.clone(), sample.node_id.clone());
+        let series = self.series.entry(key).or_insert_with(|| NodeSeries {
+            dataflow_id: sample.dataflow_id.clone(),
+            node_id: sample.node_id.clone(),
+            ..Default::default()
+        });
+        series.pid = sample.pid;
+        push_capped(&mut series.timestamps_ms, sample.timestamp_ms);
+        push_capped(&mut series.cpu, sample.cpu_percent as f64);
+        push_capped(&mut series.memory_mb, sample.memory_mb);
+    }
+
+    /// Drop series of dataflows that are no longer running.
+    pub fn retain_dataflows(&mut self, running: &[String]) {
+        self.series.retain(|(df, _), _| running.contains(df));
+    }
+
+    /// All series, ordered by dataflow then node.
+    pub fn series(&self) -> Vec<NodeSeries> {
+        self.series.values().cloned().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+}
+
+fn push_capped<T>(values: &mut VecDeque<T>, value: T) {
+    if values.len() == MAX_POINTS {
+        values.pop_front();
+    }
+    values.push_back(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(df: &str, node: &str, cpu: f32) -> NodeMetrics {
+        NodeMetrics {
+            timestamp_ms: 0,
+            dataflow_id: df.to_string(),
+            node_id: node.to_string(),
+            pid: 1,
+            cpu_percent: cpu,
+            memory_mb: 10.0,
+        }
+    }
+
+    #[test]
+    fn test_push_groups_by_node() {
+        let mut history = MetricsHistory::default();
+        history.push(sample("df", "b", 1.0));
+        history.push(sample("df", "a", 2.0));
+        history.push(sample("df", "a", 3.0));
+
+        let series = history.series();
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].node_id, "a");
+        assert_eq!(series[0].cpu, vec![2.0, 3.0]);
+        assert_eq!(series[0].latest_cpu(), 3.0);
+    }
+
+    #[test]
+    fn test_push_caps_points() {
+        let mut history = MetricsHistory::default();
+        for i in 0..(MAX_POINTS + 5) {
+            history.push(sample("df", "a", i as f32));
+        }
+        let series = &history.series()[0];
+        assert_eq!(series.cpu.len(), MAX_POINTS);
+        assert_eq!(series.cpu[0], 5.0);
+        assert_eq!(series.timestamps_ms.len(), MAX_POINTS);
+    }
+
+    #[test]
+    fn test_retain_dataflows() {
+        let mut history = MetricsHistory::default();
+        history.push(sample("old", "a", 1.0));
+        history.push(sample("new", "a", 1.0));
+        history.retain_dataflows(&["new".to_string()]);
+        let series = history.series();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].dataflow_id, "new");
+    }
+}
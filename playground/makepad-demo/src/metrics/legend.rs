@@ -0,0 +1,105 @@
+//! Legend state shared by multi-series metric views.
+//!
+//! Clicking a legend entry isolates its series; clicking it again shows all
+//! series. Shift-click adds or removes a series from the comparison set.
+//! Hovering highlights a series. Series are identified by a string key,
+//! e.g. `dataflow_id/node_id` for node metrics.
+
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Legend {
+    /// Series shown; empty means all.
+    selected: BTreeSet<String>,
+    hovered: Option<String>,
+}
+
+impl Legend {
+    /// Apply a click on a legend entry, with `shift` held to compare.
+    pub fn click(&mut self, key: &str, shift: bool) {
+        if shift {
+            if !self.selected.remove(key) {
+                self.selected.insert(key.to_string());
+            }
+        } else if self.selected.len() == 1 && self.selected.contains(key) {
+            self.selected.clear();
+        } else {
+            self.selected = BTreeSet::from([key.to_string()]);
+        }
+    }
+
+    pub fn is_visible(&self, key: &str) -> bool {
+        self.selected.is_empty() || self.selected.contains(key)
+    }
+
+    /// Whether only some series are shown.
+    pub fn is_filtered(&self) -> bool {
+        !self.selected.is_empty()
+    }
+
+    /// Set the hovered series; returns whether it changed.
+    pub fn hover(&mut self, key: Option<&str>) -> bool {
+        if self.hovered.as_deref() == key {
+            return false;
+        }
+        self.hovered = key.map(str::to_string);
+        true
+    }
+
+    pub fn is_highlighted(&self, key: &str) -> bool {
+        self.hovered.as_deref() == Some(key)
+    }
+
+    /// Forget series that are no longer present, so an isolated series that
+    /// disappears does not leave the view empty.
+    pub fn retain<'a>(&mut self, keys: impl IntoIterator<Item = &'a str>) {
+        let keys: BTreeSet<&str> = keys.into_iter().collect();
+        self.selected.retain(|k| keys.contains(k.as_str()));
+        if self.hovered.as_deref().is_some_and(|k| !keys.contains(k)) {
+            self.hovered = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_click_isolates_and_click_again_shows_all() {
+        let mut legend = Legend::default();
+        assert!(legend.is_visible("a") && legend.is_visible("b"));
+        legend.click("a", false);
+        assert!(legend.is_visible("a") && !legend.is_visible("b"));
+        legend.click("b", false);
+        assert!(!legend.is_visible("a") && legend.is_visible("b"));
+        legend.click("b", false);
+        assert!(!legend.is_filtered());
+    }
+
+    #[test]
+    fn test_shift_click_compares() {
+        let mut legend = Legend::default();
+        legend.click("a", false);
+        legend.click("b", true);
+        assert!(legend.is_visible("a") && legend.is_visible("b"));
+        assert!(!legend.is_visible("c"));
+        legend.click("a", true);
+        assert!(!legend.is_visible("a") && legend.is_visible("b"));
+        // Removing the last compared series shows all again
+        legend.click("b", true);
+        assert!(!legend.is_filtered());
+    }
+
+    #[test]
+    fn test_hover_and_retain() {
+        let mut legend = Legend::default();
+        assert!(legend.hover(Some("a")));
+        assert!(!legend.hover(Some("a")));
+        assert!(legend.is_highlighted("a"));
+        legend.click("a", false);
+        legend.retain(["b"]);
+        assert!(!legend.is_filtered());
+        assert!(!legend.is_highlighted("a"));
+    }
+}
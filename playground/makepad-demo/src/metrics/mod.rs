@@ -0,0 +1,210 @@
+//! Per-node CPU and memory sampling.
+//!
+//! Dora launches every node process with a `DORA_NODE_CONFIG` environment
+//! variable holding the coordinator-assigned `dataflow_id` and the `node_id`.
+//! A background thread scans processes with `sysinfo`, keeps those belonging
+//! to dataflows the coordinator currently reports, and records one
+//! [`NodeMetrics`] sample per node per interval. Samples are appended to the
+//! `metrics` storage table and queued for the UI (same polling pattern as the
+//! SigNoz bridge).
+
+pub mod history;
+pub mod legend;
+
+pub use history::{MetricsHistory, NodeSeries};
+pub use legend::Legend;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+use sysinfo::System;
+
+use crate::logging;
+use crate::storage::{Record, Storage, METRICS_TABLE};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+const NODE_CONFIG_ENV: &str = "DORA_NODE_CONFIG=";
+
+/// One CPU/memory sample of a dataflow node process.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeMetrics {
+    pub timestamp_ms: u64,
+    pub dataflow_id: String,
+    pub node_id: String,
+    pub pid: u32,
+    pub cpu_percent: f32,
+    pub memory_mb: f64,
+}
+
+impl Record for NodeMetrics {
+    fn timestamp_ms(&self) -> u64 {
+        self.timestamp_ms
+    }
+}
+
+static SAMPLER_STARTED: Mutex<bool> = Mutex::new(false);
+static ACTIVE_DATAFLOWS: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+static SAMPLES: Mutex<Vec<NodeMetrics>> = Mutex::new(Vec::new());
+
+/// Start the background sampler once. Samples are persisted when `storage`
+/// is available.
+pub fn start_sampler(storage: Option<Storage>) {
+    {
+        let mut started = SAMPLER_STARTED.lock().unwrap();
+        if *started {
+            return;
+        }
+        *started = true;
+    }
+
+    std::thread::spawn(move || {
+        let mut system = System::new();
+        loop {
+            // sysinfo computes CPU usage between two refreshes, so a node's
+            // first sample reports 0% CPU.
+            system.refresh_processes();
+            let samples = sample_nodes(&system, logging::now_ms());
+
+            if !samples.is_empty() {
+                if let Some(storage) = &storage {
+                    if let Err(e) = storage.append(METRICS_TABLE, &samples) {
+                        tracing::warn!(target: "Metrics", "Failed to store samples: {}", e);
+                    }
+                }
+                SAMPLES.lock().unwrap().extend(samples);
+            }
+
+            std::thread::sleep(SAMPLE_INTERVAL);
+        }
+    });
+}
+
+/// Restrict sampling to the dataflows the coordinator reports as running.
+pub fn set_active_dataflows(uuids: impl IntoIterator<Item = String>) {
+    *ACTIVE_DATAFLOWS.lock().unwrap() = Some(uuids.into_iter().collect());
+}
+
+/// Drain samples collected since the last call.
+pub fn take_node_metrics() -> Vec<NodeMetrics> {
+    std::mem::take(&mut *SAMPLES.lock().unwrap())
+}
+
+fn sample_nodes(system: &System, timestamp_ms: u64) -> Vec<NodeMetrics> {
+    let active = ACTIVE_DATAFLOWS.lock().unwrap();
+    let Some(active) = active.as_ref().filter(|a| !a.is_empty()) else {
+        return Vec::new();
+    };
+
+    system
+        .processes()
+        .iter()
+        .filter_map(|(pid, process)| {
+            let (dataflow_id, node_id) = node_identity(process.environ())?;
+            if !active.contains(&dataflow_id) {
+                return None;
+            }
+            Some(NodeMetrics {
+                timestamp_ms,
+                dataflow_id,
+                node_id,
+                pid: pid.as_u32(),
+                cpu_percent: process.cpu_usage(),
+                memory_mb: process.memory() as f64 / (1024.0 * 1024.0),
+            })
+        })
+        .collect()
+}
+
+/// Extract `(dataflow_id, node_id)` from a process environment.
+///
+/// `DORA_NODE_CONFIG` is a YAML document; only its top-level scalar keys are
+/// needed, so they are read line by line rather than pulling in a YAML parser.
+pub fn node_identity(environ: &[String]) -> Option<(String, String)> {
+    let config = environ
+        .iter()
+        .find_map(|entry| entry.strip_prefix(NODE_CONFIG_ENV))?;
+
+    let mut dataflow_id = None;
+    let mut node_id = None;
+    for line in config.lines() {
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            match key {
+                "dataflow_id" => dataflow_id = Some(value.to_string()),
+                "node_id" => node_id = Some(value.to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    match (dataflow_id, node_id) {
+        (Some(d), Some(n)) if !d.is_empty() && !n.is_empty() => Some((d, n)),
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn environ(config: &str) -> Vec<String> {
+        vec![
+            "PATH=/usr/bin".to_string(),
+            format!("{}{}", NODE_CONFIG_ENV, config),
+        ]
+    }
+
+    #[test]
+    fn test_node_identity() {
+        let env = environ(
+            "dataflow_id: 0193a1b2-7c3d-7e4f-8a9b-0c1d2e3f4a5b\nnode_id: camera\nrun_config:\n  node_id: nested\n",
+        );
+        assert_eq!(
+            node_identity(&env),
+            Some((
+                "0193a1b2-7c3d-7e4f-8a9b-0c1d2e3f4a5b".to_string(),
+                "camera".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_node_identity_quoted_values() {
+        let env = environ("dataflow_id: \"abc\"\nnode_id: 'plot'\n");
+        assert_eq!(
+            node_identity(&env),
+            Some(("abc".to_string(), "plot".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_node_identity_missing() {
+        assert_eq!(node_identity(&["PATH=/usr/bin".to_string()]), None);
+        assert_eq!(node_identity(&environ("node_id: camera\n")), None);
+    }
+
+    #[test]
+    fn test_node_metrics_storage_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(dir.path()).unwrap();
+        let sample = NodeMetrics {
+            timestamp_ms: 1_000,
+            dataflow_id: "df".to_string(),
+            node_id: "camera".to_string(),
+            pid: 42,
+            cpu_percent: 12.5,
+            memory_mb: 64.0,
+        };
+        storage.append(METRICS_TABLE, &[sample.clone()]).unwrap();
+        let read: Vec<NodeMetrics> = storage.read(METRICS_TABLE, 0, 2_000).unwrap();
+        assert_eq!(read, vec![sample]);
+    }
+}
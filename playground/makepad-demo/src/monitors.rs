@@ -0,0 +1,146 @@
+//! The monitors attached, for keying and placing the window.
+//!
+//! Makepad reports the window's geometry but not the monitors around it, so
+//! they are asked of the desktop once at startup on a background thread:
+//! `xrandr --listmonitors` on Linux, `NSScreen` through `osascript` on macOS
+//! and `System.Windows.Forms.Screen` through PowerShell on Windows. Where
+//! none of these answers, the list is empty and the window is placed as
+//! saved.
+
+use std::sync::OnceLock;
+
+use crate::layout::Monitor;
+use crate::tools::platform;
+
+static MONITORS: OnceLock<Vec<Monitor>> = OnceLock::new();
+
+/// NSScreen frames in pixels, top-left origin, one `x y width height` per line.
+const MACOS_SCRIPT: &str = "ObjC.import('AppKit');\
+    const screens = $.NSScreen.screens.js;\
+    const top = screens[0].frame.size.height;\
+    screens.map(s => { const f = s.frame; const k = s.backingScaleFactor;\
+    return [f.origin.x * k, (top - f.origin.y - f.size.height) * k,\
+    f.size.width * k, f.size.height * k].join(' '); }).join('\\n')";
+
+/// Screen bounds in pixels, one `x y width height` per line.
+const WINDOWS_SCRIPT: &str = "Add-Type -AssemblyName System.Windows.Forms;\
+    [System.Windows.Forms.Screen]::AllScreens | ForEach-Object {\
+    $b = $_.Bounds; \"$($b.X) $($b.Y) $($b.Width) $($b.Height)\" }";
+
+/// Ask the desktop for the monitors in the background.
+pub fn start_detection() {
+    std::thread::spawn(|| {
+        let monitors = detect();
+        tracing::debug!(target: "Monitors", "Detected {:?}", monitors);
+        let _ = MONITORS.set(monitors);
+    });
+}
+
+/// The monitors, once detection has finished; empty if it failed.
+pub fn detected() -> Option<Vec<Monitor>> {
+    MONITORS.get().cloned()
+}
+
+fn detect() -> Vec<Monitor> {
+    let (mut cmd, parse): (_, fn(&str) -> Vec<Monitor>) = if cfg!(windows) {
+        let mut cmd = platform::command("powershell");
+        cmd.args(["-NoProfile", "-Command", WINDOWS_SCRIPT]);
+        (cmd, parse_rects)
+    } else if cfg!(target_os = "macos") {
+        let mut cmd = platform::command("osascript");
+        cmd.args(["-l", "JavaScript", "-e", MACOS_SCRIPT]);
+        (cmd, parse_rects)
+    } else {
+        let mut cmd = platform::command("xrandr");
+        cmd.arg("--listmonitors");
+        (cmd, parse_xrandr)
+    };
+    match cmd.output() {
+        Ok(output) if output.status.success() => parse(&String::from_utf8_lossy(&output.stdout)),
+        _ => Vec::new(),
+    }
+}
+
+/// Monitors in lines of `x y width height`.
+fn parse_rects(text: &str) -> Vec<Monitor> {
+    text.lines()
+        .filter_map(|line| {
+            let values: Vec<f64> = line
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()
+                .ok()?;
+            match values[..] {
+                [x, y, width, height] if width > 0.0 && height > 0.0 => Some(Monitor {
+                    x,
+                    y,
+                    width,
+                    height,
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Monitors in `xrandr --listmonitors` output, whose lines read
+/// ` 0: +*eDP-1 1920/344x1080/193+0+0  eDP-1`.
+fn parse_xrandr(text: &str) -> Vec<Monitor> {
+    text.lines()
+        .filter_map(|line| line.split_whitespace().find_map(parse_xrandr_geometry))
+        .collect()
+}
+
+/// A monitor from `1920/344x1080/193+0+0`: pixels/millimetres on each axis,
+/// then the offset.
+fn parse_xrandr_geometry(token: &str) -> Option<Monitor> {
+    let (width, rest) = token.split_once('/')?;
+    let (_, rest) = rest.split_once('x')?;
+    let (height, rest) = rest.split_once('/')?;
+    // Signed offsets, e.g. `+0+0` or `-2560+0`
+    let offsets = &rest[rest.find(['+', '-'])?..];
+    let (x, y) = offsets.split_at(1 + offsets[1..].find(['+', '-'])?);
+    Some(Monitor {
+        x: x.parse().ok()?,
+        y: y.parse().ok()?,
+        width: width.parse().ok()?,
+        height: height.parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_xrandr() {
+        let output = "Monitors: 2\n \
+            0: +*eDP-1 1920/344x1080/193+0+0  eDP-1\n \
+            1: +HDMI-1 2560/597x1440/336-2560+0  HDMI-1\n";
+        assert_eq!(
+            parse_xrandr(output),
+            [
+                Monitor {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 1920.0,
+                    height: 1080.0,
+                },
+                Monitor {
+                    x: -2560.0,
+                    y: 0.0,
+                    width: 2560.0,
+                    height: 1440.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rects() {
+        let monitors = parse_rects("0 0 2880 1800\n2880 -200 3840 2160\nnot a screen\n");
+        assert_eq!(monitors.len(), 2);
+        assert_eq!(monitors[1].y, -200.0);
+        assert!(parse_rects("").is_empty());
+    }
+}
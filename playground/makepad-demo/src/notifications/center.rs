@@ -0,0 +1,304 @@
+//! In-app notification state.
+//!
+//! `App` owns a single [`NotificationCenter`]. Pushing a notification shows
+//! it as a toast until it is dismissed or its severity's display time runs
+//! out, and keeps a copy in a bounded history for the drawer. Identical
+//! notifications that are still on screen are coalesced into one toast with
+//! a repeat count, so a failing auto-refresh does not flood the stack.
+//!
+//! A notification can offer an action, shown as a button on its toast
+//! ([`NotificationCenter::push_action`]); such toasts stay until they are
+//! dismissed or acted on.
+
+use std::collections::VecDeque;
+
+use crate::logging::Level;
+
+/// Toasts kept on screen at once; older ones are dropped from view.
+const MAX_ACTIVE: usize = 5;
+const MAX_HISTORY: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "INFO",
+            Severity::Success => "OK",
+            Severity::Warning => "WARN",
+            Severity::Error => "ERROR",
+        }
+    }
+
+    /// How long a toast stays visible before it is dismissed automatically.
+    pub fn display_ms(&self) -> u64 {
+        match self {
+            Severity::Info | Severity::Success => 4_000,
+            Severity::Warning => 8_000,
+            Severity::Error => 15_000,
+        }
+    }
+
+    /// Level used when the notification is also written to the studio log.
+    pub fn log_level(&self) -> Level {
+        match self {
+            Severity::Info | Severity::Success => Level::Info,
+            Severity::Warning => Level::Warn,
+            Severity::Error => Level::Error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub id: u64,
+    pub severity: Severity,
+    pub title: String,
+    pub message: String,
+    /// Time of the latest occurrence, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    /// Number of coalesced occurrences.
+    pub count: u32,
+    pub expires_ms: u64,
+    /// Label of the button the toast offers, e.g. `Restart`.
+    pub action: Option<String>,
+}
+
+impl Notification {
+    /// Title with the repeat count appended, e.g. `Refresh failed (x3)`.
+    pub fn heading(&self) -> String {
+        if self.count > 1 {
+            format!("{} (x{})", self.title, self.count)
+        } else {
+            self.title.clone()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NotificationCenter {
+    /// Toasts on screen, newest first.
+    active: Vec<Notification>,
+    /// All notifications, newest first.
+    history: VecDeque<Notification>,
+    next_id: u64,
+    unread: usize,
+}
+
+impl NotificationCenter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show a notification, coalescing it with an identical visible toast.
+    /// Returns the id of the toast.
+    pub fn push(&mut self, severity: Severity, title: &str, message: &str, now_ms: u64) -> u64 {
+        self.push_inner(severity, title, message, None, now_ms)
+    }
+
+    /// Show a notification whose toast offers a button labelled `action`
+    /// until it is dismissed. Returns the id of the toast.
+    pub fn push_action(
+        &mut self,
+        severity: Severity,
+        title: &str,
+        message: &str,
+        action: &str,
+        now_ms: u64,
+    ) -> u64 {
+        self.push_inner(severity, title, message, Some(action), now_ms)
+    }
+
+    fn push_inner(
+        &mut self,
+        severity: Severity,
+        title: &str,
+        message: &str,
+        action: Option<&str>,
+        now_ms: u64,
+    ) -> u64 {
+        let expires_ms = match action {
+            Some(_) => u64::MAX,
+            None => now_ms + severity.display_ms(),
+        };
+
+        let existing = self.active.iter().position(|n| {
+            n.severity == severity
+                && n.title == title
+                && n.message == message
+                && n.action.as_deref() == action
+        });
+        if let Some(index) = existing {
+            let mut toast = self.active.remove(index);
+            toast.count += 1;
+            toast.timestamp_ms = now_ms;
+            toast.expires_ms = expires_ms;
+            if let Some(entry) = self.history.iter_mut().find(|n| n.id == toast.id) {
+                *entry = toast.clone();
+            }
+            let id = toast.id;
+            self.active.insert(0, toast);
+            return id;
+        }
+
+        self.next_id += 1;
+        let toast = Notification {
+            id: self.next_id,
+            severity,
+            title: title.to_string(),
+            message: message.to_string(),
+            timestamp_ms: now_ms,
+            count: 1,
+            expires_ms,
+            action: action.map(String::from),
+        };
+        self.history.push_front(toast.clone());
+        self.history.truncate(MAX_HISTORY);
+        self.active.insert(0, toast);
+        self.active.truncate(MAX_ACTIVE);
+        self.unread += 1;
+        self.next_id
+    }
+
+    /// Remove a toast from the screen. It stays in the history.
+    pub fn dismiss(&mut self, id: u64) -> bool {
+        let before = self.active.len();
+        self.active.retain(|n| n.id != id);
+        self.active.len() != before
+    }
+
+    /// Drop toasts whose display time has passed. Returns true if any were removed.
+    pub fn expire(&mut self, now_ms: u64) -> bool {
+        let before = self.active.len();
+        self.active.retain(|n| n.expires_ms > now_ms);
+        self.active.len() != before
+    }
+
+    pub fn active(&self) -> &[Notification] {
+        &self.active
+    }
+
+    pub fn history(&self) -> Vec<Notification> {
+        self.history.iter().cloned().collect()
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+        self.unread = 0;
+    }
+
+    /// Notifications pushed since the history was last viewed.
+    pub fn unread(&self) -> usize {
+        self.unread
+    }
+
+    pub fn mark_read(&mut self) {
+        self.unread = 0;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_shows_newest_first() {
+        let mut center = NotificationCenter::new();
+        let first = center.push(Severity::Info, "a", "", 0);
+        let second = center.push(Severity::Error, "b", "", 0);
+        assert_ne!(first, second);
+        assert_eq!(center.active()[0].id, second);
+        assert_eq!(center.history().len(), 2);
+        assert_eq!(center.unread(), 2);
+    }
+
+    #[test]
+    fn test_push_coalesces_identical_toasts() {
+        let mut center = NotificationCenter::new();
+        let id = center.push(Severity::Error, "Refresh failed", "timeout", 0);
+        center.push(Severity::Info, "Other", "", 10);
+        assert_eq!(
+            center.push(Severity::Error, "Refresh failed", "timeout", 20),
+            id
+        );
+
+        assert_eq!(center.active().len(), 2);
+        assert_eq!(center.active()[0].id, id);
+        assert_eq!(center.active()[0].count, 2);
+        assert_eq!(center.active()[0].heading(), "Refresh failed (x2)");
+        assert_eq!(center.history().len(), 2);
+        assert_eq!(center.unread(), 2);
+    }
+
+    #[test]
+    fn test_dismissed_toast_is_not_coalesced() {
+        let mut center = NotificationCenter::new();
+        let id = center.push(Severity::Warning, "SigNoz", "down", 0);
+        assert!(center.dismiss(id));
+        assert!(!center.dismiss(id));
+        assert_ne!(center.push(Severity::Warning, "SigNoz", "down", 10), id);
+        assert_eq!(center.history().len(), 2);
+    }
+
+    #[test]
+    fn test_expire_by_severity() {
+        let mut center = NotificationCenter::new();
+        center.push(Severity::Info, "info", "", 0);
+        center.push(Severity::Error, "error", "", 0);
+
+        assert!(!center.expire(1_000));
+        assert!(center.expire(Severity::Info.display_ms()));
+        assert_eq!(center.active().len(), 1);
+        assert_eq!(center.active()[0].severity, Severity::Error);
+        assert!(center.expire(Severity::Error.display_ms()));
+        assert!(center.active().is_empty());
+        assert_eq!(center.history().len(), 2);
+    }
+
+    #[test]
+    fn test_action_toast_stays_until_dismissed() {
+        let mut center = NotificationCenter::new();
+        let id = center.push_action(Severity::Info, "camera.yml changed", "", "Restart", 0);
+        let plain = center.push(Severity::Info, "camera.yml changed", "", 0);
+        assert_ne!(id, plain);
+
+        assert!(center.expire(Severity::Error.display_ms() * 10));
+        assert_eq!(center.active().len(), 1);
+        assert_eq!(center.active()[0].action.as_deref(), Some("Restart"));
+        assert!(center.dismiss(id));
+    }
+
+    #[test]
+    fn test_active_and_history_are_capped() {
+        let mut center = NotificationCenter::new();
+        for i in 0..(MAX_HISTORY + 10) {
+            center.push(Severity::Info, &format!("n{}", i), "", 0);
+        }
+        assert_eq!(center.active().len(), MAX_ACTIVE);
+        assert_eq!(center.history().len(), MAX_HISTORY);
+        assert_eq!(center.history()[0].title, format!("n{}", MAX_HISTORY + 9));
+    }
+
+    #[test]
+    fn test_mark_read_and_clear_history() {
+        let mut center = NotificationCenter::new();
+        center.push(Severity::Info, "a", "", 0);
+        center.mark_read();
+        assert_eq!(center.unread(), 0);
+        center.push(Severity::Info, "b", "", 0);
+        center.clear_history();
+        assert!(center.history().is_empty());
+        assert_eq!(center.unread(), 0);
+        assert_eq!(center.active().len(), 2);
+    }
+}
@@ -0,0 +1,250 @@
+use makepad_widgets::*;
+use std::cell::RefMut;
+
+use super::Notification;
+use crate::datetime::format_clock;
+use crate::i18n;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    // Colors (reused from diagnostics_panel)
+    ROW_BG = #ffffff
+    ROW_ALT_BG = #f8fafc
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+    DRAWER_BORDER = #e2e8f0
+
+    NotificationRow = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+        padding: { top: 8, bottom: 8, left: 12, right: 12 }
+        spacing: 2
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+
+            time_label = <Label> {
+                width: 90, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 10.0 }
+                }
+            }
+            severity_label = <Label> {
+                width: 44, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 10.0 }
+                }
+            }
+            title_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 11.0 }
+                }
+            }
+        }
+
+        message_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                wrap: Word,
+                text_style: { font_size: 10.0 }
+            }
+        }
+    }
+
+    NotificationRowAlt = <NotificationRow> {
+        draw_bg: { color: (ROW_ALT_BG) }
+    }
+
+    NotificationEmpty = <View> {
+        width: Fill, height: 80
+        align: { x: 0.5, y: 0.5 }
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+
+        empty_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 12.0 }
+            }
+            text: "No notifications"
+        }
+    }
+
+    pub NotificationDrawer = {{NotificationDrawer}} {
+        width: 360, height: Fill
+        flow: Down
+        show_bg: true
+        draw_bg: { color: (DRAWER_BORDER) }
+        padding: { left: 1 }
+
+        <View> {
+            width: Fill, height: 40
+            flow: Right
+            show_bg: true
+            draw_bg: { color: #f1f5f9 }
+            padding: { left: 12, right: 8 }
+            align: { y: 0.5 }
+            spacing: 8
+
+            drawer_title = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 13.0 }
+                }
+                text: "Notifications"
+            }
+
+            clear_button = <Button> {
+                width: 60, height: 28
+                text: "Clear"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            close_button = <Button> {
+                width: 60, height: 28
+                text: "Close"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        notification_list = <PortalList> {
+            width: Fill, height: Fill
+            flow: Down
+
+            NotificationRow = <NotificationRow> {}
+            NotificationRowAlt = <NotificationRowAlt> {}
+            NotificationEmpty = <NotificationEmpty> {}
+        }
+    }
+}
+
+/// Side drawer listing the notification history, newest first.
+#[derive(Live, LiveHook, Widget)]
+pub struct NotificationDrawer {
+    #[deref]
+    view: View,
+    #[rust]
+    notifications: Vec<Notification>,
+}
+
+impl Widget for NotificationDrawer {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                self.draw_rows(cx, &mut list);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl NotificationDrawer {
+    /// Replace the displayed history (given newest first).
+    pub fn set_notifications(&mut self, cx: &mut Cx, notifications: Vec<Notification>) {
+        self.notifications = notifications;
+        self.view.portal_list(ids!(notification_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    /// Show the text set in `live_design` in the current language
+    pub fn localize(&mut self, cx: &mut Cx) {
+        i18n::localize(
+            cx,
+            &[
+                (ids!(drawer_title), "notifications.title"),
+                (ids!(clear_button), "action.clear"),
+                (ids!(close_button), "action.close"),
+            ],
+            |path| self.view.widget(path),
+        );
+        self.view.portal_list(ids!(notification_list)).redraw(cx);
+    }
+
+    fn draw_rows(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        if self.notifications.is_empty() {
+            list.set_item_range(cx, 0, 1);
+            while let Some(item_id) = list.next_visible_item(cx) {
+                if item_id == 0 {
+                    let item = list.item(cx, item_id, live_id!(NotificationEmpty));
+                    item.label(ids!(empty_label))
+                        .set_text(cx, &i18n::tr("notifications.empty"));
+                    item.draw_all(cx, &mut Scope::empty());
+                }
+            }
+            return;
+        }
+
+        list.set_item_range(cx, 0, self.notifications.len());
+
+        while let Some(item_id) = list.next_visible_item(cx) {
+            if item_id < self.notifications.len() {
+                let notification = &self.notifications[item_id];
+
+                let template = if item_id % 2 == 0 {
+                    live_id!(NotificationRow)
+                } else {
+                    live_id!(NotificationRowAlt)
+                };
+
+                let item = list.item(cx, item_id, template);
+
+                item.label(ids!(time_label))
+                    .set_text(cx, &format_clock(notification.timestamp_ms));
+                item.label(ids!(severity_label))
+                    .set_text(cx, notification.severity.as_str());
+                item.label(ids!(title_label))
+                    .set_text(cx, &notification.heading());
+                item.label(ids!(message_label))
+                    .set_text(cx, &notification.message);
+
+                item.draw_all(cx, &mut Scope::empty());
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Ref wrapper (same pattern as DiagnosticsPanelRef)
+// ---------------------------------------------------------------------------
+
+impl NotificationDrawerRef {
+    pub fn localize(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.localize(cx);
+        }
+    }
+
+    pub fn set_notifications(&self, cx: &mut Cx, notifications: Vec<Notification>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_notifications(cx, notifications);
+        }
+    }
+
+    pub fn clear_clicked(&self, actions: &Actions) -> bool {
+        self.borrow()
+            .is_some_and(|inner| inner.view.button(ids!(clear_button)).clicked(actions))
+    }
+
+    pub fn close_clicked(&self, actions: &Actions) -> bool {
+        self.borrow()
+            .is_some_and(|inner| inner.view.button(ids!(close_button)).clicked(actions))
+    }
+}
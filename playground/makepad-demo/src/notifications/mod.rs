@@ -0,0 +1,14 @@
+pub mod center;
+pub mod drawer;
+pub mod toasts;
+
+pub use center::{Notification, NotificationCenter, Severity};
+pub use drawer::{NotificationDrawer, NotificationDrawerRef, NotificationDrawerWidgetRefExt};
+pub use toasts::{NotificationToasts, NotificationToastsRef, NotificationToastsWidgetRefExt};
+
+use makepad_widgets::*;
+
+pub fn live_design(cx: &mut Cx) {
+    toasts::live_design(cx);
+    drawer::live_design(cx);
+}
@@ -0,0 +1,209 @@
+use makepad_widgets::*;
+
+use super::{Notification, Severity};
+use crate::i18n;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #475569
+
+    ToastSlot = <View> {
+        visible: false
+        width: Fill, height: Fit
+        flow: Down
+        show_bg: true
+        draw_bg: { color: #ffffff }
+        padding: { top: 8, bottom: 8, left: 12, right: 8 }
+        spacing: 4
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 8
+
+            severity_label = <Label> {
+                width: 44, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 10.0 }
+                }
+            }
+
+            title_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 12.0 }
+                }
+            }
+
+            action_button = <Button> {
+                visible: false
+                width: Fit, height: 24
+                draw_text: { text_style: { font_size: 10.0 } }
+            }
+
+            dismiss_button = <Button> {
+                width: 24, height: 24
+                text: "x"
+                draw_text: { text_style: { font_size: 10.0 } }
+            }
+        }
+
+        message_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                wrap: Word,
+                text_style: { font_size: 11.0 }
+            }
+        }
+    }
+
+    pub NotificationToasts = {{NotificationToasts}} {
+        width: 340, height: Fit
+        flow: Down
+        spacing: 8
+
+        toast_0 = <ToastSlot> {}
+        toast_1 = <ToastSlot> {}
+        toast_2 = <ToastSlot> {}
+
+        more_label = <Label> {
+            visible: false
+            width: Fill, height: Fit
+            align: { x: 1.0 }
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 10.0 }
+            }
+        }
+    }
+}
+
+/// Number of toast slots in the stack.
+const SLOTS: usize = 3;
+
+/// Stack of the most recent active notifications, newest on top.
+#[derive(Live, LiveHook, Widget)]
+pub struct NotificationToasts {
+    #[deref]
+    view: View,
+    /// Notification id shown in each slot.
+    #[rust]
+    slot_ids: [Option<u64>; SLOTS],
+}
+
+impl Widget for NotificationToasts {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl NotificationToasts {
+    /// Show the given notifications (newest first); extra ones are summarised.
+    pub fn set_notifications(&mut self, cx: &mut Cx, notifications: &[Notification]) {
+        for index in 0..SLOTS {
+            let slot = self.slot(index);
+            match notifications.get(index) {
+                Some(n) => {
+                    let color = severity_color(n.severity);
+                    slot.apply_over(cx, live! { draw_bg: { color: (color) } });
+                    slot.label(ids!(severity_label))
+                        .set_text(cx, n.severity.as_str());
+                    slot.label(ids!(title_label)).set_text(cx, &n.heading());
+                    slot.label(ids!(message_label)).set_text(cx, &n.message);
+                    let action = slot.button(ids!(action_button));
+                    action.set_text(cx, n.action.as_deref().unwrap_or(""));
+                    action.set_visible(cx, n.action.is_some());
+                    slot.set_visible(cx, true);
+                    self.slot_ids[index] = Some(n.id);
+                }
+                None => {
+                    slot.set_visible(cx, false);
+                    self.slot_ids[index] = None;
+                }
+            }
+        }
+
+        let hidden = notifications.len().saturating_sub(SLOTS);
+        let more = self.view.label(ids!(more_label));
+        more.set_text(
+            cx,
+            &i18n::tr_args("notify.more", &[("count", &hidden.to_string())]),
+        );
+        more.set_visible(cx, hidden > 0);
+        self.redraw(cx);
+    }
+
+    fn slot(&self, index: usize) -> ViewRef {
+        match index {
+            0 => self.view.view(ids!(toast_0)),
+            1 => self.view.view(ids!(toast_1)),
+            _ => self.view.view(ids!(toast_2)),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Ref wrapper (same pattern as DataflowTableRef)
+// ---------------------------------------------------------------------------
+
+impl NotificationToastsRef {
+    pub fn set_notifications(&self, cx: &mut Cx, notifications: &[Notification]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_notifications(cx, notifications);
+        }
+    }
+
+    /// Check if a toast's dismiss button was clicked, returns its notification id if so
+    pub fn dismissed(&self, actions: &Actions) -> Option<u64> {
+        self.clicked(ids!(dismiss_button), actions)
+    }
+
+    /// Check if a toast's action button was clicked, returns its notification id if so
+    pub fn action_clicked(&self, actions: &Actions) -> Option<u64> {
+        self.clicked(ids!(action_button), actions)
+    }
+
+    fn clicked(&self, button: &[&[LiveId]], actions: &Actions) -> Option<u64> {
+        let inner = self.borrow()?;
+        (0..SLOTS).find_map(|index| {
+            inner
+                .slot(index)
+                .button(button)
+                .clicked(actions)
+                .then_some(inner.slot_ids[index])
+                .flatten()
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helper functions
+// ---------------------------------------------------------------------------
+
+/// Toast background for a severity.
+pub fn severity_color(severity: Severity) -> Vec4 {
+    let rgb: u32 = match severity {
+        Severity::Info => 0xdbeafe,
+        Severity::Success => 0xdcfce7,
+        Severity::Warning => 0xfef3c7,
+        Severity::Error => 0xfee2e2,
+    };
+    vec4(
+        ((rgb >> 16) & 0xff) as f32 / 255.0,
+        ((rgb >> 8) & 0xff) as f32 / 255.0,
+        (rgb & 0xff) as f32 / 255.0,
+        1.0,
+    )
+}
@@ -1,6 +1,32 @@
 use crate::otlp::error::OtlpError;
 use crate::otlp::types::*;
 
+/// The signals a backend can serve, so callers can leave out what it lacks
+/// instead of running into `InvalidQuery` errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    pub traces: bool,
+    pub logs: bool,
+    pub metrics: bool,
+    pub services: bool,
+}
+
+impl Capabilities {
+    pub const ALL: Capabilities = Capabilities {
+        traces: true,
+        logs: true,
+        metrics: true,
+        services: true,
+    };
+}
+
+impl Default for Capabilities {
+    /// Everything, until a backend says otherwise.
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
 /// Contract for read-only telemetry backends.
 ///
 /// Concrete backends implement this trait directly. The `TelemetryClient` enum
@@ -32,6 +58,9 @@ pub trait TelemetryBackend {
         query: &LogQuery,
     ) -> impl std::future::Future<Output = Result<QueryResult<LogEntry>, OtlpError>> + Send;
 
+    /// Which of the queries above the backend answers.
+    fn capabilities(&self) -> Capabilities;
+
     /// Human-readable name of this backend (e.g. "SigNoz @ http://localhost:3301").
     fn display_name(&self) -> String;
 }
@@ -1,30 +1,79 @@
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use tokio::runtime::Runtime;
-use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
 
-use crate::otlp::config::{AuthMethod, BackendConfig, SigNozConfig};
-use crate::otlp::create_backend;
+use crate::i18n;
+use crate::instrument;
+use crate::logging;
+use crate::otlp::backend::Capabilities;
+use crate::otlp::config::{
+    self, AuthMethod, BackendConfig, ClickHouseConfig, ElasticConfig, SigNozConfig, ZipkinConfig,
+};
 use crate::otlp::types::{Span, TraceQuery};
+use crate::otlp::{create_backend, TelemetryClient};
+use crate::secrets;
+use crate::tasks::{self, BackgroundJob};
 
 // ---------------------------------------------------------------------------
 // Types
 // ---------------------------------------------------------------------------
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub enum SignozRequest {
     HealthCheck,
-    QueryTraces(TraceQuery),
+    /// Health check from the worker's own schedule, reported only when the
+    /// connection status changes
+    ScheduledHealthCheck,
+    /// Trace query with its generation, see [`QueryTracker`]
+    QueryTraces(TraceQuery, u64),
+    /// Spans newer than those shown, for the live tail
+    TailTraces(TraceQuery, u64),
+    /// Spans for the chat agent, answered on the channel instead of polled
+    AgentTraces(TraceQuery, oneshot::Sender<Result<Vec<Span>, String>>),
+    /// Replace the backend, e.g. after the settings file changed
+    Reconfigure(BackendConfig),
+}
+
+impl SignozRequest {
+    /// Name of the span recorded while the request runs
+    fn span_name(&self) -> &'static str {
+        match self {
+            SignozRequest::HealthCheck | SignozRequest::ScheduledHealthCheck => {
+                "signoz.health_check"
+            }
+            SignozRequest::QueryTraces(..) => "signoz.query_traces",
+            SignozRequest::TailTraces(..) => "signoz.tail_traces",
+            SignozRequest::AgentTraces(..) => "signoz.agent_traces",
+            SignozRequest::Reconfigure(_) => "signoz.reconfigure",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum SignozResponse {
+    /// What the configured backend serves, sent once it is created
+    Capabilities(Capabilities),
     HealthOk,
     HealthError(String),
     Traces(Vec<Span>),
+    /// New spans for the live tail, to add to those shown
+    TraceTail(Vec<Span>),
     TracesError(String),
 }
 
+impl SignozResponse {
+    /// Whether the response carries spans, which the queue may drop when
+    /// the UI falls behind, rather than a status change it must see.
+    fn is_bulk(&self) -> bool {
+        matches!(
+            self,
+            SignozResponse::Traces(_) | SignozResponse::TraceTail(_)
+        )
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum ConnectionStatus {
     #[default]
@@ -34,15 +83,133 @@ pub enum ConnectionStatus {
     Error,
 }
 
+impl ConnectionStatus {
+    pub fn label(&self) -> String {
+        i18n::tr(match self {
+            ConnectionStatus::Unknown => "status.signoz.checking",
+            ConnectionStatus::Connected => "status.signoz.connected",
+            ConnectionStatus::Disconnected => "status.signoz.disconnected",
+            ConnectionStatus::Error => "status.signoz.error",
+        })
+    }
+
+    /// Whether the backend is usable, `None` before the first health check.
+    pub fn is_healthy(&self) -> Option<bool> {
+        match self {
+            ConnectionStatus::Unknown => None,
+            ConnectionStatus::Connected => Some(true),
+            ConnectionStatus::Disconnected | ConnectionStatus::Error => Some(false),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
-// Global statics  (same pattern as src/api.rs)
+// Bridge handle
 // ---------------------------------------------------------------------------
 
-static SIGNOZ_RUNTIME: Mutex<Option<Runtime>> = Mutex::new(None);
-static SIGNOZ_SENDER: Mutex<Option<UnboundedSender<SignozRequest>>> = Mutex::new(None);
-static PENDING_SIGNOZ_RESPONSES: Mutex<Vec<SignozResponse>> = Mutex::new(Vec::new());
-static SIGNOZ_CONNECTION_STATUS: Mutex<ConnectionStatus> = Mutex::new(ConnectionStatus::Unknown);
-static SIGNOZ_CONFIGURED: Mutex<bool> = Mutex::new(false);
+/// Handle to a telemetry backend worker: a task on the shared runtime (see
+/// [`tasks::runtime`]) that runs requests sent on the channel and reports
+/// back through shared state, which the UI polls each frame.
+///
+/// Clones share the worker, which stops once every handle is dropped.
+#[derive(Clone)]
+pub struct SignozBridge {
+    sender: UnboundedSender<SignozRequest>,
+    shared: Arc<Shared>,
+}
+
+/// State the worker updates and the handles read.
+#[derive(Default)]
+struct Shared {
+    responses: Mutex<Vec<SignozResponse>>,
+    status: Mutex<ConnectionStatus>,
+    capabilities: Mutex<Capabilities>,
+    /// Unix ms and round trip of the last health check or query that
+    /// succeeded
+    last_success: Mutex<Option<(u64, u64)>>,
+    queries: Mutex<QueryTracker>,
+}
+
+// ---------------------------------------------------------------------------
+// Query generations
+// ---------------------------------------------------------------------------
+
+/// Relative time ranges built this close together ask for the same spans.
+const COALESCE_WINDOW_MS: u64 = 1_000;
+
+/// Trace queries in flight, so that only the latest one is answered.
+///
+/// Each query from the traces panel gets the next generation; a queued
+/// query that a newer one superseded is skipped, and the answer to one
+/// that was already running is dropped. Live-tail queries carry the
+/// generation they extend and run one at a time.
+#[derive(Debug, Default)]
+struct QueryTracker {
+    generation: u64,
+    /// The latest query while unanswered, to coalesce repeats of it
+    pending: Option<TraceQuery>,
+    tail_pending: bool,
+}
+
+impl QueryTracker {
+    /// Generation for `query`, or `None` when the same query is already
+    /// pending.
+    fn begin(&mut self, query: &TraceQuery) -> Option<u64> {
+        if self.pending.as_ref().is_some_and(|p| same_query(p, query)) {
+            return None;
+        }
+        self.generation += 1;
+        self.pending = Some(query.clone());
+        Some(self.generation)
+    }
+
+    /// Generation for a tail query, or `None` while one is pending.
+    fn begin_tail(&mut self) -> Option<u64> {
+        if self.tail_pending {
+            return None;
+        }
+        self.tail_pending = true;
+        Some(self.generation)
+    }
+
+    fn is_current(&self, generation: u64) -> bool {
+        generation == self.generation
+    }
+
+    /// Mark a query answered; `false` when a newer one superseded it.
+    fn finish(&mut self, generation: u64) -> bool {
+        if !self.is_current(generation) {
+            return false;
+        }
+        self.pending = None;
+        true
+    }
+
+    /// Mark the tail query answered; `false` when a newer query superseded
+    /// the spans it extends.
+    fn finish_tail(&mut self, generation: u64) -> bool {
+        self.tail_pending = false;
+        self.is_current(generation)
+    }
+}
+
+/// Whether `a` and `b` ask for the same spans, allowing for relative time
+/// ranges built a moment apart.
+fn same_query(a: &TraceQuery, b: &TraceQuery) -> bool {
+    let ranges_match = match (&a.time_range, &b.time_range) {
+        (Some(x), Some(y)) => {
+            x.start_ms.abs_diff(y.start_ms) <= COALESCE_WINDOW_MS
+                && x.end_ms.abs_diff(y.end_ms) <= COALESCE_WINDOW_MS
+        }
+        (None, None) => true,
+        _ => false,
+    };
+    let without_range = |q: &TraceQuery| TraceQuery {
+        time_range: None,
+        ..q.clone()
+    };
+    ranges_match && without_range(a) == without_range(b)
+}
 
 // ---------------------------------------------------------------------------
 // Login support
@@ -88,6 +255,15 @@ async fn signoz_login(base_url: &str, email: &str, password: &str) -> Result<Str
 
 const DEFAULT_SIGNOZ_BASE_URL: &str = "http://localhost:8080";
 
+/// Span sets that may wait for the UI before the oldest is dropped.
+const MAX_PENDING_BULK_RESPONSES: usize = 8;
+
+/// Pages fetched for one live tail query at most.
+const MAX_TAIL_PAGES: usize = 5;
+
+/// How often the worker checks the backend between explicit requests.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Read SigNoz connection parameters from environment variables.
 ///
 /// Defaults to `http://localhost:8080` when `SIGNOZ_BASE_URL` is not set.
@@ -119,161 +295,464 @@ pub fn signoz_config_from_env() -> Option<BackendConfig> {
     }))
 }
 
-/// Check whether `SIGNOZ_EMAIL` + `SIGNOZ_PASSWORD` are set.
+/// Read a direct ClickHouse connection from environment variables, used in
+/// place of the SigNoz API when `SIGNOZ_CLICKHOUSE_URL` is set, with
+/// credentials from `SIGNOZ_CLICKHOUSE_USER` and `SIGNOZ_CLICKHOUSE_PASSWORD`.
+pub fn clickhouse_config_from_env() -> Option<BackendConfig> {
+    let var = |name: &str| std::env::var(name).ok().filter(|s| !s.is_empty());
+    Some(BackendConfig::ClickHouse(ClickHouseConfig {
+        url: var("SIGNOZ_CLICKHOUSE_URL")?,
+        user: var("SIGNOZ_CLICKHOUSE_USER"),
+        password: var("SIGNOZ_CLICKHOUSE_PASSWORD"),
+        traces_table: config::default_traces_table(),
+        logs_table: config::default_logs_table(),
+        timeout_secs: 30,
+    }))
+}
+
+/// Read an Elasticsearch or OpenSearch connection from environment
+/// variables, used when `ELASTICSEARCH_URL` is set. Logs are searched in
+/// `ELASTICSEARCH_LOGS_INDEX` (`logs-*` by default) and traces in
+/// `ELASTICSEARCH_TRACES_INDEX` when set.
+pub fn elastic_config_from_env() -> Option<BackendConfig> {
+    let var = |name: &str| std::env::var(name).ok().filter(|s| !s.is_empty());
+    Some(BackendConfig::Elastic(ElasticConfig {
+        url: var("ELASTICSEARCH_URL")?,
+        api_key: var("ELASTICSEARCH_API_KEY"),
+        user: var("ELASTICSEARCH_USER"),
+        password: var("ELASTICSEARCH_PASSWORD"),
+        logs_index: var("ELASTICSEARCH_LOGS_INDEX").unwrap_or_else(config::default_logs_index),
+        traces_index: var("ELASTICSEARCH_TRACES_INDEX"),
+        timeout_secs: 30,
+    }))
+}
+
+/// Read a Zipkin server from `ZIPKIN_URL`, used for traces when set.
+pub fn zipkin_config_from_env() -> Option<BackendConfig> {
+    let url = std::env::var("ZIPKIN_URL").ok().filter(|s| !s.is_empty())?;
+    Some(BackendConfig::Zipkin(ZipkinConfig {
+        url,
+        timeout_secs: 30,
+    }))
+}
+
+/// Check whether `SIGNOZ_EMAIL` is set, with `SIGNOZ_PASSWORD` or a stored
+/// [`secrets::SIGNOZ_PASSWORD`].
 fn login_credentials_from_env() -> Option<(String, String)> {
     let email = std::env::var("SIGNOZ_EMAIL").ok()?;
-    let password = std::env::var("SIGNOZ_PASSWORD").ok()?;
+    let password = std::env::var("SIGNOZ_PASSWORD")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .or_else(|| secrets::get(secrets::SIGNOZ_PASSWORD))?;
     if email.is_empty() || password.is_empty() {
         return None;
     }
     Some((email, password))
 }
 
-/// Attempt to initialise the SigNoz bridge from env vars.
-///
-/// Returns `true` when a valid config was found and the background runtime
-/// was started (or was already running).
-pub fn init_signoz_from_env() -> bool {
-    // Already initialised?
-    {
-        let rt = SIGNOZ_RUNTIME.lock().unwrap();
-        if rt.is_some() {
-            return *SIGNOZ_CONFIGURED.lock().unwrap();
+/// The backend picked by environment variables: ClickHouse, Elasticsearch
+/// or Zipkin when their URL is set, SigNoz otherwise.
+pub fn env_backend_config() -> BackendConfig {
+    // signoz_config_from_env always returns Some (defaults to localhost:8080)
+    clickhouse_config_from_env()
+        .or_else(elastic_config_from_env)
+        .or_else(zipkin_config_from_env)
+        .or_else(signoz_config_from_env)
+        .unwrap()
+}
+
+impl SignozBridge {
+    /// Start a worker for `configured`, or the env var backend when that is
+    /// unset.
+    pub fn start(configured: Option<BackendConfig>) -> Self {
+        let config = configured.unwrap_or_else(env_backend_config);
+        let (sender, receiver) = unbounded_channel::<SignozRequest>();
+        let shared = Arc::new(Shared::default());
+        tasks::runtime().spawn(run_worker(Arc::clone(&shared), config, receiver));
+        tracing::info!(target: "SigNoz", "Bridge initialised");
+        Self { sender, shared }
+    }
+
+    /// Switch to `configured`, or to the env var backend when that is
+    /// unset, and check the new connection.
+    pub fn reconfigure(&self, configured: Option<BackendConfig>) {
+        *self.shared.status.lock().unwrap() = ConnectionStatus::Unknown;
+        *self.shared.last_success.lock().unwrap() = None;
+        *self.shared.capabilities.lock().unwrap() = Capabilities::ALL;
+        self.send(SignozRequest::Reconfigure(
+            configured.unwrap_or_else(env_backend_config),
+        ));
+        self.send(SignozRequest::HealthCheck);
+    }
+
+    /// Current connection status (updated after health check results).
+    pub fn status(&self) -> ConnectionStatus {
+        self.shared.status()
+    }
+
+    /// What the backend serves; everything until it is created.
+    pub fn capabilities(&self) -> Capabilities {
+        *self.shared.capabilities.lock().unwrap()
+    }
+
+    /// When the backend last answered and how long it took, as
+    /// `(unix ms, round trip ms)`; `None` until something succeeds.
+    pub fn last_success(&self) -> Option<(u64, u64)> {
+        *self.shared.last_success.lock().unwrap()
+    }
+
+    /// Ask the worker to check the backend now.
+    pub fn request_health_check(&self) {
+        self.send(SignozRequest::HealthCheck);
+    }
+
+    /// Send a trace query, answered with [`SignozResponse::Traces`].
+    ///
+    /// Answers to earlier queries still running are dropped, and a repeat of
+    /// the query already pending is ignored.
+    pub fn request_traces(&self, query: TraceQuery) {
+        let Some(generation) = self.shared.queries.lock().unwrap().begin(&query) else {
+            tracing::debug!(target: "SigNoz", "Trace query already pending");
+            return;
+        };
+        self.send(SignozRequest::QueryTraces(query, generation));
+    }
+
+    /// Send a query for spans newer than those shown, answered with
+    /// [`SignozResponse::TraceTail`].
+    /// Ignored while the previous tail query is pending.
+    pub fn request_trace_tail(&self, query: TraceQuery) {
+        let Some(generation) = self.shared.queries.lock().unwrap().begin_tail() else {
+            return;
+        };
+        self.send(SignozRequest::TailTraces(query, generation));
+    }
+
+    /// Run a trace query for the chat agent, waiting for the spans.
+    pub async fn query_traces(&self, query: TraceQuery) -> Result<Vec<Span>, String> {
+        if !self.capabilities().traces {
+            return Err("the telemetry backend does not serve traces".to_string());
+        }
+        let (reply, response) = oneshot::channel();
+        if self
+            .sender
+            .send(SignozRequest::AgentTraces(query, reply))
+            .is_err()
+        {
+            return Err("SigNoz runtime stopped".to_string());
         }
+        response
+            .await
+            .unwrap_or_else(|_| Err("SigNoz runtime stopped".to_string()))
     }
 
-    // signoz_config_from_env always returns Some (defaults to localhost:8080)
-    let config = signoz_config_from_env().unwrap();
-
-    let login_creds = login_credentials_from_env();
-
-    // Mark as initialised immediately (prevents double-init).
-    {
-        let mut rt_lock = SIGNOZ_RUNTIME.lock().unwrap();
-        *rt_lock = Some(Runtime::new().expect("marker runtime"));
-    }
-    *SIGNOZ_CONFIGURED.lock().unwrap() = true;
-
-    let (sender, mut receiver) = unbounded_channel::<SignozRequest>();
-    *SIGNOZ_SENDER.lock().unwrap() = Some(sender);
-
-    std::thread::spawn(move || {
-        let rt = Runtime::new().expect("Failed to create SigNoz Tokio runtime");
-
-        rt.block_on(async {
-            // If email+password are provided and no API key was set, log in first.
-            let final_config = match (&config, login_creds) {
-                (BackendConfig::SigNoz(cfg), Some((email, password)))
-                    if matches!(cfg.auth, AuthMethod::None) =>
-                {
-                    eprintln!("[SigNoz] Logging in as {} ...", email);
-                    match signoz_login(&cfg.base_url, &email, &password).await {
-                        Ok(token) => {
-                            eprintln!("[SigNoz] Login succeeded, using JWT for auth");
-                            BackendConfig::SigNoz(SigNozConfig {
-                                base_url: cfg.base_url.clone(),
-                                auth: AuthMethod::BearerToken { token },
-                                timeout_secs: cfg.timeout_secs,
-                            })
+    /// Drain all pending responses. Returns an empty vec when there is
+    /// nothing new.
+    pub fn take_responses(&self) -> Vec<SignozResponse> {
+        std::mem::take(&mut *self.shared.responses.lock().unwrap())
+    }
+
+    fn send(&self, request: SignozRequest) {
+        let _ = self.sender.send(request);
+    }
+}
+
+/// Run requests from `receiver` against the backend for `config`, with a
+/// health check every [`HEALTH_CHECK_INTERVAL`], until every handle is
+/// dropped.
+async fn run_worker(
+    shared: Arc<Shared>,
+    config: BackendConfig,
+    mut receiver: UnboundedReceiver<SignozRequest>,
+) {
+    let mut current = config.clone();
+    let mut client = connect(&shared, config).await;
+    // The startup check is requested explicitly, so the first
+    // scheduled one comes a full interval later
+    let mut schedule = tokio::time::interval_at(
+        tokio::time::Instant::now() + HEALTH_CHECK_INTERVAL,
+        HEALTH_CHECK_INTERVAL,
+    );
+    schedule.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    tracing::info!(target: "SigNoz", "Runtime started, waiting for requests...");
+    loop {
+        let request = tokio::select! {
+            request = receiver.recv() => match request {
+                Some(request) => request,
+                None => break,
+            },
+            _ = schedule.tick() => SignozRequest::ScheduledHealthCheck,
+        };
+        if let SignozRequest::Reconfigure(config) = request {
+            current = config.clone();
+            client = connect(&shared, config).await;
+            continue;
+        }
+        if shared.superseded(&request) {
+            tracing::debug!(target: "SigNoz", "Skipping a superseded trace query");
+            continue;
+        }
+        let scheduled = matches!(request, SignozRequest::ScheduledHealthCheck);
+        // Rebuild a failing backend, logging in again, in case its
+        // session expired or the server restarted
+        if scheduled && shared.status() == ConnectionStatus::Error {
+            tracing::info!(target: "SigNoz", "Reconnecting to the telemetry backend");
+            client = connect(&shared, current.clone()).await;
+        }
+        let Some(client) = client.as_ref() else {
+            shared.fail_request(request, "no telemetry backend connected");
+            continue;
+        };
+        let _job = BackgroundJob::start();
+        let mut span = instrument::client_span(request.span_name());
+        let started = Instant::now();
+        match request {
+            SignozRequest::HealthCheck | SignozRequest::ScheduledHealthCheck => {
+                match client.health_check().await {
+                    Ok(()) => {
+                        shared.record_success(started);
+                        let changed = shared.set_status(ConnectionStatus::Connected);
+                        if changed || !scheduled {
+                            tracing::info!(target: "SigNoz", "Health check OK");
+                            shared.push_response(SignozResponse::HealthOk);
                         }
-                        Err(e) => {
-                            eprintln!("[SigNoz] Login failed: {}", e);
-                            push_response(SignozResponse::HealthError(format!(
-                                "Login failed: {}",
-                                e
-                            )));
-                            *SIGNOZ_CONNECTION_STATUS.lock().unwrap() = ConnectionStatus::Error;
-                            // Fall through with no auth — health check will also fail,
-                            // but at least the user sees the login error.
-                            config
+                    }
+                    Err(e) => {
+                        span.fail(&e.to_string());
+                        let changed = shared.set_status(ConnectionStatus::Error);
+                        if changed || !scheduled {
+                            tracing::warn!(target: "SigNoz", "Health check failed: {}", e);
+                            shared.push_response(SignozResponse::HealthError(format!("{}", e)));
                         }
                     }
                 }
-                _ => config,
-            };
-
-            let client = match create_backend(final_config) {
-                Ok(c) => c,
-                Err(e) => {
-                    eprintln!("[SigNoz] Failed to create backend: {}", e);
-                    push_response(SignozResponse::HealthError(format!("{}", e)));
-                    *SIGNOZ_CONNECTION_STATUS.lock().unwrap() = ConnectionStatus::Error;
-                    return;
-                }
-            };
-
-            eprintln!("[SigNoz] Runtime started, waiting for requests...");
-            while let Some(request) = receiver.recv().await {
-                match request {
-                    SignozRequest::HealthCheck => match client.health_check().await {
-                        Ok(()) => {
-                            eprintln!("[SigNoz] Health check OK");
-                            *SIGNOZ_CONNECTION_STATUS.lock().unwrap() = ConnectionStatus::Connected;
-                            push_response(SignozResponse::HealthOk);
+            }
+            SignozRequest::QueryTraces(query, generation) => {
+                let result = client.query_traces(&query).await;
+                let latest = shared.queries.lock().unwrap().finish(generation);
+                match result {
+                    Ok(result) => {
+                        tracing::debug!(
+                            target: "SigNoz",
+                            "Query returned {} spans",
+                            result.items.len()
+                        );
+                        span.attr("span.count", result.items.len());
+                        shared.record_success(started);
+                        if latest {
+                            shared.push_response(SignozResponse::Traces(result.items));
                         }
-                        Err(e) => {
-                            eprintln!("[SigNoz] Health check failed: {}", e);
-                            *SIGNOZ_CONNECTION_STATUS.lock().unwrap() = ConnectionStatus::Error;
-                            push_response(SignozResponse::HealthError(format!("{}", e)));
+                    }
+                    Err(e) => {
+                        span.fail(&e.to_string());
+                        tracing::error!(target: "SigNoz", "Query failed: {}", e);
+                        if latest {
+                            shared.push_response(SignozResponse::TracesError(format!("{}", e)));
                         }
-                    },
-                    SignozRequest::QueryTraces(query) => match client.query_traces(&query).await {
-                        Ok(result) => {
-                            eprintln!("[SigNoz] Query returned {} spans", result.items.len());
-                            push_response(SignozResponse::Traces(result.items));
+                    }
+                }
+            }
+            SignozRequest::TailTraces(query, generation) => {
+                let result = query_tail(client, query).await;
+                let latest = shared.queries.lock().unwrap().finish_tail(generation);
+                match result {
+                    Ok(spans) => {
+                        span.attr("span.count", spans.len());
+                        shared.record_success(started);
+                        if latest {
+                            shared.push_response(SignozResponse::TraceTail(spans));
                         }
-                        Err(e) => {
-                            eprintln!("[SigNoz] Query failed: {}", e);
-                            push_response(SignozResponse::TracesError(format!("{}", e)));
+                    }
+                    Err(e) => {
+                        span.fail(&e.to_string());
+                        tracing::warn!(target: "SigNoz", "Tail query failed: {}", e);
+                        if latest {
+                            shared.push_response(SignozResponse::TracesError(format!("{}", e)));
                         }
-                    },
+                    }
                 }
             }
-        });
-    });
-
-    eprintln!("[SigNoz] Bridge initialised");
-    true
+            SignozRequest::AgentTraces(query, reply) => {
+                let result = client.query_traces(&query).await;
+                match &result {
+                    Ok(result) => span.attr("span.count", result.items.len()),
+                    Err(e) => span.fail(&e.to_string()),
+                }
+                let _ = reply.send(result.map(|r| r.items).map_err(|e| e.to_string()));
+            }
+            // Handled before the client is picked
+            SignozRequest::Reconfigure(_) => {}
+        }
+    }
 }
 
-/// Whether a valid SigNoz config was found.
-pub fn is_signoz_configured() -> bool {
-    *SIGNOZ_CONFIGURED.lock().unwrap()
+/// Run a live tail query a page at a time until a page comes back short, so
+/// a burst larger than the query's limit is not cut off.
+async fn query_tail(
+    client: &TelemetryClient,
+    mut query: TraceQuery,
+) -> Result<Vec<Span>, crate::otlp::OtlpError> {
+    let mut spans = Vec::new();
+    for _ in 0..MAX_TAIL_PAGES {
+        let page = client.query_traces(&query).await?.items;
+        let returned = page.len();
+        spans.extend(page);
+        match next_page(&query, returned) {
+            Some(next) => query = next,
+            None => break,
+        }
+    }
+    Ok(spans)
 }
 
-/// Current connection status (updated after health check results).
-pub fn get_connection_status() -> ConnectionStatus {
-    *SIGNOZ_CONNECTION_STATUS.lock().unwrap()
+/// The page after `query`'s, when it returned a full page of `returned`
+/// spans and there may be more.
+fn next_page(query: &TraceQuery, returned: usize) -> Option<TraceQuery> {
+    let limit = query.limit.filter(|&l| l > 0)?;
+    if returned < limit as usize {
+        return None;
+    }
+    Some(TraceQuery {
+        offset: Some(query.offset.unwrap_or(0) + limit),
+        ..query.clone()
+    })
 }
 
-/// Send a health-check request to the background runtime.
-pub fn request_health_check() {
-    send_request(SignozRequest::HealthCheck);
-}
+/// Create the backend for `config`, with stored secrets filled in and
+/// logging in first when SigNoz credentials are set, and report what it
+/// serves. `None` when it cannot be created, after reporting the error.
+async fn connect(shared: &Shared, config: BackendConfig) -> Option<TelemetryClient> {
+    let config = config.with_secrets();
+    // If email+password are provided and no API key was set, log in first.
+    let config = match (&config, login_credentials_from_env()) {
+        (BackendConfig::SigNoz(cfg), Some((email, password)))
+            if matches!(cfg.auth, AuthMethod::None) =>
+        {
+            tracing::info!(target: "SigNoz", "Logging in as {} ...", email);
+            match signoz_login(&cfg.base_url, &email, &password).await {
+                Ok(token) => {
+                    tracing::info!(target: "SigNoz", "Login succeeded, using JWT for auth");
+                    BackendConfig::SigNoz(SigNozConfig {
+                        base_url: cfg.base_url.clone(),
+                        auth: AuthMethod::BearerToken { token },
+                        timeout_secs: cfg.timeout_secs,
+                    })
+                }
+                Err(e) => {
+                    tracing::error!(target: "SigNoz", "Login failed: {}", e);
+                    shared
+                        .push_response(SignozResponse::HealthError(format!("Login failed: {}", e)));
+                    shared.set_status(ConnectionStatus::Error);
+                    // Fall through with no auth — health check will also fail,
+                    // but at least the user sees the login error.
+                    config
+                }
+            }
+        }
+        _ => config,
+    };
 
-/// Send a trace query request to the background runtime.
-pub fn request_traces(query: TraceQuery) {
-    send_request(SignozRequest::QueryTraces(query));
+    match create_backend(config) {
+        Ok(client) => {
+            tracing::info!(target: "SigNoz", "Using {}", client.display_name());
+            let capabilities = client.capabilities();
+            *shared.capabilities.lock().unwrap() = capabilities;
+            shared.push_response(SignozResponse::Capabilities(capabilities));
+            Some(client)
+        }
+        Err(e) => {
+            tracing::error!(target: "SigNoz", "Failed to create backend: {}", e);
+            shared.push_response(SignozResponse::HealthError(format!("{}", e)));
+            shared.set_status(ConnectionStatus::Error);
+            None
+        }
+    }
 }
 
-/// Drain all pending responses. Returns an empty vec when there is nothing new.
-pub fn take_signoz_responses() -> Vec<SignozResponse> {
-    let mut lock = PENDING_SIGNOZ_RESPONSES.lock().unwrap();
-    std::mem::take(&mut *lock)
+impl Shared {
+    /// Whether `request` is a trace query a newer one made pointless, in which
+    /// case nothing will answer it.
+    fn superseded(&self, request: &SignozRequest) -> bool {
+        let mut queries = self.queries.lock().unwrap();
+        match request {
+            SignozRequest::QueryTraces(_, generation) => !queries.is_current(*generation),
+            SignozRequest::TailTraces(_, generation) if !queries.is_current(*generation) => {
+                queries.finish_tail(*generation);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Answer `request` with `reason` when there is no backend to run it.
+    fn fail_request(&self, request: SignozRequest, reason: &str) {
+        match request {
+            SignozRequest::HealthCheck => {
+                self.set_status(ConnectionStatus::Error);
+                self.push_response(SignozResponse::HealthError(reason.to_string()));
+            }
+            SignozRequest::ScheduledHealthCheck => {
+                if self.set_status(ConnectionStatus::Error) {
+                    self.push_response(SignozResponse::HealthError(reason.to_string()));
+                }
+            }
+            SignozRequest::QueryTraces(_, generation) => {
+                if self.queries.lock().unwrap().finish(generation) {
+                    self.push_response(SignozResponse::TracesError(reason.to_string()));
+                }
+            }
+            SignozRequest::TailTraces(_, generation) => {
+                if self.queries.lock().unwrap().finish_tail(generation) {
+                    self.push_response(SignozResponse::TracesError(reason.to_string()));
+                }
+            }
+            SignozRequest::AgentTraces(_, reply) => {
+                let _ = reply.send(Err(reason.to_string()));
+            }
+            SignozRequest::Reconfigure(_) => {}
+        }
+    }
+
+    fn push_response(&self, resp: SignozResponse) {
+        let mut pending = self.responses.lock().unwrap();
+        if push_bounded(&mut pending, resp, MAX_PENDING_BULK_RESPONSES) {
+            tracing::warn!(target: "SigNoz", "UI is behind, dropped the oldest pending spans");
+        }
+    }
+
+    fn status(&self) -> ConnectionStatus {
+        *self.status.lock().unwrap()
+    }
+
+    /// Store the connection status, returning whether it changed.
+    fn set_status(&self, status: ConnectionStatus) -> bool {
+        let mut current = self.status.lock().unwrap();
+        std::mem::replace(&mut *current, status) != status
+    }
+
+    fn record_success(&self, started: Instant) {
+        let latency_ms = started.elapsed().as_millis() as u64;
+        *self.last_success.lock().unwrap() = Some((logging::now_ms(), latency_ms));
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Internal helpers
 // ---------------------------------------------------------------------------
 
-fn send_request(req: SignozRequest) {
-    if let Some(sender) = SIGNOZ_SENDER.lock().unwrap().as_ref() {
-        let _ = sender.send(req);
+/// Queue `resp`, dropping the oldest span set when more than `max_bulk`
+/// would be waiting. Returns whether one was dropped.
+fn push_bounded(queue: &mut Vec<SignozResponse>, resp: SignozResponse, max_bulk: usize) -> bool {
+    let bulk = resp.is_bulk();
+    queue.push(resp);
+    if !bulk || queue.iter().filter(|r| r.is_bulk()).count() <= max_bulk {
+        return false;
     }
-}
-
-fn push_response(resp: SignozResponse) {
-    PENDING_SIGNOZ_RESPONSES.lock().unwrap().push(resp);
+    if let Some(oldest) = queue.iter().position(SignozResponse::is_bulk) {
+        queue.remove(oldest);
+    }
+    true
 }
 
 // ---------------------------------------------------------------------------
@@ -291,6 +770,22 @@ mod tests {
     fn test_connection_status_default() {
         let status = ConnectionStatus::default();
         assert_eq!(status, ConnectionStatus::Unknown);
+        assert_eq!(status.is_healthy(), None);
+        assert_eq!(ConnectionStatus::Error.is_healthy(), Some(false));
+    }
+
+    #[test]
+    fn test_next_page() {
+        let query = TraceQuery {
+            limit: Some(100),
+            ..Default::default()
+        };
+        assert!(next_page(&query, 99).is_none());
+        let next = next_page(&query, 100).unwrap();
+        assert_eq!(next.offset, Some(100));
+        assert_eq!(next_page(&next, 100).unwrap().offset, Some(200));
+        // Without a limit everything came back at once
+        assert!(next_page(&TraceQuery::default(), 1000).is_none());
     }
 
     #[test]
@@ -303,6 +798,7 @@ mod tests {
                 assert_eq!(cfg.base_url, "http://localhost:8080");
                 assert!(matches!(cfg.auth, AuthMethod::None));
             }
+            _ => panic!("Expected SigNoz config"),
         }
         clear_signoz_env();
     }
@@ -313,6 +809,90 @@ mod tests {
         std::env::remove_var("SIGNOZ_API_KEY");
         std::env::remove_var("SIGNOZ_EMAIL");
         std::env::remove_var("SIGNOZ_PASSWORD");
+        std::env::remove_var("SIGNOZ_CLICKHOUSE_URL");
+        std::env::remove_var("SIGNOZ_CLICKHOUSE_USER");
+        std::env::remove_var("SIGNOZ_CLICKHOUSE_PASSWORD");
+        for name in [
+            "ELASTICSEARCH_URL",
+            "ELASTICSEARCH_API_KEY",
+            "ELASTICSEARCH_USER",
+            "ELASTICSEARCH_PASSWORD",
+            "ELASTICSEARCH_LOGS_INDEX",
+            "ELASTICSEARCH_TRACES_INDEX",
+            "ZIPKIN_URL",
+        ] {
+            std::env::remove_var(name);
+        }
+    }
+
+    #[test]
+    fn test_clickhouse_config_from_env() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        clear_signoz_env();
+        assert!(clickhouse_config_from_env().is_none());
+
+        std::env::set_var("SIGNOZ_CLICKHOUSE_URL", "http://clickhouse:8123");
+        std::env::set_var("SIGNOZ_CLICKHOUSE_USER", "reader");
+        match clickhouse_config_from_env() {
+            Some(BackendConfig::ClickHouse(cfg)) => {
+                assert_eq!(cfg.url, "http://clickhouse:8123");
+                assert_eq!(cfg.user.as_deref(), Some("reader"));
+                assert_eq!(cfg.password, None);
+            }
+            _ => panic!("Expected ClickHouse config"),
+        }
+
+        clear_signoz_env();
+    }
+
+    #[test]
+    fn test_elastic_config_from_env() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        clear_signoz_env();
+        assert!(elastic_config_from_env().is_none());
+
+        std::env::set_var("ELASTICSEARCH_URL", "http://elastic:9200");
+        std::env::set_var("ELASTICSEARCH_TRACES_INDEX", "traces-apm*");
+        match elastic_config_from_env() {
+            Some(BackendConfig::Elastic(cfg)) => {
+                assert_eq!(cfg.url, "http://elastic:9200");
+                assert_eq!(cfg.logs_index, "logs-*");
+                assert_eq!(cfg.traces_index.as_deref(), Some("traces-apm*"));
+                assert_eq!(cfg.api_key, None);
+            }
+            _ => panic!("Expected Elastic config"),
+        }
+
+        clear_signoz_env();
+    }
+
+    #[test]
+    fn test_env_backend_config_precedence() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        clear_signoz_env();
+        assert!(matches!(env_backend_config(), BackendConfig::SigNoz(_)));
+
+        std::env::set_var("ZIPKIN_URL", "http://zipkin:9411");
+        assert!(matches!(env_backend_config(), BackendConfig::Zipkin(_)));
+        std::env::set_var("ELASTICSEARCH_URL", "http://elastic:9200");
+        assert!(matches!(env_backend_config(), BackendConfig::Elastic(_)));
+
+        clear_signoz_env();
+    }
+
+    #[test]
+    fn test_zipkin_config_from_env() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        clear_signoz_env();
+        assert!(zipkin_config_from_env().is_none());
+
+        std::env::set_var("ZIPKIN_URL", "http://zipkin:9411");
+        match zipkin_config_from_env() {
+            Some(BackendConfig::Zipkin(cfg)) => assert_eq!(cfg.url, "http://zipkin:9411"),
+            _ => panic!("Expected Zipkin config"),
+        }
+
+        clear_signoz_env();
     }
 
     #[test]
@@ -327,6 +907,7 @@ mod tests {
                 assert_eq!(cfg.base_url, "http://localhost:3301");
                 assert!(matches!(cfg.auth, AuthMethod::None));
             }
+            _ => panic!("Expected SigNoz config"),
         }
 
         clear_signoz_env();
@@ -348,6 +929,7 @@ mod tests {
                     _ => panic!("Expected ApiKey auth"),
                 }
             }
+            _ => panic!("Expected SigNoz config"),
         }
 
         clear_signoz_env();
@@ -389,23 +971,160 @@ mod tests {
         clear_signoz_env();
     }
 
+    fn query(service: &str, start_ms: u64) -> TraceQuery {
+        TraceQuery {
+            service_name: Some(service.to_string()),
+            time_range: Some(crate::otlp::types::TimeRange {
+                start_ms,
+                end_ms: start_ms + 900_000,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_query_tracker_supersedes_and_coalesces() {
+        let mut tracker = QueryTracker::default();
+        let first = tracker.begin(&query("camera", 10_000)).unwrap();
+        // Same filter a moment later: still pending, so coalesced
+        assert_eq!(tracker.begin(&query("camera", 10_400)), None);
+        let second = tracker.begin(&query("detector", 10_400)).unwrap();
+        assert!(!tracker.is_current(first));
+        assert!(!tracker.finish(first));
+        assert!(tracker.finish(second));
+        // Answered, so asking again runs a new query
+        assert!(tracker.begin(&query("detector", 10_400)).is_some());
+    }
+
+    #[test]
+    fn test_query_tracker_tail() {
+        let mut tracker = QueryTracker::default();
+        let generation = tracker.begin(&query("camera", 0)).unwrap();
+        let tail = tracker.begin_tail().unwrap();
+        assert_eq!(tail, generation);
+        assert_eq!(tracker.begin_tail(), None);
+        assert!(tracker.finish_tail(tail));
+
+        let tail = tracker.begin_tail().unwrap();
+        tracker.begin(&query("detector", 0)).unwrap();
+        // The spans it extends were replaced
+        assert!(!tracker.finish_tail(tail));
+        assert!(tracker.begin_tail().is_some());
+    }
+
     #[test]
-    fn test_take_signoz_responses_empty() {
-        let responses = take_signoz_responses();
-        assert!(responses.is_empty());
+    fn test_same_query_needs_close_time_ranges() {
+        assert!(same_query(&query("camera", 0), &query("camera", 1_000)));
+        assert!(!same_query(&query("camera", 0), &query("camera", 60_000)));
+        assert!(!same_query(
+            &query("camera", 0),
+            &TraceQuery {
+                time_range: None,
+                ..query("camera", 0)
+            }
+        ));
+    }
+
+    #[test]
+    fn test_push_bounded_drops_oldest_spans() {
+        let mut queue = Vec::new();
+        assert!(!push_bounded(
+            &mut queue,
+            SignozResponse::Traces(Vec::new()),
+            2
+        ));
+        assert!(!push_bounded(&mut queue, SignozResponse::HealthOk, 2));
+        assert!(!push_bounded(
+            &mut queue,
+            SignozResponse::TraceTail(Vec::new()),
+            2
+        ));
+        assert!(push_bounded(
+            &mut queue,
+            SignozResponse::TraceTail(Vec::new()),
+            2
+        ));
+        // The first span set went, status responses stay
+        assert_eq!(queue.len(), 3);
+        assert!(matches!(queue[0], SignozResponse::HealthOk));
+        assert!(queue[1..]
+            .iter()
+            .all(|r| matches!(r, SignozResponse::TraceTail(_))));
+    }
+
+    /// A handle with no worker, holding the other end of its channel.
+    fn test_bridge() -> (SignozBridge, UnboundedReceiver<SignozRequest>) {
+        let (sender, receiver) = unbounded_channel();
+        let bridge = SignozBridge {
+            sender,
+            shared: Arc::new(Shared::default()),
+        };
+        (bridge, receiver)
+    }
+
+    #[test]
+    fn test_set_status_reports_changes() {
+        let (bridge, _receiver) = test_bridge();
+        assert!(bridge.shared.set_status(ConnectionStatus::Connected));
+        assert!(!bridge.shared.set_status(ConnectionStatus::Connected));
+        assert!(bridge.shared.set_status(ConnectionStatus::Error));
+        assert!(!bridge.shared.set_status(ConnectionStatus::Error));
+        assert_eq!(bridge.status(), ConnectionStatus::Error);
+    }
+
+    #[test]
+    fn test_take_responses_empty() {
+        let (bridge, _receiver) = test_bridge();
+        assert!(bridge.take_responses().is_empty());
     }
 
     #[test]
     fn test_push_and_take_responses() {
-        push_response(SignozResponse::HealthOk);
-        push_response(SignozResponse::TracesError("oops".to_string()));
+        let (bridge, _receiver) = test_bridge();
+        bridge.shared.push_response(SignozResponse::HealthOk);
+        bridge
+            .shared
+            .push_response(SignozResponse::TracesError("oops".to_string()));
 
-        let responses = take_signoz_responses();
+        let responses = bridge.take_responses();
         assert_eq!(responses.len(), 2);
         assert!(matches!(responses[0], SignozResponse::HealthOk));
         assert!(matches!(responses[1], SignozResponse::TracesError(_)));
 
-        let responses2 = take_signoz_responses();
+        let responses2 = bridge.take_responses();
         assert!(responses2.is_empty());
     }
+
+    #[test]
+    fn test_request_traces_coalesces_pending_query() {
+        let (bridge, mut receiver) = test_bridge();
+        bridge.request_traces(query("camera", 0));
+        bridge.request_traces(query("camera", 200));
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(SignozRequest::QueryTraces(_, 1))
+        ));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_reconfigure_resets_state() {
+        let (bridge, mut receiver) = test_bridge();
+        bridge.shared.set_status(ConnectionStatus::Connected);
+        *bridge.shared.last_success.lock().unwrap() = Some((1, 2));
+        bridge.reconfigure(Some(BackendConfig::Zipkin(ZipkinConfig {
+            url: "http://zipkin:9411".to_string(),
+            timeout_secs: 30,
+        })));
+        assert_eq!(bridge.status(), ConnectionStatus::Unknown);
+        assert_eq!(bridge.last_success(), None);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(SignozRequest::Reconfigure(BackendConfig::Zipkin(_)))
+        ));
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(SignozRequest::HealthCheck)
+        ));
+    }
 }
@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+
+use reqwest::header::{HeaderMap, HeaderValue};
+
+use crate::otlp::backend::{Capabilities, TelemetryBackend};
+use crate::otlp::config::ClickHouseConfig;
+use crate::otlp::error::OtlpError;
+use crate::otlp::signoz::{parse_span_events, parse_span_links};
+use crate::otlp::types::*;
+
+use super::query::{build_log_query, build_services_query, build_trace_query, ClickHouseQuery};
+
+type Row = serde_json::Map<String, serde_json::Value>;
+
+/// A backend reading SigNoz's trace and log tables straight from
+/// ClickHouse, over its HTTP interface.
+pub struct ClickHouseBackend {
+    config: ClickHouseConfig,
+    client: reqwest::Client,
+}
+
+impl ClickHouseBackend {
+    /// Create a new `ClickHouseBackend` from configuration.
+    pub fn new(config: ClickHouseConfig) -> Result<Self, OtlpError> {
+        if config.url.is_empty() {
+            return Err(OtlpError::ConnectionFailed(
+                "url must not be empty".to_string(),
+            ));
+        }
+
+        let mut default_headers = HeaderMap::new();
+        let credentials = [
+            ("X-ClickHouse-User", &config.user),
+            ("X-ClickHouse-Key", &config.password),
+        ];
+        for (name, value) in credentials {
+            if let Some(value) = value {
+                let val = HeaderValue::from_str(value).map_err(|e| {
+                    OtlpError::ConnectionFailed(format!("invalid {} header: {}", name, e))
+                })?;
+                default_headers.insert(name, val);
+            }
+        }
+
+        let client = reqwest::Client::builder()
+            .default_headers(default_headers)
+            .timeout(std::time::Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| {
+                OtlpError::ConnectionFailed(format!("failed to build HTTP client: {}", e))
+            })?;
+
+        Ok(Self { config, client })
+    }
+
+    /// Run `query` and return its rows.
+    async fn run(&self, query: &ClickHouseQuery) -> Result<Vec<Row>, OtlpError> {
+        let mut params = query.url_params();
+        params.push(("default_format".to_string(), "JSONEachRow".to_string()));
+        params.push((
+            "output_format_json_quote_64bit_integers".to_string(),
+            "0".to_string(),
+        ));
+        let url = self.config.url.trim_end_matches('/');
+        let resp = self
+            .client
+            .post(url)
+            .query(&params)
+            .body(query.sql.clone())
+            .send()
+            .await?;
+        let status = resp.status();
+
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(OtlpError::AuthenticationFailed(format!(
+                "HTTP {}",
+                status.as_u16()
+            )));
+        }
+
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(OtlpError::ApiError {
+                status: status.as_u16(),
+                message: text.trim().to_string(),
+            });
+        }
+
+        parse_rows(&resp.text().await?)
+    }
+}
+
+impl TelemetryBackend for ClickHouseBackend {
+    async fn health_check(&self) -> Result<(), OtlpError> {
+        // `/ping` answers without credentials, so run a query to check them
+        self.run(&ClickHouseQuery {
+            sql: "SELECT 1 AS ok".to_string(),
+            params: Vec::new(),
+        })
+        .await
+        .map(|_| ())
+    }
+
+    async fn list_services(&self) -> Result<Vec<ServiceInfo>, OtlpError> {
+        let rows = self
+            .run(&build_services_query(&self.config.traces_table))
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| ServiceInfo {
+                name: row_str(row, "name"),
+                num_operations: row_u64(row, "num_operations"),
+            })
+            .collect())
+    }
+
+    async fn query_traces(&self, query: &TraceQuery) -> Result<QueryResult<Span>, OtlpError> {
+        let rows = self
+            .run(&build_trace_query(&self.config.traces_table, query))
+            .await?;
+        let items: Vec<Span> = rows.iter().map(span_from_row).collect();
+        Ok(QueryResult {
+            total: Some(items.len() as u64),
+            items,
+        })
+    }
+
+    async fn query_metrics(
+        &self,
+        _query: &MetricQuery,
+    ) -> Result<QueryResult<MetricSeries>, OtlpError> {
+        Err(OtlpError::InvalidQuery(
+            "metrics are not read from ClickHouse directly; use the SigNoz API backend".to_string(),
+        ))
+    }
+
+    async fn query_logs(&self, query: &LogQuery) -> Result<QueryResult<LogEntry>, OtlpError> {
+        let rows = self
+            .run(&build_log_query(&self.config.logs_table, query))
+            .await?;
+        let items: Vec<LogEntry> = rows.iter().map(log_from_row).collect();
+        Ok(QueryResult {
+            total: Some(items.len() as u64),
+            items,
+        })
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            metrics: false,
+            ..Capabilities::ALL
+        }
+    }
+
+    fn display_name(&self) -> String {
+        format!("ClickHouse @ {}", self.config.url)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Rows of a `JSONEachRow` response, one object per line.
+fn parse_rows(text: &str) -> Result<Vec<Row>, OtlpError> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(OtlpError::from))
+        .collect()
+}
+
+fn row_str(row: &Row, key: &str) -> String {
+    row.get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// A number column, also when ClickHouse quotes 64-bit integers.
+fn row_u64(row: &Row, key: &str) -> u64 {
+    match row.get(key) {
+        Some(serde_json::Value::String(s)) => s.parse().unwrap_or(0),
+        Some(v) => v.as_u64().unwrap_or(0),
+        None => 0,
+    }
+}
+
+/// A `Map(String, String)` column, e.g. `attributes_string`.
+fn row_map(row: &Row, key: &str) -> HashMap<String, String> {
+    row.get(key)
+        .and_then(|v| v.as_object())
+        .map(|map| {
+            map.iter()
+                .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn span_from_row(row: &Row) -> Span {
+    let parent_span_id = Some(row_str(row, "parent_span_id")).filter(|s| !s.is_empty());
+    // Span attributes win over resource attributes of the same name
+    let mut attributes = row_map(row, "resources_string");
+    attributes.extend(row_map(row, "attributes_string"));
+    Span {
+        trace_id: row_str(row, "trace_id"),
+        span_id: row_str(row, "span_id"),
+        links: row
+            .get("links")
+            .map(|v| parse_span_links(v, parent_span_id.as_deref()))
+            .unwrap_or_default(),
+        parent_span_id,
+        service_name: row_str(row, "service_name"),
+        operation_name: row_str(row, "name"),
+        start_time_ms: row_u64(row, "timestamp_ns") / 1_000_000,
+        duration_ms: row_u64(row, "duration_nano") / 1_000_000,
+        status_code: row.get("status_code").and_then(|v| v.as_i64()).unwrap_or(0) as i32,
+        has_error: row
+            .get("has_error")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        attributes,
+        events: row.get("events").map(parse_span_events).unwrap_or_default(),
+    }
+}
+
+fn log_from_row(row: &Row) -> LogEntry {
+    let resources = row_map(row, "resources_string");
+    LogEntry {
+        timestamp_ms: row_u64(row, "timestamp") / 1_000_000,
+        severity: row_str(row, "severity_text"),
+        body: row_str(row, "body"),
+        service_name: resources.get("service.name").cloned().unwrap_or_default(),
+        attributes: row_map(row, "attributes_string"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::otlp::config::{default_logs_table, default_traces_table};
+
+    fn config(url: &str) -> ClickHouseConfig {
+        ClickHouseConfig {
+            url: url.to_string(),
+            user: Some("reader".to_string()),
+            password: Some("secret".to_string()),
+            traces_table: default_traces_table(),
+            logs_table: default_logs_table(),
+            timeout_secs: 30,
+        }
+    }
+
+    #[test]
+    fn test_new_clickhouse_backend() {
+        assert!(ClickHouseBackend::new(config("")).is_err());
+        let backend = ClickHouseBackend::new(config("http://localhost:8123")).unwrap();
+        assert_eq!(backend.display_name(), "ClickHouse @ http://localhost:8123");
+        assert!(!backend.capabilities().metrics);
+    }
+
+    #[test]
+    fn test_span_from_row() {
+        let text = concat!(
+            r#"{"trace_id":"t1","span_id":"s2","parent_span_id":"s1","service_name":"camera","#,
+            r#""name":"capture","timestamp_ns":"1700000001000000000","duration_nano":12500000,"#,
+            r#""status_code":2,"has_error":true,"#,
+            r#""attributes_string":{"node.id":"camera","host.name":"span"},"#,
+            r#""resources_string":{"host.name":"edge-1","service.name":"camera"},"#,
+            r#""events":["{\"name\":\"exception\",\"timeUnixNano\":1700000001005000000}"],"#,
+            r#""links":"[{\"TraceId\":\"t0\",\"SpanId\":\"s9\",\"RefType\":\"FOLLOWS_FROM\"}]"}"#,
+            "\n\n"
+        );
+        let rows = parse_rows(text).unwrap();
+        assert_eq!(rows.len(), 1);
+        let span = span_from_row(&rows[0]);
+        assert_eq!(span.parent_span_id.as_deref(), Some("s1"));
+        assert_eq!(span.start_time_ms, 1_700_000_001_000);
+        assert_eq!(span.duration_ms, 12);
+        assert_eq!(span.status_code, 2);
+        assert!(span.has_error);
+        assert_eq!(span.attributes["node.id"], "camera");
+        assert_eq!(span.attributes["host.name"], "span");
+        assert_eq!(span.attributes["service.name"], "camera");
+        assert_eq!(span.events[0].name, "exception");
+        assert_eq!(span.links[0].span_id, "s9");
+    }
+
+    #[test]
+    fn test_log_from_row() {
+        let rows = parse_rows(concat!(
+            r#"{"timestamp":1700000002000000000,"severity_text":"ERROR","body":"no device","#,
+            r#""attributes_string":{"node.id":"camera"},"resources_string":{"service.name":"camera"}}"#
+        ))
+        .unwrap();
+        let log = log_from_row(&rows[0]);
+        assert_eq!(log.timestamp_ms, 1_700_000_002_000);
+        assert_eq!(log.severity, "ERROR");
+        assert_eq!(log.body, "no device");
+        assert_eq!(log.service_name, "camera");
+        assert_eq!(log.attributes["node.id"], "camera");
+
+        assert!(parse_rows("not json").is_err());
+    }
+}
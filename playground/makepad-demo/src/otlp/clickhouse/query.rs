@@ -0,0 +1,280 @@
+//! SQL for SigNoz's ClickHouse tables.
+//!
+//! Values are never spliced into the SQL: they are bound to `{name:Type}`
+//! placeholders and sent as `param_<name>` URL parameters of the HTTP
+//! interface. Time filters include the `ts_bucket_start` column, which the
+//! tables are partitioned and sorted by, so ClickHouse only reads the parts
+//! covering the range.
+
+use crate::otlp::signoz::query::default_time_range;
+use crate::otlp::types::{LogQuery, TraceQuery};
+
+/// Width of the `ts_bucket_start` buckets, in seconds.
+const BUCKET_SECS: u64 = 1800;
+
+/// SQL with `{name:Type}` placeholders and the values bound to them.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ClickHouseQuery {
+    pub sql: String,
+    pub params: Vec<(String, String)>,
+}
+
+impl ClickHouseQuery {
+    /// Bind `value` to a new placeholder of `ty` and return the
+    /// placeholder, e.g. `{p0:String}`.
+    fn bind(&mut self, ty: &str, value: impl ToString) -> String {
+        let name = format!("p{}", self.params.len());
+        let placeholder = format!("{{{}:{}}}", name, ty);
+        self.params.push((name, value.to_string()));
+        placeholder
+    }
+
+    /// URL parameters carrying the bound values.
+    pub fn url_params(&self) -> Vec<(String, String)> {
+        self.params
+            .iter()
+            .map(|(name, value)| (format!("param_{}", name), value.clone()))
+            .collect()
+    }
+}
+
+/// Spans matching `query` in `table`, newest first.
+pub fn build_trace_query(table: &str, query: &TraceQuery) -> ClickHouseQuery {
+    let tr = query.time_range.clone().unwrap_or_else(default_time_range);
+    let mut q = ClickHouseQuery::default();
+    let mut conditions = vec![
+        format!(
+            "timestamp >= fromUnixTimestamp64Milli({})",
+            q.bind("Int64", tr.start_ms)
+        ),
+        format!(
+            "timestamp < fromUnixTimestamp64Milli({})",
+            q.bind("Int64", tr.end_ms)
+        ),
+    ];
+    bucket_conditions(&mut q, &mut conditions, tr.start_ms, tr.end_ms);
+    if let Some(ref svc) = query.service_name {
+        conditions.push(format!(
+            "`resource_string_service$$name` = {}",
+            q.bind("String", svc)
+        ));
+    }
+    if let Some(ref op) = query.operation_name {
+        conditions.push(format!("name = {}", q.bind("String", op)));
+    }
+    if let Some(min) = query.min_duration_ms {
+        conditions.push(format!(
+            "duration_nano >= {}",
+            q.bind("UInt64", min.saturating_mul(1_000_000))
+        ));
+    }
+    if let Some(max) = query.max_duration_ms {
+        conditions.push(format!(
+            "duration_nano <= {}",
+            q.bind("UInt64", max.saturating_mul(1_000_000))
+        ));
+    }
+    for (k, v) in sorted(&query.tags) {
+        let key = q.bind("String", k);
+        conditions.push(format!(
+            "attributes_string[{}] = {}",
+            key,
+            q.bind("String", v)
+        ));
+    }
+
+    let limit = q.bind("UInt32", query.limit.unwrap_or(100));
+    let offset = q.bind("UInt32", query.offset.unwrap_or(0));
+    q.sql = format!(
+        "SELECT trace_id, span_id, parent_span_id, \
+         `resource_string_service$$name` AS service_name, name, \
+         toUnixTimestamp64Nano(timestamp) AS timestamp_ns, duration_nano, status_code, \
+         has_error, attributes_string, resources_string, events, links \
+         FROM {} WHERE {} ORDER BY timestamp DESC LIMIT {} OFFSET {}",
+        table,
+        conditions.join(" AND "),
+        limit,
+        offset
+    );
+    q
+}
+
+/// Log lines matching `query` in `table`, newest first.
+pub fn build_log_query(table: &str, query: &LogQuery) -> ClickHouseQuery {
+    let tr = query.time_range.clone().unwrap_or_else(default_time_range);
+    let mut q = ClickHouseQuery::default();
+    // `timestamp` is in ns here
+    let mut conditions = vec![
+        format!(
+            "timestamp >= {}",
+            q.bind("UInt64", tr.start_ms.saturating_mul(1_000_000))
+        ),
+        format!(
+            "timestamp < {}",
+            q.bind("UInt64", tr.end_ms.saturating_mul(1_000_000))
+        ),
+    ];
+    bucket_conditions(&mut q, &mut conditions, tr.start_ms, tr.end_ms);
+    if let Some(ref svc) = query.service_name {
+        conditions.push(format!(
+            "resources_string['service.name'] = {}",
+            q.bind("String", svc)
+        ));
+    }
+    if let Some(ref severity) = query.severity {
+        conditions.push(format!(
+            "upper(severity_text) = upper({})",
+            q.bind("String", severity)
+        ));
+    }
+    if let Some(ref text) = query.body_contains {
+        conditions.push(format!(
+            "positionCaseInsensitive(body, {}) > 0",
+            q.bind("String", text)
+        ));
+    }
+    for (k, v) in sorted(&query.attributes) {
+        let key = q.bind("String", k);
+        conditions.push(format!(
+            "attributes_string[{}] = {}",
+            key,
+            q.bind("String", v)
+        ));
+    }
+
+    let limit = q.bind("UInt32", query.limit.unwrap_or(100));
+    let offset = q.bind("UInt32", query.offset.unwrap_or(0));
+    q.sql = format!(
+        "SELECT timestamp, severity_text, body, attributes_string, resources_string \
+         FROM {} WHERE {} ORDER BY timestamp DESC LIMIT {} OFFSET {}",
+        table,
+        conditions.join(" AND "),
+        limit,
+        offset
+    );
+    q
+}
+
+/// Services with spans in `table` over the last day, with how many
+/// operations each has.
+pub fn build_services_query(table: &str) -> ClickHouseQuery {
+    let tr = default_time_range();
+    let mut q = ClickHouseQuery::default();
+    let start = q.bind("UInt64", (tr.end_ms / 1000).saturating_sub(86_400));
+    q.sql = format!(
+        "SELECT `resource_string_service$$name` AS name, uniq(name) AS num_operations \
+         FROM {} WHERE ts_bucket_start >= {} - {} AND timestamp >= toDateTime({}) \
+         GROUP BY `resource_string_service$$name` ORDER BY name",
+        table, start, BUCKET_SECS, start
+    );
+    q
+}
+
+/// Limit the scan to the buckets that can hold rows between `start_ms` and
+/// `end_ms`; a bucket starts up to [`BUCKET_SECS`] before its rows.
+fn bucket_conditions(
+    q: &mut ClickHouseQuery,
+    conditions: &mut Vec<String>,
+    start_ms: u64,
+    end_ms: u64,
+) {
+    let first = (start_ms / 1000).saturating_sub(BUCKET_SECS);
+    conditions.push(format!("ts_bucket_start >= {}", q.bind("UInt64", first)));
+    conditions.push(format!(
+        "ts_bucket_start <= {}",
+        q.bind("UInt64", end_ms / 1000)
+    ));
+}
+
+/// Map entries in key order, so the same query builds the same SQL.
+fn sorted(map: &std::collections::HashMap<String, String>) -> Vec<(&String, &String)> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort();
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::otlp::types::TimeRange;
+    use std::collections::HashMap;
+
+    fn range() -> Option<TimeRange> {
+        Some(TimeRange {
+            start_ms: 1_700_000_000_000,
+            end_ms: 1_700_003_600_000,
+        })
+    }
+
+    #[test]
+    fn test_build_trace_query() {
+        let query = TraceQuery {
+            service_name: Some("camera".to_string()),
+            min_duration_ms: Some(5),
+            time_range: range(),
+            limit: Some(50),
+            tags: HashMap::from([("node.id".to_string(), "cam'; DROP".to_string())]),
+            ..Default::default()
+        };
+        let q = build_trace_query("signoz_traces.distributed_signoz_index_v3", &query);
+        assert!(q
+            .sql
+            .contains("FROM signoz_traces.distributed_signoz_index_v3 WHERE"));
+        assert!(q
+            .sql
+            .contains("timestamp >= fromUnixTimestamp64Milli({p0:Int64})"));
+        assert!(q
+            .sql
+            .contains("`resource_string_service$$name` = {p4:String}"));
+        assert!(q.sql.contains("duration_nano >= {p5:UInt64}"));
+        assert!(q
+            .sql
+            .contains("attributes_string[{p6:String}] = {p7:String}"));
+        assert!(q.sql.ends_with("LIMIT {p8:UInt32} OFFSET {p9:UInt32}"));
+        // Values only travel as parameters
+        assert!(!q.sql.contains("DROP"));
+
+        let params = q.url_params();
+        assert_eq!(
+            params[0],
+            ("param_p0".to_string(), "1700000000000".to_string())
+        );
+        assert_eq!(params[2].1, "1699998200");
+        assert_eq!(params[3].1, "1700003600");
+        assert_eq!(params[4].1, "camera");
+        assert_eq!(params[5].1, "5000000");
+        assert_eq!(params[7].1, "cam'; DROP");
+        assert_eq!(params[8].1, "50");
+
+        // An unbounded duration saturates rather than overflowing
+        let query = TraceQuery {
+            max_duration_ms: Some(u64::MAX),
+            time_range: range(),
+            ..Default::default()
+        };
+        let q = build_trace_query("signoz_traces.distributed_signoz_index_v3", &query);
+        assert!(q.params.iter().any(|(_, v)| *v == u64::MAX.to_string()));
+    }
+
+    #[test]
+    fn test_build_log_query() {
+        let query = LogQuery {
+            service_name: Some("camera".to_string()),
+            severity: Some("error".to_string()),
+            body_contains: Some("timeout".to_string()),
+            time_range: range(),
+            ..Default::default()
+        };
+        let q = build_log_query("signoz_logs.distributed_logs_v2", &query);
+        assert!(q.sql.contains("timestamp >= {p0:UInt64}"));
+        assert!(q
+            .sql
+            .contains("resources_string['service.name'] = {p4:String}"));
+        assert!(q.sql.contains("upper(severity_text) = upper({p5:String})"));
+        assert!(q
+            .sql
+            .contains("positionCaseInsensitive(body, {p6:String}) > 0"));
+        assert_eq!(q.params[0].1, "1700000000000000000");
+        assert_eq!(q.params[7].1, "100");
+    }
+}
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::secrets;
+
 /// Authentication method for connecting to a backend.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum AuthMethod {
     #[serde(rename = "api_key")]
@@ -13,7 +15,7 @@ pub enum AuthMethod {
 }
 
 /// Configuration for a SigNoz backend.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SigNozConfig {
     pub base_url: String,
     pub auth: AuthMethod,
@@ -25,12 +27,135 @@ fn default_timeout() -> u64 {
     30
 }
 
+/// Configuration for reading SigNoz's ClickHouse tables directly, for
+/// installations where the query API is too slow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClickHouseConfig {
+    /// HTTP interface, e.g. `http://localhost:8123`
+    pub url: String,
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default = "default_traces_table")]
+    pub traces_table: String,
+    #[serde(default = "default_logs_table")]
+    pub logs_table: String,
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+}
+
+pub fn default_traces_table() -> String {
+    "signoz_traces.distributed_signoz_index_v3".to_string()
+}
+
+pub fn default_logs_table() -> String {
+    "signoz_logs.distributed_logs_v2".to_string()
+}
+
+/// Configuration for an Elasticsearch or OpenSearch cluster that logs (and
+/// optionally APM traces) are shipped to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ElasticConfig {
+    /// Cluster URL, e.g. `http://localhost:9200`
+    pub url: String,
+    /// Sent as `Authorization: ApiKey <key>`
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Basic auth, used instead of the API key when set
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Index pattern searched for logs
+    #[serde(default = "default_logs_index")]
+    pub logs_index: String,
+    /// Index pattern searched for spans, e.g. `traces-apm*`
+    #[serde(default)]
+    pub traces_index: Option<String>,
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+}
+
+pub fn default_logs_index() -> String {
+    "logs-*".to_string()
+}
+
+/// Configuration for a Zipkin server, or another store serving its v2 API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZipkinConfig {
+    /// Server URL, e.g. `http://localhost:9411`
+    pub url: String,
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+}
+
 /// Tagged enum of all supported backend configurations.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "backend")]
 pub enum BackendConfig {
     #[serde(rename = "signoz")]
     SigNoz(SigNozConfig),
+    #[serde(rename = "clickhouse")]
+    ClickHouse(ClickHouseConfig),
+    #[serde(rename = "elastic")]
+    Elastic(ElasticConfig),
+    #[serde(rename = "zipkin")]
+    Zipkin(ZipkinConfig),
+}
+
+impl BackendConfig {
+    /// Fill in credentials left out of the configuration from the secret
+    /// store (see [`crate::secrets`]), so they need not be written to
+    /// `settings.json`. This may query the OS credential store, so call it
+    /// off the UI thread.
+    pub fn with_secrets(self) -> Self {
+        self.with_secrets_from(secrets::get)
+    }
+
+    /// The configuration with every credential removed, for sharing it.
+    pub fn without_secrets(mut self) -> Self {
+        match &mut self {
+            BackendConfig::SigNoz(cfg) => cfg.auth = AuthMethod::None,
+            BackendConfig::ClickHouse(cfg) => cfg.password = None,
+            BackendConfig::Elastic(cfg) => {
+                cfg.api_key = None;
+                cfg.password = None;
+            }
+            BackendConfig::Zipkin(_) => {}
+        }
+        self
+    }
+
+    fn with_secrets_from(mut self, get: impl Fn(&str) -> Option<String>) -> Self {
+        match &mut self {
+            BackendConfig::SigNoz(cfg) => {
+                if matches!(cfg.auth, AuthMethod::None) {
+                    if let Some(key) = get(secrets::SIGNOZ_API_KEY) {
+                        cfg.auth = AuthMethod::ApiKey {
+                            header_name: "SIGNOZ-API-KEY".to_string(),
+                            key,
+                        };
+                    }
+                }
+            }
+            BackendConfig::ClickHouse(cfg) => {
+                if cfg.password.is_none() {
+                    cfg.password = get(secrets::CLICKHOUSE_PASSWORD);
+                }
+            }
+            BackendConfig::Elastic(cfg) => {
+                if cfg.api_key.is_none() {
+                    cfg.api_key = get(secrets::ELASTIC_API_KEY);
+                }
+                if cfg.user.is_some() && cfg.password.is_none() {
+                    cfg.password = get(secrets::ELASTIC_PASSWORD);
+                }
+            }
+            BackendConfig::Zipkin(_) => {}
+        }
+        self
+    }
 }
 
 #[cfg(test)]
@@ -101,6 +226,79 @@ mod tests {
                 assert_eq!(cfg.base_url, "http://signoz.example.com");
                 assert_eq!(cfg.timeout_secs, 60);
             }
+            _ => panic!("Expected SigNoz variant"),
+        }
+    }
+
+    #[test]
+    fn test_clickhouse_config_defaults() {
+        let json = r#"{"backend":"clickhouse","url":"http://localhost:8123","user":"reader"}"#;
+        match serde_json::from_str::<BackendConfig>(json).unwrap() {
+            BackendConfig::ClickHouse(cfg) => {
+                assert_eq!(cfg.user.as_deref(), Some("reader"));
+                assert_eq!(cfg.password, None);
+                assert_eq!(cfg.traces_table, default_traces_table());
+                assert_eq!(cfg.logs_table, "signoz_logs.distributed_logs_v2");
+                assert_eq!(cfg.timeout_secs, 30);
+            }
+            _ => panic!("Expected ClickHouse variant"),
+        }
+    }
+
+    #[test]
+    fn test_elastic_config_defaults() {
+        let json = r#"{"backend":"elastic","url":"http://localhost:9200","api_key":"abc"}"#;
+        match serde_json::from_str::<BackendConfig>(json).unwrap() {
+            BackendConfig::Elastic(cfg) => {
+                assert_eq!(cfg.api_key.as_deref(), Some("abc"));
+                assert_eq!(cfg.user, None);
+                assert_eq!(cfg.logs_index, "logs-*");
+                assert_eq!(cfg.traces_index, None);
+                assert_eq!(cfg.timeout_secs, 30);
+            }
+            _ => panic!("Expected Elastic variant"),
+        }
+    }
+
+    #[test]
+    fn test_zipkin_config_serde() {
+        let json = r#"{"backend":"zipkin","url":"http://localhost:9411"}"#;
+        match serde_json::from_str::<BackendConfig>(json).unwrap() {
+            BackendConfig::Zipkin(cfg) => {
+                assert_eq!(cfg.url, "http://localhost:9411");
+                assert_eq!(cfg.timeout_secs, 30);
+            }
+            _ => panic!("Expected Zipkin variant"),
+        }
+    }
+
+    #[test]
+    fn test_with_secrets_fills_missing_credentials() {
+        let stored = |name: &str| Some(format!("stored-{}", name));
+        let signoz = BackendConfig::SigNoz(SigNozConfig {
+            base_url: "http://localhost:3301".to_string(),
+            auth: AuthMethod::None,
+            timeout_secs: 30,
+        });
+        match signoz.with_secrets_from(stored) {
+            BackendConfig::SigNoz(cfg) => match cfg.auth {
+                AuthMethod::ApiKey { key, .. } => assert_eq!(key, "stored-signoz_api_key"),
+                _ => panic!("Expected ApiKey auth"),
+            },
+            _ => panic!("Expected SigNoz variant"),
+        }
+
+        // Configured credentials win
+        let elastic: BackendConfig = serde_json::from_str(
+            r#"{"backend":"elastic","url":"http://e:9200","api_key":"own","user":"studio"}"#,
+        )
+        .unwrap();
+        match elastic.with_secrets_from(stored) {
+            BackendConfig::Elastic(cfg) => {
+                assert_eq!(cfg.api_key.as_deref(), Some("own"));
+                assert_eq!(cfg.password.as_deref(), Some("stored-elastic_password"));
+            }
+            _ => panic!("Expected Elastic variant"),
         }
     }
 
@@ -0,0 +1,449 @@
+use std::collections::HashMap;
+
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde_json::Value;
+
+use crate::datetime::parse_rfc3339_ms;
+use crate::otlp::backend::{Capabilities, TelemetryBackend};
+use crate::otlp::config::ElasticConfig;
+use crate::otlp::error::OtlpError;
+use crate::otlp::signoz::{parse_span_events, parse_span_links};
+use crate::otlp::types::*;
+
+use super::query::{
+    build_log_query, build_services_query, build_trace_query, LEVEL_FIELDS, MESSAGE_FIELDS,
+    SERVICE_FIELDS, SPAN_NAME_FIELDS,
+};
+
+/// A backend searching Elasticsearch or OpenSearch indices for logs and,
+/// when a traces index is configured, APM spans.
+pub struct ElasticBackend {
+    config: ElasticConfig,
+    client: reqwest::Client,
+}
+
+impl ElasticBackend {
+    /// Create a new `ElasticBackend` from configuration.
+    pub fn new(config: ElasticConfig) -> Result<Self, OtlpError> {
+        if config.url.is_empty() {
+            return Err(OtlpError::ConnectionFailed(
+                "url must not be empty".to_string(),
+            ));
+        }
+
+        let mut default_headers = HeaderMap::new();
+        default_headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+        if let Some(ref key) = config.api_key {
+            let val = HeaderValue::from_str(&format!("ApiKey {}", key))
+                .map_err(|e| OtlpError::ConnectionFailed(format!("invalid API key: {}", e)))?;
+            default_headers.insert("Authorization", val);
+        }
+
+        let client = reqwest::Client::builder()
+            .default_headers(default_headers)
+            .timeout(std::time::Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| {
+                OtlpError::ConnectionFailed(format!("failed to build HTTP client: {}", e))
+            })?;
+
+        Ok(Self { config, client })
+    }
+
+    /// Build the full URL for a given path.
+    fn url(&self, path: &str) -> String {
+        let base = self.config.url.trim_end_matches('/');
+        format!("{}{}", base, path)
+    }
+
+    /// Send a request with basic auth when a user is configured, and parse
+    /// the JSON response.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<Value, OtlpError> {
+        let request = match self.config.user {
+            Some(ref user) => request.basic_auth(user, self.config.password.as_ref()),
+            None => request,
+        };
+        let resp = request.send().await?;
+        let status = resp.status();
+
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(OtlpError::AuthenticationFailed(format!(
+                "HTTP {}",
+                status.as_u16()
+            )));
+        }
+
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(OtlpError::ApiError {
+                status: status.as_u16(),
+                message: error_reason(&text).unwrap_or(text),
+            });
+        }
+
+        let text = resp.text().await?;
+        serde_json::from_str(&text).map_err(OtlpError::from)
+    }
+
+    /// Run a `_search` over `index`.
+    async fn search(&self, index: &str, body: &Value) -> Result<Value, OtlpError> {
+        let url = self.url(&format!("/{}/_search", index));
+        self.send(self.client.post(&url).json(body)).await
+    }
+
+    fn traces_index(&self) -> Result<&str, OtlpError> {
+        self.config.traces_index.as_deref().ok_or_else(|| {
+            OtlpError::InvalidQuery(
+                "no traces index configured, e.g. traces-apm* or otel-v1-apm-span-*".to_string(),
+            )
+        })
+    }
+}
+
+impl TelemetryBackend for ElasticBackend {
+    async fn health_check(&self) -> Result<(), OtlpError> {
+        self.send(self.client.get(self.url("/"))).await.map(|_| ())
+    }
+
+    async fn list_services(&self) -> Result<Vec<ServiceInfo>, OtlpError> {
+        let index = self
+            .config
+            .traces_index
+            .as_deref()
+            .unwrap_or(&self.config.logs_index);
+        let resp = self.search(index, &build_services_query()).await?;
+        Ok(parse_services(&resp))
+    }
+
+    async fn query_traces(&self, query: &TraceQuery) -> Result<QueryResult<Span>, OtlpError> {
+        let resp = self
+            .search(self.traces_index()?, &build_trace_query(query))
+            .await?;
+        let items: Vec<Span> = hits(&resp).map(span_from_doc).collect();
+        Ok(QueryResult {
+            total: total_hits(&resp),
+            items,
+        })
+    }
+
+    async fn query_metrics(
+        &self,
+        _query: &MetricQuery,
+    ) -> Result<QueryResult<MetricSeries>, OtlpError> {
+        Err(OtlpError::InvalidQuery(
+            "metrics are not supported by the Elasticsearch backend".to_string(),
+        ))
+    }
+
+    async fn query_logs(&self, query: &LogQuery) -> Result<QueryResult<LogEntry>, OtlpError> {
+        let resp = self
+            .search(&self.config.logs_index, &build_log_query(query))
+            .await?;
+        let items: Vec<LogEntry> = hits(&resp).map(log_from_doc).collect();
+        Ok(QueryResult {
+            total: total_hits(&resp),
+            items,
+        })
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            traces: self.config.traces_index.is_some(),
+            metrics: false,
+            ..Capabilities::ALL
+        }
+    }
+
+    fn display_name(&self) -> String {
+        format!("Elasticsearch @ {}", self.config.url)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// `error.reason` of an error response, e.g. for a bad index pattern.
+fn error_reason(text: &str) -> Option<String> {
+    let body: Value = serde_json::from_str(text).ok()?;
+    let error = &body["error"];
+    error["root_cause"][0]["reason"]
+        .as_str()
+        .or(error["reason"].as_str())
+        .or(error.as_str())
+        .map(String::from)
+}
+
+/// `_source` of each hit.
+fn hits(resp: &Value) -> impl Iterator<Item = &Value> {
+    resp["hits"]["hits"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|hit| &hit["_source"])
+}
+
+/// Matching documents, beyond those returned.
+fn total_hits(resp: &Value) -> Option<u64> {
+    let total = &resp["hits"]["total"];
+    total["value"].as_u64().or(total.as_u64())
+}
+
+/// The value at a dotted `path`, whether the document nests objects
+/// (`{"service": {"name": ..}}`) or has dotted keys (`{"service.name": ..}`).
+fn field<'a>(doc: &'a Value, path: &str) -> Option<&'a Value> {
+    if let Some(value) = doc.get(path) {
+        return Some(value);
+    }
+    path.match_indices('.').find_map(|(i, _)| {
+        doc.get(&path[..i])
+            .and_then(|inner| field(inner, &path[i + 1..]))
+    })
+}
+
+/// The first of `paths` holding a string.
+fn first_str<'a>(doc: &'a Value, paths: &[&str]) -> Option<&'a str> {
+    paths
+        .iter()
+        .find_map(|path| field(doc, path).and_then(Value::as_str))
+}
+
+fn first_string(doc: &Value, paths: &[&str]) -> String {
+    first_str(doc, paths).unwrap_or_default().to_string()
+}
+
+/// A time in ms: epoch ms, or an RFC 3339 string.
+fn time_ms(doc: &Value, paths: &[&str]) -> u64 {
+    paths
+        .iter()
+        .filter_map(|path| field(doc, path))
+        .find_map(|v| v.as_u64().or_else(|| v.as_str().and_then(parse_rfc3339_ms)))
+        .unwrap_or(0)
+}
+
+/// Leaf values under `value` as strings, keyed by their dotted path after
+/// `prefix`.
+fn flatten_into(value: &Value, prefix: &str, out: &mut HashMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map {
+                let key = if prefix.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{}.{}", prefix, k)
+                };
+                flatten_into(v, &key, out);
+            }
+        }
+        Value::String(s) => {
+            out.insert(prefix.to_string(), s.clone());
+        }
+        Value::Null | Value::Array(_) => {}
+        other => {
+            out.insert(prefix.to_string(), other.to_string());
+        }
+    }
+}
+
+/// ECS labels and OpenTelemetry attributes of a document, under `paths`
+/// as objects or as dotted keys; OpenSearch writes the dots of attribute
+/// names as `@`.
+fn attributes(doc: &Value, paths: &[&str]) -> HashMap<String, String> {
+    let mut attributes = HashMap::new();
+    for path in paths {
+        if let Some(value) = field(doc, path) {
+            flatten_into(value, "", &mut attributes);
+        }
+        let prefix = format!("{}.", path);
+        for (key, value) in doc.as_object().into_iter().flatten() {
+            if let Some(name) = key.strip_prefix(&prefix) {
+                flatten_into(value, name, &mut attributes);
+            }
+        }
+    }
+    attributes
+        .into_iter()
+        .map(|(k, v)| (k.replace('@', "."), v))
+        .collect()
+}
+
+fn span_from_doc(doc: &Value) -> Span {
+    let parent_span_id = first_str(doc, &["parent.id", "parentSpanId"])
+        .filter(|s| !s.is_empty())
+        .map(String::from);
+    let duration_ms = match field(doc, "durationInNanos").and_then(Value::as_u64) {
+        Some(ns) => ns / 1_000_000,
+        None => {
+            ["span.duration.us", "transaction.duration.us"]
+                .iter()
+                .find_map(|path| field(doc, path).and_then(Value::as_u64))
+                .unwrap_or(0)
+                / 1000
+        }
+    };
+    let status_code = field(doc, "status.code")
+        .and_then(Value::as_i64)
+        .unwrap_or(0) as i32;
+    let has_error = status_code == 2
+        || first_str(doc, &["event.outcome"]) == Some("failure")
+        || first_str(doc, &["status.code"]) == Some("ERROR");
+    Span {
+        trace_id: first_string(doc, &["trace.id", "traceId"]),
+        span_id: first_string(doc, &["span.id", "transaction.id", "spanId"]),
+        links: field(doc, "links")
+            .or_else(|| field(doc, "span.links"))
+            .map(|v| parse_span_links(v, parent_span_id.as_deref()))
+            .unwrap_or_default(),
+        parent_span_id,
+        service_name: first_string(doc, &SERVICE_FIELDS),
+        operation_name: first_string(doc, &SPAN_NAME_FIELDS),
+        start_time_ms: time_ms(doc, &["@timestamp", "startTime"]),
+        duration_ms,
+        status_code: if has_error { 2 } else { status_code },
+        has_error,
+        attributes: attributes(doc, &["labels", "span.attributes", "resource.attributes"]),
+        events: field(doc, "events")
+            .map(parse_span_events)
+            .unwrap_or_default(),
+    }
+}
+
+fn log_from_doc(doc: &Value) -> LogEntry {
+    LogEntry {
+        timestamp_ms: time_ms(doc, &["@timestamp", "time", "timestamp"]),
+        severity: first_string(doc, &LEVEL_FIELDS),
+        body: first_string(doc, &MESSAGE_FIELDS),
+        service_name: first_string(doc, &SERVICE_FIELDS),
+        attributes: attributes(doc, &["labels", "attributes"]),
+    }
+}
+
+/// Services from both aggregations of [`build_services_query`].
+fn parse_services(resp: &Value) -> Vec<ServiceInfo> {
+    let mut services: Vec<ServiceInfo> = Vec::new();
+    for agg in ["ecs", "otel"] {
+        let buckets = resp["aggregations"][agg]["buckets"].as_array();
+        for bucket in buckets.into_iter().flatten() {
+            let Some(name) = bucket["key"].as_str() else {
+                continue;
+            };
+            let num_operations = bucket["operations"]["value"].as_u64().unwrap_or(0);
+            match services.iter_mut().find(|s| s.name == name) {
+                Some(s) => s.num_operations = s.num_operations.max(num_operations),
+                None => services.push(ServiceInfo {
+                    name: name.to_string(),
+                    num_operations,
+                }),
+            }
+        }
+    }
+    services.sort_by(|a, b| a.name.cmp(&b.name));
+    services
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::otlp::config::default_logs_index;
+    use serde_json::json;
+
+    #[test]
+    fn test_new_elastic_backend() {
+        let config = ElasticConfig {
+            url: "http://localhost:9200/".to_string(),
+            api_key: Some("a2V5".to_string()),
+            user: None,
+            password: None,
+            logs_index: default_logs_index(),
+            traces_index: None,
+            timeout_secs: 30,
+        };
+        let backend = ElasticBackend::new(config.clone()).unwrap();
+        assert_eq!(
+            backend.url("/logs-*/_search"),
+            "http://localhost:9200/logs-*/_search"
+        );
+        assert!(backend.traces_index().is_err());
+        assert!(!backend.capabilities().traces);
+        assert!(backend.capabilities().logs);
+        assert!(ElasticBackend::new(ElasticConfig {
+            url: String::new(),
+            ..config
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_log_from_ecs_document() {
+        let resp = json!({"hits": {"total": {"value": 42}, "hits": [{"_source": {
+            "@timestamp": "2024-03-01T12:00:00.250Z",
+            "message": "no device",
+            "log": {"level": "error"},
+            "service.name": "camera",
+            "labels": {"node_id": "camera", "restarts": 2}
+        }}]}});
+        let logs: Vec<LogEntry> = hits(&resp).map(log_from_doc).collect();
+        assert_eq!(total_hits(&resp), Some(42));
+        assert_eq!(logs[0].timestamp_ms, 1_709_294_400_250);
+        assert_eq!(logs[0].severity, "error");
+        assert_eq!(logs[0].body, "no device");
+        assert_eq!(logs[0].service_name, "camera");
+        assert_eq!(logs[0].attributes["node_id"], "camera");
+        assert_eq!(logs[0].attributes["restarts"], "2");
+
+        // OpenTelemetry logs keep the text under body
+        let otel = json!({"time": 5, "body": {"text": "ready"}, "severityText": "INFO"});
+        let log = log_from_doc(&otel);
+        assert_eq!((log.timestamp_ms, log.body.as_str()), (5, "ready"));
+        assert_eq!(log.severity, "INFO");
+    }
+
+    #[test]
+    fn test_span_from_documents() {
+        let apm = json!({
+            "@timestamp": "2024-03-01T12:00:00Z",
+            "trace": {"id": "t1"}, "span": {"id": "s2", "name": "capture", "duration": {"us": 12_500}},
+            "parent": {"id": "s1"}, "service": {"name": "camera"},
+            "event": {"outcome": "failure"}
+        });
+        let span = span_from_doc(&apm);
+        assert_eq!(
+            (span.trace_id.as_str(), span.span_id.as_str()),
+            ("t1", "s2")
+        );
+        assert_eq!(span.parent_span_id.as_deref(), Some("s1"));
+        assert_eq!(span.operation_name, "capture");
+        assert_eq!(span.duration_ms, 12);
+        assert!(span.has_error);
+        assert_eq!(span.status_code, 2);
+
+        let otel = json!({
+            "traceId": "t1", "spanId": "s3", "parentSpanId": "", "serviceName": "plot",
+            "name": "render", "startTime": "2024-03-01T12:00:01.5Z", "durationInNanos": 3_000_000,
+            "status.code": 0, "span.attributes.node@id": "plot"
+        });
+        let span = span_from_doc(&otel);
+        assert_eq!(span.parent_span_id, None);
+        assert_eq!(span.service_name, "plot");
+        assert_eq!(span.start_time_ms, 1_709_294_401_500);
+        assert_eq!(span.duration_ms, 3);
+        assert!(!span.has_error);
+        assert_eq!(span.attributes["node.id"], "plot");
+    }
+
+    #[test]
+    fn test_parse_services() {
+        let resp = json!({"aggregations": {
+            "ecs": {"buckets": [{"key": "camera", "doc_count": 5, "operations": {"value": 3}}]},
+            "otel": {"buckets": [
+                {"key": "plot", "doc_count": 2, "operations": {"value": 1}},
+                {"key": "camera", "doc_count": 1, "operations": {"value": 4}}
+            ]}
+        }});
+        let services = parse_services(&resp);
+        assert_eq!(services.len(), 2);
+        assert_eq!(services[0].name, "camera");
+        assert_eq!(services[0].num_operations, 4);
+        assert_eq!(services[1].name, "plot");
+    }
+}
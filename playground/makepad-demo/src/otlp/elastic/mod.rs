@@ -0,0 +1,4 @@
+mod client;
+pub mod query;
+
+pub use client::ElasticBackend;
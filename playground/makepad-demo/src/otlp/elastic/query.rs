@@ -0,0 +1,226 @@
+//! `_search` bodies for Elasticsearch and OpenSearch.
+//!
+//! Documents may follow the Elastic Common Schema (`@timestamp`,
+//! `service.name`, `log.level`; APM spans and transactions in
+//! `traces-apm*`) or OpenSearch's OpenTelemetry layout (`startTime`,
+//! `serviceName`, `durationInNanos` in `otel-v1-apm-span-*`). Filters match
+//! a field of either, so the same query works on both without configuring
+//! which one an index uses.
+
+use serde_json::{json, Value};
+
+use crate::otlp::signoz::query::default_time_range;
+use crate::otlp::types::{LogQuery, TimeRange, TraceQuery};
+
+/// Fields holding the service name.
+pub(super) const SERVICE_FIELDS: [&str; 3] =
+    ["service.name", "serviceName", "resource.service.name"];
+/// Fields holding the log level.
+pub(super) const LEVEL_FIELDS: [&str; 3] = ["log.level", "severity_text", "severityText"];
+/// Fields holding the log message.
+pub(super) const MESSAGE_FIELDS: [&str; 3] = ["message", "body", "body.text"];
+/// Fields holding the span name.
+pub(super) const SPAN_NAME_FIELDS: [&str; 3] = ["span.name", "transaction.name", "name"];
+
+/// Latest log lines matching `query`.
+pub fn build_log_query(query: &LogQuery) -> Value {
+    let tr = query.time_range.clone().unwrap_or_else(default_time_range);
+    let mut filters = vec![time_filter(&["@timestamp", "time"], &tr)];
+    if let Some(ref svc) = query.service_name {
+        filters.push(any_of(&SERVICE_FIELDS, |f| json!({"term": {f: svc}})));
+    }
+    if let Some(ref severity) = query.severity {
+        filters.push(any_of(
+            &LEVEL_FIELDS,
+            |f| json!({"term": {f: {"value": severity, "case_insensitive": true}}}),
+        ));
+    }
+    if let Some(ref text) = query.body_contains {
+        filters.push(any_of(
+            &MESSAGE_FIELDS,
+            |f| json!({"match_phrase": {f: text}}),
+        ));
+    }
+    for (k, v) in &query.attributes {
+        filters.push(attribute_filter(k, v));
+    }
+    search(filters, &["@timestamp", "time"], query.limit, query.offset)
+}
+
+/// Latest spans (and APM transactions) matching `query`.
+pub fn build_trace_query(query: &TraceQuery) -> Value {
+    let tr = query.time_range.clone().unwrap_or_else(default_time_range);
+    let mut filters = vec![
+        time_filter(&["@timestamp", "startTime"], &tr),
+        // APM indices also hold errors and metrics
+        json!({"bool": {"must_not": {"terms": {"processor.event": ["error", "metric"]}}}}),
+    ];
+    if let Some(ref svc) = query.service_name {
+        filters.push(any_of(&SERVICE_FIELDS, |f| json!({"term": {f: svc}})));
+    }
+    if let Some(ref op) = query.operation_name {
+        filters.push(any_of(&SPAN_NAME_FIELDS, |f| json!({"term": {f: op}})));
+    }
+    if query.min_duration_ms.is_some() || query.max_duration_ms.is_some() {
+        let range = |unit: u64| {
+            let mut range = serde_json::Map::new();
+            if let Some(ms) = query.min_duration_ms {
+                range.insert("gte".to_string(), json!(ms * unit));
+            }
+            if let Some(ms) = query.max_duration_ms {
+                range.insert("lte".to_string(), json!(ms * unit));
+            }
+            Value::Object(range)
+        };
+        filters.push(json!({"bool": {"should": [
+            {"range": {"span.duration.us": range(1000)}},
+            {"range": {"transaction.duration.us": range(1000)}},
+            {"range": {"durationInNanos": range(1_000_000)}},
+        ], "minimum_should_match": 1}}));
+    }
+    for (k, v) in &query.tags {
+        filters.push(attribute_filter(k, v));
+    }
+    search(
+        filters,
+        &["@timestamp", "startTime"],
+        query.limit,
+        query.offset,
+    )
+}
+
+/// Service names, with how many span names each has.
+pub fn build_services_query() -> Value {
+    let tr = default_time_range();
+    json!({
+        "size": 0,
+        "query": time_filter(&["@timestamp", "startTime", "time"], &tr),
+        "aggs": {
+            "ecs": {
+                "terms": {"field": "service.name", "size": 200},
+                "aggs": {"operations": {"cardinality": {"field": "span.name"}}}
+            },
+            "otel": {
+                "terms": {"field": "serviceName", "size": 200},
+                "aggs": {"operations": {"cardinality": {"field": "name"}}}
+            }
+        }
+    })
+}
+
+/// A bool query matching at least one of the `fields` with `clause`.
+fn any_of(fields: &[&str], clause: impl Fn(&str) -> Value) -> Value {
+    let should: Vec<Value> = fields.iter().map(|f| clause(f)).collect();
+    json!({"bool": {"should": should, "minimum_should_match": 1}})
+}
+
+fn time_filter(fields: &[&str], tr: &TimeRange) -> Value {
+    any_of(
+        fields,
+        |f| json!({"range": {f: {"gte": tr.start_ms, "lt": tr.end_ms, "format": "epoch_millis"}}}),
+    )
+}
+
+/// An attribute as an ECS label, an OpenTelemetry attribute, or a field of
+/// its own name.
+fn attribute_filter(key: &str, value: &str) -> Value {
+    let label = format!("labels.{}", key.replace('.', "_"));
+    let span_attribute = format!("span.attributes.{}", key.replace('.', "@"));
+    let attribute = format!("attributes.{}", key);
+    let fields = [
+        key,
+        label.as_str(),
+        span_attribute.as_str(),
+        attribute.as_str(),
+    ];
+    any_of(&fields, |f| json!({"term": {f: value}}))
+}
+
+/// Newest first on whichever of `time_fields` an index has.
+fn search(
+    filters: Vec<Value>,
+    time_fields: &[&str],
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Value {
+    let sort: Vec<Value> = time_fields
+        .iter()
+        .map(|f| json!({*f: {"order": "desc", "unmapped_type": "date"}}))
+        .collect();
+    json!({
+        "size": limit.unwrap_or(100),
+        "from": offset.unwrap_or(0),
+        "sort": sort,
+        "query": {"bool": {"filter": filters}}
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn range() -> Option<TimeRange> {
+        Some(TimeRange {
+            start_ms: 1_000,
+            end_ms: 2_000,
+        })
+    }
+
+    #[test]
+    fn test_build_log_query() {
+        let query = LogQuery {
+            service_name: Some("camera".to_string()),
+            severity: Some("error".to_string()),
+            body_contains: Some("no device".to_string()),
+            time_range: range(),
+            limit: Some(20),
+            attributes: HashMap::from([("node.id".to_string(), "camera".to_string())]),
+            ..Default::default()
+        };
+        let body = build_log_query(&query);
+        assert_eq!(body["size"], 20);
+        assert_eq!(body["from"], 0);
+        assert_eq!(body["sort"][0]["@timestamp"]["unmapped_type"], "date");
+
+        let filters = body["query"]["bool"]["filter"].as_array().unwrap();
+        assert_eq!(filters.len(), 5);
+        let time = &filters[0]["bool"]["should"][0]["range"]["@timestamp"];
+        assert_eq!(time["gte"], 1_000);
+        assert_eq!(time["lt"], 2_000);
+        assert_eq!(
+            filters[1]["bool"]["should"][1]["term"]["serviceName"],
+            "camera"
+        );
+        assert_eq!(
+            filters[2]["bool"]["should"][0]["term"]["log.level"]["case_insensitive"],
+            true
+        );
+        assert_eq!(
+            filters[3]["bool"]["should"][0]["match_phrase"]["message"],
+            "no device"
+        );
+        let attribute = &filters[4]["bool"]["should"];
+        assert_eq!(attribute[1]["term"]["labels.node_id"], "camera");
+        assert_eq!(attribute[2]["term"]["span.attributes.node@id"], "camera");
+    }
+
+    #[test]
+    fn test_build_trace_query() {
+        let query = TraceQuery {
+            min_duration_ms: Some(5),
+            time_range: range(),
+            ..Default::default()
+        };
+        let body = build_trace_query(&query);
+        assert_eq!(body["size"], 100);
+        let filters = body["query"]["bool"]["filter"].as_array().unwrap();
+        assert_eq!(filters.len(), 3);
+        let durations = &filters[2]["bool"]["should"];
+        assert_eq!(durations[0]["range"]["span.duration.us"]["gte"], 5_000);
+        assert!(durations[0]["range"]["span.duration.us"]
+            .get("lte")
+            .is_none());
+        assert_eq!(durations[2]["range"]["durationInNanos"]["gte"], 5_000_000);
+    }
+}
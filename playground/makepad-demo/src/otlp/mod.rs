@@ -1,45 +1,84 @@
+// Span and query types are shared with the web build, which gets them from
+// a `--serve` studio (see `crate::remote`); the backends are native only
+#[cfg(not(target_arch = "wasm32"))]
 pub mod backend;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod bridge;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod clickhouse;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod config;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod elastic;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod error;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod signoz;
 pub mod types;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod zipkin;
 
-pub use bridge::{
-    get_connection_status, init_signoz_from_env, is_signoz_configured, request_health_check,
-    request_traces, take_signoz_responses, ConnectionStatus, SignozResponse,
+#[cfg(not(target_arch = "wasm32"))]
+pub use backend::Capabilities;
+#[cfg(not(target_arch = "wasm32"))]
+pub use bridge::{ConnectionStatus, SignozBridge, SignozResponse};
+#[cfg(not(target_arch = "wasm32"))]
+pub use clickhouse::ClickHouseBackend;
+#[cfg(not(target_arch = "wasm32"))]
+pub use config::{
+    AuthMethod, BackendConfig, ClickHouseConfig, ElasticConfig, SigNozConfig, ZipkinConfig,
 };
-pub use config::{AuthMethod, BackendConfig, SigNozConfig};
+#[cfg(not(target_arch = "wasm32"))]
+pub use elastic::ElasticBackend;
+#[cfg(not(target_arch = "wasm32"))]
 pub use error::OtlpError;
+#[cfg(not(target_arch = "wasm32"))]
 pub use signoz::SigNozBackend;
 pub use types::*;
+#[cfg(not(target_arch = "wasm32"))]
+pub use zipkin::ZipkinBackend;
 
+#[cfg(not(target_arch = "wasm32"))]
 use backend::TelemetryBackend;
 
 /// Enum-dispatch wrapper over concrete telemetry backends.
 ///
 /// Each variant delegates to the underlying backend's `TelemetryBackend` impl.
 /// This avoids pulling in `async-trait` as a dependency.
+#[cfg(not(target_arch = "wasm32"))]
 pub enum TelemetryClient {
     SigNoz(SigNozBackend),
+    ClickHouse(ClickHouseBackend),
+    Elastic(ElasticBackend),
+    Zipkin(ZipkinBackend),
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl TelemetryClient {
     pub async fn health_check(&self) -> Result<(), OtlpError> {
         match self {
             TelemetryClient::SigNoz(b) => b.health_check().await,
+            TelemetryClient::ClickHouse(b) => b.health_check().await,
+            TelemetryClient::Elastic(b) => b.health_check().await,
+            TelemetryClient::Zipkin(b) => b.health_check().await,
         }
     }
 
     pub async fn list_services(&self) -> Result<Vec<ServiceInfo>, OtlpError> {
         match self {
             TelemetryClient::SigNoz(b) => b.list_services().await,
+            TelemetryClient::ClickHouse(b) => b.list_services().await,
+            TelemetryClient::Elastic(b) => b.list_services().await,
+            TelemetryClient::Zipkin(b) => b.list_services().await,
         }
     }
 
     pub async fn query_traces(&self, query: &TraceQuery) -> Result<QueryResult<Span>, OtlpError> {
         match self {
             TelemetryClient::SigNoz(b) => b.query_traces(query).await,
+            TelemetryClient::ClickHouse(b) => b.query_traces(query).await,
+            TelemetryClient::Elastic(b) => b.query_traces(query).await,
+            TelemetryClient::Zipkin(b) => b.query_traces(query).await,
         }
     }
 
@@ -49,29 +88,60 @@ impl TelemetryClient {
     ) -> Result<QueryResult<MetricSeries>, OtlpError> {
         match self {
             TelemetryClient::SigNoz(b) => b.query_metrics(query).await,
+            TelemetryClient::ClickHouse(b) => b.query_metrics(query).await,
+            TelemetryClient::Elastic(b) => b.query_metrics(query).await,
+            TelemetryClient::Zipkin(b) => b.query_metrics(query).await,
         }
     }
 
     pub async fn query_logs(&self, query: &LogQuery) -> Result<QueryResult<LogEntry>, OtlpError> {
         match self {
             TelemetryClient::SigNoz(b) => b.query_logs(query).await,
+            TelemetryClient::ClickHouse(b) => b.query_logs(query).await,
+            TelemetryClient::Elastic(b) => b.query_logs(query).await,
+            TelemetryClient::Zipkin(b) => b.query_logs(query).await,
+        }
+    }
+
+    pub fn capabilities(&self) -> Capabilities {
+        match self {
+            TelemetryClient::SigNoz(b) => b.capabilities(),
+            TelemetryClient::ClickHouse(b) => b.capabilities(),
+            TelemetryClient::Elastic(b) => b.capabilities(),
+            TelemetryClient::Zipkin(b) => b.capabilities(),
         }
     }
 
     pub fn display_name(&self) -> String {
         match self {
             TelemetryClient::SigNoz(b) => b.display_name(),
+            TelemetryClient::ClickHouse(b) => b.display_name(),
+            TelemetryClient::Elastic(b) => b.display_name(),
+            TelemetryClient::Zipkin(b) => b.display_name(),
         }
     }
 }
 
 /// Create a telemetry client from a backend configuration.
+#[cfg(not(target_arch = "wasm32"))]
 pub fn create_backend(config: BackendConfig) -> Result<TelemetryClient, OtlpError> {
     match config {
         BackendConfig::SigNoz(cfg) => {
             let backend = SigNozBackend::new(cfg)?;
             Ok(TelemetryClient::SigNoz(backend))
         }
+        BackendConfig::ClickHouse(cfg) => {
+            let backend = ClickHouseBackend::new(cfg)?;
+            Ok(TelemetryClient::ClickHouse(backend))
+        }
+        BackendConfig::Elastic(cfg) => {
+            let backend = ElasticBackend::new(cfg)?;
+            Ok(TelemetryClient::Elastic(backend))
+        }
+        BackendConfig::Zipkin(cfg) => {
+            let backend = ZipkinBackend::new(cfg)?;
+            Ok(TelemetryClient::Zipkin(backend))
+        }
     }
 }
 
@@ -90,6 +160,50 @@ mod tests {
         assert_eq!(client.display_name(), "SigNoz @ http://localhost:3301");
     }
 
+    #[test]
+    fn test_create_backend_clickhouse() {
+        let config = BackendConfig::ClickHouse(ClickHouseConfig {
+            url: "http://localhost:8123".to_string(),
+            user: None,
+            password: None,
+            traces_table: config::default_traces_table(),
+            logs_table: config::default_logs_table(),
+            timeout_secs: 30,
+        });
+        let client = create_backend(config).unwrap();
+        assert_eq!(client.display_name(), "ClickHouse @ http://localhost:8123");
+    }
+
+    #[test]
+    fn test_create_backend_elastic() {
+        let config = BackendConfig::Elastic(ElasticConfig {
+            url: "http://localhost:9200".to_string(),
+            api_key: None,
+            user: None,
+            password: None,
+            logs_index: config::default_logs_index(),
+            traces_index: None,
+            timeout_secs: 30,
+        });
+        let client = create_backend(config).unwrap();
+        assert_eq!(
+            client.display_name(),
+            "Elasticsearch @ http://localhost:9200"
+        );
+    }
+
+    #[test]
+    fn test_create_backend_zipkin() {
+        let config = BackendConfig::Zipkin(ZipkinConfig {
+            url: "http://localhost:9411".to_string(),
+            timeout_secs: 30,
+        });
+        let client = create_backend(config).unwrap();
+        assert_eq!(client.display_name(), "Zipkin @ http://localhost:9411");
+        let capabilities = client.capabilities();
+        assert!(capabilities.traces && !capabilities.logs && !capabilities.metrics);
+    }
+
     #[test]
     fn test_create_backend_invalid_config() {
         let config = BackendConfig::SigNoz(SigNozConfig {
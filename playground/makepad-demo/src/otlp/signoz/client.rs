@@ -2,7 +2,8 @@ use std::collections::HashMap;
 
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 
-use crate::otlp::backend::TelemetryBackend;
+use crate::datetime::parse_rfc3339_ms;
+use crate::otlp::backend::{Capabilities, TelemetryBackend};
 use crate::otlp::config::{AuthMethod, SigNozConfig};
 use crate::otlp::error::OtlpError;
 use crate::otlp::types::*;
@@ -146,7 +147,7 @@ impl SigNozBackend {
     }
 
     /// Parse list-type results into `Span` values.
-    fn parse_trace_results(resp: &SigNozResponse) -> Vec<Span> {
+    pub fn parse_trace_results(resp: &SigNozResponse) -> Vec<Span> {
         let entries = Self::extract_result_entries(resp);
         let mut spans = Vec::new();
 
@@ -154,20 +155,26 @@ impl SigNozBackend {
             if let Some(ref list) = entry.list {
                 for row in list {
                     let data = &row.data;
+                    let parent_span_id = data
+                        .get("parentSpanID")
+                        .and_then(|v| v.as_str())
+                        .filter(|s| !s.is_empty())
+                        .map(String::from);
                     let span = Span {
                         trace_id: json_str(data, "traceID"),
                         span_id: json_str(data, "spanID"),
-                        parent_span_id: data
-                            .get("parentSpanID")
-                            .and_then(|v| v.as_str())
-                            .filter(|s| !s.is_empty())
-                            .map(String::from),
+                        links: data
+                            .get("references")
+                            .or_else(|| data.get("links"))
+                            .map(|v| parse_span_links(v, parent_span_id.as_deref()))
+                            .unwrap_or_default(),
+                        parent_span_id,
                         service_name: json_str(data, "serviceName"),
                         operation_name: json_str(data, "name"),
                         start_time_ms: data
                             .get("timestamp")
                             .and_then(parse_timestamp)
-                            .or_else(|| row.timestamp.as_deref().and_then(parse_iso8601_to_ms))
+                            .or_else(|| row.timestamp.as_deref().and_then(parse_rfc3339_ms))
                             .unwrap_or(0),
                         duration_ms: data
                             .get("durationNano")
@@ -181,6 +188,10 @@ impl SigNozBackend {
                             .and_then(|v| v.as_bool())
                             .unwrap_or(false),
                         attributes: extract_string_map(data),
+                        events: data
+                            .get("events")
+                            .map(parse_span_events)
+                            .unwrap_or_default(),
                     };
                     spans.push(span);
                 }
@@ -190,7 +201,7 @@ impl SigNozBackend {
     }
 
     /// Parse list-type results into `LogEntry` values.
-    fn parse_log_results(resp: &SigNozResponse) -> Vec<LogEntry> {
+    pub fn parse_log_results(resp: &SigNozResponse) -> Vec<LogEntry> {
         let entries = Self::extract_result_entries(resp);
         let mut logs = Vec::new();
 
@@ -202,7 +213,7 @@ impl SigNozBackend {
                         timestamp_ms: data
                             .get("timestamp")
                             .and_then(parse_timestamp)
-                            .or_else(|| row.timestamp.as_deref().and_then(parse_iso8601_to_ms))
+                            .or_else(|| row.timestamp.as_deref().and_then(parse_rfc3339_ms))
                             .unwrap_or(0),
                         severity: json_str(data, "severity_text"),
                         body: json_str(data, "body"),
@@ -217,7 +228,7 @@ impl SigNozBackend {
     }
 
     /// Parse time-series results into `MetricSeries` values.
-    fn parse_metric_results(resp: &SigNozResponse) -> Vec<MetricSeries> {
+    pub fn parse_metric_results(resp: &SigNozResponse) -> Vec<MetricSeries> {
         let entries = Self::extract_result_entries(resp);
         let mut metrics = Vec::new();
 
@@ -316,6 +327,10 @@ impl TelemetryBackend for SigNozBackend {
         })
     }
 
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::ALL
+    }
+
     fn display_name(&self) -> String {
         format!("SigNoz @ {}", self.config.base_url)
     }
@@ -333,87 +348,114 @@ fn json_str(map: &HashMap<String, serde_json::Value>, key: &str) -> String {
 }
 
 fn parse_timestamp(v: &serde_json::Value) -> Option<u64> {
-    if let Some(n) = v.as_u64() {
-        // If the value looks like nanoseconds (> 1e15), convert to ms
-        if n > 1_000_000_000_000_000 {
-            Some(n / 1_000_000)
-        } else if n > 1_000_000_000_000 {
-            // Already milliseconds
-            Some(n)
-        } else {
-            // Seconds
-            Some(n * 1000)
-        }
-    } else if let Some(s) = v.as_str() {
-        s.parse::<u64>().ok().map(|n| {
-            if n > 1_000_000_000_000_000 {
-                n / 1_000_000
-            } else if n > 1_000_000_000_000 {
-                n
-            } else {
-                n * 1000
-            }
-        })
+    let n = match v.as_u64() {
+        Some(n) => n,
+        None => v.as_str()?.parse::<u64>().ok()?,
+    };
+    // If the value looks like nanoseconds (> 1e15), convert to ms
+    if n > 1_000_000_000_000_000 {
+        Some(n / 1_000_000)
+    } else if n > 1_000_000_000_000 {
+        // Already milliseconds
+        Some(n)
     } else {
-        None
+        // Seconds
+        Some(n * 1000)
     }
 }
 
-/// Parse an ISO 8601 / RFC 3339 timestamp string to milliseconds since epoch.
-/// Handles formats like "2026-02-02T19:40:37.126981Z" and "2026-02-02T19:40:37Z".
-fn parse_iso8601_to_ms(s: &str) -> Option<u64> {
-    // Expected: "YYYY-MM-DDTHH:MM:SS[.frac]Z"
-    let s = s.trim();
-    let (date_part, time_part) = s.split_once('T')?;
-    // Handle +00:00 offset
-    let time_part = time_part
-        .strip_suffix('Z')
-        .or_else(|| time_part.strip_suffix("+00:00"))
-        .or(Some(time_part))?;
-
-    let mut date_iter = date_part.splitn(3, '-');
-    let year: i64 = date_iter.next()?.parse().ok()?;
-    let month: i64 = date_iter.next()?.parse().ok()?;
-    let day: i64 = date_iter.next()?.parse().ok()?;
-
-    let (time_hms, frac_str) = if let Some((hms, frac)) = time_part.split_once('.') {
-        (hms, frac)
-    } else {
-        (time_part, "0")
-    };
-
-    let mut time_iter = time_hms.splitn(3, ':');
-    let hour: i64 = time_iter.next()?.parse().ok()?;
-    let minute: i64 = time_iter.next()?.parse().ok()?;
-    let second: i64 = time_iter.next()?.parse().ok()?;
-
-    // Parse fractional seconds to milliseconds
-    let frac_ms: u64 = if frac_str.len() >= 3 {
-        frac_str[..3].parse().unwrap_or(0)
-    } else {
-        let padded = format!("{:0<3}", frac_str);
-        padded.parse().unwrap_or(0)
-    };
+/// Objects in a column holding JSON objects, either directly or as
+/// JSON-encoded strings: `events` is an array of strings, `references` a
+/// string holding an array.
+fn json_objects(value: &serde_json::Value) -> Vec<serde_json::Map<String, serde_json::Value>> {
+    match value {
+        serde_json::Value::Object(map) => vec![map.clone()],
+        serde_json::Value::Array(items) => items.iter().flat_map(json_objects).collect(),
+        serde_json::Value::String(s) => serde_json::from_str::<serde_json::Value>(s)
+            .map(|v| json_objects(&v))
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
 
-    // Days from epoch (1970-01-01) using a simplified calculation
-    let days = days_from_civil(year, month, day);
-    let total_secs = days * 86400 + hour * 3600 + minute * 60 + second;
+/// Value of the first of `keys` present in `map`.
+fn first_of<'a>(
+    map: &'a serde_json::Map<String, serde_json::Value>,
+    keys: &[&str],
+) -> Option<&'a serde_json::Value> {
+    keys.iter().find_map(|key| map.get(*key))
+}
 
-    if total_secs < 0 {
-        return None;
-    }
+/// String attributes of an event or link; numbers and booleans as text.
+fn attribute_map(value: Option<&serde_json::Value>) -> HashMap<String, String> {
+    value
+        .and_then(|v| v.as_object())
+        .map(|map| {
+            map.iter()
+                .map(|(k, v)| {
+                    let text = v
+                        .as_str()
+                        .map(String::from)
+                        .unwrap_or_else(|| v.to_string());
+                    (k.clone(), text)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
 
-    Some(total_secs as u64 * 1000 + frac_ms)
+/// Span events such as `{"name":"exception","timeUnixNano":...,"attributeMap":{...}}`,
+/// in time order.
+pub(crate) fn parse_span_events(value: &serde_json::Value) -> Vec<SpanEvent> {
+    let mut events: Vec<SpanEvent> = json_objects(value)
+        .iter()
+        .map(|event| SpanEvent {
+            name: first_of(event, &["name", "Name"])
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            timestamp_ms: first_of(event, &["timeUnixNano", "timestamp", "Timestamp"])
+                .and_then(parse_timestamp)
+                .unwrap_or(0),
+            attributes: attribute_map(first_of(event, &["attributeMap", "attributes"])),
+        })
+        .filter(|event| !event.name.is_empty())
+        .collect();
+    events.sort_by_key(|event| event.timestamp_ms);
+    events
 }
 
-/// Convert a civil date to days since 1970-01-01 (Howard Hinnant's algorithm).
-fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
-    let y = if m <= 2 { y - 1 } else { y };
-    let era = if y >= 0 { y } else { y - 399 } / 400;
-    let yoe = (y - era * 400) as u64;
-    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) as u64 + 2) / 5 + d as u64 - 1;
-    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
-    era * 146097 + doe as i64 - 719468
+/// Span links from the `references` column, e.g.
+/// `[{"TraceId":"...","SpanId":"...","RefType":"FOLLOWS_FROM"}]`. The
+/// reference to the parent span is not a link and is skipped.
+pub(crate) fn parse_span_links(
+    value: &serde_json::Value,
+    parent_span_id: Option<&str>,
+) -> Vec<SpanLink> {
+    json_objects(value)
+        .iter()
+        .filter_map(|link| {
+            let id = |keys: &[&str]| {
+                first_of(link, keys)
+                    .and_then(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+            };
+            let span_id = id(&["SpanId", "spanId", "span_id"])?;
+            if Some(span_id.as_str()) == parent_span_id {
+                return None;
+            }
+            let mut attributes = attribute_map(first_of(link, &["attributes", "attributeMap"]));
+            if let Some(ref_type) = id(&["RefType", "refType"]) {
+                attributes.insert("ref_type".to_string(), ref_type);
+            }
+            Some(SpanLink {
+                trace_id: id(&["TraceId", "traceId", "trace_id"]).unwrap_or_default(),
+                span_id,
+                attributes,
+            })
+        })
+        .collect()
 }
 
 fn extract_string_map(data: &HashMap<String, serde_json::Value>) -> HashMap<String, String> {
@@ -426,6 +468,7 @@ fn extract_string_map(data: &HashMap<String, serde_json::Value>) -> HashMap<Stri
 mod tests {
     use super::*;
     use crate::otlp::config::AuthMethod;
+    use proptest::prelude::*;
 
     #[test]
     fn test_new_signoz_backend_empty_url() {
@@ -616,24 +659,26 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_iso8601_basic() {
-        assert_eq!(
-            parse_iso8601_to_ms("2026-02-02T19:40:37.126981Z"),
-            Some(1770061237126)
-        );
-    }
-
-    #[test]
-    fn test_parse_iso8601_no_frac() {
-        assert_eq!(
-            parse_iso8601_to_ms("2026-02-02T19:40:37Z"),
-            Some(1770061237000)
+    fn test_parse_span_events_and_links() {
+        let events = serde_json::json!([
+            "{\"name\":\"retry\",\"timeUnixNano\":1700000000200000000,\"attributeMap\":{\"attempt\":2}}",
+            "{\"name\":\"exception\",\"timeUnixNano\":1700000000100000000,\"attributeMap\":{\"exception.type\":\"IOError\",\"exception.message\":\"device busy\"}}",
+            "not json"
+        ]);
+        let events = parse_span_events(&events);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].timestamp_ms, 1700000000100);
+        assert_eq!(events[0].describe(), "IOError: device busy");
+        assert_eq!(events[1].attributes.get("attempt").unwrap(), "2");
+
+        let references = serde_json::json!(
+            "[{\"TraceId\":\"t1\",\"SpanId\":\"parent\",\"RefType\":\"CHILD_OF\"},{\"TraceId\":\"t2\",\"SpanId\":\"s2\",\"RefType\":\"FOLLOWS_FROM\"}]"
         );
-    }
-
-    #[test]
-    fn test_parse_iso8601_epoch() {
-        assert_eq!(parse_iso8601_to_ms("1970-01-01T00:00:00Z"), Some(0));
+        let links = parse_span_links(&references, Some("parent"));
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].trace_id, "t2");
+        assert_eq!(links[0].span_id, "s2");
+        assert_eq!(links[0].attributes.get("ref_type").unwrap(), "FOLLOWS_FROM");
     }
 
     #[test]
@@ -648,4 +693,120 @@ mod tests {
         assert_eq!(result.get("key1").unwrap(), "val1");
         assert_eq!(result.get("key3").unwrap(), "val3");
     }
+
+    // -----------------------------------------------------------------------
+    // Property tests: the parsers run on whatever SigNoz sends, so they must
+    // not panic on any input.
+    // -----------------------------------------------------------------------
+
+    /// Columns the parsers read, so generated rows hit the typed paths
+    /// rather than only unknown keys.
+    const COLUMNS: [&str; 15] = [
+        "traceID",
+        "spanID",
+        "parentSpanID",
+        "serviceName",
+        "name",
+        "timestamp",
+        "durationNano",
+        "statusCode",
+        "hasError",
+        "events",
+        "references",
+        "links",
+        "severity_text",
+        "body",
+        "service_name",
+    ];
+
+    /// Arbitrary JSON a few levels deep.
+    fn json_value() -> impl Strategy<Value = serde_json::Value> {
+        let leaf = prop_oneof![
+            Just(serde_json::Value::Null),
+            any::<bool>().prop_map(serde_json::Value::from),
+            any::<u64>().prop_map(serde_json::Value::from),
+            any::<i64>().prop_map(serde_json::Value::from),
+            any::<f64>().prop_map(serde_json::Value::from),
+            ".*".prop_map(serde_json::Value::from),
+        ];
+        leaf.prop_recursive(3, 32, 4, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..4).prop_map(serde_json::Value::from),
+                prop::collection::hash_map(".*", inner, 0..4)
+                    .prop_map(|m| serde_json::Value::Object(m.into_iter().collect())),
+            ]
+        })
+    }
+
+    /// A list row with known columns holding arbitrary values.
+    fn list_row() -> impl Strategy<Value = SigNozListRow> {
+        let column = prop_oneof![
+            prop::sample::select(COLUMNS.to_vec()).prop_map(String::from),
+            ".*"
+        ];
+        (
+            proptest::option::of(".*"),
+            prop::collection::hash_map(column, json_value(), 0..8),
+        )
+            .prop_map(|(timestamp, data)| SigNozListRow { timestamp, data })
+    }
+
+    fn list_response(rows: Vec<SigNozListRow>) -> SigNozResponse {
+        SigNozResponse {
+            status: "success".to_string(),
+            data: Some(SigNozResponseData {
+                result: vec![SigNozResultEntry {
+                    query_name: None,
+                    series: None,
+                    list: Some(rows),
+                }],
+                new_result: None,
+            }),
+            error: None,
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_list_rows_parse_one_to_one(rows in prop::collection::vec(list_row(), 0..6)) {
+            let count = rows.len();
+            let resp = list_response(rows);
+            prop_assert_eq!(SigNozBackend::parse_trace_results(&resp).len(), count);
+            prop_assert_eq!(SigNozBackend::parse_log_results(&resp).len(), count);
+        }
+
+        #[test]
+        fn prop_arbitrary_response_never_panics(
+            result in json_value(),
+            new_result in json_value(),
+        ) {
+            let body = serde_json::json!({
+                "status": "success",
+                "data": {
+                    "result": [result],
+                    "newResult": { "data": { "result": [new_result] } }
+                }
+            });
+            if let Ok(resp) = serde_json::from_value::<SigNozResponse>(body) {
+                SigNozBackend::parse_trace_results(&resp);
+                SigNozBackend::parse_log_results(&resp);
+                SigNozBackend::parse_metric_results(&resp);
+            }
+        }
+
+        #[test]
+        fn prop_parse_timestamp_units(ms in 1_000_000_000_001u64..4_000_000_000_000) {
+            let secs = ms / 1000;
+            prop_assert_eq!(parse_timestamp(&serde_json::json!(ms)), Some(ms));
+            prop_assert_eq!(parse_timestamp(&serde_json::json!(ms * 1_000_000)), Some(ms));
+            prop_assert_eq!(parse_timestamp(&serde_json::json!(secs)), Some(secs * 1000));
+            prop_assert_eq!(parse_timestamp(&serde_json::json!(ms.to_string())), Some(ms));
+        }
+
+        #[test]
+        fn prop_parse_timestamp_any_value(n in any::<u64>(), v in json_value()) {
+            prop_assert!(parse_timestamp(&serde_json::json!(n)).is_some());
+            parse_timestamp(&v);
+        }
+    }
 }
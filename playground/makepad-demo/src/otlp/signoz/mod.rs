@@ -3,3 +3,4 @@ pub mod query;
 pub mod response;
 
 pub use client::SigNozBackend;
+pub(crate) use client::{parse_span_events, parse_span_links};
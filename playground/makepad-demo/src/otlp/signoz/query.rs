@@ -1,7 +1,7 @@
 use crate::otlp::types::{LogQuery, MetricQuery, TimeRange, TraceQuery};
 
 /// Default time range: last 1 hour.
-fn default_time_range() -> TimeRange {
+pub(crate) fn default_time_range() -> TimeRange {
     let now_ms = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
@@ -60,6 +60,25 @@ pub fn build_trace_query(query: &TraceQuery) -> serde_json::Value {
         }));
     }
 
+    let mut columns = vec![
+        serde_json::json!({"key": "serviceName", "dataType": "string", "type": "tag", "isColumn": true}),
+        serde_json::json!({"key": "name", "dataType": "string", "type": "tag", "isColumn": true}),
+        serde_json::json!({"key": "durationNano", "dataType": "float64", "type": "tag", "isColumn": true}),
+        serde_json::json!({"key": "traceID", "dataType": "string", "type": "tag", "isColumn": true}),
+        serde_json::json!({"key": "spanID", "dataType": "string", "type": "tag", "isColumn": true}),
+        serde_json::json!({"key": "parentSpanID", "dataType": "string", "type": "tag", "isColumn": true}),
+        serde_json::json!({"key": "statusCode", "dataType": "int64", "type": "tag", "isColumn": true}),
+        serde_json::json!({"key": "hasError", "dataType": "bool", "type": "tag", "isColumn": true}),
+        serde_json::json!({"key": "events", "dataType": "array(string)", "type": "tag", "isColumn": true}),
+        serde_json::json!({"key": "references", "dataType": "string", "type": "tag", "isColumn": true}),
+        serde_json::json!({"key": "spanKind", "dataType": "string", "type": "tag", "isColumn": true}),
+    ];
+    for key in &query.attributes {
+        columns.push(serde_json::json!({
+            "key": key, "dataType": "string", "type": "tag", "isColumn": false
+        }));
+    }
+
     serde_json::json!({
         "start": tr.start_ms * 1_000_000,
         "end": tr.end_ms * 1_000_000,
@@ -80,16 +99,7 @@ pub fn build_trace_query(query: &TraceQuery) -> serde_json::Value {
                     "limit": limit,
                     "offset": offset,
                     "orderBy": [{"columnName": "timestamp", "order": "desc"}],
-                    "selectColumns": [
-                        {"key": "serviceName", "dataType": "string", "type": "tag", "isColumn": true},
-                        {"key": "name", "dataType": "string", "type": "tag", "isColumn": true},
-                        {"key": "durationNano", "dataType": "float64", "type": "tag", "isColumn": true},
-                        {"key": "traceID", "dataType": "string", "type": "tag", "isColumn": true},
-                        {"key": "spanID", "dataType": "string", "type": "tag", "isColumn": true},
-                        {"key": "parentSpanID", "dataType": "string", "type": "tag", "isColumn": true},
-                        {"key": "statusCode", "dataType": "int64", "type": "tag", "isColumn": true},
-                        {"key": "hasError", "dataType": "bool", "type": "tag", "isColumn": true}
-                    ]
+                    "selectColumns": columns
                 }
             }
         }
@@ -302,6 +312,20 @@ mod tests {
         assert_eq!(filters[0]["value"], "POST");
     }
 
+    #[test]
+    fn test_build_trace_query_selects_attributes() {
+        let query = TraceQuery {
+            attributes: vec!["node.id".to_string()],
+            ..Default::default()
+        };
+
+        let payload = build_trace_query(&query);
+        let columns = &payload["compositeQuery"]["builderQueries"]["A"]["selectColumns"];
+        let last = columns.as_array().unwrap().last().unwrap();
+        assert_eq!(last["key"], "node.id");
+        assert_eq!(last["isColumn"], false);
+    }
+
     #[test]
     fn test_build_log_query_minimal() {
         let query = LogQuery::default();
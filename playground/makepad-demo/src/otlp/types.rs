@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::storage::Record;
+
 /// A time range specified in milliseconds since epoch.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TimeRange {
     pub start_ms: u64,
     pub end_ms: u64,
@@ -21,6 +24,64 @@ pub struct Span {
     pub status_code: i32,
     pub has_error: bool,
     pub attributes: HashMap<String, String>,
+    /// Events recorded during the span, in time order.
+    #[serde(default)]
+    pub events: Vec<SpanEvent>,
+    /// Spans this span is causally related to outside its parent.
+    #[serde(default)]
+    pub links: Vec<SpanLink>,
+}
+
+impl Span {
+    /// Exceptions recorded on the span as `exception` events.
+    pub fn exceptions(&self) -> impl Iterator<Item = &SpanEvent> {
+        self.events.iter().filter(|e| e.is_exception())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Record for Span {
+    fn timestamp_ms(&self) -> u64 {
+        self.start_time_ms
+    }
+}
+
+/// A timestamped event recorded on a span, such as an exception.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpanEvent {
+    pub name: String,
+    pub timestamp_ms: u64,
+    #[serde(default)]
+    pub attributes: HashMap<String, String>,
+}
+
+impl SpanEvent {
+    pub fn is_exception(&self) -> bool {
+        self.name == "exception"
+    }
+
+    /// `exception.type: exception.message` for exceptions, otherwise the
+    /// event name.
+    pub fn describe(&self) -> String {
+        if !self.is_exception() {
+            return self.name.clone();
+        }
+        let attr = |key: &str| self.attributes.get(key).filter(|v| !v.is_empty());
+        match (attr("exception.type"), attr("exception.message")) {
+            (Some(kind), Some(message)) => format!("{}: {}", kind, message),
+            (Some(text), None) | (None, Some(text)) => text.clone(),
+            (None, None) => self.name.clone(),
+        }
+    }
+}
+
+/// A link from a span to another span, possibly in another trace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpanLink {
+    pub trace_id: String,
+    pub span_id: String,
+    #[serde(default)]
+    pub attributes: HashMap<String, String>,
 }
 
 /// A single log entry.
@@ -33,6 +94,13 @@ pub struct LogEntry {
     pub attributes: HashMap<String, String>,
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl Record for LogEntry {
+    fn timestamp_ms(&self) -> u64 {
+        self.timestamp_ms
+    }
+}
+
 /// A single point in a metric time series.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricPoint {
@@ -57,7 +125,7 @@ pub struct ServiceInfo {
 }
 
 /// Query parameters for trace queries.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct TraceQuery {
     pub service_name: Option<String>,
     pub operation_name: Option<String>,
@@ -67,6 +135,10 @@ pub struct TraceQuery {
     pub limit: Option<u32>,
     pub offset: Option<u32>,
     pub tags: HashMap<String, String>,
+    /// Span attributes to return along with the fixed columns, e.g.
+    /// `node.id`; they end up in [`Span::attributes`].
+    #[serde(default)]
+    pub attributes: Vec<String>,
 }
 
 /// Query parameters for metric queries.
@@ -143,6 +215,15 @@ mod tests {
             status_code: 0,
             has_error: false,
             attributes: HashMap::from([("http.method".to_string(), "GET".to_string())]),
+            events: vec![SpanEvent {
+                name: "exception".to_string(),
+                timestamp_ms: 1700000000100,
+                attributes: HashMap::from([
+                    ("exception.type".to_string(), "IOError".to_string()),
+                    ("exception.message".to_string(), "device busy".to_string()),
+                ]),
+            }],
+            links: Vec::new(),
         };
 
         let json = serde_json::to_string(&span).unwrap();
@@ -150,6 +231,20 @@ mod tests {
         assert_eq!(deserialized.trace_id, "abc123");
         assert_eq!(deserialized.duration_ms, 150);
         assert_eq!(deserialized.attributes.get("http.method").unwrap(), "GET");
+        assert_eq!(deserialized.events, span.events);
+        assert_eq!(
+            deserialized.exceptions().next().unwrap().describe(),
+            "IOError: device busy"
+        );
+    }
+
+    #[test]
+    fn test_span_without_events_deserializes() {
+        // Spans stored before events and links were recorded
+        let json = r#"{"trace_id":"t","span_id":"s","parent_span_id":null,"service_name":"svc","operation_name":"op","start_time_ms":1,"duration_ms":2,"status_code":0,"has_error":false,"attributes":{}}"#;
+        let span: Span = serde_json::from_str(json).unwrap();
+        assert!(span.events.is_empty());
+        assert!(span.links.is_empty());
     }
 
     #[test]
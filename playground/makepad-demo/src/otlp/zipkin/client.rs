@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::otlp::backend::{Capabilities, TelemetryBackend};
+use crate::otlp::config::ZipkinConfig;
+use crate::otlp::error::OtlpError;
+use crate::otlp::types::*;
+
+use super::query::build_trace_params;
+
+/// A backend querying a Zipkin server's v2 API for traces. Zipkin holds no
+/// logs or metrics.
+pub struct ZipkinBackend {
+    config: ZipkinConfig,
+    client: reqwest::Client,
+}
+
+impl ZipkinBackend {
+    /// Create a new `ZipkinBackend` from configuration.
+    pub fn new(config: ZipkinConfig) -> Result<Self, OtlpError> {
+        if config.url.is_empty() {
+            return Err(OtlpError::ConnectionFailed(
+                "url must not be empty".to_string(),
+            ));
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(config.timeout_secs))
+            .build()
+            .map_err(|e| {
+                OtlpError::ConnectionFailed(format!("failed to build HTTP client: {}", e))
+            })?;
+
+        Ok(Self { config, client })
+    }
+
+    /// Build the full URL for a given path.
+    fn url(&self, path: &str) -> String {
+        let base = self.config.url.trim_end_matches('/');
+        format!("{}{}", base, path)
+    }
+
+    /// GET `path` with `params` and parse the JSON response.
+    async fn get(&self, path: &str, params: &[(String, String)]) -> Result<Value, OtlpError> {
+        let resp = self.client.get(self.url(path)).query(params).send().await?;
+        let status = resp.status();
+
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(OtlpError::AuthenticationFailed(format!(
+                "HTTP {}",
+                status.as_u16()
+            )));
+        }
+
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(OtlpError::ApiError {
+                status: status.as_u16(),
+                message: text.trim().to_string(),
+            });
+        }
+
+        let text = resp.text().await?;
+        serde_json::from_str(&text).map_err(OtlpError::from)
+    }
+}
+
+impl TelemetryBackend for ZipkinBackend {
+    async fn health_check(&self) -> Result<(), OtlpError> {
+        // `/health` only exists on the Zipkin server itself, not on other
+        // stores serving its API, so check the API
+        self.get("/api/v2/services", &[]).await.map(|_| ())
+    }
+
+    async fn list_services(&self) -> Result<Vec<ServiceInfo>, OtlpError> {
+        let names = string_list(&self.get("/api/v2/services", &[]).await?);
+        let mut services = Vec::with_capacity(names.len());
+        for name in names {
+            let params = [("serviceName".to_string(), name.clone())];
+            let spans = self.get("/api/v2/spans", &params).await?;
+            services.push(ServiceInfo {
+                num_operations: string_list(&spans).len() as u64,
+                name,
+            });
+        }
+        services.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(services)
+    }
+
+    async fn query_traces(&self, query: &TraceQuery) -> Result<QueryResult<Span>, OtlpError> {
+        let resp = self
+            .get("/api/v2/traces", &build_trace_params(query))
+            .await?;
+        Ok(select_spans(parse_traces(&resp), query))
+    }
+
+    async fn query_metrics(
+        &self,
+        _query: &MetricQuery,
+    ) -> Result<QueryResult<MetricSeries>, OtlpError> {
+        Err(OtlpError::InvalidQuery(
+            "metrics are not supported by the Zipkin backend".to_string(),
+        ))
+    }
+
+    async fn query_logs(&self, _query: &LogQuery) -> Result<QueryResult<LogEntry>, OtlpError> {
+        Err(OtlpError::InvalidQuery(
+            "logs are not supported by the Zipkin backend".to_string(),
+        ))
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            traces: true,
+            logs: false,
+            metrics: false,
+            services: true,
+        }
+    }
+
+    fn display_name(&self) -> String {
+        format!("Zipkin @ {}", self.config.url)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn string_list(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect()
+}
+
+/// Spans of a `/api/v2/traces` response, a list of traces each being a
+/// list of spans.
+fn parse_traces(resp: &Value) -> Vec<Span> {
+    resp.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_array)
+        .flatten()
+        .map(span_from_zipkin)
+        .collect()
+}
+
+/// The spans of the matched traces that match `query` themselves, newest
+/// first, paged by `offset` and `limit`.
+fn select_spans(mut spans: Vec<Span>, query: &TraceQuery) -> QueryResult<Span> {
+    spans.retain(|span| {
+        query
+            .service_name
+            .as_ref()
+            .is_none_or(|svc| &span.service_name == svc)
+            && query
+                .operation_name
+                .as_ref()
+                .is_none_or(|op| &span.operation_name == op)
+            && query
+                .min_duration_ms
+                .is_none_or(|ms| span.duration_ms >= ms)
+            && query
+                .max_duration_ms
+                .is_none_or(|ms| span.duration_ms <= ms)
+            && query
+                .tags
+                .iter()
+                .all(|(k, v)| span.attributes.get(k) == Some(v))
+    });
+    spans.sort_by(|a, b| b.start_time_ms.cmp(&a.start_time_ms));
+    let total = spans.len() as u64;
+    let items = spans
+        .into_iter()
+        .skip(query.offset.unwrap_or(0) as usize)
+        .take(query.limit.unwrap_or(100) as usize)
+        .collect();
+    QueryResult {
+        items,
+        total: Some(total),
+    }
+}
+
+fn span_from_zipkin(value: &Value) -> Span {
+    let str_of = |key: &str| value[key].as_str().unwrap_or_default().to_string();
+    let mut attributes: HashMap<String, String> = value["tags"]
+        .as_object()
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(kind) = value["kind"].as_str() {
+        attributes.insert("span.kind".to_string(), kind.to_lowercase());
+    }
+    // Zipkin marks failed spans with an `error` tag, holding the message
+    let has_error = attributes.contains_key("error");
+    let events = value["annotations"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|a| SpanEvent {
+            name: a["value"].as_str().unwrap_or_default().to_string(),
+            timestamp_ms: a["timestamp"].as_u64().unwrap_or(0) / 1000,
+            attributes: HashMap::new(),
+        })
+        .collect();
+    Span {
+        trace_id: str_of("traceId"),
+        span_id: str_of("id"),
+        parent_span_id: value["parentId"].as_str().map(String::from),
+        service_name: value["localEndpoint"]["serviceName"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        operation_name: str_of("name"),
+        start_time_ms: value["timestamp"].as_u64().unwrap_or(0) / 1000,
+        duration_ms: value["duration"].as_u64().unwrap_or(0) / 1000,
+        status_code: if has_error { 2 } else { 0 },
+        has_error,
+        attributes,
+        events,
+        links: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACES: &str = r#"[[
+        {"traceId": "t1", "id": "s1", "name": "run", "kind": "SERVER",
+         "timestamp": 1700000000000000, "duration": 50000,
+         "localEndpoint": {"serviceName": "dataflow"}},
+        {"traceId": "t1", "parentId": "s1", "id": "s2", "name": "capture",
+         "timestamp": 1700000000010000, "duration": 12500,
+         "localEndpoint": {"serviceName": "camera"},
+         "annotations": [{"timestamp": 1700000000015000, "value": "frame dropped"}],
+         "tags": {"node.id": "camera", "error": "no device"}}
+    ]]"#;
+
+    #[test]
+    fn test_new_zipkin_backend() {
+        let config = |url: &str| ZipkinConfig {
+            url: url.to_string(),
+            timeout_secs: 30,
+        };
+        assert!(ZipkinBackend::new(config("")).is_err());
+        let backend = ZipkinBackend::new(config("http://localhost:9411/")).unwrap();
+        assert_eq!(
+            backend.url("/api/v2/services"),
+            "http://localhost:9411/api/v2/services"
+        );
+        assert_eq!(backend.display_name(), "Zipkin @ http://localhost:9411/");
+    }
+
+    #[test]
+    fn test_parse_traces() {
+        let spans = parse_traces(&serde_json::from_str(TRACES).unwrap());
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].parent_span_id, None);
+        assert_eq!(spans[0].attributes["span.kind"], "server");
+        assert!(!spans[0].has_error);
+
+        let capture = &spans[1];
+        assert_eq!(capture.parent_span_id.as_deref(), Some("s1"));
+        assert_eq!(capture.service_name, "camera");
+        assert_eq!(capture.start_time_ms, 1_700_000_000_010);
+        assert_eq!(capture.duration_ms, 12);
+        assert!(capture.has_error);
+        assert_eq!(capture.status_code, 2);
+        assert_eq!(capture.events[0].name, "frame dropped");
+        assert_eq!(capture.events[0].timestamp_ms, 1_700_000_000_015);
+    }
+
+    #[test]
+    fn test_select_spans() {
+        let spans = || parse_traces(&serde_json::from_str(TRACES).unwrap());
+        let query = TraceQuery {
+            service_name: Some("camera".to_string()),
+            ..Default::default()
+        };
+        let result = select_spans(spans(), &query);
+        assert_eq!(result.total, Some(1));
+        assert_eq!(result.items[0].span_id, "s2");
+
+        // Newest first, then paged
+        let query = TraceQuery {
+            limit: Some(1),
+            offset: Some(1),
+            ..Default::default()
+        };
+        let result = select_spans(spans(), &query);
+        assert_eq!(result.total, Some(2));
+        assert_eq!(result.items.len(), 1);
+        assert_eq!(result.items[0].span_id, "s1");
+    }
+}
@@ -0,0 +1,97 @@
+//! Query parameters for Zipkin's `/api/v2/traces`.
+//!
+//! Zipkin selects whole traces: a trace matches when any of its spans
+//! does, and `limit` counts traces. Durations are in microseconds and the
+//! window is given as `endTs` plus a `lookback`, both in milliseconds.
+
+use crate::otlp::signoz::query::default_time_range;
+use crate::otlp::types::TraceQuery;
+
+/// URL parameters for traces matching `query`, asking for enough traces to
+/// fill `limit + offset` spans.
+pub fn build_trace_params(query: &TraceQuery) -> Vec<(String, String)> {
+    let tr = query.time_range.clone().unwrap_or_else(default_time_range);
+    let mut params = vec![
+        ("endTs".to_string(), tr.end_ms.to_string()),
+        (
+            "lookback".to_string(),
+            tr.end_ms.saturating_sub(tr.start_ms).to_string(),
+        ),
+    ];
+    if let Some(ref svc) = query.service_name {
+        params.push(("serviceName".to_string(), svc.clone()));
+    }
+    if let Some(ref op) = query.operation_name {
+        params.push(("spanName".to_string(), op.clone()));
+    }
+    if let Some(ms) = query.min_duration_ms {
+        params.push(("minDuration".to_string(), (ms * 1000).to_string()));
+    }
+    if let Some(ms) = query.max_duration_ms {
+        params.push(("maxDuration".to_string(), (ms * 1000).to_string()));
+    }
+    if let Some(annotations) = annotation_query(query) {
+        params.push(("annotationQuery".to_string(), annotations));
+    }
+    let limit = query.limit.unwrap_or(100) + query.offset.unwrap_or(0);
+    params.push(("limit".to_string(), limit.to_string()));
+    params
+}
+
+/// Tags as an annotation query, e.g. `node.id=camera and error=true`, in
+/// key order so the same query builds the same URL.
+fn annotation_query(query: &TraceQuery) -> Option<String> {
+    let mut tags: Vec<_> = query.tags.iter().collect();
+    if tags.is_empty() {
+        return None;
+    }
+    tags.sort();
+    let terms: Vec<String> = tags
+        .into_iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect();
+    Some(terms.join(" and "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::otlp::types::TimeRange;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_build_trace_params() {
+        let query = TraceQuery {
+            service_name: Some("camera".to_string()),
+            min_duration_ms: Some(5),
+            time_range: Some(TimeRange {
+                start_ms: 1_000,
+                end_ms: 61_000,
+            }),
+            limit: Some(20),
+            offset: Some(10),
+            tags: HashMap::from([
+                ("node.id".to_string(), "camera".to_string()),
+                ("error".to_string(), "true".to_string()),
+            ]),
+            ..Default::default()
+        };
+        let params = build_trace_params(&query);
+        let get = |name: &str| {
+            params
+                .iter()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(get("endTs"), Some("61000"));
+        assert_eq!(get("lookback"), Some("60000"));
+        assert_eq!(get("serviceName"), Some("camera"));
+        assert_eq!(get("spanName"), None);
+        assert_eq!(get("minDuration"), Some("5000"));
+        assert_eq!(
+            get("annotationQuery"),
+            Some("error=true and node.id=camera")
+        );
+        assert_eq!(get("limit"), Some("30"));
+    }
+}
@@ -0,0 +1,286 @@
+use makepad_widgets::*;
+use std::cell::RefMut;
+
+use super::commands::{filter, Command, PaletteEntry};
+use crate::i18n;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    // Colors (reused from diagnostics_panel)
+    ROW_BG = #ffffff
+    ROW_SELECTED_BG = #dbeafe
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+    PALETTE_BORDER = #cbd5e1
+
+    PaletteRow = <View> {
+        width: Fill, height: 36
+        flow: Right
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+        padding: { left: 4, right: 12 }
+        align: { y: 0.5 }
+        spacing: 8
+
+        run_button = <Button> {
+            width: Fill, height: 30
+            align: { x: 0.0, y: 0.5 }
+            draw_bg: {
+                fn pixel(self) -> vec4 {
+                    return vec4(0.0, 0.0, 0.0, 0.0);
+                }
+            }
+            draw_text: {
+                color: (TEXT_PRIMARY),
+                text_style: { font_size: 12.0 }
+            }
+        }
+        detail_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 10.0 }
+            }
+        }
+    }
+
+    PaletteRowSelected = <PaletteRow> {
+        draw_bg: { color: (ROW_SELECTED_BG) }
+    }
+
+    PaletteEmpty = <View> {
+        width: Fill, height: 48
+        align: { x: 0.5, y: 0.5 }
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+
+        empty_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 12.0 }
+            }
+            text: "No matching commands"
+        }
+    }
+
+    pub CommandPalette = {{CommandPalette}} {
+        width: 520, height: Fit
+        flow: Down
+        show_bg: true
+        draw_bg: { color: (PALETTE_BORDER) }
+        padding: 1
+
+        <View> {
+            width: Fill, height: Fit
+            show_bg: true
+            draw_bg: { color: (ROW_BG) }
+            padding: 8
+
+            query_input = <TextInput> {
+                width: Fill, height: 32
+                empty_text: "Type a command…"
+                draw_text: { text_style: { font_size: 12.0 } }
+            }
+        }
+
+        command_list = <PortalList> {
+            width: Fill, height: 288
+            flow: Down
+
+            PaletteRow = <PaletteRow> {}
+            PaletteRowSelected = <PaletteRowSelected> {}
+            PaletteEmpty = <PaletteEmpty> {}
+        }
+    }
+}
+
+/// Actions emitted by the CommandPalette
+#[derive(Clone, Debug, DefaultNone)]
+pub enum CommandPaletteAction {
+    None,
+    Run(Command),
+    Dismissed,
+}
+
+/// Searchable list of app commands, opened with Ctrl+K.
+#[derive(Live, LiveHook, Widget)]
+pub struct CommandPalette {
+    #[deref]
+    view: View,
+    #[rust]
+    entries: Vec<PaletteEntry>,
+    /// Entries matching the current query, best match first.
+    #[rust]
+    filtered: Vec<PaletteEntry>,
+    #[rust]
+    selected: usize,
+}
+
+impl Widget for CommandPalette {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if !self.view.visible() {
+            return;
+        }
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+
+        if let Event::KeyDown(ke) = event {
+            match ke.key_code {
+                KeyCode::ArrowDown => self.move_selection(cx, 1),
+                KeyCode::ArrowUp => self.move_selection(cx, -1),
+                KeyCode::Escape => {
+                    cx.widget_action(
+                        self.widget_uid(),
+                        &scope.path,
+                        CommandPaletteAction::Dismissed,
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                self.draw_rows(cx, &mut list);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl WidgetMatchEvent for CommandPalette {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope) {
+        let query_input = self.view.text_input(ids!(query_input));
+        if let Some(query) = query_input.changed(actions) {
+            self.filtered = filter(&self.entries, &query);
+            self.selected = 0;
+            self.redraw_list(cx);
+        }
+
+        let mut chosen = query_input
+            .returned(actions)
+            .and_then(|_| self.filtered.get(self.selected))
+            .map(|entry| entry.command.clone());
+
+        let command_list = self.view.portal_list(ids!(command_list));
+        for (item_id, item) in command_list.items_with_actions(actions) {
+            if item_id < self.filtered.len() && item.button(ids!(run_button)).clicked(actions) {
+                chosen = Some(self.filtered[item_id].command.clone());
+            }
+        }
+
+        if let Some(command) = chosen {
+            cx.widget_action(
+                self.widget_uid(),
+                &scope.path,
+                CommandPaletteAction::Run(command),
+            );
+        }
+    }
+}
+
+impl CommandPalette {
+    /// Show the palette with `entries`, clearing the previous query.
+    pub fn open(&mut self, cx: &mut Cx, entries: Vec<PaletteEntry>) {
+        self.filtered = entries.clone();
+        self.entries = entries;
+        self.selected = 0;
+        self.view.set_visible(cx, true);
+        let query_input = self.view.text_input(ids!(query_input));
+        query_input.set_text(cx, "");
+        query_input.set_key_focus(cx);
+        self.redraw_list(cx);
+    }
+
+    pub fn close(&mut self, cx: &mut Cx) {
+        self.view.set_visible(cx, false);
+        self.redraw(cx);
+    }
+
+    fn move_selection(&mut self, cx: &mut Cx, delta: isize) {
+        if self.filtered.is_empty() {
+            return;
+        }
+        let last = self.filtered.len() - 1;
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+        self.redraw_list(cx);
+    }
+
+    fn redraw_list(&mut self, cx: &mut Cx) {
+        self.view.portal_list(ids!(command_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    fn draw_rows(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        if self.filtered.is_empty() {
+            list.set_item_range(cx, 0, 1);
+            while let Some(item_id) = list.next_visible_item(cx) {
+                if item_id == 0 {
+                    let item = list.item(cx, item_id, live_id!(PaletteEmpty));
+                    item.label(ids!(empty_label))
+                        .set_text(cx, &i18n::tr("palette.no_match"));
+                    item.draw_all(cx, &mut Scope::empty());
+                }
+            }
+            return;
+        }
+
+        list.set_item_range(cx, 0, self.filtered.len());
+
+        while let Some(item_id) = list.next_visible_item(cx) {
+            if item_id < self.filtered.len() {
+                let entry = &self.filtered[item_id];
+
+                let template = if item_id == self.selected {
+                    live_id!(PaletteRowSelected)
+                } else {
+                    live_id!(PaletteRow)
+                };
+
+                let item = list.item(cx, item_id, template);
+
+                item.button(ids!(run_button)).set_text(cx, &entry.label);
+                item.label(ids!(detail_label)).set_text(cx, &entry.detail);
+
+                item.draw_all(cx, &mut Scope::empty());
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Ref wrapper (same pattern as DataflowTableRef)
+// ---------------------------------------------------------------------------
+
+impl CommandPaletteRef {
+    pub fn open(&self, cx: &mut Cx, entries: Vec<PaletteEntry>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.open(cx, entries);
+        }
+    }
+
+    pub fn close(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.close(cx);
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.borrow().is_some_and(|inner| inner.view.visible())
+    }
+
+    /// Check if a CommandPaletteAction was triggered
+    pub fn action(&self, actions: &Actions) -> Option<CommandPaletteAction> {
+        if let Some(item) = actions.find_widget_action(self.widget_uid()) {
+            item.cast()
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Palette commands and query matching.
+
+use crate::i18n;
+
+/// An app action that can be run from the command palette.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    RefreshDataflows,
+    ShowDataflows,
+    ShowTraces,
+    ShowAlerts,
+    ShowDiagnostics,
+    ShowQuery,
+    /// Focus the dataflow path input.
+    StartDataflow,
+    /// Scaffold a project with `dora new`.
+    NewProject,
+    /// Start a recently used dataflow YAML.
+    StartRecent(String),
+    /// Stop a running dataflow by UUID.
+    StopDataflow(String),
+    /// Settings live in the diagnostics panel.
+    OpenSettings,
+    ToggleNotifications,
+    /// Show or hide the debug console.
+    ToggleDebugConsole,
+    /// Apply a layout preset by name.
+    ApplyLayout(String),
+}
+
+/// A palette row: what the user searches and sees, and what it runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteEntry {
+    pub label: String,
+    /// Secondary text, e.g. the dataflow UUID or YAML path.
+    pub detail: String,
+    pub command: Command,
+}
+
+impl PaletteEntry {
+    pub fn new(label: &str, command: Command) -> Self {
+        Self {
+            label: label.to_string(),
+            detail: String::new(),
+            command,
+        }
+    }
+
+    pub fn with_detail(mut self, detail: &str) -> Self {
+        self.detail = detail.to_string();
+        self
+    }
+}
+
+/// Commands that are always available.
+pub fn static_entries() -> Vec<PaletteEntry> {
+    vec![
+        PaletteEntry::new(&i18n::tr("palette.refresh"), Command::RefreshDataflows),
+        PaletteEntry::new(&i18n::tr("palette.show_dataflows"), Command::ShowDataflows),
+        PaletteEntry::new(&i18n::tr("palette.show_traces"), Command::ShowTraces),
+        PaletteEntry::new(&i18n::tr("palette.show_alerts"), Command::ShowAlerts),
+        PaletteEntry::new(
+            &i18n::tr("palette.show_diagnostics"),
+            Command::ShowDiagnostics,
+        ),
+        PaletteEntry::new(&i18n::tr("palette.show_query"), Command::ShowQuery),
+        PaletteEntry::new(&i18n::tr("palette.start_dataflow"), Command::StartDataflow),
+        PaletteEntry::new(&i18n::tr("palette.new_project"), Command::NewProject),
+        PaletteEntry::new(&i18n::tr("palette.open_settings"), Command::OpenSettings),
+        PaletteEntry::new(
+            &i18n::tr("palette.toggle_notifications"),
+            Command::ToggleNotifications,
+        ),
+        PaletteEntry::new(
+            &i18n::tr("palette.toggle_debug_console"),
+            Command::ToggleDebugConsole,
+        ),
+    ]
+}
+
+/// Entries matching `query`, best match first. An empty query keeps all
+/// entries in their original order.
+pub fn filter(entries: &[PaletteEntry], query: &str) -> Vec<PaletteEntry> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return entries.to_vec();
+    }
+    let mut scored: Vec<(u32, &PaletteEntry)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let label = match_score(&entry.label.to_lowercase(), &query);
+            let detail = match_score(&entry.detail.to_lowercase(), &query).map(|s| s / 2);
+            label.max(detail).map(|score| (score, entry))
+        })
+        .collect();
+    // Stable sort keeps the original order between equal scores
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+    scored.into_iter().map(|(_, entry)| entry.clone()).collect()
+}
+
+/// Score `query` as a subsequence of `text`, or `None` if it does not match.
+///
+/// Substring matches rank above scattered ones, and matches at the start of
+/// the text or of a word rank higher still.
+fn match_score(text: &str, query: &str) -> Option<u32> {
+    if let Some(pos) = text.find(query) {
+        let at_word = pos == 0 || !text[..pos].ends_with(char::is_alphanumeric);
+        return Some(if pos == 0 {
+            300
+        } else if at_word {
+            200
+        } else {
+            100
+        });
+    }
+
+    let mut chars = text.chars();
+    for q in query.chars() {
+        chars.find(|&c| c == q)?;
+    }
+    Some(10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(entries: &[PaletteEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_empty_query_keeps_order() {
+        let entries = static_entries();
+        assert_eq!(filter(&entries, "  "), entries);
+    }
+
+    #[test]
+    fn test_filter_ranks_prefix_and_word_matches() {
+        let entries = vec![
+            PaletteEntry::new("Switch to Traces", Command::ShowTraces),
+            PaletteEntry::new("Stop camera", Command::StopDataflow("a".to_string())),
+            PaletteEntry::new("Start dataflow…", Command::StartDataflow),
+        ];
+        assert_eq!(
+            labels(&filter(&entries, "st")),
+            vec!["Stop camera", "Start dataflow…", "Switch to Traces"]
+        );
+        assert_eq!(
+            labels(&filter(&entries, "traces")),
+            vec!["Switch to Traces"]
+        );
+        assert_eq!(labels(&filter(&entries, "swtr")), vec!["Switch to Traces"]);
+        assert!(filter(&entries, "xyz").is_empty());
+    }
+
+    #[test]
+    fn test_filter_matches_detail() {
+        let entries =
+            vec![
+                PaletteEntry::new("Stop camera", Command::StopDataflow("a".to_string()))
+                    .with_detail("0f3c9a12"),
+            ];
+        assert_eq!(labels(&filter(&entries, "0f3c")), vec!["Stop camera"]);
+    }
+}
@@ -0,0 +1,20 @@
+//! Command palette (Ctrl+K).
+//!
+//! The palette lists app actions as [`PaletteEntry`] values built by the app
+//! (static commands plus recent dataflows and running dataflows to stop) and
+//! emits the chosen [`Command`]; the app dispatches it to the same handlers
+//! its buttons use.
+
+pub mod command_palette;
+pub mod commands;
+
+pub use command_palette::{
+    CommandPalette, CommandPaletteAction, CommandPaletteRef, CommandPaletteWidgetRefExt,
+};
+pub use commands::{filter, static_entries, Command, PaletteEntry};
+
+use makepad_widgets::*;
+
+pub fn live_design(cx: &mut Cx) {
+    command_palette::live_design(cx);
+}
@@ -0,0 +1,15 @@
+//! SQL console over the local telemetry tables.
+//!
+//! The panel collects SQL and shows results; queries run against
+//! [`crate::storage`] through [`crate::storage::run_query`] on a background
+//! task, and past queries are kept in settings.
+
+pub mod query_panel;
+
+pub use query_panel::{QueryPanel, QueryPanelRef, QueryPanelWidgetRefExt};
+
+use makepad_widgets::*;
+
+pub fn live_design(cx: &mut Cx) {
+    query_panel::live_design(cx);
+}
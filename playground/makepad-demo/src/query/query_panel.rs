@@ -0,0 +1,382 @@
+use makepad_widgets::*;
+use std::cell::RefMut;
+
+use crate::i18n;
+use crate::storage::query::cell_text;
+use crate::storage::QueryResult;
+use crate::text::truncate;
+
+/// Columns shown in the results table; further columns are only exported.
+pub const MAX_COLUMNS: usize = 8;
+/// Characters of a cell shown before it is cut off.
+const CELL_CHARS: usize = 40;
+/// Characters of a query shown in the history dropdown.
+const HISTORY_CHARS: usize = 60;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    // Colors (reused from alerts_panel)
+    ROW_BG = #ffffff
+    ROW_ALT_BG = #f8fafc
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+
+    QueryToolbar = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        spacing: 4
+        padding: { bottom: 8 }
+
+        <View> {
+            width: Fill, height: 40
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 8
+
+            query_input = <TextInput> {
+                width: Fill, height: 28
+                empty_text: "SELECT node_id, avg(cpu_percent) FROM metrics GROUP BY node_id"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            run_query_button = <Button> {
+                width: 70, height: 28
+                text: "Run"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            history_dropdown = <DropDown> {
+                width: 160, height: 28
+                labels: ["History"]
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+
+        <View> {
+            width: Fill, height: 36
+            flow: Right
+            align: { y: 0.5 }
+            spacing: 8
+
+            query_status_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    wrap: Word,
+                    text_style: { font_size: 11.0 }
+                }
+                text: "Tables: metrics, logs, spans, messages"
+            }
+
+            export_path_input = <TextInput> {
+                width: 220, height: 28
+                empty_text: "query-results.csv"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            export_csv_button = <Button> {
+                width: 90, height: 28
+                text: "Export CSV"
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+        }
+    }
+
+    Cell = <Label> {
+        width: 140, height: Fit
+        draw_text: {
+            color: (TEXT_PRIMARY),
+            text_style: { font_size: 11.0 }
+        }
+    }
+
+    HeaderCell = <Cell> {
+        draw_text: { color: (TEXT_SECONDARY) }
+    }
+
+    ResultRow = <View> {
+        width: Fill, height: 32
+        flow: Right
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+        padding: { left: 16, right: 16 }
+        align: { y: 0.5 }
+        spacing: 8
+
+        cell_0 = <Cell> {}
+        cell_1 = <Cell> {}
+        cell_2 = <Cell> {}
+        cell_3 = <Cell> {}
+        cell_4 = <Cell> {}
+        cell_5 = <Cell> {}
+        cell_6 = <Cell> {}
+        cell_7 = <Cell> {}
+    }
+
+    ResultRowAlt = <ResultRow> {
+        draw_bg: { color: (ROW_ALT_BG) }
+    }
+
+    QueryEmpty = <View> {
+        width: Fill, height: 60
+        align: { x: 0.5, y: 0.5 }
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+
+        empty_label = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (TEXT_SECONDARY),
+                text_style: { font_size: 12.0 }
+            }
+        }
+    }
+
+    pub QueryPanel = {{QueryPanel}} {
+        width: Fill, height: Fill
+        flow: Down
+
+        <QueryToolbar> {}
+
+        header_row = <View> {
+            width: Fill, height: 32
+            flow: Right
+            show_bg: true
+            draw_bg: { color: #f1f5f9 }
+            padding: { left: 16, right: 16 }
+            align: { y: 0.5 }
+            spacing: 8
+
+            cell_0 = <HeaderCell> {}
+            cell_1 = <HeaderCell> {}
+            cell_2 = <HeaderCell> {}
+            cell_3 = <HeaderCell> {}
+            cell_4 = <HeaderCell> {}
+            cell_5 = <HeaderCell> {}
+            cell_6 = <HeaderCell> {}
+            cell_7 = <HeaderCell> {}
+        }
+
+        result_list = <PortalList> {
+            width: Fill, height: Fill
+            flow: Down
+
+            ResultRow = <ResultRow> {}
+            ResultRowAlt = <ResultRowAlt> {}
+            QueryEmpty = <QueryEmpty> {}
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct QueryPanel {
+    #[deref]
+    view: View,
+    #[rust]
+    result: QueryResult,
+    /// Queries offered in the history dropdown, most recent first.
+    #[rust]
+    history: Vec<String>,
+}
+
+impl Widget for QueryPanel {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            if let Some(mut list) = item.as_portal_list().borrow_mut() {
+                self.draw_rows(cx, &mut list);
+            }
+        }
+        DrawStep::done()
+    }
+}
+
+impl QueryPanel {
+    pub fn set_result(&mut self, cx: &mut Cx, result: QueryResult) {
+        let header = self.view.view(ids!(header_row));
+        for (i, cell) in cell_ids().into_iter().enumerate() {
+            let label = header.label(&[&[cell]]);
+            label.set_text(cx, result.columns.get(i).map_or("", |c| c.as_str()));
+            label.set_visible(cx, i < result.columns.len());
+        }
+        self.result = result;
+        self.view.portal_list(ids!(result_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    /// Show the text set in `live_design` in the current language
+    pub fn localize(&mut self, cx: &mut Cx) {
+        i18n::localize(
+            cx,
+            &[
+                (ids!(run_query_button), "action.run"),
+                (ids!(query_status_label), "query.hint"),
+                (ids!(export_csv_button), "query.export_csv"),
+            ],
+            |path| self.view.widget(path),
+        );
+        let history = std::mem::take(&mut self.history);
+        self.set_history(cx, &history);
+        self.view.portal_list(ids!(result_list)).redraw(cx);
+    }
+
+    pub fn set_history(&mut self, cx: &mut Cx, history: &[String]) {
+        self.history = history.to_vec();
+        let mut labels = vec![i18n::tr("query.history")];
+        labels.extend(history.iter().map(|q| truncate(q, HISTORY_CHARS)));
+        let dropdown = self.view.drop_down(ids!(history_dropdown));
+        dropdown.set_labels(cx, labels);
+        dropdown.set_selected_item(cx, 0);
+    }
+
+    fn draw_rows(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        if self.result.rows.is_empty() {
+            list.set_item_range(cx, 0, 1);
+            while let Some(item_id) = list.next_visible_item(cx) {
+                if item_id == 0 {
+                    let item = list.item(cx, item_id, live_id!(QueryEmpty));
+                    let message = if self.result.columns.is_empty() {
+                        "query.empty"
+                    } else {
+                        "query.no_rows"
+                    };
+                    item.label(ids!(empty_label))
+                        .set_text(cx, &i18n::tr(message));
+                    item.draw_all(cx, &mut Scope::empty());
+                }
+            }
+            return;
+        }
+
+        list.set_item_range(cx, 0, self.result.rows.len());
+
+        while let Some(item_id) = list.next_visible_item(cx) {
+            if item_id < self.result.rows.len() {
+                let row = &self.result.rows[item_id];
+
+                let template = if item_id % 2 == 0 {
+                    live_id!(ResultRow)
+                } else {
+                    live_id!(ResultRowAlt)
+                };
+
+                let item = list.item(cx, item_id, template);
+
+                for (i, cell) in cell_ids().into_iter().enumerate() {
+                    let label = item.label(&[&[cell]]);
+                    let text = row.get(i).map(cell_text).unwrap_or_default();
+                    label.set_text(cx, &truncate(&text, CELL_CHARS));
+                    label.set_visible(cx, i < row.len());
+                }
+
+                item.draw_all(cx, &mut Scope::empty());
+            }
+        }
+    }
+}
+
+fn cell_ids() -> [LiveId; MAX_COLUMNS] {
+    [
+        live_id!(cell_0),
+        live_id!(cell_1),
+        live_id!(cell_2),
+        live_id!(cell_3),
+        live_id!(cell_4),
+        live_id!(cell_5),
+        live_id!(cell_6),
+        live_id!(cell_7),
+    ]
+}
+
+// ---------------------------------------------------------------------------
+// Ref wrapper (same pattern as AlertsPanelRef)
+// ---------------------------------------------------------------------------
+
+impl QueryPanelRef {
+    pub fn localize(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.localize(cx);
+        }
+    }
+
+    pub fn set_result(&self, cx: &mut Cx, result: QueryResult) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_result(cx, result);
+        }
+    }
+
+    /// The rows currently shown, e.g. for export
+    pub fn result(&self) -> QueryResult {
+        self.borrow()
+            .map(|inner| inner.result.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_history(&self, cx: &mut Cx, history: &[String]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_history(cx, history);
+        }
+    }
+
+    /// Show the row count, an error, or the outcome of an export
+    pub fn set_status(&self, cx: &mut Cx, message: &str) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .label(ids!(query_status_label))
+                .set_text(cx, message);
+        }
+    }
+
+    /// Put `sql` in the query input, e.g. a follow-up query from another panel
+    pub fn set_query(&self, cx: &mut Cx, sql: &str) {
+        if let Some(inner) = self.borrow() {
+            inner.view.text_input(ids!(query_input)).set_text(cx, sql);
+        }
+    }
+
+    /// Check if run was clicked, returns the entered SQL if so
+    pub fn run_clicked(&self, actions: &Actions) -> Option<String> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(run_query_button))
+            .clicked(actions)
+            .then(|| inner.view.text_input(ids!(query_input)).text())
+    }
+
+    /// Put a query picked from the history dropdown back in the query input
+    pub fn handle_history_selection(&self, cx: &mut Cx, actions: &Actions) {
+        let Some(inner) = self.borrow() else {
+            return;
+        };
+        let index = inner
+            .view
+            .drop_down(ids!(history_dropdown))
+            .selected(actions);
+        // Index 0 is the "History" placeholder label
+        let Some(sql) = index
+            .and_then(|i| i.checked_sub(1))
+            .and_then(|i| inner.history.get(i))
+        else {
+            return;
+        };
+        inner.view.text_input(ids!(query_input)).set_text(cx, sql);
+    }
+
+    /// Check if export was clicked, returns the entered path if so
+    pub fn export_clicked(&self, actions: &Actions) -> Option<String> {
+        let inner = self.borrow()?;
+        inner
+            .view
+            .button(ids!(export_csv_button))
+            .clicked(actions)
+            .then(|| inner.view.text_input(ids!(export_path_input)).text())
+    }
+}
@@ -0,0 +1,286 @@
+//! Client for a studio running with `--serve` (see `crate::serve`), for the
+//! web build: a browser cannot run `dora` or hold SigNoz credentials, so it
+//! lists and controls dataflows and queries traces through that server.
+//!
+//! The server's address is fixed when the web build is compiled, from
+//! `DORA_STUDIO_REMOTE_URL` (default [`DEFAULT_REMOTE_URL`]); the server must
+//! allow the page's origin with `DORA_STUDIO_CORS_ORIGIN`. The API token is
+//! fixed at build time too, from `DORA_STUDIO_REMOTE_TOKEN`, so the server
+//! should be given the same one as `DORA_STUDIO_SERVE_TOKEN`. Requests run
+//! in the background and the app polls [`take_responses`] each frame, as
+//! with the SigNoz bridge.
+
+use serde_json::{json, Value};
+use std::sync::Mutex;
+
+use crate::dataflow::DataflowInfo;
+use crate::otlp::types::{Span, TraceQuery};
+
+/// Where the `--serve` studio listens by default.
+pub const DEFAULT_REMOTE_URL: &str = "http://127.0.0.1:7070";
+
+static PENDING_RESPONSES: Mutex<Vec<RemoteResponse>> = Mutex::new(Vec::new());
+
+/// A call to the server's API.
+#[derive(Debug, Clone)]
+pub enum RemoteRequest {
+    ListDataflows,
+    /// The dataflow path and the environment variables to set on its nodes
+    Start(String, Vec<(String, String)>),
+    Stop(String),
+    Destroy(String),
+    Nodes(String),
+    Logs(String),
+    Traces(TraceQuery),
+}
+
+/// The outcome of a [`RemoteRequest`], with what it was about.
+#[derive(Debug, Clone)]
+pub enum RemoteResponse {
+    Dataflows(Result<Vec<DataflowInfo>, String>),
+    /// The dataflow path and the started dataflow's UUID
+    Started(String, Result<Option<String>, String>),
+    Stopped(String, Result<(), String>),
+    Destroyed(String, Result<(), String>),
+    Nodes(String, Result<Vec<String>, String>),
+    Logs(String, Result<String, String>),
+    Traces(Result<Vec<Span>, String>),
+}
+
+impl RemoteRequest {
+    /// Method, path and body of the API call.
+    pub fn endpoint(&self) -> (&'static str, String, Option<Value>) {
+        match self {
+            RemoteRequest::ListDataflows => ("GET", "/dataflows".to_string(), None),
+            RemoteRequest::Start(path, env) => {
+                let env: serde_json::Map<String, Value> = env
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Value::from(value.as_str())))
+                    .collect();
+                (
+                    "POST",
+                    "/dataflows".to_string(),
+                    Some(json!({ "path": path, "env": env })),
+                )
+            }
+            RemoteRequest::Stop(uuid) => ("POST", format!("/dataflows/{}/stop", uuid), None),
+            RemoteRequest::Destroy(uuid) => ("POST", format!("/dataflows/{}/destroy", uuid), None),
+            RemoteRequest::Nodes(uuid) => ("GET", format!("/dataflows/{}/nodes", uuid), None),
+            RemoteRequest::Logs(uuid) => ("GET", format!("/dataflows/{}/logs", uuid), None),
+            RemoteRequest::Traces(query) => (
+                "POST",
+                "/traces".to_string(),
+                Some(serde_json::to_value(query).unwrap_or_else(|_| json!({}))),
+            ),
+        }
+    }
+
+    /// The response for the server's answer, `body` on success.
+    pub fn response(self, result: Result<Value, String>) -> RemoteResponse {
+        let field = |key: &str| -> Result<Value, String> {
+            let body = result.clone()?;
+            body.get(key)
+                .cloned()
+                .ok_or_else(|| format!("Response has no \"{}\"", key))
+        };
+        let parse = |key: &str| field(key).and_then(|v| parse_field(key, v));
+        match self {
+            RemoteRequest::ListDataflows => {
+                RemoteResponse::Dataflows(field("dataflows").and_then(parse_dataflows))
+            }
+            RemoteRequest::Start(path, _) => RemoteResponse::Started(
+                path,
+                field("uuid").map(|uuid| uuid.as_str().map(str::to_string)),
+            ),
+            RemoteRequest::Stop(uuid) => RemoteResponse::Stopped(uuid, result.map(|_| ())),
+            RemoteRequest::Destroy(uuid) => RemoteResponse::Destroyed(uuid, result.map(|_| ())),
+            RemoteRequest::Nodes(uuid) => RemoteResponse::Nodes(uuid, parse("nodes")),
+            RemoteRequest::Logs(uuid) => RemoteResponse::Logs(uuid, parse("logs")),
+            RemoteRequest::Traces(_) => RemoteResponse::Traces(parse("spans")),
+        }
+    }
+}
+
+fn parse_field<T: serde::de::DeserializeOwned>(key: &str, value: Value) -> Result<T, String> {
+    serde_json::from_value(value).map_err(|e| format!("Invalid \"{}\" in response: {}", key, e))
+}
+
+/// Dataflows as listed by the server (`crate::serve::dataflow_json`).
+pub fn parse_dataflows(value: Value) -> Result<Vec<DataflowInfo>, String> {
+    match value {
+        Value::Array(entries) => Ok(entries
+            .iter()
+            .filter_map(DataflowInfo::from_value)
+            .collect()),
+        _ => Err("Invalid \"dataflows\" in response".to_string()),
+    }
+}
+
+/// The server's base URL, without a trailing slash.
+pub fn base_url() -> String {
+    option_env!("DORA_STUDIO_REMOTE_URL")
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or(DEFAULT_REMOTE_URL)
+        .trim()
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// The server's API token, if the build was given one.
+pub fn token() -> Option<&'static str> {
+    option_env!("DORA_STUDIO_REMOTE_TOKEN")
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// Make `request` against the server at `base`.
+pub async fn call(base: &str, request: RemoteRequest) -> RemoteResponse {
+    let (method, path, body) = request.endpoint();
+    let url = format!("{}{}", base, path);
+    let client = reqwest::Client::new();
+    let builder = match method {
+        "GET" => client.get(&url),
+        // The server takes POSTs only as JSON, even without a body
+        _ => client.post(&url).json(&body.unwrap_or_else(|| json!({}))),
+    };
+    let builder = match token() {
+        Some(token) => builder.bearer_auth(token),
+        None => builder,
+    };
+    let result = match builder.send().await {
+        // Worded so the app treats the server like an unreachable coordinator
+        Err(e) => Err(format!(
+            "Could not connect to the studio server at {}: {}",
+            base, e
+        )),
+        Ok(response) => {
+            let status = response.status();
+            match response.json::<Value>().await {
+                Ok(body) if status.is_success() => Ok(body),
+                Ok(body) => Err(body
+                    .get("error")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("Studio server returned {}", status))),
+                Err(e) => Err(format!("Invalid response from the studio server: {}", e)),
+            }
+        }
+    };
+    request.response(result)
+}
+
+/// Make `request` in the background; its response shows up in
+/// [`take_responses`].
+#[cfg(target_arch = "wasm32")]
+pub fn submit(request: RemoteRequest) {
+    wasm_bindgen_futures::spawn_local(async move {
+        let response = call(&base_url(), request).await;
+        PENDING_RESPONSES.lock().unwrap().push(response);
+    });
+}
+
+/// Make `request` in the background; its response shows up in
+/// [`take_responses`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn submit(request: RemoteRequest) {
+    std::thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                tracing::warn!(target: "Remote", "Failed to start runtime: {}", e);
+                return;
+            }
+        };
+        let response = runtime.block_on(call(&base_url(), request));
+        PENDING_RESPONSES.lock().unwrap().push(response);
+    });
+}
+
+/// Responses that arrived since the last call.
+pub fn take_responses() -> Vec<RemoteResponse> {
+    std::mem::take(&mut *PENDING_RESPONSES.lock().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoints() {
+        let (method, path, body) = RemoteRequest::Stop("u1".to_string()).endpoint();
+        assert_eq!((method, path.as_str()), ("POST", "/dataflows/u1/stop"));
+        assert_eq!(body, None);
+        let (method, path, body) = RemoteRequest::Logs("u1".to_string()).endpoint();
+        assert_eq!(
+            (method, path.as_str(), body),
+            ("GET", "/dataflows/u1/logs", None)
+        );
+        let env = vec![("RUST_LOG".to_string(), "debug".to_string())];
+        let (_, _, body) = RemoteRequest::Start("df.yml".to_string(), env).endpoint();
+        assert_eq!(
+            body,
+            Some(json!({ "path": "df.yml", "env": { "RUST_LOG": "debug" } }))
+        );
+        let query = TraceQuery {
+            service_name: Some("camera".to_string()),
+            ..Default::default()
+        };
+        let (_, path, body) = RemoteRequest::Traces(query).endpoint();
+        assert_eq!(path, "/traces");
+        assert_eq!(body.unwrap()["service_name"], "camera");
+    }
+
+    #[test]
+    fn test_dataflows_response() {
+        // As written by `serve::dataflow_json`
+        let body = json!({ "dataflows": [{
+            "uuid": "u1",
+            "name": "camera",
+            "status": "Running",
+            "nodes": 3,
+            "cpu": 12.5,
+            "memory_gb": 0.5,
+            "started_at_ms": 1_714_557_600_000u64,
+            "uptime_secs": 60,
+            "daemon_id": "lab-1",
+        }]});
+        let RemoteResponse::Dataflows(Ok(dataflows)) =
+            RemoteRequest::ListDataflows.response(Ok(body))
+        else {
+            panic!("expected dataflows");
+        };
+        assert_eq!(dataflows[0].uuid, "u1");
+        assert_eq!(dataflows[0].nodes, 3);
+        assert_eq!(dataflows[0].memory, 0.5);
+        assert_eq!(dataflows[0].started_at_ms, Some(1_714_557_600_000));
+        assert_eq!(dataflows[0].daemon_id.as_deref(), Some("lab-1"));
+
+        match RemoteRequest::ListDataflows.response(Ok(json!({}))) {
+            RemoteResponse::Dataflows(Err(e)) => assert!(e.contains("dataflows")),
+            other => panic!("expected an error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_errors_pass_through() {
+        let error = Err("Could not connect to the studio server".to_string());
+        match RemoteRequest::Logs("u1".to_string()).response(error) {
+            RemoteResponse::Logs(uuid, Err(e)) => {
+                assert_eq!(uuid, "u1");
+                assert!(crate::dataflow::connection::is_unreachable(&e));
+            }
+            other => panic!("expected an error, got {:?}", other),
+        }
+        let start = RemoteRequest::Start("df.yml".to_string(), Vec::new());
+        match start.response(Ok(json!({ "uuid": "u2" }))) {
+            RemoteResponse::Started(path, Ok(uuid)) => {
+                assert_eq!(path, "df.yml");
+                assert_eq!(uuid.as_deref(), Some("u2"));
+            }
+            other => panic!("expected a start, got {:?}", other),
+        }
+    }
+}
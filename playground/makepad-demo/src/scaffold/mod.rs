@@ -0,0 +1,225 @@
+//! "New project" wizard: scaffolds a dora project with `dora new`.
+//!
+//! The wizard ([`new_project_wizard::NewProjectWizard`]) asks for a
+//! language, node names and a directory. [`create`] then runs
+//! `dora new --kind node` for each node in that directory and writes a
+//! `dataflow.yml` running them all on a timer tick (plus a Cargo workspace
+//! for Rust nodes), for the app to open and start.
+
+pub mod new_project_wizard;
+
+pub use new_project_wizard::{
+    NewProjectAction, NewProjectWizard, NewProjectWizardRef, NewProjectWizardWidgetRefExt,
+};
+
+use std::path::PathBuf;
+
+use makepad_widgets::*;
+
+/// File name of the generated dataflow.
+pub const DATAFLOW_FILE: &str = "dataflow.yml";
+/// Timer input each generated node gets, until the user wires them up.
+const TICK_INPUT: &str = "dora/timer/millis/100";
+
+pub fn live_design(cx: &mut Cx) {
+    new_project_wizard::live_design(cx);
+}
+
+/// Language the nodes are written in, as `dora new --lang` takes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    Rust,
+    Python,
+}
+
+impl Language {
+    /// In the order the wizard lists them.
+    pub const ALL: [Language; 2] = [Language::Rust, Language::Python];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::Python => "python",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Language::Rust => "Rust",
+            Language::Python => "Python",
+        }
+    }
+}
+
+/// What the wizard asked for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectSpec {
+    pub dir: PathBuf,
+    pub language: Language,
+    pub nodes: Vec<String>,
+}
+
+impl ProjectSpec {
+    /// Validate the wizard's fields: a directory and one or more node names
+    /// separated by commas or spaces.
+    pub fn parse(dir: &str, language: Language, nodes: &str) -> Result<Self, String> {
+        let dir = dir.trim();
+        if dir.is_empty() {
+            return Err("Enter a directory for the project".to_string());
+        }
+        let mut names: Vec<String> = Vec::new();
+        for name in nodes
+            .split([',', ' '])
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+        {
+            if !is_valid_node_name(name) {
+                return Err(format!(
+                    "Invalid node name \"{}\": use lowercase letters, digits and _, starting with a letter",
+                    name
+                ));
+            }
+            if names.iter().any(|n| n == name) {
+                return Err(format!("Node \"{}\" is listed twice", name));
+            }
+            names.push(name.to_string());
+        }
+        if names.is_empty() {
+            return Err("Enter at least one node name".to_string());
+        }
+        Ok(Self {
+            dir: PathBuf::from(dir),
+            language,
+            nodes: names,
+        })
+    }
+}
+
+/// Whether `name` works as a node id, Rust crate and Python package alike.
+pub fn is_valid_node_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// The dataflow running the scaffolded nodes.
+pub fn dataflow_yaml(spec: &ProjectSpec) -> String {
+    let mut yaml = String::from("nodes:\n");
+    for node in &spec.nodes {
+        let (build, path) = match spec.language {
+            Language::Rust => (
+                format!("cargo build -p {}", node),
+                format!("target/debug/{}", node),
+            ),
+            Language::Python => (format!("pip install -e {}", node), node.clone()),
+        };
+        yaml.push_str(&format!(
+            "  - id: {}\n    build: {}\n    path: {}\n    inputs:\n      tick: {}\n",
+            node, build, path, TICK_INPUT
+        ));
+    }
+    yaml
+}
+
+/// Cargo workspace over the scaffolded Rust nodes, so `cargo build -p`
+/// finds them.
+pub fn workspace_toml(spec: &ProjectSpec) -> String {
+    let members: Vec<String> = spec.nodes.iter().map(|n| format!("\"{}\"", n)).collect();
+    format!(
+        "[workspace]\nresolver = \"2\"\nmembers = [{}]\n",
+        members.join(", ")
+    )
+}
+
+/// Scaffold the project and return the path of its dataflow. Refuses to
+/// touch a directory that already has a dataflow.
+pub fn create(spec: &ProjectSpec) -> Result<PathBuf, String> {
+    use crate::tools::{self, execute_tool};
+
+    if tools::ssh_target().is_some() {
+        return Err(
+            "Projects are scaffolded on this machine; clear the SSH host to create one".to_string(),
+        );
+    }
+    let dataflow = spec.dir.join(DATAFLOW_FILE);
+    if dataflow.exists() {
+        return Err(format!("{} already exists", dataflow.display()));
+    }
+    std::fs::create_dir_all(&spec.dir)
+        .map_err(|e| format!("Failed to create {}: {}", spec.dir.display(), e))?;
+
+    for node in &spec.nodes {
+        let args = serde_json::json!({
+            "name": node,
+            "kind": "node",
+            "lang": spec.language.as_str(),
+            "directory": spec.dir.display().to_string(),
+        });
+        let result = execute_tool("dora_new", "new-project", &args);
+        if result.is_error {
+            return Err(format!(
+                "Failed to create node {}: {}",
+                node, result.content
+            ));
+        }
+    }
+
+    let write = |name: &str, contents: String| {
+        let path = spec.dir.join(name);
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    };
+    if spec.language == Language::Rust && !spec.dir.join("Cargo.toml").exists() {
+        write("Cargo.toml", workspace_toml(spec))?;
+    }
+    write(DATAFLOW_FILE, dataflow_yaml(spec))?;
+    Ok(dataflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec() {
+        let spec = ProjectSpec::parse(" robots/vision ", Language::Python, "camera, detector plot")
+            .unwrap();
+        assert_eq!(spec.dir, PathBuf::from("robots/vision"));
+        assert_eq!(spec.nodes, ["camera", "detector", "plot"]);
+
+        assert!(ProjectSpec::parse("", Language::Rust, "camera").is_err());
+        assert!(ProjectSpec::parse("p", Language::Rust, " , ").is_err());
+        assert!(ProjectSpec::parse("p", Language::Rust, "Camera").is_err());
+        assert!(ProjectSpec::parse("p", Language::Rust, "--help").is_err());
+        assert!(ProjectSpec::parse("p", Language::Rust, "a a")
+            .unwrap_err()
+            .contains("twice"));
+    }
+
+    #[test]
+    fn test_generated_files() {
+        let spec = ProjectSpec {
+            dir: PathBuf::from("vision"),
+            language: Language::Rust,
+            nodes: vec!["camera".to_string(), "plot".to_string()],
+        };
+        let yaml = dataflow_yaml(&spec);
+        assert!(yaml.starts_with("nodes:\n  - id: camera\n    build: cargo build -p camera\n"));
+        assert!(yaml.contains(
+            "    path: target/debug/plot\n    inputs:\n      tick: dora/timer/millis/100\n"
+        ));
+        assert_eq!(
+            workspace_toml(&spec),
+            "[workspace]\nresolver = \"2\"\nmembers = [\"camera\", \"plot\"]\n"
+        );
+
+        let python = ProjectSpec {
+            language: Language::Python,
+            ..spec
+        };
+        assert!(
+            dataflow_yaml(&python).contains("    build: pip install -e camera\n    path: camera\n")
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Dialog asking what project to scaffold; the app runs [`super::create`]
+//! on the [`ProjectSpec`] it emits and reports back with
+//! [`NewProjectWizardRef::set_error`] when that fails.
+
+use makepad_widgets::*;
+
+use super::{Language, ProjectSpec};
+use crate::i18n;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    TEXT_PRIMARY = #1e293b
+    TEXT_SECONDARY = #64748b
+    TEXT_ERROR = #b91c1c
+    WIZARD_BORDER = #cbd5e1
+
+    WizardLabel = <Label> {
+        width: Fill, height: Fit
+        draw_text: {
+            color: (TEXT_SECONDARY),
+            text_style: { font_size: 10.0 }
+        }
+    }
+
+    WizardInput = <TextInput> {
+        width: Fill, height: 32
+        draw_text: { text_style: { font_size: 12.0 } }
+    }
+
+    pub NewProjectWizard = {{NewProjectWizard}} {
+        width: 440, height: Fit
+        flow: Down
+        show_bg: true
+        draw_bg: { color: (WIZARD_BORDER) }
+        padding: 1
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Down
+            show_bg: true
+            draw_bg: { color: #ffffff }
+            padding: 12
+            spacing: 6
+
+            title_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 14.0 }
+                }
+                text: "New dora project"
+            }
+
+            language_label = <WizardLabel> { text: "LANGUAGE" }
+            language_dropdown = <DropDown> {
+                width: 140, height: 32
+                labels: ["Rust", "Python"]
+                draw_text: { text_style: { font_size: 11.0 } }
+            }
+
+            nodes_label = <WizardLabel> { text: "NODES" }
+            nodes_input = <WizardInput> {
+                empty_text: "Node names, e.g. camera, detector"
+            }
+
+            directory_label = <WizardLabel> { text: "DIRECTORY" }
+            dir_input = <WizardInput> {
+                empty_text: "path/to/project"
+            }
+
+            error_label = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_ERROR),
+                    text_style: { font_size: 10.0 }
+                    wrap: Word
+                }
+            }
+
+            <View> {
+                width: Fill, height: Fit
+                flow: Right
+                spacing: 8
+
+                <View> { width: Fill, height: 1 }
+                cancel_button = <Button> {
+                    width: 80, height: 30
+                    text: "Cancel"
+                    draw_text: { text_style: { font_size: 11.0 } }
+                }
+                create_button = <Button> {
+                    width: 80, height: 30
+                    text: "Create"
+                    draw_text: { text_style: { font_size: 11.0 } }
+                }
+            }
+        }
+    }
+}
+
+/// Actions emitted by the NewProjectWizard
+#[derive(Clone, Debug, DefaultNone)]
+pub enum NewProjectAction {
+    None,
+    Create(ProjectSpec),
+    Dismissed,
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct NewProjectWizard {
+    #[deref]
+    view: View,
+}
+
+impl Widget for NewProjectWizard {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        if !self.view.visible() {
+            return;
+        }
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+
+        if let Event::KeyDown(ke) = event {
+            if ke.key_code == KeyCode::Escape {
+                cx.widget_action(self.widget_uid(), &scope.path, NewProjectAction::Dismissed);
+            }
+        }
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for NewProjectWizard {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope) {
+        if self.view.button(ids!(cancel_button)).clicked(actions) {
+            cx.widget_action(self.widget_uid(), &scope.path, NewProjectAction::Dismissed);
+            return;
+        }
+        let dir_input = self.view.text_input(ids!(dir_input));
+        let create = self.view.button(ids!(create_button)).clicked(actions)
+            || dir_input.returned(actions).is_some();
+        if !create {
+            return;
+        }
+        let language = Language::ALL
+            .get(self.view.drop_down(ids!(language_dropdown)).selected_item())
+            .copied()
+            .unwrap_or_default();
+        let nodes = self.view.text_input(ids!(nodes_input)).text();
+        match ProjectSpec::parse(&dir_input.text(), language, &nodes) {
+            Ok(spec) => {
+                self.set_error(cx, "");
+                cx.widget_action(
+                    self.widget_uid(),
+                    &scope.path,
+                    NewProjectAction::Create(spec),
+                );
+            }
+            Err(e) => self.set_error(cx, &e),
+        }
+    }
+}
+
+impl NewProjectWizard {
+    /// Show the wizard, keeping what was entered last time.
+    pub fn open(&mut self, cx: &mut Cx) {
+        i18n::localize(
+            cx,
+            &[
+                (ids!(title_label), "wizard.title"),
+                (ids!(language_label), "wizard.language"),
+                (ids!(nodes_label), "wizard.nodes"),
+                (ids!(directory_label), "wizard.directory"),
+                (ids!(cancel_button), "action.cancel"),
+                (ids!(create_button), "wizard.create"),
+            ],
+            |path| self.view.widget(path),
+        );
+        self.set_error(cx, "");
+        self.view.set_visible(cx, true);
+        self.view.text_input(ids!(nodes_input)).set_key_focus(cx);
+        self.redraw(cx);
+    }
+
+    pub fn close(&mut self, cx: &mut Cx) {
+        self.view.set_visible(cx, false);
+        self.redraw(cx);
+    }
+
+    pub fn set_error(&mut self, cx: &mut Cx, error: &str) {
+        self.view.label(ids!(error_label)).set_text(cx, error);
+        self.redraw(cx);
+    }
+}
+
+impl NewProjectWizardRef {
+    pub fn open(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.open(cx);
+        }
+    }
+
+    pub fn close(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.close(cx);
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.borrow().is_some_and(|inner| inner.view.visible())
+    }
+
+    /// Show why the project could not be created.
+    pub fn set_error(&self, cx: &mut Cx, error: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_error(cx, error);
+        }
+    }
+
+    /// Check if a NewProjectAction was triggered
+    pub fn action(&self, actions: &Actions) -> Option<NewProjectAction> {
+        actions.find_widget_action(self.widget_uid())?.cast()
+    }
+}
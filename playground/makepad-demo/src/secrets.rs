@@ -0,0 +1,377 @@
+//! Credentials kept out of plain-text files.
+//!
+//! Secrets go to the OS credential store through the `keyring` crate: the
+//! Keychain on macOS, the Credential Manager on Windows and the Secret
+//! Service (GNOME Keyring, KWallet) on Linux. Where there is none, e.g. a
+//! headless Linux box without a Secret Service, they go to `secrets.bin` in
+//! the data directory, encrypted with ChaCha20-Poly1305 under a random key
+//! kept in `secrets.key` beside it (owner-only on Unix).
+//! The file keeps secrets out of `settings.json` and config bundles; it
+//! does not protect them from someone who can read the data directory.
+//!
+//! Environment variables still win over stored secrets. Store one with
+//! `dora-studio --set-secret <name>`, reading the value from stdin.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::settings;
+
+/// Claude API key, in place of `ANTHROPIC_API_KEY`.
+pub const ANTHROPIC_API_KEY: &str = "anthropic_api_key";
+/// SigNoz API key, in place of `SIGNOZ_API_KEY`.
+pub const SIGNOZ_API_KEY: &str = "signoz_api_key";
+/// SigNoz login password, in place of `SIGNOZ_PASSWORD`.
+pub const SIGNOZ_PASSWORD: &str = "signoz_password";
+/// ClickHouse password, in place of `SIGNOZ_CLICKHOUSE_PASSWORD`.
+pub const CLICKHOUSE_PASSWORD: &str = "clickhouse_password";
+/// Elasticsearch API key, in place of `ELASTICSEARCH_API_KEY`.
+pub const ELASTIC_API_KEY: &str = "elastic_api_key";
+/// Elasticsearch password, in place of `ELASTICSEARCH_PASSWORD`.
+pub const ELASTIC_PASSWORD: &str = "elastic_password";
+
+/// Names accepted by [`command`].
+pub const NAMES: [&str; 6] = [
+    ANTHROPIC_API_KEY,
+    SIGNOZ_API_KEY,
+    SIGNOZ_PASSWORD,
+    CLICKHOUSE_PASSWORD,
+    ELASTIC_API_KEY,
+    ELASTIC_PASSWORD,
+];
+
+/// Credential store service the secrets are filed under.
+const SERVICE: &str = "dora-studio";
+const SECRETS_FILE: &str = "secrets.bin";
+const KEY_FILE: &str = "secrets.key";
+const KEY_LEN: usize = 32;
+
+/// The secret stored as `name`, from the credential store or else the
+/// encrypted file.
+pub fn get(name: &str) -> Option<String> {
+    keychain_get(name).or_else(|| {
+        let file = SecretFile::in_data_dir()?;
+        match file.get(name) {
+            Ok(secret) => secret,
+            Err(e) => {
+                tracing::warn!(target: "Secrets", "Failed to read {}: {}", name, e);
+                None
+            }
+        }
+    })
+}
+
+/// Store `value` as `name` in the credential store, or in the encrypted
+/// file when there is no store to take it. Returns where it went.
+pub fn set(name: &str, value: &str) -> Result<&'static str, String> {
+    match keychain_set(name, value) {
+        Ok(()) => Ok("the OS credential store"),
+        Err(e) => {
+            tracing::info!(
+                target: "Secrets",
+                "Credential store unavailable ({}), using the encrypted file",
+                e
+            );
+            SecretFile::in_data_dir()
+                .ok_or("No data directory available")?
+                .set(name, Some(value))?;
+            Ok("the encrypted secrets file")
+        }
+    }
+}
+
+/// Remove `name` from both the credential store and the encrypted file.
+pub fn delete(name: &str) -> Result<(), String> {
+    let _ = keychain_delete(name);
+    match SecretFile::in_data_dir() {
+        Some(file) => file.set(name, None),
+        None => Ok(()),
+    }
+}
+
+/// A command line asking to store or remove a secret.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecretCommand {
+    /// `--set-secret <name>`, with the value on stdin
+    Set(String),
+    /// `--delete-secret <name>`
+    Delete(String),
+}
+
+/// The secret command in `args`, if any.
+pub fn command(args: impl IntoIterator<Item = String>) -> Option<SecretCommand> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--set-secret" => return Some(SecretCommand::Set(args.next().unwrap_or_default())),
+            "--delete-secret" => {
+                return Some(SecretCommand::Delete(args.next().unwrap_or_default()))
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+impl SecretCommand {
+    /// Run the command, returning what to tell the user.
+    pub fn run(&self) -> Result<String, String> {
+        let name = match self {
+            SecretCommand::Set(name) | SecretCommand::Delete(name) => name,
+        };
+        if !NAMES.contains(&name.as_str()) {
+            return Err(format!(
+                "Unknown secret '{}', expected one of: {}",
+                name,
+                NAMES.join(", ")
+            ));
+        }
+        match self {
+            SecretCommand::Set(name) => {
+                let mut value = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut value)
+                    .map_err(|e| format!("Failed to read the secret from stdin: {}", e))?;
+                let value = value.trim_end_matches(['\r', '\n']);
+                if value.is_empty() {
+                    return Err("Empty secret on stdin".to_string());
+                }
+                let place = set(name, value)?;
+                Ok(format!("Stored {} in {}", name, place))
+            }
+            SecretCommand::Delete(name) => {
+                delete(name)?;
+                Ok(format!("Deleted {}", name))
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OS credential store
+// ---------------------------------------------------------------------------
+
+fn keychain_entry(name: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, name).map_err(|e| e.to_string())
+}
+
+fn keychain_get(name: &str) -> Option<String> {
+    let secret = keychain_entry(name).ok()?.get_password().ok()?;
+    (!secret.is_empty()).then_some(secret)
+}
+
+fn keychain_set(name: &str, value: &str) -> Result<(), String> {
+    keychain_entry(name)?
+        .set_password(value)
+        .map_err(|e| e.to_string())
+}
+
+fn keychain_delete(name: &str) -> Result<(), String> {
+    match keychain_entry(name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Encrypted file
+// ---------------------------------------------------------------------------
+
+/// Secrets in `secrets.bin`, encrypted under the key in `secrets.key`.
+struct SecretFile {
+    dir: PathBuf,
+}
+
+impl SecretFile {
+    fn in_data_dir() -> Option<Self> {
+        settings::data_dir().map(|dir| Self { dir })
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>, String> {
+        Ok(self.read()?.remove(name))
+    }
+
+    /// Store `value` as `name`, or remove `name` when `None`.
+    fn set(&self, name: &str, value: Option<&str>) -> Result<(), String> {
+        let mut secrets = self.read()?;
+        match value {
+            Some(value) => {
+                secrets.insert(name.to_string(), value.to_string());
+            }
+            None => {
+                if secrets.remove(name).is_none() {
+                    return Ok(());
+                }
+            }
+        }
+        let key = self.key(true)?.ok_or("No secrets key")?;
+        let json = serde_json::to_vec(&secrets).map_err(|e| e.to_string())?;
+        write_private(&self.dir.join(SECRETS_FILE), &seal(&key, &json)?)
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, String>, String> {
+        let data = match std::fs::read(self.dir.join(SECRETS_FILE)) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(format!("Failed to read {}: {}", SECRETS_FILE, e)),
+        };
+        let key = self
+            .key(false)?
+            .ok_or_else(|| format!("{} is missing", KEY_FILE))?;
+        let json = open(&key, &data)?;
+        serde_json::from_slice(&json).map_err(|e| format!("Corrupt {}: {}", SECRETS_FILE, e))
+    }
+
+    /// The file key, created when missing and `create` is set.
+    fn key(&self, create: bool) -> Result<Option<[u8; KEY_LEN]>, String> {
+        let path = self.dir.join(KEY_FILE);
+        match std::fs::read(&path) {
+            Ok(bytes) => bytes
+                .try_into()
+                .map(Some)
+                .map_err(|_| format!("{} is not a {}-byte key", KEY_FILE, KEY_LEN)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && create => {
+                let mut key = [0u8; KEY_LEN];
+                SystemRandom::new()
+                    .fill(&mut key)
+                    .map_err(|_| "No system randomness available")?;
+                write_private(&path, &key)?;
+                Ok(Some(key))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to read {}: {}", KEY_FILE, e)),
+        }
+    }
+}
+
+/// `plaintext` encrypted under `key`, as a random nonce followed by the
+/// ciphertext and tag.
+fn seal(key: &[u8; KEY_LEN], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| "No system randomness available")?;
+    let mut sealed = plaintext.to_vec();
+    aead_key(key)
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut sealed,
+        )
+        .map_err(|_| "Encryption failed")?;
+    let mut data = nonce.to_vec();
+    data.extend(sealed);
+    Ok(data)
+}
+
+/// The plaintext of [`seal`]ed `data`; an error when it was altered or
+/// sealed under another key.
+fn open(key: &[u8; KEY_LEN], data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < NONCE_LEN {
+        return Err(format!("{} is truncated", SECRETS_FILE));
+    }
+    let (nonce, sealed) = data.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| "Bad nonce")?;
+    let mut sealed = sealed.to_vec();
+    let plaintext = aead_key(key)
+        .open_in_place(nonce, Aad::empty(), &mut sealed)
+        .map_err(|_| format!("{} does not match {}", SECRETS_FILE, KEY_FILE))?;
+    Ok(plaintext.to_vec())
+}
+
+fn aead_key(key: &[u8; KEY_LEN]) -> LessSafeKey {
+    LessSafeKey::new(UnboundKey::new(&CHACHA20_POLY1305, key).expect("32-byte key"))
+}
+
+/// Write `data` to `path`, readable by the owner only on Unix.
+pub(crate) fn write_private(path: &Path, data: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(data))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open() {
+        let key = [7u8; KEY_LEN];
+        let data = seal(&key, b"hunter2").unwrap();
+        assert!(!data.windows(7).any(|w| w == b"hunter2"));
+        assert_eq!(open(&key, &data).unwrap(), b"hunter2");
+
+        // A fresh nonce per seal
+        assert_ne!(seal(&key, b"hunter2").unwrap(), data);
+
+        assert!(open(&[8u8; KEY_LEN], &data).is_err());
+        let mut tampered = data.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(open(&key, &tampered).is_err());
+        assert!(open(&key, &data[..4]).is_err());
+    }
+
+    #[test]
+    fn test_secret_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = SecretFile {
+            dir: dir.path().to_path_buf(),
+        };
+        assert_eq!(file.get(SIGNOZ_API_KEY).unwrap(), None);
+
+        file.set(SIGNOZ_API_KEY, Some("secret123")).unwrap();
+        file.set(ELASTIC_PASSWORD, Some("changeme")).unwrap();
+        assert_eq!(
+            file.get(SIGNOZ_API_KEY).unwrap().as_deref(),
+            Some("secret123")
+        );
+
+        file.set(SIGNOZ_API_KEY, None).unwrap();
+        assert_eq!(file.get(SIGNOZ_API_KEY).unwrap(), None);
+        assert_eq!(
+            file.get(ELASTIC_PASSWORD).unwrap().as_deref(),
+            Some("changeme")
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.path().join(KEY_FILE))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_command() {
+        let args = |s: &[&str]| s.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            command(args(&["--set-secret", "signoz_api_key"])),
+            Some(SecretCommand::Set("signoz_api_key".to_string()))
+        );
+        assert_eq!(
+            command(args(&["--delete-secret", "elastic_password"])),
+            Some(SecretCommand::Delete("elastic_password".to_string()))
+        );
+        assert_eq!(command(args(&["--serve"])), None);
+        assert!(SecretCommand::Delete("nope".to_string())
+            .run()
+            .unwrap_err()
+            .contains("Unknown secret"));
+    }
+}
@@ -0,0 +1,224 @@
+//! Who may call the API.
+//!
+//! Every route but `/health` takes a bearer token, generated when the
+//! server starts (or taken from `DORA_STUDIO_SERVE_TOKEN`, to share it with
+//! a web build) and written owner-only to `serve.token` in the data
+//! directory for local scripts to read. Requests whose `Host` is neither an
+//! IP address, `localhost` nor the bound host are refused, so a page that
+//! rebinds its own domain to the loopback address cannot reach the API.
+//!
+//! Browsers apply no CORS to WebSockets, so the `/events` upgrade also
+//! checks `Origin` against the allowed ones, and takes the token as
+//! `?token=` since browsers cannot set headers on it.
+
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+use ring::rand::{SecureRandom, SystemRandom};
+
+use super::{Request, Response};
+use crate::settings;
+
+/// Fixed token, in place of a generated one.
+pub const TOKEN_ENV: &str = "DORA_STUDIO_SERVE_TOKEN";
+/// Web origins allowed to call the API, comma-separated.
+const CORS_ORIGIN_ENV: &str = "DORA_STUDIO_CORS_ORIGIN";
+const TOKEN_FILE: &str = "serve.token";
+const TOKEN_BYTES: usize = 32;
+
+/// Where the token of the running server is written.
+pub fn token_path() -> Option<PathBuf> {
+    settings::data_dir().map(|dir| dir.join(TOKEN_FILE))
+}
+
+/// The token for this run: `DORA_STUDIO_SERVE_TOKEN` or a fresh random one,
+/// written to [`token_path`] readable by the owner only.
+pub fn issue_token() -> Result<String, String> {
+    let token = match std::env::var(TOKEN_ENV) {
+        Ok(token) if !token.trim().is_empty() => token.trim().to_string(),
+        _ => {
+            let mut bytes = [0u8; TOKEN_BYTES];
+            SystemRandom::new()
+                .fill(&mut bytes)
+                .map_err(|_| "No system randomness available")?;
+            bytes.iter().map(|b| format!("{:02x}", b)).collect()
+        }
+    };
+    let path = token_path().ok_or("No data directory for the API token")?;
+    // Recreated so an old file's permissions do not carry over
+    let _ = std::fs::remove_file(&path);
+    crate::secrets::write_private(&path, token.as_bytes())?;
+    Ok(token)
+}
+
+/// Web origins in `DORA_STUDIO_CORS_ORIGIN`.
+pub fn cors_origins_from_env() -> Vec<String> {
+    std::env::var(CORS_ORIGIN_ENV)
+        .unwrap_or_default()
+        .split(',')
+        .map(|o| o.trim().trim_end_matches('/').to_string())
+        .filter(|o| !o.is_empty())
+        .collect()
+}
+
+/// Checks applied to every request.
+pub struct Guard {
+    token: String,
+    /// Host part of the address the server is bound to
+    bound_host: String,
+    /// Web origins allowed to call the API
+    origins: Vec<String>,
+}
+
+impl Guard {
+    pub fn new(token: String, bound_addr: &str, origins: Vec<String>) -> Self {
+        Self {
+            token,
+            bound_host: host_name(bound_addr).to_ascii_lowercase(),
+            origins,
+        }
+    }
+
+    pub fn origins(&self) -> &[String] {
+        &self.origins
+    }
+
+    /// An error response when `request` may not be served.
+    pub fn check(&self, request: &Request, needs_token: bool) -> Result<(), Response> {
+        if !host_allowed(request.header("host"), &self.bound_host) {
+            return Err(Response::error(403, "Host not allowed"));
+        }
+        if needs_token && !self.authorized(request) {
+            return Err(Response::error(401, "Missing or wrong API token"));
+        }
+        Ok(())
+    }
+
+    /// An error response when the `/events` WebSocket upgrade in `request`
+    /// may not be accepted. Requests without `Origin` come from outside a
+    /// browser.
+    pub fn check_events(&self, request: &Request) -> Result<(), Response> {
+        if !host_allowed(request.header("host"), &self.bound_host) {
+            return Err(Response::error(403, "Host not allowed"));
+        }
+        if let Some(origin) = request.header("origin") {
+            if !self.origins.iter().any(|o| o == origin) {
+                return Err(Response::error(403, "Origin not allowed"));
+            }
+        }
+        let query_token = request.query.get("token");
+        if !self.authorized(request) && !query_token.is_some_and(|t| same_token(t, &self.token)) {
+            return Err(Response::error(401, "Missing or wrong API token"));
+        }
+        Ok(())
+    }
+
+    /// Whether `request` carries the token as `Authorization: Bearer`.
+    pub fn authorized(&self, request: &Request) -> bool {
+        request
+            .header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| same_token(token.trim(), &self.token))
+    }
+}
+
+/// Host of a `Host` header or socket address, without the port.
+fn host_name(addr: &str) -> &str {
+    let addr = addr.trim();
+    if let Some(rest) = addr.strip_prefix('[') {
+        return rest.split(']').next().unwrap_or(rest);
+    }
+    match addr.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') && port.parse::<u16>().is_ok() => host,
+        _ => addr,
+    }
+}
+
+/// Whether a request's `Host` header names this server rather than some
+/// domain that resolves to it. IP addresses cannot be rebound.
+pub fn host_allowed(header: Option<&str>, bound_host: &str) -> bool {
+    let Some(host) = header.map(host_name) else {
+        return false;
+    };
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<IpAddr>().is_ok()
+        || (!bound_host.is_empty() && host.eq_ignore_ascii_case(bound_host))
+}
+
+/// Compare without returning early, so timing does not leak the token.
+fn same_token(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(host: Option<&str>, authorization: Option<&str>) -> Request {
+        let mut request = Request::default();
+        if let Some(host) = host {
+            request.headers.insert("host".to_string(), host.to_string());
+        }
+        if let Some(value) = authorization {
+            request
+                .headers
+                .insert("authorization".to_string(), value.to_string());
+        }
+        request
+    }
+
+    #[test]
+    fn test_host_allowed() {
+        assert!(host_allowed(Some("localhost:7070"), "127.0.0.1"));
+        assert!(host_allowed(Some("127.0.0.1:7070"), "127.0.0.1"));
+        assert!(host_allowed(Some("[::1]:7070"), "127.0.0.1"));
+        assert!(host_allowed(Some("192.168.1.20"), "0.0.0.0"));
+        assert!(host_allowed(Some("Studio.lab:7070"), "studio.lab"));
+        assert!(!host_allowed(Some("evil.example:7070"), "127.0.0.1"));
+        assert!(!host_allowed(Some("localhost.evil.example"), "127.0.0.1"));
+        assert!(!host_allowed(None, "127.0.0.1"));
+    }
+
+    #[test]
+    fn test_guard() {
+        let guard = Guard::new("s3cret".to_string(), "127.0.0.1:7070", Vec::new());
+        let ok = request(Some("127.0.0.1:7070"), Some("Bearer s3cret"));
+        assert!(guard.check(&ok, true).is_ok());
+
+        let no_token = request(Some("127.0.0.1:7070"), None);
+        assert!(guard.check(&no_token, false).is_ok());
+        assert_eq!(guard.check(&no_token, true).unwrap_err().status, 401);
+        let wrong = request(Some("localhost"), Some("Bearer s3cre"));
+        assert_eq!(guard.check(&wrong, true).unwrap_err().status, 401);
+
+        let rebound = request(Some("attacker.example"), Some("Bearer s3cret"));
+        assert_eq!(guard.check(&rebound, false).unwrap_err().status, 403);
+    }
+
+    #[test]
+    fn test_guard_events() {
+        let origins = vec!["http://localhost:8010".to_string()];
+        let guard = Guard::new("s3cret".to_string(), "127.0.0.1:7070", origins);
+        let mut upgrade = request(Some("127.0.0.1:7070"), None);
+        assert_eq!(guard.check_events(&upgrade).unwrap_err().status, 401);
+        upgrade
+            .query
+            .insert("token".to_string(), "s3cret".to_string());
+        assert!(guard.check_events(&upgrade).is_ok());
+
+        // Any page can open a WebSocket; only allowed origins get through
+        upgrade
+            .headers
+            .insert("origin".to_string(), "http://evil.example".to_string());
+        assert_eq!(guard.check_events(&upgrade).unwrap_err().status, 403);
+        upgrade
+            .headers
+            .insert("origin".to_string(), "http://localhost:8010".to_string());
+        assert!(guard.check_events(&upgrade).is_ok());
+    }
+}
@@ -0,0 +1,277 @@
+//! Dataflow and telemetry events pushed to WebSocket subscribers (see
+//! [`super::websocket`]), so dashboards need not poll the API.
+//!
+//! Whoever lists dataflows (the app's refresh or the server's watcher)
+//! feeds the listing to an [`EventWatcher`], which publishes status changes
+//! and, while anyone is subscribed, new log lines of running dataflows.
+//! Alert transitions are published where alert rules are evaluated.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+
+use crate::alerts::{AlertEvent, AlertState};
+use crate::dataflow::log_buffer::LogAnchor;
+use crate::dataflow::{DataflowInfo, DoraClient};
+
+/// Log lines sent per dataflow and poll; older new lines are dropped.
+const MAX_LOG_LINES: usize = 200;
+
+static SUBSCRIBERS: Mutex<Vec<Subscriber>> = Mutex::new(Vec::new());
+
+/// An update pushed to subscribers, as JSON tagged by `type`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StudioEvent {
+    /// A dataflow appeared or changed status
+    DataflowStatus {
+        uuid: String,
+        name: String,
+        status: String,
+        /// Status before the change; `None` for a new dataflow
+        previous: Option<String>,
+    },
+    /// A dataflow is no longer listed
+    DataflowRemoved { uuid: String, name: String },
+    /// Lines a running dataflow logged since the last poll
+    LogLines {
+        uuid: String,
+        name: String,
+        lines: Vec<String>,
+    },
+    /// An alert rule started or stopped firing
+    Alert {
+        rule: String,
+        /// `firing` or `resolved`
+        state: String,
+        value: String,
+        timestamp_ms: u64,
+    },
+}
+
+impl StudioEvent {
+    /// The `type` tag, for subscribers' filters
+    pub fn kind(&self) -> &'static str {
+        match self {
+            StudioEvent::DataflowStatus { .. } => "dataflow_status",
+            StudioEvent::DataflowRemoved { .. } => "dataflow_removed",
+            StudioEvent::LogLines { .. } => "log_lines",
+            StudioEvent::Alert { .. } => "alert",
+        }
+    }
+}
+
+impl From<&AlertEvent> for StudioEvent {
+    fn from(event: &AlertEvent) -> Self {
+        let state = match event.state {
+            AlertState::Firing => "firing",
+            AlertState::Resolved => "resolved",
+        };
+        StudioEvent::Alert {
+            rule: event.rule.clone(),
+            state: state.to_string(),
+            value: event.value.clone(),
+            timestamp_ms: event.timestamp_ms,
+        }
+    }
+}
+
+struct Subscriber {
+    /// Event types wanted; all when `None`
+    kinds: Option<Vec<String>>,
+    sender: Sender<String>,
+}
+
+/// Receive events as JSON text, only those of `kinds` when given.
+pub fn subscribe(kinds: Option<Vec<String>>) -> Receiver<String> {
+    let (sender, receiver) = channel();
+    SUBSCRIBERS
+        .lock()
+        .unwrap()
+        .push(Subscriber { kinds, sender });
+    receiver
+}
+
+/// Whether anyone is subscribed, so work done only for events can be skipped.
+pub fn has_subscribers() -> bool {
+    !SUBSCRIBERS.lock().unwrap().is_empty()
+}
+
+/// Send `event` to the subscribers that want it, dropping those that have
+/// gone away.
+pub fn publish(event: &StudioEvent) {
+    let mut subscribers = SUBSCRIBERS.lock().unwrap();
+    if subscribers.is_empty() {
+        return;
+    }
+    let Ok(json) = serde_json::to_string(event) else {
+        return;
+    };
+    let kind = event.kind();
+    subscribers.retain(|s| {
+        let wanted = s
+            .kinds
+            .as_ref()
+            .is_none_or(|kinds| kinds.iter().any(|k| k == kind));
+        !wanted || s.sender.send(json.clone()).is_ok()
+    });
+}
+
+/// Turns successive dataflow listings into events.
+#[derive(Debug, Default)]
+pub struct EventWatcher {
+    /// Name and status by UUID as of the last listing; `None` before the
+    /// first, which only sets the baseline
+    statuses: Option<HashMap<String, (String, String)>>,
+    /// Where the logs last fetched ended, by UUID
+    logs: HashMap<String, LogAnchor>,
+}
+
+impl EventWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Status changes since the last listing.
+    pub fn dataflow_events(&mut self, dataflows: &[DataflowInfo]) -> Vec<StudioEvent> {
+        let current: HashMap<String, (String, String)> = dataflows
+            .iter()
+            .map(|df| (df.uuid.clone(), (df.name.clone(), df.status.clone())))
+            .collect();
+        let Some(previous) = self.statuses.replace(current) else {
+            return Vec::new();
+        };
+        let mut events: Vec<StudioEvent> = dataflows
+            .iter()
+            .filter_map(|df| {
+                let before = previous.get(&df.uuid).map(|(_, status)| status);
+                (before != Some(&df.status)).then(|| StudioEvent::DataflowStatus {
+                    uuid: df.uuid.clone(),
+                    name: df.name.clone(),
+                    status: df.status.clone(),
+                    previous: before.cloned(),
+                })
+            })
+            .collect();
+        let listed = self.statuses.as_ref().unwrap();
+        let mut removed: Vec<_> = previous
+            .into_iter()
+            .filter(|(uuid, _)| !listed.contains_key(uuid))
+            .collect();
+        removed.sort();
+        events.extend(
+            removed
+                .into_iter()
+                .map(|(uuid, (name, _))| StudioEvent::DataflowRemoved { uuid, name }),
+        );
+        events
+    }
+
+    /// New lines in `logs` of a dataflow since they were last fetched; the
+    /// first fetch only sets the baseline.
+    pub fn log_event(&mut self, df: &DataflowInfo, logs: &str) -> Option<StudioEvent> {
+        let previous = self.logs.insert(df.uuid.clone(), LogAnchor::new(logs))?;
+        let mut lines = previous.new_lines(logs);
+        if lines.is_empty() {
+            return None;
+        }
+        lines.drain(..lines.len().saturating_sub(MAX_LOG_LINES));
+        Some(StudioEvent::LogLines {
+            uuid: df.uuid.clone(),
+            name: df.name.clone(),
+            lines,
+        })
+    }
+
+    /// Publish what changed in `dataflows`, fetching the logs of running
+    /// ones through `dora` while anyone is subscribed. That runs `dora logs`
+    /// per dataflow, so call it off the UI thread.
+    pub fn observe(&mut self, dora: &dyn DoraClient, dataflows: &[DataflowInfo]) {
+        for event in self.dataflow_events(dataflows) {
+            publish(&event);
+        }
+        if !has_subscribers() {
+            self.logs.clear();
+            return;
+        }
+        self.logs.retain(|uuid, _| {
+            dataflows
+                .iter()
+                .any(|df| df.is_running() && &df.uuid == uuid)
+        });
+        for df in dataflows.iter().filter(|df| df.is_running()) {
+            let Ok(logs) = dora.logs(&df.uuid, None) else {
+                continue;
+            };
+            if let Some(event) = self.log_event(df, &logs) {
+                publish(&event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataflow(uuid: &str, status: &str) -> DataflowInfo {
+        DataflowInfo {
+            uuid: uuid.to_string(),
+            name: format!("{}-name", uuid),
+            status: status.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_dataflow_events() {
+        let mut watcher = EventWatcher::new();
+        assert!(watcher
+            .dataflow_events(&[dataflow("a", "Running"), dataflow("b", "Running")])
+            .is_empty());
+        let events = watcher.dataflow_events(&[dataflow("a", "Failed"), dataflow("c", "Running")]);
+        assert_eq!(
+            events,
+            [
+                StudioEvent::DataflowStatus {
+                    uuid: "a".to_string(),
+                    name: "a-name".to_string(),
+                    status: "Failed".to_string(),
+                    previous: Some("Running".to_string()),
+                },
+                StudioEvent::DataflowStatus {
+                    uuid: "c".to_string(),
+                    name: "c-name".to_string(),
+                    status: "Running".to_string(),
+                    previous: None,
+                },
+                StudioEvent::DataflowRemoved {
+                    uuid: "b".to_string(),
+                    name: "b-name".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(&events[2]).unwrap(),
+            serde_json::json!({ "type": "dataflow_removed", "uuid": "b", "name": "b-name" })
+        );
+    }
+
+    #[test]
+    fn test_log_event_sets_baseline_first() {
+        let mut watcher = EventWatcher::new();
+        let df = dataflow("a", "Running");
+        assert_eq!(watcher.log_event(&df, "old\n"), None);
+        assert_eq!(watcher.log_event(&df, "old\n"), None);
+        let lines = (0..MAX_LOG_LINES + 5).map(|i| format!("line {}\n", i));
+        let logs = format!("old\n{}", lines.collect::<String>());
+        match watcher.log_event(&df, &logs) {
+            Some(StudioEvent::LogLines { lines, .. }) => {
+                assert_eq!(lines.len(), MAX_LOG_LINES);
+                assert_eq!(lines[0], "line 5");
+            }
+            other => panic!("expected log lines, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,897 @@
+//! Headless mode: the dora client, SigNoz bridge, local storage and chat
+//! agent behind a small JSON HTTP API, for CI scripts and web frontends.
+//!
+//! Started with `dora-studio --serve [ADDR]` (default
+//! [`DEFAULT_SERVE_ADDR`]). It uses the same settings, data directory and
+//! environment variables as the app. Routes:
+//!
+//! ```text
+//! GET  /health
+//! GET  /dataflows                      listed dataflows
+//! POST /dataflows                      {"path": "dataflow.yml", "env": {..}} → {"uuid": ..}
+//! POST /dataflows/{uuid}/stop
+//! POST /dataflows/{uuid}/destroy
+//! GET  /dataflows/{uuid}/nodes
+//! GET  /dataflows/{uuid}/logs[?node=ID]
+//! POST /traces                         a TraceQuery, e.g. {"service_name": "camera"}
+//! POST /query                          {"sql": "SELECT ..."} over local storage, limited
+//!                                      to 10 000 result rows and 10 s
+//! POST /chat                           {"prompt": ".."} or {"messages": [..]}, optional "template"
+//! GET  /events[?types=..]              WebSocket stream of dataflow, log and alert events
+//! ```
+//!
+//! Started dataflows get the environment variables saved for their path in
+//! the settings, or those given as `"env"`.
+//!
+//! Every route but `/health` needs `Authorization: Bearer <token>`, with
+//! the token from `serve.token` in the data directory (see [`auth`]).
+//!
+//! Responses are JSON; failures are `{"error": ".."}` with a 4xx or 5xx
+//! status. POST requests must be sent as `application/json`, even without a
+//! body; browsers cannot do that cross-origin without passing a CORS
+//! preflight, which only the origins in `DORA_STUDIO_CORS_ORIGIN`
+//! (comma-separated, e.g. where the web build is hosted) pass, so other web
+//! pages cannot drive it.
+//!
+//! It listens on loopback by default; bind another address only on a
+//! trusted network, since chat can run the agent's shell tools.
+
+pub mod auth;
+pub mod events;
+pub mod websocket;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+
+use crate::alerts::{self, AlertEngine, AlertRule};
+use crate::api::{ChatClient, ChatMessage, ChatResponse, MessageRole};
+use crate::chat::prompts;
+use crate::dataflow::client::{CliClient, DoraClient};
+use crate::dataflow::{env, DataflowInfo};
+use crate::otlp::bridge::SignozBridge;
+use crate::otlp::types::TraceQuery;
+use crate::settings::{self, Settings};
+use crate::storage::{query, Storage};
+use crate::{instrument, logging, metrics, tools};
+
+pub const DEFAULT_SERVE_ADDR: &str = "127.0.0.1:7070";
+
+/// Largest request body accepted.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// Largest request line and headers accepted, together.
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+/// Most header lines accepted.
+const MAX_HEADER_LINES: usize = 100;
+/// How long a chat request may take, tool calls included.
+const CHAT_TIMEOUT: Duration = Duration::from_secs(600);
+const CHAT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// How often dataflows and alerts are checked for events while anyone is
+/// subscribed.
+const EVENT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// What a `POST /query` may read, hold and return.
+const QUERY_LIMITS: query::QueryLimits = query::QueryLimits {
+    max_scanned_rows: 1_000_000,
+    max_rows: 10_000,
+    timeout: Some(Duration::from_secs(10)),
+};
+
+/// Address to serve on if the arguments ask for headless mode: `--serve`,
+/// `--serve ADDR` or `--serve=ADDR`.
+pub fn serve_addr(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let mut args = args.into_iter().peekable();
+    while let Some(arg) = args.next() {
+        if let Some(addr) = arg.strip_prefix("--serve=") {
+            return Some(addr.to_string());
+        }
+        if arg == "--serve" {
+            return Some(
+                args.next_if(|next| !next.starts_with('-'))
+                    .unwrap_or_else(|| DEFAULT_SERVE_ADDR.to_string()),
+            );
+        }
+    }
+    None
+}
+
+/// A parsed HTTP request.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    /// Header values by lowercase name
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+}
+
+/// A JSON response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+    /// Extra headers, such as CORS ones
+    pub headers: Vec<(&'static str, String)>,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self {
+            status: 200,
+            body,
+            headers: Vec::new(),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+            headers: Vec::new(),
+        }
+    }
+}
+
+/// Read one request, without keep-alive. Fails with the response to send:
+/// 431 when the request line and headers are over [`MAX_HEADER_BYTES`] or
+/// [`MAX_HEADER_LINES`], 400 when the request is malformed.
+pub fn read_request(reader: &mut impl BufRead) -> Result<Request, Response> {
+    let bad_request = |message: String| Response::error(400, message);
+    let mut budget = MAX_HEADER_BYTES;
+    let mut line = String::new();
+    read_head_line(reader, &mut line, &mut budget)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad_request("Malformed request line".to_string()));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method: method.to_ascii_uppercase(),
+        path: percent_decode(path),
+        query: parse_query(query),
+        ..Default::default()
+    };
+
+    let mut content_length = 0;
+    let mut header_lines = 0;
+    loop {
+        line.clear();
+        read_head_line(reader, &mut line, &mut budget)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        header_lines += 1;
+        if header_lines > MAX_HEADER_LINES {
+            return Err(Response::error(
+                431,
+                format!("More than {} header lines", MAX_HEADER_LINES),
+            ));
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(bad_request(format!("Malformed header '{}'", header)));
+        };
+        let (name, value) = (name.trim().to_ascii_lowercase(), value.trim());
+        if name == "content-length" {
+            content_length = value
+                .parse()
+                .map_err(|_| bad_request(format!("Invalid Content-Length '{}'", value)))?;
+        }
+        request.headers.insert(name, value.to_string());
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err(bad_request(format!(
+            "Request body over {} bytes",
+            MAX_BODY_BYTES
+        )));
+    }
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|e| bad_request(format!("Failed to read request body: {}", e)))?;
+    request.body = String::from_utf8(body)
+        .map_err(|_| bad_request("Request body is not UTF-8".to_string()))?;
+    Ok(request)
+}
+
+/// Read one line of the request head into `line`, taking its length from
+/// `budget`; a 431 response once the head outgrows [`MAX_HEADER_BYTES`].
+fn read_head_line(
+    reader: &mut impl BufRead,
+    line: &mut String,
+    budget: &mut usize,
+) -> Result<(), Response> {
+    // One byte past the budget tells a line that fits exactly from one
+    // that is too long
+    let read = reader
+        .take(*budget as u64 + 1)
+        .read_line(line)
+        .map_err(|e| Response::error(400, format!("Failed to read request: {}", e)))?;
+    if read > *budget {
+        return Err(Response::error(
+            431,
+            format!("Request headers over {} bytes", MAX_HEADER_BYTES),
+        ));
+    }
+    *budget -= read;
+    Ok(())
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+/// Decode `%XX` escapes and `+` (as a space), leaving malformed escapes as
+/// they are.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => match text
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+            {
+                Some(byte) => {
+                    out.push(byte);
+                    i += 3;
+                    continue;
+                }
+                None => out.push(b'%'),
+            },
+            b'+' => out.push(b' '),
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+pub fn write_response(writer: &mut impl Write, response: &Response) -> std::io::Result<()> {
+    let body = response.body.to_string();
+    let headers: String = response
+        .headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}\r\n", name, value))
+        .collect();
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        response.status,
+        reason(response.status),
+        body.len(),
+        headers,
+        body
+    )?;
+    writer.flush()
+}
+
+/// CORS headers letting the request's origin read the response, if it is
+/// one of `allowed`.
+pub fn cors_headers(allowed: &[String], request: &Request) -> Vec<(&'static str, String)> {
+    let Some(origin) = request.header("origin") else {
+        return Vec::new();
+    };
+    if !allowed.iter().any(|a| a == origin) {
+        return Vec::new();
+    }
+    vec![
+        ("Access-Control-Allow-Origin", origin.to_string()),
+        ("Access-Control-Allow-Methods", "GET, POST".to_string()),
+        (
+            "Access-Control-Allow-Headers",
+            "Content-Type, Authorization".to_string(),
+        ),
+        ("Vary", "Origin".to_string()),
+    ]
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        415 => "Unsupported Media Type",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "",
+    }
+}
+
+/// What a request asks for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Route {
+    Health,
+    ListDataflows,
+    StartDataflow,
+    Stop(String),
+    Destroy(String),
+    Nodes(String),
+    Logs(String),
+    Traces,
+    Query,
+    Chat,
+    Events,
+    MethodNotAllowed,
+    NotFound,
+}
+
+pub fn route(method: &str, path: &str) -> Route {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let route = match segments.as_slice() {
+        ["health"] => ("GET", Route::Health),
+        ["dataflows"] if method == "POST" => ("POST", Route::StartDataflow),
+        ["dataflows"] => ("GET", Route::ListDataflows),
+        ["dataflows", uuid, "stop"] => ("POST", Route::Stop(uuid.to_string())),
+        ["dataflows", uuid, "destroy"] => ("POST", Route::Destroy(uuid.to_string())),
+        ["dataflows", uuid, "nodes"] => ("GET", Route::Nodes(uuid.to_string())),
+        ["dataflows", uuid, "logs"] => ("GET", Route::Logs(uuid.to_string())),
+        ["traces"] => ("POST", Route::Traces),
+        ["query"] => ("POST", Route::Query),
+        ["chat"] => ("POST", Route::Chat),
+        ["events"] => ("GET", Route::Events),
+        _ => return Route::NotFound,
+    };
+    match route {
+        (expected, route) if expected == method => route,
+        _ => Route::MethodNotAllowed,
+    }
+}
+
+/// A dataflow as listed by `GET /dataflows`.
+pub fn dataflow_json(df: &DataflowInfo) -> Value {
+    json!({
+        "uuid": df.uuid,
+        "name": df.name,
+        "status": df.status,
+        "nodes": df.nodes,
+        "cpu": df.cpu,
+        "memory_gb": df.memory,
+        "started_at_ms": df.started_at_ms,
+        "uptime_secs": df.uptime_at(logging::now_ms()),
+        "daemon_id": df.daemon_id,
+    })
+}
+
+/// A trace query from `body`, with unset fields at their defaults.
+pub fn trace_query(body: &str) -> Result<TraceQuery, String> {
+    let mut query = serde_json::to_value(TraceQuery::default()).map_err(|e| e.to_string())?;
+    if !body.trim().is_empty() {
+        let fields: Value =
+            serde_json::from_str(body).map_err(|e| format!("Invalid trace query: {}", e))?;
+        let Value::Object(fields) = fields else {
+            return Err("Trace query must be a JSON object".to_string());
+        };
+        if let Value::Object(query) = &mut query {
+            query.extend(fields);
+        }
+    }
+    serde_json::from_value(query).map_err(|e| format!("Invalid trace query: {}", e))
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct StartRequest {
+    path: String,
+    /// Variables set on the dataflow's nodes instead of the saved ones
+    env: Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct QueryRequest {
+    sql: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ChatRequest {
+    /// A single question, sent after `messages`
+    prompt: Option<String>,
+    /// Earlier turns, oldest first
+    messages: Vec<ChatMessage>,
+    /// Prompt template name; the default template when unset
+    template: Option<String>,
+}
+
+fn parse_body<T: Default + for<'de> Deserialize<'de>>(request: &Request) -> Result<T, Response> {
+    if request.body.trim().is_empty() {
+        return Ok(T::default());
+    }
+    serde_json::from_str(&request.body)
+        .map_err(|e| Response::error(400, format!("Invalid request body: {}", e)))
+}
+
+struct Server {
+    settings: Settings,
+    dora: Box<dyn DoraClient>,
+    storage: Option<Storage>,
+    runtime: Runtime,
+    telemetry: SignozBridge,
+    agent: ChatClient,
+    /// The agent answers one chat request at a time
+    chat: Mutex<()>,
+    guard: auth::Guard,
+}
+
+impl Server {
+    fn handle(&self, request: &Request) -> Response {
+        let mut response = match request.method.as_str() {
+            // A CORS preflight, passed only by the headers below
+            "OPTIONS" => Response::ok(json!({})),
+            _ => match self.guard.check(request, !is_public(request)) {
+                Ok(()) => self.respond(request),
+                Err(response) => response,
+            },
+        };
+        response.headers = cors_headers(self.guard.origins(), request);
+        response
+    }
+
+    fn respond(&self, request: &Request) -> Response {
+        let route = route(&request.method, &request.path);
+        let is_json = request
+            .header("content-type")
+            .is_some_and(|t| t.to_ascii_lowercase().starts_with("application/json"));
+        if request.method == "POST" && !is_json {
+            return Response::error(415, "Send POST requests as application/json");
+        }
+        let result = match route {
+            Route::Health => Ok(Response::ok(json!({ "status": "ok" }))),
+            Route::ListDataflows => self.list_dataflows(),
+            Route::StartDataflow => self.start_dataflow(request),
+            Route::Stop(uuid) => self
+                .dora
+                .stop(&uuid)
+                .map(|()| Response::ok(json!({ "uuid": uuid }))),
+            Route::Destroy(uuid) => self
+                .dora
+                .destroy(&uuid)
+                .map(|()| Response::ok(json!({ "uuid": uuid }))),
+            Route::Nodes(uuid) => self
+                .dora
+                .list_nodes(&uuid)
+                .map(|nodes| Response::ok(json!({ "nodes": nodes }))),
+            Route::Logs(uuid) => {
+                let node = request.query.get("node").map(String::as_str);
+                self.dora
+                    .logs(&uuid, node)
+                    .map(|logs| Response::ok(json!({ "logs": logs })))
+            }
+            Route::Traces => return self.query_traces(request),
+            Route::Query => return self.run_query(request),
+            Route::Chat => return self.chat(request),
+            // Upgraded to a WebSocket before it gets here
+            Route::Events => return Response::error(400, "Connect to /events with a WebSocket"),
+            Route::MethodNotAllowed => return Response::error(405, "Method not allowed"),
+            Route::NotFound => return Response::error(404, "Not found"),
+        };
+        result.unwrap_or_else(|e| Response::error(502, e))
+    }
+
+    fn list_dataflows(&self) -> Result<Response, String> {
+        let dataflows = self.dora.list_dataflows()?;
+        metrics::set_active_dataflows(
+            dataflows
+                .iter()
+                .filter(|df| df.is_running())
+                .map(|df| df.uuid.clone()),
+        );
+        let dataflows: Vec<Value> = dataflows.iter().map(dataflow_json).collect();
+        Ok(Response::ok(json!({ "dataflows": dataflows })))
+    }
+
+    fn start_dataflow(&self, request: &Request) -> Result<Response, String> {
+        let start: StartRequest = match parse_body(request) {
+            Ok(start) => start,
+            Err(response) => return Ok(response),
+        };
+        if start.path.trim().is_empty() {
+            return Ok(Response::error(400, "Missing dataflow \"path\""));
+        }
+        let path = start.path.trim();
+        let vars = match start.env {
+            Some(env) => env.into_iter().collect(),
+            None => self.settings.dataflow_env(path),
+        };
+        let uuid = self.dora.start(&env::prepare_start(path, &vars)?)?;
+        Ok(Response::ok(json!({ "uuid": uuid })))
+    }
+
+    fn query_traces(&self, request: &Request) -> Response {
+        let query = match trace_query(&request.body) {
+            Ok(query) => query,
+            Err(e) => return Response::error(400, e),
+        };
+        match self.runtime.block_on(self.telemetry.query_traces(query)) {
+            Ok(spans) => Response::ok(json!({ "spans": spans })),
+            Err(e) => Response::error(502, e),
+        }
+    }
+
+    fn run_query(&self, request: &Request) -> Response {
+        let QueryRequest { sql } = match parse_body(request) {
+            Ok(query) => query,
+            Err(response) => return response,
+        };
+        let Some(storage) = &self.storage else {
+            return Response::error(503, "Local storage is unavailable");
+        };
+        match query::run_query_limited(storage, &sql, &QUERY_LIMITS) {
+            Ok(result) => Response::ok(json!({
+                "columns": result.columns,
+                "rows": result.rows,
+            })),
+            Err(e) => Response::error(400, e.to_string()),
+        }
+    }
+
+    fn chat(&self, request: &Request) -> Response {
+        let ChatRequest {
+            prompt,
+            mut messages,
+            template,
+        } = match parse_body(request) {
+            Ok(chat) => chat,
+            Err(response) => return response,
+        };
+        if let Some(prompt) = prompt {
+            messages.push(ChatMessage {
+                role: MessageRole::User,
+                content: prompt,
+                model: None,
+                tool_steps: Vec::new(),
+            });
+        }
+        if messages.last().is_none_or(|m| m.role != MessageRole::User) {
+            return Response::error(
+                400,
+                "Send a \"prompt\" or end \"messages\" with a user turn",
+            );
+        }
+        let templates = prompts::templates(&self.settings.prompt_templates);
+        let system = prompts::system_prompt(&templates, template.as_deref());
+
+        // The lock only orders turns, so one that panicked leaves nothing to
+        // repair
+        let _turn = self.chat.lock().unwrap_or_else(|e| e.into_inner());
+        let model = self.agent.model();
+        self.agent.submit_chat_request(messages, system);
+        let deadline = Instant::now() + CHAT_TIMEOUT;
+        let reply = loop {
+            if let Some(reply) = self.agent.take_pending_response() {
+                break reply;
+            }
+            if Instant::now() >= deadline {
+                self.agent.cancel_chat_request();
+                return Response::error(504, "Chat request timed out");
+            }
+            std::thread::sleep(CHAT_POLL_INTERVAL);
+        };
+        let tool_steps = self.agent.take_tool_steps();
+        match reply {
+            ChatResponse::Message(text) | ChatResponse::ToolExecution(text) => {
+                Response::ok(json!({
+                    "reply": text,
+                    "model": model,
+                    "tool_steps": tool_steps,
+                }))
+            }
+            ChatResponse::Error(e) => Response::error(502, e),
+        }
+    }
+}
+
+/// Whether `request` is served without the API token.
+fn is_public(request: &Request) -> bool {
+    route(&request.method, &request.path) == Route::Health
+}
+
+fn handle_connection(server: &Server, stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(e) => {
+            tracing::warn!(target: "Serve", "Failed to accept connection: {}", e);
+            return;
+        }
+    };
+    let mut reader = BufReader::new(stream);
+    let response = match read_request(&mut reader) {
+        Ok(request) if route(&request.method, &request.path) == Route::Events => {
+            match server.guard.check_events(&request) {
+                Ok(()) => return websocket::serve_events(writer, &request),
+                Err(response) => response,
+            }
+        }
+        Ok(request) => {
+            let mut span = instrument::span(&format!("{} {}", request.method, request.path));
+            let response = server.handle(&request);
+            span.attr("http.response.status_code", response.status as i64);
+            tracing::info!(
+                target: "Serve",
+                "{} {} → {}",
+                request.method,
+                request.path,
+                response.status
+            );
+            response
+        }
+        Err(response) => response,
+    };
+    if let Err(e) = write_response(&mut writer, &response) {
+        tracing::debug!(target: "Serve", "Failed to send response: {}", e);
+    }
+}
+
+/// Check dataflows and alert rules for [`events`] while anyone is
+/// subscribed.
+fn watch_events(server: &Server) {
+    let rules: Vec<AlertRule> = server
+        .settings
+        .alert_rules
+        .iter()
+        .filter_map(|rule| {
+            AlertRule::parse(rule)
+                .map_err(|e| tracing::warn!(target: "Serve", "Skipping alert rule: {}", e))
+                .ok()
+        })
+        .collect();
+    let mut watcher = events::EventWatcher::new();
+    let mut alerts = AlertEngine::new();
+    loop {
+        std::thread::sleep(EVENT_POLL_INTERVAL);
+        if !events::has_subscribers() {
+            continue;
+        }
+        match server.dora.list_dataflows() {
+            Ok(dataflows) => watcher.observe(&*server.dora, &dataflows),
+            Err(e) => tracing::debug!(target: "Serve", "Listing dataflows failed: {}", e),
+        }
+        if rules.is_empty() {
+            continue;
+        }
+        let now = logging::now_ms();
+        let query = alerts::window_query(&rules, now);
+        if let Ok(spans) = server
+            .runtime
+            .block_on(server.telemetry.query_traces(query))
+        {
+            for event in alerts.evaluate(&rules, &spans, now) {
+                events::publish(&(&event).into());
+            }
+        }
+    }
+}
+
+/// Start the subsystems as the app does and serve the API on `addr` until
+/// the process is stopped.
+pub fn run(addr: &str) -> Result<(), String> {
+    let settings = Settings::load();
+    logging::init(settings::log_dir(), settings.log_level);
+    tools::set_dora_path(settings.dora_path.as_deref());
+    if let Err(e) = tools::set_ssh_host(settings.ssh_host.as_deref()) {
+        tracing::warn!(target: "Serve", "Running dora locally: {}", e);
+    }
+    if let Err(e) = tools::set_coordinator_addr(settings.coordinator_addr.as_deref()) {
+        tracing::warn!(target: "Serve", "Using the default coordinator: {}", e);
+    }
+    if let Some(endpoint) = instrument::start_exporter_from_env() {
+        tracing::info!(target: "Serve", "Exporting studio spans to {}", endpoint);
+    }
+
+    let telemetry = SignozBridge::start(settings.telemetry_backend.clone());
+    let agent = ChatClient::start(Some(telemetry.clone()));
+    agent.init_api_key_from_env();
+    agent.set_backend(
+        settings.llm_backend,
+        settings.local_llm_url.as_deref().unwrap_or(""),
+    );
+    if let Some(model) = &settings.chat_model {
+        agent.set_model(model);
+    }
+    agent.set_saved_trace_queries(&settings.saved_trace_queries);
+
+    let storage = settings::data_dir().and_then(|d| {
+        Storage::open(&d.join("storage"))
+            .map_err(|e| tracing::warn!(target: "Serve", "Storage unavailable: {}", e))
+            .ok()
+    });
+    metrics::start_sampler(storage.clone());
+
+    let runtime = Runtime::new().map_err(|e| format!("Failed to start runtime: {}", e))?;
+    let token = auth::issue_token()?;
+    let server = Arc::new(Server {
+        dora: Box::new(CliClient),
+        settings,
+        storage,
+        runtime,
+        telemetry,
+        agent,
+        chat: Mutex::new(()),
+        guard: auth::Guard::new(token, addr, auth::cors_origins_from_env()),
+    });
+    let watched = Arc::clone(&server);
+    std::thread::spawn(move || watch_events(&watched));
+
+    let listener =
+        TcpListener::bind(addr).map_err(|e| format!("Failed to listen on {}: {}", addr, e))?;
+    tracing::info!(target: "Serve", "Serving the API on http://{}", addr);
+    if let Some(path) = auth::token_path() {
+        tracing::info!(target: "Serve", "API token in {}", path.display());
+    }
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let server = Arc::clone(&server);
+                std::thread::spawn(move || handle_connection(&server, stream));
+            }
+            Err(e) => tracing::warn!(target: "Serve", "Failed to accept connection: {}", e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_serve_addr() {
+        assert_eq!(serve_addr(args(&[])), None);
+        assert_eq!(
+            serve_addr(args(&["--serve"])).as_deref(),
+            Some(DEFAULT_SERVE_ADDR)
+        );
+        assert_eq!(
+            serve_addr(args(&["--serve", "0.0.0.0:80"])).as_deref(),
+            Some("0.0.0.0:80")
+        );
+        assert_eq!(
+            serve_addr(args(&["--serve", "--verbose"])).as_deref(),
+            Some(DEFAULT_SERVE_ADDR)
+        );
+        assert_eq!(
+            serve_addr(args(&["--serve=:8000"])).as_deref(),
+            Some(":8000")
+        );
+    }
+
+    #[test]
+    fn test_read_request() {
+        let raw = "POST /dataflows/a%20b/logs?node=cam%2D1&x HTTP/1.1\r\n\
+                   Host: localhost\r\n\
+                   Content-Type: application/JSON\r\n\
+                   Content-Length: 13\r\n\r\n\
+                   {\"path\":\"x\"}\nignored";
+        let request = read_request(&mut Cursor::new(raw)).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/dataflows/a b/logs");
+        assert_eq!(request.query["node"], "cam-1");
+        assert_eq!(request.query["x"], "");
+        assert_eq!(request.header("content-type"), Some("application/JSON"));
+        assert_eq!(request.header("host"), Some("localhost"));
+        assert_eq!(request.body, "{\"path\":\"x\"}\n");
+
+        assert!(read_request(&mut Cursor::new("\r\n")).is_err());
+        let too_big = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY_BYTES + 1
+        );
+        assert!(read_request(&mut Cursor::new(too_big)).is_err());
+
+        let status = |raw: String| read_request(&mut Cursor::new(raw)).unwrap_err().status;
+        let long_header = format!(
+            "GET / HTTP/1.1\r\nX-Pad: {}\r\n\r\n",
+            "a".repeat(MAX_HEADER_BYTES)
+        );
+        assert_eq!(status(long_header), 431);
+        let many_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X-Pad: a\r\n".repeat(MAX_HEADER_LINES + 1)
+        );
+        assert_eq!(status(many_headers), 431);
+        assert_eq!(status("GET\r\n\r\n".to_string()), 400);
+    }
+
+    #[test]
+    fn test_route() {
+        assert_eq!(route("GET", "/health"), Route::Health);
+        assert_eq!(route("GET", "/dataflows/"), Route::ListDataflows);
+        assert_eq!(route("POST", "/dataflows"), Route::StartDataflow);
+        assert_eq!(
+            route("POST", "/dataflows/u1/stop"),
+            Route::Stop("u1".to_string())
+        );
+        assert_eq!(
+            route("GET", "/dataflows/u1/logs"),
+            Route::Logs("u1".to_string())
+        );
+        assert_eq!(route("GET", "/dataflows/u1/stop"), Route::MethodNotAllowed);
+        assert_eq!(route("DELETE", "/dataflows"), Route::MethodNotAllowed);
+        assert_eq!(route("GET", "/chat"), Route::MethodNotAllowed);
+        assert_eq!(route("GET", "/events"), Route::Events);
+        assert_eq!(route("GET", "/nope"), Route::NotFound);
+    }
+
+    #[test]
+    fn test_trace_query_defaults_unset_fields() {
+        let query = trace_query(r#"{"service_name": "camera", "limit": 5}"#).unwrap();
+        assert_eq!(query.service_name.as_deref(), Some("camera"));
+        assert_eq!(query.limit, Some(5));
+        assert!(query.tags.is_empty());
+        assert!(trace_query("").is_ok());
+        assert!(trace_query("[1]").is_err());
+    }
+
+    #[test]
+    fn test_write_response() {
+        let mut out = Vec::new();
+        write_response(&mut out, &Response::error(404, "Not found")).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(out.contains("Content-Length: 21\r\n"));
+        assert!(out.ends_with("\r\n\r\n{\"error\":\"Not found\"}"));
+    }
+
+    #[test]
+    fn test_cors_headers() {
+        let allowed = vec!["http://localhost:8010".to_string()];
+        let mut request = Request::default();
+        assert!(cors_headers(&allowed, &request).is_empty());
+        request
+            .headers
+            .insert("origin".to_string(), "http://evil.example".to_string());
+        assert!(cors_headers(&allowed, &request).is_empty());
+        request
+            .headers
+            .insert("origin".to_string(), "http://localhost:8010".to_string());
+        let headers = cors_headers(&allowed, &request);
+        assert_eq!(
+            headers[0],
+            (
+                "Access-Control-Allow-Origin",
+                "http://localhost:8010".to_string()
+            )
+        );
+
+        let mut out = Vec::new();
+        let mut response = Response::ok(json!({}));
+        response.headers = headers;
+        write_response(&mut out, &response).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("\r\nVary: Origin\r\nConnection: close\r\n"));
+    }
+}
@@ -0,0 +1,329 @@
+//! `GET /events` WebSocket endpoint streaming [`super::events`] as JSON text
+//! messages, e.g. `{"type": "dataflow_status", "uuid": .., "status": ..}`.
+//!
+//! `?types=dataflow_status,alert` limits the stream to those event types.
+//! The server only sends; pings from the client are answered, other client
+//! messages are ignored. Besides `--serve`, the app serves this endpoint
+//! alone on `DORA_STUDIO_EVENTS_ADDR` when set (see [`listen_from_env`]).
+//! Either way the upgrade is checked by [`auth::Guard::check_events`]: the
+//! API token as `?token=`, and `Origin`, if sent, among the CORS origins.
+//!
+//! Only the handshake and unfragmented frames are implemented, which is all
+//! an event stream needs; SHA-1 and base64 for the handshake are done here
+//! rather than pulling in crates for them.
+
+use std::io::{self, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{auth, events, read_request, route, write_response, Request, Response, Route};
+
+/// Appended to the client's key to answer the handshake (RFC 6455).
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+/// Pings keep idle connections open through proxies.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+/// Largest client frame read; the client has nothing large to send.
+const MAX_CLIENT_FRAME: u64 = 64 * 1024;
+
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+/// `Sec-WebSocket-Accept` for a client's `Sec-WebSocket-Key`.
+pub fn accept_key(key: &str) -> String {
+    base64(&sha1(
+        format!("{}{}", key.trim(), HANDSHAKE_GUID).as_bytes(),
+    ))
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (word, bytes) in w.iter_mut().zip(block.chunks(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (bytes, state) in digest.chunks_mut(4).zip(h) {
+        bytes.copy_from_slice(&state.to_be_bytes());
+    }
+    digest
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | ((b as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// A server frame: final, unmasked.
+pub fn encode_frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len if len < 126 => frame.push(len as u8),
+        len if len <= u16::MAX as usize => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Read a client frame, returning its opcode and unmasked payload.
+pub fn read_frame(reader: &mut impl Read) -> io::Result<(u8, Vec<u8>)> {
+    let mut head = [0u8; 2];
+    reader.read_exact(&mut head)?;
+    let opcode = head[0] & 0x0F;
+    let len = match head[1] & 0x7F {
+        126 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_be_bytes(len) as u64
+        }
+        127 => {
+            let mut len = [0u8; 8];
+            reader.read_exact(&mut len)?;
+            u64::from_be_bytes(len)
+        }
+        len => len as u64,
+    };
+    if len > MAX_CLIENT_FRAME {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Client frame of {} bytes is too large", len),
+        ));
+    }
+    let mut mask = [0u8; 4];
+    if head[1] & 0x80 != 0 {
+        reader.read_exact(&mut mask)?;
+    }
+    let mut payload = vec![0u8; len as usize];
+    reader.read_exact(&mut payload)?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+    Ok((opcode, payload))
+}
+
+/// Event types asked for with `?types=`; all when absent or empty.
+fn event_kinds(request: &Request) -> Option<Vec<String>> {
+    let kinds: Vec<String> = request
+        .query
+        .get("types")?
+        .split(',')
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty())
+        .collect();
+    (!kinds.is_empty()).then_some(kinds)
+}
+
+/// Answer the handshake on `stream` and stream events until the client goes
+/// away.
+pub fn serve_events(mut stream: TcpStream, request: &Request) {
+    let upgrade = request
+        .header("upgrade")
+        .is_some_and(|u| u.eq_ignore_ascii_case("websocket"));
+    let Some(key) = request.header("sec-websocket-key").filter(|_| upgrade) else {
+        let response = Response::error(400, "Connect to /events with a WebSocket");
+        let _ = write_response(&mut stream, &response);
+        return;
+    };
+    let handshake = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(key)
+    );
+    if stream.write_all(handshake.as_bytes()).is_err() {
+        return;
+    }
+    let _ = stream.set_read_timeout(None);
+    let Ok(reader) = stream.try_clone() else {
+        return;
+    };
+    let events = events::subscribe(event_kinds(request));
+    let writer = Arc::new(Mutex::new(stream));
+    let send = |writer: &Mutex<TcpStream>, opcode, payload: &[u8]| {
+        writer
+            .lock()
+            .unwrap()
+            .write_all(&encode_frame(opcode, payload))
+    };
+
+    // Answer pings and the close handshake; closing the socket ends the
+    // writer loop below
+    let replies = Arc::clone(&writer);
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        while let Ok((opcode, payload)) = read_frame(&mut reader) {
+            match opcode {
+                OPCODE_PING => {
+                    let _ = send(&replies, OPCODE_PONG, &payload);
+                }
+                OPCODE_CLOSE => {
+                    let _ = send(&replies, OPCODE_CLOSE, &payload);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let _ = replies.lock().unwrap().shutdown(Shutdown::Both);
+    });
+
+    tracing::info!(target: "Serve", "Event stream subscriber connected");
+    loop {
+        let sent = match events.recv_timeout(PING_INTERVAL) {
+            Ok(event) => send(&writer, OPCODE_TEXT, event.as_bytes()),
+            Err(RecvTimeoutError::Timeout) => send(&writer, OPCODE_PING, &[]),
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        if sent.is_err() {
+            break;
+        }
+    }
+    let _ = writer.lock().unwrap().shutdown(Shutdown::Both);
+    tracing::info!(target: "Serve", "Event stream subscriber disconnected");
+}
+
+/// Serve only the event stream on `addr`, on a background thread.
+pub fn listen(addr: &str, guard: auth::Guard) -> Result<(), String> {
+    let listener =
+        TcpListener::bind(addr).map_err(|e| format!("Failed to listen on {}: {}", addr, e))?;
+    let guard = Arc::new(guard);
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let guard = Arc::clone(&guard);
+            std::thread::spawn(move || {
+                let Ok(writer) = stream.try_clone() else {
+                    return;
+                };
+                let mut reader = BufReader::new(stream);
+                let response = match read_request(&mut reader) {
+                    Ok(request) if route(&request.method, &request.path) == Route::Events => {
+                        match guard.check_events(&request) {
+                            Ok(()) => return serve_events(writer, &request),
+                            Err(response) => response,
+                        }
+                    }
+                    Ok(_) => Response::error(404, "Only /events is served alongside the app"),
+                    Err(response) => response,
+                };
+                let mut writer = writer;
+                let _ = write_response(&mut writer, &response);
+            });
+        }
+    });
+    Ok(())
+}
+
+/// Serve the event stream alongside the app on `DORA_STUDIO_EVENTS_ADDR`,
+/// if set. Returns the address served on.
+pub fn listen_from_env() -> Option<String> {
+    let addr = std::env::var("DORA_STUDIO_EVENTS_ADDR")
+        .ok()
+        .filter(|a| !a.trim().is_empty())?;
+    let guard = match auth::issue_token() {
+        Ok(token) => auth::Guard::new(token, addr.trim(), auth::cors_origins_from_env()),
+        Err(e) => {
+            tracing::warn!(target: "Serve", "Not serving events: {}", e);
+            return None;
+        }
+    };
+    match listen(addr.trim(), guard) {
+        Ok(()) => Some(addr),
+        Err(e) => {
+            tracing::warn!(target: "Serve", "{}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_accept_key() {
+        // Example handshake from RFC 6455, section 1.3
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+        assert_eq!(base64(b"a"), "YQ==");
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"abc"), "YWJj");
+    }
+
+    #[test]
+    fn test_frames() {
+        assert_eq!(encode_frame(OPCODE_TEXT, b"hi"), [0x81, 2, b'h', b'i']);
+        let long = encode_frame(OPCODE_TEXT, &[0; 300]);
+        assert_eq!(long[..4], [0x81, 126, 1, 44]);
+
+        // A masked "Hello" from a client (RFC 6455, section 5.7)
+        let frame = [
+            0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58,
+        ];
+        let (opcode, payload) = read_frame(&mut Cursor::new(frame)).unwrap();
+        assert_eq!(opcode, OPCODE_TEXT);
+        assert_eq!(payload, b"Hello");
+
+        let huge = [0x81, 127, 0, 0, 0, 1, 0, 0, 0, 0];
+        assert!(read_frame(&mut Cursor::new(huge)).is_err());
+    }
+}
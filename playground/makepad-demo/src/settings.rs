@@ -0,0 +1,467 @@
+//! Persisted user settings.
+//!
+//! Settings live in `settings.json` inside the per-user data directory. Missing
+//! or unreadable files fall back to defaults so a corrupt file never blocks
+//! startup.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::api::LlmBackend;
+use crate::chat::prompts::PromptTemplate;
+use crate::dataflow::highlight::HighlightRule;
+use crate::dataflow::liveness::DEFAULT_STALE_AFTER_MINUTES;
+use crate::dataflow::log_buffer::DEFAULT_LOG_BUFFER_LINES;
+use crate::dataflow::DataflowSort;
+use crate::datetime::{DisplayZone, TimeStyle};
+use crate::i18n::Locale;
+use crate::layout::{LayoutPreset, Session, WindowGeometry};
+use crate::logging::Level;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::otlp::BackendConfig;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::traces::SavedTraceQuery;
+use crate::units::Unit;
+
+const APP_DIR_NAME: &str = "dora-studio";
+
+/// Data directory the test harness keeps everything in.
+#[cfg(any(test, feature = "test-harness"))]
+static DATA_DIR_OVERRIDE: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+const SETTINGS_FILE: &str = "settings.json";
+const MAX_RECENT_DATAFLOWS: usize = 10;
+const MAX_QUERY_HISTORY: usize = 20;
+
+/// Resolve the per-user data directory.
+///
+/// `DORA_STUDIO_DATA_DIR` overrides the platform default:
+/// - Linux: `$XDG_DATA_HOME/dora-studio` or `~/.local/share/dora-studio`
+/// - macOS: `~/Library/Application Support/dora-studio`
+/// - Windows: `%APPDATA%\dora-studio`
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = data_dir_override() {
+        return Some(dir);
+    }
+
+    if cfg!(target_os = "windows") {
+        env_path("APPDATA").map(|p| p.join(APP_DIR_NAME))
+    } else if cfg!(target_os = "macos") {
+        env_path("HOME").map(|p| {
+            p.join("Library")
+                .join("Application Support")
+                .join(APP_DIR_NAME)
+        })
+    } else {
+        env_path("XDG_DATA_HOME")
+            .or_else(|| env_path("HOME").map(|p| p.join(".local").join("share")))
+            .map(|p| p.join(APP_DIR_NAME))
+    }
+}
+
+/// Resolve the directory log files are written to: `logs` under
+/// [`data_dir`], except on Windows without `DORA_STUDIO_DATA_DIR`, where
+/// logs go to `%LOCALAPPDATA%\dora-studio\logs` so they stay out of the
+/// roaming profile.
+pub fn log_dir() -> Option<PathBuf> {
+    if cfg!(target_os = "windows") && data_dir_override().is_none() {
+        if let Some(local) = env_path("LOCALAPPDATA") {
+            return Some(local.join(APP_DIR_NAME).join("logs"));
+        }
+    }
+    data_dir().map(|d| d.join("logs"))
+}
+
+/// Use `dir` as the data directory for the rest of the process, ahead of
+/// `DORA_STUDIO_DATA_DIR`; later calls are ignored.
+#[cfg(any(test, feature = "test-harness"))]
+pub fn override_data_dir(dir: PathBuf) {
+    let _ = DATA_DIR_OVERRIDE.set(dir);
+}
+
+/// The directory set with [`override_data_dir`], else `DORA_STUDIO_DATA_DIR`.
+fn data_dir_override() -> Option<PathBuf> {
+    #[cfg(any(test, feature = "test-harness"))]
+    {
+        if let Some(dir) = DATA_DIR_OVERRIDE.get() {
+            return Some(dir.clone());
+        }
+    }
+    env_path("DORA_STUDIO_DATA_DIR")
+}
+
+fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var(name)
+        .ok()
+        .filter(|s| !s.is_empty())
+        .map(PathBuf::from)
+}
+
+/// User-configurable settings persisted across sessions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Minimum level written to the internal log files.
+    pub log_level: Level,
+    /// Recently started dataflow YAML paths, most recent first.
+    pub recent_dataflows: Vec<String>,
+    /// Show OS notifications for dataflow failures and lost SigNoz
+    /// connections while the window is in the background.
+    pub desktop_notifications: bool,
+    /// Alert rule expressions, e.g. `p99 > 500ms over 5m for camera`.
+    pub alert_rules: Vec<String>,
+    /// Last window geometry per monitor configuration (see
+    /// [`crate::layout::monitor_key`]).
+    pub window_geometry: BTreeMap<String, WindowGeometry>,
+    /// User-saved layout presets.
+    pub layout_presets: Vec<LayoutPreset>,
+    /// Keyboard shortcut overrides by action id, e.g. `"refresh": "F5"`; an
+    /// empty chord disables the shortcut (see [`crate::shortcuts`]).
+    pub shortcuts: BTreeMap<String, String>,
+    /// Minutes without telemetry activity before a running dataflow is
+    /// shown as stale; 0 disables the check.
+    pub stale_after_minutes: u64,
+    /// Splitter pane sizes in logical pixels (see [`crate::layout::split_size`]).
+    pub split_sizes: BTreeMap<String, f64>,
+    /// Unit overrides by metric name where the name does not imply the
+    /// unit, e.g. `"frame_time": "milliseconds"` (see [`crate::units`]).
+    pub metric_units: BTreeMap<String, Unit>,
+    /// View state from the last session (see [`crate::layout::Session`]).
+    pub session: Session,
+    /// Coordinator control address, e.g. `10.0.0.2:6012`, passed to `dora`
+    /// commands; the CLI's default when unset (see
+    /// [`crate::tools::set_coordinator_addr`]).
+    pub coordinator_addr: Option<String>,
+    /// SQL run in the Query panel, most recent first.
+    pub query_history: Vec<String>,
+    /// Hours of data kept per local storage table, e.g. `"logs": 24`; 0 keeps
+    /// a table forever (see [`crate::storage::retention`]).
+    pub retention_hours: BTreeMap<String, u64>,
+    /// Path of the dora executable; `dora` on the `PATH` when unset.
+    pub dora_path: Option<String>,
+    /// Host to run `dora` commands on over SSH, as `[user@]host[:port]`;
+    /// this machine when unset (see [`crate::tools::ssh`]). Pair it with
+    /// `coordinator_addr` to manage a headless robot.
+    pub ssh_host: Option<String>,
+    /// Traces table columns in order, e.g. `service` or `attr:node.id`; the
+    /// default columns when empty (see [`crate::traces::columns`]).
+    pub trace_columns: Vec<String>,
+    /// Trace filters saved by name in the traces panel, also runnable by the
+    /// chat agent (see [`crate::traces::saved`]).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub saved_trace_queries: Vec<SavedTraceQuery>,
+    /// Chat system prompt templates added or edited by the user; they
+    /// replace built-ins of the same name (see [`crate::chat::prompts`]).
+    pub prompt_templates: Vec<PromptTemplate>,
+    /// Model picked for the chat agent; [`crate::api::DEFAULT_MODEL`] when
+    /// unset.
+    pub chat_model: Option<String>,
+    /// Where the chat agent's requests go: the Claude API or a local
+    /// inference server (see [`crate::api::LlmBackend`]).
+    pub llm_backend: LlmBackend,
+    /// Base URL of the local inference server, e.g. `http://127.0.0.1:8080`;
+    /// [`crate::api::DEFAULT_LOCAL_URL`] when unset.
+    pub local_llm_url: Option<String>,
+    /// Dataflow table order picked by clicking a column header; the order
+    /// dora lists them in when unset.
+    pub dataflow_sort: Option<DataflowSort>,
+    /// Environment variables set on every node when a dataflow starts, by
+    /// dataflow path (see [`crate::dataflow::env`]).
+    pub dataflow_env: BTreeMap<String, Vec<(String, String)>>,
+    /// Log viewer highlight rules, first match first (see
+    /// [`crate::dataflow::highlight`]).
+    pub log_highlights: Vec<HighlightRule>,
+    /// Log lines the log viewer holds in memory; older ones are spilled to
+    /// local storage (see [`crate::dataflow::log_buffer`]).
+    pub log_buffer_lines: usize,
+    /// UI language (see [`crate::i18n`]).
+    pub language: Locale,
+    /// Size of text and rows in percent, 80–200 (see [`crate::layout`]).
+    pub ui_scale_percent: u32,
+    /// Zone timestamps are shown in (see [`crate::datetime`]).
+    pub time_zone: DisplayZone,
+    /// Whether trace times read `5m ago` or as a clock time.
+    pub time_style: TimeStyle,
+    /// Telemetry backend to query, e.g. `{"backend": "zipkin", "url":
+    /// "http://localhost:9411"}`; read from the environment variables when
+    /// unset (see [`crate::otlp::bridge`]). Edits to the file apply without
+    /// a restart.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub telemetry_backend: Option<BackendConfig>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            log_level: Level::Info,
+            recent_dataflows: Vec::new(),
+            desktop_notifications: true,
+            alert_rules: Vec::new(),
+            window_geometry: BTreeMap::new(),
+            layout_presets: Vec::new(),
+            shortcuts: BTreeMap::new(),
+            stale_after_minutes: DEFAULT_STALE_AFTER_MINUTES,
+            split_sizes: BTreeMap::new(),
+            metric_units: BTreeMap::new(),
+            session: Session::default(),
+            coordinator_addr: None,
+            query_history: Vec::new(),
+            retention_hours: BTreeMap::new(),
+            dora_path: None,
+            ssh_host: None,
+            trace_columns: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            saved_trace_queries: Vec::new(),
+            prompt_templates: Vec::new(),
+            chat_model: None,
+            llm_backend: LlmBackend::Anthropic,
+            local_llm_url: None,
+            dataflow_sort: None,
+            dataflow_env: BTreeMap::new(),
+            log_highlights: Vec::new(),
+            log_buffer_lines: DEFAULT_LOG_BUFFER_LINES,
+            language: Locale::En,
+            ui_scale_percent: 100,
+            time_zone: DisplayZone::Utc,
+            time_style: TimeStyle::Relative,
+            #[cfg(not(target_arch = "wasm32"))]
+            telemetry_backend: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Path of the settings file, if a data directory is available.
+    pub fn path() -> Option<PathBuf> {
+        data_dir().map(|d| d.join(SETTINGS_FILE))
+    }
+
+    /// Load settings from disk, falling back to defaults.
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .map(|text| Self::from_json(&text))
+            .unwrap_or_default()
+    }
+
+    /// Parse settings JSON, falling back to defaults on error.
+    pub fn from_json(text: &str) -> Self {
+        serde_json::from_str(text).unwrap_or_default()
+    }
+
+    /// Record a started dataflow path, moving it to the front of the recent list.
+    pub fn push_recent_dataflow(&mut self, path: &str) {
+        let path = path.trim();
+        if path.is_empty() {
+            return;
+        }
+        self.recent_dataflows.retain(|p| p != path);
+        self.recent_dataflows.insert(0, path.to_string());
+        self.recent_dataflows.truncate(MAX_RECENT_DATAFLOWS);
+    }
+
+    /// Environment variables for the dataflow at `path`.
+    pub fn dataflow_env(&self, path: &str) -> Vec<(String, String)> {
+        self.dataflow_env
+            .get(path.trim())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Set the environment variables for the dataflow at `path`, forgetting
+    /// the dataflow when there are none.
+    pub fn set_dataflow_env(&mut self, path: &str, vars: Vec<(String, String)>) {
+        let path = path.trim();
+        if vars.is_empty() {
+            self.dataflow_env.remove(path);
+        } else if !path.is_empty() {
+            self.dataflow_env.insert(path.to_string(), vars);
+        }
+    }
+
+    /// Record a query run in the Query panel, moving it to the front of the history.
+    pub fn push_query_history(&mut self, sql: &str) {
+        let sql = sql.trim();
+        if sql.is_empty() {
+            return;
+        }
+        self.query_history.retain(|q| q != sql);
+        self.query_history.insert(0, sql.to_string());
+        self.query_history.truncate(MAX_QUERY_HISTORY);
+    }
+
+    /// Write settings to disk, creating the data directory if needed.
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path().ok_or("No data directory available")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        std::fs::write(&path, json).map_err(|e| format!("Failed to write settings: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataflow::SortColumn;
+    use crate::otlp::ZipkinConfig;
+    use crate::traces::TraceFilter;
+
+    #[test]
+    fn test_settings_default() {
+        let settings = Settings::default();
+        assert_eq!(settings.log_level, Level::Info);
+        assert!(settings.desktop_notifications);
+    }
+
+    #[test]
+    fn test_settings_serde_roundtrip() {
+        let settings = Settings {
+            log_level: Level::Debug,
+            recent_dataflows: vec!["/tmp/dataflow.yml".to_string()],
+            desktop_notifications: false,
+            alert_rules: vec!["p99 > 500ms over 5m".to_string()],
+            window_geometry: BTreeMap::from([(
+                "dpi-2.00".to_string(),
+                WindowGeometry {
+                    width: 1440.0,
+                    height: 900.0,
+                    x: 40.0,
+                    y: 20.0,
+                },
+            )]),
+            layout_presets: vec![LayoutPreset {
+                name: "desk".to_string(),
+                panel: "traces".to_string(),
+                drawer_open: true,
+                window: None,
+                size_only: false,
+            }],
+            shortcuts: BTreeMap::from([("refresh".to_string(), "F5".to_string())]),
+            stale_after_minutes: 10,
+            split_sizes: BTreeMap::from([("chat".to_string(), 240.0)]),
+            metric_units: BTreeMap::from([("frame_time".to_string(), Unit::Milliseconds)]),
+            session: Session {
+                panel: "traces".to_string(),
+                drawer_open: true,
+                snapped_run: Some("0191".to_string()),
+            },
+            coordinator_addr: Some("10.0.0.2:6012".to_string()),
+            query_history: vec!["SELECT count(*) FROM logs".to_string()],
+            retention_hours: BTreeMap::from([("spans".to_string(), 168)]),
+            dora_path: Some("/opt/dora/bin/dora".to_string()),
+            ssh_host: Some("robot@10.0.0.2".to_string()),
+            trace_columns: vec!["service".to_string(), "attr:node.id".to_string()],
+            saved_trace_queries: vec![SavedTraceQuery {
+                name: "slow camera".to_string(),
+                filter: TraceFilter {
+                    service_name: Some("camera".to_string()),
+                    last_minutes: Some(60),
+                    tags: BTreeMap::from([("node.id".to_string(), "cam0".to_string())]),
+                    ..Default::default()
+                },
+            }],
+            prompt_templates: vec![PromptTemplate {
+                name: "Pipeline author".to_string(),
+                prompt: "Write dataflow YAML.".to_string(),
+            }],
+            chat_model: Some("claude-opus-4-20250514".to_string()),
+            llm_backend: LlmBackend::Local,
+            local_llm_url: Some("http://10.0.0.2:8080".to_string()),
+            dataflow_sort: Some(DataflowSort {
+                column: SortColumn::Started,
+                descending: true,
+            }),
+            dataflow_env: BTreeMap::from([(
+                "/tmp/dataflow.yml".to_string(),
+                vec![("RUST_LOG".to_string(), "debug".to_string())],
+            )]),
+            log_highlights: vec![HighlightRule {
+                pattern: "timeout|dropped".to_string(),
+                color: "Orange".to_string(),
+            }],
+            log_buffer_lines: 20_000,
+            language: Locale::Zh,
+            ui_scale_percent: 125,
+            time_zone: DisplayZone::Local,
+            time_style: TimeStyle::Absolute,
+            telemetry_backend: Some(BackendConfig::Zipkin(ZipkinConfig {
+                url: "http://localhost:9411".to_string(),
+                timeout_secs: 30,
+            })),
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+        assert!(json.contains("\"log_level\":\"debug\""));
+        assert_eq!(Settings::from_json(&json), settings);
+    }
+
+    #[test]
+    fn test_settings_missing_fields_use_defaults() {
+        let settings = Settings::from_json("{}");
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn test_settings_telemetry_backend() {
+        let settings = Settings::from_json(
+            r#"{"telemetry_backend": {"backend": "elastic", "url": "http://elastic:9200"}}"#,
+        );
+        match settings.telemetry_backend {
+            Some(BackendConfig::Elastic(cfg)) => assert_eq!(cfg.logs_index, "logs-*"),
+            _ => panic!("Expected Elastic backend"),
+        }
+    }
+
+    #[test]
+    fn test_settings_invalid_json_uses_defaults() {
+        let settings = Settings::from_json("not json");
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn test_push_recent_dataflow_moves_to_front() {
+        let mut settings = Settings::default();
+        settings.push_recent_dataflow("a.yml");
+        settings.push_recent_dataflow("b.yml");
+        settings.push_recent_dataflow("a.yml");
+        assert_eq!(settings.recent_dataflows, vec!["a.yml", "b.yml"]);
+    }
+
+    #[test]
+    fn test_push_recent_dataflow_ignores_blank() {
+        let mut settings = Settings::default();
+        settings.push_recent_dataflow("   ");
+        assert!(settings.recent_dataflows.is_empty());
+    }
+
+    #[test]
+    fn test_push_recent_dataflow_caps_length() {
+        let mut settings = Settings::default();
+        for i in 0..(MAX_RECENT_DATAFLOWS + 5) {
+            settings.push_recent_dataflow(&format!("{}.yml", i));
+        }
+        assert_eq!(settings.recent_dataflows.len(), MAX_RECENT_DATAFLOWS);
+        assert_eq!(
+            settings.recent_dataflows[0],
+            format!("{}.yml", MAX_RECENT_DATAFLOWS + 4)
+        );
+    }
+
+    #[test]
+    fn test_push_query_history_moves_to_front_and_caps_length() {
+        let mut settings = Settings::default();
+        for i in 0..(MAX_QUERY_HISTORY + 2) {
+            settings.push_query_history(&format!("SELECT {} FROM logs", i));
+        }
+        settings.push_query_history("  SELECT 5 FROM logs ");
+        assert_eq!(settings.query_history.len(), MAX_QUERY_HISTORY);
+        assert_eq!(settings.query_history[0], "SELECT 5 FROM logs");
+        assert_eq!(
+            settings.query_history[1],
+            format!("SELECT {} FROM logs", MAX_QUERY_HISTORY + 1)
+        );
+    }
+}
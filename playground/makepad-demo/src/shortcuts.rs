@@ -0,0 +1,438 @@
+//! Global keyboard shortcuts.
+//!
+//! Each [`ShortcutAction`] has a default key chord; settings can rebind or
+//! disable it by action id (`"refresh": "F5"`, `"show_alerts": ""`). Chords
+//! are written like `R`, `Ctrl+Enter` or `Shift+/`; `Ctrl` means the platform
+//! primary modifier, so it is Cmd on macOS.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use makepad_widgets::{KeyCode, KeyEvent};
+
+/// Something a shortcut can trigger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutAction {
+    Refresh,
+    ShowDataflows,
+    ShowTraces,
+    ShowAlerts,
+    ShowDiagnostics,
+    ShowQuery,
+    /// Open the command palette as a search box.
+    FocusSearch,
+    /// Close the topmost dialog: the command palette, then the notification
+    /// drawer, then the debug console.
+    CloseDialog,
+    SendChat,
+    CommandPalette,
+    /// Show or hide the debug console with studio's own log.
+    ToggleDebugConsole,
+}
+
+impl ShortcutAction {
+    pub const ALL: [ShortcutAction; 11] = [
+        ShortcutAction::Refresh,
+        ShortcutAction::ShowDataflows,
+        ShortcutAction::ShowTraces,
+        ShortcutAction::ShowAlerts,
+        ShortcutAction::ShowDiagnostics,
+        ShortcutAction::ShowQuery,
+        ShortcutAction::FocusSearch,
+        ShortcutAction::CloseDialog,
+        ShortcutAction::SendChat,
+        ShortcutAction::CommandPalette,
+        ShortcutAction::ToggleDebugConsole,
+    ];
+
+    /// Id used as the settings key.
+    pub fn id(self) -> &'static str {
+        match self {
+            ShortcutAction::Refresh => "refresh",
+            ShortcutAction::ShowDataflows => "show_dataflows",
+            ShortcutAction::ShowTraces => "show_traces",
+            ShortcutAction::ShowAlerts => "show_alerts",
+            ShortcutAction::ShowDiagnostics => "show_diagnostics",
+            ShortcutAction::ShowQuery => "show_query",
+            ShortcutAction::FocusSearch => "focus_search",
+            ShortcutAction::CloseDialog => "close_dialog",
+            ShortcutAction::SendChat => "send_chat",
+            ShortcutAction::CommandPalette => "command_palette",
+            ShortcutAction::ToggleDebugConsole => "toggle_debug_console",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ShortcutAction::Refresh => "Refresh",
+            ShortcutAction::ShowDataflows => "Dataflows",
+            ShortcutAction::ShowTraces => "Traces",
+            ShortcutAction::ShowAlerts => "Alerts",
+            ShortcutAction::ShowDiagnostics => "Diagnostics",
+            ShortcutAction::ShowQuery => "Query",
+            ShortcutAction::FocusSearch => "Search",
+            ShortcutAction::CloseDialog => "Close",
+            ShortcutAction::SendChat => "Send chat",
+            ShortcutAction::CommandPalette => "Commands",
+            ShortcutAction::ToggleDebugConsole => "Debug console",
+        }
+    }
+
+    /// Whether the action opens the command palette, taking the key focus
+    /// from whatever has it.
+    pub fn opens_palette(self) -> bool {
+        matches!(
+            self,
+            ShortcutAction::FocusSearch | ShortcutAction::CommandPalette
+        )
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.id() == id)
+    }
+
+    fn default_chord(self) -> &'static str {
+        match self {
+            ShortcutAction::Refresh => "R",
+            ShortcutAction::ShowDataflows => "1",
+            ShortcutAction::ShowTraces => "2",
+            ShortcutAction::ShowAlerts => "3",
+            ShortcutAction::ShowDiagnostics => "4",
+            ShortcutAction::ShowQuery => "5",
+            ShortcutAction::FocusSearch => "/",
+            ShortcutAction::CloseDialog => "Esc",
+            ShortcutAction::SendChat => "Ctrl+Enter",
+            ShortcutAction::CommandPalette => "Ctrl+K",
+            ShortcutAction::ToggleDebugConsole => "Ctrl+Shift+D",
+        }
+    }
+}
+
+/// A key with modifiers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChord {
+    /// Normalized key name: `A`–`Z`, `0`–`9`, `/`, `Enter`, `Esc`, `Space`,
+    /// `Tab` or `F1`–`F12`.
+    pub key: String,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+impl KeyChord {
+    /// Parse a chord like `Ctrl+Shift+R`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut parts: Vec<&str> = spec.split('+').map(str::trim).collect();
+        // `Ctrl++` is not supported, but a trailing `+` should not panic
+        let key = parts.pop().filter(|k| !k.is_empty());
+        let key = key
+            .and_then(normalize_key)
+            .ok_or_else(|| format!("Unknown key in shortcut '{}'", spec))?;
+        let mut chord = KeyChord {
+            key,
+            ctrl: false,
+            alt: false,
+            shift: false,
+        };
+        for modifier in parts {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "cmd" | "control" => chord.ctrl = true,
+                "alt" | "option" => chord.alt = true,
+                "shift" => chord.shift = true,
+                other => return Err(format!("Unknown modifier '{}' in '{}'", other, spec)),
+            }
+        }
+        Ok(chord)
+    }
+
+    /// Chord for a key press, if the key can be bound.
+    pub fn from_event(ke: &KeyEvent) -> Option<Self> {
+        Some(KeyChord {
+            key: key_name(ke.key_code)?.to_string(),
+            ctrl: ke.modifiers.is_primary(),
+            alt: ke.modifiers.alt,
+            shift: ke.modifiers.shift,
+        })
+    }
+
+    /// Whether the chord types text when a text input has focus.
+    pub fn is_plain(&self) -> bool {
+        !self.ctrl && !self.alt && self.key != "Esc"
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+fn normalize_key(key: &str) -> Option<String> {
+    let lower = key.to_lowercase();
+    let named = match lower.as_str() {
+        "enter" | "return" => Some("Enter"),
+        "esc" | "escape" => Some("Esc"),
+        "space" => Some("Space"),
+        "tab" => Some("Tab"),
+        "/" | "slash" => Some("/"),
+        _ => None,
+    };
+    if let Some(name) = named {
+        return Some(name.to_string());
+    }
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphanumeric() {
+            return Some(c.to_ascii_uppercase().to_string());
+        }
+    }
+    let function = lower.strip_prefix('f')?.parse::<u8>().ok()?;
+    (1..=12)
+        .contains(&function)
+        .then(|| format!("F{}", function))
+}
+
+fn key_name(code: KeyCode) -> Option<&'static str> {
+    Some(match code {
+        KeyCode::KeyA => "A",
+        KeyCode::KeyB => "B",
+        KeyCode::KeyC => "C",
+        KeyCode::KeyD => "D",
+        KeyCode::KeyE => "E",
+        KeyCode::KeyF => "F",
+        KeyCode::KeyG => "G",
+        KeyCode::KeyH => "H",
+        KeyCode::KeyI => "I",
+        KeyCode::KeyJ => "J",
+        KeyCode::KeyK => "K",
+        KeyCode::KeyL => "L",
+        KeyCode::KeyM => "M",
+        KeyCode::KeyN => "N",
+        KeyCode::KeyO => "O",
+        KeyCode::KeyP => "P",
+        KeyCode::KeyQ => "Q",
+        KeyCode::KeyR => "R",
+        KeyCode::KeyS => "S",
+        KeyCode::KeyT => "T",
+        KeyCode::KeyU => "U",
+        KeyCode::KeyV => "V",
+        KeyCode::KeyW => "W",
+        KeyCode::KeyX => "X",
+        KeyCode::KeyY => "Y",
+        KeyCode::KeyZ => "Z",
+        KeyCode::Key0 => "0",
+        KeyCode::Key1 => "1",
+        KeyCode::Key2 => "2",
+        KeyCode::Key3 => "3",
+        KeyCode::Key4 => "4",
+        KeyCode::Key5 => "5",
+        KeyCode::Key6 => "6",
+        KeyCode::Key7 => "7",
+        KeyCode::Key8 => "8",
+        KeyCode::Key9 => "9",
+        KeyCode::Slash => "/",
+        KeyCode::ReturnKey | KeyCode::NumpadEnter => "Enter",
+        KeyCode::Escape => "Esc",
+        KeyCode::Space => "Space",
+        KeyCode::Tab => "Tab",
+        KeyCode::F1 => "F1",
+        KeyCode::F2 => "F2",
+        KeyCode::F3 => "F3",
+        KeyCode::F4 => "F4",
+        KeyCode::F5 => "F5",
+        KeyCode::F6 => "F6",
+        KeyCode::F7 => "F7",
+        KeyCode::F8 => "F8",
+        KeyCode::F9 => "F9",
+        KeyCode::F10 => "F10",
+        KeyCode::F11 => "F11",
+        KeyCode::F12 => "F12",
+        _ => return None,
+    })
+}
+
+/// Active bindings: defaults with the settings overrides applied.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(ShortcutAction, KeyChord)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new()).0
+    }
+}
+
+impl Keymap {
+    /// Build the keymap from overrides keyed by action id. An empty chord
+    /// disables the action. Invalid overrides are reported and the default
+    /// binding is kept; when two actions share a chord the later one is
+    /// dropped.
+    pub fn new(overrides: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let mut errors: Vec<String> = overrides
+            .keys()
+            .filter(|id| ShortcutAction::from_id(id).is_none())
+            .map(|id| format!("Unknown shortcut action '{}'", id))
+            .collect();
+
+        let mut bindings: Vec<(ShortcutAction, KeyChord)> = Vec::new();
+        for action in ShortcutAction::ALL {
+            let default = KeyChord::parse(action.default_chord()).ok();
+            let chord = match overrides.get(action.id()).map(|s| s.trim()) {
+                Some("") => None,
+                Some(spec) => match KeyChord::parse(spec) {
+                    Ok(chord) => Some(chord),
+                    Err(e) => {
+                        errors.push(e);
+                        default
+                    }
+                },
+                None => default,
+            };
+            let Some(chord) = chord else { continue };
+            if let Some((other, _)) = bindings.iter().find(|(_, c)| *c == chord) {
+                errors.push(format!(
+                    "{} is bound to both {} and {}",
+                    chord,
+                    other.id(),
+                    action.id()
+                ));
+                continue;
+            }
+            bindings.push((action, chord));
+        }
+        (Keymap { bindings }, errors)
+    }
+
+    pub fn action(&self, chord: &KeyChord) -> Option<ShortcutAction> {
+        self.bindings
+            .iter()
+            .find(|(_, c)| c == chord)
+            .map(|(action, _)| *action)
+    }
+
+    pub fn chord(&self, action: ShortcutAction) -> Option<&KeyChord> {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, chord)| chord)
+    }
+
+    /// One-line summary, e.g. `R Refresh · 1 Dataflows · …`.
+    pub fn describe(&self) -> String {
+        self.bindings
+            .iter()
+            .map(|(action, chord)| format!("{} {}", chord, action.label()))
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+/// Parse overrides typed as `refresh=F5, show_alerts=`.
+pub fn parse_overrides(text: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut overrides = BTreeMap::new();
+    for part in text.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (id, chord) = part
+            .split_once('=')
+            .ok_or_else(|| format!("Expected action=key, got '{}'", part))?;
+        overrides.insert(id.trim().to_string(), chord.trim().to_string());
+    }
+    Ok(overrides)
+}
+
+/// Inverse of [`parse_overrides`].
+pub fn format_overrides(overrides: &BTreeMap<String, String>) -> String {
+    overrides
+        .iter()
+        .map(|(id, chord)| format!("{}={}", id, chord))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chord(spec: &str) -> KeyChord {
+        KeyChord::parse(spec).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display_chords() {
+        assert_eq!(chord("ctrl+enter").to_string(), "Ctrl+Enter");
+        assert_eq!(chord("Cmd + k").to_string(), "Ctrl+K");
+        assert_eq!(chord("shift+slash").to_string(), "Shift+/");
+        assert_eq!(chord("f5").to_string(), "F5");
+        assert_eq!(chord("escape"), chord("Esc"));
+        assert!(KeyChord::parse("Hyper+R").is_err());
+        assert!(KeyChord::parse("F13").is_err());
+        assert!(KeyChord::parse("Ctrl+").is_err());
+    }
+
+    #[test]
+    fn test_plain_chords() {
+        assert!(chord("R").is_plain());
+        assert!(chord("Shift+/").is_plain());
+        assert!(!chord("Ctrl+Enter").is_plain());
+        assert!(!chord("Esc").is_plain());
+        // Not plain, but still ignored while typing
+        assert!(ShortcutAction::CommandPalette.opens_palette());
+        assert!(!ShortcutAction::SendChat.opens_palette());
+    }
+
+    #[test]
+    fn test_default_bindings() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action(&chord("R")), Some(ShortcutAction::Refresh));
+        assert_eq!(keymap.action(&chord("2")), Some(ShortcutAction::ShowTraces));
+        assert_eq!(
+            keymap.action(&chord("Ctrl+Enter")),
+            Some(ShortcutAction::SendChat)
+        );
+        assert_eq!(
+            keymap.action(&chord("Ctrl+Shift+D")),
+            Some(ShortcutAction::ToggleDebugConsole)
+        );
+        assert_eq!(keymap.action(&chord("Enter")), None);
+        assert!(keymap.describe().starts_with("R Refresh · 1 Dataflows"));
+    }
+
+    #[test]
+    fn test_overrides_rebind_and_disable() {
+        let overrides = parse_overrides("refresh=F5, show_alerts=, focus_search=Ctrl+F").unwrap();
+        let (keymap, errors) = Keymap::new(&overrides);
+        assert!(errors.is_empty());
+        assert_eq!(keymap.action(&chord("F5")), Some(ShortcutAction::Refresh));
+        assert_eq!(keymap.action(&chord("R")), None);
+        assert_eq!(keymap.chord(ShortcutAction::ShowAlerts), None);
+        assert_eq!(keymap.action(&chord("3")), None);
+        assert_eq!(
+            format_overrides(&overrides),
+            "focus_search=Ctrl+F, refresh=F5, show_alerts="
+        );
+    }
+
+    #[test]
+    fn test_invalid_overrides_are_reported() {
+        let overrides = parse_overrides("refresh=Hyper+R, nope=X, show_traces=1").unwrap();
+        let (keymap, errors) = Keymap::new(&overrides);
+        assert_eq!(errors.len(), 3);
+        // The invalid binding keeps its default; the conflicting one is dropped
+        assert_eq!(keymap.action(&chord("R")), Some(ShortcutAction::Refresh));
+        assert_eq!(
+            keymap.action(&chord("1")),
+            Some(ShortcutAction::ShowDataflows)
+        );
+        assert_eq!(keymap.chord(ShortcutAction::ShowTraces), None);
+        assert!(parse_overrides("refresh").is_err());
+    }
+}
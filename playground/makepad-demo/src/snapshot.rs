@@ -0,0 +1,236 @@
+//! Versioned file format for shared artifacts.
+//!
+//! Dashboards, chat sessions and config bundles are written as a JSON
+//! envelope around the artifact itself:
+//!
+//! ```json
+//! {
+//!   "format": "dora-studio",
+//!   "kind": "dashboard",
+//!   "version": 1,
+//!   "min_reader_version": 1,
+//!   "created_at_ms": 1700000000000,
+//!   "payload": { ... }
+//! }
+//! ```
+//!
+//! `version` is the payload schema version of the writer. Compatibility rules:
+//!
+//! - Older files are upgraded on read by [`Artifact::migrate`], one version at
+//!   a time, so every version ever written keeps opening.
+//! - Newer files are read as long as `min_reader_version` is not above our
+//!   version. Writers only raise it for changes older readers would get
+//!   wrong; added fields do not, since unknown fields are ignored. A missing
+//!   `min_reader_version` means the file's own `version`.
+//! - Payload fields added after version 1 must be `#[serde(default)]`.
+//!
+//! Each version that ships gets a fixture in the tests of its artifact so
+//! it is checked to still open as the crate evolves.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const FORMAT: &str = "dora-studio";
+
+/// Whether `text` is a JSON object whose `format` is `format`, to recognise
+/// files written before their kind moved into an [`Envelope`].
+pub fn has_format(text: &str, format: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(text)
+        .ok()
+        .is_some_and(|value| value.get("format").and_then(|f| f.as_str()) == Some(format))
+}
+
+/// A payload that can be exported in an envelope.
+pub trait Artifact: Serialize + DeserializeOwned {
+    /// Value of the envelope's `kind` field, e.g. `snapshot`.
+    const KIND: &'static str;
+    /// Current payload schema version.
+    const VERSION: u32;
+    /// Oldest reader version able to read what this version writes.
+    const MIN_READER_VERSION: u32 = Self::VERSION;
+
+    /// Upgrade a payload written at `from` to `from + 1`.
+    fn migrate(from: u32, payload: &mut serde_json::Value) -> Result<(), String> {
+        let _ = payload;
+        Err(format!("No migration from {} version {}", Self::KIND, from))
+    }
+}
+
+/// On-disk envelope.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Envelope<T> {
+    pub format: String,
+    pub kind: String,
+    pub version: u32,
+    #[serde(default)]
+    pub min_reader_version: Option<u32>,
+    pub created_at_ms: u64,
+    pub payload: T,
+}
+
+impl<T: Artifact> Envelope<T> {
+    pub fn new(payload: T, created_at_ms: u64) -> Self {
+        Self {
+            format: FORMAT.to_string(),
+            kind: T::KIND.to_string(),
+            version: T::VERSION,
+            min_reader_version: Some(T::MIN_READER_VERSION),
+            created_at_ms,
+            payload,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize {}: {}", T::KIND, e))
+    }
+
+    /// Parse an envelope of any supported version, upgrading older payloads.
+    /// The result carries the current version, since its payload is now in
+    /// the current schema.
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let raw: Envelope<serde_json::Value> =
+            serde_json::from_str(text).map_err(|e| format!("Invalid {} file: {}", T::KIND, e))?;
+        if raw.format != FORMAT {
+            return Err(format!("Not a Dora Studio file (format '{}')", raw.format));
+        }
+        if raw.kind != T::KIND {
+            return Err(format!("Expected a {}, found a {}", T::KIND, raw.kind));
+        }
+        let min_reader = raw.min_reader_version.unwrap_or(raw.version);
+        if min_reader > T::VERSION {
+            return Err(format!(
+                "This {} needs version {} or newer (this build reads version {})",
+                T::KIND,
+                min_reader,
+                T::VERSION
+            ));
+        }
+
+        let mut payload = raw.payload;
+        let mut version = raw.version;
+        while version < T::VERSION {
+            T::migrate(version, &mut payload)?;
+            version += 1;
+        }
+        let payload = serde_json::from_value(payload)
+            .map_err(|e| format!("Invalid {} (version {}): {}", T::KIND, raw.version, e))?;
+        Ok(Self::new(payload, raw.created_at_ms))
+    }
+
+    pub fn read_from(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_json(&text)
+    }
+
+    pub fn write_to(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_json()?)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A payload at version 3 whose fields were renamed and added over time.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Board {
+        title: String,
+        #[serde(default)]
+        columns: u32,
+    }
+
+    impl Artifact for Board {
+        const KIND: &'static str = "board";
+        const VERSION: u32 = 3;
+
+        fn migrate(from: u32, payload: &mut serde_json::Value) -> Result<(), String> {
+            match from {
+                // v1 called the title `name`
+                1 => {
+                    let name = payload["name"].take();
+                    payload["title"] = name;
+                }
+                // v2 had no columns; the v2 default was a single column
+                2 => payload["columns"] = serde_json::json!(1),
+                _ => return Err(format!("unknown version {}", from)),
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let envelope = Envelope::new(
+            Board {
+                title: "Latency".to_string(),
+                columns: 2,
+            },
+            42,
+        );
+        let parsed = Envelope::<Board>::from_json(&envelope.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, envelope);
+    }
+
+    #[test]
+    fn test_older_versions_are_migrated() {
+        let v1 = r#"{"format":"dora-studio","kind":"board","version":1,"created_at_ms":0,
+            "payload":{"name":"Latency"}}"#;
+        let parsed = Envelope::<Board>::from_json(v1).unwrap();
+        assert_eq!(parsed.version, 3);
+        assert_eq!(
+            parsed.payload,
+            Board {
+                title: "Latency".to_string(),
+                columns: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_newer_versions_open_unless_readers_are_too_old() {
+        let compatible = r#"{"format":"dora-studio","kind":"board","version":5,
+            "min_reader_version":3,"created_at_ms":0,
+            "payload":{"title":"Latency","columns":2,"theme":"dark"}}"#;
+        let parsed = Envelope::<Board>::from_json(compatible).unwrap();
+        assert_eq!(parsed.payload.columns, 2);
+
+        let incompatible =
+            compatible.replace(r#""min_reader_version":3"#, r#""min_reader_version":4"#);
+        let err = Envelope::<Board>::from_json(&incompatible).unwrap_err();
+        assert!(err.contains("needs version 4"), "{}", err);
+        // Without min_reader_version the file's own version applies
+        let unmarked = compatible.replace(r#""min_reader_version":3,"#, "");
+        assert!(Envelope::<Board>::from_json(&unmarked).is_err());
+    }
+
+    #[test]
+    fn test_has_format() {
+        assert!(has_format(
+            r#"{"format":"dora-studio-chat"}"#,
+            "dora-studio-chat"
+        ));
+        assert!(!has_format(
+            r#"{"format":"dora-studio"}"#,
+            "dora-studio-chat"
+        ));
+        assert!(!has_format("not json", "dora-studio-chat"));
+    }
+
+    #[test]
+    fn test_rejects_other_formats_and_kinds() {
+        let err = Envelope::<Board>::from_json(
+            r#"{"format":"x","kind":"board","version":3,"created_at_ms":0,"payload":{}}"#,
+        )
+        .unwrap_err();
+        assert!(err.contains("Not a Dora Studio file"));
+        let err = Envelope::<Board>::from_json(
+            r#"{"format":"dora-studio","kind":"snapshot","version":1,"created_at_ms":0,"payload":{}}"#,
+        )
+        .unwrap_err();
+        assert!(err.contains("Expected a board"));
+    }
+}
@@ -0,0 +1,586 @@
+//! Status bar along the bottom of the window: coordinator connectivity,
+//! telemetry backend health, running dataflows, time since the last refresh
+//! and background task activity. Clicking the connection items opens a
+//! popover with round-trip latency, the last successful query and actions
+//! to retest or edit the connection.
+
+use makepad_widgets::*;
+
+use crate::dataflow::CoordinatorState;
+use crate::datetime;
+use crate::i18n;
+
+live_design! {
+    use link::theme::*;
+    use link::shaders::*;
+    use link::widgets::*;
+
+    STATUS_BG = #f1f5f9
+    STATUS_TEXT = #334155
+    STATUS_MUTED = #64748b
+
+    StatusItem = <Label> {
+        width: Fit, height: Fit
+        draw_text: {
+            color: (STATUS_TEXT),
+            text_style: { font_size: 10.0 }
+        }
+        text: ""
+    }
+
+    StatusSeparator = <Label> {
+        width: Fit, height: Fit
+        draw_text: {
+            color: (STATUS_MUTED),
+            text_style: { font_size: 10.0 }
+        }
+        text: "|"
+    }
+
+    PopoverRow = <View> {
+        width: Fill, height: Fit
+        flow: Down
+        spacing: 2
+
+        title = <Label> {
+            width: Fit, height: Fit
+            draw_text: {
+                color: (STATUS_TEXT),
+                text_style: { font_size: 10.0 }
+            }
+        }
+        detail = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: (STATUS_MUTED),
+                text_style: { font_size: 9.0 }
+            }
+        }
+    }
+
+    pub StatusBar = {{StatusBar}} {
+        width: Fill, height: Fit
+        flow: Down
+
+        // Opened by clicking the connection items, docked above the bar
+        connection_popover = <View> {
+            visible: false
+            width: 360, height: Fit
+            flow: Down
+            margin: { left: 8, bottom: 4 }
+            padding: 12
+            spacing: 10
+            show_bg: true
+            draw_bg: { color: #ffffff }
+
+            coordinator_row = <PopoverRow> {
+                title = { text: "Coordinator: connecting…" }
+                detail = { text: "No successful query yet" }
+            }
+            telemetry_row = <PopoverRow> {
+                title = { text: "Telemetry: not configured" }
+                detail = { text: "No successful query yet" }
+            }
+
+            <View> {
+                width: Fill, height: Fit
+                flow: Right
+                spacing: 8
+
+                retest_button = <Button> {
+                    width: Fit, height: 24
+                    text: "Retest"
+                    draw_text: { text_style: { font_size: 10.0 } }
+                }
+                edit_connection_button = <Button> {
+                    width: Fit, height: 24
+                    text: "Edit connection"
+                    draw_text: { text_style: { font_size: 10.0 } }
+                }
+            }
+        }
+
+        bar = <View> {
+            width: Fill, height: 28
+            flow: Right
+            show_bg: true
+            draw_bg: { color: (STATUS_BG) }
+            padding: { left: 12, right: 12 }
+            align: { y: 0.5 }
+            spacing: 8
+
+            connection_items = <View> {
+                width: Fit, height: Fill
+                flow: Right
+                align: { y: 0.5 }
+                spacing: 8
+                cursor: Hand
+                show_bg: true
+                draw_bg: { color: (STATUS_BG) }
+
+                coordinator_label = <StatusItem> { text: "Coordinator: connecting…" }
+                <StatusSeparator> {}
+                dora_label = <StatusItem> { text: "dora: unknown" }
+                <StatusSeparator> {}
+                telemetry_label = <StatusItem> { text: "Telemetry: not configured" }
+            }
+            <StatusSeparator> {}
+            running_label = <StatusItem> { text: "0 running" }
+            <StatusSeparator> {}
+            refresh_label = <StatusItem> { text: "Not refreshed yet" }
+
+            // Spacer pushing task activity to the right
+            <View> { width: Fill, height: Fit }
+
+            task_label = <StatusItem> {
+                draw_text: { color: (STATUS_MUTED) }
+                text: "Idle"
+            }
+
+            cancel_tasks_button = <Button> {
+                visible: false
+                width: 64, height: 22
+                text: "Cancel"
+                draw_text: { text_style: { font_size: 10.0 } }
+            }
+        }
+    }
+}
+
+#[derive(Live, LiveHook, Widget)]
+pub struct StatusBar {
+    #[deref]
+    view: View,
+    /// Text last shown by the refresh label, to avoid redrawing every frame.
+    #[rust]
+    refresh_text: String,
+    /// Last successful coordinator round trip.
+    #[rust]
+    coordinator_timing: LinkTiming,
+    /// Last successful telemetry backend round trip.
+    #[rust]
+    telemetry_timing: LinkTiming,
+    /// Popover details last shown, to avoid redrawing every frame.
+    #[rust]
+    popover_text: (String, String),
+    /// Shown again in the new language by [`StatusBar::localize`].
+    #[rust]
+    coordinator: CoordinatorState,
+    /// Whether the telemetry and dora labels still show their placeholders.
+    #[rust]
+    telemetry_set: bool,
+    #[rust]
+    dora_set: bool,
+}
+
+impl Widget for StatusBar {
+    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+        self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
+    }
+
+    fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        self.view.draw_walk(cx, scope, walk)
+    }
+}
+
+impl WidgetMatchEvent for StatusBar {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, _scope: &mut Scope) {
+        if self
+            .view
+            .view(ids!(connection_items))
+            .finger_down(actions)
+            .is_some()
+        {
+            let popover = self.view.view(ids!(connection_popover));
+            popover.set_visible(cx, !popover.visible());
+            self.redraw(cx);
+        }
+    }
+}
+
+impl StatusBar {
+    /// Show the text set in `live_design` in the current language
+    pub fn localize(&mut self, cx: &mut Cx) {
+        i18n::localize(
+            cx,
+            &[
+                (ids!(retest_button), "status.retest"),
+                (ids!(edit_connection_button), "status.edit_connection"),
+                (ids!(cancel_tasks_button), "action.cancel"),
+            ],
+            |path| self.view.widget(path),
+        );
+        if !self.telemetry_set {
+            i18n::localize(
+                cx,
+                &[
+                    (ids!(telemetry_label), "status.telemetry_not_configured"),
+                    (ids!(telemetry_row.title), "status.telemetry_not_configured"),
+                ],
+                |path| self.view.widget(path),
+            );
+        }
+        if !self.dora_set {
+            i18n::localize(cx, &[(ids!(dora_label), "status.dora_unknown")], |path| {
+                self.view.widget(path)
+            });
+        }
+        self.set_coordinator(cx, self.coordinator);
+        // Force the ages to be shown again in the new language
+        self.refresh_text.clear();
+        self.popover_text = Default::default();
+    }
+
+    pub fn set_coordinator(&mut self, cx: &mut Cx, state: CoordinatorState) {
+        self.coordinator = state;
+        let color = health_color(coordinator_health(state));
+        for label in [
+            self.view.label(ids!(coordinator_label)),
+            self.view.label(ids!(coordinator_row.title)),
+        ] {
+            label.set_text(cx, &coordinator_text(state));
+            label.apply_over(cx, live! { draw_text: { color: (color) } });
+        }
+        self.redraw(cx);
+    }
+
+    /// Show the telemetry backend status; `healthy` is `None` while unknown.
+    pub fn set_telemetry(&mut self, cx: &mut Cx, text: &str, healthy: Option<bool>) {
+        self.telemetry_set = true;
+        let color = health_color(healthy);
+        for label in [
+            self.view.label(ids!(telemetry_label)),
+            self.view.label(ids!(telemetry_row.title)),
+        ] {
+            label.set_text(cx, text);
+            label.apply_over(cx, live! { draw_text: { color: (color) } });
+        }
+        self.redraw(cx);
+    }
+
+    /// Record the coordinator's last successful round trip for the popover.
+    pub fn set_coordinator_timing(&mut self, timing: LinkTiming) {
+        self.coordinator_timing = timing;
+    }
+
+    /// Record the telemetry backend's last successful round trip for the
+    /// popover.
+    pub fn set_telemetry_timing(&mut self, timing: LinkTiming) {
+        self.telemetry_timing = timing;
+    }
+
+    /// Show the installed dora version; `healthy` is false when it is
+    /// missing or older than the tools expect.
+    pub fn set_dora(&mut self, cx: &mut Cx, text: &str, healthy: Option<bool>) {
+        self.dora_set = true;
+        let label = self.view.label(ids!(dora_label));
+        let color = health_color(healthy);
+        label.set_text(cx, text);
+        label.apply_over(cx, live! { draw_text: { color: (color) } });
+        self.redraw(cx);
+    }
+
+    pub fn set_running(&mut self, cx: &mut Cx, running: usize, stale: usize) {
+        self.view
+            .label(ids!(running_label))
+            .set_text(cx, &running_text(running, stale));
+        self.redraw(cx);
+    }
+
+    /// Update the time since the last successful refresh; cheap to call
+    /// every frame, it only redraws when the text changes.
+    pub fn set_last_refresh(&mut self, cx: &mut Cx, refreshed_ms: Option<u64>, now_ms: u64) {
+        let text = match refreshed_ms {
+            Some(at) => i18n::tr_args(
+                "status.refreshed",
+                &[("ago", &datetime::format_ago(at, now_ms))],
+            ),
+            None => i18n::tr("status.not_refreshed"),
+        };
+        if text != self.refresh_text {
+            self.view.label(ids!(refresh_label)).set_text(cx, &text);
+            self.refresh_text = text;
+            self.redraw(cx);
+        }
+        self.update_popover(cx, now_ms);
+    }
+
+    /// Refresh the popover's ages and latencies while it is open.
+    fn update_popover(&mut self, cx: &mut Cx, now_ms: u64) {
+        if !self.view.view(ids!(connection_popover)).visible() {
+            return;
+        }
+        let text = (
+            link_detail(self.coordinator_timing, now_ms),
+            link_detail(self.telemetry_timing, now_ms),
+        );
+        if text != self.popover_text {
+            self.view
+                .label(ids!(coordinator_row.detail))
+                .set_text(cx, &text.0);
+            self.view
+                .label(ids!(telemetry_row.detail))
+                .set_text(cx, &text.1);
+            self.popover_text = text;
+            self.redraw(cx);
+        }
+    }
+
+    /// Show running tasks, empty when there are none, or else how many
+    /// worker requests (trace queries, LLM calls) are in flight.
+    pub fn set_tasks(&mut self, cx: &mut Cx, summary: &str, background_jobs: usize) {
+        let idle = summary.is_empty();
+        let text = if idle {
+            background_text(background_jobs)
+        } else {
+            summary.to_string()
+        };
+        self.view.label(ids!(task_label)).set_text(cx, &text);
+        self.view
+            .button(ids!(cancel_tasks_button))
+            .set_visible(cx, !idle);
+        self.redraw(cx);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Ref wrapper (same pattern as DataflowTableRef)
+// ---------------------------------------------------------------------------
+
+impl StatusBarRef {
+    pub fn localize(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.localize(cx);
+        }
+    }
+
+    pub fn set_coordinator(&self, cx: &mut Cx, state: CoordinatorState) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_coordinator(cx, state);
+        }
+    }
+
+    pub fn set_telemetry(&self, cx: &mut Cx, text: &str, healthy: Option<bool>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_telemetry(cx, text, healthy);
+        }
+    }
+
+    pub fn set_dora(&self, cx: &mut Cx, text: &str, healthy: Option<bool>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_dora(cx, text, healthy);
+        }
+    }
+
+    pub fn set_running(&self, cx: &mut Cx, running: usize, stale: usize) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_running(cx, running, stale);
+        }
+    }
+
+    pub fn set_last_refresh(&self, cx: &mut Cx, refreshed_ms: Option<u64>, now_ms: u64) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_last_refresh(cx, refreshed_ms, now_ms);
+        }
+    }
+
+    pub fn set_coordinator_timing(&self, timing: LinkTiming) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_coordinator_timing(timing);
+        }
+    }
+
+    pub fn set_telemetry_timing(&self, timing: LinkTiming) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_telemetry_timing(timing);
+        }
+    }
+
+    pub fn set_tasks(&self, cx: &mut Cx, summary: &str, background_jobs: usize) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_tasks(cx, summary, background_jobs);
+        }
+    }
+
+    /// Check if the cancel button for running tasks was clicked
+    pub fn cancel_tasks_clicked(&self, actions: &Actions) -> bool {
+        self.borrow().is_some_and(|inner| {
+            inner
+                .view
+                .button(ids!(cancel_tasks_button))
+                .clicked(actions)
+        })
+    }
+
+    /// Check if the popover's Retest button was clicked
+    pub fn retest_clicked(&self, actions: &Actions) -> bool {
+        self.borrow()
+            .is_some_and(|inner| inner.view.button(ids!(retest_button)).clicked(actions))
+    }
+
+    /// Check if the popover's Edit connection button was clicked
+    pub fn edit_connection_clicked(&self, actions: &Actions) -> bool {
+        self.borrow().is_some_and(|inner| {
+            inner
+                .view
+                .button(ids!(edit_connection_button))
+                .clicked(actions)
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Helper functions
+// ---------------------------------------------------------------------------
+
+/// When a backend last answered and how long that round trip took.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinkTiming {
+    /// Unix ms of the last successful query
+    pub success_ms: Option<u64>,
+    /// Round trip of that query, when it was measured
+    pub latency_ms: Option<u64>,
+}
+
+/// e.g. `42 ms` or `1.3 s`.
+pub fn latency_text(latency_ms: u64) -> String {
+    if latency_ms < 1000 {
+        format!("{} ms", latency_ms)
+    } else {
+        format!("{:.1} s", latency_ms as f64 / 1000.0)
+    }
+}
+
+/// Popover detail line, e.g. `Last success 3m ago · 42 ms round trip`.
+pub fn link_detail(timing: LinkTiming, now_ms: u64) -> String {
+    let Some(at) = timing.success_ms else {
+        return i18n::tr("status.no_success");
+    };
+    let age = datetime::format_ago(at, now_ms);
+    match timing.latency_ms {
+        Some(latency) => i18n::tr_args(
+            "status.last_success_latency",
+            &[("ago", &age), ("latency", &latency_text(latency))],
+        ),
+        None => i18n::tr_args("status.last_success", &[("ago", &age)]),
+    }
+}
+
+fn coordinator_text(state: CoordinatorState) -> String {
+    i18n::tr(match state {
+        CoordinatorState::Unknown => "status.coordinator_connecting",
+        CoordinatorState::Online => "status.coordinator_connected",
+        CoordinatorState::Offline => "status.coordinator_offline",
+    })
+}
+
+fn coordinator_health(state: CoordinatorState) -> Option<bool> {
+    match state {
+        CoordinatorState::Unknown => None,
+        CoordinatorState::Online => Some(true),
+        CoordinatorState::Offline => Some(false),
+    }
+}
+
+/// Text colour for a health state: green, red, or muted while unknown.
+fn health_color(healthy: Option<bool>) -> Vec4 {
+    let rgb: u32 = match healthy {
+        Some(true) => 0x15803d,
+        Some(false) => 0xb91c1c,
+        None => 0x64748b,
+    };
+    vec4(
+        ((rgb >> 16) & 0xff) as f32 / 255.0,
+        ((rgb >> 8) & 0xff) as f32 / 255.0,
+        (rgb & 0xff) as f32 / 255.0,
+        1.0,
+    )
+}
+
+/// e.g. `3 running` or `3 running (1 stale)`.
+pub fn running_text(running: usize, stale: usize) -> String {
+    let running = running.to_string();
+    if stale > 0 {
+        i18n::tr_args(
+            "status.running_stale",
+            &[("running", &running), ("stale", &stale.to_string())],
+        )
+    } else {
+        i18n::tr_args("status.running", &[("running", &running)])
+    }
+}
+
+/// e.g. `Idle` or `2 background jobs`.
+pub fn background_text(jobs: usize) -> String {
+    match jobs {
+        0 => i18n::tr("status.idle"),
+        1 => i18n::tr("status.background_job"),
+        n => i18n::tr_args("status.background_jobs", &[("count", &n.to_string())]),
+    }
+}
+
+/// Banner for panels showing stored data while their backend is
+/// unreachable, e.g. `Offline · stale since 14:02:11 UTC (3m ago)`.
+pub fn offline_text(since_ms: Option<u64>, now_ms: u64) -> String {
+    match since_ms {
+        Some(at) => i18n::tr_args(
+            "status.offline_since",
+            &[
+                ("time", &datetime::format_clock_secs(at)),
+                ("zone", datetime::display_zone().label()),
+                ("ago", &datetime::format_ago(at, now_ms)),
+            ],
+        ),
+        None => i18n::tr("status.offline_no_data"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_running_text() {
+        assert_eq!(running_text(0, 0), "0 running");
+        assert_eq!(running_text(3, 1), "3 running (1 stale)");
+    }
+
+    #[test]
+    fn test_background_text() {
+        assert_eq!(background_text(0), "Idle");
+        assert_eq!(background_text(1), "1 background job");
+        assert_eq!(background_text(3), "3 background jobs");
+    }
+
+    #[test]
+    fn test_link_detail() {
+        assert_eq!(
+            link_detail(LinkTiming::default(), 0),
+            "No successful query yet"
+        );
+        let timing = LinkTiming {
+            success_ms: Some(10_000),
+            latency_ms: Some(42),
+        };
+        assert_eq!(
+            link_detail(timing, 190_000),
+            "Last success 3m ago · 42 ms round trip"
+        );
+        assert_eq!(latency_text(1_340), "1.3 s");
+        let unmeasured = LinkTiming {
+            latency_ms: None,
+            ..timing
+        };
+        assert_eq!(link_detail(unmeasured, 11_000), "Last success 1s ago");
+    }
+
+    #[test]
+    fn test_offline_text() {
+        assert_eq!(
+            offline_text(Some(50_531_000), 50_711_000),
+            "Offline · stale since 14:02:11 UTC (3m ago)"
+        );
+        assert_eq!(offline_text(None, 0), "Offline · no stored data");
+    }
+}
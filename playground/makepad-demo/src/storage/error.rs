@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// Errors that can occur when reading or writing local storage.
+#[derive(Debug)]
+pub enum StorageError {
+    Io(std::io::Error),
+    Serialization(serde_json::Error),
+    UnknownTable(String),
+    /// A SQL query that could not be parsed or run.
+    Query(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Io(e) => write!(f, "I/O error: {}", e),
+            StorageError::Serialization(e) => write!(f, "serialization error: {}", e),
+            StorageError::UnknownTable(name) => write!(f, "unknown table: {}", name),
+            StorageError::Query(message) => write!(f, "query error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StorageError::Io(e) => Some(e),
+            StorageError::Serialization(e) => Some(e),
+            StorageError::UnknownTable(_) | StorageError::Query(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for StorageError {
+    fn from(err: std::io::Error) -> Self {
+        StorageError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(err: serde_json::Error) -> Self {
+        StorageError::Serialization(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_unknown_table() {
+        let err = StorageError::UnknownTable("widgets".to_string());
+        assert_eq!(format!("{}", err), "unknown table: widgets");
+    }
+
+    #[test]
+    fn test_from_io_error() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        let err: StorageError = io_err.into();
+        assert!(matches!(err, StorageError::Io(_)));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+}
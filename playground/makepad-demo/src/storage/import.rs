@@ -0,0 +1,243 @@
+//! Import of recorded dataflow telemetry.
+//!
+//! Recordings use a JSONL capture format with one entry per line, tagged by
+//! `kind`:
+//!
+//! ```text
+//! {"kind":"message","timestamp_ms":1700000000000,"dataflow_id":"…","node_id":"camera","output_id":"image","size_bytes":921600,"metadata":{}}
+//! {"kind":"span", ...Span fields}
+//! {"kind":"log", ...LogEntry fields}
+//! ```
+//!
+//! Messages are the outputs a node sent during the run (payloads are not
+//! captured); spans and logs use the same shape as the OTLP types. Entries are
+//! appended to the imported partitions of the `messages`, `spans` and `logs`
+//! tables so recorded runs can be inspected without a coordinator or
+//! telemetry backend, for as long as the user keeps them. Entries imported
+//! before are left out, so importing the same file twice stores them once.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use super::{Record, Storage, StorageError, LOGS_TABLE, MESSAGES_TABLE, SPANS_TABLE};
+use crate::i18n;
+use crate::otlp::types::{LogEntry, Span};
+
+/// An output message sent by a node, without its payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedMessage {
+    pub timestamp_ms: u64,
+    pub dataflow_id: String,
+    pub node_id: String,
+    pub output_id: String,
+    #[serde(default)]
+    pub size_bytes: u64,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+impl Record for RecordedMessage {
+    fn timestamp_ms(&self) -> u64 {
+        self.timestamp_ms
+    }
+}
+
+/// One line of a capture file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum CaptureEntry {
+    Message(RecordedMessage),
+    Span(Span),
+    Log(LogEntry),
+}
+
+/// Entries read from a capture file.
+#[derive(Debug, Default)]
+pub struct Capture {
+    pub messages: Vec<RecordedMessage>,
+    pub spans: Vec<Span>,
+    pub logs: Vec<LogEntry>,
+    /// Blank lines are ignored; other unparseable lines are counted here.
+    pub skipped: usize,
+}
+
+impl Capture {
+    /// Parse a capture from a reader, line by line.
+    pub fn parse<R: BufRead>(reader: R) -> Result<Self, StorageError> {
+        let mut capture = Capture::default();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<CaptureEntry>(&line) {
+                Ok(CaptureEntry::Message(m)) => capture.messages.push(m),
+                Ok(CaptureEntry::Span(s)) => capture.spans.push(s),
+                Ok(CaptureEntry::Log(l)) => capture.logs.push(l),
+                Err(_) => capture.skipped += 1,
+            }
+        }
+        Ok(capture)
+    }
+
+    /// Time range covered by the capture as `(start_ms, end_ms)`, end
+    /// exclusive, or `None` when empty.
+    pub fn time_range(&self) -> Option<(u64, u64)> {
+        let timestamps = self
+            .messages
+            .iter()
+            .map(|m| m.timestamp_ms)
+            .chain(self.spans.iter().map(|s| s.start_time_ms))
+            .chain(self.logs.iter().map(|l| l.timestamp_ms));
+        let (min, max) =
+            timestamps.fold((u64::MAX, 0), |(min, max), ts| (min.min(ts), max.max(ts)));
+        (min <= max).then_some((min, max + 1))
+    }
+}
+
+/// Outcome of an import.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportSummary {
+    pub messages: usize,
+    pub spans: usize,
+    pub logs: usize,
+    pub skipped: usize,
+    /// Entries left out because they were imported before
+    pub duplicates: usize,
+    /// Time range of the imported entries, end exclusive.
+    pub range: Option<(u64, u64)>,
+}
+
+impl ImportSummary {
+    /// One-line summary for the UI.
+    pub fn describe(&self) -> String {
+        let mut summary = i18n::tr_args(
+            "import.summary",
+            &[
+                ("messages", &self.messages.to_string()),
+                ("spans", &self.spans.to_string()),
+                ("logs", &self.logs.to_string()),
+            ],
+        );
+        if self.skipped > 0 {
+            summary.push_str(&i18n::tr_args(
+                "import.skipped",
+                &[("count", &self.skipped.to_string())],
+            ));
+        }
+        if self.duplicates > 0 {
+            summary.push_str(&i18n::tr_args(
+                "import.duplicates",
+                &[("count", &self.duplicates.to_string())],
+            ));
+        }
+        summary
+    }
+}
+
+/// Read the capture at `path` and append the entries not imported yet to
+/// storage.
+pub fn import_capture(storage: &Storage, path: &Path) -> Result<ImportSummary, StorageError> {
+    let file = std::fs::File::open(path)?;
+    let capture = Capture::parse(BufReader::new(file))?;
+
+    let messages = storage.append_imported(MESSAGES_TABLE, &capture.messages)?;
+    let spans = storage.append_imported(SPANS_TABLE, &capture.spans)?;
+    let logs = storage.append_imported(LOGS_TABLE, &capture.logs)?;
+    let total = capture.messages.len() + capture.spans.len() + capture.logs.len();
+
+    Ok(ImportSummary {
+        messages,
+        spans,
+        logs,
+        skipped: capture.skipped,
+        duplicates: total - (messages + spans + logs),
+        range: capture.time_range(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::TABLES;
+    use std::io::Write;
+
+    const CAPTURE: &str = r#"{"kind":"message","timestamp_ms":1000,"dataflow_id":"df","node_id":"camera","output_id":"image","size_bytes":64}
+{"kind":"span","trace_id":"t","span_id":"s","parent_span_id":null,"service_name":"camera","operation_name":"send","start_time_ms":1500,"duration_ms":3,"status_code":0,"has_error":false,"attributes":{}}
+
+{"kind":"log","timestamp_ms":2000,"severity":"INFO","body":"started","service_name":"camera","attributes":{}}
+{"kind":"metric","timestamp_ms":2500}
+not json
+"#;
+
+    #[test]
+    fn test_parse_capture() {
+        let capture = Capture::parse(CAPTURE.as_bytes()).unwrap();
+        assert_eq!(capture.messages.len(), 1);
+        assert_eq!(capture.messages[0].output_id, "image");
+        assert!(capture.messages[0].metadata.is_empty());
+        assert_eq!(capture.spans.len(), 1);
+        assert_eq!(capture.logs.len(), 1);
+        assert_eq!(capture.skipped, 2);
+        assert_eq!(capture.time_range(), Some((1000, 2001)));
+    }
+
+    #[test]
+    fn test_empty_capture_has_no_range() {
+        let capture = Capture::parse("".as_bytes()).unwrap();
+        assert_eq!(capture.time_range(), None);
+    }
+
+    #[test]
+    fn test_import_capture_into_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(&dir.path().join("storage")).unwrap();
+        let path = dir.path().join("capture.jsonl");
+        std::fs::File::create(&path)
+            .unwrap()
+            .write_all(CAPTURE.as_bytes())
+            .unwrap();
+
+        let summary = import_capture(&storage, &path).unwrap();
+        assert_eq!(
+            summary.describe(),
+            "Imported 1 message(s), 1 span(s), 1 log(s), skipped 2 invalid line(s)"
+        );
+
+        let messages: Vec<RecordedMessage> = storage.read(MESSAGES_TABLE, 0, 10_000).unwrap();
+        assert_eq!(messages.len(), 1);
+        let spans: Vec<Span> = storage.read(SPANS_TABLE, 0, 10_000).unwrap();
+        assert_eq!(spans[0].span_id, "s");
+        let logs: Vec<LogEntry> = storage.read(LOGS_TABLE, 0, 10_000).unwrap();
+        assert_eq!(logs[0].body, "started");
+
+        // A second import adds nothing
+        let summary = import_capture(&storage, &path).unwrap();
+        assert_eq!(
+            summary.describe(),
+            "Imported 0 message(s), 0 span(s), 0 log(s), skipped 2 invalid line(s), \
+             3 already imported"
+        );
+        let spans: Vec<Span> = storage.read(SPANS_TABLE, 0, 10_000).unwrap();
+        assert_eq!(spans.len(), 1);
+    }
+
+    #[test]
+    fn test_retention_keeps_imported_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(dir.path()).unwrap();
+        let path = dir.path().join("capture.jsonl");
+        std::fs::write(&path, CAPTURE).unwrap();
+        import_capture(&storage, &path).unwrap();
+
+        // Years after the recording, with every table expiring after an hour
+        let retention: std::collections::BTreeMap<String, u64> =
+            TABLES.iter().map(|t| (t.to_string(), 1)).collect();
+        let summary = storage.prune(&retention, 100_000_000_000).unwrap();
+        assert_eq!(summary.deleted_partitions, 0);
+        let logs: Vec<LogEntry> = storage.read(LOGS_TABLE, 0, 10_000).unwrap();
+        assert_eq!(logs.len(), 1);
+    }
+}
@@ -0,0 +1,394 @@
+//! Local time-series storage.
+//!
+//! Each table is a directory of daily partitions (`<table>/YYYY-MM-DD.jsonl`,
+//! UTC) holding one JSON record per line. The layout mirrors the planned
+//! Parquet store so tables can be registered with a query engine directly,
+//! and whole partitions can be dropped for retention. Records imported from
+//! recordings go to partitions of their own (`YYYY-MM-DD.imported.jsonl`),
+//! read along with the rest but left alone by retention.
+
+pub mod error;
+pub mod import;
+pub mod query;
+pub mod retention;
+pub mod stored_spans;
+
+pub use error::StorageError;
+pub use import::{import_capture, ImportSummary, RecordedMessage};
+pub use query::{run_query, QueryResult};
+pub use retention::{PruneSummary, TableUsage};
+pub use stored_spans::StoredSpans;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::datetime;
+
+/// Per-node CPU/memory samples.
+pub const METRICS_TABLE: &str = "metrics";
+/// Dataflow and node log lines.
+pub const LOGS_TABLE: &str = "logs";
+/// Trace spans.
+pub const SPANS_TABLE: &str = "spans";
+/// Node output messages imported from recordings.
+pub const MESSAGES_TABLE: &str = "messages";
+
+pub const TABLES: [&str; 4] = [METRICS_TABLE, LOGS_TABLE, SPANS_TABLE, MESSAGES_TABLE];
+
+const PARTITION_EXT: &str = "jsonl";
+/// Ends the name of partitions holding imported records, before the
+/// extension.
+const IMPORTED_SUFFIX: &str = ".imported";
+
+/// A record that can be stored in a time-partitioned table.
+pub trait Record: Serialize + DeserializeOwned {
+    /// Event time in milliseconds since the Unix epoch (UTC).
+    fn timestamp_ms(&self) -> u64;
+}
+
+/// Handle to the storage directory.
+#[derive(Debug, Clone)]
+pub struct Storage {
+    base_path: PathBuf,
+}
+
+impl Storage {
+    /// Open or create storage at `path`, creating a directory per table.
+    pub fn open(path: &Path) -> Result<Self, StorageError> {
+        for table in TABLES {
+            std::fs::create_dir_all(path.join(table))?;
+        }
+        Ok(Self {
+            base_path: path.to_path_buf(),
+        })
+    }
+
+    pub fn base_path(&self) -> &Path {
+        &self.base_path
+    }
+
+    /// Directory holding the partitions of `table`.
+    pub fn table_dir(&self, table: &str) -> Result<PathBuf, StorageError> {
+        if !TABLES.contains(&table) {
+            return Err(StorageError::UnknownTable(table.to_string()));
+        }
+        Ok(self.base_path.join(table))
+    }
+
+    /// Append records, routing each to the partition for its day.
+    pub fn append<T: Record>(&self, table: &str, records: &[T]) -> Result<(), StorageError> {
+        let dir = self.table_dir(table)?;
+
+        let mut by_day: BTreeMap<String, String> = BTreeMap::new();
+        for record in records {
+            let line = serde_json::to_string(record)?;
+            let chunk = by_day
+                .entry(partition_name(record.timestamp_ms()))
+                .or_default();
+            chunk.push_str(&line);
+            chunk.push('\n');
+        }
+
+        for (day, chunk) in by_day {
+            append_chunk(&dir.join(format!("{}.{}", day, PARTITION_EXT)), &chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Append imported records to the imported partitions, leaving out
+    /// those imported before, and return how many were new.
+    pub fn append_imported<T: Record>(
+        &self,
+        table: &str,
+        records: &[T],
+    ) -> Result<usize, StorageError> {
+        let dir = self.table_dir(table)?;
+
+        let mut by_day: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for record in records {
+            // Through a Value, whose keys are sorted, so the same record
+            // gives the same line whatever order its maps iterate in
+            let line = serde_json::to_value(record)?.to_string();
+            by_day
+                .entry(partition_name(record.timestamp_ms()))
+                .or_default()
+                .push(line);
+        }
+
+        let mut appended = 0;
+        for (day, lines) in by_day {
+            let path = dir.join(format!("{}{}.{}", day, IMPORTED_SUFFIX, PARTITION_EXT));
+            let imported: HashSet<String> = match std::fs::read_to_string(&path) {
+                Ok(text) => text
+                    .lines()
+                    .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+                    .map(|record| record.to_string())
+                    .collect(),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+                Err(e) => return Err(e.into()),
+            };
+            let mut chunk = String::new();
+            for line in lines.iter().filter(|line| !imported.contains(*line)) {
+                chunk.push_str(line);
+                chunk.push('\n');
+                appended += 1;
+            }
+            if !chunk.is_empty() {
+                append_chunk(&path, &chunk)?;
+            }
+        }
+        Ok(appended)
+    }
+
+    /// Read records with `start_ms <= timestamp < end_ms`, oldest first.
+    ///
+    /// Malformed lines (e.g. a partially written tail) are skipped.
+    pub fn read<T: Record>(
+        &self,
+        table: &str,
+        start_ms: u64,
+        end_ms: u64,
+    ) -> Result<Vec<T>, StorageError> {
+        let first = partition_name(start_ms);
+        let last = partition_name(end_ms.saturating_sub(1));
+
+        let mut records = Vec::new();
+        for (day, path) in self.partitions(table)? {
+            if day < first || day > last {
+                continue;
+            }
+            let text = std::fs::read_to_string(path)?;
+            records.extend(
+                text.lines()
+                    .filter_map(|line| serde_json::from_str::<T>(line).ok())
+                    .filter(|r| (start_ms..end_ms).contains(&r.timestamp_ms())),
+            );
+        }
+        records.sort_by_key(|r| r.timestamp_ms());
+        Ok(records)
+    }
+
+    /// The newest `limit` records of `table`, oldest first, however long ago
+    /// they were written. Only the most recent partitions are read.
+    pub fn latest<T: Record>(&self, table: &str, limit: usize) -> Result<Vec<T>, StorageError> {
+        let mut records = Vec::new();
+        for (_, path) in self.partitions(table)?.into_iter().rev() {
+            if records.len() >= limit {
+                break;
+            }
+            let text = std::fs::read_to_string(path)?;
+            records.extend(
+                text.lines()
+                    .filter_map(|line| serde_json::from_str::<T>(line).ok()),
+            );
+        }
+        records.sort_by_key(|r| r.timestamp_ms());
+        let excess = records.len().saturating_sub(limit);
+        records.drain(..excess);
+        Ok(records)
+    }
+
+    /// Every record of `table` as a JSON object, oldest partition first.
+    ///
+    /// Lines that are not JSON objects are skipped, as in [`Storage::read`].
+    pub fn scan(
+        &self,
+        table: &str,
+    ) -> Result<Vec<serde_json::Map<String, serde_json::Value>>, StorageError> {
+        let mut rows = Vec::new();
+        for (_, path) in self.partitions(table)? {
+            let text = std::fs::read_to_string(path)?;
+            rows.extend(
+                text.lines()
+                    .filter_map(|line| match serde_json::from_str(line) {
+                        Ok(serde_json::Value::Object(row)) => Some(row),
+                        _ => None,
+                    }),
+            );
+        }
+        Ok(rows)
+    }
+
+    /// Partitions of `table` as `(day, path)`, sorted by day, imported ones
+    /// included (see [`is_imported`]).
+    pub fn partitions(&self, table: &str) -> Result<Vec<(String, PathBuf)>, StorageError> {
+        let dir = self.table_dir(table)?;
+        let mut partitions = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(PARTITION_EXT) {
+                continue;
+            }
+            if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                let day = stem.strip_suffix(IMPORTED_SUFFIX).unwrap_or(stem);
+                partitions.push((day.to_string(), path.clone()));
+            }
+        }
+        partitions.sort();
+        Ok(partitions)
+    }
+}
+
+fn append_chunk(path: &Path, chunk: &str) -> Result<(), StorageError> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(chunk.as_bytes())?;
+    Ok(())
+}
+
+/// Whether the partition at `path` holds imported records.
+pub fn is_imported(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .is_some_and(|stem| stem.ends_with(IMPORTED_SUFFIX))
+}
+
+/// `YYYY-MM-DD` (UTC) for a millisecond timestamp.
+pub fn partition_name(timestamp_ms: u64) -> String {
+    datetime::utc_date(timestamp_ms)
+}
+
+/// Start of a `YYYY-MM-DD` partition in milliseconds since the epoch (UTC).
+pub fn partition_start_ms(day: &str) -> Option<u64> {
+    datetime::parse_utc_date_ms(day)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Sample {
+        ts: u64,
+        value: f64,
+    }
+
+    impl Record for Sample {
+        fn timestamp_ms(&self) -> u64 {
+            self.ts
+        }
+    }
+
+    const DAY_MS: u64 = 86_400_000;
+
+    #[test]
+    fn test_partition_name() {
+        assert_eq!(partition_name(0), "1970-01-01");
+        assert_eq!(partition_name(1_700_000_000_000), "2023-11-14");
+        assert_eq!(partition_name(951_782_400_000), "2000-02-29");
+    }
+
+    #[test]
+    fn test_partition_start_ms_inverts_partition_name() {
+        for ts in [0, 951_782_400_000, 1_700_000_000_000] {
+            let day = partition_name(ts);
+            let start = partition_start_ms(&day).unwrap();
+            assert_eq!(start, ts - ts % DAY_MS);
+        }
+        assert_eq!(partition_start_ms("2023-13-01"), None);
+        assert_eq!(partition_start_ms("notes"), None);
+    }
+
+    #[test]
+    fn test_open_creates_table_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(dir.path()).unwrap();
+        for table in TABLES {
+            assert!(storage.base_path().join(table).is_dir());
+        }
+    }
+
+    #[test]
+    fn test_unknown_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(dir.path()).unwrap();
+        let err = storage.append::<Sample>("widgets", &[]).unwrap_err();
+        assert!(matches!(err, StorageError::UnknownTable(_)));
+    }
+
+    #[test]
+    fn test_append_partitions_by_day() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(dir.path()).unwrap();
+        let records = vec![
+            Sample { ts: 10, value: 1.0 },
+            Sample {
+                ts: DAY_MS + 10,
+                value: 2.0,
+            },
+        ];
+        storage.append(METRICS_TABLE, &records).unwrap();
+
+        let days: Vec<String> = storage
+            .partitions(METRICS_TABLE)
+            .unwrap()
+            .into_iter()
+            .map(|(day, _)| day)
+            .collect();
+        assert_eq!(days, vec!["1970-01-01", "1970-01-02"]);
+    }
+
+    #[test]
+    fn test_read_filters_range_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let storage = Storage::open(dir.path()).unwrap();
+            let records: Vec<Sample> = (0..5)
+                .map(|i| Sample {
+                    ts: i * DAY_MS / 2,
+                    value: i as f64,
+                })
+                .collect();
+            storage.append(METRICS_TABLE, &records).unwrap();
+        }
+
+        let storage = Storage::open(dir.path()).unwrap();
+        let read: Vec<Sample> = storage.read(METRICS_TABLE, DAY_MS / 2, 2 * DAY_MS).unwrap();
+        let values: Vec<f64> = read.iter().map(|s| s.value).collect();
+        assert_eq!(values, vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_latest_reads_newest_partitions_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(dir.path()).unwrap();
+        let records: Vec<Sample> = (0..6)
+            .map(|i| Sample {
+                ts: i * DAY_MS / 2,
+                value: i as f64,
+            })
+            .collect();
+        storage.append(METRICS_TABLE, &records).unwrap();
+
+        let latest: Vec<Sample> = storage.latest(METRICS_TABLE, 3).unwrap();
+        let values: Vec<f64> = latest.iter().map(|s| s.value).collect();
+        assert_eq!(values, vec![3.0, 4.0, 5.0]);
+
+        let all: Vec<Sample> = storage.latest(METRICS_TABLE, 100).unwrap();
+        assert_eq!(all.len(), 6);
+        assert!(storage.latest::<Sample>(LOGS_TABLE, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_read_skips_malformed_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(dir.path()).unwrap();
+        storage
+            .append(METRICS_TABLE, &[Sample { ts: 1, value: 1.0 }])
+            .unwrap();
+        let path = storage.partitions(METRICS_TABLE).unwrap()[0].1.clone();
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(b"{\"ts\":2,\"val").unwrap();
+
+        let read: Vec<Sample> = storage.read(METRICS_TABLE, 0, 10).unwrap();
+        assert_eq!(read.len(), 1);
+    }
+}
@@ -0,0 +1,435 @@
+//! SQL queries over the local tables, run by DataFusion.
+//!
+//! Each table a query names is read from its partitions into memory, its
+//! schema inferred from the records, and registered with a fresh
+//! DataFusion session under the table's name, so any SQL DataFusion
+//! supports can be used, e.g.
+//!
+//! ```text
+//! SELECT node_id, avg(cpu_percent) FROM metrics
+//!     WHERE timestamp_ms >= 1700000000000 GROUP BY node_id
+//! ```
+//!
+//! Nested fields such as span and log attributes are struct columns, read
+//! with brackets: `attributes['node.id']`. Lines that are not JSON objects
+//! (e.g. a partially written tail) are skipped, as in [`Storage::read`].
+//!
+//! [`QueryLimits`] caps the rows a query may load and return and how long it
+//! may run, for callers such as the HTTP API.
+
+use datafusion::arrow::json::reader::{infer_json_schema_from_iterator, ReaderBuilder};
+use datafusion::arrow::json::ArrayWriter;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::datasource::MemTable;
+use datafusion::prelude::SessionContext;
+use serde_json::{Map, Value};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::{Storage, StorageError, TABLES};
+
+/// Records decoded into each record batch.
+const BATCH_ROWS: usize = 8192;
+
+/// Bounds on the work a query may do.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueryLimits {
+    /// Records loaded from the tables the query names, which are held in
+    /// memory.
+    pub max_scanned_rows: usize,
+    /// Rows in the result.
+    pub max_rows: usize,
+    /// Time to load the tables and run the query.
+    pub timeout: Option<Duration>,
+}
+
+impl QueryLimits {
+    pub const NONE: Self = Self {
+        max_scanned_rows: usize::MAX,
+        max_rows: usize::MAX,
+        timeout: None,
+    };
+}
+
+/// Columns and rows returned by a query.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl QueryResult {
+    /// The result as CSV with a header row.
+    pub fn to_csv(&self) -> String {
+        let mut csv = csv_line(self.columns.iter().map(|c| c.as_str()));
+        for row in &self.rows {
+            let cells: Vec<String> = row.iter().map(cell_text).collect();
+            csv.push_str(&csv_line(cells.iter().map(|c| c.as_str())));
+        }
+        csv
+    }
+
+    pub fn write_csv(&self, path: &Path) -> Result<(), StorageError> {
+        std::fs::write(path, self.to_csv())?;
+        Ok(())
+    }
+}
+
+/// Display text for a result cell: strings unquoted, null as empty.
+pub fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn csv_line<'a>(cells: impl Iterator<Item = &'a str>) -> String {
+    let mut line = cells
+        .map(|cell| {
+            if cell.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
+
+/// Run `sql` against the tables in `storage`.
+pub fn run_query(storage: &Storage, sql: &str) -> Result<QueryResult, StorageError> {
+    run_query_limited(storage, sql, &QueryLimits::NONE)
+}
+
+/// Run `sql` against the tables in `storage`, failing once it goes past
+/// `limits`.
+///
+/// Blocks on a runtime of its own, so it must not be called from async
+/// code.
+pub fn run_query_limited(
+    storage: &Storage,
+    sql: &str,
+    limits: &QueryLimits,
+) -> Result<QueryResult, StorageError> {
+    let deadline = limits.timeout.map(|timeout| Instant::now() + timeout);
+    let ctx = SessionContext::new();
+    let mut scanned = 0;
+    for table in TABLES.iter().filter(|table| mentions(sql, table)) {
+        let mem_table = load_table(storage, table, limits, &mut scanned, deadline)?;
+        ctx.register_table(*table, Arc::new(mem_table))
+            .map_err(query_error)?;
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()?;
+    let run = async {
+        let df = ctx.sql(sql).await.map_err(query_error)?;
+        let columns: Vec<String> = df
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect();
+        // One row past the cap tells a result that fits from one cut short
+        let batches = df
+            .limit(0, limits.max_rows.checked_add(1))
+            .map_err(query_error)?
+            .collect()
+            .await
+            .map_err(query_error)?;
+        Ok::<_, StorageError>((columns, batches))
+    };
+    let (columns, batches) = match deadline {
+        Some(deadline) => {
+            let left = deadline.saturating_duration_since(Instant::now());
+            runtime
+                .block_on(tokio::time::timeout(left, run))
+                .map_err(|_| timed_out())??
+        }
+        None => runtime.block_on(run)?,
+    };
+
+    let rows = result_rows(&columns, &batches)?;
+    if rows.len() > limits.max_rows {
+        return Err(StorageError::Query(format!(
+            "the result has more than {} rows, add a LIMIT",
+            limits.max_rows
+        )));
+    }
+    Ok(QueryResult { columns, rows })
+}
+
+/// Whether `sql` names `table` as a word, so that only tables a query can
+/// read are loaded.
+fn mentions(sql: &str, table: &str) -> bool {
+    sql.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .any(|word| word.eq_ignore_ascii_case(table))
+}
+
+/// Every record of `table` as an in-memory table, with a schema inferred
+/// from the records.
+fn load_table(
+    storage: &Storage,
+    table: &str,
+    limits: &QueryLimits,
+    scanned: &mut usize,
+    deadline: Option<Instant>,
+) -> Result<MemTable, StorageError> {
+    let mut records: Vec<Map<String, Value>> = Vec::new();
+    for (_, path) in storage.partitions(table)? {
+        if deadline.is_some_and(|d| Instant::now() >= d) {
+            return Err(timed_out());
+        }
+        let text = std::fs::read_to_string(path)?;
+        for line in text.lines() {
+            let Ok(Value::Object(record)) = serde_json::from_str(line) else {
+                continue;
+            };
+            if *scanned == limits.max_scanned_rows {
+                return Err(StorageError::Query(format!(
+                    "the tables queried hold more than {} rows",
+                    limits.max_scanned_rows
+                )));
+            }
+            *scanned += 1;
+            records.push(record);
+        }
+    }
+
+    let schema = Arc::new(
+        infer_json_schema_from_iterator(records.iter().map(|r| Ok(Value::Object(r.clone()))))
+            .map_err(query_error)?,
+    );
+    let mut decoder = ReaderBuilder::new(schema.clone())
+        .with_batch_size(BATCH_ROWS)
+        .with_coerce_primitive(true)
+        .build_decoder()
+        .map_err(query_error)?;
+    let mut batches = Vec::new();
+    for chunk in records.chunks(BATCH_ROWS) {
+        decoder.serialize(chunk).map_err(query_error)?;
+        batches.extend(decoder.flush().map_err(query_error)?);
+    }
+    MemTable::try_new(schema, vec![batches]).map_err(query_error)
+}
+
+/// Result rows as JSON values in `columns` order, null where a row has none.
+fn result_rows(
+    columns: &[String],
+    batches: &[RecordBatch],
+) -> Result<Vec<Vec<Value>>, StorageError> {
+    let mut writer = ArrayWriter::new(Vec::new());
+    writer
+        .write_batches(&batches.iter().collect::<Vec<_>>())
+        .map_err(query_error)?;
+    writer.finish().map_err(query_error)?;
+    let objects: Vec<Map<String, Value>> = serde_json::from_slice(&writer.into_inner())?;
+    Ok(objects
+        .into_iter()
+        .map(|mut object| {
+            columns
+                .iter()
+                .map(|column| object.remove(column).unwrap_or(Value::Null))
+                .collect()
+        })
+        .collect())
+}
+
+fn query_error(e: impl std::fmt::Display) -> StorageError {
+    StorageError::Query(e.to_string())
+}
+
+fn timed_out() -> StorageError {
+    StorageError::Query("the query took too long, narrow the time range".to_string())
+}
+
+/// Query for the newest rows of `table` whose attribute `key` is `value`,
+/// e.g. logs of one dataflow.
+pub fn attribute_query(table: &str, key: &str, value: &str) -> String {
+    format!(
+        "SELECT * FROM {} WHERE attributes['{}'] = '{}' ORDER BY timestamp_ms DESC LIMIT 100",
+        table,
+        key.replace('\'', "''"),
+        value.replace('\'', "''")
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{LOGS_TABLE, METRICS_TABLE};
+    use serde_json::json;
+
+    /// Storage whose `table` holds `lines` in one partition.
+    fn storage_with(dir: &Path, table: &str, lines: &[&str]) -> Storage {
+        let storage = Storage::open(dir).unwrap();
+        let mut text = lines.join("\n");
+        text.push('\n');
+        std::fs::write(
+            storage.table_dir(table).unwrap().join("1970-01-01.jsonl"),
+            text,
+        )
+        .unwrap();
+        storage
+    }
+
+    fn metrics(dir: &Path) -> Storage {
+        storage_with(
+            dir,
+            METRICS_TABLE,
+            &[
+                r#"{"timestamp_ms": 1, "node_id": "camera", "cpu_percent": 10.0}"#,
+                r#"{"timestamp_ms": 2, "node_id": "detector", "cpu_percent": 40.0}"#,
+                r#"{"timestamp_ms": 3, "node_id": "camera", "cpu_percent": 20.0}"#,
+                r#"{"timestamp_ms": 4, "node_id": "plot", "cpu_percent": null}"#,
+            ],
+        )
+    }
+
+    #[test]
+    fn test_select_with_filter_order_and_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = metrics(dir.path());
+        let result = run_query(
+            &storage,
+            "select node_id, cpu_percent as cpu from metrics \
+             where cpu_percent >= 15 order by cpu desc limit 1",
+        )
+        .unwrap();
+        assert_eq!(result.columns, vec!["node_id", "cpu"]);
+        assert_eq!(result.rows, vec![vec![json!("detector"), json!(40.0)]]);
+    }
+
+    #[test]
+    fn test_group_by_aggregates() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = metrics(dir.path());
+        let result = run_query(
+            &storage,
+            "SELECT node_id, count(*) AS samples, avg(cpu_percent) AS avg_cpu \
+             FROM metrics GROUP BY node_id ORDER BY node_id",
+        )
+        .unwrap();
+        assert_eq!(result.columns, vec!["node_id", "samples", "avg_cpu"]);
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![json!("camera"), json!(2), json!(15.0)],
+                vec![json!("detector"), json!(1), json!(40.0)],
+                vec![json!("plot"), json!(1), Value::Null],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_attribute_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = storage_with(
+            dir.path(),
+            LOGS_TABLE,
+            &[
+                r#"{"timestamp_ms": 1, "body": "frame", "attributes": {"node.id": "it's"}}"#,
+                r#"{"timestamp_ms": 2, "body": "plan", "attributes": {"node.id": "its"}}"#,
+            ],
+        );
+        let sql = attribute_query(LOGS_TABLE, "node.id", "it's");
+        let result = run_query(&storage, &sql).unwrap();
+        assert_eq!(result.rows.len(), 1);
+        let body = result.columns.iter().position(|c| c == "body").unwrap();
+        assert_eq!(result.rows[0][body], json!("frame"));
+    }
+
+    #[test]
+    fn test_csv_escapes_cells() {
+        let result = QueryResult {
+            columns: vec!["node".to_string(), "message".to_string()],
+            rows: vec![
+                vec![json!("camera"), json!("a, \"b\"")],
+                vec![json!(1), Value::Null],
+            ],
+        };
+        assert_eq!(
+            result.to_csv(),
+            "node,message\ncamera,\"a, \"\"b\"\"\"\n1,\n"
+        );
+    }
+
+    #[test]
+    fn test_mentions() {
+        assert!(mentions("select * from LOGS where x = 1", "logs"));
+        assert!(mentions("SELECT count(*) FROM spans;", "spans"));
+        assert!(!mentions("SELECT * FROM logs_archive", "logs"));
+    }
+
+    #[test]
+    fn test_query_limits() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = storage_with(
+            dir.path(),
+            LOGS_TABLE,
+            &[
+                r#"{"level":"ERROR"}"#,
+                r#"{"level":"INFO"}"#,
+                r#"{"level":"INFO"}"#,
+            ],
+        );
+        let limits = QueryLimits {
+            max_scanned_rows: 3,
+            max_rows: 1,
+            timeout: None,
+        };
+        let run = |sql: &str| run_query_limited(&storage, sql, &limits);
+        assert!(run("SELECT * FROM logs WHERE level = 'INFO'")
+            .unwrap_err()
+            .to_string()
+            .contains("add a LIMIT"));
+        assert_eq!(
+            run("SELECT * FROM logs WHERE level = 'INFO' LIMIT 1")
+                .unwrap()
+                .rows
+                .len(),
+            1
+        );
+        let scanned = QueryLimits {
+            max_scanned_rows: 2,
+            ..QueryLimits::NONE
+        };
+        assert!(run_query_limited(&storage, "SELECT * FROM logs", &scanned).is_err());
+        let timed_out = QueryLimits {
+            timeout: Some(Duration::ZERO),
+            ..QueryLimits::NONE
+        };
+        assert!(run_query_limited(&storage, "SELECT * FROM logs", &timed_out).is_err());
+    }
+
+    #[test]
+    fn test_run_query_reads_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = storage_with(
+            dir.path(),
+            LOGS_TABLE,
+            &[r#"{"level":"ERROR"}"#, r#"{"level":"INFO"}"#, "not json"],
+        );
+
+        let result = run_query(
+            &storage,
+            "SELECT count(*) AS n FROM logs WHERE level = 'ERROR'",
+        )
+        .unwrap();
+        assert_eq!(result.rows, vec![vec![json!(1)]]);
+        // An empty table has no columns but can still be counted
+        let result = run_query(&storage, "SELECT count(*) AS n FROM spans").unwrap();
+        assert_eq!(result.rows, vec![vec![json!(0)]]);
+        let err = run_query(&storage, "SELECT * FROM widgets").unwrap_err();
+        assert!(matches!(err, StorageError::Query(_)));
+    }
+}
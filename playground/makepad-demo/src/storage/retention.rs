@@ -0,0 +1,392 @@
+//! Retention of local tables.
+//!
+//! Each table keeps records for a number of hours (`spans=7d, logs=24h`);
+//! settings override the defaults per table and 0 keeps a table forever.
+//! Pruning deletes partitions that lie entirely before the cutoff and
+//! rewrites the partition straddling it without the expired records, dropping
+//! malformed lines (e.g. a partially written tail) on the way. Today's
+//! partition is never rewritten, since the sampler may be appending to it,
+//! and imported partitions are kept however old the recording is.
+//!
+//! Partitions are not merged: each day is a single file per table (and one
+//! more for imported records), so there are no small files to combine until
+//! storage moves to Parquet (tracked as C-08 in `ISSUES.md`).
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use super::{
+    is_imported, partition_name, partition_start_ms, Storage, StorageError, LOGS_TABLE,
+    MESSAGES_TABLE, METRICS_TABLE, SPANS_TABLE, TABLES,
+};
+use crate::i18n;
+use crate::units::{self, Unit};
+
+const HOUR_MS: u64 = 3_600_000;
+const DAY_MS: u64 = 24 * HOUR_MS;
+
+/// Hours of data kept per table unless overridden in settings.
+pub const DEFAULT_RETENTION_HOURS: [(&str, u64); 4] = [
+    (METRICS_TABLE, 7 * 24),
+    (LOGS_TABLE, 24),
+    (SPANS_TABLE, 7 * 24),
+    (MESSAGES_TABLE, 7 * 24),
+];
+
+/// Retention per table: the defaults with `overrides` applied.
+pub fn retention_hours(overrides: &BTreeMap<String, u64>) -> BTreeMap<String, u64> {
+    DEFAULT_RETENTION_HOURS
+        .iter()
+        .map(|(table, hours)| {
+            let hours = overrides.get(*table).copied().unwrap_or(*hours);
+            (table.to_string(), hours)
+        })
+        .collect()
+}
+
+/// Parse overrides like `spans=7d, logs=24h, metrics=0`.
+pub fn parse_retention(text: &str) -> Result<BTreeMap<String, u64>, String> {
+    let mut retention = BTreeMap::new();
+    for entry in text.split([',', '\n']).map(str::trim) {
+        if entry.is_empty() {
+            continue;
+        }
+        let (table, duration) = entry
+            .split_once('=')
+            .ok_or_else(|| i18n::tr_args("retention.expected_entry", &[("entry", entry)]))?;
+        let table = table.trim();
+        if !TABLES.contains(&table) {
+            return Err(i18n::tr_args(
+                "retention.unknown_table",
+                &[("table", table), ("tables", &TABLES.join(", "))],
+            ));
+        }
+        retention.insert(table.to_string(), parse_hours(duration.trim())?);
+    }
+    Ok(retention)
+}
+
+/// `24h`, `7d` or `0` as hours.
+fn parse_hours(text: &str) -> Result<u64, String> {
+    let invalid = || i18n::tr_args("retention.invalid", &[("text", text)]);
+    if text == "0" {
+        return Ok(0);
+    }
+    let (number, factor) = if let Some(days) = text.strip_suffix('d') {
+        (days, 24)
+    } else if let Some(hours) = text.strip_suffix('h') {
+        (hours, 1)
+    } else {
+        return Err(invalid());
+    };
+    let number: u64 = number.trim().parse().map_err(|_| invalid())?;
+    Ok(number * factor)
+}
+
+/// Format retention for the settings input, e.g. `logs=24h, spans=7d`.
+pub fn format_retention(retention: &BTreeMap<String, u64>) -> String {
+    retention
+        .iter()
+        .map(|(table, hours)| match *hours {
+            0 => format!("{}=0", table),
+            h if h % 24 == 0 => format!("{}={}d", table, h / 24),
+            h => format!("{}={}h", table, h),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// What a pruning pass removed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PruneSummary {
+    pub deleted_partitions: usize,
+    pub rewritten_partitions: usize,
+    pub dropped_records: usize,
+    pub bytes_freed: u64,
+}
+
+impl PruneSummary {
+    pub fn describe(&self) -> String {
+        format!(
+            "Pruned storage: {} partitions deleted, {} rewritten, {} freed",
+            self.deleted_partitions,
+            self.rewritten_partitions,
+            units::format(self.bytes_freed as f64, Unit::Bytes)
+        )
+    }
+}
+
+/// Disk use of one table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableUsage {
+    pub table: String,
+    pub bytes: u64,
+    pub partitions: usize,
+}
+
+/// Usage readout such as `metrics 1.2 MiB (3 days) · logs 0 B (0 days)`.
+pub fn describe_usage(usage: &[TableUsage]) -> String {
+    usage
+        .iter()
+        .map(|u| {
+            let key = match u.partitions {
+                1 => "storage.usage_day",
+                _ => "storage.usage_days",
+            };
+            i18n::tr_args(
+                key,
+                &[
+                    ("table", &u.table),
+                    ("size", &units::format(u.bytes as f64, Unit::Bytes)),
+                    ("count", &u.partitions.to_string()),
+                ],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// Field holding the event time of records in `table`.
+fn timestamp_field(table: &str) -> &'static str {
+    if table == SPANS_TABLE {
+        "start_time_ms"
+    } else {
+        "timestamp_ms"
+    }
+}
+
+impl Storage {
+    /// Bytes and partitions per table.
+    pub fn usage(&self) -> Result<Vec<TableUsage>, StorageError> {
+        let mut usage = Vec::new();
+        for table in TABLES {
+            let partitions = self.partitions(table)?;
+            let mut bytes = 0;
+            for (_, path) in &partitions {
+                bytes += std::fs::metadata(path)?.len();
+            }
+            usage.push(TableUsage {
+                table: table.to_string(),
+                bytes,
+                partitions: partitions.len(),
+            });
+        }
+        Ok(usage)
+    }
+
+    /// Apply `retention` (hours per table, 0 = forever) as of `now_ms`.
+    pub fn prune(
+        &self,
+        retention: &BTreeMap<String, u64>,
+        now_ms: u64,
+    ) -> Result<PruneSummary, StorageError> {
+        let today = partition_name(now_ms);
+        let mut summary = PruneSummary::default();
+        for (table, hours) in retention {
+            if *hours == 0 {
+                continue;
+            }
+            let cutoff_ms = now_ms.saturating_sub(hours * HOUR_MS);
+            for (day, path) in self.partitions(table)? {
+                let Some(start_ms) = partition_start_ms(&day) else {
+                    continue;
+                };
+                if is_imported(&path) {
+                    continue;
+                }
+                let size = std::fs::metadata(&path)?.len();
+                if start_ms + DAY_MS <= cutoff_ms {
+                    std::fs::remove_file(&path)?;
+                    summary.deleted_partitions += 1;
+                    summary.bytes_freed += size;
+                } else if start_ms < cutoff_ms && day != today {
+                    let dropped =
+                        self.rewrite_partition(&path, timestamp_field(table), cutoff_ms)?;
+                    if dropped > 0 {
+                        let new_size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                        summary.rewritten_partitions += 1;
+                        summary.dropped_records += dropped;
+                        summary.bytes_freed += size.saturating_sub(new_size);
+                    }
+                }
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Rewrite a partition keeping records at or after `cutoff_ms`, and
+    /// return how many lines were dropped. The new file replaces the old one
+    /// by rename, so readers never see a partial partition.
+    fn rewrite_partition(
+        &self,
+        path: &std::path::Path,
+        field: &str,
+        cutoff_ms: u64,
+    ) -> Result<usize, StorageError> {
+        let text = std::fs::read_to_string(path)?;
+        let mut kept = String::new();
+        let mut dropped = 0;
+        for line in text.lines() {
+            let keep = serde_json::from_str::<serde_json::Value>(line)
+                .ok()
+                .filter(|record| record.is_object())
+                .is_some_and(|record| {
+                    record
+                        .get(field)
+                        .and_then(|ts| ts.as_u64())
+                        .is_none_or(|ts| ts >= cutoff_ms)
+                });
+            if keep {
+                kept.push_str(line);
+                kept.push('\n');
+            } else {
+                dropped += 1;
+            }
+        }
+        if dropped == 0 {
+            return Ok(0);
+        }
+
+        if kept.is_empty() {
+            std::fs::remove_file(path)?;
+        } else {
+            let tmp = path.with_extension("jsonl.tmp");
+            let mut file = std::fs::File::create(&tmp)?;
+            file.write_all(kept.as_bytes())?;
+            file.sync_all()?;
+            std::fs::rename(&tmp, path)?;
+        }
+        Ok(dropped)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_partition(storage: &Storage, table: &str, day_ms: u64, lines: &[String]) {
+        let path = storage
+            .table_dir(table)
+            .unwrap()
+            .join(format!("{}.jsonl", partition_name(day_ms)));
+        std::fs::write(path, lines.join("\n") + "\n").unwrap();
+    }
+
+    fn log_line(ts: u64) -> String {
+        format!("{{\"timestamp_ms\":{},\"body\":\"x\"}}", ts)
+    }
+
+    fn days(storage: &Storage, table: &str) -> Vec<String> {
+        storage
+            .partitions(table)
+            .unwrap()
+            .into_iter()
+            .map(|(day, _)| day)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_and_format_retention() {
+        let retention = parse_retention("spans=7d, logs = 36h,\nmetrics=0").unwrap();
+        assert_eq!(retention["spans"], 168);
+        assert_eq!(retention["logs"], 36);
+        assert_eq!(retention["metrics"], 0);
+        assert_eq!(
+            format_retention(&retention),
+            "logs=36h, metrics=0, spans=7d"
+        );
+        assert_eq!(parse_retention("").unwrap(), BTreeMap::new());
+
+        assert!(parse_retention("widgets=1d")
+            .unwrap_err()
+            .contains("Unknown table"));
+        assert!(parse_retention("logs=1w")
+            .unwrap_err()
+            .contains("Invalid retention"));
+        assert!(parse_retention("logs")
+            .unwrap_err()
+            .contains("table=duration"));
+    }
+
+    #[test]
+    fn test_retention_hours_applies_overrides() {
+        let overrides = BTreeMap::from([("logs".to_string(), 0)]);
+        let retention = retention_hours(&overrides);
+        assert_eq!(retention["logs"], 0);
+        assert_eq!(retention["spans"], 168);
+        assert_eq!(retention.len(), TABLES.len());
+    }
+
+    #[test]
+    fn test_prune_deletes_expired_and_trims_boundary() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(dir.path()).unwrap();
+        let now = 10 * DAY_MS + 12 * HOUR_MS;
+        // 30h before now is day 9, 06:00: day 8 is expired, day 9 straddles
+        // the cutoff and today is left alone even with old records
+        write_partition(&storage, LOGS_TABLE, 8 * DAY_MS, &[log_line(8 * DAY_MS)]);
+        write_partition(
+            &storage,
+            LOGS_TABLE,
+            9 * DAY_MS,
+            &[
+                log_line(9 * DAY_MS + HOUR_MS),
+                log_line(9 * DAY_MS + 7 * HOUR_MS),
+                "{\"timestamp".to_string(),
+            ],
+        );
+        write_partition(&storage, LOGS_TABLE, now, &[log_line(1)]);
+
+        let retention = BTreeMap::from([("logs".to_string(), 30)]);
+        let summary = storage.prune(&retention, now).unwrap();
+        assert_eq!(summary.deleted_partitions, 1);
+        assert_eq!(summary.rewritten_partitions, 1);
+        assert_eq!(summary.dropped_records, 2);
+        assert!(summary.bytes_freed > 0);
+
+        let remaining = days(&storage, LOGS_TABLE);
+        assert_eq!(
+            remaining,
+            vec![partition_name(9 * DAY_MS), partition_name(now)]
+        );
+        let path = &storage.partitions(LOGS_TABLE).unwrap()[0].1;
+        assert_eq!(
+            std::fs::read_to_string(path).unwrap(),
+            log_line(9 * DAY_MS + 7 * HOUR_MS) + "\n"
+        );
+    }
+
+    #[test]
+    fn test_prune_keeps_tables_without_retention() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(dir.path()).unwrap();
+        write_partition(
+            &storage,
+            SPANS_TABLE,
+            0,
+            &["{\"start_time_ms\":0}".to_string()],
+        );
+
+        let retention = BTreeMap::from([("spans".to_string(), 0)]);
+        let summary = storage.prune(&retention, 100 * DAY_MS).unwrap();
+        assert_eq!(summary, PruneSummary::default());
+        assert_eq!(days(&storage, SPANS_TABLE).len(), 1);
+    }
+
+    #[test]
+    fn test_usage() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(dir.path()).unwrap();
+        write_partition(&storage, METRICS_TABLE, 0, &["{}".to_string()]);
+
+        let usage = storage.usage().unwrap();
+        assert_eq!(usage.len(), TABLES.len());
+        assert_eq!(usage[0].table, METRICS_TABLE);
+        assert_eq!((usage[0].bytes, usage[0].partitions), (3, 1));
+        assert!(describe_usage(&usage).starts_with("metrics 3 B (1 day) · logs 0 B (0 days)"));
+    }
+}
@@ -0,0 +1,130 @@
+//! Which fetched spans are already in the spans table.
+//!
+//! Trace queries overlap (each refresh asks for the last N minutes, and the
+//! live tail reaches back past the newest span shown), so the same span is
+//! fetched many times. Spans are told apart by trace and span ID; those
+//! starting too long before the newest stored span to still be tracked are
+//! taken to be stored already.
+
+use std::collections::{HashMap, HashSet};
+
+use super::{Storage, StorageError, SPANS_TABLE};
+use crate::otlp::types::Span;
+
+/// How far before the newest stored span IDs are remembered.
+pub const TRACKED_WINDOW_MS: u64 = 15 * 60_000;
+
+/// IDs of the spans stored recently.
+#[derive(Debug, Default)]
+pub struct StoredSpans {
+    /// Start time by `(trace ID, span ID)`
+    ids: HashMap<(String, String), u64>,
+    newest_ms: u64,
+}
+
+impl StoredSpans {
+    /// Track the spans of the last [`TRACKED_WINDOW_MS`] in `storage`.
+    pub fn load(storage: &Storage) -> Result<Self, StorageError> {
+        let mut stored = Self::default();
+        let Some(newest) = storage.latest::<Span>(SPANS_TABLE, 1)?.pop() else {
+            return Ok(stored);
+        };
+        let start_ms = newest.start_time_ms.saturating_sub(TRACKED_WINDOW_MS);
+        let end_ms = newest.start_time_ms + 1;
+        let spans: Vec<Span> = storage.read(SPANS_TABLE, start_ms, end_ms)?;
+        stored.insert(&spans);
+        Ok(stored)
+    }
+
+    /// The spans in `spans` not stored yet, each once.
+    pub fn unstored(&self, spans: &[Span]) -> Vec<Span> {
+        let oldest_ms = self.newest_ms.saturating_sub(TRACKED_WINDOW_MS);
+        let mut seen = HashSet::new();
+        spans
+            .iter()
+            .filter(|s| s.start_time_ms >= oldest_ms)
+            .filter(|s| !self.ids.contains_key(&key(s)))
+            .filter(|s| seen.insert(key(s)))
+            .cloned()
+            .collect()
+    }
+
+    /// Remember `spans` as stored, forgetting those that fell out of the
+    /// tracked window.
+    pub fn insert(&mut self, spans: &[Span]) {
+        for span in spans {
+            self.ids.insert(key(span), span.start_time_ms);
+            self.newest_ms = self.newest_ms.max(span.start_time_ms);
+        }
+        let oldest_ms = self.newest_ms.saturating_sub(TRACKED_WINDOW_MS);
+        self.ids.retain(|_, start_ms| *start_ms >= oldest_ms);
+    }
+}
+
+fn key(span: &Span) -> (String, String) {
+    (span.trace_id.clone(), span.span_id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(trace_id: &str, span_id: &str, start_time_ms: u64) -> Span {
+        Span {
+            trace_id: trace_id.to_string(),
+            span_id: span_id.to_string(),
+            parent_span_id: None,
+            service_name: "camera".to_string(),
+            operation_name: "capture".to_string(),
+            start_time_ms,
+            duration_ms: 1,
+            status_code: 0,
+            has_error: false,
+            attributes: Default::default(),
+            events: Vec::new(),
+            links: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_unstored_skips_known_ids() {
+        let mut stored = StoredSpans::default();
+        stored.insert(&[span("t", "a", 1_000)]);
+        // Same start, different span: the old timestamp rule dropped it
+        let fetched = vec![
+            span("t", "a", 1_000),
+            span("t", "b", 1_000),
+            span("t", "b", 1_000),
+        ];
+        let new = stored.unstored(&fetched);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].span_id, "b");
+        stored.insert(&new);
+        assert!(stored.unstored(&fetched).is_empty());
+    }
+
+    #[test]
+    fn test_untracked_spans_count_as_stored() {
+        let mut stored = StoredSpans::default();
+        stored.insert(&[span("t", "a", 0), span("t", "b", TRACKED_WINDOW_MS + 1)]);
+        assert_eq!(stored.ids.len(), 1);
+        assert!(stored.unstored(&[span("t", "a", 0)]).is_empty());
+        assert_eq!(stored.unstored(&[span("t", "c", 2)]).len(), 1);
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Storage::open(dir.path()).unwrap();
+        let now = 1_700_000_000_000;
+        let spans = vec![
+            span("t", "a", now - TRACKED_WINDOW_MS - 1),
+            span("t", "b", now),
+        ];
+        storage.append(SPANS_TABLE, &spans).unwrap();
+        let stored = StoredSpans::load(&storage).unwrap();
+        assert_eq!(stored.newest_ms, now);
+        assert_eq!(stored.ids.len(), 1);
+        assert!(stored.unstored(&spans).is_empty());
+    }
+}
@@ -0,0 +1,412 @@
+//! Background tasks with progress reporting and cancellation.
+//!
+//! Long-running one-off work (exports, imports, bulk operations) is spawned
+//! through a [`TaskManager`] instead of ad-hoc threads. Each task runs on its
+//! own thread, or on the shared runtime when it is async, with a
+//! [`TaskContext`] to report progress and check for cancellation; the UI
+//! polls [`TaskManager::poll`] on its frame tick (same polling pattern as the
+//! SigNoz bridge) and lists running tasks from [`TaskManager::running`].
+//!
+//! Cancellation is cooperative: a task stops at its next
+//! [`TaskContext::check_cancelled`], and its result is reported as
+//! [`TaskOutcome::Cancelled`] either way.
+//!
+//! Async work shares one Tokio [`runtime`]: tasks spawned with
+//! [`TaskManager::spawn_async`] and the long-lived workers (the telemetry
+//! bridge, the LLM client). Requests those workers have in flight are
+//! counted with a [`BackgroundJob`] so the status bar can show them.
+
+use std::any::Any;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, OnceLock};
+
+use tokio::runtime::Runtime;
+
+use crate::i18n;
+use crate::logging;
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+static BACKGROUND_JOBS: AtomicUsize = AtomicUsize::new(0);
+
+/// The Tokio runtime shared by background workers and async tasks,
+/// started on first use.
+pub fn runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .thread_name("studio-jobs")
+            .enable_all()
+            .build()
+            .expect("Failed to create the background job runtime")
+    })
+}
+
+/// A worker request in flight, e.g. a trace query or an LLM call, counted
+/// by [`background_jobs`] until dropped.
+pub struct BackgroundJob(());
+
+impl BackgroundJob {
+    pub fn start() -> Self {
+        BACKGROUND_JOBS.fetch_add(1, Ordering::Relaxed);
+        Self(())
+    }
+}
+
+impl Drop for BackgroundJob {
+    fn drop(&mut self) {
+        BACKGROUND_JOBS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Worker requests in flight.
+pub fn background_jobs() -> usize {
+    BACKGROUND_JOBS.load(Ordering::Relaxed)
+}
+
+pub type TaskId = u64;
+
+/// Handed to a running task.
+pub struct TaskContext {
+    id: TaskId,
+    cancelled: Arc<AtomicBool>,
+    sender: Sender<Update>,
+}
+
+impl TaskContext {
+    /// Report progress, `fraction` between 0 and 1 or `None` if unknown.
+    pub fn progress(&self, fraction: Option<f32>, message: &str) {
+        let _ = self.sender.send(Update::Progress {
+            id: self.id,
+            fraction: fraction.map(|f| f.clamp(0.0, 1.0)),
+            message: message.to_string(),
+        });
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Err if the task was cancelled, for use with `?` between steps.
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err("Cancelled".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// How a task ended.
+pub enum TaskOutcome {
+    /// The task's return value; downcast to the type the task returned.
+    Done(Box<dyn Any + Send>),
+    Failed(String),
+    Cancelled,
+}
+
+impl std::fmt::Debug for TaskOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskOutcome::Done(_) => write!(f, "Done"),
+            TaskOutcome::Failed(e) => write!(f, "Failed({})", e),
+            TaskOutcome::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+/// A task that ended, returned by [`TaskManager::poll`].
+#[derive(Debug)]
+pub struct FinishedTask {
+    pub id: TaskId,
+    pub name: String,
+    pub outcome: TaskOutcome,
+}
+
+/// A task still running.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskInfo {
+    pub id: TaskId,
+    pub name: String,
+    pub started_ms: u64,
+    pub fraction: Option<f32>,
+    pub message: String,
+    /// Cancellation was requested but the task has not stopped yet.
+    pub cancelling: bool,
+}
+
+impl TaskInfo {
+    /// e.g. `Importing capture 45%` or `Exporting settings (cancelling)`.
+    pub fn describe(&self) -> String {
+        let mut text = self.name.clone();
+        if let Some(fraction) = self.fraction {
+            text.push_str(&format!(" {:.0}%", fraction * 100.0));
+        }
+        if self.cancelling {
+            text.push_str(&i18n::tr("task.cancelling"));
+        } else if !self.message.is_empty() {
+            text.push_str(&format!(": {}", self.message));
+        }
+        text
+    }
+}
+
+enum Update {
+    Progress {
+        id: TaskId,
+        fraction: Option<f32>,
+        message: String,
+    },
+    Finished {
+        id: TaskId,
+        result: Result<Box<dyn Any + Send>, String>,
+    },
+}
+
+pub struct TaskManager {
+    next_id: TaskId,
+    running: Vec<TaskInfo>,
+    cancel_flags: Vec<(TaskId, Arc<AtomicBool>)>,
+    sender: Sender<Update>,
+    receiver: Receiver<Update>,
+}
+
+impl Default for TaskManager {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            next_id: 1,
+            running: Vec::new(),
+            cancel_flags: Vec::new(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl TaskManager {
+    /// Run `work` on a background thread.
+    pub fn spawn<T, F>(&mut self, name: &str, work: F) -> TaskId
+    where
+        T: Send + 'static,
+        F: FnOnce(&TaskContext) -> Result<T, String> + Send + 'static,
+    {
+        let context = self.register(name);
+        let id = context.id;
+        std::thread::spawn(move || {
+            let result = work(&context).map(|value| Box::new(value) as Box<dyn Any + Send>);
+            let _ = context.sender.send(Update::Finished { id, result });
+        });
+        id
+    }
+
+    /// Run the future `work` returns on the shared [`runtime`], for tasks
+    /// that wait on I/O rather than block.
+    pub fn spawn_async<T, F, Fut>(&mut self, name: &str, work: F) -> TaskId
+    where
+        T: Send + 'static,
+        F: FnOnce(TaskContext) -> Fut,
+        Fut: Future<Output = Result<T, String>> + Send + 'static,
+    {
+        let context = self.register(name);
+        let id = context.id;
+        let sender = context.sender.clone();
+        let future = work(context);
+        runtime().spawn(async move {
+            let result = future
+                .await
+                .map(|value| Box::new(value) as Box<dyn Any + Send>);
+            let _ = sender.send(Update::Finished { id, result });
+        });
+        id
+    }
+
+    /// List a new task as running and make its context.
+    fn register(&mut self, name: &str) -> TaskContext {
+        let id = self.next_id;
+        self.next_id += 1;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.cancel_flags.push((id, cancelled.clone()));
+        self.running.push(TaskInfo {
+            id,
+            name: name.to_string(),
+            started_ms: logging::now_ms(),
+            fraction: None,
+            message: String::new(),
+            cancelling: false,
+        });
+
+        tracing::debug!(target: "Tasks", "Starting task {} ({})", id, name);
+        TaskContext {
+            id,
+            cancelled,
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Request cancellation; returns false if the task is not running.
+    pub fn cancel(&mut self, id: TaskId) -> bool {
+        let Some(task) = self.running.iter_mut().find(|t| t.id == id) else {
+            return false;
+        };
+        task.cancelling = true;
+        if let Some((_, flag)) = self.cancel_flags.iter().find(|(i, _)| *i == id) {
+            flag.store(true, Ordering::Relaxed);
+        }
+        true
+    }
+
+    /// Apply progress updates and collect finished tasks. Returns whether
+    /// anything changed, so the caller knows to redraw.
+    pub fn poll(&mut self) -> (bool, Vec<FinishedTask>) {
+        let mut changed = false;
+        let mut finished = Vec::new();
+        while let Ok(update) = self.receiver.try_recv() {
+            changed = true;
+            match update {
+                Update::Progress {
+                    id,
+                    fraction,
+                    message,
+                } => {
+                    if let Some(task) = self.running.iter_mut().find(|t| t.id == id) {
+                        task.fraction = fraction;
+                        task.message = message;
+                    }
+                }
+                Update::Finished { id, result } => {
+                    let Some(index) = self.running.iter().position(|t| t.id == id) else {
+                        continue;
+                    };
+                    let task = self.running.remove(index);
+                    self.cancel_flags.retain(|(i, _)| *i != id);
+                    let outcome = match result {
+                        _ if task.cancelling => TaskOutcome::Cancelled,
+                        Ok(value) => TaskOutcome::Done(value),
+                        Err(e) => TaskOutcome::Failed(e),
+                    };
+                    tracing::debug!(
+                        target: "Tasks",
+                        "Task {} ({}) ended: {:?}",
+                        id,
+                        task.name,
+                        outcome
+                    );
+                    finished.push(FinishedTask {
+                        id,
+                        name: task.name,
+                        outcome,
+                    });
+                }
+            }
+        }
+        (changed, finished)
+    }
+
+    pub fn running(&self) -> &[TaskInfo] {
+        &self.running
+    }
+
+    /// One line summarizing running tasks, empty when idle.
+    pub fn describe(&self) -> String {
+        self.running
+            .iter()
+            .map(TaskInfo::describe)
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// Poll until `count` tasks finished.
+    fn wait_for(manager: &mut TaskManager, count: usize) -> Vec<FinishedTask> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut finished = Vec::new();
+        while finished.len() < count && Instant::now() < deadline {
+            finished.extend(manager.poll().1);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        finished
+    }
+
+    #[test]
+    fn test_task_result_is_delivered() {
+        let mut manager = TaskManager::default();
+        let id = manager.spawn("Adding", |ctx| {
+            ctx.progress(Some(0.5), "halfway");
+            Ok(40 + 2)
+        });
+        assert_eq!(manager.running().len(), 1);
+
+        let finished = wait_for(&mut manager, 1);
+        assert_eq!(finished[0].id, id);
+        match &finished[0].outcome {
+            TaskOutcome::Done(value) => assert_eq!(value.downcast_ref::<i32>(), Some(&42)),
+            other => panic!("unexpected outcome {:?}", other),
+        }
+        assert!(manager.running().is_empty());
+    }
+
+    #[test]
+    fn test_async_task_result_is_delivered() {
+        let mut manager = TaskManager::default();
+        manager.spawn_async("Waiting", |ctx| async move {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            ctx.check_cancelled()?;
+            Ok("done")
+        });
+        let finished = wait_for(&mut manager, 1);
+        match &finished[0].outcome {
+            TaskOutcome::Done(value) => assert_eq!(value.downcast_ref::<&str>(), Some(&"done")),
+            other => panic!("unexpected outcome {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_background_job_is_counted_until_dropped() {
+        let job = BackgroundJob::start();
+        assert!(background_jobs() >= 1);
+        drop(job);
+    }
+
+    #[test]
+    fn test_failed_task() {
+        let mut manager = TaskManager::default();
+        manager.spawn::<(), _>("Failing", |_| Err("disk full".to_string()));
+        let finished = wait_for(&mut manager, 1);
+        assert!(matches!(&finished[0].outcome, TaskOutcome::Failed(e) if e == "disk full"));
+    }
+
+    #[test]
+    fn test_cancellation_is_cooperative() {
+        let mut manager = TaskManager::default();
+        let id = manager.spawn::<(), _>("Looping", |ctx| loop {
+            ctx.check_cancelled()?;
+            std::thread::sleep(Duration::from_millis(1));
+        });
+        assert!(manager.cancel(id));
+        assert!(manager.running()[0].cancelling);
+        assert_eq!(manager.describe(), "Looping (cancelling)");
+
+        let finished = wait_for(&mut manager, 1);
+        assert!(matches!(finished[0].outcome, TaskOutcome::Cancelled));
+        assert!(!manager.cancel(id));
+    }
+
+    #[test]
+    fn test_describe_progress() {
+        let task = TaskInfo {
+            id: 1,
+            name: "Importing capture".to_string(),
+            started_ms: 0,
+            fraction: Some(0.45),
+            message: "spans".to_string(),
+            cancelling: false,
+        };
+        assert_eq!(task.describe(), "Importing capture 45%: spans");
+    }
+}
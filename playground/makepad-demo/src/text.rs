@@ -0,0 +1,157 @@
+//! Text helpers shared by widgets.
+//!
+//! All truncation works on extended grapheme clusters so multi-byte UTF-8
+//! (accents, CJK, emoji sequences) is never split mid-character.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+const ELLIPSIS: &str = "...";
+
+/// Keep the first `max` graphemes, appending `...` when text was cut.
+pub fn truncate(s: &str, max: usize) -> String {
+    match s.grapheme_indices(true).nth(max) {
+        Some((idx, _)) => format!("{}{}", &s[..idx], ELLIPSIS),
+        None => s.to_string(),
+    }
+}
+
+/// Keep `max` graphemes split between the start and end, with `...` in the
+/// middle. Suited to long IDs where both prefix and suffix are meaningful.
+pub fn truncate_middle(s: &str, max: usize) -> String {
+    let graphemes: Vec<&str> = s.graphemes(true).collect();
+    if graphemes.len() <= max {
+        return s.to_string();
+    }
+    let head = max.div_ceil(2);
+    let tail = max / 2;
+    format!(
+        "{}{}{}",
+        graphemes[..head].concat(),
+        ELLIPSIS,
+        graphemes[graphemes.len() - tail..].concat()
+    )
+}
+
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Render values as a block-character sparkline scaled between their min and
+/// max. A flat series renders at the lowest level.
+pub fn sparkline<I: IntoIterator<Item = f64>>(values: I) -> String {
+    let values: Vec<f64> = values.into_iter().filter(|v| v.is_finite()).collect();
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+    let top = (SPARK_LEVELS.len() - 1) as f64;
+    values
+        .iter()
+        .map(|v| {
+            let level = if range > 0.0 {
+                ((v - min) / range * top).round() as usize
+            } else {
+                0
+            };
+            SPARK_LEVELS[level.min(SPARK_LEVELS.len() - 1)]
+        })
+        .collect()
+}
+
+/// Line diff of `old` to `new`: every line prefixed with `+ `, `- ` or two
+/// spaces when unchanged, from a longest common subsequence of lines.
+pub fn line_diff(old: &str, new: &str) -> String {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    // common[i][j]: longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            diff.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            diff.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+    diff.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff() {
+        assert_eq!(
+            line_diff(
+                "nodes:\n  - id: a\n  - id: b",
+                "nodes:\n  - id: a\n  - id: c"
+            ),
+            "  nodes:\n    - id: a\n-   - id: b\n+   - id: c"
+        );
+        assert_eq!(line_diff("", "a\nb"), "+ a\n+ b");
+        assert_eq!(line_diff("a", "a"), "  a");
+    }
+
+    #[test]
+    fn test_truncate_ascii() {
+        assert_eq!(truncate("hello", 10), "hello");
+        assert_eq!(truncate("hello", 5), "hello");
+        assert_eq!(truncate("hello world", 5), "hello...");
+    }
+
+    #[test]
+    fn test_truncate_multibyte() {
+        // Byte slicing at 3 would split the 'é'
+        assert_eq!(truncate("héllo", 2), "hé...");
+        assert_eq!(truncate("连接失败：超时", 4), "连接失败...");
+    }
+
+    #[test]
+    fn test_truncate_keeps_grapheme_clusters() {
+        let family = "👨‍👩‍👧";
+        let s = format!("{}{}ok", family, family);
+        assert_eq!(truncate(&s, 1), format!("{}...", family));
+        assert_eq!(truncate("e\u{301}x", 1), "e\u{301}...");
+    }
+
+    #[test]
+    fn test_truncate_zero() {
+        assert_eq!(truncate("abc", 0), "...");
+        assert_eq!(truncate("", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_middle() {
+        assert_eq!(truncate_middle("short", 8), "short");
+        assert_eq!(truncate_middle("abcdef0123456789", 8), "abcd...6789");
+        assert_eq!(truncate_middle("abcdef0123456789", 5), "abc...89");
+    }
+
+    #[test]
+    fn test_truncate_middle_multibyte() {
+        assert_eq!(truncate_middle("ääääbbbbcccc", 4), "ää...cc");
+    }
+
+    #[test]
+    fn test_sparkline() {
+        assert_eq!(sparkline([0.0, 7.0, 3.5]), "▁█▅");
+        assert_eq!(sparkline([2.0, 2.0]), "▁▁");
+        assert_eq!(sparkline(std::iter::empty()), "");
+        assert_eq!(sparkline([1.0, f64::NAN, 2.0]), "▁█");
+    }
+}
@@ -0,0 +1,369 @@
+//! Generic access to the `dora` CLI with argument whitelisting.
+//!
+//! The `dora_cli` tool and the console in the Diagnostics panel run any
+//! subcommand listed in [`SUBCOMMANDS`]. Flags are checked against that
+//! subcommand's whitelist and the number of positional arguments is bounded.
+//! Arguments go to the process directly, never through a shell. Long-running
+//! servers (`coordinator`, `daemon`, `runtime`), `up` and `destroy`, which
+//! start or tear down the whole runtime, and `self` are not listed.
+
+use super::{run_dora, Limits, ToolDefinition, ToolError};
+
+/// A flag accepted by a subcommand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Flag {
+    pub name: &'static str,
+    /// Whether the flag takes a value, as `--flag value` or `--flag=value`.
+    pub takes_value: bool,
+}
+
+/// A whitelisted dora subcommand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Subcommand {
+    pub name: &'static str,
+    /// Positional arguments by name; a trailing `?` marks an optional one.
+    pub positionals: &'static [&'static str],
+    pub flags: &'static [Flag],
+}
+
+const fn switch(name: &'static str) -> Flag {
+    Flag {
+        name,
+        takes_value: false,
+    }
+}
+
+const fn option(name: &'static str) -> Flag {
+    Flag {
+        name,
+        takes_value: true,
+    }
+}
+
+/// Subcommands the tool may run. Add new dora CLI features here instead of
+/// writing a dedicated tool for each.
+pub const SUBCOMMANDS: &[Subcommand] = &[
+    Subcommand {
+        name: "list",
+        positionals: &[],
+        flags: &[option("--format")],
+    },
+    Subcommand {
+        name: "check",
+        positionals: &[],
+        flags: &[option("--dataflow")],
+    },
+    Subcommand {
+        name: "graph",
+        positionals: &["dataflow"],
+        flags: &[switch("--mermaid")],
+    },
+    Subcommand {
+        name: "build",
+        positionals: &["dataflow"],
+        flags: &[switch("--uv")],
+    },
+    Subcommand {
+        name: "start",
+        positionals: &["dataflow"],
+        flags: &[
+            option("--name"),
+            switch("--detach"),
+            switch("--hot-reload"),
+            switch("--uv"),
+        ],
+    },
+    Subcommand {
+        name: "stop",
+        positionals: &["uuid?"],
+        flags: &[option("--name"), option("--grace-duration")],
+    },
+    Subcommand {
+        name: "logs",
+        positionals: &["dataflow", "node?"],
+        flags: &[],
+    },
+    Subcommand {
+        name: "new",
+        positionals: &["name"],
+        flags: &[option("--kind"), option("--lang")],
+    },
+];
+
+impl Subcommand {
+    /// e.g. `start <dataflow> [--name <value>] [--detach]`
+    pub fn usage(&self) -> String {
+        let mut parts = vec![self.name.to_string()];
+        for positional in self.positionals {
+            parts.push(match positional.strip_suffix('?') {
+                Some(name) => format!("[{}]", name),
+                None => format!("<{}>", positional),
+            });
+        }
+        for flag in self.flags {
+            parts.push(if flag.takes_value {
+                format!("[{} <value>]", flag.name)
+            } else {
+                format!("[{}]", flag.name)
+            });
+        }
+        parts.join(" ")
+    }
+
+    fn required_positionals(&self) -> usize {
+        self.positionals
+            .iter()
+            .filter(|p| !p.ends_with('?'))
+            .count()
+    }
+}
+
+/// Look up a whitelisted subcommand by name.
+pub fn find(name: &str) -> Option<&'static Subcommand> {
+    SUBCOMMANDS.iter().find(|s| s.name == name)
+}
+
+/// Check `args` against the whitelist of `subcommand`, returning the
+/// arguments to pass to `dora`, subcommand first.
+pub fn validate(subcommand: &str, args: &[String]) -> Result<Vec<String>, String> {
+    let spec = find(subcommand).ok_or_else(|| {
+        let names: Vec<&str> = SUBCOMMANDS.iter().map(|s| s.name).collect();
+        format!(
+            "Subcommand '{}' is not allowed (allowed: {})",
+            subcommand,
+            names.join(", ")
+        )
+    })?;
+
+    let mut argv = vec![spec.name.to_string()];
+    let mut positionals = 0;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        check_arg(arg)?;
+        if !arg.starts_with('-') {
+            positionals += 1;
+            if positionals > spec.positionals.len() {
+                return Err(format!(
+                    "Too many arguments for dora {} (usage: {})",
+                    spec.name,
+                    spec.usage()
+                ));
+            }
+            argv.push(arg.clone());
+            continue;
+        }
+
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (arg.as_str(), None),
+        };
+        let flag = spec.flags.iter().find(|f| f.name == name).ok_or_else(|| {
+            format!(
+                "Flag '{}' is not allowed for dora {} (usage: {})",
+                name,
+                spec.name,
+                spec.usage()
+            )
+        })?;
+        argv.push(name.to_string());
+        match (flag.takes_value, inline_value) {
+            (false, None) => {}
+            (false, Some(_)) => return Err(format!("Flag '{}' does not take a value", name)),
+            (true, value) => {
+                let value = value
+                    .map(str::to_string)
+                    .or_else(|| args.next().cloned())
+                    .filter(|v| !v.is_empty() && !v.starts_with('-'))
+                    .ok_or_else(|| format!("Flag '{}' needs a value", name))?;
+                check_arg(&value)?;
+                argv.push(value);
+            }
+        }
+    }
+
+    if positionals < spec.required_positionals() {
+        return Err(format!(
+            "Missing arguments for dora {} (usage: {})",
+            spec.name,
+            spec.usage()
+        ));
+    }
+    Ok(argv)
+}
+
+fn check_arg(arg: &str) -> Result<(), String> {
+    if arg.is_empty() || arg.chars().any(char::is_control) {
+        return Err(format!("Invalid argument {:?}", arg));
+    }
+    Ok(())
+}
+
+/// Validate and run a whitelisted subcommand within `limits`.
+pub fn run(subcommand: &str, args: &[String], limits: &Limits) -> Result<String, ToolError> {
+    let argv = validate(subcommand, args)?;
+    let argv: Vec<&str> = argv.iter().map(String::as_str).collect();
+    run_dora(&argv, limits)
+}
+
+/// Split a console line like `dora start "my flow.yml" --detach` into the
+/// subcommand and its arguments. Double quotes group words; a leading
+/// `dora` is optional.
+pub fn parse_command_line(line: &str) -> Result<(String, Vec<String>), String> {
+    let mut words = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => words.extend(current.take()),
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quoted {
+        return Err("Unterminated quote".to_string());
+    }
+    words.extend(current);
+
+    let mut words = words.into_iter().peekable();
+    words.next_if(|w| w == "dora");
+    let subcommand = words
+        .next()
+        .ok_or("Enter a dora subcommand, e.g. list --format json")?;
+    Ok((subcommand, words.collect()))
+}
+
+/// Definition of the `dora_cli` tool for the agent.
+pub fn tool_definition() -> ToolDefinition {
+    let usages: Vec<String> = SUBCOMMANDS.iter().map(Subcommand::usage).collect();
+    let names: Vec<&str> = SUBCOMMANDS.iter().map(|s| s.name).collect();
+    ToolDefinition {
+        name: "dora_cli".to_string(),
+        description: format!(
+            "Run a whitelisted dora CLI subcommand with validated arguments. Use this for dora features without a dedicated tool. Allowed: {}.",
+            usages.join("; ")
+        ),
+        input_schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "subcommand": {
+                    "type": "string",
+                    "enum": names,
+                    "description": "The dora subcommand to run"
+                },
+                "args": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional: arguments after the subcommand, e.g. [\"--format\", \"json\"]"
+                }
+            },
+            "required": ["subcommand"]
+        }),
+    }
+}
+
+pub(super) fn execute(args: &serde_json::Value, limits: &Limits) -> Result<String, ToolError> {
+    let subcommand = args
+        .get("subcommand")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing subcommand argument")?;
+
+    let cli_args = match args.get("args") {
+        None | Some(serde_json::Value::Null) => Vec::new(),
+        Some(serde_json::Value::Array(values)) => values
+            .iter()
+            .map(|v| v.as_str().map(str::to_string))
+            .collect::<Option<Vec<_>>>()
+            .ok_or("args must be an array of strings")?,
+        Some(_) => return Err("args must be an array of strings".into()),
+    };
+
+    run(subcommand, &cli_args, limits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_validate_accepts_whitelisted_arguments() {
+        let argv = validate("start", &strings(&["df.yml", "--name=demo", "--detach"])).unwrap();
+        assert_eq!(
+            argv,
+            strings(&["start", "df.yml", "--name", "demo", "--detach"])
+        );
+
+        let argv = validate("list", &strings(&["--format", "json"])).unwrap();
+        assert_eq!(argv, strings(&["list", "--format", "json"]));
+
+        assert_eq!(validate("stop", &[]).unwrap(), strings(&["stop"]));
+    }
+
+    #[test]
+    fn test_validate_rejects_arguments_outside_whitelist() {
+        let err = validate("daemon", &[]).unwrap_err();
+        assert!(err.contains("not allowed"));
+        assert!(err.contains("list"));
+        assert!(validate("destroy", &[]).is_err());
+        assert!(validate("up", &[]).is_err());
+
+        let err = validate("list", &strings(&["--coordinator-addr", "1.2.3.4"])).unwrap_err();
+        assert!(err.contains("Flag '--coordinator-addr' is not allowed"));
+
+        let err = validate("build", &strings(&["a.yml", "b.yml"])).unwrap_err();
+        assert!(err.contains("Too many arguments"));
+
+        let err = validate("build", &[]).unwrap_err();
+        assert!(err.contains("usage: build <dataflow> [--uv]"));
+
+        let err = validate("list", &strings(&["--format"])).unwrap_err();
+        assert!(err.contains("needs a value"));
+        assert!(validate("start", &strings(&["df.yml", "--detach=yes"])).is_err());
+        assert!(validate("logs", &strings(&["df\nrm"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_command_line() {
+        let (subcommand, args) = parse_command_line("dora start \"my flow.yml\" --detach").unwrap();
+        assert_eq!(subcommand, "start");
+        assert_eq!(args, strings(&["my flow.yml", "--detach"]));
+
+        let (subcommand, args) = parse_command_line("  list ").unwrap();
+        assert_eq!(subcommand, "list");
+        assert!(args.is_empty());
+
+        assert!(parse_command_line("dora").is_err());
+        assert!(parse_command_line("start \"df.yml").is_err());
+    }
+
+    #[test]
+    fn test_execute_validates_json_arguments() {
+        let execute = |args| execute(&args, &Limits::none()).unwrap_err().to_string();
+
+        let err = execute(serde_json::json!({}));
+        assert!(err.contains("Missing subcommand"));
+
+        let err = execute(serde_json::json!({ "subcommand": "list", "args": [1] }));
+        assert!(err.contains("array of strings"));
+
+        let err = execute(serde_json::json!({ "subcommand": "self", "args": ["update"] }));
+        assert!(err.contains("not allowed"));
+    }
+
+    #[test]
+    fn test_tool_definition_lists_subcommands() {
+        let tool = tool_definition();
+        assert_eq!(tool.name, "dora_cli");
+        assert!(tool.description.contains("logs <dataflow> [node]"));
+        assert_eq!(
+            tool.input_schema["properties"]["subcommand"]["enum"][0],
+            "list"
+        );
+    }
+}
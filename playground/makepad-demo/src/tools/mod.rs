@@ -0,0 +1,1009 @@
+pub mod dora_cli;
+pub mod payload;
+pub mod platform;
+pub mod process;
+pub mod ssh;
+pub mod version;
+
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::dataflow::undo::parse_started_uuid;
+use crate::logging;
+pub use payload::ToolPayload;
+pub use process::{Limits, ToolGroup};
+pub use ssh::SshTarget;
+
+/// Executable run for `dora` commands; empty for `dora` on the `PATH`.
+static DORA_PATH: Mutex<String> = Mutex::new(String::new());
+/// Host `dora` commands run on over SSH; this machine when unset.
+static SSH_TARGET: Mutex<Option<SshTarget>> = Mutex::new(None);
+/// Coordinator `dora` commands talk to; the CLI's default when unset.
+static COORDINATOR: Mutex<Option<(String, u16)>> = Mutex::new(None);
+
+/// Default port of the coordinator's control socket.
+pub const DEFAULT_CONTROL_PORT: u16 = 6012;
+/// Subcommands that talk to the coordinator and take `--coordinator-addr`.
+const COORDINATOR_COMMANDS: [&str; 7] =
+    ["list", "start", "stop", "destroy", "logs", "check", "node"];
+
+/// Tool definition for Claude API
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    pub input_schema: serde_json::Value,
+}
+
+/// Result of executing a tool
+#[derive(Debug, Clone)]
+pub struct ToolResult {
+    pub tool_use_id: String,
+    pub content: String,
+    pub is_error: bool,
+    /// Why the tool failed, when `is_error` is set
+    pub error: Option<ToolError>,
+    /// The result as data, for tools with structured output
+    pub payload: Option<ToolPayload>,
+}
+
+/// Why a tool call failed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolError {
+    Failed(String),
+    /// The tool's program could not be started, e.g. dora is not installed
+    Unavailable(String),
+    /// The tool ran past its timeout and its process was killed
+    TimedOut {
+        tool: String,
+        timeout: Duration,
+    },
+    /// The user cancelled the tool and its process was killed
+    Cancelled,
+}
+
+impl fmt::Display for ToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolError::Failed(message) | ToolError::Unavailable(message) => f.write_str(message),
+            ToolError::TimedOut { tool, timeout } => write!(
+                f,
+                "{} timed out after {}s; the process was stopped",
+                tool,
+                timeout.as_secs_f64()
+            ),
+            ToolError::Cancelled => f.write_str("Cancelled by the user"),
+        }
+    }
+}
+
+impl From<String> for ToolError {
+    fn from(message: String) -> Self {
+        ToolError::Failed(message)
+    }
+}
+
+impl From<&str> for ToolError {
+    fn from(message: &str) -> Self {
+        ToolError::Failed(message.to_string())
+    }
+}
+
+impl From<ToolError> for String {
+    fn from(error: ToolError) -> Self {
+        error.to_string()
+    }
+}
+
+/// How long a tool may run before its process is killed; `None` for tools
+/// that never start a process.
+pub fn tool_timeout(name: &str) -> Option<Duration> {
+    let secs = match name {
+        "dora_list" | "dora_check" | "dora_stop" | "dora_destroy" | "dora_logs" => 30,
+        "dora_up" | "dora_start" | "dora_new" => 60,
+        "shell_command" => 300,
+        "dora_build" | "dora_cli" | "start_dataflow" => 600,
+        _ => return None,
+    };
+    Some(Duration::from_secs(secs))
+}
+
+/// Get all available dora tools for Claude
+pub fn get_dora_tools() -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition {
+            name: "dora_list".to_string(),
+            description: "List all running dataflows. Returns information about active dora dataflows including their IDs and status.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {},
+                "required": []
+            }),
+        },
+        ToolDefinition {
+            name: "dora_up".to_string(),
+            description: "Start the dora runtime (coordinator and daemon) on this machine. Use this when other dora commands fail because no coordinator is reachable.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {},
+                "required": []
+            }),
+        },
+        ToolDefinition {
+            name: "dora_start".to_string(),
+            description: "Start a new dataflow from a YAML file. The dataflow_path should be the path to a valid dora dataflow YAML configuration file.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dataflow_path": {
+                        "type": "string",
+                        "description": "Path to the dataflow YAML file to start"
+                    }
+                },
+                "required": ["dataflow_path"]
+            }),
+        },
+        ToolDefinition {
+            name: "start_dataflow".to_string(),
+            description: "Build and start a dataflow, from a YAML file path or from inline YAML, and return its UUID. Inline YAML is written to a new file first; relative node paths resolve from its directory.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dataflow_path": {
+                        "type": "string",
+                        "description": "Path to the dataflow YAML file to start"
+                    },
+                    "yaml": {
+                        "type": "string",
+                        "description": "Dataflow YAML to start, instead of dataflow_path"
+                    },
+                    "directory": {
+                        "type": "string",
+                        "description": "Optional: directory to write inline YAML to; the app's dataflows directory by default"
+                    },
+                    "build": {
+                        "type": "boolean",
+                        "description": "Optional: run `dora build` first (default true)"
+                    }
+                },
+                "required": []
+            }),
+        },
+        ToolDefinition {
+            name: "dora_build".to_string(),
+            description: "Build the nodes of a dataflow (runs their build commands) from a YAML file. Run this after editing node sources or the YAML before restarting.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dataflow_path": {
+                        "type": "string",
+                        "description": "Path to the dataflow YAML file to build"
+                    }
+                },
+                "required": ["dataflow_path"]
+            }),
+        },
+        ToolDefinition {
+            name: "dora_check".to_string(),
+            description: "Validate a dataflow YAML file with `dora check` before starting it. Returns the problems found (node, field, message), or that the check passed.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dataflow_path": {
+                        "type": "string",
+                        "description": "Path to the dataflow YAML file to check"
+                    }
+                },
+                "required": ["dataflow_path"]
+            }),
+        },
+        ToolDefinition {
+            name: "dora_new".to_string(),
+            description: "Scaffold a new dora node or dataflow project with `dora new`, in a directory on this machine.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Name of the node or project; a directory of that name is created"
+                    },
+                    "kind": {
+                        "type": "string",
+                        "enum": ["node", "dataflow"],
+                        "description": "Optional: what to create (default node)"
+                    },
+                    "lang": {
+                        "type": "string",
+                        "enum": ["rust", "python", "c", "cxx"],
+                        "description": "Optional: language of the node (default rust)"
+                    },
+                    "directory": {
+                        "type": "string",
+                        "description": "Optional: directory to create it in (default the current directory)"
+                    }
+                },
+                "required": ["name"]
+            }),
+        },
+        ToolDefinition {
+            name: "dora_stop".to_string(),
+            description: "Stop a running dataflow by its UUID or name.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dataflow_id": {
+                        "type": "string",
+                        "description": "UUID or name of the dataflow to stop"
+                    }
+                },
+                "required": ["dataflow_id"]
+            }),
+        },
+        ToolDefinition {
+            name: "dora_destroy".to_string(),
+            description: "Destroy (forcefully stop) a dataflow and clean up all resources.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dataflow_id": {
+                        "type": "string",
+                        "description": "UUID or name of the dataflow to destroy"
+                    }
+                },
+                "required": ["dataflow_id"]
+            }),
+        },
+        ToolDefinition {
+            name: "dora_logs".to_string(),
+            description: "Get logs from a running dataflow. Optionally filter by node name.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "dataflow_id": {
+                        "type": "string",
+                        "description": "UUID or name of the dataflow"
+                    },
+                    "node": {
+                        "type": "string",
+                        "description": "Optional: filter logs by node name"
+                    }
+                },
+                "required": ["dataflow_id"]
+            }),
+        },
+        ToolDefinition {
+            name: "shell_command".to_string(),
+            description: format!("Execute a shell command with {}. Use this for general system commands, file operations, or when dora-specific commands are not sufficient. Be careful with this tool.", platform::shell_name()),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "command": {
+                        "type": "string",
+                        "description": "The shell command to execute"
+                    },
+                    "working_dir": {
+                        "type": "string",
+                        "description": "Optional: working directory for the command"
+                    }
+                },
+                "required": ["command"]
+            }),
+        },
+        ToolDefinition {
+            name: "read_file".to_string(),
+            description: "Read the contents of a file. Useful for inspecting dataflow YAML files or checking configurations.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path to the file to read"
+                    }
+                },
+                "required": ["path"]
+            }),
+        },
+        ToolDefinition {
+            name: "write_file".to_string(),
+            description: "Write content to a file. Useful for creating or modifying dataflow YAML files.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Path to the file to write"
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "Content to write to the file"
+                    }
+                },
+                "required": ["path", "content"]
+            }),
+        },
+        ToolDefinition {
+            name: "list_directory".to_string(),
+            description: "List files and directories in a given path.".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Directory path to list"
+                    }
+                },
+                "required": ["path"]
+            }),
+        },
+        dora_cli::tool_definition(),
+    ]
+}
+
+/// Execute a tool by name with given arguments
+///
+/// Processes started by the tool are killed after [`tool_timeout`] or when
+/// cancelled with [`process::cancel_all`].
+pub fn execute_tool(name: &str, tool_use_id: &str, args: &serde_json::Value) -> ToolResult {
+    run_tool(
+        Limits::register(name, tool_timeout(name)),
+        name,
+        tool_use_id,
+        args,
+    )
+}
+
+/// [`execute_tool`], also cancelled along with the rest of `group`.
+pub fn execute_tool_in(
+    group: &ToolGroup,
+    name: &str,
+    tool_use_id: &str,
+    args: &serde_json::Value,
+) -> ToolResult {
+    run_tool(
+        group.register(name, tool_timeout(name)),
+        name,
+        tool_use_id,
+        args,
+    )
+}
+
+fn run_tool(limits: Limits, name: &str, tool_use_id: &str, args: &serde_json::Value) -> ToolResult {
+    let result = match name {
+        "dora_list" => execute_dora_list(&limits),
+        "dora_up" => execute_dora_up(&limits),
+        "dora_start" => execute_dora_start(args, &limits),
+        "start_dataflow" => execute_start_dataflow(args, &limits),
+        "dora_build" => execute_dora_build(args, &limits),
+        "dora_check" => execute_dora_check(args, &limits),
+        "dora_new" => execute_dora_new(args, &limits),
+        "dora_stop" => execute_dora_stop(args, &limits),
+        "dora_destroy" => execute_dora_destroy(args, &limits),
+        "dora_logs" => execute_dora_logs(args, &limits),
+        "dora_cli" => dora_cli::execute(args, &limits),
+        "shell_command" => execute_shell_command(args, &limits),
+        "read_file" => execute_read_file(args).map_err(ToolError::from),
+        "write_file" => execute_write_file(args).map_err(ToolError::from),
+        "list_directory" => execute_list_directory(args).map_err(ToolError::from),
+        _ => Err(format!("Unknown tool: {}", name).into()),
+    };
+
+    let payload = ToolPayload::parse(name, args, &result);
+    match result {
+        Ok(content) => ToolResult {
+            tool_use_id: tool_use_id.to_string(),
+            content,
+            is_error: false,
+            error: None,
+            payload,
+        },
+        Err(error) => ToolResult {
+            tool_use_id: tool_use_id.to_string(),
+            content: error.to_string(),
+            is_error: true,
+            error: Some(error),
+            payload,
+        },
+    }
+}
+
+fn execute_dora_list(limits: &Limits) -> Result<String, ToolError> {
+    run_dora(&["list", "--format", "json"], limits)
+}
+
+fn execute_dora_up(limits: &Limits) -> Result<String, ToolError> {
+    run_dora(&["up"], limits)
+}
+
+fn execute_dora_start(args: &serde_json::Value, limits: &Limits) -> Result<String, ToolError> {
+    let path = args
+        .get("dataflow_path")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing dataflow_path argument")?;
+
+    run_dora(&["start", "--detach", path], limits)
+}
+
+fn execute_start_dataflow(args: &serde_json::Value, limits: &Limits) -> Result<String, ToolError> {
+    if args.get("dataflow_path").is_none() && ssh_target().is_some() {
+        return Err(
+            "Inline YAML cannot be started on a remote host; write it there and pass dataflow_path"
+                .into(),
+        );
+    }
+    let file = dataflow_file(args, logging::now_ms())?;
+    let path = file.display().to_string();
+    let result = build_and_start(args, &path, limits);
+    // The coordinator holds the descriptor once started, so the file written
+    // from inline YAML is no longer needed either way
+    if args.get("dataflow_path").is_none() {
+        let _ = std::fs::remove_file(&file);
+    }
+    let output = result?;
+    Ok(match parse_started_uuid(&output) {
+        Some(uuid) => format!("Started dataflow {} from {}", uuid, path),
+        None => output,
+    })
+}
+
+fn build_and_start(
+    args: &serde_json::Value,
+    path: &str,
+    limits: &Limits,
+) -> Result<String, ToolError> {
+    if args.get("build").and_then(|v| v.as_bool()).unwrap_or(true) {
+        run_dora(&["build", path], limits).map_err(|e| match e {
+            ToolError::Failed(output) => {
+                ToolError::Failed(format!("Build failed for {}:\n{}", path, output))
+            }
+            e => e,
+        })?;
+    }
+    run_dora(&["start", "--detach", path], limits)
+}
+
+/// The dataflow file to start: `dataflow_path`, or inline `yaml` written to
+/// a new file in `directory` (the app's `dataflows` directory by default,
+/// where the editor also saves agent dataflows).
+fn dataflow_file(args: &serde_json::Value, now_ms: u64) -> Result<PathBuf, String> {
+    if let Some(path) = args.get("dataflow_path").and_then(|v| v.as_str()) {
+        return Ok(PathBuf::from(path));
+    }
+    let yaml = args
+        .get("yaml")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing dataflow_path or yaml argument")?;
+    if !yaml.lines().any(|line| line.starts_with("nodes:")) {
+        return Err("The YAML has no top-level nodes list".to_string());
+    }
+
+    let dir = args
+        .get("directory")
+        .and_then(|v| v.as_str())
+        .map(PathBuf::from)
+        .or_else(|| crate::settings::data_dir().map(|d| d.join("dataflows")))
+        .ok_or("No directory for the dataflow YAML; pass directory")?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("agent-dataflow-{}.yml", now_ms));
+    std::fs::write(&path, yaml).map_err(|e| format!("Failed to write dataflow YAML: {}", e))?;
+    Ok(path)
+}
+
+fn execute_dora_build(args: &serde_json::Value, limits: &Limits) -> Result<String, ToolError> {
+    let path = args
+        .get("dataflow_path")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing dataflow_path argument")?;
+
+    run_dora(&["build", path], limits)
+}
+
+fn execute_dora_check(args: &serde_json::Value, limits: &Limits) -> Result<String, ToolError> {
+    let path = args
+        .get("dataflow_path")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing dataflow_path argument")?;
+
+    run_dora(&["check", "--dataflow", path], limits)
+}
+
+fn execute_dora_new(args: &serde_json::Value, limits: &Limits) -> Result<String, ToolError> {
+    let name = args
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing name argument")?;
+    let kind = args.get("kind").and_then(|v| v.as_str()).unwrap_or("node");
+    let lang = args.get("lang").and_then(|v| v.as_str()).unwrap_or("rust");
+    let valid_name = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid_name {
+        return Err(format!("Invalid name \"{}\"", name).into());
+    }
+    if !["node", "dataflow"].contains(&kind) {
+        return Err(format!("Invalid kind \"{}\"", kind).into());
+    }
+    if !["rust", "python", "c", "cxx"].contains(&lang) {
+        return Err(format!("Invalid lang \"{}\"", lang).into());
+    }
+    if ssh_target().is_some() {
+        return Err("dora new runs on this machine only; clear the SSH host to use it".into());
+    }
+
+    let dir = args.get("directory").and_then(|v| v.as_str());
+    let program = dora_program();
+    let args = ["new", name, "--kind", kind, "--lang", lang];
+    run_command_in(&program, &args, dir.map(Path::new), limits)?;
+    let created = PathBuf::from(dir.unwrap_or(".")).join(name);
+    Ok(format!(
+        "Created {} {} in {}",
+        lang,
+        kind,
+        created.display()
+    ))
+}
+
+fn execute_dora_stop(args: &serde_json::Value, limits: &Limits) -> Result<String, ToolError> {
+    let id = args
+        .get("dataflow_id")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing dataflow_id argument")?;
+
+    run_dora(&["stop", id], limits)
+}
+
+fn execute_dora_destroy(args: &serde_json::Value, limits: &Limits) -> Result<String, ToolError> {
+    let id = args
+        .get("dataflow_id")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing dataflow_id argument")?;
+
+    run_dora(&["destroy", id], limits)
+}
+
+fn execute_dora_logs(args: &serde_json::Value, limits: &Limits) -> Result<String, ToolError> {
+    let id = args
+        .get("dataflow_id")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing dataflow_id argument")?;
+
+    let mut cmd_args = vec!["logs", id];
+
+    if let Some(node) = args.get("node").and_then(|v| v.as_str()) {
+        cmd_args.push("--node");
+        cmd_args.push(node);
+    }
+
+    run_dora(&cmd_args, limits)
+}
+
+fn execute_shell_command(args: &serde_json::Value, limits: &Limits) -> Result<String, ToolError> {
+    let command = args
+        .get("command")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing command argument")?;
+
+    let working_dir = args.get("working_dir").and_then(|v| v.as_str());
+
+    let mut cmd = platform::shell(command);
+
+    if let Some(dir) = working_dir {
+        cmd.current_dir(dir);
+    }
+
+    let output = process::output(cmd, "command", limits)?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    if output.status.success() {
+        Ok(format!("{}{}", stdout, stderr))
+    } else {
+        Err(format!(
+            "Command failed with exit code {:?}\nstdout: {}\nstderr: {}",
+            output.status.code(),
+            stdout,
+            stderr
+        )
+        .into())
+    }
+}
+
+fn execute_read_file(args: &serde_json::Value) -> Result<String, String> {
+    let path = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing path argument")?;
+
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))
+}
+
+fn execute_write_file(args: &serde_json::Value) -> Result<String, String> {
+    let path = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing path argument")?;
+
+    let content = args
+        .get("content")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing content argument")?;
+
+    std::fs::write(path, content).map_err(|e| format!("Failed to write file: {}", e))?;
+
+    Ok(format!(
+        "Successfully wrote {} bytes to {}",
+        content.len(),
+        path
+    ))
+}
+
+fn execute_list_directory(args: &serde_json::Value) -> Result<String, String> {
+    let path = args
+        .get("path")
+        .and_then(|v| v.as_str())
+        .ok_or("Missing path argument")?;
+
+    let entries =
+        std::fs::read_dir(path).map_err(|e| format!("Failed to read directory: {}", e))?;
+
+    let mut result = Vec::new();
+    for entry in entries.flatten() {
+        let file_type = entry.file_type().ok();
+        let type_str = match file_type {
+            Some(ft) if ft.is_dir() => "[DIR]",
+            Some(ft) if ft.is_file() => "[FILE]",
+            Some(ft) if ft.is_symlink() => "[LINK]",
+            _ => "[?]",
+        };
+        result.push(format!(
+            "{} {}",
+            type_str,
+            entry.file_name().to_string_lossy()
+        ));
+    }
+
+    Ok(result.join("\n"))
+}
+
+/// Use `path` as the dora executable, or `dora` on the `PATH` when unset.
+pub fn set_dora_path(path: Option<&str>) {
+    *DORA_PATH.lock().unwrap() = path.map(str::trim).unwrap_or_default().to_string();
+}
+
+/// Run `dora` commands on `host` (`[user@]host[:port]`) over SSH, or on
+/// this machine when unset. An invalid host leaves the current one in place.
+pub fn set_ssh_host(host: Option<&str>) -> Result<(), String> {
+    let target = host
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .map(SshTarget::parse)
+        .transpose()?;
+    *SSH_TARGET.lock().unwrap() = target;
+    Ok(())
+}
+
+/// Point `dora` commands at the coordinator at `addr` (`ip[:port]`), or at
+/// the CLI's default one when unset. dora takes only IP addresses here. An
+/// invalid address leaves the current one in place.
+pub fn set_coordinator_addr(addr: Option<&str>) -> Result<(), String> {
+    let coordinator = addr
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .map(parse_coordinator_addr)
+        .transpose()?;
+    *COORDINATOR.lock().unwrap() = coordinator;
+    Ok(())
+}
+
+/// `(ip, port)` of `ip[:port]`, e.g. `10.0.0.2:6012` or `[::1]`.
+pub fn parse_coordinator_addr(addr: &str) -> Result<(String, u16), String> {
+    let invalid = || {
+        format!(
+            "Invalid coordinator address '{}': expected an IP address and optional port, e.g. 10.0.0.2:6012",
+            addr
+        )
+    };
+    if let Ok(socket) = addr.parse::<std::net::SocketAddr>() {
+        return Ok((socket.ip().to_string(), socket.port()));
+    }
+    let ip = addr.trim_start_matches('[').trim_end_matches(']');
+    ip.parse::<std::net::IpAddr>()
+        .map(|ip| (ip.to_string(), DEFAULT_CONTROL_PORT))
+        .map_err(|_| invalid())
+}
+
+/// `args` with the coordinator options added when `args` is a command that
+/// talks to the coordinator.
+fn with_coordinator<'a>(
+    args: &[&'a str],
+    coordinator: Option<&'a (String, u16)>,
+) -> Vec<std::borrow::Cow<'a, str>> {
+    let mut argv: Vec<std::borrow::Cow<str>> = args.iter().map(|a| (*a).into()).collect();
+    let Some((ip, port)) = coordinator else {
+        return argv;
+    };
+    if args
+        .first()
+        .is_some_and(|cmd| COORDINATOR_COMMANDS.contains(cmd))
+    {
+        argv.extend([
+            "--coordinator-addr".into(),
+            ip.as_str().into(),
+            "--coordinator-port".into(),
+            port.to_string().into(),
+        ]);
+    }
+    argv
+}
+
+/// The remote host `dora` commands run on, if any.
+pub fn ssh_target() -> Option<SshTarget> {
+    SSH_TARGET.lock().unwrap().clone()
+}
+
+/// The dora executable commands are run with, on the SSH host when one is
+/// set.
+pub fn dora_program() -> String {
+    let path = DORA_PATH.lock().unwrap();
+    if path.is_empty() {
+        "dora".to_string()
+    } else {
+        path.clone()
+    }
+}
+
+/// Run the configured dora executable with `args` within `limits`, over
+/// SSH when a remote host is set, against the configured coordinator.
+pub(crate) fn run_dora(args: &[&str], limits: &Limits) -> Result<String, ToolError> {
+    let program = dora_program();
+    let coordinator = COORDINATOR.lock().unwrap().clone();
+    let args = with_coordinator(args, coordinator.as_ref());
+    let args: Vec<&str> = args.iter().map(|a| a.as_ref()).collect();
+    let args = args.as_slice();
+    match ssh_target() {
+        Some(target) => {
+            let argv = target.command_args(&program, args);
+            let argv: Vec<&str> = argv.iter().map(String::as_str).collect();
+            run_command("ssh", &argv, limits)
+        }
+        None => run_command(&program, args, limits),
+    }
+}
+
+pub(crate) fn run_command(
+    program: &str,
+    args: &[&str],
+    limits: &Limits,
+) -> Result<String, ToolError> {
+    run_command_in(program, args, None, limits)
+}
+
+/// [`run_command`] in `dir` instead of the current directory.
+pub(crate) fn run_command_in(
+    program: &str,
+    args: &[&str],
+    dir: Option<&Path>,
+    limits: &Limits,
+) -> Result<String, ToolError> {
+    let mut cmd = platform::command(program);
+    cmd.args(args);
+    if let Some(dir) = dir {
+        cmd.current_dir(dir);
+    }
+    let output = process::output(cmd, program, limits)?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    if output.status.success() {
+        Ok(format!("{}{}", stdout, stderr))
+    } else {
+        Err(format!(
+            "{} failed with exit code {:?}\nstdout: {}\nstderr: {}",
+            program,
+            output.status.code(),
+            stdout,
+            stderr
+        )
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_dora_tools() {
+        let tools = get_dora_tools();
+        assert!(!tools.is_empty());
+
+        // Check that essential tools are present
+        let tool_names: Vec<&str> = tools.iter().map(|t| t.name.as_str()).collect();
+        assert!(tool_names.contains(&"dora_list"));
+        assert!(tool_names.contains(&"dora_up"));
+        assert!(tool_names.contains(&"dora_start"));
+        assert!(tool_names.contains(&"start_dataflow"));
+        assert!(tool_names.contains(&"dora_build"));
+        assert!(tool_names.contains(&"dora_check"));
+        assert!(tool_names.contains(&"dora_new"));
+        assert!(tool_names.contains(&"dora_stop"));
+        assert!(tool_names.contains(&"shell_command"));
+        assert!(tool_names.contains(&"read_file"));
+        assert!(tool_names.contains(&"dora_cli"));
+    }
+
+    #[test]
+    fn test_tool_definition_serialization() {
+        let tool = ToolDefinition {
+            name: "test_tool".to_string(),
+            description: "A test tool".to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {}
+            }),
+        };
+
+        let json = serde_json::to_string(&tool).unwrap();
+        assert!(json.contains("test_tool"));
+        assert!(json.contains("A test tool"));
+    }
+
+    #[test]
+    fn test_execute_unknown_tool() {
+        let result = execute_tool("unknown_tool", "test-id", &serde_json::json!({}));
+        assert!(result.is_error);
+        assert!(result.content.contains("Unknown tool"));
+    }
+
+    #[test]
+    fn test_execute_list_directory() {
+        let args = serde_json::json!({ "path": "." });
+        let result = execute_tool("list_directory", "test-id", &args);
+        // Should succeed for current directory
+        assert!(!result.is_error);
+    }
+
+    #[test]
+    fn test_execute_read_file_missing_arg() {
+        let result = execute_tool("read_file", "test-id", &serde_json::json!({}));
+        assert!(result.is_error);
+        assert!(result.content.contains("Missing path"));
+    }
+
+    #[test]
+    fn test_execute_dora_new_rejects_bad_args() {
+        let result = execute_tool(
+            "dora_new",
+            "test-id",
+            &serde_json::json!({ "name": "--help" }),
+        );
+        assert!(result.is_error);
+        assert!(result.content.contains("Invalid name"));
+        let args = serde_json::json!({ "name": "camera", "lang": "go" });
+        let result = execute_tool("dora_new", "test-id", &args);
+        assert!(result.content.contains("Invalid lang"));
+    }
+
+    #[test]
+    fn test_execute_dora_build_missing_arg() {
+        let result = execute_tool("dora_build", "test-id", &serde_json::json!({}));
+        assert!(result.is_error);
+        assert!(result.content.contains("Missing dataflow_path"));
+    }
+
+    #[test]
+    fn test_start_dataflow_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let args = serde_json::json!({
+            "yaml": "nodes:\n  - id: camera\n",
+            "directory": dir.path(),
+        });
+        let path = dataflow_file(&args, 42).unwrap();
+        assert_eq!(path, dir.path().join("agent-dataflow-42.yml"));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "nodes:\n  - id: camera\n"
+        );
+
+        let args = serde_json::json!({ "dataflow_path": "df.yml", "yaml": "ignored" });
+        assert_eq!(dataflow_file(&args, 1).unwrap(), PathBuf::from("df.yml"));
+        assert!(dataflow_file(&serde_json::json!({ "yaml": "key: value" }), 1).is_err());
+        assert!(dataflow_file(&serde_json::json!({}), 1).is_err());
+    }
+
+    #[test]
+    fn test_dora_path_setting() {
+        set_dora_path(Some(" /opt/dora/bin/dora "));
+        assert_eq!(dora_program(), "/opt/dora/bin/dora");
+        set_dora_path(None);
+        assert_eq!(dora_program(), "dora");
+    }
+
+    #[test]
+    fn test_ssh_host_setting() {
+        set_ssh_host(Some("robot@jetson")).unwrap();
+        assert_eq!(ssh_target().unwrap().to_string(), "robot@jetson");
+        assert!(set_ssh_host(Some("-oProxyCommand=sh")).is_err());
+        assert_eq!(ssh_target().unwrap().host, "jetson");
+        set_ssh_host(Some("  ")).unwrap();
+        assert_eq!(ssh_target(), None);
+    }
+
+    #[test]
+    fn test_coordinator_args() {
+        assert_eq!(
+            parse_coordinator_addr("10.0.0.2").unwrap(),
+            ("10.0.0.2".to_string(), DEFAULT_CONTROL_PORT)
+        );
+        assert_eq!(
+            parse_coordinator_addr("10.0.0.2:7000").unwrap(),
+            ("10.0.0.2".to_string(), 7000)
+        );
+        assert_eq!(parse_coordinator_addr("[::1]:7000").unwrap().0, "::1");
+        assert!(parse_coordinator_addr("robot.local:6012").is_err());
+
+        let coordinator = ("10.0.0.2".to_string(), 7000);
+        assert_eq!(
+            with_coordinator(&["stop", "u1"], Some(&coordinator)),
+            [
+                "stop",
+                "u1",
+                "--coordinator-addr",
+                "10.0.0.2",
+                "--coordinator-port",
+                "7000"
+            ]
+        );
+        assert_eq!(
+            with_coordinator(&["build", "df.yml"], Some(&coordinator)),
+            ["build", "df.yml"]
+        );
+        assert_eq!(with_coordinator(&["list"], None), ["list"]);
+    }
+
+    #[test]
+    fn test_tool_result_structure() {
+        let result = ToolResult {
+            tool_use_id: "123".to_string(),
+            content: "test content".to_string(),
+            is_error: false,
+            error: None,
+            payload: None,
+        };
+        assert_eq!(result.tool_use_id, "123");
+        assert_eq!(result.content, "test content");
+        assert!(!result.is_error);
+    }
+
+    #[test]
+    fn test_tool_timeouts() {
+        assert_eq!(tool_timeout("dora_logs"), Some(Duration::from_secs(30)));
+        assert_eq!(tool_timeout("dora_build"), Some(Duration::from_secs(600)));
+        assert_eq!(tool_timeout("read_file"), None);
+
+        let error = ToolError::TimedOut {
+            tool: "dora_logs".to_string(),
+            timeout: Duration::from_secs(30),
+        };
+        assert_eq!(
+            error.to_string(),
+            "dora_logs timed out after 30s; the process was stopped"
+        );
+        assert_eq!(String::from(ToolError::from("boom")), "boom");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shell_command_reports_timeout() {
+        let limits = Limits::register("shell_command", Some(Duration::from_millis(100)));
+        let args = serde_json::json!({ "command": "sleep 10" });
+        let error = execute_shell_command(&args, &limits).unwrap_err();
+        assert!(matches!(error, ToolError::TimedOut { .. }));
+    }
+}
@@ -0,0 +1,419 @@
+//! Typed data parsed from tool output.
+//!
+//! [`ToolResult::content`](super::ToolResult) stays the text the agent reads;
+//! the payload carries the same result as data so callers in the UI do not
+//! parse stdout themselves.
+
+use crate::dataflow::undo::parse_started_uuid;
+use crate::dataflow::DataflowInfo;
+use crate::logging::Level;
+
+use super::ToolError;
+
+/// Typed result of a tool call.
+#[derive(Debug, Clone)]
+pub enum ToolPayload {
+    /// `dora_list`
+    Dataflows(Vec<DataflowInfo>),
+    /// `dora_start`: the UUID of the started dataflow, when printed
+    Started(Option<String>),
+    /// `dora_logs`
+    Logs(Vec<LogLine>),
+    /// `dora_build`, also when the build fails
+    Build(Vec<BuildDiagnostic>),
+    /// `dora_check`: problems found in the dataflow, empty when it passed
+    Check(Vec<CheckDiagnostic>),
+}
+
+/// A problem `dora check` found in a dataflow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckDiagnostic {
+    /// Node the problem is in, when named
+    pub node: Option<String>,
+    /// Input, output or YAML field the problem is in, when named
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// One line of `dora logs` output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    /// Level named near the start of the line, if any
+    pub level: Option<Level>,
+    pub text: String,
+}
+
+/// An error or warning reported by a build command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildDiagnostic {
+    /// [`Level::Error`] or [`Level::Warn`]
+    pub level: Level,
+    pub message: String,
+    /// `file:line:col` from a following `-->` line
+    pub location: Option<String>,
+}
+
+impl ToolPayload {
+    /// Parse the output of tool `name`; `None` for tools without typed
+    /// output and for failures other than a failed build.
+    pub fn parse(
+        name: &str,
+        args: &serde_json::Value,
+        result: &Result<String, ToolError>,
+    ) -> Option<Self> {
+        let output = match (name, result) {
+            (_, Ok(output)) => output,
+            ("dora_build" | "dora_check", Err(ToolError::Failed(output))) => output,
+            _ => return None,
+        };
+        match name {
+            "dora_list" => Some(Self::Dataflows(parse_dataflows(output))),
+            "dora_start" | "start_dataflow" => Some(Self::Started(parse_started_uuid(output))),
+            "dora_logs" => {
+                let node = args.get("node").and_then(|v| v.as_str());
+                Some(Self::Logs(parse_log_lines(output, node)))
+            }
+            "dora_build" => Some(Self::Build(parse_build_diagnostics(output))),
+            "dora_check" => {
+                let mut diagnostics = parse_check_diagnostics(output);
+                // A failed check always reports at least one problem
+                if diagnostics.is_empty() && result.is_err() {
+                    diagnostics.push(CheckDiagnostic {
+                        node: None,
+                        field: None,
+                        message: output.trim().to_string(),
+                    });
+                }
+                Some(Self::Check(diagnostics))
+            }
+            _ => None,
+        }
+    }
+
+    /// One-line description for tool call previews, e.g. `Build: 2 errors, 1 warning`.
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Dataflows(dataflows) if dataflows.is_empty() => "No dataflows".to_string(),
+            Self::Dataflows(dataflows) => {
+                let names: Vec<String> = dataflows
+                    .iter()
+                    .map(|df| {
+                        let name = if df.name.is_empty() {
+                            df.uuid_short()
+                        } else {
+                            df.name.clone()
+                        };
+                        format!("{} ({})", name, df.status)
+                    })
+                    .collect();
+                format!(
+                    "{}: {}",
+                    count(dataflows.len(), "dataflow"),
+                    names.join(", ")
+                )
+            }
+            Self::Started(Some(uuid)) => format!("Started dataflow {}", uuid),
+            Self::Started(None) => "Started dataflow".to_string(),
+            Self::Logs(lines) => {
+                let errors = lines
+                    .iter()
+                    .filter(|l| l.level == Some(Level::Error))
+                    .count();
+                format!(
+                    "{}, {}",
+                    count(lines.len(), "log line"),
+                    count(errors, "error")
+                )
+            }
+            Self::Build(diagnostics) => {
+                let errors = diagnostics
+                    .iter()
+                    .filter(|d| d.level == Level::Error)
+                    .count();
+                format!(
+                    "Build: {}, {}",
+                    count(errors, "error"),
+                    count(diagnostics.len() - errors, "warning")
+                )
+            }
+            Self::Check(diagnostics) if diagnostics.is_empty() => "Check passed".to_string(),
+            Self::Check(diagnostics) => format!("Check: {}", count(diagnostics.len(), "problem")),
+        }
+    }
+}
+
+impl BuildDiagnostic {
+    /// e.g. `error: cannot find value `x` (src/main.rs:3:5)`
+    pub fn describe(&self) -> String {
+        let level = match self.level {
+            Level::Error => "error",
+            _ => "warning",
+        };
+        match &self.location {
+            Some(location) => format!("{}: {} ({})", level, self.message, location),
+            None => format!("{}: {}", level, self.message),
+        }
+    }
+}
+
+impl CheckDiagnostic {
+    /// e.g. `camera.tick: input is not mapped to any output`
+    pub fn describe(&self) -> String {
+        let location = [self.node.as_deref(), self.field.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(".");
+        if location.is_empty() {
+            self.message.clone()
+        } else {
+            format!("{}: {}", location, self.message)
+        }
+    }
+}
+
+fn count(n: usize, noun: &str) -> String {
+    format!("{} {}{}", n, noun, if n == 1 { "" } else { "s" })
+}
+
+/// `dora list --format json` prints an array or NDJSON depending on version.
+pub fn parse_dataflows(output: &str) -> Vec<DataflowInfo> {
+    if output.trim().starts_with('[') {
+        DataflowInfo::parse_json_array(output)
+    } else {
+        DataflowInfo::parse_ndjson(output)
+    }
+}
+
+/// Split log output into lines, prefixed with `[node]` when filtered by node.
+pub fn parse_log_lines(output: &str, node: Option<&str>) -> Vec<LogLine> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| LogLine {
+            level: line_level(line),
+            text: match node {
+                Some(node) => format!("[{}] {}", node, line),
+                None => line.to_string(),
+            },
+        })
+        .collect()
+}
+
+/// Level named in the first few words, as in `2024-05-01T10:00:00Z ERROR msg`
+/// or `[WARN] msg`.
+fn line_level(line: &str) -> Option<Level> {
+    line.split(|c: char| c.is_whitespace() || matches!(c, '[' | ']' | ':'))
+        .filter(|word| !word.is_empty())
+        .take(4)
+        .find_map(|word| {
+            let word = word.to_ascii_uppercase();
+            if word == "WARNING" {
+                return Some(Level::Warn);
+            }
+            Level::ALL.into_iter().find(|l| l.as_str() == word)
+        })
+}
+
+/// Errors and warnings in cargo/rustc style build output: `error: ...`,
+/// `error[E0425]: ...`, `warning: ...`, with an optional `--> file` line.
+pub fn parse_build_diagnostics(output: &str) -> Vec<BuildDiagnostic> {
+    let mut diagnostics: Vec<BuildDiagnostic> = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(location) = line.strip_prefix("--> ") {
+            if let Some(last) = diagnostics.last_mut().filter(|d| d.location.is_none()) {
+                last.location = Some(location.trim().to_string());
+            }
+            continue;
+        }
+        let lower = line.to_ascii_lowercase();
+        let (level, rest) = if lower.starts_with("error") {
+            (Level::Error, &line["error".len()..])
+        } else if lower.starts_with("warning") {
+            (Level::Warn, &line["warning".len()..])
+        } else {
+            continue;
+        };
+        // Skip an error code such as `[E0425]`
+        let rest = match rest.strip_prefix('[') {
+            Some(code) => code.split_once(']').map_or("", |(_, rest)| rest),
+            None => rest,
+        };
+        if let Some(message) = rest.strip_prefix(':') {
+            diagnostics.push(BuildDiagnostic {
+                level,
+                message: message.trim().to_string(),
+                location: None,
+            });
+        }
+    }
+    diagnostics
+}
+
+/// Problems in `dora check` output. Each line of an error chain (`Error:`,
+/// `Caused by:` and numbered causes) becomes one diagnostic; the node comes
+/// from ``node `camera` ``, the field from ``input `tick` ``-style names or a
+/// leading YAML path such as `nodes[1].inputs.tick:`.
+pub fn parse_check_diagnostics(output: &str) -> Vec<CheckDiagnostic> {
+    output
+        .lines()
+        .map(str::trim)
+        // What follows `Location:` is the CLI's own source position
+        .take_while(|line| *line != "Location:")
+        .filter_map(|line| {
+            let line = line.strip_prefix("Error:").unwrap_or(line).trim();
+            // Cause numbering such as `0: `
+            let line = match line.split_once(": ") {
+                Some((n, rest)) if n.parse::<u32>().is_ok() => rest,
+                _ => line,
+            };
+            let skip = line.is_empty()
+                || line == "Caused by:"
+                || line.to_ascii_lowercase().contains("check passed");
+            (!skip).then(|| check_diagnostic(line))
+        })
+        .collect()
+}
+
+fn check_diagnostic(line: &str) -> CheckDiagnostic {
+    let mut node = named(line, &["node"]);
+    let mut field = named(line, &["input", "output", "field"]);
+    let mut message = line;
+
+    // serde_yaml style: `nodes[1].inputs.tick: invalid type ...`
+    if let Some((path, rest)) = line.split_once(": ") {
+        let is_path = path.contains(['.', '['])
+            && !path.contains(char::is_whitespace)
+            && path.starts_with(|c: char| c.is_ascii_alphabetic());
+        if is_path {
+            field = field.or_else(|| path.rsplit('.').next().map(str::to_string));
+            if node.is_none() && path.starts_with("nodes") {
+                node = Some(path.split('.').next().unwrap_or(path).to_string());
+            }
+            message = rest;
+        }
+    }
+
+    CheckDiagnostic {
+        node,
+        field,
+        message: message.trim().to_string(),
+    }
+}
+
+/// Name quoted after one of `kinds`, as in ``node `camera` `` or `input "tick"`.
+fn named(line: &str, kinds: &[&str]) -> Option<String> {
+    kinds.iter().find_map(|kind| {
+        let start = line.find(&format!("{} ", kind))? + kind.len() + 1;
+        let rest = &line[start..];
+        let quote = rest
+            .chars()
+            .next()
+            .filter(|c| matches!(c, '`' | '"' | '\''))?;
+        let name = rest[1..].split(quote).next()?;
+        (!name.is_empty()).then(|| name.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dora_list_payload() {
+        let output = r#"[{"uuid":"a1","name":"camera","status":"Running"},{"uuid":"b2","name":"","status":"Failed"}]"#;
+        let payload = ToolPayload::parse("dora_list", &serde_json::json!({}), &Ok(output.into()));
+        let Some(ToolPayload::Dataflows(dataflows)) = &payload else {
+            panic!("expected dataflows, got {:?}", payload);
+        };
+        assert_eq!(dataflows.len(), 2);
+        assert_eq!(
+            payload.unwrap().summary(),
+            "2 dataflows: camera (Running), b2 (Failed)"
+        );
+
+        let ndjson = "{\"uuid\":\"a1\",\"status\":\"Running\"}\n";
+        assert_eq!(parse_dataflows(ndjson).len(), 1);
+
+        let failed = Err(ToolError::Failed("no coordinator".into()));
+        assert!(ToolPayload::parse("dora_list", &serde_json::json!({}), &failed).is_none());
+    }
+
+    #[test]
+    fn test_parse_log_lines() {
+        let output =
+            "2024-05-01T10:00:00Z ERROR camera: device busy\n\n[warn] retrying\nframe 12 sent\n";
+        let lines = parse_log_lines(output, Some("camera"));
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].level, Some(Level::Error));
+        assert_eq!(lines[1].level, Some(Level::Warn));
+        assert_eq!(lines[2].level, None);
+        assert_eq!(lines[2].text, "[camera] frame 12 sent");
+        assert_eq!(ToolPayload::Logs(lines).summary(), "3 log lines, 1 error");
+    }
+
+    #[test]
+    fn test_parse_build_diagnostics() {
+        let output = "   Compiling node v0.1.0\n\
+            warning: unused variable: `x`\n\
+            \x20 --> src/main.rs:2:9\n\
+            error[E0425]: cannot find value `y` in this scope\n\
+            \x20 --> src/main.rs:3:5\n\
+            error: could not compile `node`\n";
+        let failed = Err(ToolError::Failed(output.to_string()));
+        let payload = ToolPayload::parse("dora_build", &serde_json::json!({}), &failed).unwrap();
+        let ToolPayload::Build(diagnostics) = &payload else {
+            panic!("expected build diagnostics");
+        };
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0].level, Level::Warn);
+        assert_eq!(diagnostics[0].location.as_deref(), Some("src/main.rs:2:9"));
+        assert_eq!(
+            diagnostics[1].describe(),
+            "error: cannot find value `y` in this scope (src/main.rs:3:5)"
+        );
+        assert_eq!(diagnostics[2].location, None);
+        assert_eq!(payload.summary(), "Build: 2 errors, 1 warning");
+    }
+
+    #[test]
+    fn test_parse_check_diagnostics() {
+        let output = "Error: failed to validate dataflow\n\n\
+            Caused by:\n\
+            \x20   0: paths of node `camera` are not valid\n\
+            \x20   1: input `tick` of node `plot` is not mapped to any output\n\
+            \x20   2: nodes[1].inputs.image: invalid type: map, expected a string\n\
+            \n\
+            Location:\n\
+            \x20   binaries/cli/src/check.rs:42:5\n";
+        let failed = Err(ToolError::Failed(output.to_string()));
+        let payload = ToolPayload::parse("dora_check", &serde_json::json!({}), &failed).unwrap();
+        let ToolPayload::Check(diagnostics) = &payload else {
+            panic!("expected check diagnostics");
+        };
+        assert_eq!(diagnostics.len(), 4);
+        assert_eq!(diagnostics[0].message, "failed to validate dataflow");
+        assert_eq!(diagnostics[1].node.as_deref(), Some("camera"));
+        assert_eq!(diagnostics[1].field, None);
+        assert_eq!(
+            diagnostics[2].describe(),
+            "plot.tick: input `tick` of node `plot` is not mapped to any output"
+        );
+        assert_eq!(diagnostics[3].node.as_deref(), Some("nodes[1]"));
+        assert_eq!(diagnostics[3].field.as_deref(), Some("image"));
+        assert_eq!(
+            diagnostics[3].message,
+            "invalid type: map, expected a string"
+        );
+        assert_eq!(payload.summary(), "Check: 4 problems");
+
+        let passed = Ok("Dataflow check passed\n".to_string());
+        let payload = ToolPayload::parse("dora_check", &serde_json::json!({}), &passed).unwrap();
+        assert_eq!(payload.summary(), "Check passed");
+
+        let failed = Err(ToolError::Failed("\n".to_string()));
+        let payload = ToolPayload::parse("dora_check", &serde_json::json!({}), &failed).unwrap();
+        assert_eq!(payload.summary(), "Check: 1 problem");
+    }
+}
@@ -0,0 +1,195 @@
+//! Building tool commands portably.
+//!
+//! Programs run without a shell. On Windows a bare name or extensionless
+//! path such as the `dora_path` setting is resolved against `PATHEXT`
+//! ([`find_executable`]), since the standard library only tries `.exe`
+//! and misses `.cmd`/`.bat` launchers, and children get no console window
+//! of their own. Arguments are passed as they are; the standard library
+//! quotes them for the platform. [`open_path`] hands a file to the desktop.
+//!
+//! A tool started in its own process group ([`own_process_group`]) is
+//! stopped with everything it started ([`kill_tree`]), so a shell command's
+//! children do not outlive a timeout or cancel.
+
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+
+/// Extensions tried when `PATHEXT` is unset.
+#[cfg_attr(not(windows), allow(dead_code))]
+const DEFAULT_PATHEXT: &str = ".COM;.EXE;.BAT;.CMD";
+
+/// The shell `shell_command` runs in, for the tool's description.
+pub fn shell_name() -> &'static str {
+    if cfg!(windows) {
+        "cmd.exe"
+    } else {
+        "sh"
+    }
+}
+
+/// A command running `program` directly.
+pub fn command(program: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let pathext = std::env::var("PATHEXT").unwrap_or_else(|_| DEFAULT_PATHEXT.to_string());
+        let path = std::env::var_os("PATH");
+        let program = find_executable(program, path.as_deref(), &pathext, Path::is_file)
+            .unwrap_or_else(|| PathBuf::from(program));
+        without_window(Command::new(program))
+    }
+    #[cfg(not(windows))]
+    Command::new(program)
+}
+
+/// A command running `line` in the platform shell.
+pub fn shell(line: &str) -> Command {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // `/S` strips just the outer quotes, so cmd sees `line` as written
+        // rather than as re-quoted by the standard library
+        let mut cmd = without_window(Command::new("cmd"));
+        cmd.args(["/D", "/S", "/C"])
+            .raw_arg(format!("\"{}\"", line));
+        cmd
+    }
+    #[cfg(not(windows))]
+    {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", line]);
+        cmd
+    }
+}
+
+/// Open `path` in the application the desktop associates with it, e.g. the
+/// user's editor for YAML, without waiting for it to exit.
+pub fn open_path(path: &Path) -> Result<(), String> {
+    let mut cmd = if cfg!(windows) {
+        // `start` takes its first quoted argument as a window title
+        let mut cmd = command("cmd");
+        cmd.args(["/C", "start", ""]).arg(path);
+        cmd
+    } else if cfg!(target_os = "macos") {
+        let mut cmd = command("open");
+        cmd.arg(path);
+        cmd
+    } else {
+        let mut cmd = command("xdg-open");
+        cmd.arg(path);
+        cmd
+    };
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    // Reap the opener once it hands the file over
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Start `cmd` as the leader of a new process group, which the processes it
+/// starts join, for [`kill_tree`]. Windows finds the tree by parent instead.
+pub fn own_process_group(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = cmd;
+}
+
+/// Kill `child` and the processes it started, without waiting for them.
+pub fn kill_tree(child: &mut Child) {
+    #[cfg(unix)]
+    if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+        // SAFETY: kill takes no pointers; a negative pid names the group
+        // the child leads (see own_process_group)
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+    #[cfg(windows)]
+    {
+        let mut taskkill = command("taskkill");
+        taskkill
+            .args(["/T", "/F", "/PID"])
+            .arg(child.id().to_string());
+        let _ = taskkill.output();
+    }
+    // Also covers a child that left its group or a failed taskkill
+    let _ = child.kill();
+}
+
+/// Keep a console program started from the GUI from flashing a window.
+#[cfg(windows)]
+fn without_window(mut cmd: Command) -> Command {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    cmd.creation_flags(CREATE_NO_WINDOW);
+    cmd
+}
+
+/// The file `program` runs: itself or with one of `pathext`'s extensions
+/// (`;`-separated, e.g. `.EXE;.CMD`), looked up in the `path` directories
+/// when it is a bare name. `None` when no candidate `is_file`.
+pub fn find_executable(
+    program: &str,
+    path: Option<&OsStr>,
+    pathext: &str,
+    is_file: impl Fn(&Path) -> bool,
+) -> Option<PathBuf> {
+    let program = Path::new(program);
+    let candidates = |base: &Path| -> Vec<PathBuf> {
+        let mut candidates = Vec::new();
+        if base.extension().is_some() {
+            candidates.push(base.to_path_buf());
+        }
+        for ext in pathext.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let mut name = OsString::from(base.as_os_str());
+            name.push(ext.to_ascii_lowercase());
+            candidates.push(PathBuf::from(name));
+        }
+        candidates
+    };
+    let is_bare = program.components().count() == 1 && !program.is_absolute();
+    let dirs: Vec<PathBuf> = match path {
+        Some(path) if is_bare => std::env::split_paths(path).collect(),
+        _ => vec![PathBuf::new()],
+    };
+    dirs.iter()
+        .flat_map(|dir| candidates(&dir.join(program)))
+        .find(|candidate| is_file(candidate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_executable() {
+        let bin = PathBuf::from("tools").join("bin");
+        let other = PathBuf::from("other");
+        let path = std::env::join_paths([&other, &bin]).unwrap();
+        let files = [
+            bin.join("dora.cmd"),
+            bin.join("ssh.exe"),
+            other.join("ssh.exe"),
+        ];
+        let is_file = |p: &Path| files.iter().any(|f| f == p);
+        let find = |program: &str| find_executable(program, Some(&path), DEFAULT_PATHEXT, is_file);
+
+        // A `.cmd` launcher on the PATH, which `.exe` alone would miss
+        assert_eq!(find("dora"), Some(bin.join("dora.cmd")));
+        // PATH order decides
+        assert_eq!(find("ssh"), Some(other.join("ssh.exe")));
+        assert_eq!(find("ssh.exe"), Some(other.join("ssh.exe")));
+        // A path is not looked up on the PATH
+        assert_eq!(
+            find(bin.join("dora").to_str().unwrap()),
+            Some(bin.join("dora.cmd"))
+        );
+        assert_eq!(find(other.join("dora").to_str().unwrap()), None);
+        assert_eq!(find("missing"), None);
+    }
+}
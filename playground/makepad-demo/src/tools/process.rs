@@ -0,0 +1,342 @@
+//! Running tool processes with a timeout and cancellation.
+//!
+//! Each tool call gets [`Limits`]: a timeout, and a cancel flag listed in a
+//! process-wide registry so the UI can show running tools
+//! ([`describe_running`]) and stop them ([`cancel_all`]). Calls registered
+//! through a [`ToolGroup`], e.g. a chat client's, can also be cancelled on
+//! their own. The child is polled while helper threads drain its output,
+//! and killed with the processes it started once it runs past the timeout
+//! or is cancelled.
+
+use std::io::Read;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use super::{platform, ToolError};
+
+/// How often a running child is checked for exit, timeout and cancellation.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+struct Registered {
+    id: u64,
+    /// The [`ToolGroup`] it was registered through, if any
+    group: Option<u64>,
+    name: String,
+    started: Instant,
+    cancelled: Arc<AtomicBool>,
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static RUNNING: Mutex<Vec<Registered>> = Mutex::new(Vec::new());
+
+/// Timeout and cancellation for one tool call. Listed as running from
+/// [`Limits::register`] until dropped.
+pub struct Limits {
+    id: Option<u64>,
+    tool: String,
+    timeout: Option<Duration>,
+    cancelled: Arc<AtomicBool>,
+    /// Bytes of each output stream kept, from the end; all when unset.
+    tail: Option<usize>,
+}
+
+impl Limits {
+    /// Limits for a call of `tool`, listed as running until dropped.
+    pub fn register(tool: &str, timeout: Option<Duration>) -> Self {
+        Self::register_in(None, tool, timeout)
+    }
+
+    fn register_in(group: Option<u64>, tool: &str, timeout: Option<Duration>) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        RUNNING.lock().unwrap().push(Registered {
+            id,
+            group,
+            name: tool.to_string(),
+            started: Instant::now(),
+            cancelled: cancelled.clone(),
+        });
+        Self {
+            id: Some(id),
+            tool: tool.to_string(),
+            timeout,
+            cancelled,
+            tail: None,
+        }
+    }
+
+    /// No timeout and not listed, e.g. for tests.
+    pub fn none() -> Self {
+        Self {
+            id: None,
+            tool: String::new(),
+            timeout: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            tail: None,
+        }
+    }
+
+    /// Keep only the last `bytes` of each output stream, from a line start,
+    /// e.g. for logs that grow for as long as a dataflow runs.
+    pub fn with_tail(mut self, bytes: usize) -> Self {
+        self.tail = Some(bytes);
+        self
+    }
+
+    fn exceeded(&self, started: Instant) -> Option<ToolError> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Some(ToolError::Cancelled);
+        }
+        let timeout = self.timeout.filter(|t| started.elapsed() >= *t)?;
+        Some(ToolError::TimedOut {
+            tool: self.tool.clone(),
+            timeout,
+        })
+    }
+}
+
+impl Drop for Limits {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            RUNNING.lock().unwrap().retain(|r| r.id != id);
+        }
+    }
+}
+
+/// Tool calls cancelled together, without touching other running tools.
+#[derive(Debug)]
+pub struct ToolGroup {
+    id: u64,
+}
+
+impl Default for ToolGroup {
+    fn default() -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
+
+impl ToolGroup {
+    /// Limits for a call of `tool` in this group, listed as running until
+    /// dropped.
+    pub fn register(&self, tool: &str, timeout: Option<Duration>) -> Limits {
+        Limits::register_in(Some(self.id), tool, timeout)
+    }
+
+    /// Cancel the running calls of this group, returning how many there
+    /// were.
+    pub fn cancel(&self) -> usize {
+        cancel_where(|r| r.group == Some(self.id))
+    }
+}
+
+/// Running tool calls, e.g. `dora_logs 12s · shell_command 3s`; empty when
+/// none are running.
+pub fn describe_running() -> String {
+    RUNNING
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|r| format!("{} {}s", r.name, r.started.elapsed().as_secs()))
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+/// Cancel every running tool call, returning how many were running.
+pub fn cancel_all() -> usize {
+    cancel_where(|_| true)
+}
+
+fn cancel_where(matches: impl Fn(&Registered) -> bool) -> usize {
+    let running = RUNNING.lock().unwrap();
+    let cancelled = running.iter().filter(|r| matches(r));
+    cancelled
+        .inspect(|r| r.cancelled.store(true, Ordering::Relaxed))
+        .count()
+}
+
+/// Run `cmd` to completion within `limits`, killing it when they are
+/// exceeded. `program` names it in errors.
+pub(crate) fn output(
+    mut cmd: Command,
+    program: &str,
+    limits: &Limits,
+) -> Result<Output, ToolError> {
+    platform::own_process_group(&mut cmd);
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| ToolError::Unavailable(format!("Failed to execute {}: {}", program, e)))?;
+    let stdout = drain(child.stdout.take(), limits.tail);
+    let stderr = drain(child.stderr.take(), limits.tail);
+
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child
+            .try_wait()
+            .map_err(|e| format!("Failed to wait for {}: {}", program, e))?
+        {
+            break status;
+        }
+        if let Some(error) = limits.exceeded(started) {
+            // Output threads finish once the pipes close; no need to join.
+            // Killing the group too closes the pipes its members inherited
+            platform::kill_tree(&mut child);
+            let _ = child.wait();
+            return Err(error);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+/// Read a pipe to the end on a helper thread, so a chatty child never blocks
+/// on a full pipe while it is polled. With a `tail`, only that many bytes
+/// from the end are kept.
+fn drain<R: Read + Send + 'static>(pipe: Option<R>, tail: Option<usize>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let Some(mut pipe) = pipe else {
+            return bytes;
+        };
+        let Some(tail) = tail else {
+            let _ = pipe.read_to_end(&mut bytes);
+            return bytes;
+        };
+        let mut chunk = [0u8; 8192];
+        loop {
+            match pipe.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => {
+                    bytes.extend_from_slice(&chunk[..n]);
+                    // Trim in batches rather than on every read
+                    if bytes.len() > tail.saturating_mul(2) {
+                        keep_tail(&mut bytes, tail);
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+        keep_tail(&mut bytes, tail);
+        bytes
+    })
+}
+
+/// Drop all but the last `tail` bytes of `bytes`, and the partial line the
+/// cut leaves at the front.
+fn keep_tail(bytes: &mut Vec<u8>, tail: usize) {
+    if bytes.len() <= tail {
+        return;
+    }
+    let cut = bytes.len() - tail;
+    let start = bytes[cut..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(bytes.len(), |i| cut + i + 1);
+    bytes.drain(..start);
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn sleep_command(secs: &str) -> Command {
+        let mut cmd = Command::new("sleep");
+        cmd.arg(secs);
+        cmd
+    }
+
+    #[test]
+    fn test_output_collects_stdout() {
+        let mut cmd = Command::new("echo");
+        cmd.arg("hello");
+        let output = output(cmd, "echo", &Limits::none()).unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n");
+    }
+
+    #[test]
+    fn test_output_keeps_tail() {
+        let mut cmd = Command::new("seq");
+        cmd.args(["1", "100000"]);
+        let limits = Limits::none().with_tail(100);
+        let output = output(cmd, "seq", &limits).unwrap();
+        let text = String::from_utf8_lossy(&output.stdout);
+        assert!(text.len() <= 100);
+        assert!(text.starts_with("99"), "{:?}", text);
+        assert!(text.ends_with("\n99999\n100000\n"));
+    }
+
+    #[test]
+    fn test_output_kills_child_after_timeout() {
+        let limits = Limits::register("sleepy", Some(Duration::from_millis(100)));
+        let started = Instant::now();
+        let err = output(sleep_command("10"), "sleep", &limits).unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(
+            err,
+            ToolError::TimedOut {
+                tool: "sleepy".to_string(),
+                timeout: Duration::from_millis(100),
+            }
+        );
+    }
+
+    #[test]
+    fn test_group_cancel_stops_its_tools_only() {
+        // A group of its own, so tests running alongside are not cancelled
+        let group = ToolGroup::default();
+        let limits = group.register("cancel_me", None);
+        let other = Limits::register("keep_me", None);
+        assert!(describe_running().contains("cancel_me 0s"));
+
+        let handle = std::thread::spawn(move || output(sleep_command("10"), "sleep", &limits));
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(group.cancel(), 1);
+        assert_eq!(handle.join().unwrap().unwrap_err(), ToolError::Cancelled);
+        assert!(!describe_running().contains("cancel_me"));
+        assert!(!other.cancelled.load(Ordering::Relaxed));
+    }
+
+    /// Whether process `pid` runs; a killed but unreaped one does not.
+    #[cfg(target_os = "linux")]
+    fn running(pid: &str) -> bool {
+        std::fs::read_to_string(format!("/proc/{}/stat", pid))
+            .is_ok_and(|stat| !stat.rsplit(") ").next().unwrap_or("").starts_with('Z'))
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_timeout_kills_grandchildren() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(format!("sleep 30 & echo $! > {}; wait", pid_file.display()));
+        let limits = Limits::register("sleepy_shell", Some(Duration::from_millis(200)));
+        assert!(output(cmd, "sh", &limits).is_err());
+
+        let pid = std::fs::read_to_string(&pid_file).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while running(pid.trim()) {
+            assert!(
+                Instant::now() < deadline,
+                "sleep {} outlived its shell",
+                pid
+            );
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! Running dora commands on a remote host over SSH.
+//!
+//! When an SSH target is set (settings `ssh_host`), [`super::run_dora`] runs
+//! `ssh <target> -- <dora> <args>` instead of the local executable, so a
+//! headless robot can be managed without installing dora on the desktop.
+//! Authentication is left to the user's SSH setup (keys, agent,
+//! `~/.ssh/config`); `BatchMode` makes a missing key fail instead of waiting
+//! for a password prompt. Only dora commands go to the remote host; file and
+//! shell tools keep running locally.
+
+use std::fmt;
+
+/// Seconds `ssh` waits for the connection before giving up.
+const CONNECT_TIMEOUT_SECS: u32 = 10;
+
+/// A remote host given as `[user@]host[:port]`, with IPv6 addresses in
+/// brackets, e.g. `robot@[fe80::1]:2222`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SshTarget {
+    pub user: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+impl SshTarget {
+    pub fn parse(target: &str) -> Result<Self, String> {
+        let target = target.trim();
+        let invalid = |reason: &str| format!("Invalid SSH host '{}': {}", target, reason);
+        if target.is_empty() {
+            return Err(invalid("empty"));
+        }
+        // A leading dash would be read by ssh as an option
+        if target.starts_with('-') || target.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(invalid("unexpected characters"));
+        }
+
+        let (user, rest) = match target.rsplit_once('@') {
+            Some((user, rest)) if !user.is_empty() => (Some(user.to_string()), rest),
+            Some(_) => return Err(invalid("empty user name")),
+            None => (None, target),
+        };
+        let (host, port) = if let Some(bracketed) = rest.strip_prefix('[') {
+            let (host, after) = bracketed
+                .split_once(']')
+                .ok_or_else(|| invalid("missing ']'"))?;
+            match after {
+                "" => (host, None),
+                _ => (
+                    host,
+                    Some(after.strip_prefix(':').ok_or_else(|| invalid("bad port"))?),
+                ),
+            }
+        } else {
+            match rest.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (rest, None),
+            }
+        };
+        if host.is_empty() || host.starts_with('-') {
+            return Err(invalid("missing host"));
+        }
+        let port = port
+            .map(|p| p.parse::<u16>().ok().filter(|p| *p > 0))
+            .map(|p| p.ok_or_else(|| invalid("bad port")))
+            .transpose()?;
+
+        Ok(Self {
+            user,
+            host: host.to_string(),
+            port,
+        })
+    }
+
+    /// Arguments for `ssh` that run `program args` on this host.
+    pub fn command_args(&self, program: &str, args: &[&str]) -> Vec<String> {
+        let mut argv = vec![
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            format!("ConnectTimeout={}", CONNECT_TIMEOUT_SECS),
+        ];
+        if let Some(port) = self.port {
+            argv.push("-p".to_string());
+            argv.push(port.to_string());
+        }
+        argv.push(match &self.user {
+            Some(user) => format!("{}@{}", user, self.host),
+            None => self.host.clone(),
+        });
+        argv.push("--".to_string());
+        // The remote side joins everything into one shell command line
+        let command: Vec<String> = std::iter::once(program)
+            .chain(args.iter().copied())
+            .map(quote)
+            .collect();
+        argv.push(command.join(" "));
+        argv
+    }
+}
+
+impl fmt::Display for SshTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(user) = &self.user {
+            write!(f, "{}@", user)?;
+        }
+        match self.port {
+            Some(port) if self.host.contains(':') => write!(f, "[{}]:{}", self.host, port),
+            Some(port) => write!(f, "{}:{}", self.host, port),
+            None => f.write_str(&self.host),
+        }
+    }
+}
+
+/// Quote `arg` for a POSIX shell.
+fn quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@,+".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ssh_target() {
+        let target = SshTarget::parse(" robot@10.0.0.7:2222 ").unwrap();
+        assert_eq!(target.user.as_deref(), Some("robot"));
+        assert_eq!(target.host, "10.0.0.7");
+        assert_eq!(target.port, Some(2222));
+        assert_eq!(target.to_string(), "robot@10.0.0.7:2222");
+
+        let target = SshTarget::parse("[fe80::1]:22").unwrap();
+        assert_eq!(target.host, "fe80::1");
+        assert_eq!(target.to_string(), "[fe80::1]:22");
+        assert_eq!(SshTarget::parse("jetson").unwrap().to_string(), "jetson");
+
+        assert!(SshTarget::parse("").is_err());
+        assert!(SshTarget::parse("-oProxyCommand=sh").is_err());
+        assert!(SshTarget::parse("robot@host:99999").is_err());
+        assert!(SshTarget::parse("@host").is_err());
+        assert!(SshTarget::parse("host name").is_err());
+    }
+
+    #[test]
+    fn test_command_args_quote_remote_command() {
+        let target = SshTarget::parse("robot@jetson:2222").unwrap();
+        let argv = target.command_args("dora", &["start", "my flow.yml", "--name", "it's"]);
+        assert_eq!(
+            argv,
+            vec![
+                "-o",
+                "BatchMode=yes",
+                "-o",
+                "ConnectTimeout=10",
+                "-p",
+                "2222",
+                "robot@jetson",
+                "--",
+                "dora start 'my flow.yml' --name 'it'\\''s'",
+            ]
+        );
+        assert_eq!(quote(""), "''");
+        assert_eq!(quote("$(rm -rf ~)"), "'$(rm -rf ~)'");
+    }
+}
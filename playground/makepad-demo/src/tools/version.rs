@@ -0,0 +1,97 @@
+//! Detection of the installed dora version.
+
+use std::fmt;
+use std::time::Duration;
+
+use super::{run_dora, Limits};
+
+/// `dora --version` should answer at once; a hang means a broken install.
+const DETECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Oldest dora release the tools are written against; older CLIs lack some
+/// of the subcommands and flags they use.
+pub const MIN_DORA_VERSION: DoraVersion = DoraVersion {
+    major: 0,
+    minor: 3,
+    patch: 9,
+};
+
+/// A `major.minor.patch` dora release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DoraVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl DoraVersion {
+    /// Find the version in `dora --version` output such as `dora-cli 0.3.12`.
+    /// Pre-release suffixes like `-rc.1` are ignored.
+    pub fn parse(output: &str) -> Option<Self> {
+        output.split_whitespace().find_map(|word| {
+            let word = word.strip_prefix('v').unwrap_or(word);
+            let core = word.split(['-', '+']).next()?;
+            let mut parts = core.split('.').map(|p| p.parse::<u32>().ok());
+            let version = Self {
+                major: parts.next()??,
+                minor: parts.next()??,
+                patch: parts.next()??,
+            };
+            parts.next().is_none().then_some(version)
+        })
+    }
+
+    pub fn is_supported(&self) -> bool {
+        *self >= MIN_DORA_VERSION
+    }
+}
+
+impl fmt::Display for DoraVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Run `dora --version` with the configured executable.
+pub fn detect() -> Result<DoraVersion, String> {
+    let limits = Limits::register("dora --version", Some(DETECT_TIMEOUT));
+    let output = run_dora(&["--version"], &limits)?;
+    DoraVersion::parse(&output)
+        .ok_or_else(|| format!("Unrecognized dora --version output: {}", output.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(major: u32, minor: u32, patch: u32) -> DoraVersion {
+        DoraVersion {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    #[test]
+    fn test_parse_version_output() {
+        assert_eq!(
+            DoraVersion::parse("dora-cli 0.3.12\n"),
+            Some(version(0, 3, 12))
+        );
+        assert_eq!(
+            DoraVersion::parse("dora v1.0.0-rc.1"),
+            Some(version(1, 0, 0))
+        );
+        assert_eq!(DoraVersion::parse("dora-cli 0.3"), None);
+        assert_eq!(DoraVersion::parse("command not found"), None);
+        assert_eq!(version(0, 3, 12).to_string(), "0.3.12");
+    }
+
+    #[test]
+    fn test_minimum_version() {
+        assert!(version(0, 3, 12).is_supported());
+        assert!(MIN_DORA_VERSION.is_supported());
+        assert!(!version(0, 3, 8).is_supported());
+        assert!(version(0, 10, 0) > version(0, 9, 20));
+    }
+}
@@ -0,0 +1,254 @@
+//! Which columns the traces table shows, and in what order.
+//!
+//! A layout is stored in settings as a list of column keys, e.g.
+//! `["service", "operation", "attr:node.id"]`. Attribute columns show one
+//! span attribute and are fetched from SigNoz alongside the fixed columns.
+
+use std::fmt;
+
+/// Most columns the table can show at once.
+pub const MAX_COLUMNS: usize = 10;
+
+const ATTRIBUTE_PREFIX: &str = "attr:";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceColumn {
+    Service,
+    Operation,
+    Duration,
+    Status,
+    Events,
+    Time,
+    TraceId,
+    SpanId,
+    SpanKind,
+    /// A span attribute, by key
+    Attribute(String),
+}
+
+/// Every column other than attribute columns, in chooser order.
+pub const FIXED_COLUMNS: [TraceColumn; 9] = [
+    TraceColumn::Service,
+    TraceColumn::Operation,
+    TraceColumn::Duration,
+    TraceColumn::Status,
+    TraceColumn::Events,
+    TraceColumn::Time,
+    TraceColumn::TraceId,
+    TraceColumn::SpanId,
+    TraceColumn::SpanKind,
+];
+
+impl TraceColumn {
+    pub fn key(&self) -> String {
+        match self {
+            TraceColumn::Service => "service".to_string(),
+            TraceColumn::Operation => "operation".to_string(),
+            TraceColumn::Duration => "duration".to_string(),
+            TraceColumn::Status => "status".to_string(),
+            TraceColumn::Events => "events".to_string(),
+            TraceColumn::Time => "time".to_string(),
+            TraceColumn::TraceId => "trace_id".to_string(),
+            TraceColumn::SpanId => "span_id".to_string(),
+            TraceColumn::SpanKind => "span_kind".to_string(),
+            TraceColumn::Attribute(key) => format!("{}{}", ATTRIBUTE_PREFIX, key),
+        }
+    }
+
+    pub fn parse(key: &str) -> Option<Self> {
+        let key = key.trim();
+        if let Some(attribute) = key.strip_prefix(ATTRIBUTE_PREFIX) {
+            let attribute = attribute.trim();
+            return (!attribute.is_empty()).then(|| TraceColumn::Attribute(attribute.to_string()));
+        }
+        FIXED_COLUMNS.into_iter().find(|c| c.key() == key)
+    }
+
+    /// Header text, e.g. `SPAN KIND` or `NODE.ID`
+    pub fn title(&self) -> String {
+        match self {
+            TraceColumn::Attribute(key) => key.to_uppercase(),
+            column => column.key().replace('_', " ").to_uppercase(),
+        }
+    }
+
+    /// Width in pixels; `None` fills the space left over.
+    pub fn width(&self) -> Option<f64> {
+        match self {
+            TraceColumn::Operation => None,
+            TraceColumn::Service | TraceColumn::Attribute(_) => Some(120.0),
+            TraceColumn::Duration | TraceColumn::SpanKind => Some(80.0),
+            TraceColumn::Status => Some(60.0),
+            TraceColumn::Events => Some(200.0),
+            TraceColumn::Time => Some(140.0),
+            TraceColumn::TraceId | TraceColumn::SpanId => Some(150.0),
+        }
+    }
+}
+
+impl fmt::Display for TraceColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceColumn::Attribute(key) => write!(f, "Attribute {}", key),
+            column => {
+                let title = column.key().replace('_', " ");
+                let mut chars = title.chars();
+                let first = chars.next().map(|c| c.to_ascii_uppercase());
+                write!(f, "{}{}", first.unwrap_or_default(), chars.as_str())
+            }
+        }
+    }
+}
+
+/// The columns shown, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnLayout {
+    columns: Vec<TraceColumn>,
+}
+
+impl Default for ColumnLayout {
+    fn default() -> Self {
+        Self {
+            columns: FIXED_COLUMNS[..6].to_vec(),
+        }
+    }
+}
+
+impl ColumnLayout {
+    /// Layout from stored keys; unknown and repeated keys are skipped, and
+    /// no usable keys gives the default layout.
+    pub fn from_keys(keys: &[String]) -> Self {
+        let mut columns: Vec<TraceColumn> = Vec::new();
+        for column in keys.iter().filter_map(|k| TraceColumn::parse(k)) {
+            if !columns.contains(&column) && columns.len() < MAX_COLUMNS {
+                columns.push(column);
+            }
+        }
+        if columns.is_empty() {
+            return Self::default();
+        }
+        Self { columns }
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.columns.iter().map(TraceColumn::key).collect()
+    }
+
+    pub fn columns(&self) -> &[TraceColumn] {
+        &self.columns
+    }
+
+    pub fn is_shown(&self, column: &TraceColumn) -> bool {
+        self.columns.contains(column)
+    }
+
+    /// Attribute keys to fetch with the spans.
+    pub fn attribute_keys(&self) -> Vec<String> {
+        self.columns
+            .iter()
+            .filter_map(|c| match c {
+                TraceColumn::Attribute(key) => Some(key.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Show or hide `column`; hidden columns are shown at the end. The last
+    /// column cannot be hidden, nor more than [`MAX_COLUMNS`] shown.
+    pub fn toggle(&mut self, column: &TraceColumn) -> bool {
+        if let Some(index) = self.columns.iter().position(|c| c == column) {
+            if self.columns.len() == 1 {
+                return false;
+            }
+            self.columns.remove(index);
+        } else {
+            if self.columns.len() >= MAX_COLUMNS {
+                return false;
+            }
+            self.columns.push(column.clone());
+        }
+        true
+    }
+
+    /// Move a shown column `by` places left (negative) or right.
+    pub fn move_column(&mut self, column: &TraceColumn, by: isize) -> bool {
+        let Some(index) = self.columns.iter().position(|c| c == column) else {
+            return false;
+        };
+        let target = index as isize + by;
+        if target < 0 || target >= self.columns.len() as isize {
+            return false;
+        }
+        let column = self.columns.remove(index);
+        self.columns.insert(target as usize, column);
+        true
+    }
+
+    /// Entries for the column chooser: shown columns in order, then the
+    /// hidden fixed columns.
+    pub fn choices(&self) -> Vec<(TraceColumn, bool)> {
+        let hidden = FIXED_COLUMNS.into_iter().filter(|c| !self.is_shown(c));
+        self.columns
+            .iter()
+            .map(|c| (c.clone(), true))
+            .chain(hidden.map(|c| (c, false)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[test]
+    fn test_column_keys_roundtrip() {
+        for column in FIXED_COLUMNS {
+            assert_eq!(TraceColumn::parse(&column.key()), Some(column));
+        }
+        let attribute = TraceColumn::Attribute("node.id".to_string());
+        assert_eq!(TraceColumn::parse("attr:node.id"), Some(attribute.clone()));
+        assert_eq!(attribute.title(), "NODE.ID");
+        assert_eq!(TraceColumn::SpanKind.title(), "SPAN KIND");
+        assert_eq!(TraceColumn::SpanKind.to_string(), "Span kind");
+        assert_eq!(TraceColumn::parse("attr: "), None);
+        assert_eq!(TraceColumn::parse("colour"), None);
+    }
+
+    #[test]
+    fn test_layout_from_keys() {
+        assert_eq!(ColumnLayout::from_keys(&[]), ColumnLayout::default());
+        assert_eq!(
+            ColumnLayout::from_keys(&keys(&["bogus"])),
+            ColumnLayout::default()
+        );
+
+        let layout =
+            ColumnLayout::from_keys(&keys(&["time", "attr:node.id", "time", "bogus", "service"]));
+        assert_eq!(layout.keys(), keys(&["time", "attr:node.id", "service"]));
+        assert_eq!(layout.attribute_keys(), keys(&["node.id"]));
+    }
+
+    #[test]
+    fn test_toggle_and_move_columns() {
+        let mut layout = ColumnLayout::from_keys(&keys(&["service", "operation"]));
+        assert!(layout.toggle(&TraceColumn::TraceId));
+        assert!(layout.move_column(&TraceColumn::TraceId, -2));
+        assert_eq!(layout.keys(), keys(&["trace_id", "service", "operation"]));
+        assert!(!layout.move_column(&TraceColumn::TraceId, -1));
+        assert!(!layout.move_column(&TraceColumn::Time, 1));
+
+        assert!(layout.toggle(&TraceColumn::Service));
+        assert!(layout.toggle(&TraceColumn::Operation));
+        assert!(!layout.toggle(&TraceColumn::TraceId));
+        assert_eq!(layout.keys(), keys(&["trace_id"]));
+
+        let choices = layout.choices();
+        assert_eq!(choices[0], (TraceColumn::TraceId, true));
+        assert_eq!(choices[1], (TraceColumn::Service, false));
+        assert_eq!(choices.len(), FIXED_COLUMNS.len());
+    }
+}
@@ -0,0 +1,142 @@
+//! Aggregating spans into one summary per trace.
+
+use crate::otlp::types::Span;
+
+/// One trace built from its spans, for the grouped traces table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceSummary {
+    pub trace_id: String,
+    /// Service and operation of the root span; the earliest span stands in
+    /// when the root was not returned.
+    pub root_service: String,
+    pub root_operation: String,
+    pub start_time_ms: u64,
+    /// From the first span's start to the last span's end.
+    pub duration_ms: u64,
+    pub span_count: usize,
+    pub error_count: usize,
+    /// Indices of the trace's spans in the input, in start order.
+    pub span_indices: Vec<usize>,
+}
+
+impl TraceSummary {
+    /// e.g. `5 spans · 2 errors`
+    pub fn describe_counts(&self) -> String {
+        let plural = |n: usize, noun: &str| {
+            if n == 1 {
+                format!("1 {}", noun)
+            } else {
+                format!("{} {}s", n, noun)
+            }
+        };
+        match self.error_count {
+            0 => plural(self.span_count, "span"),
+            errors => format!(
+                "{} · {}",
+                plural(self.span_count, "span"),
+                plural(errors, "error")
+            ),
+        }
+    }
+}
+
+/// Group `spans` by trace id, keeping traces in the order they first appear.
+pub fn group_by_trace(spans: &[Span]) -> Vec<TraceSummary> {
+    let mut order: Vec<&str> = Vec::new();
+    let mut members: Vec<Vec<usize>> = Vec::new();
+    for (index, span) in spans.iter().enumerate() {
+        match order.iter().position(|id| *id == span.trace_id) {
+            Some(group) => members[group].push(index),
+            None => {
+                order.push(&span.trace_id);
+                members.push(vec![index]);
+            }
+        }
+    }
+
+    order
+        .into_iter()
+        .zip(members)
+        .map(|(trace_id, mut indices)| {
+            indices.sort_by_key(|&i| spans[i].start_time_ms);
+            let in_trace = |id: &str| indices.iter().any(|&i| spans[i].span_id == id);
+            let root = indices
+                .iter()
+                .map(|&i| &spans[i])
+                .find(|s| s.parent_span_id.as_deref().is_none_or(|p| !in_trace(p)))
+                .unwrap_or(&spans[indices[0]]);
+            let start = indices.iter().map(|&i| spans[i].start_time_ms).min();
+            let end = indices
+                .iter()
+                .map(|&i| spans[i].start_time_ms + spans[i].duration_ms)
+                .max();
+            TraceSummary {
+                trace_id: trace_id.to_string(),
+                root_service: root.service_name.clone(),
+                root_operation: root.operation_name.clone(),
+                start_time_ms: start.unwrap_or_default(),
+                duration_ms: end.unwrap_or_default() - start.unwrap_or_default(),
+                span_count: indices.len(),
+                error_count: indices.iter().filter(|&&i| spans[i].has_error).count(),
+                span_indices: indices,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn span(trace: &str, id: &str, parent: Option<&str>, start: u64, duration: u64) -> Span {
+        Span {
+            trace_id: trace.to_string(),
+            span_id: id.to_string(),
+            parent_span_id: parent.map(str::to_string),
+            service_name: format!("{}-service", id),
+            operation_name: format!("{}-op", id),
+            start_time_ms: start,
+            duration_ms: duration,
+            status_code: 0,
+            has_error: false,
+            attributes: HashMap::new(),
+            events: Vec::new(),
+            links: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_group_by_trace() {
+        let mut failed = span("a", "encode", Some("root"), 1_010, 50);
+        failed.has_error = true;
+        let spans = vec![
+            failed,
+            span("b", "solo", None, 5_000, 7),
+            span("a", "root", None, 1_000, 40),
+            span("a", "capture", Some("root"), 1_002, 5),
+        ];
+
+        let groups = group_by_trace(&spans);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].trace_id, "a");
+        assert_eq!(groups[0].root_operation, "root-op");
+        assert_eq!(groups[0].root_service, "root-service");
+        assert_eq!(groups[0].start_time_ms, 1_000);
+        assert_eq!(groups[0].duration_ms, 60);
+        assert_eq!(groups[0].span_indices, vec![2, 3, 0]);
+        assert_eq!(groups[0].describe_counts(), "3 spans · 1 error");
+        assert_eq!(groups[1].describe_counts(), "1 span");
+    }
+
+    #[test]
+    fn test_group_without_root_uses_earliest_span() {
+        let spans = vec![
+            span("a", "late", Some("missing"), 2_000, 10),
+            span("a", "early", Some("missing"), 1_000, 10),
+        ];
+        let groups = group_by_trace(&spans);
+        assert_eq!(groups[0].root_operation, "early-op");
+        assert_eq!(groups[0].duration_ms, 1_010);
+    }
+}
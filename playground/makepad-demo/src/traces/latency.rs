@@ -0,0 +1,123 @@
+//! Duration percentiles of the spans or traces shown, for colouring the
+//! DURATION column and drawing the latency histogram.
+
+/// Fewer durations than this are not worth ranking.
+const MIN_DURATIONS: usize = 5;
+
+/// How a duration ranks among those shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationHeat {
+    /// At or below the 75th percentile
+    Normal,
+    /// Above the 75th percentile
+    Slow,
+    /// Above the 95th percentile
+    Outlier,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencyStats {
+    sorted: Vec<u64>,
+}
+
+impl LatencyStats {
+    pub fn new<I: IntoIterator<Item = u64>>(durations: I) -> Self {
+        let mut sorted: Vec<u64> = durations.into_iter().collect();
+        sorted.sort_unstable();
+        Self { sorted }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sorted.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sorted.is_empty()
+    }
+
+    /// Nearest-rank percentile, `None` without durations.
+    pub fn percentile(&self, p: u8) -> Option<u64> {
+        if self.sorted.is_empty() {
+            return None;
+        }
+        let rank = (p as f64 / 100.0 * self.sorted.len() as f64).ceil() as usize;
+        Some(self.sorted[rank.clamp(1, self.sorted.len()) - 1])
+    }
+
+    pub fn max(&self) -> Option<u64> {
+        self.sorted.last().copied()
+    }
+
+    /// Rank `duration` against the others; `None` when too few are shown
+    /// for percentiles to mean much.
+    pub fn heat(&self, duration_ms: u64) -> Option<DurationHeat> {
+        if self.sorted.len() < MIN_DURATIONS {
+            return None;
+        }
+        Some(if duration_ms > self.percentile(95)? {
+            DurationHeat::Outlier
+        } else if duration_ms > self.percentile(75)? {
+            DurationHeat::Slow
+        } else {
+            DurationHeat::Normal
+        })
+    }
+
+    /// Counts of durations in `buckets` equal steps of log duration between
+    /// the shortest and the longest, so a long tail does not squash the rest
+    /// into the first bucket.
+    pub fn histogram(&self, buckets: usize) -> Vec<usize> {
+        let mut counts = vec![0; buckets];
+        let (Some(&min), Some(&max)) = (self.sorted.first(), self.sorted.last()) else {
+            return counts;
+        };
+        if buckets == 0 {
+            return counts;
+        }
+        let low = (min as f64 + 1.0).ln();
+        let span = (max as f64 + 1.0).ln() - low;
+        for &duration in &self.sorted {
+            let bucket = if span > 0.0 {
+                (((duration as f64 + 1.0).ln() - low) / span * buckets as f64) as usize
+            } else {
+                0
+            };
+            counts[bucket.min(buckets - 1)] += 1;
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_and_heat() {
+        let stats = LatencyStats::new((1..=20).map(|i| i * 10));
+        assert_eq!(stats.percentile(50), Some(100));
+        assert_eq!(stats.percentile(95), Some(190));
+        assert_eq!(stats.max(), Some(200));
+
+        assert_eq!(stats.heat(200), Some(DurationHeat::Outlier));
+        assert_eq!(stats.heat(190), Some(DurationHeat::Slow));
+        assert_eq!(stats.heat(150), Some(DurationHeat::Normal));
+
+        // Identical durations are all normal, and a handful are not ranked
+        assert_eq!(
+            LatencyStats::new([5; 10]).heat(5),
+            Some(DurationHeat::Normal)
+        );
+        assert_eq!(LatencyStats::new([1, 2, 300]).heat(300), None);
+        assert_eq!(LatencyStats::default().percentile(50), None);
+    }
+
+    #[test]
+    fn test_histogram_log_buckets() {
+        let stats = LatencyStats::new([0, 1, 9, 99, 999]);
+        assert_eq!(stats.histogram(3), vec![2, 1, 2]);
+        assert_eq!(stats.histogram(3).iter().sum::<usize>(), stats.len());
+        assert_eq!(LatencyStats::new([7, 7]).histogram(4), vec![2, 0, 0, 0]);
+        assert_eq!(LatencyStats::default().histogram(2), vec![0, 0]);
+    }
+}
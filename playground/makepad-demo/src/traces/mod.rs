@@ -1,6 +1,13 @@
+pub mod columns;
+pub mod grouping;
+pub mod latency;
+pub mod saved;
 pub mod traces_panel;
 
-pub use traces_panel::{TracesPanel, TracesPanelRef, TracesPanelWidgetRefExt};
+pub use columns::{ColumnLayout, TraceColumn};
+pub use grouping::{group_by_trace, TraceSummary};
+pub use saved::{SavedTraceQuery, TraceFilter};
+pub use traces_panel::{TracesLoadingState, TracesPanel, TracesPanelRef, TracesPanelWidgetRefExt};
 
 use makepad_widgets::*;
 
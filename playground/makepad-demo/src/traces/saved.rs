@@ -0,0 +1,350 @@
+//! Trace filters, and filters saved under a name.
+//!
+//! The traces panel filter bar edits a [`TraceFilter`]; saving it stores a
+//! [`SavedTraceQuery`] in settings, where the chat agent can also run it by
+//! name.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::otlp::types::{Span, TimeRange, TraceQuery};
+
+/// Spans asked for by a filter.
+const QUERY_LIMIT: u32 = 100;
+
+/// Spans listed when a saved query is run for the chat agent.
+const AGENT_SPAN_LINES: usize = 20;
+
+/// Service, operation, time range and tags to narrow the traces table to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraceFilter {
+    pub service_name: Option<String>,
+    pub operation_name: Option<String>,
+    /// Only spans from the last this many minutes; all stored spans when
+    /// unset.
+    pub last_minutes: Option<u64>,
+    /// Span attributes that must match exactly, e.g. `node.id = camera`.
+    pub tags: BTreeMap<String, String>,
+}
+
+impl TraceFilter {
+    /// Filter from the filter bar fields; blank fields match everything.
+    pub fn parse(service: &str, operation: &str, range: &str, tags: &str) -> Result<Self, String> {
+        let text = |s: &str| Some(s.trim().to_string()).filter(|s| !s.is_empty());
+        let range = range.trim();
+        Ok(Self {
+            service_name: text(service),
+            operation_name: text(operation),
+            last_minutes: if range.is_empty() {
+                None
+            } else {
+                Some(parse_range(range)?)
+            },
+            tags: parse_tags(tags)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The filter bar text of the time range, e.g. `2h`.
+    pub fn range_text(&self) -> String {
+        self.last_minutes.map(format_range).unwrap_or_default()
+    }
+
+    /// The filter bar text of the tags, e.g. `node.id=camera, env=lab`.
+    pub fn tags_text(&self) -> String {
+        self.tags
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Whether `span` passes the filter as of `now_ms`, for spans fetched
+    /// earlier, e.g. those kept in local storage.
+    pub fn matches(&self, span: &Span, now_ms: u64) -> bool {
+        self.service_name
+            .as_ref()
+            .is_none_or(|s| span.service_name == *s)
+            && self
+                .operation_name
+                .as_ref()
+                .is_none_or(|o| span.operation_name == *o)
+            && self
+                .last_minutes
+                .is_none_or(|m| span.start_time_ms >= now_ms.saturating_sub(m * 60_000))
+            && self
+                .tags
+                .iter()
+                .all(|(k, v)| span.attributes.get(k) == Some(v))
+    }
+
+    /// Query for spans matching the filter as of `now_ms`.
+    pub fn to_query(&self, now_ms: u64) -> TraceQuery {
+        TraceQuery {
+            service_name: self.service_name.clone(),
+            operation_name: self.operation_name.clone(),
+            time_range: self.last_minutes.map(|minutes| TimeRange {
+                start_ms: now_ms.saturating_sub(minutes * 60_000),
+                end_ms: now_ms,
+            }),
+            limit: Some(QUERY_LIMIT),
+            tags: self.tags.clone().into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// e.g. `service=camera operation=capture last 15m node.id=cam0`, or
+    /// `all spans`.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(service) = &self.service_name {
+            parts.push(format!("service={}", service));
+        }
+        if let Some(operation) = &self.operation_name {
+            parts.push(format!("operation={}", operation));
+        }
+        if let Some(minutes) = self.last_minutes {
+            parts.push(format!("last {}", format_range(minutes)));
+        }
+        for (key, value) in &self.tags {
+            parts.push(format!("{}={}", key, value));
+        }
+        if parts.is_empty() {
+            return "all spans".to_string();
+        }
+        parts.join(" ")
+    }
+}
+
+/// A trace filter saved under a name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedTraceQuery {
+    pub name: String,
+    #[serde(flatten)]
+    pub filter: TraceFilter,
+}
+
+/// Replace the saved query with the same name, or append a new one.
+pub fn upsert_query(saved: &mut Vec<SavedTraceQuery>, query: SavedTraceQuery) {
+    match saved.iter_mut().find(|q| q.name == query.name) {
+        Some(existing) => *existing = query,
+        None => saved.push(query),
+    }
+}
+
+/// Look up a saved query by name, ignoring case.
+pub fn find_query<'a>(saved: &'a [SavedTraceQuery], name: &str) -> Option<&'a SavedTraceQuery> {
+    let name = name.trim();
+    saved.iter().find(|q| q.name.eq_ignore_ascii_case(name))
+}
+
+/// One line per saved query, e.g. `- slow-camera: service=camera last 1h`.
+pub fn describe_queries(saved: &[SavedTraceQuery]) -> String {
+    let mut out = String::new();
+    for query in saved {
+        let _ = writeln!(out, "- {}: {}", query.name, query.filter.describe());
+    }
+    out
+}
+
+/// Result of running `query` for the chat agent: counts, then the slowest
+/// spans, e.g. `- camera capture 120ms error (trace 4bf9…)`.
+pub fn describe_results(query: &SavedTraceQuery, spans: &[Span]) -> String {
+    let errors = spans.iter().filter(|s| s.has_error).count();
+    let mut out = format!(
+        "{} ({}): {} spans, {} errors\n",
+        query.name,
+        query.filter.describe(),
+        spans.len(),
+        errors
+    );
+    let mut slowest: Vec<&Span> = spans.iter().collect();
+    slowest.sort_by_key(|s| std::cmp::Reverse(s.duration_ms));
+    for span in slowest.iter().take(AGENT_SPAN_LINES) {
+        let _ = writeln!(
+            out,
+            "- {} {} {}ms {} (trace {})",
+            span.service_name,
+            span.operation_name,
+            span.duration_ms,
+            if span.has_error { "error" } else { "ok" },
+            span.trace_id
+        );
+    }
+    if spans.len() > AGENT_SPAN_LINES {
+        let _ = writeln!(out, "… {} more", spans.len() - AGENT_SPAN_LINES);
+    }
+    out
+}
+
+/// Minutes in a time range such as `30m`, `2h` or `1d`.
+fn parse_range(s: &str) -> Result<u64, String> {
+    let invalid = || format!("Invalid time range '{}' (e.g. 15m, 2h, 1d)", s);
+    let split = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+    let (amount, unit) = s.split_at(split);
+    let amount: u64 = amount.parse().map_err(|_| invalid())?;
+    let minutes = match unit.trim() {
+        "m" => 1,
+        "h" => 60,
+        "d" => 1_440,
+        _ => return Err(invalid()),
+    };
+    match amount * minutes {
+        0 => Err(invalid()),
+        minutes => Ok(minutes),
+    }
+}
+
+fn format_range(minutes: u64) -> String {
+    if minutes % 1_440 == 0 {
+        format!("{}d", minutes / 1_440)
+    } else if minutes % 60 == 0 {
+        format!("{}h", minutes / 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Tags written as `key=value` pairs separated by commas.
+fn parse_tags(s: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut tags = BTreeMap::new();
+    for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (key, value) = pair
+            .split_once('=')
+            .map(|(k, v)| (k.trim(), v.trim()))
+            .filter(|(k, _)| !k.is_empty())
+            .ok_or_else(|| format!("Invalid tag '{}' (expected key=value)", pair))?;
+        tags.insert(key.to_string(), value.to_string());
+    }
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_parse_filter() {
+        let filter = TraceFilter::parse(" camera ", "", "2h", "node.id=cam0, env = lab").unwrap();
+        assert_eq!(filter.service_name.as_deref(), Some("camera"));
+        assert_eq!(filter.operation_name, None);
+        assert_eq!(filter.last_minutes, Some(120));
+        assert_eq!(filter.tags_text(), "env=lab, node.id=cam0");
+        assert_eq!(filter.range_text(), "2h");
+        assert_eq!(
+            filter.describe(),
+            "service=camera last 2h env=lab node.id=cam0"
+        );
+
+        assert!(TraceFilter::parse("", "", "", "").unwrap().is_empty());
+        assert_eq!(TraceFilter::default().describe(), "all spans");
+        assert!(TraceFilter::parse("", "", "5x", "").is_err());
+        assert!(TraceFilter::parse("", "", "0m", "").is_err());
+        assert!(TraceFilter::parse("", "", "", "node.id").is_err());
+    }
+
+    #[test]
+    fn test_filter_to_query() {
+        let filter = TraceFilter::parse("camera", "capture", "15m", "node.id=cam0").unwrap();
+        let query = filter.to_query(1_000_000);
+        assert_eq!(query.service_name.as_deref(), Some("camera"));
+        assert_eq!(query.operation_name.as_deref(), Some("capture"));
+        let range = query.time_range.unwrap();
+        assert_eq!((range.start_ms, range.end_ms), (100_000, 1_000_000));
+        assert_eq!(query.tags.get("node.id").map(String::as_str), Some("cam0"));
+        assert!(TraceFilter::default().to_query(5).time_range.is_none());
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let span = Span {
+            trace_id: "t1".to_string(),
+            span_id: "s1".to_string(),
+            parent_span_id: None,
+            service_name: "camera".to_string(),
+            operation_name: "capture".to_string(),
+            start_time_ms: 500_000,
+            duration_ms: 5,
+            status_code: 0,
+            has_error: false,
+            attributes: HashMap::from([("node.id".to_string(), "cam0".to_string())]),
+            events: Vec::new(),
+            links: Vec::new(),
+        };
+        let matches = |service: &str, range: &str, tags: &str| {
+            TraceFilter::parse(service, "", range, tags)
+                .unwrap()
+                .matches(&span, 1_000_000)
+        };
+        assert!(matches("", "", ""));
+        assert!(matches("camera", "15m", "node.id=cam0"));
+        assert!(!matches("planner", "", ""));
+        assert!(!matches("", "", "node.id=cam1"));
+        // 500 s before now is outside the last 5 minutes
+        assert!(!matches("", "5m", ""));
+    }
+
+    #[test]
+    fn test_upsert_and_find_queries() {
+        let query = |name: &str, service: &str| SavedTraceQuery {
+            name: name.to_string(),
+            filter: TraceFilter {
+                service_name: Some(service.to_string()),
+                ..Default::default()
+            },
+        };
+        let mut saved = Vec::new();
+        upsert_query(&mut saved, query("Camera", "camera"));
+        upsert_query(&mut saved, query("planner", "planner"));
+        upsert_query(&mut saved, query("Camera", "camera-v2"));
+        assert_eq!(saved.len(), 2);
+        assert_eq!(
+            find_query(&saved, " camera")
+                .unwrap()
+                .filter
+                .service_name
+                .as_deref(),
+            Some("camera-v2")
+        );
+        assert!(find_query(&saved, "lidar").is_none());
+        assert_eq!(
+            describe_queries(&saved),
+            "- Camera: service=camera-v2\n- planner: service=planner\n"
+        );
+    }
+
+    #[test]
+    fn test_describe_results() {
+        let span = |operation: &str, duration_ms: u64, has_error: bool| Span {
+            trace_id: "t1".to_string(),
+            span_id: operation.to_string(),
+            parent_span_id: None,
+            service_name: "camera".to_string(),
+            operation_name: operation.to_string(),
+            start_time_ms: 0,
+            duration_ms,
+            status_code: 0,
+            has_error,
+            attributes: HashMap::new(),
+            events: Vec::new(),
+            links: Vec::new(),
+        };
+        let query = SavedTraceQuery {
+            name: "camera".to_string(),
+            filter: TraceFilter::parse("camera", "", "1h", "").unwrap(),
+        };
+        let spans = vec![span("capture", 5, false), span("encode", 40, true)];
+        assert_eq!(
+            describe_results(&query, &spans),
+            "camera (service=camera last 1h): 2 spans, 1 errors\n\
+             - camera encode 40ms error (trace t1)\n\
+             - camera capture 5ms ok (trace t1)\n"
+        );
+    }
+}
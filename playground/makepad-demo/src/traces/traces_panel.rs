@@ -1,8 +1,16 @@
 use makepad_widgets::*;
 use std::cell::RefMut;
+use std::collections::{HashMap, HashSet};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::otlp::types::Span;
+use super::columns::{ColumnLayout, TraceColumn, MAX_COLUMNS};
+use super::grouping::{group_by_trace, TraceSummary};
+use super::latency::{DurationHeat, LatencyStats};
+use super::saved::{SavedTraceQuery, TraceFilter};
+use crate::datetime;
+use crate::i18n;
+use crate::otlp::types::{Span, SpanEvent};
+use crate::text;
 
 live_design! {
     use link::theme::*;
@@ -21,6 +29,23 @@ live_design! {
     STATUS_ERROR = #ef4444
     STATUS_UNSET = #94a3b8
 
+    // Table cells; which column each slot shows, and its width, is set from
+    // the column layout (see `set_cells`)
+    TraceHeaderCell = <Label> {
+        width: 120, height: Fit
+        draw_text: {
+            color: (TEXT_SECONDARY),
+            text_style: { font_size: 11.0 }
+        }
+    }
+    TraceCell = <Label> {
+        width: 120, height: Fit
+        draw_text: {
+            color: (TEXT_PRIMARY),
+            text_style: { font_size: 11.0 }
+        }
+    }
+
     // Trace table header
     TraceTableHeader = <View> {
         width: Fill, height: 40
@@ -31,140 +56,295 @@ live_design! {
         align: { y: 0.5 }
         spacing: 8
 
-        <Label> {
-            width: 120, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
-            }
-            text: "SERVICE"
-        }
-        <Label> {
-            width: Fill, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
-            }
-            text: "OPERATION"
-        }
-        <Label> {
-            width: 80, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
-            }
-            text: "DURATION"
-        }
-        <Label> {
-            width: 60, height: Fit
+        <View> { width: 24, height: Fit }
+        cell_0 = <TraceHeaderCell> {}
+        cell_1 = <TraceHeaderCell> {}
+        cell_2 = <TraceHeaderCell> {}
+        cell_3 = <TraceHeaderCell> {}
+        cell_4 = <TraceHeaderCell> {}
+        cell_5 = <TraceHeaderCell> {}
+        cell_6 = <TraceHeaderCell> {}
+        cell_7 = <TraceHeaderCell> {}
+        cell_8 = <TraceHeaderCell> {}
+        cell_9 = <TraceHeaderCell> {}
+        <View> { width: 60, height: Fit }
+    }
+
+    // Trace row; a cursor makes it report hover in/out, for the exact-time
+    // tooltip
+    TraceRow = <View> {
+        width: Fill, height: 40
+        flow: Right
+        show_bg: true
+        draw_bg: { color: (ROW_BG) }
+        padding: { left: 16, right: 16 }
+        align: { y: 0.5 }
+        spacing: 8
+        cursor: Default
+
+        // Marks spans listed under an expanded trace
+        lead_label = <Label> {
+            width: 24, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
                 text_style: { font_size: 11.0 }
             }
-            text: "STATUS"
         }
-        <Label> {
-            width: 140, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
-            }
-            text: "TIME"
+        cell_0 = <TraceCell> {}
+        cell_1 = <TraceCell> {}
+        cell_2 = <TraceCell> {}
+        cell_3 = <TraceCell> {}
+        cell_4 = <TraceCell> {}
+        cell_5 = <TraceCell> {}
+        cell_6 = <TraceCell> {}
+        cell_7 = <TraceCell> {}
+        cell_8 = <TraceCell> {}
+        cell_9 = <TraceCell> {}
+        inspect_button = <Button> {
+            width: 60, height: 24
+            draw_text: { text_style: { font_size: 10.0 } }
+            text: "Inspect"
         }
     }
 
-    // Trace row
-    TraceRow = <View> {
+    // Alternate trace row
+    TraceRowAlt = <TraceRow> {
+        draw_bg: { color: (ROW_ALT_BG) }
+    }
+
+    // Span that just arrived through the live tail
+    TraceRowFresh = <TraceRow> {
+        draw_bg: { color: #fef9c3 }
+    }
+
+    // One row per trace in grouped mode; the button expands its spans
+    TraceGroupRow = <View> {
         width: Fill, height: 40
         flow: Right
         show_bg: true
-        draw_bg: { color: (ROW_BG) }
+        draw_bg: { color: #eef2f7 }
         padding: { left: 16, right: 16 }
         align: { y: 0.5 }
         spacing: 8
+        cursor: Default
 
-        service_label = <Label> {
-            width: 120, height: Fit
+        expand_button = <Button> {
+            width: 24, height: 24
+            padding: 0
+            draw_text: { text_style: { font_size: 11.0 } }
+            text: "+"
+        }
+        cell_0 = <TraceCell> {}
+        cell_1 = <TraceCell> {}
+        cell_2 = <TraceCell> {}
+        cell_3 = <TraceCell> {}
+        cell_4 = <TraceCell> {}
+        cell_5 = <TraceCell> {}
+        cell_6 = <TraceCell> {}
+        cell_7 = <TraceCell> {}
+        cell_8 = <TraceCell> {}
+        cell_9 = <TraceCell> {}
+        <View> { width: 60, height: Fit }
+    }
+
+    // Column chooser entry: shown columns first, in order
+    ColumnOption = <View> {
+        width: Fill, height: 32
+        flow: Right
+        align: { y: 0.5 }
+        padding: { left: 16, right: 16 }
+        spacing: 8
+
+        column_label = <Label> {
+            width: 160, height: Fit
             draw_text: {
                 color: (TEXT_PRIMARY),
                 text_style: { font_size: 11.0 }
             }
         }
-        operation_label = <Label> {
+        show_button = <Button> {
+            width: 60, height: 24
+            draw_text: { text_style: { font_size: 10.0 } }
+            text: "Hide"
+        }
+        left_button = <Button> {
+            width: 60, height: 24
+            draw_text: { text_style: { font_size: 10.0 } }
+            text: "Left"
+        }
+        right_button = <Button> {
+            width: 60, height: 24
+            draw_text: { text_style: { font_size: 10.0 } }
+            text: "Right"
+        }
+    }
+
+    ColumnChooser = <View> {
+        width: Fill, height: Fit
+        visible: false
+        flow: Down
+        show_bg: true
+        draw_bg: { color: #f8fafc }
+        padding: { top: 6, bottom: 6 }
+
+        column_list = <PortalList> {
+            width: Fill, height: 240
+            flow: Down
+
+            ColumnOption = <ColumnOption> {}
+        }
+        <View> {
             width: Fill, height: Fit
-            draw_text: {
-                color: (TEXT_PRIMARY),
-                text_style: { font_size: 12.0 }
+            flow: Right
+            align: { y: 0.5 }
+            padding: { left: 16, right: 16, top: 6 }
+            spacing: 8
+
+            attribute_input = <TextInput> {
+                width: 240, height: Fit
+                empty_text: "Span attribute, e.g. node.id"
             }
-        }
-        duration_label = <Label> {
-            width: 80, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
+            add_attribute_button = <Button> {
+                width: Fit, height: 28
+                draw_text: { text_style: { font_size: 11.0 } }
+                text: "Add column"
             }
         }
-        status_label = <Label> {
-            width: 60, height: Fit
-            draw_text: {
-                color: (STATUS_OK),
-                text_style: { font_size: 11.0 }
-            }
+    }
+
+    FilterInput = <TextInput> {
+        width: 140, height: Fit
+        draw_text: { text_style: { font_size: 11.0 } }
+    }
+
+    // Service, operation, time range and tag filters, and saved filters
+    FilterBar = <View> {
+        width: Fill, height: Fit
+        flow: Right
+        align: { y: 0.5 }
+        padding: { left: 16, right: 16, bottom: 6 }
+        spacing: 8
+
+        service_filter = <FilterInput> { empty_text: "Service" }
+        operation_filter = <FilterInput> { empty_text: "Operation" }
+        range_filter = <FilterInput> { width: 80, empty_text: "Last, e.g. 1h" }
+        tags_filter = <FilterInput> { width: 200, empty_text: "Tags, e.g. node.id=camera" }
+        apply_filter_button = <Button> {
+            width: Fit, height: 28
+            draw_text: { text_style: { font_size: 11.0 } }
+            text: "Apply"
         }
-        time_label = <Label> {
-            width: 140, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
-            }
+
+        <View> { width: Fill, height: 1 }
+
+        saved_dropdown = <DropDown> {
+            width: 140, height: 28
+            labels: ["Saved"]
+            draw_text: { text_style: { font_size: 11.0 } }
+        }
+        save_name_input = <FilterInput> { width: 120, empty_text: "Name" }
+        save_query_button = <Button> {
+            width: Fit, height: 28
+            draw_text: { text_style: { font_size: 11.0 } }
+            text: "Save"
         }
     }
 
-    // Alternate trace row
-    TraceRowAlt = <View> {
-        width: Fill, height: 40
+    // Attribute of the inspected span, with follow-up queries on its value
+    AttributeRow = <View> {
+        width: Fill, height: 32
         flow: Right
-        show_bg: true
-        draw_bg: { color: (ROW_ALT_BG) }
-        padding: { left: 16, right: 16 }
         align: { y: 0.5 }
+        padding: { left: 16, right: 16 }
         spacing: 8
 
-        service_label = <Label> {
-            width: 120, height: Fit
+        attribute_key = <Label> {
+            width: 200, height: Fit
             draw_text: {
-                color: (TEXT_PRIMARY),
+                color: (TEXT_SECONDARY),
                 text_style: { font_size: 11.0 }
             }
         }
-        operation_label = <Label> {
+        attribute_value = <Label> {
             width: Fill, height: Fit
             draw_text: {
                 color: (TEXT_PRIMARY),
-                text_style: { font_size: 12.0 }
+                text_style: { font_size: 11.0 }
             }
         }
-        duration_label = <Label> {
-            width: 80, height: Fit
+        filter_traces_button = <Button> {
+            width: Fit, height: 24
+            draw_text: { text_style: { font_size: 10.0 } }
+            text: "Filter traces"
+        }
+        filter_logs_button = <Button> {
+            width: Fit, height: 24
+            draw_text: { text_style: { font_size: 10.0 } }
+            text: "Filter logs"
+        }
+    }
+
+    // Event or link of the inspected span, after its attributes
+    DetailRow = <View> {
+        width: Fill, height: 28
+        flow: Right
+        align: { y: 0.5 }
+        padding: { left: 16, right: 16 }
+        spacing: 8
+
+        detail_label = <Label> {
+            width: 200, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
                 text_style: { font_size: 11.0 }
             }
         }
-        status_label = <Label> {
-            width: 60, height: Fit
+        detail_value = <Label> {
+            width: Fill, height: Fit
             draw_text: {
-                color: (STATUS_OK),
+                color: (TEXT_PRIMARY),
                 text_style: { font_size: 11.0 }
             }
         }
-        time_label = <Label> {
-            width: 140, height: Fit
-            draw_text: {
-                color: (TEXT_SECONDARY),
-                text_style: { font_size: 11.0 }
+    }
+
+    // Attributes, events and links of the span picked with its Inspect
+    // button
+    SpanInspector = <View> {
+        width: Fill, height: Fit
+        visible: false
+        flow: Down
+        show_bg: true
+        draw_bg: { color: #f8fafc }
+        padding: { top: 6, bottom: 6 }
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { y: 0.5 }
+            padding: { left: 16, right: 16, bottom: 4 }
+
+            inspector_title = <Label> {
+                width: Fill, height: Fit
+                draw_text: {
+                    color: (TEXT_PRIMARY),
+                    text_style: { font_size: 12.0 }
+                }
+                text: ""
+            }
+            close_inspector_button = <Button> {
+                width: Fit, height: 24
+                draw_text: { text_style: { font_size: 10.0 } }
+                text: "Close"
             }
         }
+        attribute_list = <PortalList> {
+            width: Fill, height: 200
+            flow: Down
+
+            AttributeRow = <AttributeRow> {}
+            DetailRow = <DetailRow> {}
+        }
     }
 
     // Empty state
@@ -175,7 +355,7 @@ live_design! {
         show_bg: true
         draw_bg: { color: (ROW_BG) }
 
-        <Label> {
+        title_label = <Label> {
             width: Fit, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
@@ -183,7 +363,7 @@ live_design! {
             }
             text: "No traces found"
         }
-        <Label> {
+        hint_label = <Label> {
             width: Fit, height: Fit
             margin: { top: 8 }
             draw_text: {
@@ -202,7 +382,7 @@ live_design! {
         show_bg: true
         draw_bg: { color: (ROW_BG) }
 
-        <Label> {
+        loading_label = <Label> {
             width: Fit, height: Fit
             draw_text: {
                 color: (TEXT_SECONDARY),
@@ -239,24 +419,104 @@ live_design! {
         }
     }
 
-    pub TracesPanel = {{TracesPanel}} {
+    // Shown while traces come from local storage instead of SigNoz
+    StaleBanner = <View> {
         width: Fill, height: Fit
+        visible: false
+        show_bg: true
+        draw_bg: { color: #fef3c7 }
+        padding: { left: 16, right: 16, top: 6, bottom: 6 }
+
+        stale_label = <Label> {
+            width: Fill, height: Fit
+            draw_text: {
+                color: #x92400e,
+                text_style: { font_size: 11.0 }
+            }
+            text: ""
+        }
+    }
+
+    pub TracesPanel = {{TracesPanel}} {
+        width: Fill, height: Fill
         flow: Down
 
+        stale_banner = <StaleBanner> {}
+
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { x: 1.0, y: 0.5 }
+            padding: { top: 6, bottom: 6 }
+            spacing: 8
+
+            columns_button = <Button> {
+                width: Fit, height: 28
+                draw_text: { text_style: { font_size: 11.0 } }
+                text: "Columns"
+            }
+            live_button = <Button> {
+                width: Fit, height: 28
+                draw_text: { text_style: { font_size: 11.0 } }
+                text: "Live: off"
+            }
+            group_button = <Button> {
+                width: Fit, height: 28
+                draw_text: { text_style: { font_size: 11.0 } }
+                text: "Group by trace"
+            }
+        }
+
+        column_chooser = <ColumnChooser> {}
+
+        filter_bar = <FilterBar> {}
+
+        // Durations shown, log-scaled from the shortest to the longest
+        <View> {
+            width: Fill, height: Fit
+            flow: Right
+            align: { y: 0.5 }
+            padding: { left: 16, right: 16, bottom: 6 }
+            spacing: 12
+
+            latency_histogram = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 14.0 }
+                }
+                text: ""
+            }
+            latency_summary = <Label> {
+                width: Fit, height: Fit
+                draw_text: {
+                    color: (TEXT_SECONDARY),
+                    text_style: { font_size: 11.0 }
+                }
+                text: ""
+            }
+        }
+
         // Header
-        <TraceTableHeader> {}
+        table_header = <TraceTableHeader> {}
 
         // Data rows via PortalList
         trace_list = <PortalList> {
-            width: Fill, height: 300
+            width: Fill, height: Fill
             flow: Down
 
             TraceRow = <TraceRow> {}
             TraceRowAlt = <TraceRowAlt> {}
+            TraceRowFresh = <TraceRowFresh> {}
+            TraceGroupRow = <TraceGroupRow> {}
             TracesEmptyState = <TracesEmptyState> {}
             TracesLoadingState = <TracesLoadingState> {}
             TracesErrorState = <TracesErrorState> {}
         }
+
+        span_inspector = <SpanInspector> {}
+
+        time_tooltip = <Tooltip> {}
     }
 }
 
@@ -269,6 +529,67 @@ pub enum TracesLoadingState {
     Error,
 }
 
+/// Spans kept while live tailing; the oldest are dropped past this.
+const LIVE_TAIL_MAX_SPANS: usize = 500;
+
+/// Buckets in the latency histogram above the table.
+const HISTOGRAM_BUCKETS: usize = 32;
+
+/// Characters of a trace or span ID that fit its cell; longer IDs keep
+/// both ends (see [`text::truncate_middle`]).
+const ID_CELL_CHARS: usize = 16;
+
+// TEXT_PRIMARY, STATUS_OK and STATUS_ERROR, for cells coloured per row
+const CELL_TEXT_COLOR: Vec4 = Vec4 {
+    x: 0.118,
+    y: 0.161,
+    z: 0.231,
+    w: 1.0,
+};
+const CELL_OK_COLOR: Vec4 = Vec4 {
+    x: 0.133,
+    y: 0.773,
+    z: 0.369,
+    w: 1.0,
+};
+const CELL_SLOW_COLOR: Vec4 = Vec4 {
+    x: 0.851,
+    y: 0.467,
+    z: 0.024,
+    w: 1.0,
+};
+const CELL_ERROR_COLOR: Vec4 = Vec4 {
+    x: 0.937,
+    y: 0.267,
+    z: 0.267,
+    w: 1.0,
+};
+
+/// Actions emitted by the TracesPanel
+#[derive(Clone, Debug, DefaultNone)]
+pub enum TracesPanelAction {
+    None,
+    /// The column layout was changed in the chooser; its keys, for settings
+    ColumnsChanged(Vec<String>),
+    /// The filter bar was applied, or a saved query picked
+    FilterChanged(TraceFilter),
+    /// The filter bar was saved under a name, for settings
+    QuerySaved(SavedTraceQuery),
+    /// The filter bar could not be parsed; the reason
+    FilterInvalid(String),
+    /// Show logs whose attribute `.0` is `.1`, from the span inspector
+    FilterLogs(String, String),
+}
+
+/// A row of the traces table: a span, or in grouped mode a whole trace
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TraceTableRow {
+    /// Index into the spans; `nested` under an expanded trace
+    Span { index: usize, nested: bool },
+    /// Index into the trace groups
+    Trace(usize),
+}
+
 #[derive(Live, LiveHook, Widget)]
 pub struct TracesPanel {
     #[deref]
@@ -276,6 +597,36 @@ pub struct TracesPanel {
     #[rust]
     spans: Vec<Span>,
     #[rust]
+    groups: Vec<TraceSummary>,
+    #[rust]
+    grouped: bool,
+    /// Trace ids whose spans are shown under their group row
+    #[rust]
+    expanded: HashSet<String>,
+    /// Whether the app should poll for new spans and prepend them
+    #[rust]
+    live: bool,
+    /// `(trace_id, span_id)` of the spans the last live poll added
+    #[rust]
+    fresh: HashSet<(String, String)>,
+    #[rust]
+    columns: ColumnLayout,
+    /// The filter the spans shown were fetched with
+    #[rust]
+    filter: TraceFilter,
+    /// Filters in the saved dropdown, after its placeholder
+    #[rust]
+    saved_queries: Vec<SavedTraceQuery>,
+    /// The span shown in the inspector
+    #[rust]
+    inspected: Option<Span>,
+    /// Whether the header shows the current columns
+    #[rust]
+    header_applied: bool,
+    /// Durations of the rows shown: spans, or traces when grouped
+    #[rust]
+    latency: LatencyStats,
+    #[rust]
     loading_state: TracesLoadingState,
     #[rust]
     error_message: String,
@@ -284,23 +635,247 @@ pub struct TracesPanel {
 impl Widget for TracesPanel {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
         self.view.handle_event(cx, event, scope);
+        self.widget_match_event(cx, event, scope);
     }
 
     fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
+        if !self.header_applied {
+            self.apply_header(cx);
+        }
+        let column_list_uid = self.view.portal_list(ids!(column_list)).widget_uid();
+        let attribute_list_uid = self.view.portal_list(ids!(attribute_list)).widget_uid();
         while let Some(item) = self.view.draw_walk(cx, scope, walk).step() {
+            let uid = item.widget_uid();
             if let Some(mut list) = item.as_portal_list().borrow_mut() {
-                self.draw_rows(cx, &mut list);
+                if uid == column_list_uid {
+                    self.draw_column_options(cx, &mut list);
+                } else if uid == attribute_list_uid {
+                    self.draw_attributes(cx, &mut list);
+                } else {
+                    self.draw_rows(cx, &mut list);
+                }
             }
         }
         DrawStep::done()
     }
 }
 
+impl WidgetMatchEvent for TracesPanel {
+    fn handle_actions(&mut self, cx: &mut Cx, actions: &Actions, scope: &mut Scope) {
+        if self.view.button(ids!(columns_button)).clicked(actions) {
+            let chooser = self.view.view(ids!(column_chooser));
+            chooser.set_visible(cx, !chooser.visible());
+            self.redraw(cx);
+        }
+
+        let choices = self.columns.choices();
+        let mut layout = self.columns.clone();
+        let column_list = self.view.portal_list(ids!(column_list));
+        for (item_id, item) in column_list.items_with_actions(actions) {
+            let Some((column, _)) = choices.get(item_id) else {
+                continue;
+            };
+            if item.button(ids!(show_button)).clicked(actions) {
+                layout.toggle(column);
+            }
+            if item.button(ids!(left_button)).clicked(actions) {
+                layout.move_column(column, -1);
+            }
+            if item.button(ids!(right_button)).clicked(actions) {
+                layout.move_column(column, 1);
+            }
+        }
+        if self
+            .view
+            .button(ids!(add_attribute_button))
+            .clicked(actions)
+        {
+            let input = self.view.text_input(ids!(attribute_input));
+            if let Some(column) = TraceColumn::parse(&format!("attr:{}", input.text())) {
+                if !layout.is_shown(&column) {
+                    layout.toggle(&column);
+                }
+                input.set_text(cx, "");
+            }
+        }
+        if layout != self.columns {
+            self.set_columns(cx, layout);
+            cx.widget_action(
+                self.widget_uid(),
+                &scope.path,
+                TracesPanelAction::ColumnsChanged(self.columns.keys()),
+            );
+        }
+
+        if let Some(action) = self.filter_action(cx, actions) {
+            cx.widget_action(self.widget_uid(), &scope.path, action);
+        }
+
+        if self.view.button(ids!(group_button)).clicked(actions) {
+            self.set_grouped(cx, !self.grouped);
+        }
+        if self.view.button(ids!(live_button)).clicked(actions) {
+            self.set_live(cx, !self.live);
+        }
+
+        let rows = self.rows();
+        let trace_list = self.view.portal_list(ids!(trace_list));
+        let tooltip = self.view.tooltip(ids!(time_tooltip));
+        let time_shown = self.columns.is_shown(&TraceColumn::Time);
+        for (item_id, item) in trace_list.items_with_actions(actions) {
+            // Hovering a row shows its exact start time while the Time
+            // column is shown
+            if let Some(hover) = item.as_view().finger_hover_in(actions) {
+                let start_ms = match rows.get(item_id) {
+                    Some(TraceTableRow::Trace(group)) => Some(self.groups[*group].start_time_ms),
+                    Some(TraceTableRow::Span { index, .. }) => {
+                        Some(self.spans[*index].start_time_ms)
+                    }
+                    None => None,
+                };
+                if let Some(start_ms) = start_ms.filter(|_| time_shown) {
+                    tooltip.show_with_options(cx, hover.abs, &datetime::format_rfc3339(start_ms));
+                }
+            }
+            if item.as_view().finger_hover_out(actions).is_some() {
+                tooltip.hide(cx);
+            }
+            match rows.get(item_id) {
+                Some(TraceTableRow::Trace(group)) => {
+                    if item.button(ids!(expand_button)).clicked(actions) {
+                        let trace_id = self.groups[*group].trace_id.clone();
+                        let expand = !self.expanded.contains(&trace_id);
+                        self.set_expanded(cx, &trace_id, expand);
+                    }
+                }
+                Some(TraceTableRow::Span { index, .. }) => {
+                    if item.button(ids!(inspect_button)).clicked(actions) {
+                        self.inspect(cx, Some(*index));
+                    }
+                }
+                None => {}
+            }
+        }
+
+        if self
+            .view
+            .button(ids!(close_inspector_button))
+            .clicked(actions)
+        {
+            self.inspect(cx, None);
+        }
+        let attributes = self.inspected_attributes();
+        let attribute_list = self.view.portal_list(ids!(attribute_list));
+        for (item_id, item) in attribute_list.items_with_actions(actions) {
+            let Some((key, value)) = attributes.get(item_id) else {
+                continue;
+            };
+            if item.button(ids!(filter_traces_button)).clicked(actions) {
+                let filter = self.filter_by_attribute(cx, key, value);
+                cx.widget_action(
+                    self.widget_uid(),
+                    &scope.path,
+                    TracesPanelAction::FilterChanged(filter),
+                );
+            }
+            if item.button(ids!(filter_logs_button)).clicked(actions) {
+                cx.widget_action(
+                    self.widget_uid(),
+                    &scope.path,
+                    TracesPanelAction::FilterLogs(key.clone(), value.clone()),
+                );
+            }
+        }
+    }
+}
+
 impl TracesPanel {
+    /// Apply, save or recall a filter from the filter bar.
+    fn filter_action(&mut self, cx: &mut Cx, actions: &Actions) -> Option<TracesPanelAction> {
+        if let Some(index) = self.view.drop_down(ids!(saved_dropdown)).selected(actions) {
+            // Index 0 is the "Saved" placeholder label
+            let query = index
+                .checked_sub(1)
+                .and_then(|i| self.saved_queries.get(i))
+                .cloned()?;
+            self.set_filter(cx, query.filter.clone());
+            self.view
+                .text_input(ids!(save_name_input))
+                .set_text(cx, &query.name);
+            return Some(TracesPanelAction::FilterChanged(query.filter));
+        }
+
+        let apply = self.view.button(ids!(apply_filter_button)).clicked(actions);
+        let save = self.view.button(ids!(save_query_button)).clicked(actions);
+        if !apply && !save {
+            return None;
+        }
+        let filter = match self.filter_input() {
+            Ok(filter) => filter,
+            Err(e) => return Some(TracesPanelAction::FilterInvalid(e)),
+        };
+        if apply {
+            self.filter = filter.clone();
+            return Some(TracesPanelAction::FilterChanged(filter));
+        }
+        let name = self.view.text_input(ids!(save_name_input)).text();
+        let name = name.trim();
+        if name.is_empty() {
+            return Some(TracesPanelAction::FilterInvalid(
+                "Enter a name for the query".to_string(),
+            ));
+        }
+        Some(TracesPanelAction::QuerySaved(SavedTraceQuery {
+            name: name.to_string(),
+            filter,
+        }))
+    }
+
+    /// The filter typed into the filter bar
+    fn filter_input(&self) -> Result<TraceFilter, String> {
+        TraceFilter::parse(
+            &self.view.text_input(ids!(service_filter)).text(),
+            &self.view.text_input(ids!(operation_filter)).text(),
+            &self.view.text_input(ids!(range_filter)).text(),
+            &self.view.text_input(ids!(tags_filter)).text(),
+        )
+    }
+
+    fn set_filter(&mut self, cx: &mut Cx, filter: TraceFilter) {
+        let text = |value: &Option<String>| value.clone().unwrap_or_default();
+        self.view
+            .text_input(ids!(service_filter))
+            .set_text(cx, &text(&filter.service_name));
+        self.view
+            .text_input(ids!(operation_filter))
+            .set_text(cx, &text(&filter.operation_name));
+        self.view
+            .text_input(ids!(range_filter))
+            .set_text(cx, &filter.range_text());
+        self.view
+            .text_input(ids!(tags_filter))
+            .set_text(cx, &filter.tags_text());
+        self.filter = filter;
+    }
+
+    fn set_saved_queries(&mut self, cx: &mut Cx, saved: &[SavedTraceQuery]) {
+        let mut labels = vec![i18n::tr("traces.saved")];
+        labels.extend(saved.iter().map(|q| q.name.clone()));
+        let dropdown = self.view.drop_down(ids!(saved_dropdown));
+        dropdown.set_labels(cx, labels);
+        dropdown.set_selected_item(cx, 0);
+        self.saved_queries = saved.to_vec();
+    }
+
     pub fn set_spans(&mut self, cx: &mut Cx, spans: Vec<Span>) {
-        log!("[TracesPanel] set_spans: {} items", spans.len());
+        tracing::debug!(target: "TracesPanel", "set_spans: {} items", spans.len());
+        self.groups = group_by_trace(&spans);
+        self.expanded
+            .retain(|id| self.groups.iter().any(|g| g.trace_id == *id));
+        self.fresh.clear();
         self.spans = spans;
         self.loading_state = TracesLoadingState::Idle;
+        self.update_latency(cx);
         self.view.portal_list(ids!(trace_list)).redraw(cx);
         self.redraw(cx);
     }
@@ -318,6 +893,321 @@ impl TracesPanel {
         self.redraw(cx);
     }
 
+    /// Add `spans` below those shown, e.g. the rest of a large result
+    /// applied over several frames.
+    pub fn append_spans(&mut self, cx: &mut Cx, spans: Vec<Span>) {
+        self.spans.extend(spans);
+        self.groups = group_by_trace(&self.spans);
+        self.update_latency(cx);
+        self.view.portal_list(ids!(trace_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    /// Add `spans` from a live poll above those shown, skipping ones already
+    /// shown, and highlight them until the next poll.
+    pub fn prepend_spans(&mut self, cx: &mut Cx, mut spans: Vec<Span>) {
+        let shown: HashSet<(&str, &str)> = self
+            .spans
+            .iter()
+            .map(|s| (s.trace_id.as_str(), s.span_id.as_str()))
+            .collect();
+        spans.retain(|s| !shown.contains(&(s.trace_id.as_str(), s.span_id.as_str())));
+        spans.sort_by(|a, b| b.start_time_ms.cmp(&a.start_time_ms));
+
+        self.fresh = spans
+            .iter()
+            .map(|s| (s.trace_id.clone(), s.span_id.clone()))
+            .collect();
+        spans.append(&mut self.spans);
+        spans.truncate(LIVE_TAIL_MAX_SPANS);
+        self.groups = group_by_trace(&spans);
+        self.spans = spans;
+        self.loading_state = TracesLoadingState::Idle;
+        self.update_latency(cx);
+        self.view.portal_list(ids!(trace_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    /// Rank the durations shown and redraw the histogram above the table.
+    fn update_latency(&mut self, cx: &mut Cx) {
+        self.latency = if self.grouped {
+            LatencyStats::new(self.groups.iter().map(|g| g.duration_ms))
+        } else {
+            LatencyStats::new(self.spans.iter().map(|s| s.duration_ms))
+        };
+        let histogram = if self.latency.is_empty() {
+            String::new()
+        } else {
+            let counts = self.latency.histogram(HISTOGRAM_BUCKETS);
+            text::sparkline(counts.into_iter().map(|count| count as f64))
+        };
+        self.view
+            .label(ids!(latency_histogram))
+            .set_text(cx, &histogram);
+        self.view
+            .label(ids!(latency_summary))
+            .set_text(cx, &latency_summary(&self.latency));
+    }
+
+    /// Show the attributes, events and links of the span at `index` in the
+    /// inspector, or close it.
+    pub fn inspect(&mut self, cx: &mut Cx, index: Option<usize>) {
+        self.inspected = index.and_then(|i| self.spans.get(i)).cloned();
+        let title = self
+            .inspected
+            .as_ref()
+            .map(|span| format!("{} · {}", span.service_name, span.operation_name))
+            .unwrap_or_default();
+        self.view.label(ids!(inspector_title)).set_text(cx, &title);
+        self.view
+            .view(ids!(span_inspector))
+            .set_visible(cx, self.inspected.is_some());
+        self.view.portal_list(ids!(attribute_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    /// Attributes of the inspected span, by key
+    fn inspected_attributes(&self) -> Vec<(String, String)> {
+        let mut attributes: Vec<(String, String)> = self
+            .inspected
+            .iter()
+            .flat_map(|span| span.attributes.clone())
+            .collect();
+        attributes.sort();
+        attributes
+    }
+
+    /// Add `key = value` to the trace filter's tags, returning the filter to
+    /// query with.
+    pub fn filter_by_attribute(&mut self, cx: &mut Cx, key: &str, value: &str) -> TraceFilter {
+        let mut filter = self.filter.clone();
+        filter.tags.insert(key.to_string(), value.to_string());
+        self.set_filter(cx, filter.clone());
+        filter
+    }
+
+    fn draw_attributes(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        let attributes = self.inspected_attributes();
+        let details = self
+            .inspected
+            .as_ref()
+            .map(span_details)
+            .unwrap_or_default();
+        list.set_item_range(cx, 0, attributes.len() + details.len());
+        while let Some(item_id) = list.next_visible_item(cx) {
+            let Some((key, value)) = attributes.get(item_id) else {
+                let Some(detail) = details.get(item_id - attributes.len()) else {
+                    continue;
+                };
+                let (label, value) = match detail {
+                    SpanDetail::Event { offset_ms, text } => (
+                        i18n::tr_args(
+                            "traces.event_at",
+                            &[("offset", &format!("+{}", format_duration(*offset_ms)))],
+                        ),
+                        text,
+                    ),
+                    SpanDetail::Link { text } => (i18n::tr("traces.link"), text),
+                };
+                let item = list.item(cx, item_id, live_id!(DetailRow));
+                item.label(ids!(detail_label)).set_text(cx, &label);
+                item.label(ids!(detail_value)).set_text(cx, value);
+                item.draw_all(cx, &mut Scope::empty());
+                continue;
+            };
+            let item = list.item(cx, item_id, live_id!(AttributeRow));
+            item.label(ids!(attribute_key)).set_text(cx, key);
+            item.label(ids!(attribute_value)).set_text(cx, value);
+            item.button(ids!(filter_traces_button))
+                .set_text(cx, &i18n::tr("traces.filter_traces"));
+            item.button(ids!(filter_logs_button))
+                .set_text(cx, &i18n::tr("traces.filter_logs"));
+            item.draw_all(cx, &mut Scope::empty());
+        }
+    }
+
+    /// Start of the newest span shown, where a live poll picks up
+    pub fn newest_start_ms(&self) -> Option<u64> {
+        self.spans.iter().map(|s| s.start_time_ms).max()
+    }
+
+    pub fn set_live(&mut self, cx: &mut Cx, live: bool) {
+        self.live = live;
+        if !live {
+            self.fresh.clear();
+        }
+        self.show_toggles(cx);
+        self.view.portal_list(ids!(trace_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    /// Label the live and group buttons with what they are set to
+    fn show_toggles(&mut self, cx: &mut Cx) {
+        let live = if self.live {
+            "traces.live_on"
+        } else {
+            "traces.live_off"
+        };
+        let grouped = if self.grouped {
+            "traces.show_spans"
+        } else {
+            "traces.group"
+        };
+        self.view
+            .button(ids!(live_button))
+            .set_text(cx, &i18n::tr(live));
+        self.view
+            .button(ids!(group_button))
+            .set_text(cx, &i18n::tr(grouped));
+    }
+
+    /// Show the text set in `live_design` in the current language
+    pub fn localize(&mut self, cx: &mut Cx) {
+        i18n::localize(
+            cx,
+            &[
+                (ids!(columns_button), "traces.columns"),
+                (ids!(add_attribute_button), "traces.add_column"),
+                (ids!(apply_filter_button), "action.apply"),
+                (ids!(save_query_button), "action.save"),
+                (ids!(close_inspector_button), "action.close"),
+            ],
+            |path| self.view.widget(path),
+        );
+        let saved = self.saved_queries.clone();
+        self.set_saved_queries(cx, &saved);
+        self.show_toggles(cx);
+        self.view.portal_list(ids!(trace_list)).redraw(cx);
+        self.view.portal_list(ids!(column_list)).redraw(cx);
+        self.view.portal_list(ids!(attribute_list)).redraw(cx);
+    }
+
+    /// Show one row per trace instead of one per span
+    pub fn set_grouped(&mut self, cx: &mut Cx, grouped: bool) {
+        self.grouped = grouped;
+        self.header_applied = false;
+        self.update_latency(cx);
+        self.show_toggles(cx);
+        self.view.portal_list(ids!(trace_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    /// Show or hide the spans of `trace_id` under its row in grouped mode
+    pub fn set_expanded(&mut self, cx: &mut Cx, trace_id: &str, expanded: bool) {
+        if expanded {
+            self.expanded.insert(trace_id.to_string());
+        } else {
+            self.expanded.remove(trace_id);
+        }
+        self.view.portal_list(ids!(trace_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    /// Group by trace, expand `trace_id`, scroll to it and inspect its root
+    /// span. Whether the trace is among the spans shown.
+    pub fn focus_trace(&mut self, cx: &mut Cx, trace_id: &str) -> bool {
+        let in_trace = |s: &Span| s.trace_id == trace_id;
+        let Some(root) = self
+            .spans
+            .iter()
+            .position(|s| in_trace(s) && s.parent_span_id.as_deref().unwrap_or("").is_empty())
+            .or_else(|| self.spans.iter().position(in_trace))
+        else {
+            return false;
+        };
+        if !self.grouped {
+            self.set_grouped(cx, true);
+        }
+        self.set_expanded(cx, trace_id, true);
+        let row = self.rows().iter().position(
+            |row| matches!(row, TraceTableRow::Trace(group) if self.groups[*group].trace_id == trace_id),
+        );
+        if let Some(row) = row {
+            self.view
+                .portal_list(ids!(trace_list))
+                .set_first_id_and_scroll(row, 0.0);
+        }
+        self.inspect(cx, Some(root));
+        true
+    }
+
+    fn rows(&self) -> Vec<TraceTableRow> {
+        if !self.grouped {
+            return (0..self.spans.len())
+                .map(|index| TraceTableRow::Span {
+                    index,
+                    nested: false,
+                })
+                .collect();
+        }
+        let mut rows = Vec::new();
+        for (group, summary) in self.groups.iter().enumerate() {
+            rows.push(TraceTableRow::Trace(group));
+            if self.expanded.contains(&summary.trace_id) {
+                rows.extend(
+                    summary
+                        .span_indices
+                        .iter()
+                        .map(|&index| TraceTableRow::Span {
+                            index,
+                            nested: true,
+                        }),
+                );
+            }
+        }
+        rows
+    }
+
+    pub fn set_columns(&mut self, cx: &mut Cx, columns: ColumnLayout) {
+        self.columns = columns;
+        self.header_applied = false;
+        self.view.portal_list(ids!(trace_list)).redraw(cx);
+        self.view.portal_list(ids!(column_list)).redraw(cx);
+        self.redraw(cx);
+    }
+
+    fn apply_header(&mut self, cx: &mut Cx) {
+        let titles: Vec<String> = self
+            .columns
+            .columns()
+            .iter()
+            .map(|column| match column {
+                // Grouped rows count spans where span rows list events
+                TraceColumn::Events if self.grouped => "SPANS".to_string(),
+                column => column.title(),
+            })
+            .collect();
+        let header = self.view.view(ids!(table_header));
+        set_cells(cx, &header, self.columns.columns(), &titles, &[]);
+        self.header_applied = true;
+    }
+
+    fn draw_column_options(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
+        let choices = self.columns.choices();
+        list.set_item_range(cx, 0, choices.len());
+        while let Some(item_id) = list.next_visible_item(cx) {
+            let Some((column, shown)) = choices.get(item_id) else {
+                continue;
+            };
+            let item = list.item(cx, item_id, live_id!(ColumnOption));
+            item.label(ids!(column_label))
+                .set_text(cx, &column.to_string());
+            let show = if *shown {
+                "traces.column_hide"
+            } else {
+                "traces.column_show"
+            };
+            item.button(ids!(show_button)).set_text(cx, &i18n::tr(show));
+            item.button(ids!(left_button))
+                .set_text(cx, &i18n::tr("traces.column_left"));
+            item.button(ids!(right_button))
+                .set_text(cx, &i18n::tr("traces.column_right"));
+            item.button(ids!(left_button)).set_visible(cx, *shown);
+            item.button(ids!(right_button)).set_visible(cx, *shown);
+            item.draw_all(cx, &mut Scope::empty());
+        }
+    }
+
     fn draw_rows(&mut self, cx: &mut Cx2d, list: &mut RefMut<PortalList>) {
         // Loading state
         if self.loading_state == TracesLoadingState::Loading {
@@ -325,6 +1215,8 @@ impl TracesPanel {
             while let Some(item_id) = list.next_visible_item(cx) {
                 if item_id == 0 {
                     let item = list.item(cx, item_id, live_id!(TracesLoadingState));
+                    item.label(ids!(loading_label))
+                        .set_text(cx, &i18n::tr("traces.loading"));
                     item.draw_all(cx, &mut Scope::empty());
                 }
             }
@@ -337,6 +1229,8 @@ impl TracesPanel {
             while let Some(item_id) = list.next_visible_item(cx) {
                 if item_id == 0 {
                     let item = list.item(cx, item_id, live_id!(TracesErrorState));
+                    item.label(ids!(error_title))
+                        .set_text(cx, &i18n::tr("traces.error_title"));
                     item.label(ids!(error_detail))
                         .set_text(cx, &self.error_message);
                     item.draw_all(cx, &mut Scope::empty());
@@ -351,6 +1245,10 @@ impl TracesPanel {
             while let Some(item_id) = list.next_visible_item(cx) {
                 if item_id == 0 {
                     let item = list.item(cx, item_id, live_id!(TracesEmptyState));
+                    item.label(ids!(title_label))
+                        .set_text(cx, &i18n::tr("traces.empty.title"));
+                    item.label(ids!(hint_label))
+                        .set_text(cx, &i18n::tr("traces.empty.hint"));
                     item.draw_all(cx, &mut Scope::empty());
                 }
             }
@@ -358,30 +1256,56 @@ impl TracesPanel {
         }
 
         // Data rows
-        list.set_item_range(cx, 0, self.spans.len());
+        let rows = self.rows();
+        list.set_item_range(cx, 0, rows.len());
 
         while let Some(item_id) = list.next_visible_item(cx) {
-            if item_id < self.spans.len() {
-                let span = &self.spans[item_id];
+            let Some(row) = rows.get(item_id) else {
+                continue;
+            };
+            if let TraceTableRow::Trace(group) = *row {
+                let summary = &self.groups[group];
+                let item = list.item(cx, item_id, live_id!(TraceGroupRow));
+
+                let expanded = self.expanded.contains(&summary.trace_id);
+                item.button(ids!(expand_button))
+                    .set_text(cx, if expanded { "-" } else { "+" });
+                let columns = self.columns.columns();
+                let cells: Vec<String> = columns.iter().map(|c| trace_cell(c, summary)).collect();
+                let colors: Vec<Vec4> = columns
+                    .iter()
+                    .map(|c| trace_color(c, summary, &self.latency))
+                    .collect();
+                set_cells(cx, &item, columns, &cells, &colors);
+
+                item.draw_all(cx, &mut Scope::empty());
+            } else if let TraceTableRow::Span { index, nested } = *row {
+                let span = &self.spans[index];
 
-                let template = if item_id % 2 == 0 {
+                let fresh = self
+                    .fresh
+                    .contains(&(span.trace_id.clone(), span.span_id.clone()));
+                let template = if fresh {
+                    live_id!(TraceRowFresh)
+                } else if item_id % 2 == 0 {
                     live_id!(TraceRow)
                 } else {
                     live_id!(TraceRowAlt)
                 };
 
                 let item = list.item(cx, item_id, template);
+                item.button(ids!(inspect_button))
+                    .set_text(cx, &i18n::tr("traces.inspect"));
 
-                item.label(ids!(service_label))
-                    .set_text(cx, &span.service_name);
-                item.label(ids!(operation_label))
-                    .set_text(cx, &span.operation_name);
-                item.label(ids!(duration_label))
-                    .set_text(cx, &format_duration(span.duration_ms));
-                item.label(ids!(status_label))
-                    .set_text(cx, &format_status(span.has_error, span.status_code));
-                item.label(ids!(time_label))
-                    .set_text(cx, &format_time(span.start_time_ms));
+                item.label(ids!(lead_label))
+                    .set_text(cx, if nested { "›" } else { "" });
+                let columns = self.columns.columns();
+                let cells: Vec<String> = columns.iter().map(|c| span_cell(c, span)).collect();
+                let colors: Vec<Vec4> = columns
+                    .iter()
+                    .map(|c| span_color(c, span, &self.latency))
+                    .collect();
+                set_cells(cx, &item, columns, &cells, &colors);
 
                 item.draw_all(cx, &mut Scope::empty());
             }
@@ -394,6 +1318,12 @@ impl TracesPanel {
 // ---------------------------------------------------------------------------
 
 impl TracesPanelRef {
+    pub fn localize(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.localize(cx);
+        }
+    }
+
     pub fn set_spans(&self, cx: &mut Cx, spans: Vec<Span>) {
         if let Some(mut inner) = self.borrow_mut() {
             inner.set_spans(cx, spans);
@@ -411,12 +1341,319 @@ impl TracesPanelRef {
             inner.set_error(cx, message);
         }
     }
+
+    /// Mark the spans shown as stored data, e.g. `Offline · stale since …`;
+    /// `None` hides the banner once live data is back.
+    pub fn set_stale(&self, cx: &mut Cx, banner: Option<&str>) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .label(ids!(stale_label))
+                .set_text(cx, banner.unwrap_or(""));
+            inner
+                .view
+                .view(ids!(stale_banner))
+                .set_visible(cx, banner.is_some());
+        }
+    }
+
+    /// Add spans from a live poll above those shown
+    pub fn prepend_spans(&self, cx: &mut Cx, spans: Vec<Span>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.prepend_spans(cx, spans);
+        }
+    }
+
+    /// Add spans below those shown
+    pub fn append_spans(&self, cx: &mut Cx, spans: Vec<Span>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.append_spans(cx, spans);
+        }
+    }
+
+    /// Start of the newest span shown
+    pub fn newest_start_ms(&self) -> Option<u64> {
+        self.borrow()?.newest_start_ms()
+    }
+
+    /// Whether live tailing is switched on
+    pub fn is_live(&self) -> bool {
+        self.borrow().is_some_and(|inner| inner.live)
+    }
+
+    pub fn set_live(&self, cx: &mut Cx, live: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_live(cx, live);
+        }
+    }
+
+    /// Show the columns of `keys` (see [`ColumnLayout::from_keys`])
+    pub fn set_columns(&self, cx: &mut Cx, keys: &[String]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_columns(cx, ColumnLayout::from_keys(keys));
+        }
+    }
+
+    /// Get the columns shown
+    pub fn columns(&self) -> ColumnLayout {
+        self.borrow()
+            .map(|inner| inner.columns.clone())
+            .unwrap_or_default()
+    }
+
+    /// Check if the columns were changed in the chooser, returns their keys if so
+    pub fn columns_changed(&self, actions: &Actions) -> Option<Vec<String>> {
+        match actions.find_widget_action(self.widget_uid())?.cast() {
+            TracesPanelAction::ColumnsChanged(keys) => Some(keys),
+            _ => None,
+        }
+    }
+
+    /// The filter the spans shown were fetched with
+    pub fn filter(&self) -> TraceFilter {
+        self.borrow()
+            .map(|inner| inner.filter.clone())
+            .unwrap_or_default()
+    }
+
+    /// Show `filter` in the filter bar, e.g. when recalled by the agent
+    pub fn set_filter(&self, cx: &mut Cx, filter: TraceFilter) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_filter(cx, filter);
+        }
+    }
+
+    /// Fill the saved dropdown
+    pub fn set_saved_queries(&self, cx: &mut Cx, saved: &[SavedTraceQuery]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_saved_queries(cx, saved);
+        }
+    }
+
+    /// Check if a filter was applied or recalled, returns it if so
+    pub fn filter_changed(&self, actions: &Actions) -> Option<TraceFilter> {
+        match actions.find_widget_action(self.widget_uid())?.cast() {
+            TracesPanelAction::FilterChanged(filter) => Some(filter),
+            _ => None,
+        }
+    }
+
+    /// Check if the filter was saved under a name, returns the query if so
+    pub fn query_saved(&self, actions: &Actions) -> Option<SavedTraceQuery> {
+        match actions.find_widget_action(self.widget_uid())?.cast() {
+            TracesPanelAction::QuerySaved(query) => Some(query),
+            _ => None,
+        }
+    }
+
+    /// Show the attributes of the span at `index` in the inspector, or
+    /// close it
+    pub fn inspect(&self, cx: &mut Cx, index: Option<usize>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.inspect(cx, index);
+        }
+    }
+
+    /// Attributes of the inspected span, by key
+    pub fn inspected_attributes(&self) -> Vec<(String, String)> {
+        self.borrow()
+            .map(|inner| inner.inspected_attributes())
+            .unwrap_or_default()
+    }
+
+    /// Add `key = value` to the trace filter, as the inspector's "Filter
+    /// traces" does
+    pub fn filter_by_attribute(&self, cx: &mut Cx, key: &str, value: &str) -> TraceFilter {
+        self.borrow_mut()
+            .map(|mut inner| inner.filter_by_attribute(cx, key, value))
+            .unwrap_or_default()
+    }
+
+    /// Check if "Filter logs" was clicked in the inspector, returns the
+    /// attribute key and value if so
+    pub fn filter_logs_clicked(&self, actions: &Actions) -> Option<(String, String)> {
+        match actions.find_widget_action(self.widget_uid())?.cast() {
+            TracesPanelAction::FilterLogs(key, value) => Some((key, value)),
+            _ => None,
+        }
+    }
+
+    /// Check if the filter bar could not be parsed, returns why if so
+    pub fn filter_invalid(&self, actions: &Actions) -> Option<String> {
+        match actions.find_widget_action(self.widget_uid())?.cast() {
+            TracesPanelAction::FilterInvalid(reason) => Some(reason),
+            _ => None,
+        }
+    }
+
+    /// Show one row per trace instead of one per span
+    pub fn set_grouped(&self, cx: &mut Cx, grouped: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_grouped(cx, grouped);
+        }
+    }
+
+    /// Show `trace_id` expanded and inspected; false if it is not shown
+    pub fn focus_trace(&self, cx: &mut Cx, trace_id: &str) -> bool {
+        self.borrow_mut()
+            .is_some_and(|mut inner| inner.focus_trace(cx, trace_id))
+    }
+
+    /// Show or hide the spans of `trace_id` under its row in grouped mode
+    pub fn set_expanded(&self, cx: &mut Cx, trace_id: &str, expanded: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_expanded(cx, trace_id, expanded);
+        }
+    }
+
+    /// Get the traces the spans shown belong to
+    pub fn trace_groups(&self) -> Vec<TraceSummary> {
+        self.borrow()
+            .map(|inner| inner.groups.clone())
+            .unwrap_or_default()
+    }
+
+    /// Number of table rows, counting expanded spans in grouped mode
+    pub fn row_count(&self) -> usize {
+        self.borrow()
+            .map(|inner| inner.rows().len())
+            .unwrap_or_default()
+    }
+
+    /// Get a copy of the spans shown
+    pub fn spans(&self) -> Vec<Span> {
+        self.borrow()
+            .map(|inner| inner.spans.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the loading state and the error message, if any
+    pub fn loading_state(&self) -> (TracesLoadingState, String) {
+        self.borrow()
+            .map(|inner| (inner.loading_state, inner.error_message.clone()))
+            .unwrap_or_default()
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Helper functions
 // ---------------------------------------------------------------------------
 
+fn cell_ids() -> [LiveId; MAX_COLUMNS] {
+    [
+        live_id!(cell_0),
+        live_id!(cell_1),
+        live_id!(cell_2),
+        live_id!(cell_3),
+        live_id!(cell_4),
+        live_id!(cell_5),
+        live_id!(cell_6),
+        live_id!(cell_7),
+        live_id!(cell_8),
+        live_id!(cell_9),
+    ]
+}
+
+/// Fill the cell slots of `row` with `texts` in `colors`, sized for
+/// `columns`; without colours the template's are kept. Spare slots are hidden.
+fn set_cells(
+    cx: &mut Cx,
+    row: &WidgetRef,
+    columns: &[TraceColumn],
+    texts: &[String],
+    colors: &[Vec4],
+) {
+    for (i, cell) in cell_ids().into_iter().enumerate() {
+        let label = row.label(&[&[cell]]);
+        let Some(column) = columns.get(i) else {
+            label.set_visible(cx, false);
+            continue;
+        };
+        match column.width() {
+            Some(width) => label.apply_over(cx, live! { width: (width) }),
+            None => label.apply_over(cx, live! { width: Fill }),
+        }
+        if let Some(color) = colors.get(i).copied() {
+            label.apply_over(cx, live! { draw_text: { color: (color) } });
+        }
+        label.set_text(cx, texts.get(i).map_or("", |t| t.as_str()));
+        label.set_visible(cx, true);
+    }
+}
+
+fn span_cell(column: &TraceColumn, span: &Span) -> String {
+    match column {
+        TraceColumn::Service => span.service_name.clone(),
+        TraceColumn::Operation => span.operation_name.clone(),
+        TraceColumn::Duration => format_duration(span.duration_ms),
+        TraceColumn::Status => format_status(span.has_error, span.status_code),
+        TraceColumn::Events => format_events(span),
+        TraceColumn::Time => format_time(span.start_time_ms),
+        TraceColumn::TraceId => text::truncate_middle(&span.trace_id, ID_CELL_CHARS),
+        TraceColumn::SpanId => text::truncate_middle(&span.span_id, ID_CELL_CHARS),
+        TraceColumn::SpanKind => span.attributes.get("spanKind").cloned().unwrap_or_default(),
+        TraceColumn::Attribute(key) => span.attributes.get(key).cloned().unwrap_or_default(),
+    }
+}
+
+/// Colour for a span's cell: failures and exceptions in red, and the
+/// duration by how it ranks among those shown.
+fn span_color(column: &TraceColumn, span: &Span, latency: &LatencyStats) -> Vec4 {
+    match column {
+        TraceColumn::Status if span.has_error => CELL_ERROR_COLOR,
+        TraceColumn::Events if span.exceptions().next().is_some() => CELL_ERROR_COLOR,
+        TraceColumn::Duration => heat_color(latency.heat(span.duration_ms)),
+        _ => CELL_TEXT_COLOR,
+    }
+}
+
+fn trace_color(column: &TraceColumn, summary: &TraceSummary, latency: &LatencyStats) -> Vec4 {
+    match column {
+        TraceColumn::Status if summary.error_count > 0 => CELL_ERROR_COLOR,
+        TraceColumn::Duration => heat_color(latency.heat(summary.duration_ms)),
+        _ => CELL_TEXT_COLOR,
+    }
+}
+
+fn heat_color(heat: Option<DurationHeat>) -> Vec4 {
+    match heat {
+        None => CELL_TEXT_COLOR,
+        Some(DurationHeat::Normal) => CELL_OK_COLOR,
+        Some(DurationHeat::Slow) => CELL_SLOW_COLOR,
+        Some(DurationHeat::Outlier) => CELL_ERROR_COLOR,
+    }
+}
+
+/// e.g. `p50 12ms · p95 340ms · max 1.2s`
+fn latency_summary(latency: &LatencyStats) -> String {
+    let (Some(p50), Some(p95), Some(max)) = (
+        latency.percentile(50),
+        latency.percentile(95),
+        latency.max(),
+    ) else {
+        return String::new();
+    };
+    format!(
+        "p50 {} · p95 {} · max {}",
+        format_duration(p50),
+        format_duration(p95),
+        format_duration(max)
+    )
+}
+
+fn trace_cell(column: &TraceColumn, summary: &TraceSummary) -> String {
+    match column {
+        TraceColumn::Service => summary.root_service.clone(),
+        TraceColumn::Operation => summary.root_operation.clone(),
+        TraceColumn::Duration => format_duration(summary.duration_ms),
+        TraceColumn::Status => format_status(summary.error_count > 0, 1),
+        TraceColumn::Events => summary.describe_counts(),
+        TraceColumn::Time => format_time(summary.start_time_ms),
+        TraceColumn::TraceId => text::truncate_middle(&summary.trace_id, ID_CELL_CHARS),
+        TraceColumn::SpanId | TraceColumn::SpanKind | TraceColumn::Attribute(_) => String::new(),
+    }
+}
+
 fn format_duration(ms: u64) -> String {
     if ms < 1000 {
         format!("{}ms", ms)
@@ -437,27 +1674,79 @@ fn format_status(has_error: bool, status_code: i32) -> String {
     }
 }
 
+/// Recorded exceptions, which otherwise go unnoticed, or counts of events
+/// and links, e.g. `! IOError: device busy (+1)` or `2 events · 1 link`.
+fn format_events(span: &Span) -> String {
+    let mut exceptions = span.exceptions();
+    if let Some(first) = exceptions.next() {
+        let more = exceptions.count();
+        let text = format!("! {}", first.describe());
+        return if more == 0 {
+            text
+        } else {
+            format!("{} (+{})", text, more)
+        };
+    }
+    let mut parts = Vec::new();
+    for (count, one, many) in [
+        (span.events.len(), "traces.one_event", "traces.events"),
+        (span.links.len(), "traces.one_link", "traces.links"),
+    ] {
+        match count {
+            0 => {}
+            1 => parts.push(i18n::tr(one)),
+            n => parts.push(i18n::tr_args(many, &[("count", &n.to_string())])),
+        }
+    }
+    parts.join(" · ")
+}
+
+/// An inspector row after the attributes.
+#[derive(Debug, Clone, PartialEq)]
+enum SpanDetail {
+    /// An event, `offset_ms` after the span started
+    Event { offset_ms: u64, text: String },
+    /// A span this one links to
+    Link { text: String },
+}
+
+/// The span's events, in time order, then its links, e.g. an event
+/// `exception (exception.message=device busy)` and a link `t2 / s2`.
+fn span_details(span: &Span) -> Vec<SpanDetail> {
+    let mut events: Vec<&SpanEvent> = span.events.iter().collect();
+    events.sort_by_key(|event| event.timestamp_ms);
+    let events = events.into_iter().map(|event| SpanDetail::Event {
+        offset_ms: event.timestamp_ms.saturating_sub(span.start_time_ms),
+        text: with_attributes(&event.name, &event.attributes),
+    });
+    let links = span.links.iter().map(|link| SpanDetail::Link {
+        text: with_attributes(
+            &format!("{} / {}", link.trace_id, link.span_id),
+            &link.attributes,
+        ),
+    });
+    events.chain(links).collect()
+}
+
+/// `text` followed by `attributes` sorted by key, if there are any.
+fn with_attributes(text: &str, attributes: &HashMap<String, String>) -> String {
+    if attributes.is_empty() {
+        return text.to_string();
+    }
+    let mut pairs: Vec<String> = attributes
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    pairs.sort();
+    format!("{} ({})", text, pairs.join(", "))
+}
+
 fn format_time(timestamp_ms: u64) -> String {
     let now_ms = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
         .as_millis() as u64;
-
-    if timestamp_ms > now_ms {
-        return "just now".to_string();
-    }
-
-    let diff_secs = (now_ms - timestamp_ms) / 1000;
-
-    if diff_secs < 60 {
-        format!("{}s ago", diff_secs)
-    } else if diff_secs < 3600 {
-        format!("{}m ago", diff_secs / 60)
-    } else if diff_secs < 86400 {
-        format!("{}h ago", diff_secs / 3600)
-    } else {
-        format!("{}d ago", diff_secs / 86400)
-    }
+    datetime::format_timestamp(timestamp_ms, now_ms)
 }
 
 // ---------------------------------------------------------------------------
@@ -467,6 +1756,7 @@ fn format_time(timestamp_ms: u64) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::otlp::types::SpanLink;
 
     #[test]
     fn test_format_duration_ms() {
@@ -495,6 +1785,152 @@ mod tests {
         assert_eq!(format_status(false, 1), "OK");
     }
 
+    #[test]
+    fn test_format_events() {
+        let event = |name: &str, message: Option<&str>| SpanEvent {
+            name: name.to_string(),
+            timestamp_ms: 0,
+            attributes: message
+                .map(|m| HashMap::from([("exception.message".to_string(), m.to_string())]))
+                .unwrap_or_default(),
+        };
+        let mut span = Span {
+            trace_id: "t".to_string(),
+            span_id: "s".to_string(),
+            parent_span_id: None,
+            service_name: "camera".to_string(),
+            operation_name: "capture".to_string(),
+            start_time_ms: 0,
+            duration_ms: 1,
+            status_code: 0,
+            has_error: false,
+            attributes: HashMap::new(),
+            events: Vec::new(),
+            links: Vec::new(),
+        };
+        assert_eq!(format_events(&span), "");
+
+        span.events = vec![event("frame_dropped", None), event("retry", None)];
+        span.links = vec![SpanLink {
+            trace_id: "t2".to_string(),
+            span_id: "s2".to_string(),
+            attributes: HashMap::new(),
+        }];
+        assert_eq!(format_events(&span), "2 events · 1 link");
+
+        span.events.push(event("exception", Some("device busy")));
+        assert_eq!(format_events(&span), "! device busy");
+        span.events.push(event("exception", None));
+        assert_eq!(format_events(&span), "! device busy (+1)");
+    }
+
+    #[test]
+    fn test_span_details() {
+        let mut span = Span {
+            trace_id: "t".to_string(),
+            span_id: "s".to_string(),
+            parent_span_id: None,
+            service_name: "camera".to_string(),
+            operation_name: "capture".to_string(),
+            start_time_ms: 1_000,
+            duration_ms: 50,
+            status_code: 0,
+            has_error: false,
+            attributes: HashMap::new(),
+            events: Vec::new(),
+            links: Vec::new(),
+        };
+        assert!(span_details(&span).is_empty());
+
+        span.events = vec![
+            SpanEvent {
+                name: "exception".to_string(),
+                timestamp_ms: 1_040,
+                attributes: HashMap::from([
+                    ("exception.type".to_string(), "IOError".to_string()),
+                    ("exception.message".to_string(), "device busy".to_string()),
+                ]),
+            },
+            SpanEvent {
+                name: "frame_dropped".to_string(),
+                timestamp_ms: 1_012,
+                attributes: HashMap::new(),
+            },
+        ];
+        span.links = vec![SpanLink {
+            trace_id: "t2".to_string(),
+            span_id: "s2".to_string(),
+            attributes: HashMap::from([("reason".to_string(), "retry".to_string())]),
+        }];
+        assert_eq!(
+            span_details(&span),
+            [
+                SpanDetail::Event {
+                    offset_ms: 12,
+                    text: "frame_dropped".to_string(),
+                },
+                SpanDetail::Event {
+                    offset_ms: 40,
+                    text: "exception (exception.message=device busy, exception.type=IOError)"
+                        .to_string(),
+                },
+                SpanDetail::Link {
+                    text: "t2 / s2 (reason=retry)".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_span_cells() {
+        let span = Span {
+            trace_id: "t".to_string(),
+            span_id: "s".to_string(),
+            parent_span_id: None,
+            service_name: "camera".to_string(),
+            operation_name: "capture".to_string(),
+            start_time_ms: 0,
+            duration_ms: 1500,
+            status_code: 2,
+            has_error: true,
+            attributes: HashMap::from([
+                ("spanKind".to_string(), "Internal".to_string()),
+                ("node.id".to_string(), "cam-0".to_string()),
+            ]),
+            events: Vec::new(),
+            links: Vec::new(),
+        };
+        let attribute = TraceColumn::Attribute("node.id".to_string());
+        assert_eq!(span_cell(&TraceColumn::Duration, &span), "1.5s");
+        assert_eq!(span_cell(&TraceColumn::SpanKind, &span), "Internal");
+        assert_eq!(span_cell(&attribute, &span), "cam-0");
+        let long_id = Span {
+            trace_id: "0123456789abcdef0123456789abcdef".to_string(),
+            ..span.clone()
+        };
+        assert_eq!(
+            span_cell(&TraceColumn::TraceId, &long_id),
+            "01234567...89abcdef"
+        );
+        assert_eq!(
+            span_cell(&TraceColumn::Attribute("missing".to_string()), &span),
+            ""
+        );
+        let latency = LatencyStats::new([1500, 1500, 2000, 3000, 4000, 9000]);
+        assert_eq!(
+            span_color(&TraceColumn::Status, &span, &latency),
+            CELL_ERROR_COLOR
+        );
+        assert_eq!(
+            span_color(&TraceColumn::Events, &span, &latency),
+            CELL_TEXT_COLOR
+        );
+        assert_eq!(
+            span_color(&TraceColumn::Duration, &span, &latency),
+            CELL_OK_COLOR
+        );
+    }
+
     #[test]
     fn test_format_time_recent() {
         let now_ms = SystemTime::now()
@@ -0,0 +1,199 @@
+//! Units for metric values and human-friendly formatting.
+//!
+//! Metrics arrive as raw doubles. The unit is inferred from the metric name
+//! following the OpenTelemetry/Prometheus suffix conventions (`_bytes`,
+//! `_seconds`, `_ratio`, ...) and can be overridden per metric name in
+//! settings. [`format`] is meant for labels and tooltips (`1.2 GiB`,
+//! `45 ms`, `99.2%`); [`format_axis`] is the compact form for chart axes.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Bytes per mebibyte, for values reported in MB.
+pub const MIB: f64 = 1024.0 * 1024.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Unit {
+    Bytes,
+    Seconds,
+    Milliseconds,
+    /// Fraction between 0 and 1, shown as a percentage.
+    Ratio,
+    Percent,
+    #[default]
+    Count,
+}
+
+impl Unit {
+    /// Infer the unit from a metric name, e.g. `process_memory_bytes` or
+    /// `request_duration_seconds`. Unknown names are plain counts.
+    pub fn infer(metric: &str) -> Self {
+        let name = metric.to_lowercase();
+        let ends = |suffixes: &[&str]| suffixes.iter().any(|s| name.ends_with(s));
+        if ends(&["_ms", "_millis", "_milliseconds"]) {
+            Unit::Milliseconds
+        } else if ends(&["_bytes", "_size"]) || name.contains("memory") {
+            Unit::Bytes
+        } else if ends(&["_seconds", "_duration", "_latency", "_time"]) {
+            Unit::Seconds
+        } else if ends(&["_ratio", "_utilization"]) {
+            Unit::Ratio
+        } else if ends(&["_percent", "_pct"]) || name == "cpu" {
+            Unit::Percent
+        } else {
+            Unit::Count
+        }
+    }
+}
+
+/// Unit for a metric: the override from settings if any, otherwise inferred.
+pub fn unit_for(metric: &str, overrides: &BTreeMap<String, Unit>) -> Unit {
+    overrides
+        .get(metric)
+        .copied()
+        .unwrap_or_else(|| Unit::infer(metric))
+}
+
+/// Format a value for labels and tooltips, e.g. `1.2 GiB` or `45 ms`.
+pub fn format(value: f64, unit: Unit) -> String {
+    if !value.is_finite() {
+        return "n/a".to_string();
+    }
+    let sign = if value < 0.0 { "-" } else { "" };
+    let (scaled, suffix, decimals) = scale(value.abs(), unit);
+    format!("{}{:.*}{}", sign, decimals, scaled, suffix)
+}
+
+/// Compact form for chart axes: like [`format`] without a trailing `.0`.
+pub fn format_axis(value: f64, unit: Unit) -> String {
+    let text = format(value, unit);
+    match text.find(".0") {
+        // Only drop a `.0` that ends the number
+        Some(pos) if !text[pos + 2..].starts_with(|c: char| c.is_ascii_digit()) => {
+            format!("{}{}", &text[..pos], &text[pos + 2..])
+        }
+        _ => text,
+    }
+}
+
+/// Scale a non-negative value to a readable magnitude, returning the scaled
+/// value, its suffix and the decimals to show.
+fn scale(value: f64, unit: Unit) -> (f64, &'static str, usize) {
+    match unit {
+        Unit::Bytes => {
+            const UNITS: [&str; 5] = [" B", " KiB", " MiB", " GiB", " TiB"];
+            let mut scaled = value;
+            let mut index = 0;
+            while scaled >= 1024.0 && index < UNITS.len() - 1 {
+                scaled /= 1024.0;
+                index += 1;
+            }
+            let decimals = if index == 0 {
+                0
+            } else {
+                short_decimals(scaled)
+            };
+            (scaled, UNITS[index], decimals)
+        }
+        Unit::Seconds => scale_duration(value),
+        Unit::Milliseconds => scale_duration(value / 1000.0),
+        Unit::Ratio => (value * 100.0, "%", 1),
+        Unit::Percent => (value, "%", 1),
+        Unit::Count => {
+            const UNITS: [(f64, &str); 3] = [(1e9, "G"), (1e6, "M"), (1e3, "k")];
+            match UNITS.iter().find(|(size, _)| value >= *size) {
+                Some(&(size, suffix)) => (value / size, suffix, short_decimals(value / size)),
+                None if value.fract() == 0.0 => (value, "", 0),
+                None => (value, "", 2),
+            }
+        }
+    }
+}
+
+fn scale_duration(secs: f64) -> (f64, &'static str, usize) {
+    let (scaled, suffix) = if secs == 0.0 {
+        (0.0, " s")
+    } else if secs < 1e-3 {
+        (secs * 1e6, " µs")
+    } else if secs < 1.0 {
+        (secs * 1e3, " ms")
+    } else if secs < 60.0 {
+        (secs, " s")
+    } else if secs < 3600.0 {
+        (secs / 60.0, " min")
+    } else {
+        (secs / 3600.0, " h")
+    };
+    (scaled, suffix, short_decimals(scaled))
+}
+
+/// One decimal for small numbers, none once the integer part carries enough
+/// precision.
+fn short_decimals(scaled: f64) -> usize {
+    if scaled < 10.0 {
+        1
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_from_metric_name() {
+        assert_eq!(Unit::infer("process_memory_bytes"), Unit::Bytes);
+        assert_eq!(Unit::infer("memory"), Unit::Bytes);
+        assert_eq!(Unit::infer("request_duration_seconds"), Unit::Seconds);
+        assert_eq!(Unit::infer("queue_latency_ms"), Unit::Milliseconds);
+        assert_eq!(Unit::infer("cache_hit_ratio"), Unit::Ratio);
+        assert_eq!(Unit::infer("cpu_percent"), Unit::Percent);
+        assert_eq!(Unit::infer("messages_total"), Unit::Count);
+    }
+
+    #[test]
+    fn test_override_wins_over_inference() {
+        let overrides = BTreeMap::from([("frame_time".to_string(), Unit::Milliseconds)]);
+        assert_eq!(unit_for("frame_time", &overrides), Unit::Milliseconds);
+        assert_eq!(unit_for("other_time", &overrides), Unit::Seconds);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format(512.0, Unit::Bytes), "512 B");
+        assert_eq!(format(1.2 * 1024.0 * MIB, Unit::Bytes), "1.2 GiB");
+        assert_eq!(format(512.4 * MIB, Unit::Bytes), "512 MiB");
+        assert_eq!(format(0.0, Unit::Bytes), "0 B");
+    }
+
+    #[test]
+    fn test_format_durations() {
+        assert_eq!(format(0.045, Unit::Seconds), "45 ms");
+        assert_eq!(format(45.0, Unit::Milliseconds), "45 ms");
+        assert_eq!(format(1500.0, Unit::Milliseconds), "1.5 s");
+        assert_eq!(format(0.0000025, Unit::Seconds), "2.5 µs");
+        assert_eq!(format(150.0, Unit::Seconds), "2.5 min");
+        assert_eq!(format(7200.0, Unit::Seconds), "2.0 h");
+    }
+
+    #[test]
+    fn test_format_ratios_and_counts() {
+        assert_eq!(format(0.992, Unit::Ratio), "99.2%");
+        assert_eq!(format(99.2, Unit::Percent), "99.2%");
+        assert_eq!(format(42.0, Unit::Count), "42");
+        assert_eq!(format(0.25, Unit::Count), "0.25");
+        assert_eq!(format(1234.0, Unit::Count), "1.2k");
+        assert_eq!(format(-3_400_000.0, Unit::Count), "-3.4M");
+        assert_eq!(format(f64::NAN, Unit::Count), "n/a");
+    }
+
+    #[test]
+    fn test_format_axis_drops_trailing_zero() {
+        assert_eq!(format_axis(1024.0 * MIB, Unit::Bytes), "1 GiB");
+        assert_eq!(format_axis(0.5, Unit::Ratio), "50%");
+        assert_eq!(format_axis(0.992, Unit::Ratio), "99.2%");
+        assert_eq!(format_axis(10.5, Unit::Count), "10.50");
+    }
+}
@@ -0,0 +1,223 @@
+//! File watcher for the YAML files of running dataflows.
+//!
+//! A single `notify` watcher runs in the background and pushes changed paths
+//! into a queue. Parent directories are watched rather than the files
+//! themselves so editors that save via rename-and-replace keep working.
+//!
+//! The UI calls [`sync_watched`] after each refresh to match the watched set
+//! to running dataflows, and polls [`take_changed_paths`] from its frame loop
+//! (same pattern as the SigNoz bridge).
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Editors often emit several events per save; ignore repeats within this window.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+/// Paths the debouncer remembers. Every file in a watched directory passes
+/// through it, e.g. build output next to a dataflow.
+const MAX_DEBOUNCED: usize = 256;
+
+struct WatchState {
+    watcher: Option<RecommendedWatcher>,
+    /// Watched directories.
+    dirs: HashSet<PathBuf>,
+    /// Files whose changes are reported.
+    files: HashSet<PathBuf>,
+}
+
+static STATE: Mutex<Option<WatchState>> = Mutex::new(None);
+static CHANGED: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+static DEBOUNCER: Mutex<Option<Debouncer>> = Mutex::new(None);
+
+/// Watch exactly `paths`, adding new ones and dropping those no longer listed.
+pub fn sync_watched(paths: &[String]) {
+    let files: HashSet<PathBuf> = paths.iter().map(|p| canonical(Path::new(p))).collect();
+    let dirs: HashSet<PathBuf> = files
+        .iter()
+        .filter_map(|f| f.parent().map(Path::to_path_buf))
+        .collect();
+
+    let mut guard = STATE.lock().unwrap();
+    let state = guard.get_or_insert_with(|| WatchState {
+        watcher: create_watcher(),
+        dirs: HashSet::new(),
+        files: HashSet::new(),
+    });
+    let Some(watcher) = state.watcher.as_mut() else {
+        return;
+    };
+
+    for dir in state.dirs.difference(&dirs) {
+        let _ = watcher.unwatch(dir);
+        tracing::debug!(target: "Watcher", "Unwatching {}", dir.display());
+    }
+
+    let mut watched = HashSet::new();
+    for dir in dirs {
+        if state.dirs.contains(&dir) {
+            watched.insert(dir);
+            continue;
+        }
+        match watcher.watch(&dir, RecursiveMode::NonRecursive) {
+            Ok(()) => {
+                tracing::debug!(target: "Watcher", "Watching {}", dir.display());
+                watched.insert(dir);
+            }
+            Err(e) => {
+                tracing::warn!(target: "Watcher", "Failed to watch {}: {}", dir.display(), e);
+            }
+        }
+    }
+    state.dirs = watched;
+    state.files = files;
+}
+
+/// Drain watched files that changed since the last call (deduplicated).
+pub fn take_changed_paths() -> Vec<PathBuf> {
+    let changed = std::mem::take(&mut *CHANGED.lock().unwrap());
+    let guard = STATE.lock().unwrap();
+    let Some(state) = guard.as_ref() else {
+        return Vec::new();
+    };
+    filter_changed(changed, &state.files)
+}
+
+/// Keep only paths in `files`, sorted and deduplicated.
+fn filter_changed(mut changed: Vec<PathBuf>, files: &HashSet<PathBuf>) -> Vec<PathBuf> {
+    changed.retain(|p| files.contains(p));
+    changed.sort();
+    changed.dedup();
+    changed
+}
+
+/// Canonicalize when possible so editor events and start paths compare equal.
+pub fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn create_watcher() -> Option<RecommendedWatcher> {
+    let result = notify::recommended_watcher(|res: notify::Result<notify::Event>| match res {
+        Ok(event) => {
+            if !matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_)) {
+                return;
+            }
+            let now = Instant::now();
+            let mut debouncer = DEBOUNCER.lock().unwrap();
+            let debouncer = debouncer.get_or_insert_with(|| Debouncer::new(DEBOUNCE));
+            let mut changed = CHANGED.lock().unwrap();
+            for path in event.paths {
+                if debouncer.accept(&path, now) {
+                    changed.push(path);
+                }
+            }
+        }
+        Err(e) => tracing::warn!(target: "Watcher", "Watch error: {}", e),
+    });
+
+    match result {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            tracing::error!(target: "Watcher", "Failed to create file watcher: {}", e);
+            None
+        }
+    }
+}
+
+/// Per-path debounce: accepts an event only if the previous accepted event
+/// for that path is older than the window.
+struct Debouncer {
+    window: Duration,
+    last: HashMap<PathBuf, Instant>,
+}
+
+impl Debouncer {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            last: HashMap::new(),
+        }
+    }
+
+    fn accept(&mut self, path: &Path, now: Instant) -> bool {
+        match self.last.get(path) {
+            Some(prev) if now.saturating_duration_since(*prev) < self.window => false,
+            _ => {
+                if self.last.len() >= MAX_DEBOUNCED {
+                    // Paths outside the window would be accepted anyway
+                    let window = self.window;
+                    self.last
+                        .retain(|_, prev| now.saturating_duration_since(*prev) < window);
+                    if self.last.len() >= MAX_DEBOUNCED {
+                        self.last.clear();
+                    }
+                }
+                self.last.insert(path.to_path_buf(), now);
+                true
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debouncer_drops_repeats_within_window() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(Duration::from_millis(500));
+        let path = Path::new("/tmp/dataflow.yml");
+
+        assert!(debouncer.accept(path, start));
+        assert!(!debouncer.accept(path, start + Duration::from_millis(100)));
+        assert!(debouncer.accept(path, start + Duration::from_millis(600)));
+    }
+
+    #[test]
+    fn test_debouncer_tracks_paths_independently() {
+        let now = Instant::now();
+        let mut debouncer = Debouncer::new(Duration::from_millis(500));
+        assert!(debouncer.accept(Path::new("a.yml"), now));
+        assert!(debouncer.accept(Path::new("b.yml"), now));
+    }
+
+    #[test]
+    fn test_debouncer_is_bounded() {
+        let start = Instant::now();
+        let mut debouncer = Debouncer::new(Duration::from_millis(500));
+        for i in 0..MAX_DEBOUNCED {
+            assert!(debouncer.accept(Path::new(&format!("{}.o", i)), start));
+        }
+        let later = start + Duration::from_secs(1);
+        assert!(debouncer.accept(Path::new("a.yml"), later));
+        assert_eq!(debouncer.last.len(), 1);
+        assert!(!debouncer.accept(Path::new("a.yml"), later));
+    }
+
+    #[test]
+    fn test_filter_changed_keeps_watched_files() {
+        let files: HashSet<PathBuf> = [PathBuf::from("/w/a.yml")].into_iter().collect();
+        let changed = vec![
+            PathBuf::from("/w/a.yml"),
+            PathBuf::from("/w/.a.yml.swp"),
+            PathBuf::from("/w/a.yml"),
+        ];
+        assert_eq!(
+            filter_changed(changed, &files),
+            vec![PathBuf::from("/w/a.yml")]
+        );
+    }
+
+    #[test]
+    fn test_canonical_falls_back_for_missing_path() {
+        let path = Path::new("/definitely/not/here.yml");
+        assert_eq!(canonical(path), path.to_path_buf());
+    }
+}
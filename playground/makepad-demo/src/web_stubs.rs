@@ -0,0 +1,52 @@
+//! Empty stand-ins for native-only widgets on the web.
+//!
+//! The app layout names the alerts panel, query panel, project wizard and
+//! crash dialog, whose modules only build on native platforms. On the web
+//! [`web_stub!`] declares a module of the same path with an empty, hidden
+//! widget of the same name, so the layout resolves and the tabs stay blank.
+
+/// Declare `module::inner::Widget` as an empty hidden view, with the
+/// `live_design` functions the real module has.
+macro_rules! web_stub {
+    ($module:ident :: $inner:ident :: $widget:ident) => {
+        pub mod $module {
+            pub fn live_design(cx: &mut makepad_widgets::Cx) {
+                $inner::live_design(cx);
+            }
+
+            pub mod $inner {
+                use makepad_widgets::*;
+
+                live_design! {
+                    use link::widgets::*;
+
+                    pub $widget = {{$widget}} {
+                        width: 0, height: 0
+                        visible: false
+                    }
+                }
+
+                #[derive(Live, LiveHook, Widget)]
+                pub struct $widget {
+                    #[deref]
+                    view: View,
+                }
+
+                impl Widget for $widget {
+                    fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
+                        self.view.handle_event(cx, event, scope);
+                    }
+
+                    fn draw_walk(
+                        &mut self,
+                        cx: &mut Cx2d,
+                        scope: &mut Scope,
+                        walk: Walk,
+                    ) -> DrawStep {
+                        self.view.draw_walk(cx, scope, walk)
+                    }
+                }
+            }
+        }
+    };
+}
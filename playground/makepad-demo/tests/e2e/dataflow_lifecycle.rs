@@ -1,73 +1,115 @@
 //! E2E tests for complete dataflow lifecycle
 //!
-//! These tests require a running Dora coordinator and daemon.
-//! Run with: cargo test --test e2e -- --ignored
+//! Each test launches its own coordinator and daemon through
+//! [`DoraHarness`], so only the `dora` executable is required.
+//! Run with: cargo test --test lib e2e::dataflow_lifecycle -- --ignored --test-threads 1
 
+use super::harness::{wait_until, DoraHarness, CRASHING_DATAFLOW, EXAMPLE_DATAFLOW};
+use dora_studio::dataflow::connection::is_unreachable;
+use dora_studio::dataflow::DoraClient;
 use std::time::Duration;
 
+/// How long a status change may take to show up in the dataflow list.
+const STATUS_TIMEOUT: Duration = Duration::from_secs(15);
+
+fn launch() -> DoraHarness {
+    DoraHarness::launch().unwrap_or_else(|e| panic!("Failed to launch dora: {}", e))
+}
+
+fn is_running(harness: &DoraHarness, uuid: &str) -> bool {
+    harness
+        .dataflow(uuid)
+        .is_some_and(|d| d.status == "Running")
+}
+
 // ============================================================================
 // Full Lifecycle Tests
 // ============================================================================
 
 /// Test complete dataflow lifecycle: start -> monitor -> stop
-#[tokio::test]
-#[ignore] // Requires running Dora
-async fn test_full_dataflow_lifecycle() {
-    // TODO: Implement with real Dora connection
-    // let client = DoraClient::connect("127.0.0.1:53290").await.unwrap();
-    //
-    // // Start dataflow
-    // let uuid = client.start_dataflow("examples/simple.yaml").await.unwrap();
-    //
-    // // Verify running
-    // let flows = client.list_dataflows().await.unwrap();
-    // let flow = flows.iter().find(|f| f.uuid == uuid).unwrap();
-    // assert!(matches!(flow.status, DataflowStatus::Running));
-    //
-    // // Wait for nodes to initialize
-    // tokio::time::sleep(Duration::from_secs(2)).await;
-    //
-    // // Get node metrics
-    // let nodes = client.list_nodes(&uuid).await.unwrap();
-    // assert!(!nodes.is_empty());
-    //
-    // // Stop dataflow
-    // client.stop_dataflow(&uuid).await.unwrap();
-    //
-    // // Verify stopped
-    // tokio::time::sleep(Duration::from_secs(1)).await;
-    // let flows = client.list_dataflows().await.unwrap();
-    // let flow = flows.iter().find(|f| f.uuid == uuid);
-    // assert!(flow.is_none() || !matches!(flow.unwrap().status, DataflowStatus::Running));
-
-    todo!("Implement test_full_dataflow_lifecycle with real Dora")
+#[test]
+#[ignore = "requires dora"]
+fn test_full_dataflow_lifecycle() {
+    let harness = launch();
+    let client = harness.client();
+
+    // Start dataflow
+    let uuid = harness
+        .start(&harness.example_dataflow("lifecycle"))
+        .unwrap();
+
+    // Verify running
+    assert!(
+        wait_until(STATUS_TIMEOUT, || is_running(&harness, &uuid)),
+        "dataflow {} never reported Running",
+        uuid
+    );
+
+    // Get nodes
+    let nodes = client.list_nodes(&uuid).unwrap();
+    assert!(!nodes.is_empty());
+
+    // Stop dataflow
+    client.stop(&uuid).unwrap();
+
+    // Verify stopped
+    assert!(
+        wait_until(STATUS_TIMEOUT, || !is_running(&harness, &uuid)),
+        "dataflow {} still Running after stop",
+        uuid
+    );
 }
 
-/// Test dataflow reload (hot-reload)
-#[tokio::test]
-#[ignore] // Requires running Dora
-async fn test_dataflow_reload() {
-    // TODO: Test hot-reload functionality
-    // 1. Start dataflow
-    // 2. Modify YAML
-    // 3. Reload
-    // 4. Verify changes applied without restart
-
-    todo!("Implement test_dataflow_reload")
+/// Test dataflow reload
+///
+/// The coordinator protocol has no reload request, so the client reloads
+/// by stopping the dataflow and starting the edited YAML again.
+#[test]
+#[ignore = "requires dora"]
+fn test_dataflow_reload() {
+    let harness = launch();
+    let client = harness.client();
+    let path = harness.write_dataflow("reload", EXAMPLE_DATAFLOW);
+
+    let first = harness.start(&path).unwrap();
+    assert!(wait_until(STATUS_TIMEOUT, || is_running(&harness, &first)));
+    let mut nodes = client.list_nodes(&first).unwrap();
+    nodes.sort();
+    assert_eq!(nodes, vec!["listener", "ticker"]);
+
+    // Edit the YAML: drop the listener node
+    let edited = EXAMPLE_DATAFLOW.split("  - id: listener").next().unwrap();
+    std::fs::write(&path, edited).unwrap();
+
+    client.stop(&first).unwrap();
+    assert!(wait_until(STATUS_TIMEOUT, || !is_running(&harness, &first)));
+    let second = harness.start(&path).unwrap();
+    assert!(wait_until(STATUS_TIMEOUT, || is_running(&harness, &second)));
+    assert_eq!(client.list_nodes(&second).unwrap(), vec!["ticker"]);
+
+    client.stop(&second).unwrap();
 }
 
 /// Test multiple concurrent dataflows
-#[tokio::test]
-#[ignore] // Requires running Dora
-async fn test_multiple_dataflows() {
-    // TODO: Test running multiple dataflows simultaneously
-    // 1. Start dataflow A
-    // 2. Start dataflow B
-    // 3. Verify both running
-    // 4. Stop A, verify B still running
-    // 5. Stop B
-
-    todo!("Implement test_multiple_dataflows")
+#[test]
+#[ignore = "requires dora"]
+fn test_multiple_dataflows() {
+    let harness = launch();
+    let client = harness.client();
+
+    let a = harness.start(&harness.example_dataflow("a")).unwrap();
+    let b = harness.start(&harness.example_dataflow("b")).unwrap();
+    assert_ne!(a, b);
+    assert!(wait_until(STATUS_TIMEOUT, || is_running(&harness, &a)
+        && is_running(&harness, &b)));
+
+    // Stopping A leaves B running
+    client.stop(&a).unwrap();
+    assert!(wait_until(STATUS_TIMEOUT, || !is_running(&harness, &a)));
+    assert!(is_running(&harness, &b));
+
+    client.stop(&b).unwrap();
+    assert!(wait_until(STATUS_TIMEOUT, || !is_running(&harness, &b)));
 }
 
 // ============================================================================
@@ -75,28 +117,58 @@ async fn test_multiple_dataflows() {
 // ============================================================================
 
 /// Test recovery from node crash
-#[tokio::test]
-#[ignore] // Requires running Dora
-async fn test_node_crash_recovery() {
-    // TODO: Test behavior when a node crashes
-    // 1. Start dataflow
-    // 2. Simulate node crash
-    // 3. Verify status updated to Failed
-    // 4. Verify logs captured
-
-    todo!("Implement test_node_crash_recovery")
+#[test]
+#[ignore = "requires dora"]
+fn test_node_crash_recovery() {
+    let harness = launch();
+    let client = harness.client();
+
+    let path = harness.write_dataflow("crash", CRASHING_DATAFLOW);
+    let uuid = harness.start(&path).unwrap();
+
+    // Verify status updated to Failed
+    assert!(
+        wait_until(STATUS_TIMEOUT, || harness
+            .dataflow(&uuid)
+            .is_some_and(|d| d.status == "Failed")),
+        "crashed dataflow {} never reported Failed",
+        uuid
+    );
+
+    // Verify logs captured
+    let logs = client.logs(&uuid, Some("crasher")).unwrap();
+    assert!(
+        logs.contains("crashing"),
+        "missing node stderr in {:?}",
+        logs
+    );
+
+    // The coordinator keeps serving other dataflows
+    let healthy = harness.start(&harness.example_dataflow("healthy")).unwrap();
+    assert!(wait_until(STATUS_TIMEOUT, || is_running(
+        &harness, &healthy
+    )));
+    client.stop(&healthy).unwrap();
 }
 
 /// Test coordinator disconnect handling
-#[tokio::test]
-#[ignore] // Requires running Dora
-async fn test_coordinator_disconnect() {
-    // TODO: Test client behavior when coordinator disconnects
-    // 1. Connect to coordinator
-    // 2. Kill coordinator
-    // 3. Verify client handles gracefully
-    // 4. Restart coordinator
-    // 5. Verify client reconnects
-
-    todo!("Implement test_coordinator_disconnect")
+#[test]
+#[ignore = "requires dora"]
+fn test_coordinator_disconnect() {
+    let mut harness = launch();
+    let client = harness.client();
+    assert!(client.list_dataflows().is_ok());
+
+    // Kill coordinator: calls fail with an unreachable error, not a hang
+    harness.kill_coordinator();
+    let err = client.list_dataflows().unwrap_err();
+    assert!(is_unreachable(&err), "unexpected error: {}", err);
+
+    // Restart coordinator: the same client works again, since it connects
+    // per request
+    harness.restart_coordinator().unwrap();
+    assert!(client.list_dataflows().is_ok());
+    let uuid = harness.start(&harness.example_dataflow("after")).unwrap();
+    assert!(wait_until(STATUS_TIMEOUT, || is_running(&harness, &uuid)));
+    client.stop(&uuid).unwrap();
 }
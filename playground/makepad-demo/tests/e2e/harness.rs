@@ -0,0 +1,245 @@
+//! Real dora for E2E tests
+//!
+//! [`DoraHarness`] runs a private coordinator and daemon as child processes
+//! on free local ports, so tests can run in parallel and never touch a dora
+//! the developer already has running. Both are killed when the harness is
+//! dropped. The `dora` executable comes from `DORA_BIN`, else `PATH`; the
+//! `e2e` CI workflow installs the `dora-cli` release pinned there first.
+//!
+//! The example dataflows use plain processes as nodes so they start without
+//! a build step. Set `DORA_E2E_DATAFLOW` to a real dataflow YAML (e.g. one
+//! of dora's `examples/`) to run the lifecycle tests against it instead.
+//!
+//! Tests talk to the harness through the app's own [`CliClient`], which
+//! takes the dora executable and coordinator from process-wide settings, so
+//! run them with `--test-threads 1`.
+
+use dora_studio::dataflow::client::CliClient;
+use dora_studio::dataflow::{DataflowInfo, DoraClient};
+use dora_studio::tools;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How long the coordinator gets to start listening.
+const READY_TIMEOUT: Duration = Duration::from_secs(20);
+/// How long a start is retried while the daemon is still registering.
+const START_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Two long-running nodes, one fed by a timer.
+pub const EXAMPLE_DATAFLOW: &str = r#"
+nodes:
+  - id: ticker
+    path: sleep
+    args: "600"
+    inputs:
+      tick: dora/timer/millis/100
+  - id: listener
+    path: sleep
+    args: "600"
+"#;
+
+/// A node that exits with an error as soon as it is spawned.
+pub const CRASHING_DATAFLOW: &str = r#"
+nodes:
+  - id: crasher
+    path: sh
+    args: -c "echo crashing >&2; exit 3"
+"#;
+
+/// A coordinator and daemon owned by one test.
+pub struct DoraHarness {
+    program: String,
+    dir: tempfile::TempDir,
+    coordinator_port: u16,
+    control_port: u16,
+    daemon_port: u16,
+    coordinator: Option<Child>,
+    daemon: Option<Child>,
+}
+
+impl DoraHarness {
+    /// Start a coordinator and daemon and wait until the coordinator
+    /// answers on its control port.
+    pub fn launch() -> Result<Self, String> {
+        let mut harness = Self {
+            program: std::env::var("DORA_BIN").unwrap_or_else(|_| "dora".to_string()),
+            dir: tempfile::tempdir().map_err(|e| format!("Failed to create temp dir: {}", e))?,
+            coordinator_port: free_port()?,
+            control_port: free_port()?,
+            daemon_port: free_port()?,
+            coordinator: None,
+            daemon: None,
+        };
+        harness.spawn_coordinator()?;
+        harness.spawn_daemon()?;
+        harness.wait_ready()?;
+        Ok(harness)
+    }
+
+    /// Control address of the coordinator.
+    pub fn control_addr(&self) -> String {
+        format!("127.0.0.1:{}", self.control_port)
+    }
+
+    /// A client running this harness's dora against its coordinator.
+    pub fn client(&self) -> CliClient {
+        tools::set_dora_path(Some(&self.program));
+        tools::set_coordinator_addr(Some(&self.control_addr())).expect("valid control address");
+        CliClient
+    }
+
+    /// Write `yaml` to `<name>.yml` in the harness's working directory.
+    pub fn write_dataflow(&self, name: &str, yaml: &str) -> PathBuf {
+        let path = self.dir.path().join(format!("{}.yml", name));
+        std::fs::write(&path, yaml).expect("write dataflow");
+        path
+    }
+
+    /// `DORA_E2E_DATAFLOW` if set, else [`EXAMPLE_DATAFLOW`] written as
+    /// `<name>.yml`.
+    pub fn example_dataflow(&self, name: &str) -> PathBuf {
+        match std::env::var("DORA_E2E_DATAFLOW") {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => self.write_dataflow(name, EXAMPLE_DATAFLOW),
+        }
+    }
+
+    /// Start the dataflow at `path`, retrying while the daemon has not
+    /// registered with the coordinator yet. Returns its UUID.
+    pub fn start(&self, path: &Path) -> Result<String, String> {
+        let client = self.client();
+        let path = path.to_string_lossy();
+        let deadline = Instant::now() + START_TIMEOUT;
+        loop {
+            match client.start(&path) {
+                Ok(Some(uuid)) => return Ok(uuid),
+                Ok(None) => return Err("Coordinator did not report a UUID".to_string()),
+                Err(e) if Instant::now() >= deadline => return Err(e),
+                Err(_) => std::thread::sleep(POLL_INTERVAL),
+            }
+        }
+    }
+
+    /// The dataflow with `uuid`, if the coordinator still lists it.
+    pub fn dataflow(&self, uuid: &str) -> Option<DataflowInfo> {
+        self.client()
+            .list_dataflows()
+            .ok()?
+            .into_iter()
+            .find(|d| d.uuid == uuid)
+    }
+
+    /// Kill the coordinator, leaving the daemon to notice on its own.
+    pub fn kill_coordinator(&mut self) {
+        kill(&mut self.coordinator);
+    }
+
+    /// Start the coordinator again on the same ports, and the daemon too if
+    /// it exited when the coordinator went away.
+    pub fn restart_coordinator(&mut self) -> Result<(), String> {
+        kill(&mut self.coordinator);
+        self.spawn_coordinator()?;
+        let daemon_exited = match self.daemon.as_mut() {
+            Some(daemon) => daemon.try_wait().ok().flatten().is_some(),
+            None => true,
+        };
+        if daemon_exited {
+            self.spawn_daemon()?;
+        }
+        self.wait_ready()
+    }
+
+    fn spawn_coordinator(&mut self) -> Result<(), String> {
+        let child = self.spawn(&[
+            "coordinator",
+            "--interface",
+            "127.0.0.1",
+            "--port",
+            &self.coordinator_port.to_string(),
+            "--control-interface",
+            "127.0.0.1",
+            "--control-port",
+            &self.control_port.to_string(),
+            "--quiet",
+        ])?;
+        self.coordinator = Some(child);
+        Ok(())
+    }
+
+    fn spawn_daemon(&mut self) -> Result<(), String> {
+        let child = self.spawn(&[
+            "daemon",
+            "--coordinator-addr",
+            "127.0.0.1",
+            "--coordinator-port",
+            &self.coordinator_port.to_string(),
+            "--local-listen-port",
+            &self.daemon_port.to_string(),
+            "--quiet",
+        ])?;
+        self.daemon = Some(child);
+        Ok(())
+    }
+
+    fn spawn(&self, args: &[&str]) -> Result<Child, String> {
+        Command::new(&self.program)
+            .args(args)
+            .current_dir(self.dir.path())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| format!("Failed to run '{} {}': {}", self.program, args[0], e))
+    }
+
+    fn wait_ready(&self) -> Result<(), String> {
+        wait_until(READY_TIMEOUT, || self.client().list_dataflows().is_ok())
+            .then_some(())
+            .ok_or_else(|| {
+                format!(
+                    "Coordinator did not answer on {} within {:?}",
+                    self.control_addr(),
+                    READY_TIMEOUT
+                )
+            })
+    }
+}
+
+impl Drop for DoraHarness {
+    fn drop(&mut self) {
+        kill(&mut self.daemon);
+        kill(&mut self.coordinator);
+    }
+}
+
+/// Poll `condition` until it holds or `timeout` passes; whether it held.
+pub fn wait_until(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if condition() {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// A port nothing is listening on right now.
+fn free_port() -> Result<u16, String> {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| format!("Failed to find a free port: {}", e))
+}
+
+fn kill(child: &mut Option<Child>) {
+    if let Some(mut child) = child.take() {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
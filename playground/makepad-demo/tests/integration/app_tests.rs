@@ -1,8 +1,17 @@
 //! Integration tests for Mini-Apps
 //!
-//! Tests app state management and component interaction.
+//! Tests app state management and component interaction. The app runs in a
+//! headless [`Harness`] against a `MockDoraClient`, so these are plain
+//! `#[test]`s: the harness owns a Makepad `Cx`, which stays on one thread.
 
 use super::*;
+use dora_studio::harness::Harness;
+use makepad_widgets::ids;
+
+/// Build the app around a handle on `mock`.
+fn harness(mock: &MockDoraClient) -> Harness {
+    Harness::new(Box::new(mock.clone()))
+}
 
 // ============================================================================
 // Dataflow Manager App Tests
@@ -10,29 +19,142 @@ use super::*;
 
 mod dataflow_manager {
     use super::*;
+    use dora_studio::dataflow::TableLoadingState;
+    use dora_studio::tools::payload::CheckDiagnostic;
 
-    #[tokio::test]
-    async fn test_dataflow_list_loads_from_client() {
-        // TODO: Test loading dataflows from DoraClient
-        todo!("Implement test_dataflow_list_loads_from_client")
+    fn table_names(harness: &Harness) -> Vec<String> {
+        harness
+            .dataflow_table()
+            .dataflows()
+            .into_iter()
+            .map(|df| df.name)
+            .collect()
     }
 
-    #[tokio::test]
-    async fn test_start_action_calls_client() {
-        // TODO: Test start button triggers client call
-        todo!("Implement test_start_action_calls_client")
+    #[test]
+    fn test_dataflow_list_loads_from_client() {
+        let mock = MockDoraClient::new();
+        mock.set_dataflows(vec![mock_dataflow("camera"), mock_dataflow("detector")]);
+        let mut harness = harness(&mock);
+
+        // The first frame loads the table
+        harness.frame();
+        harness.settle();
+        assert_eq!(mock.call_count("list_dataflows"), 1);
+        assert_eq!(table_names(&harness), vec!["camera", "detector"]);
+        assert_eq!(
+            harness.dataflow_table().loading_state(),
+            TableLoadingState::Idle
+        );
     }
 
-    #[tokio::test]
-    async fn test_stop_action_requires_selection() {
-        // TODO: Test stop requires selected dataflow
-        todo!("Implement test_stop_action_requires_selection")
+    #[test]
+    fn test_start_action_calls_client() {
+        let mock = MockDoraClient::new();
+        let mut harness = harness(&mock);
+        harness.frame();
+        harness.settle();
+
+        // An empty path never reaches the client
+        harness.click(ids!(start_button));
+        harness.settle();
+        assert!(!mock.was_called("start"));
+
+        harness.set_text(ids!(start_path_input), "dataflows/camera.yml");
+        harness.click(ids!(start_button));
+        harness.settle();
+        assert_eq!(mock.call_count("start"), 1);
+        // Starting refreshes the table
+        assert_eq!(mock.call_count("list_dataflows"), 2);
+        assert_eq!(table_names(&harness), vec!["camera"]);
     }
 
-    #[tokio::test]
-    async fn test_auto_refresh_updates_list() {
-        // TODO: Test auto-refresh functionality
-        todo!("Implement test_auto_refresh_updates_list")
+    #[test]
+    fn test_start_blocked_by_failed_check() {
+        let mock = MockDoraClient::new();
+        mock.expect_check_problems(vec![CheckDiagnostic {
+            node: Some("camera".to_string()),
+            field: None,
+            message: "path `./camera.py` does not exist".to_string(),
+        }]);
+        let mut harness = harness(&mock);
+        harness.frame();
+        harness.settle();
+
+        harness.set_text(ids!(start_path_input), "dataflows/camera.yml");
+        harness.click(ids!(validate_button));
+        harness.settle();
+        assert_eq!(mock.call_count("check"), 1);
+        assert!(!mock.was_called("start"));
+
+        // Starting checks first and stops at the problem
+        harness.click(ids!(start_button));
+        harness.settle();
+        assert_eq!(mock.call_count("check"), 2);
+        assert!(!mock.was_called("start"));
+
+        mock.expect_check_problems(Vec::new());
+        harness.click(ids!(start_button));
+        harness.settle();
+        assert_eq!(mock.call_count("start"), 1);
+    }
+
+    #[test]
+    fn test_stop_action_requires_selection() {
+        let mock = MockDoraClient::new();
+        mock.set_dataflows(vec![mock_dataflow("camera")]);
+        let mut harness = harness(&mock);
+        harness.frame();
+        harness.settle();
+
+        // Stop lives on the rows and the detail panel; app-level controls,
+        // refreshes and the panel with nothing selected stop nothing
+        harness.click(ids!(start_button));
+        harness.click(ids!(refresh_retry_button));
+        harness.click(ids!(dataflow_detail.stop_button));
+        harness.click(ids!(dataflow_detail.destroy_button));
+        harness.advance(5.0);
+        harness.settle();
+        assert_eq!(mock.call_count("stop"), 0);
+        assert_eq!(mock.call_count("destroy"), 0);
+
+        // Once a dataflow is selected, the same button stops it
+        let uuid = harness.dataflow_table().dataflows()[0].uuid.clone();
+        harness.select_dataflow(&uuid);
+        harness.settle();
+        harness.click(ids!(dataflow_detail.stop_button));
+        harness.settle();
+        assert_eq!(mock.call_count("stop"), 1);
+        assert_eq!(mock.call_count("destroy"), 0);
+    }
+
+    #[test]
+    fn test_auto_refresh_updates_list() {
+        let mock = MockDoraClient::new();
+        mock.set_dataflows(vec![mock_dataflow("camera")]);
+        let mut harness = harness(&mock);
+        harness.frame();
+        harness.settle();
+        assert_eq!(table_names(&harness), vec!["camera"]);
+
+        mock.set_dataflows(vec![
+            mock_dataflow("camera"),
+            mock_dataflow_with_status("detector", DataflowStatus::Finished),
+        ]);
+
+        // Not due yet
+        harness.advance(2.0);
+        assert_eq!(mock.call_count("list_dataflows"), 1);
+        assert_eq!(table_names(&harness), vec!["camera"]);
+
+        harness.advance(3.0);
+        harness.settle();
+        assert_eq!(mock.call_count("list_dataflows"), 2);
+        let dataflows = harness.dataflow_table().dataflows();
+        assert_eq!(dataflows.len(), 2);
+        assert!(dataflows
+            .iter()
+            .any(|df| df.name == "detector" && df.status == "Finished"));
     }
 }
 
@@ -41,29 +163,27 @@ mod dataflow_manager {
 // ============================================================================
 
 mod yaml_editor {
-    use super::*;
-
-    #[tokio::test]
-    async fn test_yaml_change_updates_graph() {
-        // TODO: Test YAML edit triggers graph update
+    #[test]
+    #[ignore] // No YAML editor or graph view yet
+    fn test_yaml_change_updates_graph() {
         todo!("Implement test_yaml_change_updates_graph")
     }
 
-    #[tokio::test]
-    async fn test_graph_layout_hierarchical() {
-        // TODO: Test graph uses hierarchical layout
+    #[test]
+    #[ignore] // No YAML editor or graph view yet
+    fn test_graph_layout_hierarchical() {
         todo!("Implement test_graph_layout_hierarchical")
     }
 
-    #[tokio::test]
-    async fn test_validation_errors_shown() {
-        // TODO: Test validation errors display
+    #[test]
+    #[ignore] // No YAML editor or graph view yet
+    fn test_validation_errors_shown() {
         todo!("Implement test_validation_errors_shown")
     }
 
-    #[tokio::test]
-    async fn test_file_save_and_load() {
-        // TODO: Test file operations
+    #[test]
+    #[ignore] // No YAML editor or graph view yet
+    fn test_file_save_and_load() {
         todo!("Implement test_file_save_and_load")
     }
 }
@@ -74,23 +194,127 @@ mod yaml_editor {
 
 mod log_viewer {
     use super::*;
+    use dora_studio::dataflow::node_logs::{LogFilter, LogLevel};
 
-    #[tokio::test]
-    async fn test_log_filtering_combined() {
-        // TODO: Test combined level + node + text filters
-        todo!("Implement test_log_filtering_combined")
+    const LOGS: &str = "node-0: INFO frame 1\n\
+                        node-0: WARN slow frame\n\
+                        node-1: ERROR drew nothing\n\
+                        node-0: ERROR camera lost\n\
+                        node-1: INFO drew frame 1\n";
+
+    /// The app with a running dataflow selected and `LOGS` fetched for it.
+    fn viewer(mock: &MockDoraClient) -> Harness {
+        mock.set_dataflows(vec![mock_dataflow("camera")]);
+        mock.set_logs(LOGS);
+        let mut harness = harness(mock);
+        harness.frame();
+        harness.settle();
+        let uuid = harness.dataflow_table().dataflows()[0].uuid.clone();
+        harness.select_dataflow(&uuid);
+        harness.settle();
+        harness
+    }
+
+    fn shown(harness: &Harness) -> Vec<String> {
+        let lines = harness.dataflow_detail().shown_lines();
+        lines.into_iter().map(|l| l.text).collect()
+    }
+
+    fn camera_warnings() -> LogFilter {
+        LogFilter {
+            node: Some("node-0".to_string()),
+            min_level: LogLevel::Warn,
+        }
     }
 
-    #[tokio::test]
-    async fn test_real_time_streaming() {
-        // TODO: Test real-time log updates
-        todo!("Implement test_real_time_streaming")
+    #[test]
+    fn test_log_filtering_combined() {
+        let mock = MockDoraClient::new();
+        let mut harness = viewer(&mock);
+        assert_eq!(shown(&harness).len(), 5);
+
+        // Node and level narrow together, not one or the other
+        harness
+            .dataflow_detail()
+            .set_filter(harness.cx(), camera_warnings());
+        assert_eq!(
+            shown(&harness),
+            ["node-0: WARN slow frame", "node-0: ERROR camera lost"]
+        );
+
+        // A node not listed for the dataflow filters on none
+        let filter = LogFilter {
+            node: Some("node-9".to_string()),
+            min_level: LogLevel::Error,
+        };
+        harness.dataflow_detail().set_filter(harness.cx(), filter);
+        assert_eq!(
+            shown(&harness),
+            ["node-1: ERROR drew nothing", "node-0: ERROR camera lost"]
+        );
     }
 
-    #[tokio::test]
-    async fn test_export_respects_filters() {
-        // TODO: Test export with active filters
-        todo!("Implement test_export_respects_filters")
+    #[test]
+    fn test_real_time_streaming() {
+        let mock = MockDoraClient::new();
+        let mut harness = viewer(&mock);
+        let fetched = mock.call_count("logs");
+        assert_eq!(shown(&harness).len(), 5);
+
+        // Each refresh fetches the logs of the running dataflow again and
+        // adds only the lines not shown yet
+        mock.set_logs(&format!("{}node-0: INFO frame 2\n", LOGS));
+        harness.advance(5.0);
+        harness.settle();
+        assert!(mock.call_count("logs") > fetched);
+        let lines = shown(&harness);
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[5], "node-0: INFO frame 2");
+
+        // The filter applies to streamed lines too
+        harness
+            .dataflow_detail()
+            .set_filter(harness.cx(), camera_warnings());
+        mock.set_logs(&format!(
+            "{}node-0: INFO frame 2\nnode-1: WARN late\nnode-0: WARN dropped\n",
+            LOGS
+        ));
+        harness.advance(5.0);
+        harness.settle();
+        assert_eq!(
+            shown(&harness),
+            [
+                "node-0: WARN slow frame",
+                "node-0: ERROR camera lost",
+                "node-0: WARN dropped",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_export_respects_filters() {
+        let mock = MockDoraClient::new();
+        let mut harness = viewer(&mock);
+        harness
+            .dataflow_detail()
+            .set_filter(harness.cx(), camera_warnings());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("camera.log");
+        harness.set_text(
+            ids!(dataflow_detail.export_path_input),
+            path.to_str().unwrap(),
+        );
+        harness.click(ids!(dataflow_detail.export_logs_button));
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let mut lines = text.lines();
+        let header = lines.next().unwrap();
+        assert!(header.contains("node node-0, WARN and above"), "{}", header);
+        assert_eq!(
+            lines.collect::<Vec<_>>(),
+            ["node-0: WARN slow frame", "node-0: ERROR camera lost"]
+        );
     }
 }
 
@@ -100,23 +324,271 @@ mod log_viewer {
 
 mod telemetry_dashboard {
     use super::*;
+    use dora_studio::chart::TimeSeriesChartWidgetRefExt;
+    use dora_studio::dataflow::golden_signals::GoldenSignals;
+    use dora_studio::otlp::types::TimeRange;
+    use dora_studio::otlp::Span as OtlpSpan;
+    use dora_studio::traces::{SavedTraceQuery, TraceFilter, TracesLoadingState};
+    use makepad_widgets::LiveId;
+
+    fn span(span_id: &str, parent: Option<&str>, start_time_ms: u64) -> OtlpSpan {
+        OtlpSpan {
+            trace_id: "trace-1".to_string(),
+            span_id: span_id.to_string(),
+            parent_span_id: parent.map(str::to_string),
+            service_name: "camera".to_string(),
+            operation_name: "capture".to_string(),
+            start_time_ms,
+            duration_ms: 12,
+            status_code: 0,
+            has_error: false,
+            attributes: Default::default(),
+            events: Vec::new(),
+            links: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_chart_updates_on_time_range() {
+        let mock = MockDoraClient::new();
+        let mut harness = harness(&mock);
+        harness.frame();
+        let cpu = ids!(node_metrics_panel.cpu_chart);
+        let memory = ids!(node_metrics_panel.memory_chart);
+        let chart =
+            |harness: &Harness, path: &[&[LiveId]]| harness.ui().time_series_chart(path).range();
+
+        // Zooming either chart shows the same times on the other
+        let range = TimeRange {
+            start_ms: 1_700_000_000_000,
+            end_ms: 1_700_000_060_000,
+        };
+        harness.zoom_chart(cpu, Some(range.clone()));
+        assert_eq!(chart(&harness, memory), Some(range.clone()));
+
+        let panned = TimeRange {
+            start_ms: range.start_ms + 30_000,
+            end_ms: range.end_ms + 30_000,
+        };
+        harness.zoom_chart(memory, Some(panned.clone()));
+        assert_eq!(chart(&harness, cpu), Some(panned));
+
+        // Double clicking one shows all the data on both
+        harness.zoom_chart(cpu, None);
+        assert_eq!(chart(&harness, memory), None);
+    }
+
+    #[test]
+    fn test_crosshair_shared_across_charts() {
+        let mock = MockDoraClient::new();
+        let mut harness = harness(&mock);
+        harness.frame();
+        let cpu = ids!(node_metrics_panel.cpu_chart);
+        let memory = ids!(node_metrics_panel.memory_chart);
+        let crosshair = |harness: &Harness, path: &[&[LiveId]]| {
+            harness.ui().time_series_chart(path).crosshair()
+        };
+
+        let time_ms = 1_700_000_030_000;
+        harness.hover_chart(cpu, Some(time_ms));
+        assert_eq!(crosshair(&harness, memory), Some(time_ms));
+        harness.hover_chart(memory, Some(time_ms + 1_000));
+        assert_eq!(crosshair(&harness, cpu), Some(time_ms + 1_000));
+
+        // Leaving a chart hides the crosshair on the other
+        harness.hover_chart(memory, None);
+        assert_eq!(crosshair(&harness, cpu), None);
+    }
+
+    #[test]
+    fn test_golden_signals_calculation() {
+        // 100 spans of "camera" over the last 50 s, one of them failed
+        let now = 1_000_000;
+        let spans: Vec<OtlpSpan> = (0..100)
+            .map(|i| {
+                let mut span = span(&format!("span-{}", i), None, now - 50_000 + i * 500);
+                span.duration_ms = [10, 20, 30, 100, 200][i as usize % 5];
+                span.has_error = i == 0;
+                span
+            })
+            .collect();
+        let signals = GoldenSignals::compute(&spans, &["camera".to_string()], &[], now);
+
+        assert_eq!(signals.span_count, 100);
+        assert_eq!(signals.traffic.value, Some(2.0));
+        assert_eq!(signals.errors.value, Some(1.0));
+        let p95 = signals.latency.value.unwrap() as u64;
+        assert!(signals.p50_ms.unwrap() < p95);
+        assert!(p95 <= signals.p99_ms.unwrap());
+        // Spans of other dataflows' nodes are left out
+        let other = GoldenSignals::compute(&spans, &["plot".to_string()], &[], now);
+        assert_eq!(other.span_count, 0);
+    }
+
+    #[test]
+    fn test_trace_timeline_rendering() {
+        let mut harness = harness(&MockDoraClient::new());
+        let panel = harness.traces_panel();
+
+        panel.set_loading(harness.cx());
+        assert_eq!(panel.loading_state().0, TracesLoadingState::Loading);
+
+        let spans = vec![
+            span("root", None, 1_000),
+            span("child", Some("root"), 1_004),
+        ];
+        panel.set_spans(harness.cx(), spans);
+        assert_eq!(panel.loading_state().0, TracesLoadingState::Idle);
+        let span_ids: Vec<String> = panel.spans().into_iter().map(|s| s.span_id).collect();
+        assert_eq!(span_ids, vec!["root", "child"]);
+
+        panel.set_error(harness.cx(), "SigNoz unreachable");
+        assert_eq!(
+            panel.loading_state(),
+            (TracesLoadingState::Error, "SigNoz unreachable".to_string())
+        );
+    }
+
+    #[test]
+    fn test_traces_grouped_by_trace() {
+        let mut harness = harness(&MockDoraClient::new());
+        let panel = harness.traces_panel();
+
+        let mut other = span("other", None, 2_000);
+        other.trace_id = "trace-2".to_string();
+        other.has_error = true;
+        panel.set_spans(
+            harness.cx(),
+            vec![
+                span("root", None, 1_000),
+                other,
+                span("child", Some("root"), 1_004),
+            ],
+        );
+        assert_eq!(panel.row_count(), 3);
+
+        panel.set_grouped(harness.cx(), true);
+        let groups = panel.trace_groups();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].describe_counts(), "2 spans");
+        assert_eq!(groups[0].duration_ms, 16);
+        assert_eq!(groups[1].describe_counts(), "1 span · 1 error");
+        assert_eq!(panel.row_count(), 2);
+
+        panel.set_expanded(harness.cx(), "trace-1", true);
+        assert_eq!(panel.row_count(), 4);
+        panel.set_grouped(harness.cx(), false);
+        assert_eq!(panel.row_count(), 3);
+    }
+
+    #[test]
+    fn test_trace_columns_from_settings() {
+        let mut harness = harness(&MockDoraClient::new());
+        let panel = harness.traces_panel();
+        assert_eq!(
+            panel.columns().keys(),
+            vec![
+                "service",
+                "operation",
+                "duration",
+                "status",
+                "events",
+                "time"
+            ]
+        );
 
-    #[tokio::test]
-    async fn test_chart_updates_on_time_range() {
-        // TODO: Test time range changes update charts
-        todo!("Implement test_chart_updates_on_time_range")
+        let keys = vec![
+            "time".to_string(),
+            "attr:node.id".to_string(),
+            "span_kind".to_string(),
+        ];
+        panel.set_columns(harness.cx(), &keys);
+        assert_eq!(panel.columns().keys(), keys);
+        assert_eq!(panel.columns().attribute_keys(), vec!["node.id"]);
     }
 
-    #[tokio::test]
-    async fn test_golden_signals_calculation() {
-        // TODO: Test golden signals computed correctly
-        todo!("Implement test_golden_signals_calculation")
+    #[test]
+    fn test_live_tail_prepends_new_spans() {
+        let mut harness = harness(&MockDoraClient::new());
+        let panel = harness.traces_panel();
+        assert!(!panel.is_live());
+        panel.set_live(harness.cx(), true);
+        assert!(panel.is_live());
+
+        panel.set_spans(harness.cx(), vec![span("root", None, 1_000)]);
+        assert_eq!(panel.newest_start_ms(), Some(1_000));
+
+        // A repeated span is not shown twice; new ones go on top, newest first
+        panel.prepend_spans(
+            harness.cx(),
+            vec![
+                span("root", None, 1_000),
+                span("a", Some("root"), 1_500),
+                span("b", Some("root"), 2_000),
+            ],
+        );
+        let span_ids: Vec<String> = panel.spans().into_iter().map(|s| s.span_id).collect();
+        assert_eq!(span_ids, vec!["b", "a", "root"]);
+        assert_eq!(panel.newest_start_ms(), Some(2_000));
+        assert_eq!(panel.loading_state().0, TracesLoadingState::Idle);
+    }
+
+    #[test]
+    fn test_trace_filter_recalled() {
+        let mut harness = harness(&MockDoraClient::new());
+        let panel = harness.traces_panel();
+        assert!(panel.filter().is_empty());
+
+        let filter = TraceFilter::parse("camera", "capture", "1h", "node.id=cam0").unwrap();
+        panel.set_saved_queries(
+            harness.cx(),
+            &[SavedTraceQuery {
+                name: "camera".to_string(),
+                filter: filter.clone(),
+            }],
+        );
+        panel.set_filter(harness.cx(), filter.clone());
+        assert_eq!(panel.filter(), filter);
+        assert_eq!(
+            panel.filter().to_query(3_600_000).service_name.as_deref(),
+            Some("camera")
+        );
     }
 
-    #[tokio::test]
-    async fn test_trace_timeline_rendering() {
-        // TODO: Test trace waterfall view
-        todo!("Implement test_trace_timeline_rendering")
+    #[test]
+    fn test_inspected_attribute_filters_traces() {
+        let mut harness = harness(&MockDoraClient::new());
+        let panel = harness.traces_panel();
+        let mut root = span("root", None, 1_000);
+        root.attributes
+            .insert("dataflow_id".to_string(), "df-1".to_string());
+        root.attributes
+            .insert("node.id".to_string(), "cam0".to_string());
+        panel.set_spans(harness.cx(), vec![root]);
+        assert!(panel.inspected_attributes().is_empty());
+
+        panel.inspect(harness.cx(), Some(0));
+        assert_eq!(
+            panel.inspected_attributes(),
+            vec![
+                ("dataflow_id".to_string(), "df-1".to_string()),
+                ("node.id".to_string(), "cam0".to_string()),
+            ]
+        );
+
+        let filter = panel.filter_by_attribute(harness.cx(), "dataflow_id", "df-1");
+        assert_eq!(
+            filter
+                .to_query(0)
+                .tags
+                .get("dataflow_id")
+                .map(String::as_str),
+            Some("df-1")
+        );
+        assert_eq!(panel.filter(), filter);
+
+        panel.inspect(harness.cx(), None);
+        assert!(panel.inspected_attributes().is_empty());
     }
 }
 
@@ -126,28 +598,94 @@ mod telemetry_dashboard {
 
 mod ai_agent {
     use super::*;
+    use dora_studio::api::MessageRole;
 
-    #[tokio::test]
-    async fn test_agent_executes_tool_call() {
-        // TODO: Test tool call execution flow
+    #[test]
+    #[ignore] // Tool calls go straight to the Claude API; no client seam to mock yet
+    fn test_agent_executes_tool_call() {
         todo!("Implement test_agent_executes_tool_call")
     }
 
-    #[tokio::test]
-    async fn test_agent_handles_multiple_tools() {
-        // TODO: Test chained tool calls
+    #[test]
+    #[ignore] // Tool calls go straight to the Claude API; no client seam to mock yet
+    fn test_agent_handles_multiple_tools() {
         todo!("Implement test_agent_handles_multiple_tools")
     }
 
-    #[tokio::test]
-    async fn test_chatbar_sends_to_agent() {
-        // TODO: Test ChatBar -> AgentCoordinator flow
-        todo!("Implement test_chatbar_sends_to_agent")
+    #[test]
+    fn test_chatbar_sends_to_agent() {
+        let mut harness = harness(&MockDoraClient::new());
+        let chat = harness.chat_screen();
+
+        // Blank input is not sent
+        harness.set_text(ids!(chat_screen.message_input), "   ");
+        chat.send(harness.cx());
+        assert!(!chat.is_loading());
+
+        harness.set_text(ids!(chat_screen.message_input), "list my dataflows");
+        chat.send(harness.cx());
+        assert!(chat.is_loading());
+        let last = chat.messages().pop().expect("message was sent");
+        assert_eq!(last.role, MessageRole::User);
+        assert_eq!(last.content, "list my dataflows");
+        assert!(harness
+            .ui()
+            .text_input(ids!(chat_screen.message_input))
+            .text()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_stop_and_regenerate_reply() {
+        let mut harness = harness(&MockDoraClient::new());
+        let chat = harness.chat_screen();
+
+        harness.set_text(ids!(chat_screen.message_input), "list my dataflows");
+        chat.send(harness.cx());
+        assert!(chat.is_loading());
+        chat.stop(harness.cx());
+        assert!(!chat.is_loading());
+        let sent = chat.messages().len();
+
+        // The stopped prompt is sent again without repeating it
+        chat.regenerate(harness.cx());
+        assert!(chat.is_loading());
+        assert_eq!(chat.messages().len(), sent);
+        assert_eq!(chat.messages().last().unwrap().role, MessageRole::User);
+        chat.stop(harness.cx());
+    }
+
+    #[test]
+    fn test_chat_starts_with_default_prompt_template() {
+        let harness = harness(&MockDoraClient::new());
+        assert_eq!(
+            harness.chat_screen().prompt_template().as_deref(),
+            Some("Dora Studio Assistant")
+        );
+    }
+
+    #[test]
+    fn test_dataflow_preview_from_chat() {
+        let mut harness = harness(&MockDoraClient::new());
+        let chat = harness.chat_screen();
+        assert_eq!(chat.pending_dataflow(), None);
+
+        let yaml = "nodes:\n  - id: camera";
+        chat.show_dataflow_preview(
+            harness.cx(),
+            "/tmp/df.yml",
+            yaml,
+            "+ nodes:\n+   - id: camera",
+        );
+        assert_eq!(
+            chat.pending_dataflow(),
+            Some(("/tmp/df.yml".to_string(), yaml.to_string()))
+        );
     }
 
-    #[tokio::test]
-    async fn test_context_includes_app_state() {
-        // TODO: Test context manager includes current app state
+    #[test]
+    #[ignore] // Context is built from a live Claude request; no client seam to mock yet
+    fn test_context_includes_app_state() {
         todo!("Implement test_context_includes_app_state")
     }
 }
@@ -1,8 +1,29 @@
 //! Integration tests for DoraClient
 //!
 //! Tests client interaction with mock coordinator.
+//!
+//! These drive [`MockCoordinator`] through [`CoordinatorConnection`], a raw
+//! framed connection, so the protocol and fault injection are exercised
+//! independently of any particular client implementation. The app itself
+//! reaches the coordinator through the `dora` CLI.
 
 use super::*;
+use dora_studio::dataflow::DoraClient;
+use serde_json::json;
+use std::time::Duration;
+
+/// Fail instead of hanging if the mock stops answering.
+async fn within<T>(future: impl std::future::Future<Output = T>) -> T {
+    tokio::time::timeout(Duration::from_secs(5), future)
+        .await
+        .expect("mock coordinator timed out")
+}
+
+async fn connect(mock: &MockCoordinator) -> CoordinatorConnection {
+    within(CoordinatorConnection::connect(&mock.addr()))
+        .await
+        .expect("connect to mock coordinator")
+}
 
 // ============================================================================
 // Connection Tests
@@ -10,29 +31,44 @@ use super::*;
 
 #[tokio::test]
 async fn test_client_with_mock_coordinator() {
-    // TODO: Implement when MockCoordinator is ready
-    // let mock = MockCoordinator::start().await;
-    // let client = DoraClient::connect(&mock.addr()).await.unwrap();
-    // assert!(client.is_connected());
-    // mock.shutdown().await;
-    todo!("Implement test_client_with_mock_coordinator")
+    let mock = MockCoordinator::start().await;
+    let mut client = connect(&mock).await;
+
+    let reply = within(client.request(json!("List"))).await.unwrap();
+    assert_eq!(reply, json!({ "DataflowList": [] }));
+    assert_eq!(mock.requests(), vec![json!("List")]);
+
+    mock.shutdown().await;
+    assert!(within(client.request(json!("List"))).await.is_err());
 }
 
 #[tokio::test]
 async fn test_client_handles_malformed_response() {
-    // TODO: Test error handling for invalid JSON responses
-    // let mock = MockCoordinator::start().await;
-    // mock.set_response(b"invalid json{{{");
-    // let mut client = DoraClient::connect(&mock.addr()).await.unwrap();
-    // let result = client.list_dataflows().await;
-    // assert!(result.is_err());
-    todo!("Implement test_client_handles_malformed_response")
+    let mock = MockCoordinator::start().await;
+    mock.set_response(b"invalid json{{{");
+    let mut client = connect(&mock).await;
+
+    let err = within(client.request(json!("List"))).await.unwrap_err();
+    assert!(err.contains("malformed reply"), "{}", err);
+
+    // Only the scripted request is affected
+    let reply = within(client.request(json!("List"))).await.unwrap();
+    assert!(reply.get("DataflowList").is_some());
 }
 
 #[tokio::test]
 async fn test_client_reconnects_on_disconnect() {
-    // TODO: Test automatic reconnection
-    todo!("Implement test_client_reconnects_on_disconnect")
+    let mock = MockCoordinator::start().await;
+    mock.set_dataflows(vec![mock_dataflow("camera")]);
+    mock.disconnect_next();
+
+    let mut client = connect(&mock).await;
+    let err = within(client.request(json!("List"))).await.unwrap_err();
+    assert!(err.contains("connection closed"), "{}", err);
+
+    let mut client = connect(&mock).await;
+    let reply = within(client.request(json!("List"))).await.unwrap();
+    assert_eq!(reply["DataflowList"][0]["name"], "camera");
 }
 
 // ============================================================================
@@ -41,63 +77,218 @@ async fn test_client_reconnects_on_disconnect() {
 
 #[tokio::test]
 async fn test_list_dataflows_empty() {
-    // TODO: Test listing when no dataflows exist
-    // let mock = MockCoordinator::start().await;
-    // mock.set_dataflows(vec![]);
-    // let mut client = DoraClient::connect(&mock.addr()).await.unwrap();
-    // let flows = client.list_dataflows().await.unwrap();
-    // assert!(flows.is_empty());
-    todo!("Implement test_list_dataflows_empty")
+    let mock = MockCoordinator::start().await;
+    mock.set_dataflows(vec![]);
+    let mut client = connect(&mock).await;
+
+    let reply = within(client.request(json!("List"))).await.unwrap();
+    assert_eq!(reply["DataflowList"].as_array().unwrap().len(), 0);
 }
 
 #[tokio::test]
 async fn test_list_dataflows_multiple() {
-    // TODO: Test listing multiple dataflows
-    todo!("Implement test_list_dataflows_multiple")
+    let mock = MockCoordinator::start().await;
+    let camera = mock_dataflow("camera");
+    mock.set_dataflows(vec![
+        camera.clone(),
+        mock_dataflow_with_status("detector", DataflowStatus::Finished),
+        mock_dataflow_with_status("planner", DataflowStatus::Failed("exit 1".to_string())),
+    ]);
+    let mut client = connect(&mock).await;
+
+    let reply = within(client.request(json!("List"))).await.unwrap();
+    let flows = reply["DataflowList"].as_array().unwrap();
+    assert_eq!(flows.len(), 3);
+    assert_eq!(flows[0]["uuid"], camera.uuid.to_string());
+    assert_eq!(flows[0]["status"], "Running");
+    assert_eq!(flows[1]["status"], "Finished");
+    assert_eq!(flows[2]["status"], json!({ "Failed": "exit 1" }));
 }
 
 #[tokio::test]
 async fn test_start_dataflow_success() {
-    // TODO: Test successful dataflow start
-    todo!("Implement test_start_dataflow_success")
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("camera.yml");
+    std::fs::write(&path, simple_dataflow_yaml()).unwrap();
+
+    let mock = MockCoordinator::start().await;
+    let mut client = connect(&mock).await;
+    let reply = within(client.request(json!({
+        "Start": { "dataflow_path": path.to_string_lossy(), "name": null }
+    })))
+    .await
+    .unwrap();
+
+    let uuid = reply["DataflowStarted"]["uuid"].as_str().unwrap();
+    let flows = mock.dataflows();
+    assert_eq!(flows.len(), 1);
+    assert_eq!(flows[0].uuid.to_string(), uuid);
+    assert_eq!(flows[0].name.as_deref(), Some("camera"));
+
+    // Scripted results take precedence over the file check
+    let scripted = Uuid::new_v4();
+    mock.expect_start_returns(scripted);
+    let reply = within(client.request(json!({
+        "Start": { "dataflow_path": "elsewhere.yml", "name": "demo" }
+    })))
+    .await
+    .unwrap();
+    assert_eq!(reply["DataflowStarted"]["uuid"], scripted.to_string());
 }
 
 #[tokio::test]
 async fn test_start_dataflow_file_not_found() {
-    // TODO: Test error when YAML file doesn't exist
-    todo!("Implement test_start_dataflow_file_not_found")
+    let mock = MockCoordinator::start().await;
+    let mut client = connect(&mock).await;
+
+    let reply = within(client.request(json!({
+        "Start": { "dataflow_path": "/nonexistent/dataflow.yml", "name": null }
+    })))
+    .await
+    .unwrap();
+    let err = reply["Error"].as_str().unwrap();
+    assert!(err.contains("file not found"), "{}", err);
+    assert!(mock.dataflows().is_empty());
+
+    mock.expect_start_fails("dataflow already running");
+    let reply = within(client.request(json!({
+        "Start": { "dataflow_path": "a.yml", "name": null }
+    })))
+    .await
+    .unwrap();
+    assert_eq!(reply["Error"], "dataflow already running");
 }
 
 #[tokio::test]
 async fn test_stop_dataflow_success() {
-    // TODO: Test successful dataflow stop
-    todo!("Implement test_stop_dataflow_success")
+    let mock = MockCoordinator::start().await;
+    let camera = mock_dataflow("camera");
+    mock.set_dataflows(vec![camera.clone()]);
+    let mut client = connect(&mock).await;
+
+    let uuid = camera.uuid.to_string();
+    let reply = within(client.request(json!({ "Stop": { "dataflow_uuid": uuid } })))
+        .await
+        .unwrap();
+    assert_eq!(reply["DataflowStopped"]["uuid"], uuid);
+    assert!(matches!(
+        mock.dataflows()[0].status,
+        DataflowStatus::Finished
+    ));
 }
 
 #[tokio::test]
 async fn test_stop_dataflow_not_running() {
-    // TODO: Test error when stopping non-running dataflow
-    todo!("Implement test_stop_dataflow_not_running")
+    let mock = MockCoordinator::start().await;
+    let finished = mock_dataflow_with_status("camera", DataflowStatus::Finished);
+    mock.set_dataflows(vec![finished.clone()]);
+    let mut client = connect(&mock).await;
+
+    for uuid in [finished.uuid, Uuid::new_v4()] {
+        let reply = within(client.request(json!({
+            "Stop": { "dataflow_uuid": uuid.to_string() }
+        })))
+        .await
+        .unwrap();
+        let err = reply["Error"].as_str().unwrap();
+        assert!(err.contains("no running dataflow"), "{}", err);
+    }
+}
+
+#[test]
+fn test_mock_dora_client_implements_trait() {
+    let mock = MockDoraClient::new();
+    let client: &dyn DoraClient = &mock;
+    mock.set_dataflows(vec![mock_dataflow("camera")]);
+
+    let uuid = client.start("detector.yml").unwrap().unwrap();
+    let flows = client.list_dataflows().unwrap();
+    assert_eq!(flows.len(), 2);
+    assert_eq!(flows[1].name, "detector");
+    client.stop(&uuid).unwrap();
+    assert!(client.stop(&uuid).is_err());
+    assert_eq!(mock.call_count("stop"), 2);
+    assert!(mock.was_called("list_dataflows"));
 }
 
 // ============================================================================
 // Log Subscription Tests
 // ============================================================================
 
+async fn subscribe(mock: &MockCoordinator) -> CoordinatorConnection {
+    let mut client = connect(mock).await;
+    let reply = within(client.request(json!({
+        "LogSubscribe": { "dataflow_uuid": Uuid::new_v4().to_string() }
+    })))
+    .await
+    .unwrap();
+    assert_eq!(reply, json!("LogSubscribed"));
+    client
+}
+
 #[tokio::test]
 async fn test_log_subscription_receives_logs() {
-    // TODO: Test real-time log streaming
-    todo!("Implement test_log_subscription_receives_logs")
+    let mock = MockCoordinator::start().await;
+    let mut client = subscribe(&mock).await;
+
+    mock.emit_log(log_entry_node("camera", "frame captured"));
+    mock.emit_log(log_entry(LogLevel::Warn, "slow frame"));
+
+    let first = within(client.receive()).await.unwrap();
+    assert_eq!(first["Log"]["node_id"], "camera");
+    assert_eq!(first["Log"]["message"], "frame captured");
+    let second = within(client.receive()).await.unwrap();
+    assert_eq!(second["Log"]["level"], "Warn");
+
+    // Emitted logs are also served by one-shot `Logs` requests
+    let mut other = connect(&mock).await;
+    let reply = within(other.request(json!({
+        "Logs": { "dataflow_uuid": "", "node": "camera" }
+    })))
+    .await
+    .unwrap();
+    assert_eq!(reply["Logs"], "frame captured");
 }
 
 #[tokio::test]
 async fn test_log_subscription_handles_disconnect() {
-    // TODO: Test graceful handling of disconnect during streaming
-    todo!("Implement test_log_subscription_handles_disconnect")
+    let mock = MockCoordinator::start().await;
+    let mut client = subscribe(&mock).await;
+
+    mock.emit_log(log_entry_node("camera", "before shutdown"));
+    let log = within(client.receive()).await.unwrap();
+    assert_eq!(log["Log"]["message"], "before shutdown");
+
+    mock.shutdown().await;
+    let err = within(client.receive()).await.unwrap_err();
+    assert!(err.contains("connection closed"), "{}", err);
 }
 
 #[tokio::test]
 async fn test_log_subscription_backpressure() {
-    // TODO: Test handling of high-volume log streams
-    todo!("Implement test_log_subscription_backpressure")
+    const EMITTED: u64 = 2_000;
+
+    let mock = MockCoordinator::start().await;
+    let mut client = subscribe(&mock).await;
+
+    // Emit far more than the subscriber buffer before reading anything
+    for log in generate_sample_logs(EMITTED as usize) {
+        mock.emit_log(log);
+    }
+
+    let (mut received, mut dropped) = (0u64, 0u64);
+    while received + dropped < EMITTED {
+        let message = within(client.receive()).await.unwrap();
+        if message.get("Log").is_some() {
+            received += 1;
+        } else {
+            dropped += message["LogsDropped"].as_u64().unwrap();
+        }
+    }
+    assert_eq!(received + dropped, EMITTED);
+    assert!(
+        dropped > 0,
+        "a lagging subscriber should be told logs were dropped"
+    );
+    assert!(received > 0);
 }
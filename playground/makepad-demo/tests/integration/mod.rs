@@ -5,6 +5,7 @@
 mod app_tests;
 mod client_tests;
 mod storage_tests;
+mod telemetry_tests;
 
 // Re-export test utilities
 pub use super::fixtures::*;
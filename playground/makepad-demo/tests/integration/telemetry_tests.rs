@@ -0,0 +1,297 @@
+//! Integration tests for telemetry backends
+//!
+//! `SigNozBackend` is exercised against a wiremock HTTP server, so auth
+//! handling, pagination and response parsing are covered without a live
+//! SigNoz. `MockTelemetryBackend` is checked to follow its script.
+
+use super::*;
+use dora_studio::otlp::backend::TelemetryBackend;
+use dora_studio::otlp::{AuthMethod, OtlpError, SigNozBackend, SigNozConfig, TraceQuery};
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn backend(server: &MockServer, auth: AuthMethod) -> SigNozBackend {
+    SigNozBackend::new(SigNozConfig {
+        base_url: server.uri(),
+        auth,
+        timeout_secs: 5,
+    })
+    .unwrap()
+}
+
+/// A `query_range` list response with one row per span ID.
+fn trace_page(span_ids: &[&str]) -> serde_json::Value {
+    let rows: Vec<serde_json::Value> = span_ids
+        .iter()
+        .map(|id| {
+            json!({
+                "timestamp": "2026-02-02T19:40:37.126981Z",
+                "data": {
+                    "traceID": "trace-1",
+                    "spanID": id,
+                    "serviceName": "camera",
+                    "name": "capture",
+                    "durationNano": 12_000_000.0,
+                    "hasError": false
+                }
+            })
+        })
+        .collect();
+    json!({
+        "status": "success",
+        "data": { "result": [{ "queryName": "A", "list": rows }] }
+    })
+}
+
+fn page_query(limit: u32, offset: u32) -> TraceQuery {
+    TraceQuery {
+        limit: Some(limit),
+        offset: Some(offset),
+        ..Default::default()
+    }
+}
+
+// ============================================================================
+// Authentication
+// ============================================================================
+
+#[tokio::test]
+async fn test_signoz_sends_api_key() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/health"))
+        .and(header("SIGNOZ-API-KEY", "secret"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let auth = AuthMethod::ApiKey {
+        header_name: "SIGNOZ-API-KEY".to_string(),
+        key: "secret".to_string(),
+    };
+    backend(&server, auth).health_check().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_signoz_sends_bearer_token() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/services"))
+        .and(header("Authorization", "Bearer token-1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "status": "success",
+            "data": [{ "serviceName": "camera", "numOperations": 3 }]
+        })))
+        .mount(&server)
+        .await;
+
+    let auth = AuthMethod::BearerToken {
+        token: "token-1".to_string(),
+    };
+    let services = backend(&server, auth).list_services().await.unwrap();
+    assert_eq!(services.len(), 1);
+    assert_eq!(services[0].name, "camera");
+    assert_eq!(services[0].num_operations, 3);
+}
+
+#[tokio::test]
+async fn test_signoz_auth_failures() {
+    let server = MockServer::start().await;
+    Mock::given(path("/api/v1/health"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+    Mock::given(path("/api/v1/services"))
+        .respond_with(ResponseTemplate::new(403))
+        .mount(&server)
+        .await;
+    Mock::given(path("/api/v3/query_range"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+
+    let signoz = backend(&server, AuthMethod::None);
+    let err = signoz.health_check().await.unwrap_err();
+    assert!(
+        matches!(&err, OtlpError::AuthenticationFailed(m) if m == "HTTP 401"),
+        "{}",
+        err
+    );
+    let err = signoz.list_services().await.unwrap_err();
+    assert!(matches!(&err, OtlpError::AuthenticationFailed(m) if m == "HTTP 403"));
+    let err = signoz
+        .query_traces(&TraceQuery::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OtlpError::AuthenticationFailed(_)));
+}
+
+// ============================================================================
+// Pagination
+// ============================================================================
+
+#[tokio::test]
+async fn test_signoz_trace_pagination() {
+    let server = MockServer::start().await;
+    let with_offset = |offset: u32| {
+        body_partial_json(json!({
+            "compositeQuery": { "builderQueries": { "A": { "limit": 2, "offset": offset } } }
+        }))
+    };
+    Mock::given(method("POST"))
+        .and(path("/api/v3/query_range"))
+        .and(with_offset(0))
+        .respond_with(ResponseTemplate::new(200).set_body_json(trace_page(&["s1", "s2"])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/query_range"))
+        .and(with_offset(2))
+        .respond_with(ResponseTemplate::new(200).set_body_json(trace_page(&["s3"])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let signoz = backend(&server, AuthMethod::None);
+    let mut span_ids = Vec::new();
+    let mut offset = 0;
+    loop {
+        let page = signoz.query_traces(&page_query(2, offset)).await.unwrap();
+        let count = page.items.len() as u32;
+        assert_eq!(page.total, Some(count as u64));
+        span_ids.extend(page.items.into_iter().map(|s| s.span_id));
+        if count < 2 {
+            break;
+        }
+        offset += count;
+    }
+    assert_eq!(span_ids, vec!["s1", "s2", "s3"]);
+}
+
+// ============================================================================
+// Malformed and failed responses
+// ============================================================================
+
+#[tokio::test]
+async fn test_signoz_malformed_payloads() {
+    let server = MockServer::start().await;
+    Mock::given(path("/api/v3/query_range"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{\"status\": \"success\", "))
+        .mount(&server)
+        .await;
+    Mock::given(path("/api/v1/services"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": "not a list" })))
+        .mount(&server)
+        .await;
+
+    let signoz = backend(&server, AuthMethod::None);
+    let err = signoz
+        .query_traces(&TraceQuery::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, OtlpError::Deserialization(_)), "{}", err);
+    let err = signoz.list_services().await.unwrap_err();
+    assert!(matches!(err, OtlpError::Deserialization(_)), "{}", err);
+}
+
+#[tokio::test]
+async fn test_signoz_rows_missing_fields_use_defaults() {
+    let server = MockServer::start().await;
+    Mock::given(path("/api/v3/query_range"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "status": "success",
+            "data": { "newResult": { "data": { "result": [
+                { "list": [{ "data": { "spanID": "s1", "durationNano": "slow" } }] }
+            ] } } }
+        })))
+        .mount(&server)
+        .await;
+
+    let page = backend(&server, AuthMethod::None)
+        .query_traces(&TraceQuery::default())
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 1);
+    let span = &page.items[0];
+    assert_eq!(span.span_id, "s1");
+    assert_eq!((span.duration_ms, span.start_time_ms), (0, 0));
+    assert!(span.service_name.is_empty());
+}
+
+#[tokio::test]
+async fn test_signoz_error_responses() {
+    let server = MockServer::start().await;
+    Mock::given(path("/api/v3/query_range"))
+        .and(body_partial_json(
+            json!({ "compositeQuery": { "panelType": "list" } }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "status": "error",
+            "error": "query timed out"
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(path("/api/v1/health"))
+        .respond_with(ResponseTemplate::new(503).set_body_string("starting up"))
+        .mount(&server)
+        .await;
+
+    let signoz = backend(&server, AuthMethod::None);
+    let err = signoz
+        .query_traces(&TraceQuery::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(&err, OtlpError::Backend(m) if m == "query timed out"));
+    let err = signoz.health_check().await.unwrap_err();
+    assert!(matches!(
+        &err,
+        OtlpError::ApiError { status: 503, message } if message == "starting up"
+    ));
+}
+
+// ============================================================================
+// MockTelemetryBackend
+// ============================================================================
+
+#[tokio::test]
+async fn test_mock_backend_follows_script() {
+    let mock = MockTelemetryBackend::new();
+    mock.push_health(Err(OtlpError::AuthenticationFailed("HTTP 401".to_string())));
+    mock.push_traces(Err(OtlpError::Backend("overloaded".to_string())));
+
+    assert!(mock.health_check().await.is_err());
+    // Out of script: empty success
+    assert!(mock.health_check().await.is_ok());
+    assert!(mock.query_traces(&page_query(2, 0)).await.is_err());
+    let page = mock.query_traces(&page_query(2, 2)).await.unwrap();
+    assert_eq!((page.items.len(), page.total), (0, Some(0)));
+
+    let offsets: Vec<Option<u32>> = mock.trace_queries().iter().map(|q| q.offset).collect();
+    assert_eq!(offsets, vec![Some(0), Some(2)]);
+    assert_eq!(
+        mock.calls(),
+        vec![
+            "health_check",
+            "health_check",
+            "query_traces",
+            "query_traces"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_mock_backend_latency() {
+    let mock = MockTelemetryBackend::new();
+    mock.set_latency(Duration::from_millis(200));
+
+    let slow = tokio::time::timeout(Duration::from_millis(20), mock.list_services()).await;
+    assert!(slow.is_err(), "latency should delay the reply");
+
+    mock.set_latency(Duration::ZERO);
+    let fast = tokio::time::timeout(Duration::from_millis(200), mock.list_services()).await;
+    assert!(fast.unwrap().unwrap().is_empty());
+}
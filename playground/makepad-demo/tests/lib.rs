@@ -5,14 +5,15 @@
 //! ## Running Tests
 //!
 //! ```bash
-//! # Run all unit and integration tests
-//! cargo test --workspace
+//! # Run all unit and integration tests; this suite needs the headless
+//! # harness, behind the `test-harness` feature
+//! cargo test --workspace --features test-harness
 //!
 //! # Run integration tests only
-//! cargo test --test integration
+//! cargo test --features test-harness --test lib integration
 //!
-//! # Run E2E tests (requires running Dora)
-//! cargo test --test e2e -- --ignored
+//! # Run E2E tests (requires `dora` on PATH, or DORA_BIN)
+//! cargo test --features test-harness --test lib e2e::dataflow_lifecycle -- --ignored --test-threads 1
 //!
 //! # Run with coverage
 //! cargo tarpaulin --out Html
@@ -23,7 +24,7 @@
 //! - `mocks/` - Mock implementations of external dependencies
 //! - `fixtures/` - Test data factories and helpers
 //! - `integration/` - Cross-component integration tests
-//! - `e2e/` - Full system end-to-end tests
+//! - `e2e/` - Full system end-to-end tests against a launched dora
 //!
 //! ## See Also
 //!
@@ -2,6 +2,13 @@
 //!
 //! Provides mock versions of external dependencies for isolated testing.
 
+use dora_studio::dataflow::{DataflowInfo, DoraClient};
+use dora_studio::otlp::backend::TelemetryBackend;
+use dora_studio::otlp::{
+    LogEntry, LogQuery, MetricQuery, MetricSeries, OtlpError, QueryResult, ServiceInfo,
+    Span as OtlpSpan, TraceQuery,
+};
+use dora_studio::tools::payload::CheckDiagnostic;
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
 
@@ -10,10 +17,16 @@ use std::sync::{Arc, Mutex};
 // ============================================================================
 
 /// Mock implementation of DoraClient for testing
+///
+/// Clones share state, so a test can keep a handle on a client it has
+/// handed to the app.
+#[derive(Clone)]
 pub struct MockDoraClient {
     dataflows: Arc<Mutex<Vec<DataflowEntry>>>,
     call_log: Arc<Mutex<Vec<String>>>,
     start_result: Arc<Mutex<Option<Result<Uuid, String>>>>,
+    check_problems: Arc<Mutex<Vec<CheckDiagnostic>>>,
+    logs: Arc<Mutex<String>>,
 }
 
 impl MockDoraClient {
@@ -22,6 +35,8 @@ impl MockDoraClient {
             dataflows: Arc::new(Mutex::new(Vec::new())),
             call_log: Arc::new(Mutex::new(Vec::new())),
             start_result: Arc::new(Mutex::new(None)),
+            check_problems: Arc::new(Mutex::new(Vec::new())),
+            logs: Arc::new(Mutex::new(String::new())),
         }
     }
 
@@ -37,6 +52,16 @@ impl MockDoraClient {
         *self.start_result.lock().unwrap() = Some(Err(error.to_string()));
     }
 
+    /// Make `dora check` report `problems` for every dataflow.
+    pub fn expect_check_problems(&self, problems: Vec<CheckDiagnostic>) {
+        *self.check_problems.lock().unwrap() = problems;
+    }
+
+    /// Make `dora logs` return `logs` for every dataflow.
+    pub fn set_logs(&self, logs: &str) {
+        *self.logs.lock().unwrap() = logs.to_string();
+    }
+
     pub fn was_called(&self, method: &str) -> bool {
         self.call_log.lock().unwrap().iter().any(|m| m == method)
     }
@@ -55,8 +80,107 @@ impl MockDoraClient {
     }
 }
 
-// TODO: Implement DoraClient trait for MockDoraClient
-// impl DoraClient for MockDoraClient { ... }
+impl DoraClient for MockDoraClient {
+    fn list_dataflows(&self) -> Result<Vec<DataflowInfo>, String> {
+        self.log_call("list_dataflows");
+        Ok(self
+            .dataflows
+            .lock()
+            .unwrap()
+            .iter()
+            .map(dataflow_info)
+            .collect())
+    }
+
+    fn check(&self, _dataflow_path: &str) -> Result<Vec<CheckDiagnostic>, String> {
+        self.log_call("check");
+        Ok(self.check_problems.lock().unwrap().clone())
+    }
+
+    fn start(&self, dataflow_path: &str) -> Result<Option<String>, String> {
+        self.log_call("start");
+        let uuid = self
+            .start_result
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap_or_else(|| Ok(Uuid::new_v4()))?;
+        self.dataflows.lock().unwrap().push(DataflowEntry {
+            uuid,
+            name: std::path::Path::new(dataflow_path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned()),
+            status: DataflowStatus::Running,
+            node_count: 0,
+        });
+        Ok(Some(uuid.to_string()))
+    }
+
+    fn stop(&self, uuid: &str) -> Result<(), String> {
+        self.log_call("stop");
+        self.finish(uuid)
+    }
+
+    fn destroy(&self, uuid: &str) -> Result<(), String> {
+        self.log_call("destroy");
+        self.finish(uuid)
+    }
+
+    fn logs(&self, _uuid: &str, _node: Option<&str>) -> Result<String, String> {
+        self.log_call("logs");
+        Ok(self.logs.lock().unwrap().clone())
+    }
+
+    fn list_nodes(&self, uuid: &str) -> Result<Vec<String>, String> {
+        self.log_call("list_nodes");
+        let dataflows = self.dataflows.lock().unwrap();
+        let df = dataflows
+            .iter()
+            .find(|df| df.uuid.to_string() == uuid)
+            .ok_or_else(|| format!("no dataflow with UUID `{}`", uuid))?;
+        Ok(node_ids(df))
+    }
+}
+
+impl MockDoraClient {
+    /// Mark a running dataflow as finished.
+    fn finish(&self, uuid: &str) -> Result<(), String> {
+        let mut dataflows = self.dataflows.lock().unwrap();
+        match dataflows
+            .iter_mut()
+            .find(|df| df.uuid.to_string() == uuid && matches!(df.status, DataflowStatus::Running))
+        {
+            Some(df) => {
+                df.status = DataflowStatus::Finished;
+                Ok(())
+            }
+            None => Err(format!("no running dataflow with UUID `{}`", uuid)),
+        }
+    }
+}
+
+/// Table row the app would show for a mock dataflow.
+pub fn dataflow_info(entry: &DataflowEntry) -> DataflowInfo {
+    let status = match &entry.status {
+        DataflowStatus::Running => "Running",
+        DataflowStatus::Finished => "Finished",
+        DataflowStatus::Failed(_) => "Failed",
+    };
+    DataflowInfo {
+        uuid: entry.uuid.to_string(),
+        name: entry.name.clone().unwrap_or_default(),
+        status: status.to_string(),
+        nodes: entry.node_count as u32,
+        ..Default::default()
+    }
+}
+
+/// Node IDs of a mock dataflow: `node-0`, `node-1`, ...
+pub fn node_ids(entry: &DataflowEntry) -> Vec<String> {
+    (0..entry.node_count)
+        .map(|i| format!("node-{}", i))
+        .collect()
+}
 
 // ============================================================================
 // MockLlmClient
@@ -93,6 +217,132 @@ impl MockLlmClient {
 // #[async_trait]
 // impl LlmClient for MockLlmClient { ... }
 
+// ============================================================================
+// MockTelemetryBackend
+// ============================================================================
+
+/// Scripted stand-in for a telemetry backend such as SigNoz.
+///
+/// Each method answers with the next scripted result for it, or an empty
+/// success once the script runs out. Every call first sleeps for the
+/// configured latency, so timeouts and loading states can be exercised.
+#[derive(Default)]
+pub struct MockTelemetryBackend {
+    latency: Mutex<std::time::Duration>,
+    health: Mutex<VecDeque<Result<(), OtlpError>>>,
+    services: Mutex<VecDeque<Result<Vec<ServiceInfo>, OtlpError>>>,
+    traces: Mutex<VecDeque<Result<QueryResult<OtlpSpan>, OtlpError>>>,
+    metrics: Mutex<VecDeque<Result<QueryResult<MetricSeries>, OtlpError>>>,
+    logs: Mutex<VecDeque<Result<QueryResult<LogEntry>, OtlpError>>>,
+    trace_queries: Mutex<Vec<TraceQuery>>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl MockTelemetryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delay every call by `latency`.
+    pub fn set_latency(&self, latency: std::time::Duration) {
+        *self.latency.lock().unwrap() = latency;
+    }
+
+    pub fn push_health(&self, result: Result<(), OtlpError>) {
+        self.health.lock().unwrap().push_back(result);
+    }
+
+    pub fn push_services(&self, result: Result<Vec<ServiceInfo>, OtlpError>) {
+        self.services.lock().unwrap().push_back(result);
+    }
+
+    /// Script the next trace page; `total` is set to the number of spans.
+    pub fn push_traces(&self, result: Result<Vec<OtlpSpan>, OtlpError>) {
+        self.traces
+            .lock()
+            .unwrap()
+            .push_back(result.map(query_result));
+    }
+
+    pub fn push_metrics(&self, result: Result<Vec<MetricSeries>, OtlpError>) {
+        self.metrics
+            .lock()
+            .unwrap()
+            .push_back(result.map(query_result));
+    }
+
+    pub fn push_logs(&self, result: Result<Vec<LogEntry>, OtlpError>) {
+        self.logs
+            .lock()
+            .unwrap()
+            .push_back(result.map(query_result));
+    }
+
+    /// Trace queries received so far, in order.
+    pub fn trace_queries(&self) -> Vec<TraceQuery> {
+        self.trace_queries.lock().unwrap().clone()
+    }
+
+    /// Names of the methods called so far, in order.
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    async fn begin(&self, method: &str) {
+        self.calls.lock().unwrap().push(method.to_string());
+        let latency = *self.latency.lock().unwrap();
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+    }
+}
+
+fn query_result<T>(items: Vec<T>) -> QueryResult<T> {
+    QueryResult {
+        total: Some(items.len() as u64),
+        items,
+    }
+}
+
+fn next_or<T>(script: &Mutex<VecDeque<Result<T, OtlpError>>>, empty: T) -> Result<T, OtlpError> {
+    script.lock().unwrap().pop_front().unwrap_or(Ok(empty))
+}
+
+impl TelemetryBackend for MockTelemetryBackend {
+    async fn health_check(&self) -> Result<(), OtlpError> {
+        self.begin("health_check").await;
+        next_or(&self.health, ())
+    }
+
+    async fn list_services(&self) -> Result<Vec<ServiceInfo>, OtlpError> {
+        self.begin("list_services").await;
+        next_or(&self.services, Vec::new())
+    }
+
+    async fn query_traces(&self, query: &TraceQuery) -> Result<QueryResult<OtlpSpan>, OtlpError> {
+        self.trace_queries.lock().unwrap().push(query.clone());
+        self.begin("query_traces").await;
+        next_or(&self.traces, query_result(Vec::new()))
+    }
+
+    async fn query_metrics(
+        &self,
+        _query: &MetricQuery,
+    ) -> Result<QueryResult<MetricSeries>, OtlpError> {
+        self.begin("query_metrics").await;
+        next_or(&self.metrics, query_result(Vec::new()))
+    }
+
+    async fn query_logs(&self, _query: &LogQuery) -> Result<QueryResult<LogEntry>, OtlpError> {
+        self.begin("query_logs").await;
+        next_or(&self.logs, query_result(Vec::new()))
+    }
+
+    fn display_name(&self) -> String {
+        "Mock telemetry".to_string()
+    }
+}
+
 // ============================================================================
 // MockStorage
 // ============================================================================
@@ -142,34 +392,448 @@ impl MockStorage {
 // ============================================================================
 
 /// Mock TCP server that simulates Dora Coordinator
+///
+/// Speaks a simplified, JSON-framed stand-in for the coordinator's control
+/// protocol: every message is a little-endian `u64` length followed by that
+/// many bytes of JSON. Requests and replies are externally tagged enums, as serde
+/// serializes them:
+///
+/// | Request                                                   | Reply                                |
+/// |-----------------------------------------------------------|--------------------------------------|
+/// | `"List"`                                                  | `{"DataflowList": [entry, ...]}`     |
+/// | `{"Start": {"dataflow_path": "a.yml", "name": null}}`     | `{"DataflowStarted": {"uuid": ..}}`  |
+/// | `{"Stop": {"dataflow_uuid": ".."}}`                       | `{"DataflowStopped": {"uuid": ..}}`  |
+/// | `{"Destroy": {"dataflow_uuid": ".."}}`                    | `{"DataflowDestroyed": {"uuid": ..}}`|
+/// | `{"Nodes": {"dataflow_uuid": ".."}}`                      | `{"NodeList": ["node-0", ...]}`      |
+/// | `{"Logs": {"dataflow_uuid": "..", "node": null}}`         | `{"Logs": "text"}`                   |
+/// | `{"LogSubscribe": {"dataflow_uuid": ".."}}`               | `"LogSubscribed"`, then `{"Log": ..}`|
+///
+/// Failures are replied as `{"Error": "message"}`. A log subscriber that
+/// falls behind gets `{"LogsDropped": count}` in place of the logs it missed.
+///
+/// Faults are injected per request: [`MockCoordinator::set_response`] sends
+/// raw bytes (e.g. malformed JSON) and [`MockCoordinator::disconnect_next`]
+/// closes the connection instead of replying.
 pub struct MockCoordinator {
     addr: std::net::SocketAddr,
-    // TODO: Add internal state and control methods
+    state: Arc<Mutex<CoordinatorState>>,
+    logs: tokio::sync::broadcast::Sender<LogMessage>,
+    shutdown: tokio::sync::watch::Sender<bool>,
+}
+
+/// Logs buffered per subscriber before it is reported as lagging.
+const LOG_BUFFER: usize = 256;
+
+/// Largest frame accepted, so a corrupt length cannot exhaust memory.
+const MAX_FRAME_LEN: u64 = 16 * 1024 * 1024;
+
+#[derive(Default)]
+struct CoordinatorState {
+    dataflows: Vec<DataflowEntry>,
+    /// Scripted behaviour for the next requests, oldest first.
+    script: VecDeque<Scripted>,
+    start_result: Option<Result<Uuid, String>>,
+    /// Every request received, in order.
+    requests: Vec<serde_json::Value>,
+    /// Logs emitted so far, served by `Logs` requests.
+    history: Vec<LogMessage>,
+}
+
+enum Scripted {
+    Raw(Vec<u8>),
+    Disconnect,
 }
 
 impl MockCoordinator {
     pub async fn start() -> Self {
-        // TODO: Start mock TCP server
-        todo!("Implement MockCoordinator::start")
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind mock coordinator");
+        let addr = listener.local_addr().expect("mock coordinator address");
+        let state = Arc::new(Mutex::new(CoordinatorState::default()));
+        let (logs, _) = tokio::sync::broadcast::channel(LOG_BUFFER);
+        let (shutdown, _) = tokio::sync::watch::channel(false);
+
+        let server = Self {
+            addr,
+            state,
+            logs,
+            shutdown,
+        };
+        let state = server.state.clone();
+        let logs = server.logs.clone();
+        let mut stop = server.shutdown.subscribe();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    biased;
+                    _ = stop.changed() => break,
+                    accepted = listener.accept() => {
+                        let Ok((stream, _)) = accepted else { break };
+                        let connection = Connection {
+                            state: state.clone(),
+                            logs: logs.clone(),
+                            shutdown: stop.clone(),
+                        };
+                        tokio::spawn(connection.serve(stream));
+                    }
+                }
+            }
+        });
+        server
     }
 
     pub fn addr(&self) -> String {
         self.addr.to_string()
     }
 
+    /// Stop accepting connections and close the open ones.
     pub async fn shutdown(&self) {
-        // TODO: Shutdown mock server
-        todo!("Implement MockCoordinator::shutdown")
+        let _ = self.shutdown.send(true);
+        tokio::task::yield_now().await;
+    }
+
+    /// Reply to the next request with these raw bytes instead of JSON.
+    pub fn set_response(&self, response: &[u8]) {
+        self.state()
+            .script
+            .push_back(Scripted::Raw(response.to_vec()));
+    }
+
+    /// Close the connection on the next request instead of replying.
+    pub fn disconnect_next(&self) {
+        self.state().script.push_back(Scripted::Disconnect);
+    }
+
+    pub fn set_dataflows(&self, flows: Vec<DataflowEntry>) {
+        self.state().dataflows = flows;
+    }
+
+    pub fn dataflows(&self) -> Vec<DataflowEntry> {
+        self.state().dataflows.clone()
+    }
+
+    /// Make the next `Start` succeed with `uuid`, whatever the path.
+    pub fn expect_start_returns(&self, uuid: Uuid) {
+        self.state().start_result = Some(Ok(uuid));
+    }
+
+    /// Make the next `Start` fail with `error`.
+    pub fn expect_start_fails(&self, error: &str) {
+        self.state().start_result = Some(Err(error.to_string()));
+    }
+
+    /// Emit a log to subscribers and keep it for `Logs` requests.
+    pub fn emit_log(&self, log: LogMessage) {
+        self.state().history.push(log.clone());
+        let _ = self.logs.send(log);
+    }
+
+    /// Requests received so far, in order.
+    pub fn requests(&self) -> Vec<serde_json::Value> {
+        self.state().requests.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, CoordinatorState> {
+        self.state.lock().unwrap()
+    }
+}
+
+impl Drop for MockCoordinator {
+    fn drop(&mut self) {
+        let _ = self.shutdown.send(true);
+    }
+}
+
+/// One client connection to the mock coordinator.
+struct Connection {
+    state: Arc<Mutex<CoordinatorState>>,
+    logs: tokio::sync::broadcast::Sender<LogMessage>,
+    shutdown: tokio::sync::watch::Receiver<bool>,
+}
+
+impl Connection {
+    async fn serve(mut self, mut stream: tokio::net::TcpStream) {
+        loop {
+            // Biased so nothing is answered once shutdown was requested
+            let frame = tokio::select! {
+                biased;
+                _ = self.shutdown.changed() => return,
+                frame = read_frame(&mut stream) => frame,
+            };
+            let Ok(Some(frame)) = frame else { return };
+            let request: serde_json::Value = match serde_json::from_slice(&frame) {
+                Ok(request) => request,
+                Err(e) => {
+                    let reply = error_reply(format!("invalid request: {}", e));
+                    if write_json(&mut stream, &reply).await.is_err() {
+                        return;
+                    }
+                    continue;
+                }
+            };
+
+            let scripted = {
+                let mut state = self.state.lock().unwrap();
+                state.requests.push(request.clone());
+                state.script.pop_front()
+            };
+            let result = match scripted {
+                Some(Scripted::Disconnect) => return,
+                Some(Scripted::Raw(bytes)) => write_frame(&mut stream, &bytes).await,
+                None if request == serde_json::json!("List") => {
+                    write_json(&mut stream, &self.list()).await
+                }
+                None => match request.get("LogSubscribe") {
+                    Some(_) => return self.stream_logs(stream).await,
+                    None => write_json(&mut stream, &self.handle(&request)).await,
+                },
+            };
+            if result.is_err() {
+                return;
+            }
+        }
+    }
+
+    fn list(&self) -> serde_json::Value {
+        let state = self.state.lock().unwrap();
+        let entries: Vec<serde_json::Value> = state.dataflows.iter().map(entry_json).collect();
+        serde_json::json!({ "DataflowList": entries })
+    }
+
+    fn handle(&self, request: &serde_json::Value) -> serde_json::Value {
+        let mut state = self.state.lock().unwrap();
+        let (kind, args) = match request.as_object().and_then(|o| o.iter().next()) {
+            Some((kind, args)) => (kind.as_str(), args),
+            None => return error_reply(format!("unknown request {}", request)),
+        };
+        let uuid_arg = || {
+            args.get("dataflow_uuid")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+        };
+
+        match kind {
+            "Start" => {
+                let path = args
+                    .get("dataflow_path")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("");
+                let result = state.start_result.take().unwrap_or_else(|| {
+                    if std::path::Path::new(path).is_file() {
+                        Ok(Uuid::new_v4())
+                    } else {
+                        Err(format!(
+                            "failed to read dataflow at `{}`: file not found",
+                            path
+                        ))
+                    }
+                });
+                match result {
+                    Ok(uuid) => {
+                        let name = args
+                            .get("name")
+                            .and_then(|v| v.as_str())
+                            .map(str::to_string)
+                            .or_else(|| {
+                                std::path::Path::new(path)
+                                    .file_stem()
+                                    .map(|s| s.to_string_lossy().into_owned())
+                            });
+                        state.dataflows.push(DataflowEntry {
+                            uuid,
+                            name,
+                            status: DataflowStatus::Running,
+                            node_count: 0,
+                        });
+                        serde_json::json!({ "DataflowStarted": { "uuid": uuid.to_string() } })
+                    }
+                    Err(e) => error_reply(e),
+                }
+            }
+            "Stop" | "Destroy" => {
+                let uuid = uuid_arg();
+                let running = state.dataflows.iter_mut().find(|df| {
+                    df.uuid.to_string() == uuid && matches!(df.status, DataflowStatus::Running)
+                });
+                match running {
+                    Some(df) => {
+                        df.status = DataflowStatus::Finished;
+                        let reply = if kind == "Stop" {
+                            "DataflowStopped"
+                        } else {
+                            "DataflowDestroyed"
+                        };
+                        serde_json::json!({ (reply): { "uuid": uuid } })
+                    }
+                    None => error_reply(format!("no running dataflow with UUID `{}`", uuid)),
+                }
+            }
+            "Nodes" => {
+                let uuid = uuid_arg();
+                match state
+                    .dataflows
+                    .iter()
+                    .find(|df| df.uuid.to_string() == uuid)
+                {
+                    Some(df) => serde_json::json!({ "NodeList": node_ids(df) }),
+                    None => error_reply(format!("no dataflow with UUID `{}`", uuid)),
+                }
+            }
+            "Logs" => {
+                let node = args.get("node").and_then(|v| v.as_str());
+                let text: Vec<String> = state
+                    .history
+                    .iter()
+                    .filter(|log| node.is_none() || log.node_id.as_deref() == node)
+                    .map(|log| log.message.clone())
+                    .collect();
+                serde_json::json!({ "Logs": text.join("\n") })
+            }
+            _ => error_reply(format!("unsupported request `{}`", kind)),
+        }
+    }
+
+    /// Forward emitted logs until the client disconnects or the mock shuts
+    /// down. Subscribing happens before the acknowledgement, so logs emitted
+    /// after the client reads `LogSubscribed` are never missed.
+    async fn stream_logs(mut self, stream: tokio::net::TcpStream) {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let mut logs = self.logs.subscribe();
+        let (mut reader, mut writer) = stream.into_split();
+        if write_json(&mut writer, &serde_json::json!("LogSubscribed"))
+            .await
+            .is_err()
+        {
+            return;
+        }
+        let mut buf = [0u8; 64];
+        loop {
+            let reply = tokio::select! {
+                biased;
+                _ = self.shutdown.changed() => return,
+                log = logs.recv() => match log {
+                    Ok(log) => serde_json::json!({ "Log": log_json(&log) }),
+                    Err(RecvError::Lagged(missed)) => serde_json::json!({ "LogsDropped": missed }),
+                    Err(RecvError::Closed) => return,
+                },
+                // Anything from the client ends the subscription; EOF closes it
+                _ = tokio::io::AsyncReadExt::read(&mut reader, &mut buf) => return,
+            };
+            if write_json(&mut writer, &reply).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn error_reply(message: String) -> serde_json::Value {
+    serde_json::json!({ "Error": message })
+}
+
+fn entry_json(entry: &DataflowEntry) -> serde_json::Value {
+    let status = match &entry.status {
+        DataflowStatus::Running => serde_json::json!("Running"),
+        DataflowStatus::Finished => serde_json::json!("Finished"),
+        DataflowStatus::Failed(e) => serde_json::json!({ "Failed": e }),
+    };
+    serde_json::json!({
+        "uuid": entry.uuid.to_string(),
+        "name": entry.name,
+        "status": status,
+        "node_count": entry.node_count,
+    })
+}
+
+fn log_json(log: &LogMessage) -> serde_json::Value {
+    serde_json::json!({
+        "level": format!("{:?}", log.level),
+        "node_id": log.node_id,
+        "message": log.message,
+    })
+}
+
+/// Read one length-prefixed frame; `None` on a clean EOF.
+pub async fn read_frame<R>(reader: &mut R) -> std::io::Result<Option<Vec<u8>>>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut len = [0u8; 8];
+    match reader.read_exact(&mut len).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let len = u64::from_le_bytes(len);
+    if len > MAX_FRAME_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("frame of {} bytes is too large", len),
+        ));
+    }
+    let mut frame = vec![0u8; len as usize];
+    reader.read_exact(&mut frame).await?;
+    Ok(Some(frame))
+}
+
+/// Write one length-prefixed frame.
+pub async fn write_frame<W>(writer: &mut W, frame: &[u8]) -> std::io::Result<()>
+where
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    use tokio::io::AsyncWriteExt;
+
+    writer
+        .write_all(&(frame.len() as u64).to_le_bytes())
+        .await?;
+    writer.write_all(frame).await?;
+    writer.flush().await
+}
+
+async fn write_json<W>(writer: &mut W, value: &serde_json::Value) -> std::io::Result<()>
+where
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    write_frame(writer, value.to_string().as_bytes()).await
+}
+
+/// Minimal client for exercising [`MockCoordinator`] at the protocol level.
+pub struct CoordinatorConnection {
+    stream: tokio::net::TcpStream,
+}
+
+impl CoordinatorConnection {
+    pub async fn connect(addr: &str) -> std::io::Result<Self> {
+        Ok(Self {
+            stream: tokio::net::TcpStream::connect(addr).await?,
+        })
+    }
+
+    pub async fn send(&mut self, request: &serde_json::Value) -> std::io::Result<()> {
+        write_json(&mut self.stream, request).await
     }
 
-    pub fn set_response(&self, _response: &[u8]) {
-        // TODO: Set response for next request
-        todo!("Implement MockCoordinator::set_response")
+    /// Read the next message; errors if the connection closed or the
+    /// message is not valid JSON.
+    pub async fn receive(&mut self) -> Result<serde_json::Value, String> {
+        let frame = read_frame(&mut self.stream)
+            .await
+            .map_err(|e| format!("read failed: {}", e))?
+            .ok_or("connection closed")?;
+        serde_json::from_slice(&frame).map_err(|e| format!("malformed reply: {}", e))
     }
 
-    pub fn emit_log(&self, _log: LogMessage) {
-        // TODO: Emit log to subscribers
-        todo!("Implement MockCoordinator::emit_log")
+    pub async fn request(
+        &mut self,
+        request: serde_json::Value,
+    ) -> Result<serde_json::Value, String> {
+        self.send(&request)
+            .await
+            .map_err(|e| format!("send failed: {}", e))?;
+        self.receive().await
     }
 }
 